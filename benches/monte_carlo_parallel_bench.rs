@@ -18,14 +18,16 @@ fn candidates(ship: &str, hostile: &str, seed: u64, min_count: usize) -> Vec<Cre
     }
     // Synthetic candidates so we can measure parallel vs sequential even without full officer data
     (0..min_count)
-        .map(|i| CrewCandidate {
-            captain: format!("Captain_{i}"),
-            bridge: vec!["Bridge_A".to_string(), "Bridge_B".to_string()],
-            below_decks: vec![
-                "Below_1".to_string(),
-                "Below_2".to_string(),
-                "Below_3".to_string(),
-            ],
+        .map(|i| {
+            CrewCandidate::new(
+                format!("Captain_{i}"),
+                vec!["Bridge_A".to_string(), "Bridge_B".to_string()],
+                vec![
+                    "Below_1".to_string(),
+                    "Below_2".to_string(),
+                    "Below_3".to_string(),
+                ],
+            )
         })
         .collect()
 }
@@ -5,51 +5,24 @@
 
 use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
 use kobayashi::combat::{
-    simulate_combat, Combatant, CrewConfiguration, SimulationConfig, TraceMode,
+    simulate_combat, Combatant, CrewConfiguration, SimulationConfig,
 };
 
 fn default_attacker() -> Combatant {
-    Combatant {
-        id: "attacker".to_string(),
-        attack: 500.0,
-        mitigation: 0.0,
-        pierce: 200.0,
-        crit_chance: 0.1,
-        crit_multiplier: 1.5,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    }
+    Combatant::builder("attacker")
+        .attack(500.0)
+        .pierce(200.0)
+        .crit_chance(0.1)
+        .crit_multiplier(1.5)
+        .build()
+        .expect("benchmark attacker stats are in range")
 }
 
 fn default_defender() -> Combatant {
-    Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 300.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    }
+    Combatant::builder("defender")
+        .mitigation(300.0)
+        .build()
+        .expect("benchmark defender stats are in range")
 }
 
 fn bench_simulator(c: &mut Criterion) {
@@ -66,11 +39,7 @@ fn bench_simulator(c: &mut Criterion) {
         |b, &rounds| {
             let attacker = default_attacker();
             let defender = default_defender();
-            let config = SimulationConfig {
-                rounds,
-                seed: 7,
-                trace_mode: TraceMode::Off,
-            };
+            let config = SimulationConfig::builder().rounds(rounds).seed(7).build();
             b.iter_batched(
                 || (attacker.clone(), defender.clone()),
                 |(a, d)| black_box(simulate_combat(&a, &d, config, &crew)),
@@ -88,11 +57,7 @@ fn bench_simulator(c: &mut Criterion) {
         |b, &rounds| {
             let attacker = default_attacker();
             let defender = default_defender();
-            let config = SimulationConfig {
-                rounds,
-                seed: 7,
-                trace_mode: TraceMode::Off,
-            };
+            let config = SimulationConfig::builder().rounds(rounds).seed(7).build();
             b.iter_batched(
                 || (attacker.clone(), defender.clone()),
                 |(a, d)| black_box(simulate_combat(&a, &d, config, &crew)),
@@ -110,11 +75,7 @@ fn bench_simulator(c: &mut Criterion) {
         |b, &rounds| {
             let attacker = default_attacker();
             let defender = default_defender();
-            let config = SimulationConfig {
-                rounds,
-                seed: 7,
-                trace_mode: TraceMode::Off,
-            };
+            let config = SimulationConfig::builder().rounds(rounds).seed(7).build();
             b.iter_batched(
                 || (attacker.clone(), defender.clone()),
                 |(a, d)| black_box(simulate_combat(&a, &d, config, &crew)),
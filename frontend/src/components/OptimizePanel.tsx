@@ -206,6 +206,8 @@ export default function OptimizePanel({
           <option value="exhaustive">Exhaustive</option>
           <option value="genetic">Genetic</option>
           <option value="tiered">Tiered (scout → confirm)</option>
+          <option value="two_phase">Two-phase (bridge → below-decks stats)</option>
+          <option value="auto">Auto (exhaustive if small, else genetic)</option>
         </select>
       </label>
 
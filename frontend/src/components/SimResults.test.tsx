@@ -38,6 +38,20 @@ describe('SimResults', () => {
     expect(screen.getByText(/45%/)).toBeTruthy();
   });
 
+  it('labels the optimize phase and counts generations for the GA', () => {
+    render(
+      <SimResults
+        {...baseProps}
+        loadingOptimize={true}
+        optimizeProgress={50}
+        optimizeCrewsDone={20}
+        optimizeTotalCrews={40}
+        optimizePhase="ga_generation"
+      />,
+    );
+    expect(screen.getByText(/Evolving \(GA generations\): 20 \/ 40 generations/)).toBeTruthy();
+  });
+
   it('displays sim result stats', () => {
     const simResult: SimulateStats = {
       win_rate: 0.85,
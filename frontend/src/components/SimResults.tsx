@@ -1,10 +1,17 @@
 import { useState, useEffect } from 'react';
 import type { SimulateStats } from '../lib/api';
-import type { CrewRecommendation } from '../lib/api';
+import type { CrewRecommendation, OptimizePhase } from '../lib/api';
 
 const PER_PAGE_OPTIONS = [50, 100, 200, 500] as const;
 const DEFAULT_PER_PAGE = 50;
 
+const PHASE_LABELS: Record<OptimizePhase, string> = {
+  generating: 'Generating candidates',
+  simulating: 'Simulating',
+  ga_generation: 'Evolving (GA generations)',
+  final_ranking: 'Final ranking',
+};
+
 /** Normalize captain/bridge/below_decks for display: API may return string[]; join with ", ". */
 function formatCrewCell(value: string | string[] | null | undefined): string {
   if (value == null) return '';
@@ -20,6 +27,7 @@ interface SimResultsProps {
   optimizeProgress: number | null;
   optimizeCrewsDone: number | null;
   optimizeTotalCrews: number | null;
+  optimizePhase?: OptimizePhase | null;
 }
 
 export default function SimResults({
@@ -30,6 +38,7 @@ export default function SimResults({
   optimizeProgress,
   optimizeCrewsDone,
   optimizeTotalCrews,
+  optimizePhase,
 }: SimResultsProps) {
   const [selected, setSelected] = useState<Set<number>>(new Set());
   const [page, setPage] = useState(1);
@@ -100,8 +109,11 @@ export default function SimResults({
                 />
               </div>
               <p style={{ margin: '4px 0 0', fontSize: '0.8rem', color: 'var(--text-muted)' }}>
+                {optimizePhase != null && `${PHASE_LABELS[optimizePhase]}: `}
                 {optimizeTotalCrews != null && optimizeCrewsDone != null
-                  ? `${optimizeCrewsDone} / ${optimizeTotalCrews} crews (${optimizeProgress}%)`
+                  ? `${optimizeCrewsDone} / ${optimizeTotalCrews} ${
+                      optimizePhase === 'ga_generation' ? 'generations' : 'crews'
+                    } (${optimizeProgress}%)`
                   : `${optimizeProgress}%`}
               </p>
             </div>
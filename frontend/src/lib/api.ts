@@ -100,6 +100,13 @@ export interface OfficerListItem {
   id: string;
   name: string;
   slot?: string;
+  /** common / uncommon / rare / epic. */
+  rarity?: string;
+  faction?: string;
+  /** Crew group name (e.g. "TNG CREW"). */
+  group?: string;
+  /** Officers with the same synergy group boost each other in game. */
+  synergy_group?: number;
 }
 
 export interface ShipListItem {
@@ -123,6 +130,35 @@ export async function getShipTiersLevels(shipId: string): Promise<ShipTiersLevel
   return res.json();
 }
 
+export interface CrewSlots {
+  /** Bridge seats besides the captain. */
+  bridge: number;
+  below_decks: number;
+}
+
+export interface ShipDetail {
+  id: string;
+  ship_name: string;
+  ship_class: string;
+  tier: number;
+  level: number;
+  /** Seats at `tier`; crews for this ship fill exactly these. */
+  crew_slots: CrewSlots;
+  slots_by_tier: { tier: number; crew_slots: CrewSlots }[];
+}
+
+export async function getShip(shipId: string, tier?: number, level?: number): Promise<ShipDetail> {
+  const params = new URLSearchParams();
+  if (tier != null) params.set('tier', String(tier));
+  if (level != null) params.set('level', String(level));
+  const query = params.toString();
+  const res = await fetch(
+    `${API_BASE}/api/ships/${encodeURIComponent(shipId)}${query ? `?${query}` : ''}`
+  );
+  await checkOk(res);
+  return res.json();
+}
+
 export interface HostileListItem {
   id: string;
   hostile_name: string;
@@ -146,13 +182,22 @@ function compareHostiles(a: HostileListItem, b: HostileListItem): number {
 
 export interface MechanicStatus {
   name: string;
-  status: string;
+  status: 'implemented' | 'partial' | 'planned';
+  keys: string[];
+}
+
+export interface DatasetFiles {
+  content_hash: string;
+  records: number;
+  files: number;
+  last_modified?: number | null;
 }
 
 export interface DataVersionResponse {
   officer_version?: string;
   hostile_version?: string;
   ship_version?: string;
+  files: { officers: DatasetFiles; ships: DatasetFiles; hostiles: DatasetFiles };
   mechanics: MechanicStatus[];
 }
 
@@ -247,7 +292,185 @@ export async function simulate(
   return res.json();
 }
 
+/** One traced combat event. Typed `values` keep their keys within a `schema_version`. */
+export interface TraceEvent {
+  event_type: string;
+  round_index: number;
+  phase: string;
+  source: Record<string, string>;
+  values: Record<string, unknown>;
+  weapon_index?: number;
+  schema_version: number;
+}
+
+/** One frame from `/ws/simulate`: start, a round of combat events, the result, or an error. */
+export type SimulateStreamFrame =
+  | { type: 'start'; ship: string; hostile: string; seed: number; captain: string; bridge: string[]; below_decks: string[] }
+  | { type: 'round'; round: number; events: TraceEvent[] }
+  | {
+      type: 'result';
+      attacker_won: boolean;
+      winner_by_round_limit: boolean;
+      rounds_simulated: number;
+      total_damage: number;
+      attacker_hull_remaining: number;
+      attacker_shield_remaining: number;
+      defender_hull_remaining: number;
+      defender_shield_remaining: number;
+      warnings?: string[];
+    }
+  | { type: 'error'; message: string };
+
+/** Events a traced fight keeps; omitted fields keep everything. */
+export interface TraceFilter {
+  event_types?: string[];
+  phases?: string[];
+  /** Round 0 holds combat-begin events. */
+  first_round?: number;
+  last_round?: number;
+}
+
+export interface TraceResponse {
+  status: string;
+  ship: string;
+  hostile: string;
+  seed: number;
+  attacker_won: boolean;
+  rounds_simulated: number;
+  /** Events the filter kept over the whole fight. */
+  total_events: number;
+  offset: number;
+  limit: number;
+  /** Null on the last page. */
+  next_offset: number | null;
+  events: TraceEvent[];
+  warnings?: string[];
+}
+
+/** One page of a traced fight's (filtered) events. */
+export async function simulateTrace(
+  params: {
+    ship: string;
+    hostile: string;
+    crew: SimulateCrew;
+    seed?: number;
+    ship_tier?: number | null;
+    ship_level?: number | null;
+    filter?: TraceFilter;
+    offset?: number;
+    limit?: number;
+  },
+  profileId?: string | null,
+): Promise<TraceResponse> {
+  const res = await fetch(`${API_BASE}/api/simulate/trace`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...profileHeaders(profileId) },
+    body: JSON.stringify(params),
+  });
+  await checkOk(res);
+  return res.json();
+}
+
+export interface ScenarioResponse {
+  status: string;
+  name?: string;
+  ship: string;
+  hostile: string;
+  profile: string;
+  objective: string;
+  /** The crew's optimizer score for `objective`. */
+  score: number;
+  stats: SimulateStats;
+  seed: number;
+  warnings?: string[];
+}
+
+/** Run a scenario file (YAML or JSON text) as saved; its `profile` wins over `profileId`. */
+export async function simulateScenario(
+  scenario: string,
+  profileId?: string | null,
+): Promise<ScenarioResponse> {
+  const res = await fetch(`${API_BASE}/api/simulate/scenario`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/yaml', ...profileHeaders(profileId) },
+    body: scenario,
+  });
+  await checkOk(res);
+  return res.json();
+}
+
+export interface MatrixResponse {
+  status: string;
+  /** Axis names, sorted; runs vary the last axis fastest. */
+  axes: string[];
+  runs: { axes: Record<string, unknown>; result: ScenarioResponse }[];
+}
+
+/** Run every combination of a scenario file's `matrix` axes. */
+export async function simulateMatrix(
+  scenario: string,
+  profileId?: string | null,
+): Promise<MatrixResponse> {
+  const res = await fetch(`${API_BASE}/api/simulate/matrix`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/yaml', ...profileHeaders(profileId) },
+    body: scenario,
+  });
+  await checkOk(res);
+  return res.json();
+}
+
+/** Pack a crew, ship and (by default) the profile's bonuses into a shareable code. */
+export async function encodeCrewCode(
+  params: {
+    ship: string;
+    hostile?: string | null;
+    crew: SimulateCrew;
+    ship_tier?: number | null;
+    ship_level?: number | null;
+    include_profile?: boolean;
+  },
+  profileId?: string | null,
+): Promise<string> {
+  const res = await fetch(`${API_BASE}/api/crewcode`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...profileHeaders(profileId) },
+    body: JSON.stringify(params),
+  });
+  await checkOk(res);
+  const body: { status: string; code: string } = await res.json();
+  return body.code;
+}
+
+/** Decode a crew code into a scenario body for `simulateScenario` (as JSON text). */
+export async function decodeCrewCode(
+  code: string,
+  hostile?: string | null,
+): Promise<Record<string, unknown>> {
+  const query = hostile ? `?hostile=${encodeURIComponent(hostile)}` : '';
+  const res = await fetch(`${API_BASE}/api/crewcode/${encodeURIComponent(code)}${query}`);
+  await checkOk(res);
+  const body: { status: string; scenario: Record<string, unknown> } = await res.json();
+  return body.scenario;
+}
+
+/** Stream one traced fight round by round. Returns the socket so the caller can close it early. */
+export function streamSimulation(
+  params: { ship: string; hostile: string; crew: SimulateCrew; seed?: number; ship_tier?: number | null; ship_level?: number | null; filter?: TraceFilter },
+  onFrame: (frame: SimulateStreamFrame) => void,
+): WebSocket {
+  const base = API_BASE || window.location.origin;
+  const url = new URL(`${base}/ws/simulate`, window.location.href);
+  url.protocol = url.protocol === 'https:' ? 'wss:' : 'ws:';
+  const socket = new WebSocket(url.toString());
+  socket.onopen = () => socket.send(JSON.stringify(params));
+  socket.onmessage = (msg) => onFrame(JSON.parse(String(msg.data)) as SimulateStreamFrame);
+  return socket;
+}
+
 export interface CrewRecommendation {
+  /** Stable id of the crew (officers by seat); same crew, same id across runs. */
+  crew_id?: string;
   captain: string;
   /** API returns string[]; we accept string for backward compatibility. */
   bridge: string | string[];
@@ -257,19 +480,88 @@ export interface CrewRecommendation {
   stall_rate: number;
   loss_rate: number;
   avg_hull_remaining: number;
+  /** Mean share of the round limit the player's ship survived (0–1). */
+  avg_survival_share?: number;
+  /** Mean share of the opponent's hull + shields destroyed (0–1). */
+  avg_damage_dealt?: number;
+  /** Share of fights the player's ship survived (0–1). */
+  survival_rate?: number;
+  /** Mean hostiles destroyed per run in wave scenarios. */
+  avg_waves_cleared?: number;
+  /** Median / 95th-percentile rounds to kill over winning fights; null when the crew never won. */
+  median_rounds_to_kill?: number | null;
+  p95_rounds_to_kill?: number | null;
+  /** Mean damage dealt per round fought. */
+  avg_damage_per_round?: number;
+  /** points_per_hour scenarios: expected event points (or loot value) per hour of grinding. */
+  points_per_hour?: number;
+  /** Top `explain_top` recommendations (default 3): why the crew scores as it does. */
+  explanation?: CrewExplanation;
 }
 
+export interface CrewExplanation {
+  /** Fights per leave-one-out run. */
+  sims: number;
+  /** Largest damage share first; shares are the fraction lost with the officer's seat empty. */
+  contributions: {
+    officer: string;
+    seat: 'captain' | 'bridge' | 'below_decks';
+    damage_share: number;
+    survival_share: number;
+    win_rate_delta: number;
+  }[];
+  /** Share of rounds each triggered status was active. */
+  status_uptimes: { status: string; uptime: number }[];
+}
+
+export type ScenarioType = 'offense' | 'defense' | 'mining' | 'waves' | 'points_per_hour';
+
 export interface OptimizeResponse {
   status: string;
-  scenario: { ship: string; hostile: string; sims: number; seed: number };
+  scenario: {
+    ship: string;
+    hostile: string;
+    sims: number;
+    seed: number;
+    scenario_type?: ScenarioType;
+    mining_rounds?: number;
+    waves?: number;
+    wave_repair?: number;
+    /** points_per_hour scenarios: reward per kill from the hostile record. */
+    points_per_kill?: number;
+    /** Officers searched per seat; absent for heuristics-only runs. */
+    officer_pools?: {
+      captains: number;
+      bridge: number;
+      below_decks: number;
+      only_below_decks_with_ability: boolean;
+    };
+  };
   recommendations: CrewRecommendation[];
   duration_ms?: number;
+  /** strategy "auto" only: which search ran and, when cheap, a GA cross-check. */
+  auto_strategy?: {
+    chosen: 'exhaustive' | 'genetic';
+    estimated_candidates: number;
+    exhaustive_max_candidates: number;
+    agreement?: {
+      exhaustive_crew_id: string;
+      exhaustive_score: number;
+      genetic_crew_id: string;
+      genetic_score: number;
+      agrees: boolean;
+    };
+  };
 }
 
 export interface OptimizeEstimate {
   estimated_candidates: number;
   sims_per_crew: number;
   estimated_seconds: number;
+  estimate_basis?: 'observed' | 'heuristic';
+  sims_per_second?: number;
+  /** Strategy "auto" would run for this candidate count. */
+  auto_strategy?: 'exhaustive' | 'genetic';
 }
 
 export async function getOptimizeEstimate(
@@ -329,17 +621,227 @@ export async function optimize(
   return res.json();
 }
 
+export interface OfficerUpgrade {
+  officer_id: string;
+  officer_name: string;
+  current_tier: number;
+  next_tier: number;
+  /** Null when the officer's rarity (and so the shard cost) is unknown. */
+  shard_cost: number | null;
+  win_rate_before: number;
+  win_rate_after: number;
+  /** Change in the scenario's best win rate from this promotion alone. */
+  win_rate_gain: number;
+  gain_per_100_shards: number | null;
+}
+
+export interface OfficerUpgradesResponse {
+  status: string;
+  ship: string;
+  hostile: string;
+  sims: number;
+  seed: number;
+  baseline_win_rate: number;
+  crews_considered: number;
+  /** Best value (gain per shard) first. */
+  upgrades: OfficerUpgrade[];
+}
+
+export async function analyzeOfficerUpgrades(
+  params: {
+    ship: string;
+    hostile: string;
+    ship_tier?: number | null;
+    ship_level?: number | null;
+    sims?: number;
+    seed?: number;
+    max_candidates?: number | null;
+    top_crews?: number;
+  },
+  profileId?: string | null,
+): Promise<OfficerUpgradesResponse> {
+  const res = await fetch(`${API_BASE}/api/analyze/officer-upgrades`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...profileHeaders(profileId) },
+    body: JSON.stringify(params),
+  });
+  await checkOk(res);
+  return res.json();
+}
+
+export type AttackerStat = 'attack' | 'pierce' | 'crit_chance' | 'hull_health';
+
+export interface StatSensitivity {
+  stat: AttackerStat;
+  win_rate_down: number;
+  win_rate_up: number;
+  /** Win-rate change per +1% of the stat (central difference). */
+  win_rate_per_pct: number;
+}
+
+export interface StatSensitivityResponse {
+  status: string;
+  ship: string;
+  hostile: string;
+  num_sims: number;
+  seed: number;
+  delta_pct: number;
+  baseline_win_rate: number;
+  /** Largest win_rate_per_pct first. */
+  stats: StatSensitivity[];
+}
+
+export async function analyzeStats(
+  params: {
+    ship: string;
+    hostile: string;
+    crew: SimulateCrew;
+    ship_tier?: number | null;
+    ship_level?: number | null;
+    num_sims?: number;
+    seed?: number;
+    /** Relative perturbation, e.g. 0.1 for ±10%. */
+    delta_pct?: number;
+  },
+  profileId?: string | null,
+): Promise<StatSensitivityResponse> {
+  const res = await fetch(`${API_BASE}/api/analyze/stats`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...profileHeaders(profileId) },
+    body: JSON.stringify(params),
+  });
+  await checkOk(res);
+  return res.json();
+}
+
+export interface SweepLevel {
+  level: number;
+  hostile_id: string;
+  win_rate: number;
+  avg_hull_remaining: number;
+}
+
+export interface SweepResponse {
+  status: string;
+  ship: string;
+  num_sims: number;
+  seed: number;
+  family: string;
+  target_win_rate: number;
+  /** Null when no swept level reaches target_win_rate. */
+  highest_level_at_target: number | null;
+  levels: SweepLevel[];
+}
+
+export async function analyzeSweep(
+  params: {
+    ship: string;
+    /** Any hostile of the family (see HostileListItem.family). */
+    hostile: string;
+    crew: SimulateCrew;
+    ship_tier?: number | null;
+    ship_level?: number | null;
+    num_sims?: number;
+    seed?: number;
+    min_level?: number;
+    max_level?: number;
+    target_win_rate?: number;
+  },
+  profileId?: string | null,
+): Promise<SweepResponse> {
+  const res = await fetch(`${API_BASE}/api/analyze/sweep`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...profileHeaders(profileId) },
+    body: JSON.stringify(params),
+  });
+  await checkOk(res);
+  return res.json();
+}
+
+export interface BracketStanding {
+  /** Entrants with the same record and win rate share a rank. */
+  rank: number;
+  name: string;
+  ship: string;
+  captain: string;
+  bridge: string[];
+  below_decks: string[];
+  win_rate: number;
+  avg_hull_remaining: number;
+  /** Pairings won / lost with p < significance; draws are not significant either way. */
+  wins: number;
+  losses: number;
+  draws: number;
+}
+
+export interface BracketMatchup {
+  a: string;
+  b: string;
+  win_rate_a: number;
+  win_rate_b: number;
+  p_value: number;
+  /** Null when the gap is not significant. */
+  winner: string | null;
+}
+
+export interface BracketResponse {
+  status: string;
+  hostile: string;
+  num_sims: number;
+  seed: number;
+  significance: number;
+  standings: BracketStanding[];
+  matchups: BracketMatchup[];
+}
+
+export async function analyzeBracket(
+  params: {
+    hostile: string;
+    /** Saved preset ids (see listPresets). */
+    presets: string[];
+    num_sims?: number;
+    seed?: number;
+  },
+  profileId?: string | null,
+): Promise<BracketResponse> {
+  const res = await fetch(`${API_BASE}/api/analyze/bracket`, {
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...profileHeaders(profileId) },
+    body: JSON.stringify(params),
+  });
+  await checkOk(res);
+  return res.json();
+}
+
 export interface OptimizeStartResponse {
   job_id: string;
 }
 
+export interface TieredStageProgress {
+  stage: 'analytical' | 'scout' | 'confirm';
+  done: number;
+  total: number;
+}
+
+export type OptimizePhase = 'generating' | 'simulating' | 'ga_generation' | 'final_ranking';
+
 export interface OptimizeStatusResponse {
   status: string;
   progress?: number;
   crews_done?: number;
   total_crews?: number;
+  sims_per_second?: number;
+  eta_seconds?: number;
+  /** What the job is doing; absent until the first progress report. */
+  phase?: OptimizePhase;
+  /** Tiered and two-phase strategies only: current pipeline stage and progress within it. */
+  stage?: TieredStageProgress;
+  /** Exhaustive jobs only: provisional top crews (up to 5) from completed batches, best first. */
+  provisional?: CrewRecommendation[];
   result?: OptimizeResponse;
   error?: string;
+  /** Failed jobs with KOBAYASHI_JOB_SNAPSHOTS=1: replay with `kobayashi snapshot run <file>`. */
+  snapshot?: Record<string, unknown>;
 }
 
 export async function fetchHeuristics(): Promise<string[]> {
@@ -349,7 +851,7 @@ export async function fetchHeuristics(): Promise<string[]> {
   return data.seeds ?? [];
 }
 
-export type OptimizerStrategyType = 'exhaustive' | 'genetic' | 'tiered';
+export type OptimizerStrategyType = 'exhaustive' | 'genetic' | 'tiered' | 'two_phase' | 'auto';
 
 export async function optimizeStart(
   params: {
@@ -359,6 +861,13 @@ export async function optimizeStart(
     seed?: number;
     max_candidates?: number | null;
     strategy?: OptimizerStrategyType;
+    tiered_scout_sims?: number | null;
+    tiered_top_k?: number | null;
+    max_results?: number | null;
+    scenario_type?: ScenarioType;
+    mining_rounds?: number | null;
+    waves?: number | null;
+    wave_repair?: number | null;
     prioritize_below_decks_ability?: boolean;
     heuristics_seeds?: string[];
     heuristics_only?: boolean;
@@ -380,6 +889,31 @@ export async function optimizeStart(
   if (params.strategy && params.strategy !== 'exhaustive') {
     body.strategy = params.strategy;
   }
+  if (params.scenario_type && params.scenario_type !== 'offense') {
+    body.scenario_type = params.scenario_type;
+  }
+  if (params.scenario_type === 'mining' && params.mining_rounds != null && params.mining_rounds > 0) {
+    body.mining_rounds = params.mining_rounds;
+  }
+  if (params.scenario_type === 'waves') {
+    if (params.waves != null && params.waves > 0) {
+      body.waves = params.waves;
+    }
+    if (params.wave_repair != null && params.wave_repair > 0) {
+      body.wave_repair = params.wave_repair;
+    }
+  }
+  if (params.strategy === 'tiered' || params.strategy === 'two_phase') {
+    if (params.tiered_scout_sims != null && params.tiered_scout_sims > 0) {
+      body.tiered_scout_sims = params.tiered_scout_sims;
+    }
+    if (params.tiered_top_k != null && params.tiered_top_k > 0) {
+      body.tiered_top_k = params.tiered_top_k;
+    }
+  }
+  if (params.max_results != null && params.max_results > 0) {
+    body.max_results = params.max_results;
+  }
   if (params.prioritize_below_decks_ability === true) {
     body.prioritize_below_decks_ability = true;
   }
@@ -476,8 +1010,37 @@ export async function fetchForbiddenTech(): Promise<ForbiddenTechCatalogItem[]>
   return data.items ?? [];
 }
 
+/**
+ * Hand-entered combat bonuses. Percent stats (weapon_damage, hull_hp, shield_hp, crit_damage)
+ * scale the ship's stat by 1 + value; the rest are added flat. Both are decimals (0.25 = 25%).
+ */
+export type PlayerBonuses = Partial<
+  Record<
+    | 'weapon_damage'
+    | 'hull_hp'
+    | 'shield_hp'
+    | 'crit_damage'
+    | 'crit_chance'
+    | 'pierce'
+    | 'shield_mitigation'
+    | 'armor'
+    | 'dodge'
+    | 'damage_reduction'
+    | 'isolytic_damage'
+    | 'isolytic_defense'
+    | 'apex_shred'
+    | 'apex_barrier',
+    number
+  >
+>;
+
+/** Kind of defender a fight is against. */
+export type TargetContext = 'hostile' | 'armada' | 'player';
+
 export interface PlayerProfile {
-  bonuses: Record<string, number>;
+  bonuses: PlayerBonuses;
+  /** Extra bonuses only applied against one kind of defender, added on top of `bonuses`. */
+  target_bonuses?: Partial<Record<TargetContext, PlayerBonuses>>;
   /** When undefined/null: use synced forbidden_tech.imported.json. When []: no FT. When number[]: use these fids. */
   forbidden_tech_override?: number[] | null;
   /** When undefined/null: use synced chaos tech from forbidden_tech.imported.json. When []: none. When number[]: use these fids. */
@@ -552,6 +1115,8 @@ export interface ResearchCombatSummary {
   synced_research_count: number;
   unmapped_rids: number[];
   combat_bonuses_from_research?: Record<string, number>;
+  /** Research bonuses that only apply against one kind of defender. */
+  target_combat_bonuses_from_research?: Partial<Record<TargetContext, Record<string, number>>>;
   research: ResearchSummaryRow[];
 }
 
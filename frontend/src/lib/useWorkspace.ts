@@ -20,6 +20,7 @@ import {
   type SimulateStats,
   type OptimizeEstimate,
   type CrewRecommendation,
+  type OptimizePhase,
   type OptimizeStatusResponse,
   type Preset,
 } from './api';
@@ -56,6 +57,7 @@ export function useWorkspace() {
   const [optimizeProgress, setOptimizeProgress] = useState<number | null>(null);
   const [optimizeCrewsDone, setOptimizeCrewsDone] = useState<number | null>(null);
   const [optimizeTotalCrews, setOptimizeTotalCrews] = useState<number | null>(null);
+  const [optimizePhase, setOptimizePhase] = useState<OptimizePhase | null>(null);
   const [estimate, setEstimate] = useState<OptimizeEstimate | null>(null);
   const [lastOptimizeDurationMs, setLastOptimizeDurationMs] = useState<number | null>(null);
 
@@ -224,6 +226,7 @@ export function useWorkspace() {
     setOptimizeProgress(null);
     setOptimizeCrewsDone(null);
     setOptimizeTotalCrews(null);
+    setOptimizePhase(null);
   };
 
   // Handle running optimization
@@ -234,6 +237,7 @@ export function useWorkspace() {
     setOptimizeProgress(0);
     setOptimizeCrewsDone(0);
     setOptimizeTotalCrews(null);
+    setOptimizePhase(null);
     try {
       const { job_id } = await optimizeStart(
         buildWorkspaceOptimizeStartBody({
@@ -258,6 +262,7 @@ export function useWorkspace() {
             if (status.progress != null) setOptimizeProgress(status.progress);
             if (status.crews_done != null) setOptimizeCrewsDone(status.crews_done);
             if (status.total_crews != null) setOptimizeTotalCrews(status.total_crews);
+            if (status.phase != null) setOptimizePhase(status.phase);
             if (status.status === 'done' || status.status === 'error') {
               applyOptimizeDone(status);
             }
@@ -273,6 +278,7 @@ export function useWorkspace() {
             setOptimizeProgress(null);
             setOptimizeCrewsDone(null);
             setOptimizeTotalCrews(null);
+            setOptimizePhase(null);
           });
       };
 
@@ -286,6 +292,7 @@ export function useWorkspace() {
             if (status.progress != null) setOptimizeProgress(status.progress);
             if (status.crews_done != null) setOptimizeCrewsDone(status.crews_done);
             if (status.total_crews != null) setOptimizeTotalCrews(status.total_crews);
+            if (status.phase != null) setOptimizePhase(status.phase);
             if (status.status === 'done' || status.status === 'error') {
               eventSource.close();
               eventSourceRef.current = null;
@@ -311,6 +318,7 @@ export function useWorkspace() {
       setOptimizeProgress(null);
       setOptimizeCrewsDone(null);
       setOptimizeTotalCrews(null);
+      setOptimizePhase(null);
     }
   };
 
@@ -332,6 +340,7 @@ export function useWorkspace() {
     setOptimizeProgress(null);
     setOptimizeCrewsDone(null);
     setOptimizeTotalCrews(null);
+    setOptimizePhase(null);
   };
 
   // Handle saving a preset
@@ -388,6 +397,7 @@ export function useWorkspace() {
     optimizeProgress,
     optimizeCrewsDone,
     optimizeTotalCrews,
+    optimizePhase,
     estimate,
     lastOptimizeDurationMs,
     // Optimization parameters
@@ -53,15 +53,15 @@ export default function DataMechanics() {
         <div style={{ display: 'flex', flexWrap: 'wrap', gap: '1rem', fontSize: '0.9rem' }}>
           <span>
             <strong>Officer catalog:</strong>{' '}
-            {data.officer_version ?? '—'}
+            {data.officer_version ?? '—'} ({data.files.officers.records} officers)
           </span>
           <span>
             <strong>Hostile catalog:</strong>{' '}
-            {data.hostile_version ?? '—'}
+            {data.hostile_version ?? '—'} ({data.files.hostiles.records} hostiles)
           </span>
           <span>
             <strong>Ship catalog:</strong>{' '}
-            {data.ship_version ?? '—'}
+            {data.ship_version ?? '—'} ({data.files.ships.records} ships)
           </span>
         </div>
       </section>
@@ -11,6 +11,7 @@ import {
 import type {
   ImportReport,
   PlayerProfile,
+  PlayerBonuses,
   ForbiddenTechCatalogItem,
   BuildingCombatSummary,
   ResearchCombatSummary,
@@ -19,6 +20,17 @@ import { useProfile } from '../contexts/ProfileContext';
 
 type Tab = 'profile' | 'roster' | 'bonuses';
 
+/** Bonuses shown in quick mode; stored as decimals, edited as whole percents. */
+const QUICK_BONUSES: { key: keyof PlayerBonuses; label: string }[] = [
+  { key: 'weapon_damage', label: 'weapon damage' },
+  { key: 'shield_hp', label: 'shield HP' },
+  { key: 'hull_hp', label: 'hull HP' },
+  { key: 'damage_reduction', label: 'mitigation' },
+];
+
+const toPercent = (value: number | undefined) =>
+  value === undefined ? '' : Number((value * 100).toFixed(4));
+
 export default function RosterProfile() {
   const { activeProfileId, profiles } = useProfile();
   const [tab, setTab] = useState<Tab>('profile');
@@ -105,7 +117,7 @@ export default function RosterProfile() {
     }
   };
 
-  const setBonus = (key: string, value: number) => {
+  const setBonus = (key: keyof PlayerBonuses, value: number) => {
     setProfile((p) => ({
       ...p,
       bonuses: { ...p.bonuses, [key]: value },
@@ -735,14 +747,14 @@ token = "${activeProfile.sync_token}"`}
             Quick mode: enter effective bonus percentages (e.g. weapon, shield, mitigation).
           </p>
           <div style={{ display: 'flex', flexDirection: 'column', gap: 8, maxWidth: 400 }}>
-            {['weapon', 'shield', 'mitigation', 'hull'].map((key) => (
+            {QUICK_BONUSES.map(({ key, label }) => (
               <label key={key} style={{ display: 'flex', alignItems: 'center', gap: 8 }}>
-                <span style={{ width: 100 }}>{key} %</span>
+                <span style={{ width: 140 }}>{label} %</span>
                 <input
                   type="number"
                   step={0.1}
-                  value={profile.bonuses[key] ?? ''}
-                  onChange={(e) => setBonus(key, Number(e.target.value) || 0)}
+                  value={toPercent(profile.bonuses[key])}
+                  onChange={(e) => setBonus(key, (Number(e.target.value) || 0) / 100)}
                   style={{
                     padding: '0.4rem',
                     background: 'var(--bg)',
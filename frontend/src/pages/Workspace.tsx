@@ -147,6 +147,7 @@ export default function Workspace() {
               optimizeProgress={ws.optimizeProgress}
               optimizeCrewsDone={ws.optimizeCrewsDone}
               optimizeTotalCrews={ws.optimizeTotalCrews}
+              optimizePhase={ws.optimizePhase}
             />
           </div>
         </section>
@@ -0,0 +1,32 @@
+/*
+ * C interface to the kobayashi combat engine (src/embed.rs).
+ *
+ * Build:  cargo rustc --lib --release --no-default-features --features ffi --crate-type cdylib
+ *
+ * Inputs are NUL-terminated UTF-8 JSON documents; see SimulateInput and OptimizeInput in
+ * src/embed.rs. Every call returns a JSON document owned by the library:
+ *   {"status":"ok", ...}  or  {"status":"error","message":"..."}
+ * Release it with kobayashi_free_string. A NULL return means the response could not be allocated.
+ * No files are read; the calls are safe to make from multiple threads.
+ */
+#ifndef KOBAYASHI_H
+#define KOBAYASHI_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* One fight between caller-built combatants, optionally with an LCARS crew on the attacker. */
+char *kobayashi_simulate_json(const char *input);
+
+/* Monte Carlo ranking of candidate crews for a ship record against a hostile record. */
+char *kobayashi_optimize_json(const char *input);
+
+/* Free a string returned by this library. NULL is ignored. */
+void kobayashi_free_string(char *response);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* KOBAYASHI_H */
@@ -25,14 +25,16 @@ fn main() {
             from_gen
         } else {
             (0..32)
-                .map(|i| CrewCandidate {
-                    captain: format!("Captain_{i}"),
-                    bridge: vec!["Bridge_A".to_string(), "Bridge_B".to_string()],
-                    below_decks: vec![
-                        "Below_1".to_string(),
-                        "Below_2".to_string(),
-                        "Below_3".to_string(),
-                    ],
+                .map(|i| {
+                    CrewCandidate::new(
+                        format!("Captain_{i}"),
+                        vec!["Bridge_A".to_string(), "Bridge_B".to_string()],
+                        vec![
+                            "Below_1".to_string(),
+                            "Below_2".to_string(),
+                            "Below_3".to_string(),
+                        ],
+                    )
                 })
                 .collect()
         }
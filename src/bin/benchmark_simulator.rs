@@ -10,57 +10,28 @@ use std::fs::OpenOptions;
 use std::io::Write;
 use std::time::Instant;
 
-use kobayashi::combat::{
-    simulate_combat, Combatant, CrewConfiguration, SimulationConfig, TraceMode,
-};
+use kobayashi::combat::{simulate_combat, Combatant, CrewConfiguration, SimulationConfig};
 
 fn main() {
     let log = std::env::args().any(|a| a == "--log");
 
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 500.0,
-        mitigation: 0.0,
-        pierce: 200.0,
-        crit_chance: 0.1,
-        crit_multiplier: 1.5,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 300.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 800.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(500.0)
+        .pierce(200.0)
+        .crit_chance(0.1)
+        .crit_multiplier(1.5)
+        .build()
+        .expect("benchmark attacker stats are in range");
+    let defender = Combatant::builder("defender")
+        .mitigation(300.0)
+        .shield_health(800.0)
+        .build()
+        .expect("benchmark defender stats are in range");
     let rounds_per_combat = 100u32;
-    let config = SimulationConfig {
-        rounds: rounds_per_combat,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(rounds_per_combat)
+        .seed(7)
+        .build();
     let crew = CrewConfiguration::default();
 
     // Run for at least this long or this many combats
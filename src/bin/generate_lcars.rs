@@ -160,18 +160,24 @@ fn convert_officer(o: CanonicalOfficer) -> LcarsOfficer {
         captain_ability = Some(LcarsAbility {
             name: format!("{} (Captain)", o.name),
             effects: captain_effects,
+            max_activations: None,
+            cooldown_rounds: None,
         });
     }
     if !bridge_effects.is_empty() {
         bridge_ability = Some(LcarsAbility {
             name: format!("{} (Bridge)", o.name),
             effects: bridge_effects,
+            max_activations: None,
+            cooldown_rounds: None,
         });
     }
     if !below_effects.is_empty() {
         below_decks_ability = Some(LcarsAbility {
             name: format!("{} (Below Decks)", o.name),
             effects: below_effects,
+            max_activations: None,
+            cooldown_rounds: None,
         });
     }
 
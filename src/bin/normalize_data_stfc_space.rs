@@ -12,14 +12,16 @@ struct AbilityCatalogEntry {
     timing: String,
     effect_type: String,
     value_is_percentage: bool,
+    against: Vec<String>,
 }
 
 use kobayashi::data::ship::{
-    ExtendedShipRecord, LevelBonus, ShipAbility, ShipIdRegistry, ShipIdRegistryEntry, TierStats,
-    WeaponRecord, DEFAULT_SHIP_ID_REGISTRY_PATH,
+    ExtendedShipRecord, LevelBonus, OfficerBonus, OfficerBonusStep, RepairCost, ShipAbility,
+    ShipIdRegistry, ShipIdRegistryEntry, TierStats, WeaponRecord, DEFAULT_SHIP_ID_REGISTRY_PATH,
 };
 
 const SHIP_ABILITY_CATALOG_PATH: &str = "data/upstream/data-stfc-space/ship_ability_catalog.json";
+const RESOURCE_SUMMARY_PATH: &str = "data/upstream/data-stfc-space/summary-ressource.json";
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let repo_root = Path::new(".");
@@ -50,11 +52,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     let timing = v.get("timing")?.as_str()?.to_string();
                     let effect_type = v.get("effect_type")?.as_str()?.to_string();
                     let value_is_percentage = v.get("value_is_percentage").and_then(Value::as_bool).unwrap_or(false);
-                    map.insert(k.clone(), AbilityCatalogEntry { timing, effect_type, value_is_percentage });
+                    let against = v
+                        .get("against")
+                        .and_then(Value::as_array)
+                        .map(|tags| tags.iter().filter_map(|t| t.as_str().map(str::to_string)).collect())
+                        .unwrap_or_default();
+                    map.insert(k.clone(), AbilityCatalogEntry { timing, effect_type, value_is_percentage, against });
                 }
                 Some(map)
             });
 
+    let resource_names = load_resource_names(&repo_root.join(RESOURCE_SUMMARY_PATH));
+
     let id_by_numeric: std::collections::HashMap<u64, &ShipIdRegistryEntry> =
         registry.ships.iter().map(|e| (e.numeric_id, e)).collect();
 
@@ -83,7 +92,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         let content = fs::read_to_string(&path)?;
         let raw: Value = serde_json::from_str(&content)?;
-        let extended = raw_to_extended(&raw, &reg.id, &reg.ship_name, &reg.ship_class, ability_catalog.as_ref())?;
+        let mut extended = raw_to_extended(
+            &raw,
+            &reg.id,
+            &reg.ship_name,
+            &reg.ship_class,
+            ability_catalog.as_ref(),
+            &resource_names,
+        )?;
+        // Innate ship abilities are entered by hand; keep them across re-normalization.
+        extended.ship_ability = kobayashi::data::ship::load_extended_ship_record(&out_dir, &extended.id)
+            .and_then(|existing| existing.ship_ability);
         index_entries.push(kobayashi::data::ship::ExtendedShipIndexEntry {
             id: extended.id.clone(),
             ship_name: extended.ship_name.clone(),
@@ -115,6 +134,7 @@ fn raw_to_extended(
     ship_name: &str,
     ship_class: &str,
     ability_catalog: Option<&std::collections::HashMap<String, AbilityCatalogEntry>>,
+    resource_names: &std::collections::HashMap<u64, String>,
 ) -> Result<ExtendedShipRecord, Box<dyn std::error::Error>> {
     let tiers_arr = raw
         .get("tiers")
@@ -147,6 +167,11 @@ fn raw_to_extended(
             shield_health,
             shield_mitigation: Some(shield_mitigation),
             weapons,
+            below_decks_slots: None,
+            armor: component_stat(components, "Armor", "plating"),
+            absorption: component_stat(components, "Shield", "absorption"),
+            dodge: component_stat(components, "Impulse", "dodge"),
+            repair_cost: tier_repair_cost(components, resource_names),
         });
     }
 
@@ -193,6 +218,7 @@ fn raw_to_extended(
                 timing: entry.timing.clone(),
                 effect_type: entry.effect_type.clone(),
                 value,
+                against: entry.against.clone(),
             });
         }
         if out.is_empty() {
@@ -209,9 +235,91 @@ fn raw_to_extended(
         tiers,
         levels,
         abilities,
+        ship_ability: None,
+        bridge_slots: None,
+        officer_bonus: raw.get("officer_bonus").map(officer_bonus),
     })
 }
 
+/// `percent` as a fraction, rounded to 9 places to drop the source's float noise (e.g.
+/// 1.2000000000000002).
+fn percent_to_fraction(percent: Option<f64>) -> f64 {
+    (percent.unwrap_or(0.0) * 1e7).round() / 1e9
+}
+
+/// The ship's `officer_bonus` tables, with bonuses converted from percent to fractions.
+fn officer_bonus(raw: &Value) -> OfficerBonus {
+    let steps = |stat: &str| -> Vec<OfficerBonusStep> {
+        raw.get(stat)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|step| OfficerBonusStep {
+                value: step.get("value").and_then(Value::as_f64).unwrap_or(0.0),
+                bonus: percent_to_fraction(step.get("bonus").and_then(Value::as_f64)),
+            })
+            .collect()
+    };
+    OfficerBonus {
+        attack: steps("attack"),
+        defense: steps("defense"),
+        health: steps("health"),
+    }
+}
+
+/// Upstream resource id → lowercase name ("Resource_Tritanium" → "tritanium"). Empty when the
+/// resource summary is missing; costs then keep the numeric id as the name.
+fn load_resource_names(path: &Path) -> std::collections::HashMap<u64, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return std::collections::HashMap::new();
+    };
+    let Ok(Value::Array(resources)) = serde_json::from_str::<Value>(&content) else {
+        return std::collections::HashMap::new();
+    };
+    resources
+        .iter()
+        .filter_map(|r| {
+            let id = r.get("id")?.as_u64()?;
+            let name = r.get("resource_id")?.as_str()?;
+            let name = name.strip_prefix("Resource_").unwrap_or(name);
+            Some((id, name.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
+/// Full repair at one tier: every component's `repair_time` and `repair_cost` summed. None when
+/// no component lists either (upstream leaves them empty at tier 1).
+fn tier_repair_cost(
+    components: &[Value],
+    resource_names: &std::collections::HashMap<u64, String>,
+) -> Option<RepairCost> {
+    let mut cost = RepairCost::default();
+    for comp in components {
+        if let Some(seconds) = comp.get("repair_time").and_then(Value::as_f64) {
+            cost.seconds += seconds.max(0.0);
+        }
+        for resource in comp.get("repair_cost").and_then(Value::as_array).into_iter().flatten() {
+            let Some(id) = resource.get("resource_id").and_then(Value::as_u64) else {
+                continue;
+            };
+            let amount = resource.get("amount").and_then(Value::as_f64).unwrap_or(0.0);
+            let name = resource_names.get(&id).cloned().unwrap_or_else(|| id.to_string());
+            *cost.resources.entry(name).or_default() += amount;
+        }
+    }
+    (cost.seconds > 0.0 || !cost.resources.is_empty()).then_some(cost)
+}
+
+/// `key` from the data of the first component tagged `tag` (e.g. the Armor component's plating).
+fn component_stat(components: &[Value], tag: &str, key: &str) -> Option<f64> {
+    components
+        .iter()
+        .filter_map(|comp| comp.get("data"))
+        .find(|data| data.get("tag").and_then(Value::as_str) == Some(tag))
+        .and_then(|data| data.get(key))
+        .and_then(Value::as_f64)
+}
+
 /// Order value used when component has no order or order is -1 (sort after valid weapons).
 const WEAPON_ORDER_LAST: i64 = 999;
 
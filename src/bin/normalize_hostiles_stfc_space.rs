@@ -190,6 +190,22 @@ fn raw_to_record(raw: RawUpstream, unknown_hull: &mut u32) -> HostileRecord {
         components: raw.components,
         ability: raw.ability,
         resources: raw.resources,
+        loot_value: None,
+        event_points: None,
+        tags: Vec::new(),
+        armada: None,
+        shots_per_round: None,
+    }
+}
+
+/// Hand-entered reward metadata, tags and armada mechanics have no upstream source; carry them
+/// over from the previous output.
+fn keep_hand_entered_fields(rec: &mut HostileRecord, out_dir: &Path) {
+    if let Some(existing) = kobayashi::data::hostile::load_hostile_record(out_dir, &rec.id) {
+        rec.loot_value = existing.loot_value;
+        rec.event_points = existing.event_points;
+        rec.tags = existing.tags;
+        rec.armada = existing.armada;
     }
 }
 
@@ -275,7 +291,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 
     let mut index_entries: Vec<HostileIndexEntry> = Vec::with_capacity(records.len());
-    for rec in &records {
+    for rec in &mut records {
+        keep_hand_entered_fields(rec, &out_dir);
         index_entries.push(HostileIndexEntry {
             id: rec.id.clone(),
             hostile_name: rec.hostile_name.clone(),
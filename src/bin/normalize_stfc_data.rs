@@ -266,6 +266,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     components: Vec::new(),
                     ability: Vec::new(),
                     resources: Vec::new(),
+                    loot_value: None,
+                    event_points: None,
+                    tags: Vec::new(),
+                    armada: None,
+                    shots_per_round: None,
                 };
                 hostile_index_entries.push(kobayashi::data::hostile::HostileIndexEntry {
                     id: rec.id.clone(),
@@ -606,5 +611,13 @@ fn raw_to_ship_record(id: &str, raw: &RawShip) -> Option<kobayashi::data::ship::
         isolytic_damage: 0.0,
         weapons,
         abilities: None,
+        ship_ability: None,
+        bridge_slots: None,
+        below_decks_slots: None,
+        armor: None,
+        absorption: None,
+        dodge: None,
+        officer_bonus: None,
+        repair_cost: None,
     })
 }
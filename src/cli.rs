@@ -3,12 +3,12 @@ use std::fmt::Write as _;
 
 use crate::combat::{
     default_percent_sensitivity_rows, format_sensitivity_tsv, simulate_combat, Combatant,
-    CrewConfiguration, HostileMitigationBaseline, SimulationConfig, TraceMode, MITIGATION_CEILING,
+    CrewConfiguration, HostileMitigationBaseline, SimulationConfig, MITIGATION_CEILING,
     MITIGATION_FLOOR,
 };
 use crate::data::loader::{resolve_hostile, resolve_ship};
 use crate::data::import::{import_roster_csv_to, import_spocks_export_to};
-use crate::data::profile::{apply_profile_to_attacker, load_profile};
+use crate::data::profile::{apply_profile_to_attacker, load_profile, TargetContext};
 use crate::data::profile_index::{migrate_from_legacy_if_needed, profile_path, resolve_profile_id_for_api, PROFILE_JSON, ROSTER_IMPORTED};
 use crate::data::validate::{validate_officer_dataset, ValidationSeverity};
 use crate::optimizer::optimize_crew;
@@ -40,6 +40,7 @@ pub fn parse_command(args: &[String]) -> Option<Command> {
 }
 
 pub fn run_with_args(args: &[String]) -> i32 {
+    crate::logging::init();
     let _ = migrate_from_legacy_if_needed();
     init_from_env();
 
@@ -149,55 +150,28 @@ fn handle_simulate(args: &[String]) -> i32 {
     let player_profile = load_profile(&profile_path_str);
 
     let attacker = apply_profile_to_attacker(
-        Combatant {
-            id: "player".to_string(),
-            attack: 120.0,
-            mitigation: 0.1,
-            pierce: 0.15,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            end_of_round_damage: 0.0,
-            hull_health: 1000.0,
-            shield_health: 0.0,
-            shield_mitigation: 0.8,
-            apex_barrier: 0.0,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-            weapons: vec![],
-        },
+        Combatant::builder("player")
+            .attack(120.0)
+            .mitigation(0.1)
+            .pierce(0.15)
+            .build_unchecked(),
         &player_profile,
+        TargetContext::Hostile,
     );
-    let defender = Combatant {
-        id: "hostile".to_string(),
-        attack: 10.0,
-        mitigation: 0.35,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 500.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let defender = Combatant::builder("hostile")
+        .attack(10.0)
+        .mitigation(0.35)
+        .shield_health(500.0)
+        .build_unchecked();
 
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds,
-            seed,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(rounds)
+            .seed(seed)
+            .trace(true)
+            .build(),
         &CrewConfiguration::default(),
     );
 
@@ -1,4 +1,9 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+use super::dot::DotChannel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AbilityClass {
     CaptainManeuver,
     BridgeAbility,
@@ -7,7 +12,8 @@ pub enum AbilityClass {
     ShipAbility,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TimingWindow {
     CombatBegin,
     RoundStart,
@@ -26,7 +32,23 @@ pub enum TimingWindow {
     CombatEnd,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl TimingWindow {
+    pub const ALL: [TimingWindow; 10] = [
+        Self::CombatBegin,
+        Self::RoundStart,
+        Self::AttackPhase,
+        Self::DefensePhase,
+        Self::RoundEnd,
+        Self::ShieldBreak,
+        Self::Kill,
+        Self::HullBreach,
+        Self::ReceiveDamage,
+        Self::CombatEnd,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CrewSeat {
     Captain,
     Bridge,
@@ -35,7 +57,8 @@ pub enum CrewSeat {
     Ship,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AbilityEffect {
     AttackMultiplier(f64),
     PierceBonus(f64),
@@ -53,6 +76,14 @@ pub enum AbilityEffect {
         chance: f64,
         duration_rounds: u32,
     },
+    /// Damage-over-time application on a separate channel (plasma, isolytic cascade, or a
+    /// burning variant with its own rate). hull_pct is the fraction of the target's max hull per round.
+    DamageOverTime {
+        channel: DotChannel,
+        chance: f64,
+        hull_pct: f64,
+        duration_rounds: u32,
+    },
     /// Shield HP restored per round (round end). Flat value.
     ShieldRegen(f64),
     /// Hull HP restored per round (round end). Reduces effective hull damage taken.
@@ -94,20 +125,25 @@ pub enum AbilityEffect {
 
 /// Combat context for condition evaluation at runtime.
 #[derive(Debug, Clone)]
-pub struct CombatContext {
+pub struct CombatContext<'a> {
     pub round_index: u32,
     pub defender_hull_pct: f64,
     pub defender_shield_pct: f64,
     pub attacker_hull_pct: f64,
     pub attacker_shield_pct: f64,
+    /// The defender's [crate::combat::Combatant::tags].
+    pub target_tags: &'a [String],
 }
 
 /// Condition that gates effect activation. Evaluated at runtime in the combat loop.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AbilityCondition {
     StatBelow { stat: String, threshold_pct: f64 },
     StatAbove { stat: String, threshold_pct: f64 },
     RoundRange { min: u32, max: u32 },
+    /// The target carries this tag (case-insensitive), e.g. "vs Swarm".
+    TargetTag(String),
     And(Vec<AbilityCondition>),
     Or(Vec<AbilityCondition>),
 }
@@ -136,13 +172,29 @@ impl AbilityCondition {
                 pct > *threshold_pct
             }
             Self::RoundRange { min, max } => ctx.round_index >= *min && ctx.round_index <= *max,
+            Self::TargetTag(tag) => Self::has_tag(ctx.target_tags, tag),
             Self::And(conds) => conds.iter().all(|c| c.evaluate(ctx)),
             Self::Or(conds) => conds.iter().any(|c| c.evaluate(ctx)),
         }
     }
+
+    /// Target-tag part only, with every runtime condition treated as met. Used where no combat
+    /// state exists yet (static buffs resolved before the fight).
+    pub fn matches_target(&self, target_tags: &[String]) -> bool {
+        match self {
+            Self::TargetTag(tag) => Self::has_tag(target_tags, tag),
+            Self::And(conds) => conds.iter().all(|c| c.matches_target(target_tags)),
+            Self::Or(conds) => conds.iter().any(|c| c.matches_target(target_tags)),
+            _ => true,
+        }
+    }
+
+    fn has_tag(target_tags: &[String], tag: &str) -> bool {
+        target_tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ability {
     pub name: String,
     pub class: AbilityClass,
@@ -150,12 +202,16 @@ pub struct Ability {
     pub boostable: bool,
     pub effect: AbilityEffect,
     pub condition: Option<AbilityCondition>,
+    /// Times this ability may trigger per combat; `None` is unlimited.
+    pub max_activations: Option<u32>,
+    /// Rounds after a trigger before it can trigger again (2 = every other round); 0 is no cooldown.
+    pub cooldown_rounds: u32,
 }
 
 /// Sentinel batch id: legacy or non-officer contexts group by consecutive matching [CrewSeatContext::officer_id].
 pub const NO_EXPLICIT_CONTRIBUTION_BATCH: u32 = u32::MAX;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CrewSeatContext {
     pub seat: CrewSeat,
     pub ability: Ability,
@@ -237,7 +293,7 @@ pub fn apply_duplicate_officer_policy(crew: &CrewConfiguration) -> CrewConfigura
     CrewConfiguration { seats: out }
 }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct CrewConfiguration {
     pub seats: Vec<CrewSeatContext>,
 }
@@ -248,6 +304,78 @@ pub struct ActiveAbilityEffect {
     pub effect: AbilityEffect,
     pub boosted: bool,
     pub condition: Option<AbilityCondition>,
+    /// Index of the source row in [CrewConfiguration::seats]; keys [ActivationLimits].
+    pub seat_index: usize,
+    pub max_activations: Option<u32>,
+    pub cooldown_rounds: u32,
+}
+
+impl ActiveAbilityEffect {
+    fn is_limited(&self) -> bool {
+        self.max_activations.is_some() || self.cooldown_rounds > 0
+    }
+}
+
+/// Why [ActivationLimits::try_activate] refused an activation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationSuppression {
+    MaxActivations,
+    Cooldown,
+}
+
+impl ActivationSuppression {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MaxActivations => "max_activations",
+            Self::Cooldown => "cooldown",
+        }
+    }
+}
+
+/// Per-combat activation counts backing [Ability::max_activations] and [Ability::cooldown_rounds].
+#[derive(Debug, Clone, Default)]
+pub struct ActivationLimits {
+    /// Indexed by seat: (activations so far, round of the last activation).
+    used: Vec<(u32, Option<u32>)>,
+}
+
+impl ActivationLimits {
+    /// Forget every activation so the limits can be reused for another fight.
+    pub fn clear(&mut self) {
+        self.used.clear();
+    }
+
+    /// Count an activation in `round_index`, or say why the ability may not trigger.
+    /// Unlimited abilities always pass and are not tracked.
+    pub fn try_activate(
+        &mut self,
+        effect: &ActiveAbilityEffect,
+        round_index: u32,
+    ) -> Result<(), ActivationSuppression> {
+        if !effect.is_limited() {
+            return Ok(());
+        }
+        if self.used.len() <= effect.seat_index {
+            self.used.resize(effect.seat_index + 1, (0, None));
+        }
+        let (count, last_round) = &mut self.used[effect.seat_index];
+        if effect.max_activations.is_some_and(|max| *count >= max) {
+            return Err(ActivationSuppression::MaxActivations);
+        }
+        if let Some(last) = *last_round {
+            if round_index < last + effect.cooldown_rounds {
+                return Err(ActivationSuppression::Cooldown);
+            }
+        }
+        *count += 1;
+        *last_round = Some(round_index);
+        Ok(())
+    }
+
+    /// Activations counted so far for the ability at `seat_index`.
+    pub fn activations(&self, seat_index: usize) -> u32 {
+        self.used.get(seat_index).map_or(0, |(count, _)| *count)
+    }
 }
 
 impl AbilityClass {
@@ -272,18 +400,121 @@ pub fn active_effects_for_timing(
 ) -> Vec<ActiveAbilityEffect> {
     crew.seats
         .iter()
-        .filter(|seat_context| {
+        .enumerate()
+        .filter(|(_, seat_context)| {
             can_activate_in_seat(seat_context) && seat_context.ability.timing == timing
         })
-        .map(|seat_context| ActiveAbilityEffect {
+        .map(|(seat_index, seat_context)| ActiveAbilityEffect {
             ability_name: seat_context.ability.name.clone(),
             effect: seat_context.ability.effect,
             boosted: seat_context.boosted,
             condition: seat_context.ability.condition.clone(),
+            seat_index,
+            max_activations: seat_context.ability.max_activations,
+            cooldown_rounds: seat_context.ability.cooldown_rounds,
         })
         .collect()
 }
 
+/// A crew's abilities grouped by [TimingWindow], after [apply_duplicate_officer_policy]. The crew
+/// never changes within a run, so Monte Carlo builds this once per candidate and every iteration
+/// reuses it (see [crate::combat::simulate_combat_with_effects]).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrewEffects {
+    by_timing: [Vec<ActiveAbilityEffect>; TimingWindow::ALL.len()],
+}
+
+impl CrewEffects {
+    pub fn new(crew: &CrewConfiguration) -> Self {
+        let crew = apply_duplicate_officer_policy(crew);
+        Self {
+            by_timing: TimingWindow::ALL.map(|timing| active_effects_for_timing(&crew, timing)),
+        }
+    }
+
+    /// Same as [active_effects_for_timing] on the deduplicated crew.
+    pub fn for_timing(&self, timing: TimingWindow) -> &[ActiveAbilityEffect] {
+        &self.by_timing[timing as usize]
+    }
+}
+
+/// Effects of one timing window picked by [select_effects], held as positions into the window's
+/// slice so the round loop neither clones effects nor allocates a fresh list per window.
+#[derive(Debug)]
+pub struct EffectSelection<'a> {
+    effects: &'a [ActiveAbilityEffect],
+    picked: &'a mut Vec<usize>,
+}
+
+impl<'a> EffectSelection<'a> {
+    pub fn iter(&self) -> SelectedEffects<'_, 'a> {
+        SelectedEffects {
+            effects: self.effects,
+            picked: self.picked.iter(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.picked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.picked.is_empty()
+    }
+
+    /// Drop the picked effects for which `keep` returns false; order is preserved.
+    pub fn retain(&mut self, mut keep: impl FnMut(&ActiveAbilityEffect) -> bool) {
+        let effects = self.effects;
+        self.picked.retain(|&i| keep(&effects[i]));
+    }
+}
+
+impl<'s, 'a> IntoIterator for &'s EffectSelection<'a> {
+    type Item = &'a ActiveAbilityEffect;
+    type IntoIter = SelectedEffects<'s, 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over an [EffectSelection], in officer order.
+#[derive(Debug, Clone)]
+pub struct SelectedEffects<'s, 'a> {
+    effects: &'a [ActiveAbilityEffect],
+    picked: std::slice::Iter<'s, usize>,
+}
+
+impl<'a> Iterator for SelectedEffects<'_, 'a> {
+    type Item = &'a ActiveAbilityEffect;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.picked.next().map(|&i| &self.effects[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.picked.size_hint()
+    }
+}
+
+/// [filter_effects_by_condition] into `picked`, which is cleared first and keeps its capacity
+/// between calls.
+pub fn select_effects<'a>(
+    effects: &'a [ActiveAbilityEffect],
+    ctx: &CombatContext,
+    picked: &'a mut Vec<usize>,
+) -> EffectSelection<'a> {
+    picked.clear();
+    picked.extend(
+        effects
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.condition.as_ref().is_none_or(|c| c.evaluate(ctx)))
+            .map(|(i, _)| i),
+    );
+    EffectSelection { effects, picked }
+}
+
 /// Filter effects by condition. Effects without a condition always pass.
 pub fn filter_effects_by_condition(
     effects: &[ActiveAbilityEffect],
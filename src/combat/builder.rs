@@ -0,0 +1,368 @@
+//! Builders for [Combatant] and [SimulationConfig], the supported way to construct them outside
+//! the engine. Unset stats take neutral defaults (no attack, no mitigation, ×1 crit/proc damage,
+//! 1000 hull, 80% shield mitigation) so callers only name what they care about.
+
+use std::fmt;
+
+use crate::combat::rng::RngMode;
+use crate::combat::types::{
+    ChargedAttack, Combatant, InitiativeMode, SimulationConfig, TraceFilter, TraceMode,
+    WeaponStats,
+};
+
+/// Hull used when [CombatantBuilder::hull_health] is not called.
+pub const DEFAULT_HULL_HEALTH: f64 = 1000.0;
+/// Shield share of incoming damage when [CombatantBuilder::shield_mitigation] is not called.
+pub const DEFAULT_SHIELD_MITIGATION: f64 = 0.8;
+
+/// Why [CombatantBuilder::build] rejected a stat.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombatantError {
+    /// Hull or shield health is negative.
+    NegativeHealth { field: &'static str, value: f64 },
+    /// A chance or fraction lies outside [0, 1].
+    OutOfUnitRange { field: &'static str, value: f64 },
+    /// A stat is NaN or infinite.
+    NotFinite { field: &'static str },
+}
+
+impl fmt::Display for CombatantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NegativeHealth { field, value } => {
+                write!(f, "{field} must be non-negative, got {value}")
+            }
+            Self::OutOfUnitRange { field, value } => {
+                write!(f, "{field} must be between 0 and 1, got {value}")
+            }
+            Self::NotFinite { field } => write!(f, "{field} must be a finite number"),
+        }
+    }
+}
+
+impl std::error::Error for CombatantError {}
+
+/// Step-by-step [Combatant] construction. Start with [Combatant::builder].
+#[derive(Debug, Clone)]
+pub struct CombatantBuilder {
+    combatant: Combatant,
+}
+
+impl CombatantBuilder {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            combatant: Combatant {
+                id: id.into(),
+                attack: 0.0,
+                mitigation: 0.0,
+                pierce: 0.0,
+                crit_chance: 0.0,
+                crit_multiplier: 1.0,
+                proc_chance: 0.0,
+                proc_multiplier: 1.0,
+                end_of_round_damage: 0.0,
+                hull_health: DEFAULT_HULL_HEALTH,
+                shield_health: 0.0,
+                shield_mitigation: DEFAULT_SHIELD_MITIGATION,
+                apex_barrier: 0.0,
+                apex_shred: 0.0,
+                isolytic_damage: 0.0,
+                isolytic_defense: 0.0,
+                weapons: Vec::new(),
+                tags: Vec::new(),
+                charged_attack: None,
+            },
+        }
+    }
+
+    /// Scalar attack, used when no weapons are set.
+    pub fn attack(mut self, value: f64) -> Self {
+        self.combatant.attack = value;
+        self
+    }
+
+    pub fn mitigation(mut self, value: f64) -> Self {
+        self.combatant.mitigation = value;
+        self
+    }
+
+    pub fn pierce(mut self, value: f64) -> Self {
+        self.combatant.pierce = value;
+        self
+    }
+
+    /// Critical hit chance (0–1).
+    pub fn crit_chance(mut self, value: f64) -> Self {
+        self.combatant.crit_chance = value;
+        self
+    }
+
+    pub fn crit_multiplier(mut self, value: f64) -> Self {
+        self.combatant.crit_multiplier = value;
+        self
+    }
+
+    /// Extra-attack chance (0–1).
+    pub fn proc_chance(mut self, value: f64) -> Self {
+        self.combatant.proc_chance = value;
+        self
+    }
+
+    pub fn proc_multiplier(mut self, value: f64) -> Self {
+        self.combatant.proc_multiplier = value;
+        self
+    }
+
+    pub fn end_of_round_damage(mut self, value: f64) -> Self {
+        self.combatant.end_of_round_damage = value;
+        self
+    }
+
+    pub fn hull_health(mut self, value: f64) -> Self {
+        self.combatant.hull_health = value;
+        self
+    }
+
+    pub fn shield_health(mut self, value: f64) -> Self {
+        self.combatant.shield_health = value;
+        self
+    }
+
+    /// Share of incoming damage taken by shields while they last (0–1).
+    pub fn shield_mitigation(mut self, value: f64) -> Self {
+        self.combatant.shield_mitigation = value;
+        self
+    }
+
+    pub fn apex_barrier(mut self, value: f64) -> Self {
+        self.combatant.apex_barrier = value;
+        self
+    }
+
+    pub fn apex_shred(mut self, value: f64) -> Self {
+        self.combatant.apex_shred = value;
+        self
+    }
+
+    pub fn isolytic_damage(mut self, value: f64) -> Self {
+        self.combatant.isolytic_damage = value;
+        self
+    }
+
+    pub fn isolytic_defense(mut self, value: f64) -> Self {
+        self.combatant.isolytic_defense = value;
+        self
+    }
+
+    /// Replace the weapon list; each weapon is one sub-round per round.
+    pub fn weapons(mut self, weapons: Vec<WeaponStats>) -> Self {
+        self.combatant.weapons = weapons;
+        self
+    }
+
+    /// Add one weapon with the given attack and base shots (None = 1).
+    pub fn weapon(mut self, attack: f64, shots: Option<u32>) -> Self {
+        self.combatant.weapons.push(WeaponStats { attack, shots });
+        self
+    }
+
+    /// Target tags that "vs tag" crew effects match when this combatant defends.
+    pub fn tags(mut self, tags: Vec<String>) -> Self {
+        self.combatant.tags = tags;
+        self
+    }
+
+    /// Fire a heavy attack of `damage` every `every_rounds` rounds when defending.
+    pub fn charged_attack(mut self, every_rounds: u32, damage: f64) -> Self {
+        self.combatant.charged_attack = Some(ChargedAttack { every_rounds, damage });
+        self
+    }
+
+    /// Check the stats with [Combatant::validate], then return the combatant.
+    pub fn build(self) -> Result<Combatant, CombatantError> {
+        self.combatant.validate()?;
+        Ok(self.combatant)
+    }
+
+    /// Return the combatant without range checks. For stats that are already engine-derived
+    /// (profile and crew buffs can legitimately push a chance past 1 before the engine clamps it).
+    pub fn build_unchecked(self) -> Combatant {
+        self.combatant
+    }
+}
+
+impl Combatant {
+    /// Start a [CombatantBuilder] with neutral stats.
+    pub fn builder(id: impl Into<String>) -> CombatantBuilder {
+        CombatantBuilder::new(id)
+    }
+
+    /// Check that stats are finite, health is non-negative and chances (plus the shield split)
+    /// lie in [0, 1]. [CombatantBuilder::build] runs this; call it on combatants that arrive
+    /// some other way (e.g. deserialized).
+    pub fn validate(&self) -> Result<(), CombatantError> {
+        let stats = [
+            ("attack", self.attack),
+            ("mitigation", self.mitigation),
+            ("pierce", self.pierce),
+            ("crit_chance", self.crit_chance),
+            ("crit_multiplier", self.crit_multiplier),
+            ("proc_chance", self.proc_chance),
+            ("proc_multiplier", self.proc_multiplier),
+            ("end_of_round_damage", self.end_of_round_damage),
+            ("hull_health", self.hull_health),
+            ("shield_health", self.shield_health),
+            ("shield_mitigation", self.shield_mitigation),
+            ("apex_barrier", self.apex_barrier),
+            ("apex_shred", self.apex_shred),
+            ("isolytic_damage", self.isolytic_damage),
+            ("isolytic_defense", self.isolytic_defense),
+            ("charged_attack.damage", self.charged_attack.map_or(0.0, |a| a.damage)),
+        ];
+        if let Some(&(field, _)) = stats.iter().find(|(_, v)| !v.is_finite()) {
+            return Err(CombatantError::NotFinite { field });
+        }
+        for (field, value) in [
+            ("hull_health", self.hull_health),
+            ("shield_health", self.shield_health),
+        ] {
+            if value < 0.0 {
+                return Err(CombatantError::NegativeHealth { field, value });
+            }
+        }
+        for (field, value) in [
+            ("crit_chance", self.crit_chance),
+            ("proc_chance", self.proc_chance),
+            ("shield_mitigation", self.shield_mitigation),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(CombatantError::OutOfUnitRange { field, value });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Step-by-step [SimulationConfig] construction. Start with [SimulationConfig::builder].
+#[derive(Debug, Clone, Default)]
+pub struct SimulationConfigBuilder {
+    config: SimulationConfig,
+}
+
+impl SimulationConfigBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Round limit (default 3).
+    pub fn rounds(mut self, rounds: u32) -> Self {
+        self.config.rounds = rounds;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = seed;
+        self
+    }
+
+    pub fn trace_mode(mut self, trace_mode: TraceMode) -> Self {
+        self.config.trace_mode = trace_mode;
+        self
+    }
+
+    /// Shorthand for [TraceMode::Events] when `enabled`, [TraceMode::Off] otherwise.
+    pub fn trace(self, enabled: bool) -> Self {
+        self.trace_mode(if enabled {
+            TraceMode::Events
+        } else {
+            TraceMode::Off
+        })
+    }
+
+    /// Events a traced fight keeps (default: all).
+    pub fn trace_filter(mut self, filter: TraceFilter) -> Self {
+        self.config.trace_filter = filter;
+        self
+    }
+
+    pub fn rng_mode(mut self, rng_mode: RngMode) -> Self {
+        self.config.rng_mode = rng_mode;
+        self
+    }
+
+    /// Rounds the crewed ship holds fire at the start of the fight.
+    pub fn attacker_delay_rounds(mut self, rounds: u32) -> Self {
+        self.config.attacker_delay_rounds = rounds;
+        self
+    }
+
+    /// Who shoots first in each sub-round (default: simultaneous).
+    pub fn initiative(mut self, initiative: InitiativeMode) -> Self {
+        self.config.initiative = initiative;
+        self
+    }
+
+    pub fn build(self) -> SimulationConfig {
+        self.config
+    }
+}
+
+impl SimulationConfig {
+    /// Start a [SimulationConfigBuilder] from [SimulationConfig::default].
+    pub fn builder() -> SimulationConfigBuilder {
+        SimulationConfigBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_the_neutral_combatant() {
+        let c = Combatant::builder("a").build().unwrap();
+        assert_eq!(c.id, "a");
+        assert_eq!(c.crit_multiplier, 1.0);
+        assert_eq!(c.proc_multiplier, 1.0);
+        assert_eq!(c.hull_health, DEFAULT_HULL_HEALTH);
+        assert_eq!(c.shield_mitigation, DEFAULT_SHIELD_MITIGATION);
+        assert_eq!(c.weapon_count(), 1);
+    }
+
+    #[test]
+    fn build_rejects_negative_health_and_out_of_range_chances() {
+        assert_eq!(
+            Combatant::builder("a").hull_health(-1.0).build(),
+            Err(CombatantError::NegativeHealth {
+                field: "hull_health",
+                value: -1.0
+            })
+        );
+        assert_eq!(
+            Combatant::builder("a").crit_chance(1.5).build(),
+            Err(CombatantError::OutOfUnitRange {
+                field: "crit_chance",
+                value: 1.5
+            })
+        );
+        assert_eq!(
+            Combatant::builder("a").attack(f64::NAN).build(),
+            Err(CombatantError::NotFinite { field: "attack" })
+        );
+        assert_eq!(
+            Combatant::builder("a")
+                .proc_chance(2.0)
+                .build_unchecked()
+                .proc_chance,
+            2.0
+        );
+    }
+
+    #[test]
+    fn config_builder_starts_from_default() {
+        let config = SimulationConfig::builder().seed(42).trace(true).build();
+        assert_eq!(config.rounds, SimulationConfig::default().rounds);
+        assert_eq!(config.seed, 42);
+        assert_eq!(config.trace_mode, TraceMode::Events);
+    }
+}
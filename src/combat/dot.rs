@@ -0,0 +1,214 @@
+//! Damage-over-time channels ticked at round end.
+//!
+//! Each channel keeps its own state and mitigation path so traces can be reconciled against the
+//! game's per-source numbers. Every tick is a fraction of the defender's max hull, scaled by the
+//! round-end apex factor:
+//!
+//! | Channel | Re-application | Mitigation | Pool |
+//! |---------|----------------|------------|------|
+//! | burning | refreshes (longest duration, highest %) | apex | hull |
+//! | plasma | stacks (each application ticks on its own) | apex | shields first, overflow to hull |
+//! | isolytic cascade | refreshes | ÷ (1 + isolytic defense), then apex | hull |
+
+use serde::{Deserialize, Serialize};
+
+use super::damage::apply_shield_hull_split;
+use super::types::BURNING_HULL_DAMAGE_PER_ROUND;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DotChannel {
+    Burning,
+    Plasma,
+    IsolyticCascade,
+}
+
+impl DotChannel {
+    /// Tick order at round end.
+    pub const ALL: [DotChannel; 3] = [
+        DotChannel::Burning,
+        DotChannel::Plasma,
+        DotChannel::IsolyticCascade,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DotChannel::Burning => "burning",
+            DotChannel::Plasma => "plasma",
+            DotChannel::IsolyticCascade => "isolytic_cascade",
+        }
+    }
+
+    /// Parse a channel name as written in ability data. `isolytic_cascade_dot` is the cascade
+    /// channel; plain `isolytic_cascade` elsewhere means the per-shot cascade damage bonus.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "burning" => Some(DotChannel::Burning),
+            "plasma" => Some(DotChannel::Plasma),
+            "isolytic_cascade" | "isolytic_cascade_dot" => Some(DotChannel::IsolyticCascade),
+            _ => None,
+        }
+    }
+
+    /// Per-round fraction of max hull used when an ability does not give one.
+    pub fn default_hull_pct(self) -> f64 {
+        BURNING_HULL_DAMAGE_PER_ROUND
+    }
+
+    fn stacks(self) -> bool {
+        matches!(self, DotChannel::Plasma)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DotStack {
+    hull_pct: f64,
+    rounds_remaining: u32,
+}
+
+/// Active damage-over-time applications on the defender.
+#[derive(Debug, Clone, Default)]
+pub struct DotTracker {
+    burning: Option<DotStack>,
+    plasma: Vec<DotStack>,
+    isolytic_cascade: Option<DotStack>,
+}
+
+impl DotTracker {
+    /// Drop every active application so the tracker can be reused for another fight.
+    pub fn clear(&mut self) {
+        self.burning = None;
+        self.plasma.clear();
+        self.isolytic_cascade = None;
+    }
+
+    /// Apply one triggered application. Refreshing channels keep the longest duration and
+    /// the highest percentage; stacking channels add an independent stack.
+    pub fn apply(&mut self, channel: DotChannel, hull_pct: f64, duration_rounds: u32) {
+        let stack = DotStack {
+            hull_pct: hull_pct.max(0.0),
+            rounds_remaining: duration_rounds.max(1),
+        };
+        if channel.stacks() {
+            self.plasma.push(stack);
+            return;
+        }
+        let slot = match channel {
+            DotChannel::Burning => &mut self.burning,
+            _ => &mut self.isolytic_cascade,
+        };
+        *slot = Some(match *slot {
+            Some(existing) => DotStack {
+                hull_pct: existing.hull_pct.max(stack.hull_pct),
+                rounds_remaining: existing.rounds_remaining.max(stack.rounds_remaining),
+            },
+            None => stack,
+        });
+    }
+
+    /// Summed hull fraction and number of live stacks for this round's tick.
+    pub fn tick_fraction(&self, channel: DotChannel) -> (f64, usize) {
+        match channel {
+            DotChannel::Burning => single_fraction(self.burning),
+            DotChannel::Plasma => (
+                self.plasma.iter().map(|s| s.hull_pct).sum(),
+                self.plasma.len(),
+            ),
+            DotChannel::IsolyticCascade => single_fraction(self.isolytic_cascade),
+        }
+    }
+
+    /// Count one round off every application and drop the expired ones.
+    pub fn advance(&mut self) {
+        for slot in [&mut self.burning, &mut self.isolytic_cascade] {
+            if let Some(stack) = slot {
+                stack.rounds_remaining -= 1;
+                if stack.rounds_remaining == 0 {
+                    *slot = None;
+                }
+            }
+        }
+        for stack in &mut self.plasma {
+            stack.rounds_remaining -= 1;
+        }
+        self.plasma.retain(|s| s.rounds_remaining > 0);
+    }
+}
+
+fn single_fraction(stack: Option<DotStack>) -> (f64, usize) {
+    stack.map_or((0.0, 0), |s| (s.hull_pct, 1))
+}
+
+/// Damage of one channel's tick, before and after its mitigation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DotTick {
+    pub channel: DotChannel,
+    pub stacks: usize,
+    /// hull_pct × max hull, before isolytic defense and apex.
+    pub raw_damage: f64,
+    pub shield_damage: f64,
+    pub hull_damage: f64,
+}
+
+/// Resolve one channel's tick against the defender.
+pub fn resolve_dot_tick(
+    channel: DotChannel,
+    hull_fraction: f64,
+    stacks: usize,
+    defender_max_hull: f64,
+    isolytic_defense: f64,
+    apex_factor: f64,
+    defender_shield_remaining: f64,
+) -> DotTick {
+    let raw_damage = defender_max_hull.max(0.0) * hull_fraction;
+    let mitigated = match channel {
+        DotChannel::IsolyticCascade => raw_damage / (1.0 + isolytic_defense.max(0.0)),
+        _ => raw_damage,
+    } * apex_factor;
+    let (shield_damage, hull_damage) = match channel {
+        DotChannel::Plasma => apply_shield_hull_split(mitigated, 1.0, defender_shield_remaining),
+        _ => (0.0, mitigated),
+    };
+    DotTick {
+        channel,
+        stacks,
+        raw_damage,
+        shield_damage,
+        hull_damage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plasma_stacks_while_burning_refreshes() {
+        let mut dots = DotTracker::default();
+        dots.apply(DotChannel::Burning, 0.01, 2);
+        dots.apply(DotChannel::Burning, 0.01, 3);
+        dots.apply(DotChannel::Plasma, 0.02, 1);
+        dots.apply(DotChannel::Plasma, 0.02, 2);
+        assert_eq!(dots.tick_fraction(DotChannel::Burning), (0.01, 1));
+        assert_eq!(dots.tick_fraction(DotChannel::Plasma), (0.04, 2));
+
+        dots.advance();
+        assert_eq!(dots.tick_fraction(DotChannel::Plasma), (0.02, 1));
+        dots.advance();
+        dots.advance();
+        assert_eq!(dots.tick_fraction(DotChannel::Burning), (0.0, 0));
+        assert_eq!(dots.tick_fraction(DotChannel::Plasma), (0.0, 0));
+    }
+
+    #[test]
+    fn each_channel_uses_its_own_mitigation_and_pool() {
+        let plasma = resolve_dot_tick(DotChannel::Plasma, 0.02, 1, 1000.0, 1.0, 0.5, 4.0);
+        assert_eq!((plasma.shield_damage, plasma.hull_damage), (4.0, 6.0));
+
+        let cascade = resolve_dot_tick(DotChannel::IsolyticCascade, 0.02, 1, 1000.0, 1.0, 0.5, 4.0);
+        assert_eq!((cascade.shield_damage, cascade.hull_damage), (0.0, 5.0));
+
+        let burning = resolve_dot_tick(DotChannel::Burning, 0.01, 1, 1000.0, 1.0, 1.0, 4.0);
+        assert_eq!((burning.raw_damage, burning.hull_damage), (10.0, 10.0));
+    }
+}
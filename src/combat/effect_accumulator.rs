@@ -2,11 +2,14 @@
 
 use serde_json::{Map, Value};
 
-use crate::combat::abilities::{AbilityEffect, ActiveAbilityEffect, TimingWindow};
+use crate::combat::abilities::{
+    AbilityEffect, ActivationLimits, ActiveAbilityEffect, EffectSelection, TimingWindow,
+};
 use crate::combat::events::round_f64;
 use crate::combat::stacking::{StackContribution, StatStacking};
 use crate::combat::types::{
-    Combatant, CombatEvent, EventSource, TraceCollector, ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+    Combatant, EventSource, TraceCollector, TraceDetail, ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+    BOOSTED_EFFECTIVENESS_MULTIPLIER,
 };
 
 #[derive(Debug, Clone)]
@@ -252,7 +255,7 @@ impl EffectAccumulator {
     pub(crate) fn add_effects(
         &mut self,
         timing: TimingWindow,
-        effects: &[ActiveAbilityEffect],
+        effects: &EffectSelection,
         base_attack: f64,
         assimilated_active: bool,
         round_index: u32,
@@ -260,7 +263,7 @@ impl EffectAccumulator {
         for effect in effects {
             self.add_effect(
                 timing,
-                scale_effect(effect.effect, assimilated_active),
+                scale_effect(effect, assimilated_active),
                 base_attack,
                 round_index,
             );
@@ -287,6 +290,7 @@ impl EffectAccumulator {
                 AbilityEffect::Assimilated { .. } => {}
                 AbilityEffect::HullBreach { .. } => {}
                 AbilityEffect::Burning { .. } => {}
+                AbilityEffect::DamageOverTime { .. } => {}
                 AbilityEffect::ShotsBonus { .. } => {}
                 AbilityEffect::ShieldRegen(_) => {}
                 AbilityEffect::HullRegen(_) => {}
@@ -346,6 +350,7 @@ impl EffectAccumulator {
                 AbilityEffect::Assimilated { .. } => {}
                 AbilityEffect::HullBreach { .. } => {}
                 AbilityEffect::Burning { .. } => {}
+                AbilityEffect::DamageOverTime { .. } => {}
                 AbilityEffect::ShotsBonus { .. } => {}
                 AbilityEffect::ShieldRegen(_) => {}
                 AbilityEffect::HullRegen(_) => {}
@@ -405,6 +410,7 @@ impl EffectAccumulator {
                 AbilityEffect::Assimilated { .. } => {}
                 AbilityEffect::HullBreach { .. } => {}
                 AbilityEffect::Burning { .. } => {}
+                AbilityEffect::DamageOverTime { .. } => {}
                 AbilityEffect::ShotsBonus { .. } => {}
                 AbilityEffect::ShieldRegen(_) => {}
                 AbilityEffect::HullRegen(_) => {}
@@ -450,6 +456,7 @@ impl EffectAccumulator {
                 AbilityEffect::Assimilated { .. } => {}
                 AbilityEffect::HullBreach { .. } => {}
                 AbilityEffect::Burning { .. } => {}
+                AbilityEffect::DamageOverTime { .. } => {}
                 AbilityEffect::ShotsBonus { .. } => {}
                 AbilityEffect::ShieldRegen(v) => {
                     self.stacks.add(StackContribution::flat(EffectStatKey::ShieldRegen, v));
@@ -517,6 +524,7 @@ impl EffectAccumulator {
                 AbilityEffect::Assimilated { .. } => {}
                 AbilityEffect::HullBreach { .. } => {}
                 AbilityEffect::Burning { .. } => {}
+                AbilityEffect::DamageOverTime { .. } => {}
                 AbilityEffect::ShotsBonus { .. } => {}
                 AbilityEffect::ShieldRegen(v) => {
                     self.stacks.add(StackContribution::flat(EffectStatKey::ShieldRegen, v));
@@ -573,13 +581,13 @@ impl EffectAccumulator {
 }
 
 pub(crate) fn sum_on_kill_hull_regen(
-    effects: &[ActiveAbilityEffect],
+    effects: &EffectSelection,
     assimilated_active: bool,
 ) -> f64 {
     effects
         .iter()
         .filter_map(|e| {
-            if let AbilityEffect::OnKillHullRegen(v) = scale_effect(e.effect, assimilated_active) {
+            if let AbilityEffect::OnKillHullRegen(v) = scale_effect(e, assimilated_active) {
                 Some(v)
             } else {
                 None
@@ -592,27 +600,23 @@ pub(crate) fn record_ability_activations(
     trace: &mut TraceCollector,
     round_index: u32,
     phase: &str,
+    weapon_index: Option<u32>,
     attacker: &Combatant,
-    effects: &[ActiveAbilityEffect],
+    effects: &EffectSelection,
     assimilated_active: bool,
 ) {
-    let effectiveness_multiplier = if assimilated_active {
-        ASSIMILATED_EFFECTIVENESS_MULTIPLIER
-    } else {
-        1.0
-    };
-
+    if !trace.is_enabled() {
+        return;
+    }
     for effect in effects {
-        trace.record_if(|| CombatEvent {
-            event_type: "ability_activation".to_string(),
-            round_index,
-            phase: phase.to_string(),
+        let effectiveness_multiplier = effectiveness_multiplier(effect.boosted, assimilated_active);
+        trace.record_if("ability_activation", phase, round_index, || TraceDetail {
             source: EventSource {
                 officer_id: Some(attacker.id.clone()),
                 ship_ability_id: Some(effect.ability_name.clone()),
                 ..EventSource::default()
             },
-            weapon_index: None,
+            weapon_index,
             values: Map::from_iter([
                 ("boosted".to_string(), Value::Bool(effect.boosted)),
                 (
@@ -625,26 +629,92 @@ pub(crate) fn record_ability_activations(
     }
 }
 
-pub(crate) fn scale_effect(effect: AbilityEffect, assimilated_active: bool) -> AbilityEffect {
-    if !assimilated_active {
+/// Drop effects whose [ActivationLimits] refuse this activation, tracing each as
+/// `ability_suppressed`. Call after condition filtering so only would-be activations count.
+pub(crate) fn apply_activation_limits<'a>(
+    trace: &mut TraceCollector,
+    round_index: u32,
+    phase: &str,
+    weapon_index: Option<u32>,
+    attacker: &Combatant,
+    limits: &mut ActivationLimits,
+    mut effects: EffectSelection<'a>,
+) -> EffectSelection<'a> {
+    effects.retain(|effect| match limits.try_activate(effect, round_index) {
+        Ok(()) => true,
+        Err(reason) => {
+            trace.record_if("ability_suppressed", phase, round_index, || TraceDetail {
+                source: EventSource {
+                    officer_id: Some(attacker.id.clone()),
+                    ship_ability_id: Some(effect.ability_name.clone()),
+                    ..EventSource::default()
+                },
+                weapon_index,
+                values: Map::from_iter([
+                    ("reason".to_string(), Value::from(reason.as_str())),
+                    (
+                        "activations".to_string(),
+                        Value::from(limits.activations(effect.seat_index)),
+                    ),
+                    (
+                        "max_activations".to_string(),
+                        effect.max_activations.map_or(Value::Null, Value::from),
+                    ),
+                    (
+                        "cooldown_rounds".to_string(),
+                        Value::from(effect.cooldown_rounds),
+                    ),
+                ]),
+            });
+            false
+        }
+    });
+    effects
+}
+
+/// Combined effectiveness of one activation: boost and assimilation multiply.
+pub(crate) fn effectiveness_multiplier(boosted: bool, assimilated_active: bool) -> f64 {
+    let boost = if boosted {
+        BOOSTED_EFFECTIVENESS_MULTIPLIER
+    } else {
+        1.0
+    };
+    let assimilated = if assimilated_active {
+        ASSIMILATED_EFFECTIVENESS_MULTIPLIER
+    } else {
+        1.0
+    };
+    boost * assimilated
+}
+
+/// The effect as it applies this activation, scaled by [effectiveness_multiplier].
+pub(crate) fn scale_effect(effect: &ActiveAbilityEffect, assimilated_active: bool) -> AbilityEffect {
+    scale_effect_by(
+        effect.effect,
+        effectiveness_multiplier(effect.boosted, assimilated_active),
+    )
+}
+
+fn scale_effect_by(effect: AbilityEffect, multiplier: f64) -> AbilityEffect {
+    if multiplier == 1.0 {
         return effect;
     }
 
     match effect {
         AbilityEffect::AttackMultiplier(modifier) => {
-            AbilityEffect::AttackMultiplier(modifier * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::AttackMultiplier(modifier * multiplier)
         }
         AbilityEffect::PierceBonus(value) => {
-            AbilityEffect::PierceBonus(value * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::PierceBonus(value * multiplier)
         }
         AbilityEffect::Morale(chance) => {
-            AbilityEffect::Morale(chance * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::Morale(chance * multiplier)
         }
         AbilityEffect::Assimilated {
             chance,
             duration_rounds,
         } => AbilityEffect::Assimilated {
-            chance: chance * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+            chance: chance * multiplier,
             duration_rounds,
         },
         AbilityEffect::HullBreach {
@@ -652,7 +722,7 @@ pub(crate) fn scale_effect(effect: AbilityEffect, assimilated_active: bool) -> A
             duration_rounds,
             requires_critical,
         } => AbilityEffect::HullBreach {
-            chance: chance * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+            chance: chance * multiplier,
             duration_rounds,
             requires_critical,
         },
@@ -660,42 +730,53 @@ pub(crate) fn scale_effect(effect: AbilityEffect, assimilated_active: bool) -> A
             chance,
             duration_rounds,
         } => AbilityEffect::Burning {
-            chance: chance * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+            chance: chance * multiplier,
+            duration_rounds,
+        },
+        AbilityEffect::DamageOverTime {
+            channel,
+            chance,
+            hull_pct,
+            duration_rounds,
+        } => AbilityEffect::DamageOverTime {
+            channel,
+            chance: chance * multiplier,
+            hull_pct,
             duration_rounds,
         },
         AbilityEffect::ApexShredBonus(v) => {
-            AbilityEffect::ApexShredBonus(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::ApexShredBonus(v * multiplier)
         }
         AbilityEffect::ApexBarrierBonus(v) => {
-            AbilityEffect::ApexBarrierBonus(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::ApexBarrierBonus(v * multiplier)
         }
         AbilityEffect::ShieldRegen(v) => {
-            AbilityEffect::ShieldRegen(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::ShieldRegen(v * multiplier)
         }
         AbilityEffect::HullRegen(v) => {
-            AbilityEffect::HullRegen(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::HullRegen(v * multiplier)
         }
         AbilityEffect::IsolyticDamageBonus(v) => {
-            AbilityEffect::IsolyticDamageBonus(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::IsolyticDamageBonus(v * multiplier)
         }
         AbilityEffect::IsolyticDefenseBonus(v) => {
-            AbilityEffect::IsolyticDefenseBonus(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::IsolyticDefenseBonus(v * multiplier)
         }
         AbilityEffect::IsolyticCascadeDamageBonus(v) => {
-            AbilityEffect::IsolyticCascadeDamageBonus(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::IsolyticCascadeDamageBonus(v * multiplier)
         }
         AbilityEffect::ShieldMitigationBonus(v) => {
-            AbilityEffect::ShieldMitigationBonus(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::ShieldMitigationBonus(v * multiplier)
         }
         AbilityEffect::OnKillHullRegen(v) => {
-            AbilityEffect::OnKillHullRegen(v * ASSIMILATED_EFFECTIVENESS_MULTIPLIER)
+            AbilityEffect::OnKillHullRegen(v * multiplier)
         }
         AbilityEffect::DecayingAttackMultiplier {
             initial,
             decay_per_round,
             floor,
         } => AbilityEffect::DecayingAttackMultiplier {
-            initial: 1.0 + (initial - 1.0) * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+            initial: 1.0 + (initial - 1.0) * multiplier,
             decay_per_round,
             floor,
         },
@@ -704,7 +785,7 @@ pub(crate) fn scale_effect(effect: AbilityEffect, assimilated_active: bool) -> A
             growth_per_round,
             ceiling,
         } => AbilityEffect::AccumulatingAttackMultiplier {
-            initial: 1.0 + (initial - 1.0) * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+            initial: 1.0 + (initial - 1.0) * multiplier,
             growth_per_round,
             ceiling,
         },
@@ -713,8 +794,8 @@ pub(crate) fn scale_effect(effect: AbilityEffect, assimilated_active: bool) -> A
             bonus_pct,
             duration_rounds,
         } => AbilityEffect::ShotsBonus {
-            chance: chance * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
-            bonus_pct: bonus_pct * ASSIMILATED_EFFECTIVENESS_MULTIPLIER,
+            chance: chance * multiplier,
+            bonus_pct: bonus_pct * multiplier,
             duration_rounds,
         },
     }
@@ -1,14 +1,16 @@
 //! Combat loop orchestration. Types, mitigation, effects, events, and damage helpers live in sibling modules.
 
-pub use crate::combat::events::serialize_events_json;
+pub use crate::combat::events::{serialize_events_json, serialize_events_msgpack};
 pub use crate::combat::mitigation::{
     apply_morale_primary_piercing, component_mitigation, isolytic_damage, mitigation,
     mitigation_for_hostile, mitigation_with_morale, mitigation_with_mystery,
     pierce_damage_through_bonus, MITIGATION_CEILING, MITIGATION_FLOOR, PIERCE_CAP,
 };
 pub use crate::combat::types::{
-    round_half_even, AttackerStats, CombatEvent, Combatant, DefenderStats, EventSource, FightResult,
-    ShipType, SimulationConfig, SimulationResult, TraceCollector, TraceMode, WeaponStats,
+    round_half_even, AttackerCondition, AttackerStartState, AttackerStats, ChargedAttack,
+    CombatEvent, Combatant, DamageBreakdown, DefenderStats, EventSource, FightResult,
+    InitiativeMode, ShipType, SimulationConfig, SimulationResult, TraceFilter, TraceFilterError, TraceMode,
+    WeaponStats, TRACE_EVENT_TYPES, TRACE_PHASES,
     BATTLESHIP_COEFFICIENTS, EPSILON, EXPLORER_COEFFICIENTS, INTERCEPTOR_COEFFICIENTS,
     MAX_COMBAT_ROUNDS, MORALE_PRIMARY_PIERCING_BONUS, SURVEY_COEFFICIENTS,
 };
@@ -16,18 +18,24 @@ pub use crate::combat::types::{
 use serde_json::{Map, Value};
 
 use crate::combat::abilities::{
-    active_effects_for_timing, apply_duplicate_officer_policy, filter_effects_by_condition,
-    AbilityEffect, CombatContext, CrewConfiguration, TimingWindow,
+    select_effects, AbilityEffect, ActivationLimits, CombatContext, CrewConfiguration,
+    CrewEffects, TimingWindow,
 };
 use crate::combat::damage::{
     apply_shield_hull_split, compute_apex_damage_factor, compute_crit_multiplier,
     compute_damage_through_factor, compute_isolytic_taken,
 };
 use crate::combat::effect_accumulator::{
-    record_ability_activations, scale_effect, sum_on_kill_hull_regen, EffectAccumulator,
+    apply_activation_limits, record_ability_activations, scale_effect, sum_on_kill_hull_regen,
+    EffectAccumulator,
 };
-use crate::combat::events::round_f64;
-use crate::combat::rng::Rng;
+use crate::combat::events::{
+    round_f64, AttackRoll, CounterAttackRoll, CounterDamageApplication, CritResolution,
+    DamageApplication, MitigationCalc, PierceCalc, TracePayload,
+};
+use crate::combat::rng::{RollChannel, RollSide, RollStreams};
+use crate::combat::types::{Shooter, TraceCollector, TraceDetail};
+use crate::combat::dot::{resolve_dot_tick, DotChannel, DotTracker};
 use crate::combat::types::BURNING_HULL_DAMAGE_PER_ROUND;
 
 pub fn simulate_combat(
@@ -36,46 +44,203 @@ pub fn simulate_combat(
     config: SimulationConfig,
     attacker_crew: &CrewConfiguration,
 ) -> SimulationResult {
-    let attacker_crew = apply_duplicate_officer_policy(attacker_crew);
-    let mut rng = Rng::new(config.seed);
-    let mut trace = TraceCollector::new(matches!(config.trace_mode, TraceMode::Events));
+    simulate_combat_from_state(
+        attacker,
+        defender,
+        config,
+        attacker_crew,
+        AttackerStartState::fresh(attacker),
+    )
+}
+
+/// Like [simulate_combat] but the crewed ship starts with `start` hull and shields instead of
+/// full health. Hull/shield percentages for conditions stay relative to the ship's maximums.
+pub fn simulate_combat_from_state(
+    attacker: &Combatant,
+    defender: &Combatant,
+    config: SimulationConfig,
+    attacker_crew: &CrewConfiguration,
+    start: AttackerStartState,
+) -> SimulationResult {
+    let effects = CrewEffects::new(attacker_crew);
+    simulate_combat_with_effects(attacker, defender, config, &effects, start)
+}
+
+/// Like [simulate_combat_from_state] with the crew's abilities already grouped by timing window,
+/// so repeated fights with one crew skip regrouping them. Results match
+/// [simulate_combat_from_state] with the crew `effects` was built from.
+pub fn simulate_combat_with_effects(
+    attacker: &Combatant,
+    defender: &Combatant,
+    config: SimulationConfig,
+    effects: &CrewEffects,
+    start: AttackerStartState,
+) -> SimulationResult {
+    simulate_combat_with_scratch(
+        attacker,
+        defender,
+        config,
+        effects,
+        start,
+        &mut CombatScratch::default(),
+    )
+}
+
+/// Buffers [simulate_combat_with_scratch] reuses between fights: per-window ability
+/// selections, activation counts, shots bonuses and damage-over-time stacks. Everything is
+/// reset at the start of each fight, so one scratch can serve any number of fights in a row.
+#[derive(Debug, Clone, Default)]
+pub struct CombatScratch {
+    selected: [Vec<usize>; TimingWindow::ALL.len()],
+    activation_limits: ActivationLimits,
+    shots_bonus_entries: Vec<(f64, u32)>,
+    dots: DotTracker,
+}
+
+/// Like [simulate_combat_with_effects] but the fight's working buffers come from `scratch`
+/// instead of being allocated, which keeps the Monte Carlo loop off the allocator. Results
+/// match [simulate_combat_with_effects].
+pub fn simulate_combat_with_scratch(
+    attacker: &Combatant,
+    defender: &Combatant,
+    config: SimulationConfig,
+    effects: &CrewEffects,
+    start: AttackerStartState,
+    scratch: &mut CombatScratch,
+) -> SimulationResult {
+    let trace = TraceCollector::new(matches!(config.trace_mode, TraceMode::Events))
+        .with_filter(config.trace_filter);
+    run_combat(attacker, defender, config, effects, start, scratch, trace)
+}
+
+/// Like [simulate_combat] with tracing on, but each event is passed to `on_event` as soon as it
+/// is recorded instead of being buffered; the returned `events` is empty. `config.trace_mode`
+/// is ignored (`config.trace_filter` still applies). Rolls are the same as a traced
+/// [simulate_combat] with the same config.
+pub fn simulate_combat_streaming(
+    attacker: &Combatant,
+    defender: &Combatant,
+    config: SimulationConfig,
+    attacker_crew: &CrewConfiguration,
+    on_event: &mut dyn FnMut(CombatEvent),
+) -> SimulationResult {
+    simulate_combat_streaming_from_state(
+        attacker,
+        defender,
+        config,
+        attacker_crew,
+        AttackerStartState::fresh(attacker),
+        on_event,
+    )
+}
+
+/// Like [simulate_combat_streaming] but the crewed ship starts with `start` hull and shields
+/// (see [simulate_combat_from_state]).
+pub fn simulate_combat_streaming_from_state(
+    attacker: &Combatant,
+    defender: &Combatant,
+    config: SimulationConfig,
+    attacker_crew: &CrewConfiguration,
+    start: AttackerStartState,
+    on_event: &mut dyn FnMut(CombatEvent),
+) -> SimulationResult {
+    let trace = TraceCollector::streaming(on_event).with_filter(config.trace_filter);
+    run_combat(
+        attacker,
+        defender,
+        config,
+        &CrewEffects::new(attacker_crew),
+        start,
+        &mut CombatScratch::default(),
+        trace,
+    )
+}
+
+fn run_combat(
+    attacker: &Combatant,
+    defender: &Combatant,
+    config: SimulationConfig,
+    effects: &CrewEffects,
+    start: AttackerStartState,
+    scratch: &mut CombatScratch,
+    mut trace: TraceCollector<'_>,
+) -> SimulationResult {
+    let CombatScratch {
+        selected,
+        activation_limits,
+        shots_bonus_entries,
+        dots,
+    } = scratch;
+    // Same order as TimingWindow::ALL.
+    let [
+        combat_begin_picked,
+        round_start_picked,
+        attack_phase_picked,
+        defense_phase_picked,
+        round_end_picked,
+        shield_break_picked,
+        kill_picked,
+        hull_breach_picked,
+        receive_damage_picked,
+        combat_end_picked,
+    ] = selected;
+    activation_limits.clear();
+    shots_bonus_entries.clear();
+    dots.clear();
+    // Counter mode draws each (round, side, roll channel) from its own stream so a roll added
+    // in one round, side or channel cannot shift unrelated rolls.
+    let mut rolls = RollStreams::new(config.seed, config.rng_mode);
     let mut total_hull_damage = 0.0;
     let mut total_shield_damage = 0.0;
+    let mut attacker_damage = DamageBreakdown::default();
+    let mut defender_damage = DamageBreakdown::default();
     let mut defender_shield_remaining = defender.shield_health.max(0.0);
-    let mut attacker_shield_remaining = attacker.shield_health.max(0.0);
-    let mut total_attacker_hull_damage = 0.0;
+    let mut attacker_shield_remaining = start.shield.clamp(0.0, attacker.shield_health.max(0.0));
+    let mut total_attacker_hull_damage = (attacker.hull_health - start.hull).max(0.0);
     let mut hull_breach_rounds_remaining = 0_u32;
-    let mut burning_rounds_remaining = 0_u32;
     let mut assimilated_rounds_remaining = 0_u32;
-    // Active shots bonuses: (bonus_pct, expires_round). B_shots(r) = sum of bonus where expires_round >= r.
-    let mut shots_bonus_entries: Vec<(f64, u32)> = Vec::new();
-    let combat_begin_effects = active_effects_for_timing(&attacker_crew, TimingWindow::CombatBegin);
+    // Active shots bonuses (`shots_bonus_entries`): (bonus_pct, expires_round).
+    // B_shots(r) = sum of bonus where expires_round >= r.
+    let combat_begin_effects = effects.for_timing(TimingWindow::CombatBegin);
     let combat_begin_ctx = CombatContext {
         round_index: 0,
         defender_hull_pct: 1.0,
         defender_shield_pct: 1.0,
-        attacker_hull_pct: 1.0,
-        attacker_shield_pct: 1.0,
+        attacker_hull_pct: 1.0
+            - (total_attacker_hull_damage / attacker.hull_health.max(0.0)).min(1.0),
+        attacker_shield_pct: if attacker.shield_health > 0.0 {
+            attacker_shield_remaining / attacker.shield_health
+        } else {
+            1.0
+        },
+        target_tags: &defender.tags,
     };
-    let combat_begin_filtered =
-        filter_effects_by_condition(&combat_begin_effects, &combat_begin_ctx);
-    let shield_break_effects = active_effects_for_timing(&attacker_crew, TimingWindow::ShieldBreak);
-    let kill_effects = active_effects_for_timing(&attacker_crew, TimingWindow::Kill);
-    let hull_breach_effects = active_effects_for_timing(&attacker_crew, TimingWindow::HullBreach);
-    let receive_damage_effects = active_effects_for_timing(&attacker_crew, TimingWindow::ReceiveDamage);
-    let combat_end_effects = active_effects_for_timing(&attacker_crew, TimingWindow::CombatEnd);
+    let combat_begin_filtered = apply_activation_limits(
+        &mut trace,
+        0,
+        "combat_begin",
+        None,
+        attacker,
+        activation_limits,
+        select_effects(combat_begin_effects, &combat_begin_ctx, combat_begin_picked),
+    );
+    let shield_break_effects = effects.for_timing(TimingWindow::ShieldBreak);
+    let kill_effects = effects.for_timing(TimingWindow::Kill);
+    let hull_breach_effects = effects.for_timing(TimingWindow::HullBreach);
+    let receive_damage_effects = effects.for_timing(TimingWindow::ReceiveDamage);
 
     // Pre-compute effects by timing once per combat; round loop only filters by condition.
-    let round_start_effects = active_effects_for_timing(&attacker_crew, TimingWindow::RoundStart);
-    let attack_phase_effects = active_effects_for_timing(&attacker_crew, TimingWindow::AttackPhase);
-    let defense_phase_effects = active_effects_for_timing(&attacker_crew, TimingWindow::DefensePhase);
-    let round_end_effects = active_effects_for_timing(&attacker_crew, TimingWindow::RoundEnd);
+    let round_start_effects = effects.for_timing(TimingWindow::RoundStart);
+    let attack_phase_effects = effects.for_timing(TimingWindow::AttackPhase);
+    let defense_phase_effects = effects.for_timing(TimingWindow::DefensePhase);
+    let round_end_effects = effects.for_timing(TimingWindow::RoundEnd);
 
     let combat_begin_assimilated = assimilated_rounds_remaining > 0;
     record_ability_activations(
         &mut trace,
         0,
         "combat_begin",
+        None,
         attacker,
         &combat_begin_filtered,
         combat_begin_assimilated,
@@ -87,6 +252,7 @@ pub fn simulate_combat(
 
     for round_index in 1..=rounds_to_simulate {
         rounds_completed = round_index;
+        rolls.start_round(round_index);
 
         let combat_ctx = CombatContext {
             round_index,
@@ -104,6 +270,7 @@ pub fn simulate_combat(
             } else {
                 1.0
             },
+            target_tags: &defender.tags,
         };
 
         let mut phase_effects = EffectAccumulator::default();
@@ -115,10 +282,7 @@ pub fn simulate_combat(
             round_index,
         );
 
-        trace.record_if(|| CombatEvent {
-            event_type: "round_start".to_string(),
-            round_index,
-            phase: "round".to_string(),
+        trace.record_if("round_start", "round", round_index, || TraceDetail {
             source: EventSource {
                 ship_ability_id: Some("baseline_round".to_string()),
                 ..EventSource::default()
@@ -134,12 +298,38 @@ pub fn simulate_combat(
             ]),
         });
 
+        // Delayed start (e.g. mining): the crewed ship does not fire yet, but its crew
+        // abilities still run and the opponent shoots normally.
+        let holding_fire = round_index <= config.attacker_delay_rounds;
+        if holding_fire {
+            trace.record_if("attacker_hold_fire", "round", round_index, || TraceDetail {
+                source: EventSource {
+                    officer_id: Some(attacker.id.clone()),
+                    ..EventSource::default()
+                },
+                weapon_index: None,
+                values: Map::from_iter([(
+                    "delay_rounds".to_string(),
+                    Value::from(config.attacker_delay_rounds),
+                )]),
+            });
+        }
+
         let round_start_assimilated = assimilated_rounds_remaining > 0;
-        let round_start_filtered = filter_effects_by_condition(&round_start_effects, &combat_ctx);
+        let round_start_filtered = apply_activation_limits(
+            &mut trace,
+            round_index,
+            "round_start",
+            None,
+            attacker,
+            activation_limits,
+            select_effects(round_start_effects, &combat_ctx, round_start_picked),
+        );
         record_ability_activations(
             &mut trace,
             round_index,
             "round_start",
+            None,
             attacker,
             &round_start_filtered,
             round_start_assimilated,
@@ -153,23 +343,20 @@ pub fn simulate_combat(
         );
 
         for effect in &round_start_filtered {
-            let effective_effect = scale_effect(effect.effect, round_start_assimilated);
+            let effective_effect = scale_effect(effect, round_start_assimilated);
 
             if let AbilityEffect::Assimilated {
                 chance,
                 duration_rounds,
             } = effective_effect
             {
-                let assimilated_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
+                let assimilated_roll = rolls.roll(RollChannel::Status);
                 let triggered = assimilated_roll < chance.clamp(0.0, 1.0);
                 if triggered {
                     assimilated_rounds_remaining =
                         assimilated_rounds_remaining.max(duration_rounds.max(1));
                 }
-                trace.record_if(|| CombatEvent {
-                    event_type: "assimilated_trigger".to_string(),
-                    round_index,
-                    phase: "round_start".to_string(),
+                trace.record_if("assimilated_trigger", "round_start", round_index, || TraceDetail {
                     source: EventSource {
                         officer_id: Some(attacker.id.clone()),
                         ship_ability_id: Some(effect.ability_name.clone()),
@@ -195,16 +382,13 @@ pub fn simulate_combat(
                     continue;
                 }
 
-                let hull_breach_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
+                let hull_breach_roll = rolls.roll(RollChannel::Status);
                 let triggered = hull_breach_roll < chance.clamp(0.0, 1.0);
                 if triggered {
                     hull_breach_rounds_remaining =
                         hull_breach_rounds_remaining.max(duration_rounds.max(1));
                 }
-                trace.record_if(|| CombatEvent {
-                    event_type: "hull_breach_trigger".to_string(),
-                    round_index,
-                    phase: "round_start".to_string(),
+                trace.record_if("hull_breach_trigger", "round_start", round_index, || TraceDetail {
                     source: EventSource {
                         officer_id: Some(attacker.id.clone()),
                         ship_ability_id: Some(effect.ability_name.clone()),
@@ -225,15 +409,12 @@ pub fn simulate_combat(
                 duration_rounds,
             } = effective_effect
             {
-                let burning_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
+                let burning_roll = rolls.roll(RollChannel::Status);
                 let triggered = burning_roll < chance.clamp(0.0, 1.0);
                 if triggered {
-                    burning_rounds_remaining = burning_rounds_remaining.max(duration_rounds.max(1));
+                    dots.apply(DotChannel::Burning, BURNING_HULL_DAMAGE_PER_ROUND, duration_rounds);
                 }
-                trace.record_if(|| CombatEvent {
-                    event_type: "burning_trigger".to_string(),
-                    round_index,
-                    phase: "round_start".to_string(),
+                trace.record_if("burning_trigger", "round_start", round_index, || TraceDetail {
                     source: EventSource {
                         officer_id: Some(attacker.id.clone()),
                         ship_ability_id: Some(effect.ability_name.clone()),
@@ -249,22 +430,49 @@ pub fn simulate_combat(
                 });
             }
 
+            if let AbilityEffect::DamageOverTime {
+                channel,
+                chance,
+                hull_pct,
+                duration_rounds,
+            } = effective_effect
+            {
+                let dot_roll = rolls.roll(RollChannel::Status);
+                let triggered = dot_roll < chance.clamp(0.0, 1.0);
+                if triggered {
+                    dots.apply(channel, hull_pct, duration_rounds);
+                }
+                trace.record_if("dot_trigger", "round_start", round_index, || TraceDetail {
+                    source: EventSource {
+                        officer_id: Some(attacker.id.clone()),
+                        ship_ability_id: Some(effect.ability_name.clone()),
+                        ..EventSource::default()
+                    },
+                    weapon_index: None,
+                    values: Map::from_iter([
+                        ("channel".to_string(), Value::from(channel.as_str())),
+                        ("roll".to_string(), Value::from(round_f64(dot_roll))),
+                        ("triggered".to_string(), Value::Bool(triggered)),
+                        ("chance".to_string(), Value::from(round_f64(chance))),
+                        ("hull_pct".to_string(), Value::from(round_f64(hull_pct))),
+                        ("duration_rounds".to_string(), Value::from(duration_rounds)),
+                    ]),
+                });
+            }
+
             if let AbilityEffect::ShotsBonus {
                 chance,
                 bonus_pct,
                 duration_rounds,
             } = effective_effect
             {
-                let shots_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
+                let shots_roll = rolls.roll(RollChannel::Status);
                 let triggered = shots_roll < chance.clamp(0.0, 1.0);
                 if triggered {
                     let duration = duration_rounds.max(1);
                     shots_bonus_entries.push((bonus_pct, round_index + duration));
                 }
-                trace.record_if(|| CombatEvent {
-                    event_type: "shots_bonus_trigger".to_string(),
-                    round_index,
-                    phase: "round_start".to_string(),
+                trace.record_if("shots_bonus_trigger", "round_start", round_index, || TraceDetail {
                     source: EventSource {
                         officer_id: Some(attacker.id.clone()),
                         ship_ability_id: Some(effect.ability_name.clone()),
@@ -287,7 +495,15 @@ pub fn simulate_combat(
         let b_shots: f64 = shots_bonus_entries.iter().map(|(b, _)| b).sum();
 
         let round_end_assimilated_early = assimilated_rounds_remaining > 0;
-        let round_end_filtered = filter_effects_by_condition(&round_end_effects, &combat_ctx);
+        let round_end_filtered = apply_activation_limits(
+            &mut trace,
+            round_index,
+            "round_end",
+            None,
+            attacker,
+            activation_limits,
+            select_effects(round_end_effects, &combat_ctx, round_end_picked),
+        );
         // RoundEnd stacking (apex, isolytic, shield mitigation, round-end damage multipliers, regen)
         // must not feed the same-round weapon sub-rounds. Apply RoundEnd only after all weapons
         // for this round (see merge into `phase_effects_round` below).
@@ -301,25 +517,22 @@ pub fn simulate_combat(
         // explicit policy once confirmed from game behavior.
         let morale_source = round_start_filtered.iter().find_map(|effect| {
             if let AbilityEffect::Morale(chance) =
-                scale_effect(effect.effect, round_start_assimilated)
+                scale_effect(effect, round_start_assimilated)
             {
-                Some((effect.ability_name.clone(), chance.clamp(0.0, 1.0)))
+                Some((effect.ability_name.as_str(), chance.clamp(0.0, 1.0)))
             } else {
                 None
             }
         });
         if let Some((morale_source, morale_chance)) = morale_source {
-            let morale_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
+            let morale_roll = rolls.roll(RollChannel::Morale);
             let morale_triggered = morale_roll < morale_chance;
             if morale_triggered {
                 effective_pierce *= 1.0 + MORALE_PRIMARY_PIERCING_BONUS;
             }
-            trace.record_if(|| CombatEvent {
-                event_type: "morale_activation".to_string(),
-                round_index,
-                phase: "round_start".to_string(),
+            trace.record_if("morale_activation", "round_start", round_index, || TraceDetail {
                 source: EventSource {
-                    ship_ability_id: Some(morale_source),
+                    ship_ability_id: Some(morale_source.to_string()),
                     ..EventSource::default()
                 },
                 weapon_index: None,
@@ -340,15 +553,30 @@ pub fn simulate_combat(
         }
 
         let attack_phase_assimilated = assimilated_rounds_remaining > 0;
-        let attack_phase_filtered =
-            filter_effects_by_condition(&attack_phase_effects, &combat_ctx);
-        let defense_phase_filtered =
-            filter_effects_by_condition(&defense_phase_effects, &combat_ctx);
+        let attack_phase_filtered = apply_activation_limits(
+            &mut trace,
+            round_index,
+            "attack",
+            None,
+            attacker,
+            activation_limits,
+            select_effects(attack_phase_effects, &combat_ctx, attack_phase_picked),
+        );
+        let defense_phase_filtered = apply_activation_limits(
+            &mut trace,
+            round_index,
+            "attack",
+            None,
+            attacker,
+            activation_limits,
+            select_effects(defense_phase_effects, &combat_ctx, defense_phase_picked),
+        );
 
         record_ability_activations(
             &mut trace,
             round_index,
             "attack",
+            None,
             attacker,
             &attack_phase_filtered,
             attack_phase_assimilated,
@@ -358,14 +586,16 @@ pub fn simulate_combat(
             &mut trace,
             round_index,
             "defense",
+            None,
             attacker,
             &defense_phase_filtered,
             defense_phase_assimilated,
         );
 
         let weapon_round_base = phase_effects_round.clone();
+        let mut destroyed_mid_round = None;
         let mut phase_effects = EffectAccumulator::default();
-        for weapon_index in 0..num_sub_rounds {
+        'sub_rounds: for weapon_index in 0..num_sub_rounds {
             phase_effects.clear();
             phase_effects.merge_from(&weapon_round_base);
             let weapon_base = attacker.weapon_attack(weapon_index).unwrap_or(attacker.attack);
@@ -384,450 +614,619 @@ pub fn simulate_combat(
                 round_index,
             );
 
-            let effective_apex_shred = (attacker.apex_shred + phase_effects.composed_apex_shred_bonus())
-                .max(0.0);
-            let effective_apex_barrier = (defender.apex_barrier + phase_effects.composed_apex_barrier_bonus())
-                .max(0.0);
-            let apex_damage_factor =
-                compute_apex_damage_factor(effective_apex_shred, effective_apex_barrier);
-
-            let base_shots = attacker.weapon_base_shots(weapon_index);
-            let effective_shots = round_half_even(base_shots as f64 * (1.0 + b_shots));
-            let shield_before_weapon = defender_shield_remaining;
-
-            let weapon_index_u = weapon_index as u32;
-            for _ in 0..effective_shots {
-            if let Some(attacker_weapon_attack) = attacker.weapon_attack(weapon_index) {
-            let effective_attack = attacker_weapon_attack * phase_effects.pre_attack_multiplier();
-
-            let roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-            trace.record_if(|| CombatEvent {
-                event_type: "attack_roll".to_string(),
-                round_index,
-                phase: "attack".to_string(),
-                source: EventSource {
-                    officer_id: Some(attacker.id.clone()),
-                    ..EventSource::default()
-                },
-                weapon_index: Some(weapon_index_u),
-                values: Map::from_iter([
-                    ("roll".to_string(), Value::from(round_f64(roll))),
-                    ("base_attack".to_string(), Value::from(attacker_weapon_attack)),
-                    (
-                        "effective_attack".to_string(),
-                        Value::from(round_f64(effective_attack)),
-                    ),
-                ]),
-            });
+            for shooter in config.initiative.sub_round_order() {
+                if shooter == Shooter::Attacker {
+                    let effective_apex_shred =
+                        (attacker.apex_shred + phase_effects.composed_apex_shred_bonus()).max(0.0);
+                    let effective_apex_barrier = (defender.apex_barrier
+                        + phase_effects.composed_apex_barrier_bonus())
+                        .max(0.0);
+                    let apex_damage_factor =
+                        compute_apex_damage_factor(effective_apex_shred, effective_apex_barrier);
 
-            let mitigation_multiplier = (1.0 - defender.mitigation).max(0.0);
-            trace.record_if(|| CombatEvent {
-                event_type: "mitigation_calc".to_string(),
-                round_index,
-                phase: "defense".to_string(),
-                source: EventSource {
-                    hostile_ability_id: Some(format!("{}_mitigation", defender.id)),
-                    ..EventSource::default()
-                },
-                weapon_index: Some(weapon_index_u),
-                values: Map::from_iter([
-                ("mitigation".to_string(), Value::from(defender.mitigation)),
-                (
-                    "multiplier".to_string(),
-                    Value::from(round_f64(mitigation_multiplier)),
-                ),
-            ]),
-        });
+                    let base_shots = attacker.weapon_base_shots(weapon_index);
+                    let effective_shots = if holding_fire {
+                        0
+                    } else {
+                        round_half_even(base_shots as f64 * (1.0 + b_shots))
+                    };
+                    let shield_before_weapon = defender_shield_remaining;
 
-        // Damage-through factor: fraction of attack that gets through (can exceed 1.0 with pierce).
-        let damage_through_factor = compute_damage_through_factor(
-            mitigation_multiplier,
-            effective_pierce,
-            phase_effects.defense_mitigation_bonus(),
-        );
-        trace.record_if(|| CombatEvent {
-            event_type: "pierce_calc".to_string(),
-            round_index,
-            phase: "attack".to_string(),
-            source: EventSource {
-                officer_id: Some(attacker.id.clone()),
-                player_bonus_source: Some("attack_pierce_bonus".to_string()),
-                ..EventSource::default()
-            },
-            weapon_index: None,
-            values: Map::from_iter([
-                ("pierce".to_string(), Value::from(effective_pierce)),
-                (
-                    "damage_through_factor".to_string(),
-                    Value::from(round_f64(damage_through_factor)),
-                ),
-            ]),
-        });
+                    let weapon_index_u = weapon_index as u32;
+                    for _ in 0..effective_shots {
+                        let Some(attacker_weapon_attack) = attacker.weapon_attack(weapon_index)
+                        else {
+                            continue;
+                        };
+                        let effective_attack =
+                            attacker_weapon_attack * phase_effects.pre_attack_multiplier();
 
-        let hull_breach_active = hull_breach_rounds_remaining > 0;
-        let crit_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-        let is_crit = crit_roll < attacker.crit_chance;
-        let crit_multiplier = compute_crit_multiplier(
-            is_crit,
-            attacker.crit_multiplier,
-            hull_breach_active,
-        );
-        trace.record_if(|| CombatEvent {
-            event_type: "crit_resolution".to_string(),
-            round_index,
-            phase: "attack".to_string(),
-            source: EventSource {
-                officer_id: Some(attacker.id.clone()),
-                ship_ability_id: Some("crit_matrix".to_string()),
-                ..EventSource::default()
-            },
-            weapon_index: None,
-            values: Map::from_iter([
-                ("roll".to_string(), Value::from(round_f64(crit_roll))),
-                ("is_crit".to_string(), Value::Bool(is_crit)),
-                ("multiplier".to_string(), Value::from(crit_multiplier)),
-                (
-                    "hull_breach_active".to_string(),
-                    Value::Bool(hull_breach_active),
-                ),
-            ]),
-        });
+                        let roll = rolls.roll(RollChannel::Attack);
+                        trace.record_if("attack_roll", "attack", round_index, || TraceDetail {
+                            source: EventSource {
+                                officer_id: Some(attacker.id.clone()),
+                                ..EventSource::default()
+                            },
+                            weapon_index: Some(weapon_index_u),
+                            values: AttackRoll {
+                                roll: round_f64(roll),
+                                base_attack: attacker_weapon_attack,
+                                effective_attack: round_f64(effective_attack),
+                            }
+                            .to_values(),
+                        });
 
-        for effect in &attack_phase_filtered {
-            let effective_effect = scale_effect(effect.effect, attack_phase_assimilated);
+                        let mitigation_multiplier = (1.0 - defender.mitigation).max(0.0);
+                        trace.record_if("mitigation_calc", "defense", round_index, || TraceDetail {
+                            source: EventSource {
+                                hostile_ability_id: Some(format!("{}_mitigation", defender.id)),
+                                ..EventSource::default()
+                            },
+                            weapon_index: Some(weapon_index_u),
+                            values: MitigationCalc {
+                                mitigation: defender.mitigation,
+                                multiplier: round_f64(mitigation_multiplier),
+                            }
+                            .to_values(),
+                        });
 
-            if let AbilityEffect::Assimilated {
-                chance,
-                duration_rounds,
-            } = effective_effect
-            {
-                let assimilated_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-                let triggered = assimilated_roll < chance.clamp(0.0, 1.0);
-                if triggered {
-                    assimilated_rounds_remaining =
-                        assimilated_rounds_remaining.max(duration_rounds.max(1));
-                }
-                trace.record_if(|| CombatEvent {
-                    event_type: "assimilated_trigger".to_string(),
-                    round_index,
-                    phase: "attack".to_string(),
-                    source: EventSource {
-                        officer_id: Some(attacker.id.clone()),
-                        ship_ability_id: Some(effect.ability_name.clone()),
-                        ..EventSource::default()
-                    },
-                    weapon_index: None,
-                    values: Map::from_iter([
-                        ("roll".to_string(), Value::from(round_f64(assimilated_roll))),
-                        ("triggered".to_string(), Value::Bool(triggered)),
-                        ("chance".to_string(), Value::from(round_f64(chance))),
-                        ("duration_rounds".to_string(), Value::from(duration_rounds)),
-                    ]),
-                });
-            }
+                        // Damage-through factor: fraction of attack that gets through (can exceed
+                        // 1.0 with pierce).
+                        let damage_through_factor = compute_damage_through_factor(
+                            mitigation_multiplier,
+                            effective_pierce,
+                            phase_effects.defense_mitigation_bonus(),
+                        );
+                        trace.record_if("pierce_calc", "attack", round_index, || TraceDetail {
+                            source: EventSource {
+                                officer_id: Some(attacker.id.clone()),
+                                player_bonus_source: Some("attack_pierce_bonus".to_string()),
+                                ..EventSource::default()
+                            },
+                            weapon_index: Some(weapon_index_u),
+                            values: PierceCalc {
+                                pierce: effective_pierce,
+                                damage_through_factor: round_f64(damage_through_factor),
+                            }
+                            .to_values(),
+                        });
 
-            if let AbilityEffect::HullBreach {
-                chance,
-                duration_rounds,
-                requires_critical,
-            } = effective_effect
-            {
-                if requires_critical && !is_crit {
-                    continue;
-                }
+                        let hull_breach_active = hull_breach_rounds_remaining > 0;
+                        let crit_roll = rolls.roll(RollChannel::Crit);
+                        let is_crit = crit_roll < attacker.crit_chance;
+                        let crit_multiplier = compute_crit_multiplier(
+                            is_crit,
+                            attacker.crit_multiplier,
+                            hull_breach_active,
+                        );
+                        trace.record_if("crit_resolution", "attack", round_index, || TraceDetail {
+                            source: EventSource {
+                                officer_id: Some(attacker.id.clone()),
+                                ship_ability_id: Some("crit_matrix".to_string()),
+                                ..EventSource::default()
+                            },
+                            weapon_index: Some(weapon_index_u),
+                            values: CritResolution {
+                                roll: round_f64(crit_roll),
+                                is_crit,
+                                multiplier: crit_multiplier,
+                                hull_breach_active,
+                            }
+                            .to_values(),
+                        });
 
-                let hull_breach_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-                let triggered = hull_breach_roll < chance.clamp(0.0, 1.0);
-                if triggered {
-                    hull_breach_rounds_remaining =
-                        hull_breach_rounds_remaining.max(duration_rounds.max(1));
-                }
-                trace.record_if(|| CombatEvent {
-                    event_type: "hull_breach_trigger".to_string(),
-                    round_index,
-                    phase: "attack".to_string(),
-                    source: EventSource {
-                        officer_id: Some(attacker.id.clone()),
-                        ship_ability_id: Some(effect.ability_name.clone()),
-                        ..EventSource::default()
-                    },
-                    weapon_index: None,
-                    values: Map::from_iter([
-                        ("roll".to_string(), Value::from(round_f64(hull_breach_roll))),
-                        ("triggered".to_string(), Value::Bool(triggered)),
-                        ("chance".to_string(), Value::from(round_f64(chance))),
-                        ("duration_rounds".to_string(), Value::from(duration_rounds)),
-                        (
-                            "requires_critical".to_string(),
-                            Value::Bool(requires_critical),
-                        ),
-                    ]),
-                });
-            }
+                        for effect in &attack_phase_filtered {
+                            let effective_effect = scale_effect(effect, attack_phase_assimilated);
 
-            if let AbilityEffect::Burning {
-                chance,
-                duration_rounds,
-            } = effective_effect
-            {
-                let burning_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-                let triggered = burning_roll < chance.clamp(0.0, 1.0);
-                if triggered {
-                    burning_rounds_remaining = burning_rounds_remaining.max(duration_rounds.max(1));
-                }
-                trace.record_if(|| CombatEvent {
-                    event_type: "burning_trigger".to_string(),
-                    round_index,
-                    phase: "attack".to_string(),
-                    source: EventSource {
-                        officer_id: Some(attacker.id.clone()),
-                        ship_ability_id: Some(effect.ability_name.clone()),
-                        ..EventSource::default()
-                    },
-                    weapon_index: None,
-                    values: Map::from_iter([
-                        ("roll".to_string(), Value::from(round_f64(burning_roll))),
-                        ("triggered".to_string(), Value::Bool(triggered)),
-                        ("chance".to_string(), Value::from(round_f64(chance))),
-                        ("duration_rounds".to_string(), Value::from(duration_rounds)),
-                    ]),
-                });
-            }
-        }
+                            if let AbilityEffect::Assimilated {
+                                chance,
+                                duration_rounds,
+                            } = effective_effect
+                            {
+                                let assimilated_roll = rolls.roll(RollChannel::Status);
+                                let triggered = assimilated_roll < chance.clamp(0.0, 1.0);
+                                if triggered {
+                                    assimilated_rounds_remaining =
+                                        assimilated_rounds_remaining.max(duration_rounds.max(1));
+                                }
+                                trace.record_if("assimilated_trigger", "attack", round_index, || {
+                                    TraceDetail {
+                                        source: EventSource {
+                                            officer_id: Some(attacker.id.clone()),
+                                            ship_ability_id: Some(effect.ability_name.clone()),
+                                            ..EventSource::default()
+                                        },
+                                        weapon_index: Some(weapon_index_u),
+                                        values: Map::from_iter([
+                                            (
+                                                "roll".to_string(),
+                                                Value::from(round_f64(assimilated_roll)),
+                                            ),
+                                            ("triggered".to_string(), Value::Bool(triggered)),
+                                            ("chance".to_string(), Value::from(round_f64(chance))),
+                                            (
+                                                "duration_rounds".to_string(),
+                                                Value::from(duration_rounds),
+                                            ),
+                                        ]),
+                                    }
+                                });
+                            }
 
-        let proc_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-        let did_proc = proc_roll < attacker.proc_chance;
-        let proc_multiplier = if did_proc {
-            attacker.proc_multiplier
-        } else {
-            1.0
-        };
-        trace.record_if(|| CombatEvent {
-            event_type: "proc_triggers".to_string(),
-            round_index,
-            phase: "proc".to_string(),
-            source: EventSource {
-                officer_id: Some(attacker.id.clone()),
-                ship_ability_id: Some("officer_proc".to_string()),
-                ..EventSource::default()
-            },
-            weapon_index: None,
-            values: Map::from_iter([
-                ("roll".to_string(), Value::from(round_f64(proc_roll))),
-                ("triggered".to_string(), Value::Bool(did_proc)),
-                ("multiplier".to_string(), Value::from(proc_multiplier)),
-            ]),
-        });
+                            if let AbilityEffect::HullBreach {
+                                chance,
+                                duration_rounds,
+                                requires_critical,
+                            } = effective_effect
+                            {
+                                if requires_critical && !is_crit {
+                                    continue;
+                                }
 
-        let pre_attack_damage =
-            effective_attack * damage_through_factor * crit_multiplier * proc_multiplier;
-        phase_effects.set_pre_attack_damage_base(pre_attack_damage);
-        let pre_attack_damage = phase_effects.composed_pre_attack_damage();
-        let damage = phase_effects.compose_attack_phase_damage(pre_attack_damage);
-
-        trace.record_if(|| {
-            let mut values = phase_effects.stack_resolution_values();
-            values.insert(
-                "pre_attack_damage_composed".to_string(),
-                Value::from(round_f64(pre_attack_damage)),
-            );
-            values.insert(
-                "damage_after_attack_phase_compose".to_string(),
-                Value::from(round_f64(damage)),
-            );
-            CombatEvent {
-                event_type: "stack_resolution".to_string(),
-                round_index,
-                phase: "attack".to_string(),
-                source: EventSource {
-                    officer_id: Some(attacker.id.clone()),
-                    player_bonus_source: Some("effect_stacks".to_string()),
-                    ..EventSource::default()
-                },
-                weapon_index: Some(weapon_index_u),
-                values,
-            }
-        });
+                                let hull_breach_roll = rolls.roll(RollChannel::Status);
+                                let triggered = hull_breach_roll < chance.clamp(0.0, 1.0);
+                                if triggered {
+                                    hull_breach_rounds_remaining =
+                                        hull_breach_rounds_remaining.max(duration_rounds.max(1));
+                                }
+                                trace.record_if("hull_breach_trigger", "attack", round_index, || {
+                                    TraceDetail {
+                                        source: EventSource {
+                                            officer_id: Some(attacker.id.clone()),
+                                            ship_ability_id: Some(effect.ability_name.clone()),
+                                            ..EventSource::default()
+                                        },
+                                        weapon_index: Some(weapon_index_u),
+                                        values: Map::from_iter([
+                                            (
+                                                "roll".to_string(),
+                                                Value::from(round_f64(hull_breach_roll)),
+                                            ),
+                                            ("triggered".to_string(), Value::Bool(triggered)),
+                                            ("chance".to_string(), Value::from(round_f64(chance))),
+                                            (
+                                                "duration_rounds".to_string(),
+                                                Value::from(duration_rounds),
+                                            ),
+                                            (
+                                                "requires_critical".to_string(),
+                                                Value::Bool(requires_critical),
+                                            ),
+                                        ]),
+                                    }
+                                });
+                            }
 
-        // Isolytic: from pre-apex standard damage; report formula: isolytic taken = Isolytic Damage / (1 + I_def).
-        let effective_isolytic_damage = (attacker.isolytic_damage + phase_effects.composed_isolytic_damage_bonus()).max(0.0);
-        let effective_isolytic_defense = (defender.isolytic_defense + phase_effects.composed_isolytic_defense_bonus()).max(0.0);
-        let effective_isolytic_cascade = phase_effects.composed_isolytic_cascade_damage_bonus().max(0.0);
-        let isolytic_taken = compute_isolytic_taken(
-            damage,
-            effective_isolytic_damage,
-            effective_isolytic_defense,
-            effective_isolytic_cascade,
-        );
+                            if let AbilityEffect::Burning {
+                                chance,
+                                duration_rounds,
+                            } = effective_effect
+                            {
+                                let burning_roll = rolls.roll(RollChannel::Status);
+                                let triggered = burning_roll < chance.clamp(0.0, 1.0);
+                                if triggered {
+                                    dots.apply(
+                                        DotChannel::Burning,
+                                        BURNING_HULL_DAMAGE_PER_ROUND,
+                                        duration_rounds,
+                                    );
+                                }
+                                trace.record_if("burning_trigger", "attack", round_index, || {
+                                    TraceDetail {
+                                        source: EventSource {
+                                            officer_id: Some(attacker.id.clone()),
+                                            ship_ability_id: Some(effect.ability_name.clone()),
+                                            ..EventSource::default()
+                                        },
+                                        weapon_index: Some(weapon_index_u),
+                                        values: Map::from_iter([
+                                            (
+                                                "roll".to_string(),
+                                                Value::from(round_f64(burning_roll)),
+                                            ),
+                                            ("triggered".to_string(), Value::Bool(triggered)),
+                                            ("chance".to_string(), Value::from(round_f64(chance))),
+                                            (
+                                                "duration_rounds".to_string(),
+                                                Value::from(duration_rounds),
+                                            ),
+                                        ]),
+                                    }
+                                });
+                            }
 
-        // Apex barrier: apply once to combined pool (standard_net + isolytic_taken).
-        let damage_before_apex = damage + isolytic_taken;
-        let damage_after_apex = damage_before_apex * apex_damage_factor;
+                            if let AbilityEffect::DamageOverTime {
+                                channel,
+                                chance,
+                                hull_pct,
+                                duration_rounds,
+                            } = effective_effect
+                            {
+                                let dot_roll = rolls.roll(RollChannel::Status);
+                                let triggered = dot_roll < chance.clamp(0.0, 1.0);
+                                if triggered {
+                                    dots.apply(channel, hull_pct, duration_rounds);
+                                }
+                                trace.record_if("dot_trigger", "attack", round_index, || {
+                                    TraceDetail {
+                                        source: EventSource {
+                                            officer_id: Some(attacker.id.clone()),
+                                            ship_ability_id: Some(effect.ability_name.clone()),
+                                            ..EventSource::default()
+                                        },
+                                        weapon_index: Some(weapon_index_u),
+                                        values: Map::from_iter([
+                                            ("channel".to_string(), Value::from(channel.as_str())),
+                                            ("roll".to_string(), Value::from(round_f64(dot_roll))),
+                                            ("triggered".to_string(), Value::Bool(triggered)),
+                                            ("chance".to_string(), Value::from(round_f64(chance))),
+                                            (
+                                                "hull_pct".to_string(),
+                                                Value::from(round_f64(hull_pct)),
+                                            ),
+                                            (
+                                                "duration_rounds".to_string(),
+                                                Value::from(duration_rounds),
+                                            ),
+                                        ]),
+                                    }
+                                });
+                            }
+                        }
 
-        // Shield mitigation: S * damage to shield, (1-S) * damage to hull (STFC Toolbox game-mechanics).
-        let effective_shield_mitigation = (defender.shield_mitigation + phase_effects.composed_shield_mitigation_bonus()).clamp(0.0, 1.0);
-        let shield_mitigation = if defender_shield_remaining > 0.0 {
-            effective_shield_mitigation
-        } else {
-            0.0
-        };
-        let (actual_shield_damage, hull_damage_this_round) =
-            apply_shield_hull_split(damage_after_apex, shield_mitigation, defender_shield_remaining);
+                        let proc_roll = rolls.roll(RollChannel::Proc);
+                        let did_proc = proc_roll < attacker.proc_chance;
+                        let proc_multiplier = if did_proc {
+                            attacker.proc_multiplier
+                        } else {
+                            1.0
+                        };
+                        trace.record_if("proc_triggers", "proc", round_index, || TraceDetail {
+                            source: EventSource {
+                                officer_id: Some(attacker.id.clone()),
+                                ship_ability_id: Some("officer_proc".to_string()),
+                                ..EventSource::default()
+                            },
+                            weapon_index: Some(weapon_index_u),
+                            values: Map::from_iter([
+                                ("roll".to_string(), Value::from(round_f64(proc_roll))),
+                                ("triggered".to_string(), Value::Bool(did_proc)),
+                                ("multiplier".to_string(), Value::from(proc_multiplier)),
+                            ]),
+                        });
 
-        defender_shield_remaining = (defender_shield_remaining - actual_shield_damage).max(0.0);
-        total_hull_damage += hull_damage_this_round;
-        total_shield_damage += actual_shield_damage;
+                        let pre_attack_damage = effective_attack
+                            * damage_through_factor
+                            * crit_multiplier
+                            * proc_multiplier;
+                        phase_effects.set_pre_attack_damage_base(pre_attack_damage);
+                        let pre_attack_damage = phase_effects.composed_pre_attack_damage();
+                        let damage = phase_effects.compose_attack_phase_damage(pre_attack_damage);
 
-        trace.record_if(|| CombatEvent {
-            event_type: "damage_application".to_string(),
-            round_index,
-            phase: "damage".to_string(),
-            source: EventSource {
-                officer_id: Some(attacker.id.clone()),
-                hostile_ability_id: Some(format!("{}_hull", defender.id)),
-                ..EventSource::default()
-            },
-            weapon_index: Some(weapon_index_u),
-            values: Map::from_iter([
-                ("damage_after_apex".to_string(), Value::from(round_f64(damage_after_apex))),
-                ("shield_mitigation".to_string(), Value::from(round_f64(shield_mitigation))),
-                ("shield_damage".to_string(), Value::from(round_f64(actual_shield_damage))),
-                ("hull_damage".to_string(), Value::from(round_f64(hull_damage_this_round))),
-                (
-                    "running_hull_damage".to_string(),
-                    Value::from(round_f64(total_hull_damage)),
-                ),
-                (
-                    "defender_shield_remaining".to_string(),
-                    Value::from(round_f64(defender_shield_remaining)),
-                ),
-                (
-                    "shield_broke".to_string(),
-                    Value::Bool(shield_before_weapon > 0.0 && defender_shield_remaining <= 0.0),
-                ),
-                (
-                    "assimilated_active".to_string(),
-                    Value::Bool(assimilated_rounds_remaining > 0),
-                ),
-            ]),
-        });
-            }
-            }
+                        trace.record_if("stack_resolution", "attack", round_index, || {
+                            let mut values = phase_effects.stack_resolution_values();
+                            values.insert(
+                                "pre_attack_damage_composed".to_string(),
+                                Value::from(round_f64(pre_attack_damage)),
+                            );
+                            values.insert(
+                                "damage_after_attack_phase_compose".to_string(),
+                                Value::from(round_f64(damage)),
+                            );
+                            TraceDetail {
+                                source: EventSource {
+                                    officer_id: Some(attacker.id.clone()),
+                                    player_bonus_source: Some("effect_stacks".to_string()),
+                                    ..EventSource::default()
+                                },
+                                weapon_index: Some(weapon_index_u),
+                                values,
+                            }
+                        });
 
-            let shield_broke_this_round = shield_before_weapon > 0.0 && defender_shield_remaining <= 0.0;
-        if shield_broke_this_round {
-            let shield_break_filtered =
-                filter_effects_by_condition(&shield_break_effects, &combat_ctx);
-            record_ability_activations(
-                &mut trace,
-                round_index,
-                "shield_break",
-                attacker,
-                &shield_break_filtered,
-                attack_phase_assimilated,
-            );
-            phase_effects_round.add_effects(
-                TimingWindow::ShieldBreak,
-                &shield_break_filtered,
-                weapon_base,
-                attack_phase_assimilated,
-                round_index,
-            );
-        }
+                        // Isolytic: from pre-apex standard damage; report formula: isolytic taken =
+                        // Isolytic Damage / (1 + I_def).
+                        let effective_isolytic_damage = (attacker.isolytic_damage
+                            + phase_effects.composed_isolytic_damage_bonus())
+                            .max(0.0);
+                        let effective_isolytic_defense = (defender.isolytic_defense
+                            + phase_effects.composed_isolytic_defense_bonus())
+                            .max(0.0);
+                        let effective_isolytic_cascade =
+                            phase_effects.composed_isolytic_cascade_damage_bonus().max(0.0);
+                        let isolytic_taken = compute_isolytic_taken(
+                            damage,
+                            effective_isolytic_damage,
+                            effective_isolytic_defense,
+                            effective_isolytic_cascade,
+                        );
 
-        let defender_hull_pct = 1.0
-            - (total_hull_damage / defender.hull_health.max(0.0)).min(1.0);
-        if !hull_breach_threshold_fired && defender_hull_pct < 0.5 {
-            hull_breach_threshold_fired = true;
-            let hull_breach_filtered =
-                filter_effects_by_condition(&hull_breach_effects, &combat_ctx);
-            record_ability_activations(
-                &mut trace,
-                round_index,
-                "hull_breach",
-                attacker,
-                &hull_breach_filtered,
-                attack_phase_assimilated,
-            );
-            phase_effects_round.add_effects(
-                TimingWindow::HullBreach,
-                &hull_breach_filtered,
-                weapon_base,
-                attack_phase_assimilated,
-                round_index,
-            );
-        }
+                        // Apex barrier: apply once to combined pool (standard_net +
+                        // isolytic_taken).
+                        let damage_before_apex = damage + isolytic_taken;
+                        let damage_after_apex = damage_before_apex * apex_damage_factor;
 
-            if let Some(defender_weapon_attack) = defender.weapon_attack(weapon_index) {
-        // Defender counter-attack: hostile weapon fire vs the player ship (attacker struct).
-        // Uses the same damage-through, isolytic, apex, and shield/hull helpers as outbound shots
-        // so the two paths stay in sync. Assumption: no hostile crew / effect stacks on return fire
-        // (no DefensePhase mitigation bonus from player crew on incoming fire, no isolytic cascade
-        // from officer effects on the hostile). If hostile crew is modeled later, thread an
-        // EffectAccumulator for the counter shot analogous to `phase_effects`.
-        let counter_mitigation_mult = (1.0 - attacker.mitigation).max(0.0);
-        let counter_damage_through = compute_damage_through_factor(
-            counter_mitigation_mult,
-            defender.pierce,
-            0.0,
-        );
-        let def_crit_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-        let def_is_crit = def_crit_roll < defender.crit_chance;
-        let def_crit_mult =
-            compute_crit_multiplier(def_is_crit, defender.crit_multiplier, false);
-        let def_proc_roll = (rng.next_u64() as f64) / (u64::MAX as f64);
-        let def_proc_mult = if def_proc_roll < defender.proc_chance {
-            defender.proc_multiplier
-        } else {
-            1.0
-        };
-        let counter_base_damage = defender_weapon_attack
-            * counter_damage_through
-            * def_crit_mult
-            * def_proc_mult;
-        let counter_iso_taken = compute_isolytic_taken(
-            counter_base_damage,
-            defender.isolytic_damage.max(0.0),
-            attacker.isolytic_defense.max(0.0),
-            0.0,
-        );
-        let counter_before_apex = counter_base_damage + counter_iso_taken;
-        let counter_apex_factor = compute_apex_damage_factor(
-            defender.apex_shred.max(0.0),
-            attacker.apex_barrier.max(0.0),
-        );
-        let counter_after_apex = counter_before_apex * counter_apex_factor;
-        let att_shield_mitigation = if attacker_shield_remaining > 0.0 {
-            attacker.shield_mitigation.clamp(0.0, 1.0)
-        } else {
-            0.0
-        };
-        let (att_actual_shield_damage, att_hull_damage_this_round) = apply_shield_hull_split(
-            counter_after_apex,
-            att_shield_mitigation,
-            attacker_shield_remaining,
-        );
-        attacker_shield_remaining = (attacker_shield_remaining - att_actual_shield_damage).max(0.0);
-        total_attacker_hull_damage += att_hull_damage_this_round;
-        if att_hull_damage_this_round > 0.0 {
-            let receive_damage_filtered =
-                filter_effects_by_condition(&receive_damage_effects, &combat_ctx);
-            record_ability_activations(
-                &mut trace,
-                round_index,
-                "receive_damage",
-                attacker,
-                &receive_damage_filtered,
-                assimilated_rounds_remaining > 0,
-            );
-            phase_effects_round.add_effects(
-                TimingWindow::ReceiveDamage,
-                &receive_damage_filtered,
-                defender_weapon_attack,
-                assimilated_rounds_remaining > 0,
-                round_index,
-            );
-        }
+                        // Shield mitigation: S * damage to shield, (1-S) * damage to hull (STFC
+                        // Toolbox game-mechanics).
+                        let effective_shield_mitigation = (defender.shield_mitigation
+                            + phase_effects.composed_shield_mitigation_bonus())
+                            .clamp(0.0, 1.0);
+                        let shield_mitigation = if defender_shield_remaining > 0.0 {
+                            effective_shield_mitigation
+                        } else {
+                            0.0
+                        };
+                        let (actual_shield_damage, hull_damage_this_round) =
+                            apply_shield_hull_split(
+                                damage_after_apex,
+                                shield_mitigation,
+                                defender_shield_remaining,
+                            );
+
+                        defender_shield_remaining =
+                            (defender_shield_remaining - actual_shield_damage).max(0.0);
+                        total_hull_damage += hull_damage_this_round;
+                        total_shield_damage += actual_shield_damage;
+                        attacker_damage.add_shot(
+                            damage * apex_damage_factor,
+                            isolytic_taken * apex_damage_factor,
+                            crit_multiplier,
+                            proc_multiplier,
+                            actual_shield_damage,
+                        );
+
+                        trace.record_if("damage_application", "damage", round_index, || {
+                            TraceDetail {
+                                source: EventSource {
+                                    officer_id: Some(attacker.id.clone()),
+                                    hostile_ability_id: Some(format!("{}_hull", defender.id)),
+                                    ..EventSource::default()
+                                },
+                                weapon_index: Some(weapon_index_u),
+                                values: DamageApplication {
+                                    damage_after_apex: round_f64(damage_after_apex),
+                                    shield_mitigation: round_f64(shield_mitigation),
+                                    shield_damage: round_f64(actual_shield_damage),
+                                    hull_damage: round_f64(hull_damage_this_round),
+                                    running_hull_damage: round_f64(total_hull_damage),
+                                    defender_shield_remaining: round_f64(defender_shield_remaining),
+                                    shield_broke: shield_before_weapon > 0.0
+                                    && defender_shield_remaining <= 0.0,
+                                    assimilated_active: assimilated_rounds_remaining > 0,
+                                    isolytic_damage: round_f64(isolytic_taken),
+                                }
+                                .to_values(),
+                            }
+                        });
+                    }
+
+                    let shield_broke_this_round =
+                        shield_before_weapon > 0.0 && defender_shield_remaining <= 0.0;
+                    if shield_broke_this_round {
+                        let shield_break_filtered = apply_activation_limits(
+                            &mut trace,
+                            round_index,
+                            "shield_break",
+                            Some(weapon_index_u),
+                            attacker,
+                            activation_limits,
+                            select_effects(shield_break_effects, &combat_ctx, shield_break_picked),
+                        );
+                        record_ability_activations(
+                            &mut trace,
+                            round_index,
+                            "shield_break",
+                            Some(weapon_index_u),
+                            attacker,
+                            &shield_break_filtered,
+                            attack_phase_assimilated,
+                        );
+                        phase_effects_round.add_effects(
+                            TimingWindow::ShieldBreak,
+                            &shield_break_filtered,
+                            weapon_base,
+                            attack_phase_assimilated,
+                            round_index,
+                        );
+                    }
+
+                    let defender_hull_pct = 1.0
+                        - (total_hull_damage / defender.hull_health.max(0.0)).min(1.0);
+                    if !hull_breach_threshold_fired && defender_hull_pct < 0.5 {
+                        hull_breach_threshold_fired = true;
+                        let hull_breach_filtered = apply_activation_limits(
+                            &mut trace,
+                            round_index,
+                            "hull_breach",
+                            Some(weapon_index_u),
+                            attacker,
+                            activation_limits,
+                            select_effects(hull_breach_effects, &combat_ctx, hull_breach_picked),
+                        );
+                        record_ability_activations(
+                            &mut trace,
+                            round_index,
+                            "hull_breach",
+                            Some(weapon_index_u),
+                            attacker,
+                            &hull_breach_filtered,
+                            attack_phase_assimilated,
+                        );
+                        phase_effects_round.add_effects(
+                            TimingWindow::HullBreach,
+                            &hull_breach_filtered,
+                            weapon_base,
+                            attack_phase_assimilated,
+                            round_index,
+                        );
+                    }
+
+                } else if let Some(defender_weapon_attack) = defender.weapon_attack(weapon_index) {
+                    // Defender counter-attack: hostile weapon fire vs the player ship (attacker
+                    // struct). Uses the same damage-through, isolytic, apex, and shield/hull
+                    // helpers as outbound shots so the two paths stay in sync. Assumption: no
+                    // hostile crew / effect stacks on return fire (no DefensePhase mitigation bonus
+                    // from player crew on incoming fire, no isolytic cascade from officer effects
+                    // on the hostile). If hostile crew is modeled later, thread an
+                    // EffectAccumulator for the counter shot analogous to `phase_effects`.
+                    let counter_mitigation_mult = (1.0 - attacker.mitigation).max(0.0);
+                    let counter_damage_through = compute_damage_through_factor(
+                        counter_mitigation_mult,
+                        defender.pierce,
+                        0.0,
+                    );
+                    let def_crit_roll = rolls.roll_for(RollSide::Defender, RollChannel::Crit);
+                    let def_is_crit = def_crit_roll < defender.crit_chance;
+                    let def_crit_mult =
+                        compute_crit_multiplier(def_is_crit, defender.crit_multiplier, false);
+                    let def_proc_roll = rolls.roll_for(RollSide::Defender, RollChannel::Proc);
+                    let def_proc_mult = if def_proc_roll < defender.proc_chance {
+                        defender.proc_multiplier
+                    } else {
+                        1.0
+                    };
+                    let counter_base_damage = defender_weapon_attack
+                        * counter_damage_through
+                        * def_crit_mult
+                        * def_proc_mult;
+                    let counter_iso_taken = compute_isolytic_taken(
+                        counter_base_damage,
+                        defender.isolytic_damage.max(0.0),
+                        attacker.isolytic_defense.max(0.0),
+                        0.0,
+                    );
+                    let counter_before_apex = counter_base_damage + counter_iso_taken;
+                    let counter_apex_factor = compute_apex_damage_factor(
+                        defender.apex_shred.max(0.0),
+                        attacker.apex_barrier.max(0.0),
+                    );
+                    let counter_after_apex = counter_before_apex * counter_apex_factor;
+                    let counter_source = || EventSource {
+                        hostile_ability_id: Some(format!("{}_weapon", defender.id)),
+                        ..EventSource::default()
+                    };
+                    trace.record_if("counter_attack_roll", "counter_attack", round_index, || {
+                        TraceDetail {
+                            source: counter_source(),
+                            weapon_index: Some(weapon_index as u32),
+                            values: CounterAttackRoll {
+                                base_attack: defender_weapon_attack,
+                                mitigation: attacker.mitigation,
+                                damage_through_factor: round_f64(counter_damage_through),
+                                crit_roll: round_f64(def_crit_roll),
+                                is_crit: def_is_crit,
+                                crit_multiplier: def_crit_mult,
+                                proc_roll: round_f64(def_proc_roll),
+                                proc_multiplier: def_proc_mult,
+                            }
+                            .to_values(),
+                        }
+                    });
+                    let att_shield_mitigation = if attacker_shield_remaining > 0.0 {
+                        attacker.shield_mitigation.clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    let (att_actual_shield_damage, att_hull_damage_this_round) =
+                        apply_shield_hull_split(
+                            counter_after_apex,
+                            att_shield_mitigation,
+                            attacker_shield_remaining,
+                        );
+                    let attacker_shield_before = attacker_shield_remaining;
+                    attacker_shield_remaining =
+                        (attacker_shield_remaining - att_actual_shield_damage).max(0.0);
+                    total_attacker_hull_damage += att_hull_damage_this_round;
+                    defender_damage.add_shot(
+                        counter_base_damage * counter_apex_factor,
+                        counter_iso_taken * counter_apex_factor,
+                        def_crit_mult,
+                        def_proc_mult,
+                        att_actual_shield_damage,
+                    );
+                    trace.record_if(
+                        "counter_damage_application",
+                        "counter_attack",
+                        round_index,
+                        || TraceDetail {
+                            source: counter_source(),
+                            weapon_index: Some(weapon_index as u32),
+                            values: CounterDamageApplication {
+                                damage_after_apex: round_f64(counter_after_apex),
+                                shield_mitigation: round_f64(att_shield_mitigation),
+                                shield_damage: round_f64(att_actual_shield_damage),
+                                hull_damage: round_f64(att_hull_damage_this_round),
+                                running_hull_damage: round_f64(total_attacker_hull_damage),
+                                attacker_shield_remaining: round_f64(attacker_shield_remaining),
+                                shield_broke: attacker_shield_before > 0.0
+                                && attacker_shield_remaining <= 0.0,
+                            }
+                            .to_values(),
+                        },
+                    );
+                    if att_hull_damage_this_round > 0.0 {
+                        let receive_damage_filtered = apply_activation_limits(
+                            &mut trace,
+                            round_index,
+                            "receive_damage",
+                            Some(weapon_index as u32),
+                            attacker,
+                            activation_limits,
+                            select_effects(
+                                receive_damage_effects,
+                                &combat_ctx,
+                                receive_damage_picked,
+                            ),
+                        );
+                        record_ability_activations(
+                            &mut trace,
+                            round_index,
+                            "receive_damage",
+                            Some(weapon_index as u32),
+                            attacker,
+                            &receive_damage_filtered,
+                            assimilated_rounds_remaining > 0,
+                        );
+                        phase_effects_round.add_effects(
+                            TimingWindow::ReceiveDamage,
+                            &receive_damage_filtered,
+                            defender_weapon_attack,
+                            assimilated_rounds_remaining > 0,
+                            round_index,
+                        );
+                    }
+                }
+                if config.initiative.is_simultaneous() {
+                    continue;
+                }
+                let down = if defender.hull_health - total_hull_damage <= 0.0 {
+                    Some((Shooter::Defender, defender))
+                } else if attacker.hull_health - total_attacker_hull_damage <= 0.0 {
+                    Some((Shooter::Attacker, attacker))
+                } else {
+                    None
+                };
+                if let Some((side, ship)) = down {
+                    destroyed_mid_round = Some(side);
+                    trace.record_if("ship_destroyed", "damage", round_index, || TraceDetail {
+                        source: EventSource {
+                            officer_id: Some(ship.id.clone()),
+                            ..EventSource::default()
+                        },
+                        weapon_index: Some(weapon_index as u32),
+                        values: Map::from_iter([
+                            ("initiative".to_string(), Value::from(config.initiative.as_str())),
+                            (
+                                "side".to_string(),
+                                Value::from(match side {
+                                    Shooter::Attacker => "attacker",
+                                    Shooter::Defender => "defender",
+                                }),
+                            ),
+                        ]),
+                    });
+                    break 'sub_rounds;
+                }
             }
         }
 
@@ -843,6 +1242,7 @@ pub fn simulate_combat(
             &mut trace,
             round_index,
             "round_end",
+            None,
             attacker,
             &round_end_filtered,
             round_end_assimilated_early,
@@ -851,27 +1251,121 @@ pub fn simulate_combat(
         let round_end_apex_shred = (attacker.apex_shred + phase_effects_round.composed_apex_shred_bonus()).max(0.0);
         let round_end_apex_barrier = (defender.apex_barrier + phase_effects_round.composed_apex_barrier_bonus()).max(0.0);
         let round_end_apex_factor = 10000.0 / (10000.0 + round_end_apex_barrier / (1.0 + round_end_apex_shred).max(EPSILON));
-        let bonus_damage = phase_effects_round.compose_round_end_damage(attacker.end_of_round_damage);
-        // Burning: 1% of max hull per round (official: Δ HHP_burn = 0.01 × HHP_max), no scaling.
-        let burning_damage = if burning_rounds_remaining > 0 {
-            defender.hull_health.max(0.0) * BURNING_HULL_DAMAGE_PER_ROUND
-        } else {
+        let bonus_damage = if holding_fire || destroyed_mid_round == Some(Shooter::Attacker) {
             0.0
+        } else {
+            phase_effects_round.compose_round_end_damage(attacker.end_of_round_damage)
         };
-        // Round-end and burning apply to hull only (shields do not absorb these).
-        total_hull_damage += (bonus_damage + burning_damage) * round_end_apex_factor;
-        total_attacker_hull_damage += defender.end_of_round_damage;
+        total_hull_damage += bonus_damage * round_end_apex_factor;
+        // Damage over time: each channel ticks on its own stacking and mitigation path
+        // (burning 1% of max hull per round, official: Δ HHP_burn = 0.01 × HHP_max).
+        let round_end_isolytic_defense = (defender.isolytic_defense + phase_effects_round.composed_isolytic_defense_bonus()).max(0.0);
+        let mut dot_raw_damage = [0.0; DotChannel::ALL.len()];
+        for (slot, channel) in DotChannel::ALL.into_iter().enumerate() {
+            let (hull_fraction, stacks) = dots.tick_fraction(channel);
+            if stacks == 0 {
+                continue;
+            }
+            let tick = resolve_dot_tick(
+                channel,
+                hull_fraction,
+                stacks,
+                defender.hull_health,
+                round_end_isolytic_defense,
+                round_end_apex_factor,
+                defender_shield_remaining,
+            );
+            dot_raw_damage[slot] = tick.raw_damage;
+            defender_shield_remaining = (defender_shield_remaining - tick.shield_damage).max(0.0);
+            total_shield_damage += tick.shield_damage;
+            total_hull_damage += tick.hull_damage;
+            attacker_damage.shield_damage += tick.shield_damage;
+            match channel {
+                DotChannel::Burning => {
+                    attacker_damage.burning_damage += tick.shield_damage + tick.hull_damage;
+                }
+                DotChannel::IsolyticCascade => {
+                    attacker_damage.isolytic_damage += tick.shield_damage + tick.hull_damage;
+                }
+                DotChannel::Plasma => {}
+            }
+            trace.record_if("dot_damage", "end", round_index, || TraceDetail {
+                source: EventSource {
+                    player_bonus_source: Some(channel.as_str().to_string()),
+                    ..EventSource::default()
+                },
+                weapon_index: None,
+                values: Map::from_iter([
+                    ("channel".to_string(), Value::from(channel.as_str())),
+                    ("stacks".to_string(), Value::from(tick.stacks)),
+                    ("raw_damage".to_string(), Value::from(round_f64(tick.raw_damage))),
+                    ("shield_damage".to_string(), Value::from(round_f64(tick.shield_damage))),
+                    ("hull_damage".to_string(), Value::from(round_f64(tick.hull_damage))),
+                    (
+                        "defender_shield_remaining".to_string(),
+                        Value::from(round_f64(defender_shield_remaining)),
+                    ),
+                ]),
+            });
+        }
+        let [burning_damage, plasma_damage, isolytic_cascade_damage] = dot_raw_damage;
+        // A ship destroyed mid-round (sequential initiative) deals and regains nothing more.
+        let defender_active = destroyed_mid_round != Some(Shooter::Defender);
+        if defender_active {
+            total_attacker_hull_damage += defender.end_of_round_damage;
+        }
+        // Charge bar (armadas): a full bar fires one heavy attack, taken like return fire but
+        // without crit, proc or pierce.
+        if let Some(charge) = defender
+            .charged_attack
+            .filter(|c| defender_active && c.fires_in(round_index))
+        {
+            let through =
+                compute_damage_through_factor((1.0 - attacker.mitigation).max(0.0), 0.0, 0.0);
+            let apex_factor = compute_apex_damage_factor(
+                defender.apex_shred.max(0.0),
+                attacker.apex_barrier.max(0.0),
+            );
+            let charged_damage = charge.damage.max(0.0) * through * apex_factor;
+            let shield_mitigation = if attacker_shield_remaining > 0.0 {
+                attacker.shield_mitigation.clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let (shield_damage, hull_damage) = apply_shield_hull_split(
+                charged_damage,
+                shield_mitigation,
+                attacker_shield_remaining,
+            );
+            attacker_shield_remaining = (attacker_shield_remaining - shield_damage).max(0.0);
+            total_attacker_hull_damage += hull_damage;
+            defender_damage.shield_damage += shield_damage;
+            trace.record_if("charged_attack", "end", round_index, || TraceDetail {
+                source: EventSource::default(),
+                weapon_index: None,
+                values: Map::from_iter([
+                    ("every_rounds".to_string(), Value::from(charge.every_rounds)),
+                    ("damage".to_string(), Value::from(round_f64(charged_damage))),
+                    ("shield_damage".to_string(), Value::from(round_f64(shield_damage))),
+                    ("hull_damage".to_string(), Value::from(round_f64(hull_damage))),
+                    (
+                        "attacker_shield_remaining".to_string(),
+                        Value::from(round_f64(attacker_shield_remaining)),
+                    ),
+                ]),
+            });
+        }
 
         // Regen: shield and hull restoration at round end from attacker's crew (officer/data regen effects apply to the ship with the crew).
         let shield_regen = phase_effects_round.composed_shield_regen();
         let hull_regen = phase_effects_round.composed_hull_regen();
         attacker_shield_remaining = (attacker_shield_remaining + shield_regen)
             .min(attacker.shield_health.max(0.0));
-        total_attacker_hull_damage = (total_attacker_hull_damage - hull_regen).max(0.0);
-
-        if burning_rounds_remaining > 0 {
-            burning_rounds_remaining -= 1;
+        if destroyed_mid_round != Some(Shooter::Attacker) {
+            total_attacker_hull_damage = (total_attacker_hull_damage - hull_regen).max(0.0);
         }
+
+        dots.advance();
         if hull_breach_rounds_remaining > 0 {
             hull_breach_rounds_remaining -= 1;
         }
@@ -879,10 +1373,7 @@ pub fn simulate_combat(
             assimilated_rounds_remaining -= 1;
         }
 
-        trace.record_if(|| CombatEvent {
-            event_type: "end_of_round_effects".to_string(),
-            round_index,
-            phase: "end".to_string(),
+        trace.record_if("end_of_round_effects", "end", round_index, || TraceDetail {
             source: EventSource {
                 player_bonus_source: Some("round_end_bonus".to_string()),
                 ..EventSource::default()
@@ -897,6 +1388,14 @@ pub fn simulate_combat(
                     "burning_damage".to_string(),
                     Value::from(round_f64(burning_damage)),
                 ),
+                (
+                    "plasma_damage".to_string(),
+                    Value::from(round_f64(plasma_damage)),
+                ),
+                (
+                    "isolytic_cascade_damage".to_string(),
+                    Value::from(round_f64(isolytic_cascade_damage)),
+                ),
                 (
                     "running_hull_damage".to_string(),
                     Value::from(round_f64(total_hull_damage)),
@@ -922,12 +1421,22 @@ pub fn simulate_combat(
                 } else {
                     1.0
                 },
+                target_tags: &defender.tags,
             };
-            let kill_filtered = filter_effects_by_condition(&kill_effects, &kill_ctx);
+            let kill_filtered = apply_activation_limits(
+                &mut trace,
+                round_index,
+                "kill",
+                None,
+                attacker,
+                activation_limits,
+                select_effects(kill_effects, &kill_ctx, kill_picked),
+            );
             record_ability_activations(
                 &mut trace,
                 round_index,
                 "kill",
+                None,
                 attacker,
                 &kill_filtered,
                 assimilated_rounds_remaining > 0,
@@ -942,30 +1451,46 @@ pub fn simulate_combat(
         }
     }
 
-    let combat_end_ctx = CombatContext {
-        round_index: rounds_completed,
-        defender_hull_pct: 1.0 - (total_hull_damage / defender.hull_health.max(0.0)).min(1.0),
-        defender_shield_pct: if defender.shield_health > 0.0 {
-            defender_shield_remaining / defender.shield_health
-        } else {
-            1.0
-        },
-        attacker_hull_pct: 1.0 - (total_attacker_hull_damage / attacker.hull_health.max(0.0)).min(1.0),
-        attacker_shield_pct: if attacker.shield_health > 0.0 {
-            attacker_shield_remaining / attacker.shield_health
-        } else {
-            1.0
-        },
-    };
-    let combat_end_filtered = filter_effects_by_condition(&combat_end_effects, &combat_end_ctx);
-    record_ability_activations(
-        &mut trace,
-        rounds_completed,
-        "combat_end",
-        attacker,
-        &combat_end_filtered,
-        false,
-    );
+    // Combat-end abilities change nothing after the last round; they only appear in the trace.
+    if trace.is_enabled() {
+        let combat_end_effects = effects.for_timing(TimingWindow::CombatEnd);
+        let combat_end_ctx = CombatContext {
+            round_index: rounds_completed,
+            defender_hull_pct: 1.0
+                - (total_hull_damage / defender.hull_health.max(0.0)).min(1.0),
+            defender_shield_pct: if defender.shield_health > 0.0 {
+                defender_shield_remaining / defender.shield_health
+            } else {
+                1.0
+            },
+            attacker_hull_pct: 1.0
+                - (total_attacker_hull_damage / attacker.hull_health.max(0.0)).min(1.0),
+            attacker_shield_pct: if attacker.shield_health > 0.0 {
+                attacker_shield_remaining / attacker.shield_health
+            } else {
+                1.0
+            },
+            target_tags: &defender.tags,
+        };
+        let combat_end_filtered = apply_activation_limits(
+            &mut trace,
+            rounds_completed,
+            "combat_end",
+            None,
+            attacker,
+            activation_limits,
+            select_effects(combat_end_effects, &combat_end_ctx, combat_end_picked),
+        );
+        record_ability_activations(
+            &mut trace,
+            rounds_completed,
+            "combat_end",
+            None,
+            attacker,
+            &combat_end_filtered,
+            false,
+        );
+    }
 
     let total_damage = total_hull_damage + total_shield_damage;
     let attacker_hull_remaining = (attacker.hull_health - total_attacker_hull_damage).max(0.0);
@@ -991,6 +1516,9 @@ pub fn simulate_combat(
         attacker_hull_remaining: round_f64(attacker_hull_remaining),
         defender_hull_remaining: round_f64(defender_hull_remaining),
         defender_shield_remaining: round_f64(defender_shield_remaining),
+        attacker_shield_remaining: round_f64(attacker_shield_remaining),
+        attacker_damage: attacker_damage.rounded(),
+        defender_damage: defender_damage.rounded(),
         events: trace.events(),
     }
 }
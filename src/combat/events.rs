@@ -2,11 +2,176 @@
 
 use std::collections::BTreeMap;
 
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
 use crate::combat::types::{CombatEvent, EventSource};
 
+/// Version of the trace event shape, carried by every [CombatEvent]. Bump it when a typed
+/// payload's fields are renamed or removed, or an event's meaning changes; adding event types or
+/// keys does not need a bump.
+pub const TRACE_SCHEMA_VERSION: u32 = 1;
+
+/// An event type whose `values` have a fixed, typed shape. The engine builds these events from
+/// the struct, so its field names are the keys consumers see.
+pub trait TracePayload: Serialize + DeserializeOwned {
+    const EVENT_TYPE: &'static str;
+
+    fn to_values(&self) -> Map<String, Value> {
+        match serde_json::to_value(self) {
+            Ok(Value::Object(values)) => values,
+            _ => Map::new(),
+        }
+    }
+}
+
+/// `attack_roll`: one shot's attack roll.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttackRoll {
+    pub roll: f64,
+    pub base_attack: f64,
+    /// `base_attack` after pre-attack multipliers.
+    pub effective_attack: f64,
+}
+
+/// `mitigation_calc`: the defender's mitigation against one shot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MitigationCalc {
+    pub mitigation: f64,
+    /// `1 - mitigation`, floored at 0.
+    pub multiplier: f64,
+}
+
+/// `pierce_calc`: the attacker's pierce and the resulting damage-through factor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PierceCalc {
+    pub pierce: f64,
+    pub damage_through_factor: f64,
+}
+
+/// `crit_resolution`: the crit roll and the multiplier it applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CritResolution {
+    pub roll: f64,
+    pub is_crit: bool,
+    pub multiplier: f64,
+    pub hull_breach_active: bool,
+}
+
+/// `damage_application`: one shot's damage split between the defender's shield and hull.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DamageApplication {
+    pub damage_after_apex: f64,
+    pub shield_mitigation: f64,
+    pub shield_damage: f64,
+    pub hull_damage: f64,
+    /// Hull damage dealt so far in the fight, including this shot.
+    pub running_hull_damage: f64,
+    pub defender_shield_remaining: f64,
+    /// This shot took the defender's shield to 0.
+    pub shield_broke: bool,
+    pub assimilated_active: bool,
+    /// Isolytic damage taken from this shot (part of `damage_after_apex`, before the barrier).
+    #[serde(default)]
+    pub isolytic_damage: f64,
+}
+
+/// `counter_attack_roll`: one defender shot at the player ship, before damage is applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CounterAttackRoll {
+    pub base_attack: f64,
+    /// The player ship's mitigation against the shot.
+    pub mitigation: f64,
+    pub damage_through_factor: f64,
+    pub crit_roll: f64,
+    pub is_crit: bool,
+    pub crit_multiplier: f64,
+    pub proc_roll: f64,
+    pub proc_multiplier: f64,
+}
+
+/// `counter_damage_application`: one defender shot's damage split between the player ship's
+/// shield and hull.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CounterDamageApplication {
+    pub damage_after_apex: f64,
+    pub shield_mitigation: f64,
+    pub shield_damage: f64,
+    pub hull_damage: f64,
+    /// Hull the player ship has lost so far in the fight, including this shot.
+    pub running_hull_damage: f64,
+    pub attacker_shield_remaining: f64,
+    /// This shot took the player ship's shield to 0.
+    pub shield_broke: bool,
+}
+
+impl TracePayload for AttackRoll {
+    const EVENT_TYPE: &'static str = "attack_roll";
+}
+
+impl TracePayload for MitigationCalc {
+    const EVENT_TYPE: &'static str = "mitigation_calc";
+}
+
+impl TracePayload for PierceCalc {
+    const EVENT_TYPE: &'static str = "pierce_calc";
+}
+
+impl TracePayload for CritResolution {
+    const EVENT_TYPE: &'static str = "crit_resolution";
+}
+
+impl TracePayload for DamageApplication {
+    const EVENT_TYPE: &'static str = "damage_application";
+}
+
+impl TracePayload for CounterAttackRoll {
+    const EVENT_TYPE: &'static str = "counter_attack_roll";
+}
+
+impl TracePayload for CounterDamageApplication {
+    const EVENT_TYPE: &'static str = "counter_damage_application";
+}
+
+/// Typed view of a [CombatEvent]'s `values` (see [CombatEvent::payload]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventPayload {
+    AttackRoll(AttackRoll),
+    MitigationCalc(MitigationCalc),
+    PierceCalc(PierceCalc),
+    CritResolution(CritResolution),
+    DamageApplication(DamageApplication),
+    CounterAttackRoll(CounterAttackRoll),
+    CounterDamageApplication(CounterDamageApplication),
+    /// Event types without a typed payload, or values that do not fit it: the raw map.
+    Other(Map<String, Value>),
+}
+
+impl CombatEvent {
+    /// `values` as the event type's typed payload. Extra keys are ignored, so newer engines'
+    /// additions still parse; missing or mistyped keys fall back to [EventPayload::Other].
+    pub fn payload(&self) -> EventPayload {
+        fn typed<P: TracePayload>(values: &Map<String, Value>) -> Option<P> {
+            serde_json::from_value(Value::Object(values.clone())).ok()
+        }
+        let values = &self.values;
+        let payload = match self.event_type.as_str() {
+            AttackRoll::EVENT_TYPE => typed(values).map(EventPayload::AttackRoll),
+            MitigationCalc::EVENT_TYPE => typed(values).map(EventPayload::MitigationCalc),
+            PierceCalc::EVENT_TYPE => typed(values).map(EventPayload::PierceCalc),
+            CritResolution::EVENT_TYPE => typed(values).map(EventPayload::CritResolution),
+            DamageApplication::EVENT_TYPE => typed(values).map(EventPayload::DamageApplication),
+            CounterAttackRoll::EVENT_TYPE => typed(values).map(EventPayload::CounterAttackRoll),
+            CounterDamageApplication::EVENT_TYPE => {
+                typed(values).map(EventPayload::CounterDamageApplication)
+            }
+            _ => None,
+        };
+        payload.unwrap_or_else(|| EventPayload::Other(values.clone()))
+    }
+}
+
 /// Round to 6 decimal places for stable trace output.
 pub(crate) fn round_f64(value: f64) -> f64 {
     (value * 1_000_000.0).round() / 1_000_000.0
@@ -56,8 +221,8 @@ fn to_canonical_json<T: Serialize>(value: &T) -> Result<String, serde_json::Erro
     serde_json::to_string_pretty(&sorted)
 }
 
-pub fn serialize_events_json(events: &[CombatEvent]) -> Result<String, serde_json::Error> {
-    let payload: Vec<Value> = events
+fn events_payload(events: &[CombatEvent]) -> Vec<Value> {
+    events
         .iter()
         .map(|event| {
             let mut object = Map::new();
@@ -68,13 +233,25 @@ pub fn serialize_events_json(events: &[CombatEvent]) -> Result<String, serde_jso
             object.insert("round_index".to_string(), Value::from(event.round_index));
             object.insert("phase".to_string(), Value::String(event.phase.clone()));
             object.insert("source".to_string(), serialize_source(&event.source));
+            object.insert("schema_version".to_string(), Value::from(event.schema_version));
             object.insert("values".to_string(), Value::Object(event.values.clone()));
             if let Some(wi) = event.weapon_index {
                 object.insert("weapon_index".to_string(), Value::from(wi));
             }
             Value::Object(object)
         })
-        .collect();
+        .collect()
+}
+
+pub fn serialize_events_json(events: &[CombatEvent]) -> Result<String, serde_json::Error> {
+    to_canonical_json(&events_payload(events))
+}
 
-    to_canonical_json(&payload)
+/// MessagePack encoding of [serialize_events_json]'s document: the same keys and values, with
+/// objects as string-keyed maps, so decoding it gives the JSON document back.
+pub fn serialize_events_msgpack(
+    events: &[CombatEvent],
+) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+    let sorted = sort_json(Value::Array(events_payload(events)));
+    rmp_serde::to_vec_named(&sorted)
 }
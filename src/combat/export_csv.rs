@@ -9,6 +9,7 @@ use crate::combat::{
     mitigation, pierce_damage_through_bonus, AttackerStats, Combatant, CrewConfiguration,
     DefenderStats, ShipType,
 };
+use crate::data::hostile::HostileRecord;
 use crate::optimizer::monte_carlo::crew_from_officer_names;
 
 /// Parsed game fight export (multi-section TSV).
@@ -40,6 +41,14 @@ pub struct FightExport {
     pub player_officer_three: Option<String>,
     /// Attacker (player) ship type inferred from player_ship_name.
     pub attacker_ship_type: ShipType,
+    /// Enemy name from summary (e.g. "Takret Militia").
+    pub enemy_name: Option<String>,
+    /// Enemy level from summary.
+    pub enemy_level: Option<u32>,
+    /// Enemy ship name from summary (e.g. "HOSTILE BATTLESHIP").
+    pub enemy_ship_name: Option<String>,
+    /// Defender (enemy) ship type inferred from enemy_ship_name.
+    pub defender_ship_type: ShipType,
 }
 
 /// Single event row from the events section.
@@ -53,6 +62,8 @@ pub struct FightExportEvent {
     pub critical_hit: bool,
     /// Sub-round weapon index when an optional `Weapon Index` column is present in the export.
     pub weapon_index: Option<u32>,
+    /// Name of the ship's owner the event belongs to (the shooter for `Attack` rows).
+    pub attacker_name: Option<String>,
 }
 
 fn parse_tsv_row(line: &str) -> Vec<String> {
@@ -94,6 +105,7 @@ struct EventColumns {
     shield_damage: Option<usize>,
     total_damage: Option<usize>,
     weapon_index: Option<usize>,
+    attacker_name: Option<usize>,
 }
 
 fn find_event_columns(header: &[String]) -> EventColumns {
@@ -110,6 +122,7 @@ fn find_event_columns(header: &[String]) -> EventColumns {
         shield_damage: find(header, "Shield Damage"),
         total_damage: find(header, "Total Damage"),
         weapon_index: find(header, "Weapon Index"),
+        attacker_name: find(header, "Attacker Name"),
     }
 }
 
@@ -157,6 +170,10 @@ pub fn parse_fight_export(input: &str) -> Result<FightExport, String> {
     let mut player_officer_two: Option<String> = None;
     let mut player_officer_three: Option<String> = None;
     let mut attacker_ship_type = ShipType::Battleship;
+    let mut enemy_name: Option<String> = None;
+    let mut enemy_level: Option<u32> = None;
+    let mut enemy_ship_name: Option<String> = None;
+    let mut defender_ship_type = ShipType::Battleship;
 
     let mut i = 0;
     while i < lines.len() {
@@ -190,6 +207,10 @@ pub fn parse_fight_export(input: &str) -> Result<FightExport, String> {
             player_officer_two = optional_cell(player_map.get("Officer Two"));
             player_officer_three = optional_cell(player_map.get("Officer Three"));
             attacker_ship_type = ship_type_from_name(player_ship_name.as_deref().unwrap_or(""));
+            enemy_name = optional_cell(enemy_map.get("Player Name"));
+            enemy_level = enemy_map.get("Player Level").and_then(|s| s.parse().ok());
+            enemy_ship_name = optional_cell(enemy_map.get("Ship Name"));
+            defender_ship_type = ship_type_from_name(enemy_ship_name.as_deref().unwrap_or(""));
             i += 2;
             continue;
         }
@@ -240,6 +261,9 @@ pub fn parse_fight_export(input: &str) -> Result<FightExport, String> {
                 let total = get_event_f64(&event_row, event_columns.total_damage);
                 let critical = get_event_bool_yes(&event_row, event_columns.critical_hit);
                 let weapon_index = get_event_u32(&event_row, event_columns.weapon_index);
+                let attacker_name = optional_cell(
+                    event_columns.attacker_name.and_then(|col| event_row.get(col)),
+                );
                 events.push(FightExportEvent {
                     round,
                     event_type,
@@ -248,6 +272,7 @@ pub fn parse_fight_export(input: &str) -> Result<FightExport, String> {
                     total_damage: total,
                     critical_hit: critical,
                     weapon_index,
+                    attacker_name,
                 });
                 i += 1;
             }
@@ -279,6 +304,10 @@ pub fn parse_fight_export(input: &str) -> Result<FightExport, String> {
         player_officer_two,
         player_officer_three,
         attacker_ship_type,
+        enemy_name,
+        enemy_level,
+        enemy_ship_name,
+        defender_ship_type,
     })
 }
 
@@ -323,25 +352,14 @@ pub fn export_to_attacker(
         accuracy,
     };
     let pierce = pierce_damage_through_bonus(defender_stats, attacker_stats, defender_ship_type);
-    Combatant {
-        id,
-        attack,
-        mitigation: 0.0,
-        pierce,
-        crit_chance: get_f64(player_fleet, "Critical Chance"),
-        crit_multiplier: get_f64(player_fleet, "Critical Damage"),
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: get_f64(player_fleet, "Hull Health"),
-        shield_health: get_f64(player_fleet, "Shield Health"),
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    }
+    Combatant::builder(id)
+        .attack(attack)
+        .pierce(pierce)
+        .crit_chance(get_f64(player_fleet, "Critical Chance"))
+        .crit_multiplier(get_f64(player_fleet, "Critical Damage"))
+        .hull_health(get_f64(player_fleet, "Hull Health"))
+        .shield_health(get_f64(player_fleet, "Shield Health"))
+        .build_unchecked()
 }
 
 /// Build Combatant for the defender (enemy) from export fleet row and attacker stats for mitigation.
@@ -357,25 +375,13 @@ pub fn export_to_defender(
         dodge: get_f64(enemy_fleet, "Dodge"),
     };
     let mitigation_val = mitigation(defender_stats, attacker_stats, ship_type);
-    Combatant {
-        id,
-        attack: 0.0,
-        mitigation: mitigation_val,
-        pierce: 0.0,
-        crit_chance: get_f64(enemy_fleet, "Critical Chance"),
-        crit_multiplier: get_f64(enemy_fleet, "Critical Damage"),
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: get_f64(enemy_fleet, "Hull Health"),
-        shield_health: get_f64(enemy_fleet, "Shield Health"),
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    }
+    Combatant::builder(id)
+        .mitigation(mitigation_val)
+        .crit_chance(get_f64(enemy_fleet, "Critical Chance"))
+        .crit_multiplier(get_f64(enemy_fleet, "Critical Damage"))
+        .hull_health(get_f64(enemy_fleet, "Hull Health"))
+        .shield_health(get_f64(enemy_fleet, "Shield Health"))
+        .build_unchecked()
 }
 
 /// Build attacker and defender Combatants from a parsed FightExport.
@@ -391,7 +397,7 @@ pub fn export_to_combatants(export: &FightExport) -> (Combatant, Combatant) {
         shield_deflection: get_f64(&export.enemy_fleet, "Shield Deflection"),
         dodge: get_f64(&export.enemy_fleet, "Dodge"),
     };
-    let defender_ship_type = ShipType::Battleship; // from "HOSTILE BATTLESHIP" in sample
+    let defender_ship_type = export.defender_ship_type;
     let attacker = export_to_attacker(
         &export.player_fleet,
         defender_stats,
@@ -407,6 +413,84 @@ pub fn export_to_combatants(export: &FightExport) -> (Combatant, Combatant) {
     (attacker, defender)
 }
 
+/// Build a hostile record for the enemy of a recorded fight, so the optimizer can target an enemy
+/// that is not in the hostile dataset (see [crate::data::hostile::save_hostile_record]).
+///
+/// Hull, shields, defense and offensive stats come from the enemy fleet row. Two things are
+/// inferred from the events: the shield/hull split of the player's shots while the enemy's shield
+/// was up (`shield_mitigation`; None when no shot hit a shield) and the enemy's attacks per round
+/// (`shots_per_round`). The id is `fight_<name>_<level>`, e.g. `fight_takret_militia_10`.
+pub fn export_to_hostile_record(export: &FightExport) -> HostileRecord {
+    let enemy = &export.enemy_fleet;
+    let name = export.enemy_name.clone().unwrap_or_else(|| "Recorded hostile".to_string());
+    let level = export.enemy_level.unwrap_or(0);
+    let slug: String = name
+        .to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+
+    // The export reports the uncapped split of each shot, so every player shot before the enemy's
+    // "Shield Depleted" row shows the shield mitigation.
+    let mut shield_shares = Vec::new();
+    for event in &export.events {
+        let by_enemy = event.attacker_name.is_some() && event.attacker_name == export.enemy_name;
+        if by_enemy && event.event_type.eq_ignore_ascii_case("Shield Depleted") {
+            break;
+        }
+        let landed = event.shield_damage + event.hull_damage;
+        if !by_enemy && event.event_type.eq_ignore_ascii_case("Attack") && landed > 0.0 {
+            shield_shares.push(event.shield_damage / landed);
+        }
+    }
+    let shield_mitigation = (!shield_shares.is_empty() && get_f64(enemy, "Shield Health") > 0.0)
+        .then(|| shield_shares.iter().sum::<f64>() / shield_shares.len() as f64);
+
+    let enemy_attacks = export
+        .events
+        .iter()
+        .filter(|e| e.event_type.eq_ignore_ascii_case("Attack"))
+        .filter(|e| e.attacker_name.is_some() && e.attacker_name == export.enemy_name)
+        .count();
+    let shots_per_round =
+        (export.rounds > 0).then(|| enemy_attacks as f64 / f64::from(export.rounds));
+
+    HostileRecord {
+        id: format!("fight_{slug}_{level}"),
+        hostile_name: name,
+        level,
+        ship_class: ship_class_name(export.defender_ship_type).to_string(),
+        armor: get_f64(enemy, "Armour"),
+        shield_deflection: get_f64(enemy, "Shield Deflection"),
+        dodge: get_f64(enemy, "Dodge"),
+        hull_health: get_f64(enemy, "Hull Health"),
+        shield_health: get_f64(enemy, "Shield Health"),
+        shield_mitigation,
+        stat_health: get_f64(enemy, "Health"),
+        stat_defense: get_f64(enemy, "Defense"),
+        stat_attack: get_f64(enemy, "Attack"),
+        dpr: get_f64(enemy, "Damage Per Round"),
+        accuracy: get_f64(enemy, "Accuracy"),
+        armor_piercing: get_f64(enemy, "Armour Pierce"),
+        shield_piercing: get_f64(enemy, "Shield Pierce"),
+        crit_chance: get_f64(enemy, "Critical Chance"),
+        crit_damage: get_f64(enemy, "Critical Damage"),
+        shots_per_round,
+        ..HostileRecord::default()
+    }
+}
+
+fn ship_class_name(ship_type: ShipType) -> &'static str {
+    match ship_type {
+        ShipType::Survey => "survey",
+        ShipType::Armada => "armada",
+        ShipType::Battleship => "battleship",
+        ShipType::Explorer => "explorer",
+        ShipType::Interceptor => "interceptor",
+    }
+}
+
 /// Build crew configuration from export officer slots.
 /// Officer One = captain, Officer Two/Three = bridge; below_decks = [].
 /// Returns default crew if no officers present.
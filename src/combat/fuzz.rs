@@ -0,0 +1,469 @@
+//! Seeded fuzzer for engine invariants: random combatants, crews and configs are fought and
+//! checked for non-negative damage, hull and shields within bounds, determinism, and attack
+//! monotonicity. Failures are shrunk to a small [SimulationSnapshot] that still fails, so a new
+//! mechanic that breaks an invariant comes with a replayable repro (`kobayashi snapshot run`).
+//!
+//! `kobayashi fuzz [--cases <n>] [--seed <u64>] [--out-dir <dir>]` runs it from the command line.
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::abilities::{
+    Ability, AbilityClass, AbilityEffect, CrewConfiguration, CrewSeat, CrewSeatContext,
+    TimingWindow,
+};
+use crate::combat::dot::DotChannel;
+use crate::combat::rng::{unit_roll, Rng, RngMode};
+use crate::combat::snapshot::SimulationSnapshot;
+use crate::combat::types::{
+    Combatant, InitiativeMode, SimulationConfig, SimulationResult, WeaponStats,
+};
+use crate::parallel::iteration_seed;
+
+/// Cases `kobayashi fuzz` runs when `--cases` is unset.
+pub const DEFAULT_FUZZ_CASES: u32 = 1000;
+/// Attack factor of the monotonicity check.
+pub const MONOTONICITY_ATTACK_FACTOR: f64 = 1.25;
+/// Slack for float comparisons, relative to the compared value (absolute below 1.0).
+const TOLERANCE: f64 = 1e-9;
+
+/// One property every fight must satisfy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FuzzInvariant {
+    /// Total damage and every traced `*damage` value are finite and ≥ 0.
+    NonNegativeDamage,
+    /// Remaining hull of both ships is within 0 and their starting hull.
+    HullBounds,
+    /// Remaining shields of both ships are within 0 and their starting shields.
+    ShieldBounds,
+    /// The same inputs give the same result.
+    Deterministic,
+    /// More attack (every weapon × [MONOTONICITY_ATTACK_FACTOR]) never leaves the defender with
+    /// more hull or destroys it later. Shields are not compared: a faster kill can leave more.
+    AttackMonotonic,
+}
+
+impl FuzzInvariant {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::NonNegativeDamage => "non_negative_damage",
+            Self::HullBounds => "hull_bounds",
+            Self::ShieldBounds => "shield_bounds",
+            Self::Deterministic => "deterministic",
+            Self::AttackMonotonic => "attack_monotonic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzViolation {
+    pub invariant: FuzzInvariant,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzFailure {
+    /// Case index; `fuzz_case(seed, case)` regenerates the unshrunk input.
+    pub case: u32,
+    pub violation: FuzzViolation,
+    /// Smallest input found that still breaks `violation.invariant`, with the seed, case and
+    /// message in its `context`.
+    pub snapshot: SimulationSnapshot,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FuzzReport {
+    pub seed: u64,
+    pub cases: u32,
+    /// One entry per failing case (its first violated invariant).
+    pub failures: Vec<FuzzFailure>,
+}
+
+/// Uniform draws for case generation.
+struct Gen(Rng);
+
+impl Gen {
+    fn unit(&mut self) -> f64 {
+        unit_roll(self.0.next_u64())
+    }
+
+    fn range(&mut self, lo: f64, hi: f64) -> f64 {
+        lo + (hi - lo) * self.unit()
+    }
+
+    /// `range` most of the time, 0 otherwise, so cases also cover the stat being absent.
+    fn maybe(&mut self, lo: f64, hi: f64) -> f64 {
+        if self.chance(0.3) {
+            0.0
+        } else {
+            self.range(lo, hi)
+        }
+    }
+
+    fn chance(&mut self, p: f64) -> bool {
+        self.unit() < p
+    }
+
+    fn below(&mut self, n: u32) -> u32 {
+        ((self.unit() * f64::from(n)) as u32).min(n.saturating_sub(1))
+    }
+}
+
+fn random_combatant(gen: &mut Gen, id: &str, defender: bool) -> Combatant {
+    let weapons: Vec<WeaponStats> = (0..gen.below(4))
+        .map(|_| WeaponStats {
+            attack: gen.range(5.0, 800.0),
+            shots: gen.chance(0.5).then(|| 1 + gen.below(4)),
+        })
+        .collect();
+    let mut builder = Combatant::builder(id)
+        .attack(gen.range(10.0, 2_000.0))
+        .mitigation(gen.range(0.0, 0.9))
+        .pierce(gen.maybe(0.0, 0.5))
+        .crit_chance(gen.maybe(0.0, 1.0))
+        .crit_multiplier(gen.range(1.0, 3.0))
+        .proc_chance(gen.maybe(0.0, 0.5))
+        .proc_multiplier(gen.range(1.0, 2.0))
+        .hull_health(gen.range(100.0, 100_000.0))
+        .shield_health(gen.maybe(0.0, 50_000.0))
+        .shield_mitigation(gen.range(0.0, 1.0))
+        .apex_barrier(gen.maybe(0.0, 10_000.0))
+        .apex_shred(gen.maybe(0.0, 1.0))
+        .isolytic_damage(gen.maybe(0.0, 0.5))
+        .isolytic_defense(gen.maybe(0.0, 1_000.0))
+        .weapons(weapons);
+    if defender {
+        builder = builder.end_of_round_damage(gen.maybe(0.0, 500.0));
+        if gen.chance(0.2) {
+            builder = builder.charged_attack(1 + gen.below(5), gen.range(100.0, 20_000.0));
+        }
+    }
+    builder.build().expect("generated stats are in range")
+}
+
+fn random_effect(gen: &mut Gen) -> AbilityEffect {
+    let duration = 1 + gen.below(4);
+    match gen.below(14) {
+        0 => AbilityEffect::AttackMultiplier(gen.range(-0.3, 1.0)),
+        1 => AbilityEffect::PierceBonus(gen.range(0.0, 0.3)),
+        2 => AbilityEffect::Morale(gen.range(0.0, 1.0)),
+        3 => AbilityEffect::Assimilated {
+            chance: gen.range(0.0, 1.0),
+            duration_rounds: duration,
+        },
+        4 => AbilityEffect::HullBreach {
+            chance: gen.range(0.0, 1.0),
+            duration_rounds: duration,
+            requires_critical: gen.chance(0.5),
+        },
+        5 => AbilityEffect::Burning {
+            chance: gen.range(0.0, 1.0),
+            duration_rounds: duration,
+        },
+        6 => AbilityEffect::DamageOverTime {
+            channel: DotChannel::ALL[gen.below(3) as usize],
+            chance: gen.range(0.0, 1.0),
+            hull_pct: gen.range(0.0, 0.05),
+            duration_rounds: duration,
+        },
+        7 => AbilityEffect::ShieldRegen(gen.range(0.0, 2_000.0)),
+        8 => AbilityEffect::HullRegen(gen.range(0.0, 2_000.0)),
+        9 => AbilityEffect::ApexShredBonus(gen.range(0.0, 0.5)),
+        10 => AbilityEffect::ApexBarrierBonus(gen.range(0.0, 5_000.0)),
+        11 => AbilityEffect::IsolyticDamageBonus(gen.range(0.0, 0.5)),
+        12 => AbilityEffect::ShieldMitigationBonus(gen.range(0.0, 0.3)),
+        _ => AbilityEffect::ShotsBonus {
+            chance: gen.range(0.0, 1.0),
+            bonus_pct: gen.range(0.0, 1.0),
+            duration_rounds: duration,
+        },
+    }
+}
+
+fn random_crew(gen: &mut Gen) -> CrewConfiguration {
+    const SEATS: [(CrewSeat, AbilityClass); 3] = [
+        (CrewSeat::Captain, AbilityClass::CaptainManeuver),
+        (CrewSeat::Bridge, AbilityClass::BridgeAbility),
+        (CrewSeat::BelowDeck, AbilityClass::BelowDeck),
+    ];
+    const TIMINGS: [TimingWindow; 5] = [
+        TimingWindow::CombatBegin,
+        TimingWindow::RoundStart,
+        TimingWindow::AttackPhase,
+        TimingWindow::DefensePhase,
+        TimingWindow::RoundEnd,
+    ];
+    let seats = (0..gen.below(6))
+        .map(|i| {
+            let (seat, class) = SEATS[gen.below(3) as usize];
+            let ability = Ability {
+                name: format!("fuzz ability {i}"),
+                class,
+                timing: TIMINGS[gen.below(5) as usize],
+                boostable: false,
+                effect: random_effect(gen),
+                condition: None,
+                max_activations: gen.chance(0.2).then(|| 1 + gen.below(3)),
+                cooldown_rounds: if gen.chance(0.2) { 1 + gen.below(2) } else { 0 },
+            };
+            CrewSeatContext::legacy(seat, ability, gen.chance(0.2))
+        })
+        .collect();
+    CrewConfiguration { seats }
+}
+
+/// The random fight for `case` of a run seeded with `seed`.
+pub fn fuzz_case(seed: u64, case: u32) -> SimulationSnapshot {
+    let mut gen = Gen(Rng::new(iteration_seed(seed, case as usize)));
+    let attacker = random_combatant(&mut gen, "fuzz attacker", false);
+    let defender = random_combatant(&mut gen, "fuzz defender", true);
+    let crew = random_crew(&mut gen);
+    let initiative = [
+        InitiativeMode::Simultaneous,
+        InitiativeMode::AttackerFirst,
+        InitiativeMode::DefenderFirst,
+    ][gen.below(3) as usize];
+    let config = SimulationConfig::builder()
+        .rounds(1 + gen.below(30))
+        .seed(gen.0.next_u64())
+        .trace(gen.chance(0.5))
+        .rng_mode(if gen.chance(0.8) { RngMode::Counter } else { RngMode::Legacy })
+        .attacker_delay_rounds(if gen.chance(0.2) { gen.below(4) } else { 0 })
+        .initiative(initiative)
+        .build();
+    SimulationSnapshot::new(attacker, defender, crew, config)
+}
+
+fn within(value: f64, max: f64) -> bool {
+    value >= -TOLERANCE && value <= max + TOLERANCE * max.abs().max(1.0)
+}
+
+/// Every invariant `snapshot` breaks, in [FuzzInvariant] order.
+pub fn check_invariants(snapshot: &SimulationSnapshot) -> Vec<FuzzViolation> {
+    let mut violations = Vec::new();
+    let mut violate = |invariant, message: String| {
+        violations.push(FuzzViolation { invariant, message });
+    };
+    let result = snapshot.run();
+
+    if !result.total_damage.is_finite() || result.total_damage < 0.0 {
+        violate(
+            FuzzInvariant::NonNegativeDamage,
+            format!("total_damage is {}", result.total_damage),
+        );
+    } else if let Some((event, key, value)) = negative_damage_value(&result) {
+        violate(FuzzInvariant::NonNegativeDamage, format!("{event} has {key} = {value}"));
+    }
+
+    for (side, remaining, max) in [
+        ("attacker", result.attacker_hull_remaining, snapshot.attacker.hull_health),
+        ("defender", result.defender_hull_remaining, snapshot.defender.hull_health),
+    ] {
+        if !within(remaining, max) {
+            violate(
+                FuzzInvariant::HullBounds,
+                format!("{side} hull remaining {remaining} outside 0..={max}"),
+            );
+        }
+    }
+    for (side, remaining, max) in [
+        ("attacker", result.attacker_shield_remaining, snapshot.attacker.shield_health),
+        ("defender", result.defender_shield_remaining, snapshot.defender.shield_health),
+    ] {
+        if !within(remaining, max) {
+            violate(
+                FuzzInvariant::ShieldBounds,
+                format!("{side} shield remaining {remaining} outside 0..={max}"),
+            );
+        }
+    }
+
+    let replay = snapshot.run();
+    if serde_json::to_value(&replay).ok() != serde_json::to_value(&result).ok() {
+        violate(
+            FuzzInvariant::Deterministic,
+            "replaying the same inputs gave a different result".to_string(),
+        );
+    }
+
+    let mut stronger = snapshot.attacker.clone();
+    stronger.attack *= MONOTONICITY_ATTACK_FACTOR;
+    for weapon in &mut stronger.weapons {
+        weapon.attack *= MONOTONICITY_ATTACK_FACTOR;
+    }
+    let boosted = SimulationSnapshot { attacker: stronger, ..snapshot.clone() }.run();
+    let (before, after) = (result.defender_hull_remaining, boosted.defender_hull_remaining);
+    if after > before + TOLERANCE * before.abs().max(1.0) {
+        violate(
+            FuzzInvariant::AttackMonotonic,
+            format!(
+                "defender hull left rose from {before} to {after} with attack x{}",
+                MONOTONICITY_ATTACK_FACTOR
+            ),
+        );
+    } else if let (Some(before), Some(after)) = (destroyed_in(&result), destroyed_in(&boosted)) {
+        if after > before {
+            violate(
+                FuzzInvariant::AttackMonotonic,
+                format!(
+                    "defender destroyed in round {after} instead of {before} with attack x{}",
+                    MONOTONICITY_ATTACK_FACTOR
+                ),
+            );
+        }
+    }
+    violations
+}
+
+/// Round the defender was destroyed in, if it was.
+fn destroyed_in(result: &SimulationResult) -> Option<u32> {
+    (result.defender_hull_remaining <= 0.0).then_some(result.rounds_simulated)
+}
+
+/// First traced value named `*damage` that is negative or not finite, with its event labelled
+/// `<event_type> (round <n>)`.
+fn negative_damage_value(result: &SimulationResult) -> Option<(String, String, f64)> {
+    result.events.iter().find_map(|event| {
+        event.values.iter().find_map(|(key, value)| {
+            let value = value.as_f64()?;
+            (key.ends_with("damage") && !(value.is_finite() && value >= 0.0)).then(|| {
+                let label = format!("{} (round {})", event.event_type, event.round_index);
+                (label, key.clone(), value)
+            })
+        })
+    })
+}
+
+fn breaks(snapshot: &SimulationSnapshot, invariant: FuzzInvariant) -> bool {
+    check_invariants(snapshot).iter().any(|v| v.invariant == invariant)
+}
+
+/// Simplifications tried while shrinking, each returning None when it does not apply.
+type Shrink = fn(&SimulationSnapshot) -> Option<SimulationSnapshot>;
+
+const SHRINKS: [Shrink; 6] = [
+    |s| (s.config.rounds > 1).then(|| {
+        let mut s = s.clone();
+        s.config.rounds /= 2;
+        s
+    }),
+    |s| (s.config.attacker_delay_rounds > 0).then(|| {
+        let mut s = s.clone();
+        s.config.attacker_delay_rounds = 0;
+        s
+    }),
+    |s| (!s.attacker.weapons.is_empty() || !s.defender.weapons.is_empty()).then(|| {
+        let mut s = s.clone();
+        s.attacker.weapons.clear();
+        s.defender.weapons.clear();
+        s
+    }),
+    |s| s.defender.charged_attack.map(|_| {
+        let mut s = s.clone();
+        s.defender.charged_attack = None;
+        s
+    }),
+    |s| (!s.config.initiative.is_simultaneous()).then(|| {
+        let mut s = s.clone();
+        s.config.initiative = InitiativeMode::Simultaneous;
+        s
+    }),
+    |s| {
+        let zeroed = |c: &Combatant| Combatant {
+            proc_chance: 0.0,
+            apex_barrier: 0.0,
+            apex_shred: 0.0,
+            isolytic_damage: 0.0,
+            isolytic_defense: 0.0,
+            ..c.clone()
+        };
+        let (attacker, defender) = (zeroed(&s.attacker), zeroed(&s.defender));
+        (attacker != s.attacker || defender != s.defender).then(|| SimulationSnapshot {
+            attacker,
+            defender,
+            ..s.clone()
+        })
+    },
+];
+
+/// Greedily simplifies `snapshot` (fewer crew rows and rounds, optional mechanics off) while it
+/// still breaks `invariant`.
+pub fn shrink(snapshot: &SimulationSnapshot, invariant: FuzzInvariant) -> SimulationSnapshot {
+    let mut best = snapshot.clone();
+    loop {
+        let mut progressed = false;
+        let mut i = best.crew.seats.len();
+        while i > 0 {
+            i -= 1;
+            let mut candidate = best.clone();
+            candidate.crew.seats.remove(i);
+            if breaks(&candidate, invariant) {
+                best = candidate;
+                progressed = true;
+            }
+        }
+        for simplify in SHRINKS {
+            while let Some(candidate) = simplify(&best) {
+                if !breaks(&candidate, invariant) {
+                    break;
+                }
+                best = candidate;
+                progressed = true;
+            }
+        }
+        if !progressed {
+            return best;
+        }
+    }
+}
+
+/// Runs `cases` random fights seeded from `seed` and shrinks each failing one.
+pub fn run_fuzz(seed: u64, cases: u32) -> FuzzReport {
+    let failures = (0..cases)
+        .filter_map(|case| {
+            let snapshot = fuzz_case(seed, case);
+            let violation = check_invariants(&snapshot).into_iter().next()?;
+            let snapshot = shrink(&snapshot, violation.invariant).with_context(serde_json::json!({
+                "fuzz_seed": seed,
+                "fuzz_case": case,
+                "invariant": violation.invariant.as_str(),
+                "message": violation.message,
+            }));
+            Some(FuzzFailure { case, violation, snapshot })
+        })
+        .collect();
+    FuzzReport { seed, cases, failures }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cases_are_reproducible_and_varied() {
+        assert_eq!(fuzz_case(3, 5), fuzz_case(3, 5));
+        assert_ne!(fuzz_case(3, 5), fuzz_case(3, 6));
+        assert_ne!(fuzz_case(3, 5), fuzz_case(4, 5));
+    }
+
+    #[test]
+    fn engine_holds_every_invariant_on_a_short_run() {
+        let report = run_fuzz(0, 60);
+        assert_eq!(report.cases, 60);
+        assert!(report.failures.is_empty(), "{:?}", report.failures.first());
+    }
+
+    #[test]
+    fn shrink_keeps_the_violation_and_drops_what_it_does_not_need() {
+        let mut snapshot = fuzz_case(11, 0);
+        snapshot.crew = random_crew(&mut Gen(Rng::new(1)));
+        // An attacker already below zero hull breaks the bounds whatever else is set.
+        snapshot.attacker.hull_health = -1.0;
+        assert!(breaks(&snapshot, FuzzInvariant::HullBounds));
+        let small = shrink(&snapshot, FuzzInvariant::HullBounds);
+        assert!(breaks(&small, FuzzInvariant::HullBounds));
+        assert!(small.crew.seats.is_empty());
+        assert_eq!(small.config.rounds, 1);
+        assert!(small.attacker.weapons.is_empty());
+    }
+}
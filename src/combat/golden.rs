@@ -0,0 +1,378 @@
+//! Golden values for cross-implementation parity: a matrix of canonical inputs to the
+//! mitigation, pierce, isolytic, apex and engine code paths together with this build's outputs.
+//!
+//! `kobayashi golden generate` writes the matrix as JSON; `kobayashi golden verify <file>`
+//! re-evaluates every case in another implementation's file here and reports the outputs that
+//! differ. tests/fixtures/golden/golden_values.json is the checked-in copy the combat tests use.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::abilities::CrewConfiguration;
+use crate::combat::damage::{compute_apex_damage_factor, compute_isolytic_taken};
+use crate::combat::engine::simulate_combat;
+use crate::combat::mitigation::{isolytic_damage, mitigation, pierce_damage_through_bonus};
+use crate::combat::types::{
+    AttackerStats, Combatant, DefenderStats, ShipType, SimulationConfig, TraceMode,
+};
+
+/// Bumped when a case's inputs or output keys change meaning.
+pub const GOLDEN_FORMAT_VERSION: u32 = 1;
+
+/// Default `verify` tolerance, relative to the expected value (absolute below 1.0).
+pub const DEFAULT_GOLDEN_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenMatrix {
+    pub format_version: u32,
+    /// Implementation that produced the outputs, e.g. `kobayashi 0.1.0`.
+    pub generator: String,
+    pub cases: Vec<GoldenCase>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenCase {
+    /// Stable id, `<function>/<variant>`.
+    pub id: String,
+    #[serde(flatten)]
+    pub input: GoldenInput,
+    /// Output name → value; booleans are 0/1.
+    pub outputs: BTreeMap<String, f64>,
+}
+
+/// Inputs of one case, tagged by the function under test.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "function", rename_all = "snake_case")]
+pub enum GoldenInput {
+    /// [mitigation] and [pierce_damage_through_bonus].
+    Mitigation {
+        ship_type: ShipType,
+        armor: f64,
+        shield_deflection: f64,
+        dodge: f64,
+        armor_piercing: f64,
+        shield_piercing: f64,
+        accuracy: f64,
+    },
+    /// [isolytic_damage] and [compute_isolytic_taken].
+    Isolytic {
+        damage: f64,
+        isolytic_damage: f64,
+        isolytic_cascade: f64,
+        isolytic_defense: f64,
+    },
+    /// [compute_apex_damage_factor].
+    Apex { apex_shred: f64, apex_barrier: f64 },
+    /// One uncrewed [simulate_combat] fight (trace off).
+    Engine {
+        attacker: Box<Combatant>,
+        defender: Box<Combatant>,
+        config: SimulationConfig,
+    },
+}
+
+impl GoldenInput {
+    /// Outputs of this build for these inputs.
+    pub fn evaluate(&self) -> BTreeMap<String, f64> {
+        let mut out = BTreeMap::new();
+        match self {
+            Self::Mitigation {
+                ship_type,
+                armor,
+                shield_deflection,
+                dodge,
+                armor_piercing,
+                shield_piercing,
+                accuracy,
+            } => {
+                let defender = DefenderStats {
+                    armor: *armor,
+                    shield_deflection: *shield_deflection,
+                    dodge: *dodge,
+                };
+                let attacker = AttackerStats {
+                    armor_piercing: *armor_piercing,
+                    shield_piercing: *shield_piercing,
+                    accuracy: *accuracy,
+                };
+                out.insert("mitigation".into(), mitigation(defender, attacker, *ship_type));
+                out.insert(
+                    "pierce_damage_through_bonus".into(),
+                    pierce_damage_through_bonus(defender, attacker, *ship_type),
+                );
+            }
+            Self::Isolytic {
+                damage,
+                isolytic_damage: bonus,
+                isolytic_cascade,
+                isolytic_defense,
+            } => {
+                out.insert(
+                    "isolytic_damage".into(),
+                    isolytic_damage(*damage, *bonus, *isolytic_cascade),
+                );
+                out.insert(
+                    "isolytic_taken".into(),
+                    compute_isolytic_taken(*damage, *bonus, *isolytic_defense, *isolytic_cascade),
+                );
+            }
+            Self::Apex {
+                apex_shred,
+                apex_barrier,
+            } => {
+                out.insert(
+                    "apex_damage_factor".into(),
+                    compute_apex_damage_factor(*apex_shred, *apex_barrier),
+                );
+            }
+            Self::Engine {
+                attacker,
+                defender,
+                config,
+            } => {
+                let config = SimulationConfig {
+                    trace_mode: TraceMode::Off,
+                    ..*config
+                };
+                let result =
+                    simulate_combat(attacker, defender, config, &CrewConfiguration::default());
+                out.insert("total_damage".into(), result.total_damage);
+                out.insert("attacker_won".into(), f64::from(u8::from(result.attacker_won)));
+                out.insert(
+                    "winner_by_round_limit".into(),
+                    f64::from(u8::from(result.winner_by_round_limit)),
+                );
+                out.insert("rounds_simulated".into(), f64::from(result.rounds_simulated));
+                out.insert("attacker_hull_remaining".into(), result.attacker_hull_remaining);
+                out.insert("defender_hull_remaining".into(), result.defender_hull_remaining);
+                out.insert("defender_shield_remaining".into(), result.defender_shield_remaining);
+            }
+        }
+        out
+    }
+}
+
+/// One output that differs from this build by more than the tolerance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GoldenMismatch {
+    pub case_id: String,
+    pub output: String,
+    pub expected: f64,
+    pub actual: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GoldenReport {
+    /// Outputs compared (present in both the file and this build).
+    pub compared: usize,
+    pub mismatches: Vec<GoldenMismatch>,
+    /// `case_id/output` keys in the file this build does not produce.
+    pub unknown_outputs: Vec<String>,
+}
+
+impl GoldenReport {
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty() && self.unknown_outputs.is_empty()
+    }
+}
+
+/// The canonical case matrix with this build's outputs.
+pub fn generate_golden_matrix() -> GoldenMatrix {
+    let cases = canonical_inputs()
+        .into_iter()
+        .map(|(id, input)| {
+            let outputs = input.evaluate();
+            GoldenCase { id, input, outputs }
+        })
+        .collect();
+    GoldenMatrix {
+        format_version: GOLDEN_FORMAT_VERSION,
+        generator: format!("kobayashi {}", env!("CARGO_PKG_VERSION")),
+        cases,
+    }
+}
+
+/// Re-evaluates every case of `expected` with this build. Differences are relative to the
+/// expected value, absolute when it is below 1.0.
+pub fn verify_golden_matrix(expected: &GoldenMatrix, tolerance: f64) -> GoldenReport {
+    let mut report = GoldenReport::default();
+    for case in &expected.cases {
+        let actual = case.input.evaluate();
+        for (output, &want) in &case.outputs {
+            let Some(&got) = actual.get(output) else {
+                report.unknown_outputs.push(format!("{}/{output}", case.id));
+                continue;
+            };
+            report.compared += 1;
+            if (got - want).abs() > tolerance * want.abs().max(1.0) {
+                report.mismatches.push(GoldenMismatch {
+                    case_id: case.id.clone(),
+                    output: output.clone(),
+                    expected: want,
+                    actual: got,
+                });
+            }
+        }
+    }
+    report
+}
+
+fn canonical_inputs() -> Vec<(String, GoldenInput)> {
+    let mut cases = Vec::new();
+
+    // (variant, armor, deflection, dodge, armor piercing, shield piercing, accuracy)
+    let stat_sets = [
+        ("reference", 100.0, 80.0, 60.0, 50.0, 40.0, 30.0),
+        ("high_level", 320.0, 275.0, 145.0, 210.0, 180.0, 110.0),
+        ("unpierced", 1_000.0, 1_000.0, 1_000.0, 0.0, 0.0, 0.0),
+        ("outmatched", 10.0, 10.0, 10.0, 1_000.0, 1_000.0, 1_000.0),
+    ];
+    let ship_types = [
+        ("survey", ShipType::Survey),
+        ("armada", ShipType::Armada),
+        ("battleship", ShipType::Battleship),
+        ("explorer", ShipType::Explorer),
+        ("interceptor", ShipType::Interceptor),
+    ];
+    for (variant, armor, shield_deflection, dodge, armor_piercing, shield_piercing, accuracy) in
+        stat_sets
+    {
+        for (ship_name, ship_type) in ship_types {
+            cases.push((
+                format!("mitigation/{ship_name}/{variant}"),
+                GoldenInput::Mitigation {
+                    ship_type,
+                    armor,
+                    shield_deflection,
+                    dodge,
+                    armor_piercing,
+                    shield_piercing,
+                    accuracy,
+                },
+            ));
+        }
+    }
+
+    for (variant, damage, isolytic_damage, isolytic_cascade, isolytic_defense) in [
+        ("reference", 10_000.0, 0.3, 0.4, 0.0),
+        ("defended", 10_000.0, 0.3, 0.4, 0.5),
+        ("cascade_only", 2_500.0, 0.0, 0.25, 0.0),
+        ("bonus_only", 2_500.0, 1.0, 0.0, 1.0),
+        ("no_damage", 0.0, 0.5, 0.5, 0.0),
+    ] {
+        cases.push((
+            format!("isolytic/{variant}"),
+            GoldenInput::Isolytic {
+                damage,
+                isolytic_damage,
+                isolytic_cascade,
+                isolytic_defense,
+            },
+        ));
+    }
+
+    for (variant, apex_shred, apex_barrier) in [
+        ("no_barrier", 0.0, 0.0),
+        ("barrier_10k", 0.0, 10_000.0),
+        ("shred_100_barrier_10k", 1.0, 10_000.0),
+        ("shred_50_barrier_3k", 0.5, 3_000.0),
+        ("barrier_30k", 0.0, 30_000.0),
+        ("shred_200_barrier_45k", 2.0, 45_000.0),
+    ] {
+        cases.push((
+            format!("apex/{variant}"),
+            GoldenInput::Apex {
+                apex_shred,
+                apex_barrier,
+            },
+        ));
+    }
+
+    let engine = |attacker: Combatant, defender: Combatant, rounds: u32, seed: u64| {
+        GoldenInput::Engine {
+            attacker: Box::new(attacker),
+            defender: Box::new(defender),
+            config: SimulationConfig::builder().rounds(rounds).seed(seed).build(),
+        }
+    };
+    cases.push((
+        "engine/plain_exchange".to_string(),
+        engine(
+            Combatant::builder("attacker")
+                .attack(120.0)
+                .pierce(0.1)
+                .hull_health(1_000.0)
+                .build_unchecked(),
+            Combatant::builder("defender")
+                .attack(40.0)
+                .mitigation(0.3)
+                .hull_health(800.0)
+                .build_unchecked(),
+            5,
+            11,
+        ),
+    ));
+    cases.push((
+        "engine/shields_and_crits".to_string(),
+        engine(
+            Combatant::builder("attacker")
+                .attack(250.0)
+                .crit_chance(0.25)
+                .crit_multiplier(1.5)
+                .hull_health(2_000.0)
+                .build_unchecked(),
+            Combatant::builder("defender")
+                .attack(60.0)
+                .mitigation(0.45)
+                .hull_health(1_500.0)
+                .shield_health(900.0)
+                .shield_mitigation(0.8)
+                .build_unchecked(),
+            10,
+            42,
+        ),
+    ));
+    cases.push((
+        "engine/apex_and_isolytic".to_string(),
+        engine(
+            Combatant::builder("attacker")
+                .attack(400.0)
+                .apex_shred(0.5)
+                .isolytic_damage(0.2)
+                .hull_health(3_000.0)
+                .build_unchecked(),
+            Combatant::builder("defender")
+                .attack(100.0)
+                .mitigation(0.2)
+                .apex_barrier(5_000.0)
+                .isolytic_defense(0.1)
+                .hull_health(2_500.0)
+                .build_unchecked(),
+            15,
+            7,
+        ),
+    ));
+
+    cases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_flags_outputs_that_drift_and_outputs_it_does_not_know() {
+        let mut matrix = generate_golden_matrix();
+        assert!(verify_golden_matrix(&matrix, 0.0).passed());
+
+        let case = &mut matrix.cases[0];
+        *case.outputs.get_mut("mitigation").unwrap() += 1e-6;
+        case.outputs.insert("mystery".into(), 1.0);
+        let report = verify_golden_matrix(&matrix, DEFAULT_GOLDEN_TOLERANCE);
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].output, "mitigation");
+        assert_eq!(report.unknown_outputs, vec![format!("{}/mystery", matrix.cases[0].id)]);
+        assert!(verify_golden_matrix(&matrix, 1e-3).mismatches.is_empty());
+    }
+}
@@ -2,10 +2,13 @@
 //!
 //! See [docs/combat_log_format.md](../../../docs/combat_log_format.md) for the documented format.
 
+use std::collections::BTreeMap;
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
-use crate::combat::{CombatEvent, EventSource, SimulationResult};
+use crate::combat::{CombatEvent, EventSource, SimulationResult, TRACE_SCHEMA_VERSION};
 
 /// Ingested combat log (parsed from raw JSON or export).
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -35,9 +38,119 @@ pub struct IngestedEvent {
     pub weapon_index: Option<u32>,
 }
 
-/// Parse a combat log from JSON string (format per docs/combat_log_format.md).
+/// Key renames that turn a community log export into the ingested format. Keys without a
+/// rename are kept as they are. Built-in mappings live in `data/log_mappings/`; a custom one is
+/// the same JSON shape (see [load_log_mapping]).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LogColumnMapping {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Top-level fields, e.g. `roundsSimulated` → `rounds_simulated`.
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    /// Per-event fields, e.g. `eventType` → `event_type`.
+    #[serde(default)]
+    pub event_fields: BTreeMap<String, String>,
+    /// Event type names, e.g. `hit` → `damage_application`.
+    #[serde(default)]
+    pub event_types: BTreeMap<String, String>,
+    /// Keys inside each event's `values`, e.g. `mitigationPct` → `mitigation`.
+    #[serde(default)]
+    pub values: BTreeMap<String, String>,
+    /// Factors applied to numeric `values` after renaming, keyed by the ingested name (e.g.
+    /// `mitigation: 0.01` for logs that report percentages).
+    #[serde(default)]
+    pub value_scale: BTreeMap<String, f64>,
+}
+
+const BUILTIN_LOG_MAPPINGS: [&str; 2] = [
+    include_str!("../../data/log_mappings/camel_case.json"),
+    include_str!("../../data/log_mappings/round_log.json"),
+];
+
+/// The community export variants [parse_combat_log_json] recognizes, in the order it tries them.
+pub fn builtin_log_mappings() -> Vec<LogColumnMapping> {
+    BUILTIN_LOG_MAPPINGS
+        .iter()
+        .map(|json| serde_json::from_str(json).expect("built-in log mappings are valid"))
+        .collect()
+}
+
+/// Read a custom mapping file (same shape as the files in `data/log_mappings/`).
+pub fn load_log_mapping(path: &Path) -> Result<LogColumnMapping, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn rename_keys(map: Map<String, Value>, renames: &BTreeMap<String, String>) -> Map<String, Value> {
+    map.into_iter()
+        .map(|(key, value)| match renames.get(&key) {
+            Some(renamed) => (renamed.clone(), value),
+            None => (key, value),
+        })
+        .collect()
+}
+
+impl LogColumnMapping {
+    /// Rename `raw`'s keys (and event type names) into the ingested format.
+    pub fn apply(&self, raw: Value) -> Value {
+        let Value::Object(top) = raw else {
+            return raw;
+        };
+        let mut top = rename_keys(top, &self.fields);
+        if let Some(Value::Array(events)) = top.get_mut("events") {
+            for event in events.iter_mut() {
+                if let Value::Object(fields) = event {
+                    *fields = self.apply_to_event(std::mem::take(fields));
+                }
+            }
+        }
+        Value::Object(top)
+    }
+
+    fn apply_to_event(&self, event: Map<String, Value>) -> Map<String, Value> {
+        let mut event = rename_keys(event, &self.event_fields);
+        if let Some(Value::String(event_type)) = event.get_mut("event_type") {
+            if let Some(renamed) = self.event_types.get(event_type.as_str()) {
+                *event_type = renamed.clone();
+            }
+        }
+        if let Some(Value::Object(values)) = event.get_mut("values") {
+            let mut renamed = rename_keys(std::mem::take(values), &self.values);
+            for (key, factor) in &self.value_scale {
+                if let Some(scaled) = renamed.get(key).and_then(Value::as_f64).map(|v| v * factor) {
+                    renamed.insert(key.clone(), Value::from(scaled));
+                }
+            }
+            *values = renamed;
+        }
+        event
+    }
+}
+
+/// Parse a combat log from JSON string (format per docs/combat_log_format.md). Logs that are
+/// not in the ingested format are retried through each of [builtin_log_mappings], so the common
+/// community export variants load without pre-processing.
 pub fn parse_combat_log_json(input: &str) -> Result<IngestedCombatLog, String> {
-    serde_json::from_str(input).map_err(|e| e.to_string())
+    let raw: Value = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    match serde_json::from_value(raw.clone()) {
+        Ok(log) => Ok(log),
+        Err(e) => builtin_log_mappings()
+            .iter()
+            .find_map(|mapping| serde_json::from_value(mapping.apply(raw.clone())).ok())
+            .ok_or_else(|| format!("{e} (no built-in log mapping matched either)")),
+    }
+}
+
+/// Parse a combat log with an explicit column mapping instead of auto-detection.
+pub fn parse_combat_log_json_with_mapping(
+    input: &str,
+    mapping: &LogColumnMapping,
+) -> Result<IngestedCombatLog, String> {
+    let raw: Value = serde_json::from_str(input).map_err(|e| e.to_string())?;
+    serde_json::from_value(mapping.apply(raw))
+        .map_err(|e| format!("log does not match mapping '{}': {e}", mapping.name))
 }
 
 /// Convert ingested log to a result comparable to SimulationResult (for parity checks).
@@ -76,6 +189,7 @@ pub fn ingested_events_to_combat_events(events: &[IngestedEvent]) -> Vec<CombatE
             source: EventSource::default(),
             values: e.values.clone(),
             weapon_index: e.weapon_index,
+            schema_version: TRACE_SCHEMA_VERSION,
         })
         .collect()
 }
@@ -0,0 +1,166 @@
+//! Which combat mechanics the engine models, and which ability keys (modifiers, stats,
+//! conditions) belong to each.
+//!
+//! [MECHANICS] is the one list behind `GET /api/data/version`, the data validator
+//! ([crate::data::validate]) and the mechanics matrix in docs/DESIGN.md: when the engine gains a
+//! mechanic, change its [MechanicSupport] here and every report follows.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MechanicSupport {
+    Implemented,
+    /// Modeled, but some variants or interactions are approximated.
+    Partial,
+    /// Recognized but not simulated.
+    Planned,
+}
+
+impl MechanicSupport {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Implemented => "implemented",
+            Self::Partial => "partial",
+            Self::Planned => "planned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mechanic {
+    pub name: &'static str,
+    pub support: MechanicSupport,
+    /// Normalized (snake_case) keys that map to this mechanic.
+    pub keys: &'static [&'static str],
+    /// Key substrings that map to this mechanic when no exact key matches (e.g. "burn").
+    pub key_fragments: &'static [&'static str],
+    /// False for mining, cargo, warp and loot effects the simulator ignores.
+    pub combat: bool,
+}
+
+pub const MECHANICS: &[Mechanic] = &[
+    Mechanic {
+        name: "Mitigation",
+        support: MechanicSupport::Implemented,
+        keys: &["shield_mitigation", "damage_reduction"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Piercing",
+        support: MechanicSupport::Implemented,
+        keys: &["shield_pierce", "armor_pierce", "all_piercing"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Armor",
+        support: MechanicSupport::Implemented,
+        keys: &["armor", "ship_armor", "all_defenses"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Dodge and accuracy",
+        support: MechanicSupport::Implemented,
+        keys: &["ship_dodge", "accuracy"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Critical",
+        support: MechanicSupport::Implemented,
+        keys: &["crit_chance", "crit_damage", "on_critical"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Extra attack",
+        support: MechanicSupport::Implemented,
+        keys: &["extra_attack", "shots_per_attack", "shots_per_weapon", "weapon_shots"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Isolytic",
+        support: MechanicSupport::Implemented,
+        keys: &[
+            "isolytic_damage",
+            "isolytic_defense",
+            "isolytic_cascade",
+            "isolytic_cascade_damage",
+        ],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Apex",
+        support: MechanicSupport::Implemented,
+        keys: &["apex_shred", "apex_barrier"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Burn",
+        support: MechanicSupport::Partial,
+        keys: &[],
+        key_fragments: &["burn", "ignite"],
+        combat: true,
+    },
+    Mechanic {
+        name: "Regeneration",
+        support: MechanicSupport::Partial,
+        keys: &["shield_regen", "hull_repair", "hull_hp_repair", "shield_hp_repair"],
+        key_fragments: &[],
+        combat: true,
+    },
+    Mechanic {
+        name: "Non-combat",
+        support: MechanicSupport::Planned,
+        keys: &["mining_rate", "repair_speed", "warp_speed", "cargo_capacity"],
+        key_fragments: &["loot"],
+        combat: false,
+    },
+];
+
+/// The mechanic a normalized (snake_case) key belongs to. Exact keys win over fragments.
+pub fn mechanic_for_key(key: &str) -> Option<&'static Mechanic> {
+    MECHANICS
+        .iter()
+        .find(|m| m.keys.contains(&key))
+        .or_else(|| {
+            MECHANICS
+                .iter()
+                .find(|m| m.key_fragments.iter().any(|f| key.contains(f)))
+        })
+}
+
+pub fn mechanic_support_for_key(key: &str) -> Option<MechanicSupport> {
+    mechanic_for_key(key).map(|m| m.support)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_map_to_one_mechanic() {
+        for mechanic in MECHANICS {
+            for key in mechanic.keys {
+                assert_eq!(mechanic_for_key(key).map(|m| m.name), Some(mechanic.name), "{key}");
+            }
+        }
+        assert_eq!(mechanic_for_key("burning_damage").map(|m| m.name), Some("Burn"));
+        assert_eq!(mechanic_for_key("loot_bonus").map(|m| m.combat), Some(false));
+        assert!(mechanic_for_key("weapon_damage_typo").is_none());
+    }
+
+    #[test]
+    fn isolytic_and_apex_are_implemented() {
+        for key in ["isolytic_damage", "apex_barrier", "apex_shred"] {
+            assert_eq!(mechanic_support_for_key(key), Some(MechanicSupport::Implemented));
+        }
+    }
+}
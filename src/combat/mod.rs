@@ -1,49 +1,90 @@
 pub mod abilities;
 pub mod buffs;
+pub mod builder;
 pub mod damage;
-pub mod effect_accumulator;
+pub mod dot;
+pub(crate) mod effect_accumulator;
 pub mod engine;
 pub mod events;
 pub mod export_csv;
+pub mod fuzz;
+pub mod golden;
+pub mod mechanics;
 pub mod mitigation;
 pub mod mitigation_sensitivity;
+pub mod monotonicity;
+pub mod parity;
 pub mod types;
 pub mod log_ingest;
 pub mod rng;
+pub mod snapshot;
 pub mod stacking;
+pub mod waves;
 
 pub use abilities::{
     active_effects_for_timing, apply_duplicate_officer_policy, can_activate_in_seat, Ability,
-    AbilityClass, AbilityCondition, AbilityEffect, ActiveAbilityEffect, CombatContext,
-    CrewConfiguration, CrewSeat, CrewSeatContext, TimingWindow, NO_EXPLICIT_CONTRIBUTION_BATCH,
+    AbilityClass, AbilityCondition, AbilityEffect, ActivationLimits, ActivationSuppression,
+    ActiveAbilityEffect, CombatContext, EffectSelection, SelectedEffects,
+    CrewConfiguration, CrewEffects, CrewSeat, CrewSeatContext, TimingWindow, NO_EXPLICIT_CONTRIBUTION_BATCH,
 };
 pub use engine::{
     apply_morale_primary_piercing, component_mitigation, isolytic_damage, mitigation,
-    mitigation_for_hostile, mitigation_with_morale, mitigation_with_mystery,     pierce_damage_through_bonus, round_half_even, serialize_events_json, simulate_combat,
-    AttackerStats, CombatEvent, Combatant, DefenderStats, EventSource, ShipType, SimulationConfig,
-    SimulationResult, TraceCollector, TraceMode, WeaponStats,
+    mitigation_for_hostile, mitigation_with_morale, mitigation_with_mystery,     pierce_damage_through_bonus, round_half_even, serialize_events_json, serialize_events_msgpack, simulate_combat,
+    simulate_combat_from_state, simulate_combat_streaming, simulate_combat_streaming_from_state,
+    simulate_combat_with_effects,
+    simulate_combat_with_scratch, AttackerCondition, AttackerStartState, AttackerStats,
+    ChargedAttack, CombatEvent, CombatScratch, Combatant,
+    DamageBreakdown, DefenderStats, EventSource, InitiativeMode, ShipType, SimulationConfig,
+    SimulationResult, TraceFilter, TraceFilterError, TraceMode, WeaponStats,
     BATTLESHIP_COEFFICIENTS, EPSILON, EXPLORER_COEFFICIENTS, INTERCEPTOR_COEFFICIENTS,
     MITIGATION_CEILING, MITIGATION_FLOOR, MORALE_PRIMARY_PIERCING_BONUS, PIERCE_CAP,
-    SURVEY_COEFFICIENTS,
+    SURVEY_COEFFICIENTS, TRACE_EVENT_TYPES, TRACE_PHASES,
 };
+pub use builder::{CombatantBuilder, CombatantError, SimulationConfigBuilder};
 pub use damage::{
     apply_shield_hull_split, compute_apex_damage_factor, compute_damage_through_factor,
     compute_isolytic_taken,
 };
+pub use dot::{DotChannel, DotTick, DotTracker};
+pub use fuzz::{
+    check_invariants, fuzz_case, run_fuzz, shrink, FuzzFailure, FuzzInvariant, FuzzReport,
+    FuzzViolation, DEFAULT_FUZZ_CASES, MONOTONICITY_ATTACK_FACTOR,
+};
+pub use events::{
+    AttackRoll, CounterAttackRoll, CounterDamageApplication, CritResolution, DamageApplication,
+    EventPayload, MitigationCalc, PierceCalc, TracePayload, TRACE_SCHEMA_VERSION,
+};
+pub use golden::{
+    generate_golden_matrix, verify_golden_matrix, GoldenCase, GoldenInput, GoldenMatrix,
+    GoldenMismatch, GoldenReport, DEFAULT_GOLDEN_TOLERANCE, GOLDEN_FORMAT_VERSION,
+};
+pub use mechanics::{mechanic_for_key, mechanic_support_for_key, Mechanic, MechanicSupport, MECHANICS};
 pub use mitigation_sensitivity::{
     default_percent_sensitivity_rows, format_sensitivity_tsv, HostileMitigationBaseline,
     MitigationSensitivityRow,
 };
+pub use monotonicity::{
+    is_positive_effect, verify_monotonicity, MonotonicityCheck, MonotonicityScenario,
+    MonotonicityStats,
+};
 pub use export_csv::{
     export_to_combat_input, export_to_combatants, export_to_attacker, export_to_crew,
-    export_to_defender, parse_fight_export, ship_type_from_name, FightExport, FightExportEvent,
+    export_to_defender, export_to_hostile_record, parse_fight_export, ship_type_from_name, FightExport, FightExportEvent,
 };
 pub use log_ingest::{
-    ingested_events_to_combat_events, ingested_to_comparable, parse_combat_log_json,
-    parity_within_tolerance, IngestedCombatLog, IngestedEvent,
+    builtin_log_mappings, ingested_events_to_combat_events, ingested_to_comparable,
+    load_log_mapping, parse_combat_log_json, parse_combat_log_json_with_mapping,
+    parity_within_tolerance, IngestedCombatLog, IngestedEvent, LogColumnMapping,
+};
+pub use parity::{
+    parity_report, MechanicParity, OutcomeParity, ParityMechanic, ParityReport, ParityStatus,
+    DEFAULT_PARITY_TOLERANCE,
 };
 pub use stacking::{
     aggregate_contributions, compose_totals, CategoryTotals, StackCategory, StackContribution,
     StatStacking,
 };
+pub use rng::{RngMode, RollChannel, RollSide, RollStreams};
+pub use snapshot::{SimulationSnapshot, SnapshotError, SNAPSHOT_FORMAT_VERSION};
 pub use types::{EnemyType, EnemyTypes};
+pub use waves::{simulate_waves, simulate_waves_with_effects, WavesResult};
@@ -0,0 +1,234 @@
+//! Buff monotonicity: adding a positive effect to the attacker's crew must never lower its expected
+//! damage per round or its win rate. Several formula bugs (a bonus applied with the wrong sign, a
+//! clamp that turned a buff into a cap) would have failed this check.
+//!
+//! [verify_monotonicity] compares one [MonotonicityScenario] fought with and without the effect on
+//! the same seeds; `kobayashi validate --mechanics` runs it for every canonical officer ability
+//! ([crate::data::validate::validate_officer_mechanics]).
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::abilities::{AbilityEffect, CrewConfiguration, CrewSeatContext};
+use crate::combat::engine::simulate_combat;
+use crate::combat::types::{Combatant, SimulationConfig, WeaponStats};
+use crate::parallel::iteration_seed;
+
+/// The fight a positive effect is added to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonotonicityScenario {
+    pub attacker: Combatant,
+    pub defender: Combatant,
+    pub rounds: u32,
+    /// Fights per side of the comparison; fight `n` uses `iteration_seed(seed, n)` on both.
+    pub sims: u32,
+    pub seed: u64,
+    /// Allowed drop in damage per round, relative to the baseline.
+    pub damage_tolerance: f64,
+    /// Allowed drop in win rate, absolute.
+    pub win_rate_tolerance: f64,
+}
+
+impl Default for MonotonicityScenario {
+    /// An even duel: both ships can win, both have shields, apex barrier and isolytic damage, so
+    /// every effect kind has something to act on.
+    fn default() -> Self {
+        let ship = |id: &str| {
+            Combatant::builder(id)
+                .attack(1_200.0)
+                .mitigation(0.4)
+                .pierce(0.1)
+                .crit_chance(0.2)
+                .crit_multiplier(1.5)
+                .proc_chance(0.1)
+                .proc_multiplier(1.3)
+                .hull_health(60_000.0)
+                .shield_health(25_000.0)
+                .shield_mitigation(0.8)
+                .apex_barrier(2_000.0)
+                .apex_shred(0.1)
+                .isolytic_damage(0.1)
+                .isolytic_defense(100.0)
+                .weapons(vec![
+                    WeaponStats { attack: 1_200.0, shots: Some(2) },
+                    WeaponStats { attack: 900.0, shots: Some(1) },
+                ])
+                .build()
+                .expect("reference stats are in range")
+        };
+        Self {
+            attacker: ship("monotonicity attacker"),
+            defender: ship("monotonicity defender"),
+            rounds: 15,
+            sims: 200,
+            seed: 0,
+            damage_tolerance: 0.005,
+            win_rate_tolerance: 0.02,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MonotonicityStats {
+    /// Mean of total damage / rounds fought, so a faster kill does not read as less damage.
+    pub avg_damage_per_round: f64,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonotonicityCheck {
+    pub ability: String,
+    pub baseline: MonotonicityStats,
+    pub with_effect: MonotonicityStats,
+    /// One message per metric that dropped by more than the scenario's tolerance.
+    pub regressions: Vec<String>,
+}
+
+impl MonotonicityCheck {
+    pub fn passed(&self) -> bool {
+        self.regressions.is_empty()
+    }
+}
+
+/// True when `effect` only helps the ship that holds it: a positive bonus, regen or defense, or
+/// a status/damage-over-time with a chance and duration to apply to the enemy.
+pub fn is_positive_effect(effect: &AbilityEffect) -> bool {
+    match *effect {
+        AbilityEffect::AttackMultiplier(v)
+        | AbilityEffect::PierceBonus(v)
+        | AbilityEffect::Morale(v)
+        | AbilityEffect::ShieldRegen(v)
+        | AbilityEffect::HullRegen(v)
+        | AbilityEffect::ApexShredBonus(v)
+        | AbilityEffect::ApexBarrierBonus(v)
+        | AbilityEffect::IsolyticDamageBonus(v)
+        | AbilityEffect::IsolyticDefenseBonus(v)
+        | AbilityEffect::IsolyticCascadeDamageBonus(v)
+        | AbilityEffect::ShieldMitigationBonus(v)
+        | AbilityEffect::OnKillHullRegen(v) => v > 0.0,
+        AbilityEffect::Assimilated { chance, duration_rounds }
+        | AbilityEffect::HullBreach { chance, duration_rounds, .. }
+        | AbilityEffect::Burning { chance, duration_rounds } => {
+            chance > 0.0 && duration_rounds > 0
+        }
+        AbilityEffect::DamageOverTime { chance, hull_pct, duration_rounds, .. } => {
+            chance > 0.0 && hull_pct > 0.0 && duration_rounds > 0
+        }
+        AbilityEffect::ShotsBonus { chance, bonus_pct, duration_rounds } => {
+            chance > 0.0 && bonus_pct > 0.0 && duration_rounds > 0
+        }
+        AbilityEffect::DecayingAttackMultiplier { initial, floor, .. } => {
+            initial > 0.0 && floor >= 0.0
+        }
+        AbilityEffect::AccumulatingAttackMultiplier { initial, growth_per_round, ceiling } => {
+            initial >= 0.0 && growth_per_round >= 0.0 && ceiling > 0.0
+        }
+    }
+}
+
+fn run_stats(scenario: &MonotonicityScenario, crew: &CrewConfiguration) -> MonotonicityStats {
+    let (mut damage, mut wins) = (0.0, 0u32);
+    for n in 0..scenario.sims {
+        let config = SimulationConfig::builder()
+            .rounds(scenario.rounds)
+            .seed(iteration_seed(scenario.seed, n as usize))
+            .build();
+        let result = simulate_combat(&scenario.attacker, &scenario.defender, config, crew);
+        damage += result.total_damage / f64::from(result.rounds_simulated.max(1));
+        wins += u32::from(result.attacker_won);
+    }
+    let sims = f64::from(scenario.sims.max(1));
+    MonotonicityStats {
+        avg_damage_per_round: damage / sims,
+        win_rate: f64::from(wins) / sims,
+    }
+}
+
+/// Fights `scenario` with and without `effect` (an attacker crew row holding it) on the same seeds
+/// and reports any metric the effect lowered. Errors when the row's effect is not positive
+/// ([is_positive_effect]), since only those have to be monotonic.
+pub fn verify_monotonicity(
+    effect: &CrewSeatContext,
+    scenario: &MonotonicityScenario,
+) -> Result<MonotonicityCheck, String> {
+    let ability = &effect.ability;
+    if !is_positive_effect(&ability.effect) {
+        return Err(format!("{}: {:?} is not a positive effect", ability.name, ability.effect));
+    }
+    let baseline = run_stats(scenario, &CrewConfiguration::default());
+    let crew = CrewConfiguration { seats: vec![effect.clone()] };
+    let with_effect = run_stats(scenario, &crew);
+
+    let mut regressions = Vec::new();
+    let damage_floor = baseline.avg_damage_per_round * (1.0 - scenario.damage_tolerance);
+    if with_effect.avg_damage_per_round < damage_floor {
+        regressions.push(format!(
+            "damage per round fell from {:.1} to {:.1}",
+            baseline.avg_damage_per_round, with_effect.avg_damage_per_round
+        ));
+    }
+    if with_effect.win_rate < baseline.win_rate - scenario.win_rate_tolerance {
+        regressions.push(format!(
+            "win rate fell from {:.3} to {:.3}",
+            baseline.win_rate, with_effect.win_rate
+        ));
+    }
+    Ok(MonotonicityCheck {
+        ability: ability.name.clone(),
+        baseline,
+        with_effect,
+        regressions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::abilities::{Ability, AbilityClass, CrewSeat, TimingWindow};
+
+    fn row(effect: AbilityEffect) -> CrewSeatContext {
+        CrewSeatContext::legacy(
+            CrewSeat::Captain,
+            Ability {
+                name: "test ability".to_string(),
+                class: AbilityClass::CaptainManeuver,
+                timing: TimingWindow::AttackPhase,
+                boostable: false,
+                effect,
+                condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
+            },
+            false,
+        )
+    }
+
+    fn scenario() -> MonotonicityScenario {
+        MonotonicityScenario { sims: 40, ..MonotonicityScenario::default() }
+    }
+
+    #[test]
+    fn positive_bonuses_pass_and_debuffs_are_rejected() {
+        let check = verify_monotonicity(&row(AbilityEffect::AttackMultiplier(0.2)), &scenario())
+            .expect("attack bonus is positive");
+        assert!(check.passed(), "{:?}", check.regressions);
+        assert!(check.with_effect.avg_damage_per_round > check.baseline.avg_damage_per_round);
+
+        let hull = verify_monotonicity(&row(AbilityEffect::HullRegen(500.0)), &scenario()).unwrap();
+        assert!(hull.passed(), "{:?}", hull.regressions);
+
+        assert!(!is_positive_effect(&AbilityEffect::AttackMultiplier(-0.1)));
+        let err = verify_monotonicity(&row(AbilityEffect::PierceBonus(0.0)), &scenario())
+            .expect_err("a zero bonus is not positive");
+        assert!(err.contains("not a positive effect"));
+    }
+
+    #[test]
+    fn drops_past_the_tolerance_are_regressions() {
+        // Demanding +50% damage from a small pierce bonus turns its gain into a reported drop.
+        let strict = MonotonicityScenario { damage_tolerance: -0.5, ..scenario() };
+        let check = verify_monotonicity(&row(AbilityEffect::PierceBonus(0.01)), &strict).unwrap();
+        assert!(!check.passed());
+        assert_eq!(check.regressions.len(), 1);
+        assert!(check.regressions[0].starts_with("damage per round fell"));
+    }
+}
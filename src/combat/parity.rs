@@ -0,0 +1,348 @@
+//! Per-mechanic parity between an ingested combat log and traced simulator runs of the same
+//! fight.
+//!
+//! [parity_within_tolerance](crate::combat::parity_within_tolerance) only says whether the
+//! outcomes match. A [ParityReport] breaks the comparison down by mechanic (per-shot damage,
+//! crit frequency, mitigation, isolytic damage, burning ticks) and names the engine subsystem
+//! behind each one, so a diverging log points at the code to look at. `kobayashi parity
+//! <log.json> --scenario <snapshot.json>` prints the report.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::combat::log_ingest::IngestedCombatLog;
+use crate::combat::types::SimulationResult;
+use crate::combat::EPSILON;
+
+/// Default relative error above which a mechanic counts as diverging.
+pub const DEFAULT_PARITY_TOLERANCE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParityMechanic {
+    /// Mean `damage_application.damage_after_apex` per shot.
+    BaseDamage,
+    /// Fraction of `crit_resolution` events with `is_crit`.
+    CritFrequency,
+    /// Mean `mitigation_calc.mitigation`.
+    Mitigation,
+    /// Mean `damage_application.isolytic_damage` per shot.
+    Isolytic,
+    /// Burning `dot_damage` ticks per round.
+    BurningTicks,
+}
+
+impl ParityMechanic {
+    pub const ALL: [ParityMechanic; 5] = [
+        ParityMechanic::BaseDamage,
+        ParityMechanic::CritFrequency,
+        ParityMechanic::Mitigation,
+        ParityMechanic::Isolytic,
+        ParityMechanic::BurningTicks,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ParityMechanic::BaseDamage => "base_damage",
+            ParityMechanic::CritFrequency => "crit_frequency",
+            ParityMechanic::Mitigation => "mitigation",
+            ParityMechanic::Isolytic => "isolytic",
+            ParityMechanic::BurningTicks => "burning_ticks",
+        }
+    }
+
+    /// Engine code that produces the mechanic's trace values.
+    pub fn subsystem(self) -> &'static str {
+        match self {
+            ParityMechanic::BaseDamage => "src/combat/damage.rs (damage-through, apex barrier)",
+            ParityMechanic::CritFrequency => "src/combat/engine.rs (crit roll, RollChannel::Crit)",
+            ParityMechanic::Mitigation => "src/combat/mitigation.rs",
+            ParityMechanic::Isolytic => "src/combat/damage.rs (compute_isolytic_taken)",
+            ParityMechanic::BurningTicks => "src/combat/dot.rs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParityStatus {
+    Agree,
+    Diverges,
+    /// The log has no events for the mechanic, so it cannot be scored.
+    NoData,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MechanicParity {
+    pub mechanic: ParityMechanic,
+    pub subsystem: String,
+    pub log_value: f64,
+    /// Mean over all simulated fights.
+    pub sim_value: f64,
+    pub log_samples: usize,
+    pub sim_samples: usize,
+    /// `|sim - log| / max(|sim|, |log|)`; 0 when both are 0.
+    pub relative_error: f64,
+    /// `1 - relative_error`, so 1 is exact agreement.
+    pub score: f64,
+    pub status: ParityStatus,
+}
+
+/// Fight outcome in the log next to the simulator's average over the runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutcomeParity {
+    pub log_total_damage: f64,
+    pub sim_mean_total_damage: f64,
+    pub log_attacker_won: bool,
+    pub sim_win_rate: f64,
+    pub log_rounds: u32,
+    pub sim_mean_rounds: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParityReport {
+    pub simulations: usize,
+    pub tolerance: f64,
+    pub outcome: OutcomeParity,
+    pub mechanics: Vec<MechanicParity>,
+    /// Mean score of the mechanics the log has data for (1.0 when it has none).
+    pub overall_score: f64,
+}
+
+impl ParityReport {
+    /// Mechanics whose relative error exceeds the tolerance.
+    pub fn divergent(&self) -> impl Iterator<Item = &MechanicParity> {
+        self.mechanics
+            .iter()
+            .filter(|m| m.status == ParityStatus::Diverges)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.divergent().next().is_none()
+    }
+}
+
+/// Running sums for one mechanic: `total / samples` is the reported value.
+#[derive(Debug, Clone, Copy, Default)]
+struct MechanicSample {
+    total: f64,
+    samples: usize,
+}
+
+impl MechanicSample {
+    fn value(self) -> f64 {
+        if self.samples == 0 {
+            0.0
+        } else {
+            self.total / self.samples as f64
+        }
+    }
+}
+
+/// Per-mechanic samples from one or more fights, indexed like [ParityMechanic::ALL].
+#[derive(Debug, Default)]
+struct MechanicSamples([MechanicSample; 5]);
+
+impl MechanicSamples {
+    fn add(&mut self, mechanic: ParityMechanic, value: f64) {
+        let sample = &mut self.0[mechanic as usize];
+        sample.total += value;
+        sample.samples += 1;
+    }
+
+    fn record_event(&mut self, event_type: &str, values: &Map<String, Value>) {
+        let number = |key: &str| values.get(key).and_then(Value::as_f64);
+        match event_type {
+            "damage_application" => {
+                if let Some(damage) = number("damage_after_apex") {
+                    self.add(ParityMechanic::BaseDamage, damage);
+                }
+                if let Some(isolytic) = number("isolytic_damage") {
+                    self.add(ParityMechanic::Isolytic, isolytic);
+                }
+            }
+            "crit_resolution" => {
+                if let Some(is_crit) = values.get("is_crit").and_then(Value::as_bool) {
+                    self.add(ParityMechanic::CritFrequency, if is_crit { 1.0 } else { 0.0 });
+                }
+            }
+            "mitigation_calc" => {
+                if let Some(mitigation) = number("mitigation") {
+                    self.add(ParityMechanic::Mitigation, mitigation);
+                }
+            }
+            "dot_damage" if values.get("channel").and_then(Value::as_str) == Some("burning") => {
+                // Counted per tick; normalized by rounds in `record_rounds`.
+                self.0[ParityMechanic::BurningTicks as usize].total += 1.0;
+            }
+            _ => {}
+        }
+    }
+
+    /// Burning ticks are a rate, so every simulated round is a sample whether or not it ticked.
+    fn record_rounds(&mut self, rounds: u32, has_trace: bool) {
+        if has_trace {
+            self.0[ParityMechanic::BurningTicks as usize].samples += rounds as usize;
+        }
+    }
+}
+
+/// Scores `log` against `sims`, traced runs of the same fight (typically one per seed). Each
+/// mechanic is pooled over every simulated event before it is compared.
+pub fn parity_report(
+    log: &IngestedCombatLog,
+    sims: &[SimulationResult],
+    tolerance: f64,
+) -> ParityReport {
+    let mut log_samples = MechanicSamples::default();
+    for event in &log.events {
+        log_samples.record_event(&event.event_type, &event.values);
+    }
+    log_samples.record_rounds(log.rounds_simulated, !log.events.is_empty());
+
+    let mut sim_samples = MechanicSamples::default();
+    for sim in sims {
+        for event in &sim.events {
+            sim_samples.record_event(&event.event_type, &event.values);
+        }
+        sim_samples.record_rounds(sim.rounds_simulated, !sim.events.is_empty());
+    }
+
+    let mechanics: Vec<MechanicParity> = ParityMechanic::ALL
+        .iter()
+        .map(|&mechanic| {
+            let log_sample = log_samples.0[mechanic as usize];
+            let sim_sample = sim_samples.0[mechanic as usize];
+            // Burning ticks only carry data when the log recorded at least one tick.
+            let has_log_data = match mechanic {
+                ParityMechanic::BurningTicks => log_sample.total > 0.0,
+                _ => log_sample.samples > 0,
+            };
+            let (log_value, sim_value) = (log_sample.value(), sim_sample.value());
+            let scale = log_value.abs().max(sim_value.abs());
+            let relative_error = if scale < EPSILON {
+                0.0
+            } else {
+                (sim_value - log_value).abs() / scale
+            };
+            let status = if !has_log_data {
+                ParityStatus::NoData
+            } else if relative_error > tolerance {
+                ParityStatus::Diverges
+            } else {
+                ParityStatus::Agree
+            };
+            MechanicParity {
+                mechanic,
+                subsystem: mechanic.subsystem().to_string(),
+                log_value,
+                sim_value,
+                log_samples: log_sample.samples,
+                sim_samples: sim_sample.samples,
+                relative_error,
+                score: 1.0 - relative_error,
+                status,
+            }
+        })
+        .collect();
+
+    let scored: Vec<f64> = mechanics
+        .iter()
+        .filter(|m| m.status != ParityStatus::NoData)
+        .map(|m| m.score)
+        .collect();
+    let overall_score = if scored.is_empty() {
+        1.0
+    } else {
+        scored.iter().sum::<f64>() / scored.len() as f64
+    };
+
+    let runs = sims.len().max(1) as f64;
+    let outcome = OutcomeParity {
+        log_total_damage: log.total_damage,
+        sim_mean_total_damage: sims.iter().map(|s| s.total_damage).sum::<f64>() / runs,
+        log_attacker_won: log.attacker_won,
+        sim_win_rate: sims.iter().filter(|s| s.attacker_won).count() as f64 / runs,
+        log_rounds: log.rounds_simulated,
+        sim_mean_rounds: sims.iter().map(|s| s.rounds_simulated as f64).sum::<f64>() / runs,
+    };
+
+    ParityReport {
+        simulations: sims.len(),
+        tolerance,
+        outcome,
+        mechanics,
+        overall_score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::log_ingest::IngestedEvent;
+
+    fn event(event_type: &str, values: Value) -> IngestedEvent {
+        IngestedEvent {
+            event_type: event_type.to_string(),
+            round_index: 1,
+            phase: "attack".to_string(),
+            values: values.as_object().cloned().unwrap_or_default(),
+            weapon_index: Some(0),
+        }
+    }
+
+    fn log_with(events: Vec<IngestedEvent>) -> IngestedCombatLog {
+        IngestedCombatLog {
+            rounds_simulated: 1,
+            total_damage: 20.0,
+            attacker_won: false,
+            winner_by_round_limit: true,
+            defender_hull_remaining: 80.0,
+            defender_shield_remaining: 0.0,
+            events,
+        }
+    }
+
+    fn as_sim(log: &IngestedCombatLog) -> SimulationResult {
+        SimulationResult {
+            total_damage: log.total_damage,
+            attacker_won: log.attacker_won,
+            winner_by_round_limit: log.winner_by_round_limit,
+            rounds_simulated: log.rounds_simulated,
+            attacker_hull_remaining: 100.0,
+            defender_hull_remaining: log.defender_hull_remaining,
+            defender_shield_remaining: log.defender_shield_remaining,
+            attacker_shield_remaining: 0.0,
+            attacker_damage: Default::default(),
+            defender_damage: Default::default(),
+            events: crate::combat::ingested_events_to_combat_events(&log.events),
+        }
+    }
+
+    #[test]
+    fn report_flags_the_mechanic_that_diverges_and_skips_missing_ones() {
+        let log = log_with(vec![
+            event("mitigation_calc", serde_json::json!({ "mitigation": 0.5 })),
+            event("crit_resolution", serde_json::json!({ "is_crit": false })),
+            event(
+                "damage_application",
+                serde_json::json!({ "damage_after_apex": 20.0, "isolytic_damage": 4.0 }),
+            ),
+        ]);
+        let report = parity_report(&log, &[as_sim(&log)], DEFAULT_PARITY_TOLERANCE);
+        assert!(report.passed());
+        assert_eq!(report.overall_score, 1.0);
+        let burning = &report.mechanics[ParityMechanic::BurningTicks as usize];
+        assert_eq!(burning.status, ParityStatus::NoData);
+
+        let mut sim_log = log.clone();
+        sim_log.events[0] = event("mitigation_calc", serde_json::json!({ "mitigation": 0.25 }));
+        let report = parity_report(&log, &[as_sim(&sim_log)], DEFAULT_PARITY_TOLERANCE);
+        let divergent: Vec<_> = report.divergent().map(|m| m.mechanic).collect();
+        assert_eq!(divergent, vec![ParityMechanic::Mitigation]);
+        let mitigation = &report.mechanics[ParityMechanic::Mitigation as usize];
+        assert_eq!(mitigation.subsystem, "src/combat/mitigation.rs");
+        assert!((mitigation.relative_error - 0.5).abs() < 1e-12);
+        assert!(report.overall_score < 1.0);
+    }
+}
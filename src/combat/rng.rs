@@ -1,28 +1,302 @@
-//! Fast PRNG for combat simulation. Uses SplitMix64 for throughput and good statistical quality.
-//! Deterministic: same seed produces the same sequence. Not cryptographically secure.
+//! Fast PRNGs for combat simulation. Deterministic: same seed produces the same sequence.
+//! Not cryptographically secure.
+//!
+//! - [RngMode::Counter] (default): Philox4x32-10, a counter-based generator. Output is a pure
+//!   function of (key, stream, counter), so independent streams can be split off for any
+//!   (candidate, iteration, round) without coupling their draw order.
+//! - [RngMode::Legacy]: the original sequential SplitMix64. Kept for golden tests and for
+//!   reproducing results recorded before the counter-based generator.
+
+use serde::{Deserialize, Serialize};
 
 const SPLITMIX64_GOLDEN: u64 = 0x9e3779b97f4a7c15;
 const SPLITMIX64_M1: u64 = 0xbf58476d1ce4e5b9;
 const SPLITMIX64_M2: u64 = 0x94d049bb133111eb;
 
+const PHILOX_M0: u32 = 0xD251_1F53;
+const PHILOX_M1: u32 = 0xCD9E_8D57;
+const PHILOX_W0: u32 = 0x9E37_79B9;
+const PHILOX_W1: u32 = 0xBB67_AE85;
+const PHILOX_ROUNDS: usize = 10;
+
+/// Which generator the combat engine draws rolls from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RngMode {
+    /// Counter-based Philox with a fresh stream per round.
+    #[default]
+    Counter,
+    /// Sequential SplitMix64 over the whole fight (pre-counter behavior).
+    Legacy,
+}
+
+/// SplitMix64 finalizer; used to derive keys and stream ids.
+#[inline]
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(SPLITMIX64_M1);
+    z = (z ^ (z >> 27)).wrapping_mul(SPLITMIX64_M2);
+    z ^ (z >> 31)
+}
+
+#[inline]
+fn mulhilo(a: u32, b: u32) -> (u32, u32) {
+    let product = u64::from(a) * u64::from(b);
+    ((product >> 32) as u32, product as u32)
+}
+
+/// One Philox4x32-10 block.
+pub fn philox4x32(counter: [u32; 4], key: [u32; 2]) -> [u32; 4] {
+    let mut ctr = counter;
+    let mut key = key;
+    for round in 0..PHILOX_ROUNDS {
+        if round > 0 {
+            key[0] = key[0].wrapping_add(PHILOX_W0);
+            key[1] = key[1].wrapping_add(PHILOX_W1);
+        }
+        let (hi0, lo0) = mulhilo(PHILOX_M0, ctr[0]);
+        let (hi1, lo1) = mulhilo(PHILOX_M1, ctr[2]);
+        ctr = [hi1 ^ ctr[1] ^ key[0], lo1, hi0 ^ ctr[3] ^ key[1], lo0];
+    }
+    ctr
+}
+
+/// Counter-based stream: block `i` of stream `s` under `key` is `philox(ctr = [i, s], key)`.
+#[derive(Debug, Clone, Copy)]
+struct PhiloxStream {
+    key: [u32; 2],
+    stream: u64,
+    block: u64,
+    /// Second u64 of the current block, not yet returned.
+    buffered: Option<u64>,
+}
+
+impl PhiloxStream {
+    fn new(seed: u64, stream: u64) -> Self {
+        let k = mix64(seed);
+        Self {
+            key: [k as u32, (k >> 32) as u32],
+            stream,
+            block: 0,
+            buffered: None,
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        if let Some(v) = self.buffered.take() {
+            return v;
+        }
+        let out = philox4x32(
+            [
+                self.block as u32,
+                (self.block >> 32) as u32,
+                self.stream as u32,
+                (self.stream >> 32) as u32,
+            ],
+            self.key,
+        );
+        self.block = self.block.wrapping_add(1);
+        self.buffered = Some(u64::from(out[2]) | (u64::from(out[3]) << 32));
+        u64::from(out[0]) | (u64::from(out[1]) << 32)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RngState {
+    SplitMix { state: u64 },
+    Philox(PhiloxStream),
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Rng {
-    state: u64,
+    inner: RngState,
 }
 
 impl Rng {
+    /// Sequential SplitMix64 generator (the [RngMode::Legacy] sequence).
     pub fn new(seed: u64) -> Self {
-        Self { state: seed }
+        Self {
+            inner: RngState::SplitMix { state: seed },
+        }
+    }
+
+    /// Counter-based generator on stream 0 for `seed`.
+    pub fn counter(seed: u64) -> Self {
+        Self {
+            inner: RngState::Philox(PhiloxStream::new(seed, 0)),
+        }
+    }
+
+    pub fn with_mode(seed: u64, mode: RngMode) -> Self {
+        match mode {
+            RngMode::Counter => Self::counter(seed),
+            RngMode::Legacy => Self::new(seed),
+        }
+    }
+
+    /// Independent sub-stream identified by `stream_id`, starting at its first value.
+    /// Splitting is deterministic and does not advance `self`; nested splits compose
+    /// (e.g. `rng.split(iteration).split(round)`).
+    pub fn split(&self, stream_id: u64) -> Self {
+        match self.inner {
+            RngState::SplitMix { state } => Self::new(mix64(state ^ mix64(stream_id))),
+            RngState::Philox(p) => Self {
+                inner: RngState::Philox(PhiloxStream {
+                    key: p.key,
+                    stream: mix64(p.stream ^ mix64(stream_id.wrapping_add(SPLITMIX64_GOLDEN))),
+                    block: 0,
+                    buffered: None,
+                }),
+            },
+        }
     }
 
     /// Returns the next 64-bit value. Same API as before; sequence differs from the prior LCG.
     #[inline]
     pub fn next_u64(&mut self) -> u64 {
-        self.state = self.state.wrapping_add(SPLITMIX64_GOLDEN);
-        let mut z = self.state;
-        z = (z ^ (z >> 30)).wrapping_mul(SPLITMIX64_M1);
-        z = (z ^ (z >> 27)).wrapping_mul(SPLITMIX64_M2);
-        z ^ (z >> 31)
+        match &mut self.inner {
+            RngState::SplitMix { state } => {
+                *state = state.wrapping_add(SPLITMIX64_GOLDEN);
+                mix64(*state)
+            }
+            RngState::Philox(p) => p.next_u64(),
+        }
+    }
+}
+
+/// Named roll channels. In [RngMode::Counter] each channel draws from its own sub-stream of
+/// the round stream, so a roll added to one channel never shifts the values of another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollChannel {
+    /// Per-shot attack roll.
+    Attack,
+    Crit,
+    Proc,
+    /// Status effects: assimilated, hull breach, burning, shots bonus.
+    Status,
+    Morale,
+}
+
+impl RollChannel {
+    pub const ALL: [RollChannel; 5] = [
+        RollChannel::Attack,
+        RollChannel::Crit,
+        RollChannel::Proc,
+        RollChannel::Status,
+        RollChannel::Morale,
+    ];
+
+    /// Sub-stream id under the round stream. Part of the seed contract: never renumber.
+    pub const fn stream_id(self) -> u64 {
+        match self {
+            RollChannel::Attack => 0,
+            RollChannel::Crit => 1,
+            RollChannel::Proc => 2,
+            RollChannel::Status => 3,
+            RollChannel::Morale => 4,
+        }
+    }
+
+    fn index(self) -> usize {
+        self.stream_id() as usize
+    }
+}
+
+/// Which combatant a roll belongs to. In [RngMode::Counter] each side has its own channel
+/// streams, so rolls one side adds (a defender crit, a new hostile proc) never shift the other
+/// side's rolls for a fixed seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollSide {
+    Attacker,
+    Defender,
+}
+
+impl RollSide {
+    pub const ALL: [RollSide; 2] = [RollSide::Attacker, RollSide::Defender];
+
+    /// Sub-stream id under the round stream that the side's channels split from; the attacker
+    /// draws its channels straight from the round stream. Part of the seed contract: never
+    /// renumber.
+    pub const fn stream_id(self) -> Option<u64> {
+        match self {
+            RollSide::Attacker => None,
+            RollSide::Defender => Some(1 << 32),
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            RollSide::Attacker => 0,
+            RollSide::Defender => 1,
+        }
+    }
+}
+
+/// Maps a raw draw to [0, 1].
+#[inline]
+pub fn unit_roll(value: u64) -> f64 {
+    (value as f64) / (u64::MAX as f64)
+}
+
+/// Roll source for one fight. Counter mode keeps one stream per (round, side, channel);
+/// legacy mode draws every side and channel from the single sequential generator so recorded
+/// fights replay unchanged.
+#[derive(Debug, Clone)]
+pub struct RollStreams {
+    mode: RngMode,
+    fight: Rng,
+    channels: [[Rng; RollChannel::ALL.len()]; RollSide::ALL.len()],
+}
+
+impl RollStreams {
+    pub fn new(seed: u64, mode: RngMode) -> Self {
+        let fight = Rng::with_mode(seed, mode);
+        Self {
+            mode,
+            fight,
+            channels: [[fight; RollChannel::ALL.len()]; RollSide::ALL.len()],
+        }
+    }
+
+    /// Re-derives the channel streams for `round_index`. No-op in legacy mode.
+    pub fn start_round(&mut self, round_index: u32) {
+        if self.mode != RngMode::Counter {
+            return;
+        }
+        let round = self.fight.split(u64::from(round_index));
+        for side in RollSide::ALL {
+            let side_stream = side.stream_id().map_or(round, |id| round.split(id));
+            for channel in RollChannel::ALL {
+                self.channels[side.index()][channel.index()] =
+                    side_stream.split(channel.stream_id());
+            }
+        }
+    }
+
+    /// Next attacker draw on `channel`.
+    #[inline]
+    pub fn next_u64(&mut self, channel: RollChannel) -> u64 {
+        self.next_u64_for(RollSide::Attacker, channel)
+    }
+
+    #[inline]
+    pub fn next_u64_for(&mut self, side: RollSide, channel: RollChannel) -> u64 {
+        match self.mode {
+            RngMode::Counter => self.channels[side.index()][channel.index()].next_u64(),
+            RngMode::Legacy => self.fight.next_u64(),
+        }
+    }
+
+    /// Uniform attacker roll in [0, 1] on `channel`.
+    #[inline]
+    pub fn roll(&mut self, channel: RollChannel) -> f64 {
+        self.roll_for(RollSide::Attacker, channel)
+    }
+
+    /// Uniform roll in [0, 1] on `side`'s `channel`.
+    #[inline]
+    pub fn roll_for(&mut self, side: RollSide, channel: RollChannel) -> f64 {
+        unit_roll(self.next_u64_for(side, channel))
     }
 }
 
@@ -45,4 +319,113 @@ mod tests {
         let mut b = Rng::new(2);
         assert_ne!(a.next_u64(), b.next_u64());
     }
+
+    #[test]
+    fn legacy_sequence_is_unchanged() {
+        let mut rng = Rng::with_mode(7, RngMode::Legacy);
+        assert_eq!(rng.next_u64(), 0x63cb_e1e4_5932_0dd7);
+    }
+
+    #[test]
+    fn philox_matches_random123_known_answers() {
+        assert_eq!(
+            philox4x32([0, 0, 0, 0], [0, 0]),
+            [0x6627_e8d5, 0xe169_c58d, 0xbc57_ac4c, 0x9b00_dbd8]
+        );
+        assert_eq!(
+            philox4x32([u32::MAX; 4], [u32::MAX; 2]),
+            [0x408f_276d, 0x41c8_3b0e, 0xa20b_c7c6, 0x6d54_51fd]
+        );
+    }
+
+    #[test]
+    fn counter_streams_are_deterministic_and_independent() {
+        let base = Rng::counter(42);
+        let mut a = base.split(3);
+        let mut b = base.split(3);
+        let mut c = base.split(4);
+        let xs: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        let ys: Vec<u64> = (0..8).map(|_| b.next_u64()).collect();
+        let zs: Vec<u64> = (0..8).map(|_| c.next_u64()).collect();
+        assert_eq!(xs, ys);
+        assert_ne!(xs, zs);
+    }
+
+    #[test]
+    fn split_does_not_depend_on_parent_draws() {
+        let mut parent = Rng::counter(9);
+        let before = parent.split(1).next_u64();
+        for _ in 0..5 {
+            parent.next_u64();
+        }
+        assert_eq!(parent.split(1).next_u64(), before);
+    }
+
+    /// First draw of each channel (in [RollChannel::ALL] order) for seed 42, round 1.
+    const LOCKED_ROUND1_SEED42: [u64; 5] = [
+        0x24d9_388d_40ba_795b,
+        0xfc25_fa45_8cec_3073,
+        0x7350_fbe4_a970_014a,
+        0xcafc_6f57_5b80_97f3,
+        0x405a_6b12_0be5_d9f5,
+    ];
+
+    #[test]
+    fn channel_values_are_locked() {
+        let mut streams = RollStreams::new(42, RngMode::Counter);
+        streams.start_round(1);
+        let got: Vec<u64> = RollChannel::ALL
+            .iter()
+            .map(|&channel| streams.next_u64(channel))
+            .collect();
+        assert_eq!(got, LOCKED_ROUND1_SEED42);
+    }
+
+    #[test]
+    fn extra_roll_on_one_channel_does_not_shift_others() {
+        let mut plain = RollStreams::new(5, RngMode::Counter);
+        let mut perturbed = RollStreams::new(5, RngMode::Counter);
+        plain.start_round(2);
+        perturbed.start_round(2);
+        for _ in 0..3 {
+            perturbed.next_u64(RollChannel::Status);
+        }
+        for channel in [RollChannel::Attack, RollChannel::Crit, RollChannel::Proc, RollChannel::Morale] {
+            for _ in 0..4 {
+                assert_eq!(plain.next_u64(channel), perturbed.next_u64(channel));
+            }
+        }
+    }
+
+    #[test]
+    fn defender_rolls_do_not_shift_attacker_rolls() {
+        let mut plain = RollStreams::new(5, RngMode::Counter);
+        let mut perturbed = RollStreams::new(5, RngMode::Counter);
+        plain.start_round(2);
+        perturbed.start_round(2);
+        for channel in RollChannel::ALL {
+            perturbed.next_u64_for(RollSide::Defender, channel);
+        }
+        for channel in RollChannel::ALL {
+            for _ in 0..4 {
+                assert_eq!(plain.next_u64(channel), perturbed.next_u64(channel));
+            }
+        }
+        let mut defender = RollStreams::new(5, RngMode::Counter);
+        defender.start_round(2);
+        assert_ne!(
+            defender.next_u64_for(RollSide::Defender, RollChannel::Crit),
+            plain.next_u64_for(RollSide::Attacker, RollChannel::Crit)
+        );
+    }
+
+    #[test]
+    fn legacy_channels_share_one_sequence() {
+        let mut streams = RollStreams::new(7, RngMode::Legacy);
+        streams.start_round(1);
+        let mut rng = Rng::new(7);
+        for channel in RollChannel::ALL {
+            assert_eq!(streams.next_u64(channel), rng.next_u64());
+        }
+    }
 }
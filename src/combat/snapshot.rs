@@ -0,0 +1,175 @@
+//! Self-contained record of one fight's inputs (ships after profile and crew buffs, crew rows,
+//! config) that replays to the same result. Users attach these to bug reports instead of
+//! describing their setup; `kobayashi snapshot run <file>` replays one.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::abilities::CrewConfiguration;
+use crate::combat::engine::simulate_combat;
+use crate::combat::types::{Combatant, SimulationConfig, SimulationResult};
+
+/// Bumped when a field changes meaning; readers reject snapshots from a newer format.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    pub format_version: u32,
+    /// Crate version that wrote the snapshot.
+    pub kobayashi_version: String,
+    pub attacker: Combatant,
+    pub defender: Combatant,
+    pub crew: CrewConfiguration,
+    pub config: SimulationConfig,
+    /// Data sets the inputs were resolved from, e.g. `{"ships": "...", "hostiles": "..."}`.
+    /// Informational: replay uses the recorded stats, not the current data.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data_versions: BTreeMap<String, String>,
+    /// Whatever produced the fight (request body, job id, error message).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    /// Written by a newer build with format `found`.
+    UnsupportedVersion { found: u32 },
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot read snapshot: {e}"),
+            Self::Parse(e) => write!(f, "invalid snapshot: {e}"),
+            Self::UnsupportedVersion { found } => write!(
+                f,
+                "snapshot format {found} is newer than this build reads ({SNAPSHOT_FORMAT_VERSION})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl SimulationSnapshot {
+    pub fn new(
+        attacker: Combatant,
+        defender: Combatant,
+        crew: CrewConfiguration,
+        config: SimulationConfig,
+    ) -> Self {
+        Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            kobayashi_version: env!("CARGO_PKG_VERSION").to_string(),
+            attacker,
+            defender,
+            crew,
+            config,
+            data_versions: BTreeMap::new(),
+            context: None,
+        }
+    }
+
+    /// Records one data set's version; `None` versions are skipped.
+    pub fn with_data_version(mut self, name: &str, version: Option<&str>) -> Self {
+        if let Some(version) = version {
+            self.data_versions.insert(name.to_string(), version.to_string());
+        }
+        self
+    }
+
+    pub fn with_context(mut self, context: serde_json::Value) -> Self {
+        self.context = Some(context);
+        self
+    }
+
+    /// Replays the recorded fight.
+    pub fn run(&self) -> SimulationResult {
+        simulate_combat(&self.attacker, &self.defender, self.config, &self.crew)
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, SnapshotError> {
+        let snapshot: Self = serde_json::from_str(raw).map_err(SnapshotError::Parse)?;
+        if snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                found: snapshot.format_version,
+            });
+        }
+        Ok(snapshot)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SnapshotError> {
+        let raw = fs::read_to_string(path).map_err(SnapshotError::Io)?;
+        Self::from_json(&raw)
+    }
+
+    pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::abilities::{
+        Ability, AbilityClass, AbilityEffect, CrewSeat, CrewSeatContext, TimingWindow,
+    };
+
+    #[test]
+    fn snapshot_round_trips_through_json_and_replays_identically() {
+        let attacker = Combatant::builder("player")
+            .attack(120.0)
+            .pierce(0.15)
+            .hull_health(1000.0)
+            .build()
+            .unwrap();
+        let defender = Combatant::builder("hostile")
+            .attack(20.0)
+            .mitigation(0.35)
+            .hull_health(800.0)
+            .shield_health(200.0)
+            .build()
+            .unwrap();
+        let crew = CrewConfiguration {
+            seats: vec![CrewSeatContext::legacy(
+                CrewSeat::Captain,
+                Ability {
+                    name: "opening salvo".to_string(),
+                    class: AbilityClass::CaptainManeuver,
+                    timing: TimingWindow::CombatBegin,
+                    boostable: true,
+                    effect: AbilityEffect::AttackMultiplier(0.2),
+                    condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
+                },
+                false,
+            )],
+        };
+        let config = SimulationConfig::builder().rounds(5).seed(42).build();
+        let snapshot = SimulationSnapshot::new(attacker, defender, crew, config)
+            .with_data_version("ships", Some("2024-05-01"))
+            .with_data_version("hostiles", None)
+            .with_context(serde_json::json!({ "source": "test" }));
+
+        let json = snapshot.to_json_pretty().unwrap();
+        let restored = SimulationSnapshot::from_json(&json).unwrap();
+        assert_eq!(restored, snapshot);
+        assert_eq!(restored.data_versions.len(), 1);
+        assert_eq!(restored.run(), snapshot.run());
+
+        let newer = json.replace(
+            &format!("\"format_version\": {SNAPSHOT_FORMAT_VERSION}"),
+            "\"format_version\": 99",
+        );
+        assert!(matches!(
+            SimulationSnapshot::from_json(&newer),
+            Err(SnapshotError::UnsupportedVersion { found: 99 })
+        ));
+    }
+}
@@ -4,6 +4,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::Map;
 use serde_json::Value;
 
+use crate::combat::events::{round_f64, TRACE_SCHEMA_VERSION};
+use crate::combat::rng::RngMode;
+
 /// Combat mitigation parity implementation migrated from
 /// `tools/combat_engine/mitigation.py`.
 ///
@@ -41,6 +44,9 @@ pub const MORALE_PRIMARY_PIERCING_BONUS: f64 = 0.10;
 pub const HULL_BREACH_CRIT_BONUS: f64 = 1.5;
 pub const BURNING_HULL_DAMAGE_PER_ROUND: f64 = 0.01;
 pub const ASSIMILATED_EFFECTIVENESS_MULTIPLIER: f64 = 0.75;
+/// Effectiveness of a boosted officer's abilities (officer shard / away-team boost): +10%.
+/// Stacks multiplicatively with [ASSIMILATED_EFFECTIVENESS_MULTIPLIER].
+pub const BOOSTED_EFFECTIVENESS_MULTIPLIER: f64 = 1.10;
 
 pub const SURVEY_COEFFICIENTS: (f64, f64, f64) = (0.3, 0.3, 0.3);
 pub const BATTLESHIP_COEFFICIENTS: (f64, f64, f64) = (0.55, 0.2, 0.2);
@@ -180,6 +186,14 @@ pub struct CombatEvent {
     /// Sub-round (weapon) index when tracing multi-weapon resolution. Omitted when None.
     #[serde(skip_serializing_if = "Option::is_none", default)]
     pub weapon_index: Option<u32>,
+    /// [TRACE_SCHEMA_VERSION] the event was recorded with; events without one are read as the
+    /// current version.
+    #[serde(default = "trace_schema_version")]
+    pub schema_version: u32,
+}
+
+fn trace_schema_version() -> u32 {
+    TRACE_SCHEMA_VERSION
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -189,11 +203,221 @@ pub enum TraceMode {
     Events,
 }
 
+/// Every `event_type` the engine records; [TraceFilter::event_types] accepts these names.
+pub const TRACE_EVENT_TYPES: [&str; 23] = [
+    "round_start",
+    "attacker_hold_fire",
+    "ability_activation",
+    "ability_suppressed",
+    "assimilated_trigger",
+    "hull_breach_trigger",
+    "burning_trigger",
+    "dot_trigger",
+    "shots_bonus_trigger",
+    "morale_activation",
+    "attack_roll",
+    "pierce_calc",
+    "mitigation_calc",
+    "crit_resolution",
+    "proc_triggers",
+    "stack_resolution",
+    "damage_application",
+    "counter_attack_roll",
+    "counter_damage_application",
+    "end_of_round_effects",
+    "dot_damage",
+    "charged_attack",
+    "ship_destroyed",
+];
+
+/// Every `phase` the engine records; [TraceFilter::phases] accepts these names.
+pub const TRACE_PHASES: [&str; 15] = [
+    "combat_begin",
+    "round",
+    "round_start",
+    "attack",
+    "defense",
+    "proc",
+    "damage",
+    "counter_attack",
+    "end",
+    "round_end",
+    "shield_break",
+    "hull_breach",
+    "receive_damage",
+    "kill",
+    "combat_end",
+];
+
+/// Which events a traced fight keeps. The default keeps everything; each restriction narrows it.
+/// The collector checks the filter before an event is built, so dropped events cost nothing.
+/// `Copy` (name sets are bit masks over [TRACE_EVENT_TYPES] and [TRACE_PHASES]) so
+/// [SimulationConfig] stays `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "TraceFilterSpec", into = "TraceFilterSpec")]
+pub struct TraceFilter {
+    /// 0 = every event type.
+    event_types: u32,
+    /// 0 = every phase.
+    phases: u32,
+    first_round: u32,
+    last_round: Option<u32>,
+}
+
+/// Why a [TraceFilter] was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceFilterError {
+    UnknownEventType(String),
+    UnknownPhase(String),
+    /// `first_round` is after `last_round`.
+    EmptyRoundRange { first: u32, last: u32 },
+}
+
+impl std::fmt::Display for TraceFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownEventType(name) => write!(
+                f,
+                "unknown trace event type '{name}' (expected one of {})",
+                TRACE_EVENT_TYPES.join(", ")
+            ),
+            Self::UnknownPhase(name) => write!(
+                f,
+                "unknown trace phase '{name}' (expected one of {})",
+                TRACE_PHASES.join(", ")
+            ),
+            Self::EmptyRoundRange { first, last } => {
+                write!(f, "trace round range {first}..={last} is empty")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TraceFilterError {}
+
+fn name_mask<S: AsRef<str>>(
+    known: &[&str],
+    names: &[S],
+    unknown: fn(String) -> TraceFilterError,
+) -> Result<u32, TraceFilterError> {
+    names.iter().try_fold(0u32, |mask, name| {
+        let name = name.as_ref();
+        match known.iter().position(|k| *k == name) {
+            Some(i) => Ok(mask | (1 << i)),
+            None => Err(unknown(name.to_string())),
+        }
+    })
+}
+
+fn mask_names(known: &[&str], mask: u32) -> Vec<String> {
+    known
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| mask & (1 << i) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn mask_keeps(known: &[&str], mask: u32, name: &str) -> bool {
+    mask == 0
+        || known
+            .iter()
+            .position(|k| *k == name)
+            .is_some_and(|i| mask & (1 << i) != 0)
+}
+
+impl TraceFilter {
+    /// Keep only these event types (names from [TRACE_EVENT_TYPES]); empty keeps all.
+    pub fn event_types<S: AsRef<str>>(mut self, names: &[S]) -> Result<Self, TraceFilterError> {
+        self.event_types =
+            name_mask(&TRACE_EVENT_TYPES, names, TraceFilterError::UnknownEventType)?;
+        Ok(self)
+    }
+
+    /// Keep only these phases (names from [TRACE_PHASES]); empty keeps all.
+    pub fn phases<S: AsRef<str>>(mut self, names: &[S]) -> Result<Self, TraceFilterError> {
+        self.phases = name_mask(&TRACE_PHASES, names, TraceFilterError::UnknownPhase)?;
+        Ok(self)
+    }
+
+    /// Keep rounds `first..=last` (round 0 holds combat-begin events); `last: None` is open.
+    pub fn rounds(mut self, first: u32, last: Option<u32>) -> Result<Self, TraceFilterError> {
+        if let Some(last) = last.filter(|last| *last < first) {
+            return Err(TraceFilterError::EmptyRoundRange { first, last });
+        }
+        self.first_round = first;
+        self.last_round = last;
+        Ok(self)
+    }
+
+    pub fn is_unfiltered(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn keeps(&self, event_type: &str, phase: &str, round_index: u32) -> bool {
+        round_index >= self.first_round
+            && self.last_round.is_none_or(|last| round_index <= last)
+            && mask_keeps(&TRACE_EVENT_TYPES, self.event_types, event_type)
+            && mask_keeps(&TRACE_PHASES, self.phases, phase)
+    }
+}
+
+/// Serialized form of [TraceFilter]: names instead of bit masks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TraceFilterSpec {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    event_types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    phases: Vec<String>,
+    #[serde(default)]
+    first_round: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_round: Option<u32>,
+}
+
+impl TryFrom<TraceFilterSpec> for TraceFilter {
+    type Error = TraceFilterError;
+
+    fn try_from(spec: TraceFilterSpec) -> Result<Self, Self::Error> {
+        TraceFilter::default()
+            .event_types(&spec.event_types)?
+            .phases(&spec.phases)?
+            .rounds(spec.first_round, spec.last_round)
+    }
+}
+
+impl From<TraceFilter> for TraceFilterSpec {
+    fn from(filter: TraceFilter) -> Self {
+        Self {
+            event_types: mask_names(&TRACE_EVENT_TYPES, filter.event_types),
+            phases: mask_names(&TRACE_PHASES, filter.phases),
+            first_round: filter.first_round,
+            last_round: filter.last_round,
+        }
+    }
+}
+
+/// Fight settings. Outside the crate, build one with [SimulationConfig::builder] (or start from
+/// [SimulationConfig::default] and set fields): new settings are added as fields.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct SimulationConfig {
     pub rounds: u32,
     pub seed: u64,
     pub trace_mode: TraceMode,
+    /// Roll generator. [RngMode::Legacy] reproduces pre-counter-RNG results (golden tests).
+    #[serde(default)]
+    pub rng_mode: RngMode,
+    /// Rounds at the start of the fight in which the crewed ship holds fire (e.g. still
+    /// mining) while the opponent shoots. Crew abilities still apply; 0 = fight from round 1.
+    #[serde(default)]
+    pub attacker_delay_rounds: u32,
+    /// Events a traced fight keeps; ignored when tracing is off.
+    #[serde(default, skip_serializing_if = "TraceFilter::is_unfiltered")]
+    pub trace_filter: TraceFilter,
+    /// Who shoots first in each sub-round (PvP).
+    #[serde(default, skip_serializing_if = "InitiativeMode::is_simultaneous")]
+    pub initiative: InitiativeMode,
 }
 
 impl Default for SimulationConfig {
@@ -202,6 +426,55 @@ impl Default for SimulationConfig {
             rounds: 3,
             seed: 7,
             trace_mode: TraceMode::Off,
+            rng_mode: RngMode::default(),
+            attacker_delay_rounds: 0,
+            trace_filter: TraceFilter::default(),
+            initiative: InitiativeMode::default(),
+        }
+    }
+}
+
+/// Who shoots first in each sub-round. It only matters when both ships can destroy each other
+/// (PvP): in the sequential modes a ship destroyed by the first volley of a sub-round does not
+/// fire back, and the fight ends there instead of at the end of the round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitiativeMode {
+    /// Both ships fire in every sub-round of a round even if one is destroyed partway; the
+    /// outcome is decided at round end (the engine's behavior before initiative existed).
+    #[default]
+    Simultaneous,
+    /// The crewed ship's volley resolves first in each sub-round.
+    AttackerFirst,
+    /// The opponent's volley resolves first in each sub-round.
+    DefenderFirst,
+}
+
+/// One side's volley within a sub-round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Shooter {
+    Attacker,
+    Defender,
+}
+
+impl InitiativeMode {
+    pub fn is_simultaneous(&self) -> bool {
+        *self == Self::Simultaneous
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Simultaneous => "simultaneous",
+            Self::AttackerFirst => "attacker_first",
+            Self::DefenderFirst => "defender_first",
+        }
+    }
+
+    /// Volley order inside each sub-round; simultaneous fire resolves the attacker's first.
+    pub(crate) fn sub_round_order(self) -> [Shooter; 2] {
+        match self {
+            Self::DefenderFirst => [Shooter::Defender, Shooter::Attacker],
+            Self::Simultaneous | Self::AttackerFirst => [Shooter::Attacker, Shooter::Defender],
         }
     }
 }
@@ -217,9 +490,115 @@ pub struct SimulationResult {
     /// Defender shield HP remaining at end of combat (0 when shields were depleted).
     #[serde(default)]
     pub defender_shield_remaining: f64,
+    /// Attacker shield HP remaining at end of combat.
+    #[serde(default)]
+    pub attacker_shield_remaining: f64,
+    /// Where the attacker's damage came from (`total_damage` split by source).
+    #[serde(default)]
+    pub attacker_damage: DamageBreakdown,
+    /// Where the defender's damage to the attacker came from.
+    #[serde(default)]
+    pub defender_damage: DamageBreakdown,
     pub events: Vec<CombatEvent>,
 }
 
+/// One side's damage over the fight by source, so a damage-source chart needs no trace.
+/// Shot damage is split multiplicatively after apex: `crit_damage` and `proc_damage` are what
+/// the crit and proc multipliers added on top of the shot, `isolytic_damage` is the isolytic
+/// part of shots plus isolytic cascade ticks. `shield_damage` is the share of all damage that
+/// shields absorbed rather than a source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DamageBreakdown {
+    pub crit_damage: f64,
+    pub proc_damage: f64,
+    pub isolytic_damage: f64,
+    /// Burning ticks (attacker only; hostiles do not burn the player).
+    pub burning_damage: f64,
+    pub shield_damage: f64,
+}
+
+impl DamageBreakdown {
+    /// Adds one shot: `standard` is its mitigated damage after apex, `isolytic` the isolytic
+    /// part after apex, and `crit_multiplier`/`proc_multiplier` the multipliers it rolled.
+    pub(crate) fn add_shot(
+        &mut self,
+        standard: f64,
+        isolytic: f64,
+        crit_multiplier: f64,
+        proc_multiplier: f64,
+        shield_damage: f64,
+    ) {
+        let before_crit = if crit_multiplier > 0.0 { standard / crit_multiplier } else { 0.0 };
+        let before_proc = if proc_multiplier > 0.0 { before_crit / proc_multiplier } else { 0.0 };
+        self.crit_damage += standard - before_crit;
+        self.proc_damage += before_crit - before_proc;
+        self.isolytic_damage += isolytic;
+        self.shield_damage += shield_damage;
+    }
+
+    pub(crate) fn rounded(self) -> Self {
+        Self {
+            crit_damage: round_f64(self.crit_damage),
+            proc_damage: round_f64(self.proc_damage),
+            isolytic_damage: round_f64(self.isolytic_damage),
+            burning_damage: round_f64(self.burning_damage),
+            shield_damage: round_f64(self.shield_damage),
+        }
+    }
+}
+
+/// Hull and shield the crewed ship enters a fight with, when it is not at full health
+/// (e.g. a later wave in [crate::combat::waves::simulate_waves]).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AttackerStartState {
+    pub hull: f64,
+    pub shield: f64,
+}
+
+impl AttackerStartState {
+    /// Full hull and shields.
+    pub fn fresh(attacker: &Combatant) -> Self {
+        Self {
+            hull: attacker.hull_health,
+            shield: attacker.shield_health,
+        }
+    }
+}
+
+/// Share of the crewed ship's hull and shields still up when a scenario starts, for a ship that
+/// is already damaged (e.g. "can I finish this armada at 40% hull"). Full health by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AttackerCondition {
+    /// Hull left, as a share of full hull (0–1, above 0).
+    pub hull_pct: f64,
+    /// Shields up, as a share of full shields (0–1).
+    pub shield_pct: f64,
+}
+
+impl Default for AttackerCondition {
+    fn default() -> Self {
+        Self {
+            hull_pct: 1.0,
+            shield_pct: 1.0,
+        }
+    }
+}
+
+impl AttackerCondition {
+    /// True at full hull and shields.
+    pub fn is_fresh(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Hull and shields `attacker` enters its first fight with.
+    pub fn start_state(&self, attacker: &Combatant) -> AttackerStartState {
+        AttackerStartState {
+            hull: attacker.hull_health * self.hull_pct.clamp(0.0, 1.0),
+            shield: attacker.shield_health * self.shield_pct.clamp(0.0, 1.0),
+        }
+    }
+}
+
 /// Per-weapon stats for sub-round resolution. Combatant-level pierce/crit/proc apply to all weapons.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WeaponStats {
@@ -229,7 +608,26 @@ pub struct WeaponStats {
     pub shots: Option<u32>,
 }
 
+/// Charge-bar heavy attack (armada ultimates): the defender charges for `every_rounds` rounds and
+/// fires one attack of `damage` at the end of each full bar, through the player's mitigation,
+/// apex barrier and shields.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChargedAttack {
+    pub every_rounds: u32,
+    pub damage: f64,
+}
+
+impl ChargedAttack {
+    /// Whether the bar is full at the end of `round_index` (1-based); never for `every_rounds` 0.
+    pub fn fires_in(&self, round_index: u32) -> bool {
+        self.every_rounds > 0 && round_index > 0 && round_index.is_multiple_of(self.every_rounds)
+    }
+}
+
+/// One ship in a fight. Outside the crate, build one with [Combatant::builder], which also
+/// validates the stats: new stats are added as fields.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Combatant {
     pub id: String,
     pub attack: f64,
@@ -262,6 +660,13 @@ pub struct Combatant {
     /// Per-weapon attack values for sub-round resolution. If empty, one weapon with scalar `attack` is used (backward compat).
     #[serde(default)]
     pub weapons: Vec<WeaponStats>,
+    /// Target tags (hull class, `swarm`, `faction:<id>`, …). When this combatant defends, crew effects
+    /// conditioned on [crate::combat::AbilityCondition::TargetTag] match against them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Defender: periodic heavy attack on top of weapon fire (armadas).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charged_attack: Option<ChargedAttack>,
 }
 
 fn default_shield_mitigation() -> f64 {
@@ -303,34 +708,93 @@ impl Combatant {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct TraceCollector {
+/// The parts of a [CombatEvent] that depend on fight state; [TraceCollector::record_if] adds
+/// the event type, phase and round.
+pub(crate) struct TraceDetail {
+    pub source: EventSource,
+    pub weapon_index: Option<u32>,
+    pub values: Map<String, Value>,
+}
+
+/// Collects trace events, or hands each one to a sink as it happens (see
+/// [crate::combat::simulate_combat_streaming]).
+#[derive(Default)]
+pub(crate) struct TraceCollector<'a> {
     enabled: bool,
+    filter: TraceFilter,
     events: Vec<CombatEvent>,
+    sink: Option<&'a mut dyn FnMut(CombatEvent)>,
 }
 
-impl TraceCollector {
+impl std::fmt::Debug for TraceCollector<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceCollector")
+            .field("enabled", &self.enabled)
+            .field("filter", &self.filter)
+            .field("events", &self.events)
+            .field("streaming", &self.sink.is_some())
+            .finish()
+    }
+}
+
+impl<'a> TraceCollector<'a> {
     pub fn new(enabled: bool) -> Self {
         Self {
             enabled,
-            events: Vec::new(),
+            ..Self::default()
         }
     }
 
-    pub fn record(&mut self, event: CombatEvent) {
-        if self.enabled {
-            self.events.push(event);
+    /// Enabled collector that passes events to `sink` instead of buffering them.
+    pub fn streaming(sink: &'a mut dyn FnMut(CombatEvent)) -> Self {
+        Self {
+            enabled: true,
+            sink: Some(sink),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_filter(mut self, filter: TraceFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// False for TraceMode::Off: callers skip work whose only output is trace events.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Records an event only when tracing is enabled and the filter keeps it. The closure is
+    /// not called otherwise, so TraceMode::Off and filtered-out events allocate nothing.
+    pub fn record_if(
+        &mut self,
+        event_type: &'static str,
+        phase: &str,
+        round_index: u32,
+        f: impl FnOnce() -> TraceDetail,
+    ) {
+        if self.enabled && self.filter.keeps(event_type, phase, round_index) {
+            let detail = f();
+            self.push(CombatEvent {
+                event_type: event_type.to_string(),
+                round_index,
+                phase: phase.to_string(),
+                source: detail.source,
+                values: detail.values,
+                weapon_index: detail.weapon_index,
+                schema_version: TRACE_SCHEMA_VERSION,
+            });
         }
     }
 
-    /// Records an event only when tracing is enabled. The closure is not called when disabled,
-    /// avoiding allocation and construction of CombatEvent when TraceMode::Off.
-    pub fn record_if(&mut self, f: impl FnOnce() -> CombatEvent) {
-        if self.enabled {
-            self.events.push(f());
+    fn push(&mut self, event: CombatEvent) {
+        match self.sink.as_mut() {
+            Some(sink) => sink(event),
+            None => self.events.push(event),
         }
     }
 
+    /// Buffered events (always empty when streaming).
     pub fn events(self) -> Vec<CombatEvent> {
         self.events
     }
@@ -394,3 +858,88 @@ mod enemy_types_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod trace_collector_tests {
+    use super::*;
+
+    fn round_start() -> TraceDetail {
+        TraceDetail {
+            source: EventSource {
+                ship_ability_id: Some("baseline_round".to_string()),
+                ..EventSource::default()
+            },
+            values: Map::new(),
+            weapon_index: None,
+        }
+    }
+
+    #[test]
+    fn records_only_when_enabled() {
+        let mut trace_on = TraceCollector::new(true);
+        trace_on.record_if("round_start", "round", 1, round_start);
+        let events = trace_on.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event_type, "round_start");
+        assert_eq!(events[0].phase, "round");
+
+        let mut trace_off = TraceCollector::new(false);
+        assert!(!trace_off.is_enabled());
+        trace_off.record_if("round_start", "round", 1, || {
+            panic!("disabled collector must not build events")
+        });
+        assert!(trace_off.events().is_empty());
+    }
+
+    #[test]
+    fn streaming_hands_events_to_the_sink() {
+        let mut seen = Vec::new();
+        let mut sink = |event: CombatEvent| seen.push(event);
+        let mut trace = TraceCollector::streaming(&mut sink);
+        trace.record_if("round_start", "round", 1, round_start);
+        assert!(trace.events().is_empty());
+        assert_eq!(seen.len(), 1);
+    }
+
+    #[test]
+    fn filter_skips_events_before_they_are_built() {
+        let filter = TraceFilter::default()
+            .event_types(&["attack_roll", "round_start"])
+            .unwrap()
+            .rounds(2, Some(3))
+            .unwrap();
+        let mut trace = TraceCollector::new(true).with_filter(filter);
+        trace.record_if("round_start", "round", 2, round_start);
+        trace.record_if("round_start", "round", 4, || panic!("round 4 is filtered out"));
+        trace.record_if("pierce_calc", "attack", 2, || panic!("pierce_calc is filtered out"));
+        assert_eq!(trace.events().len(), 1);
+
+        let phases = TraceFilter::default().phases(&["attack"]).unwrap();
+        assert!(phases.keeps("pierce_calc", "attack", 0));
+        assert!(!phases.keeps("round_start", "round", 0));
+        assert!(TraceFilter::default().is_unfiltered());
+    }
+
+    #[test]
+    fn filter_rejects_unknown_names_and_round_trips_as_names() {
+        assert_eq!(
+            TraceFilter::default().event_types(&["nope"]),
+            Err(TraceFilterError::UnknownEventType("nope".to_string()))
+        );
+        assert!(TraceFilter::default().phases(&["nope"]).is_err());
+        assert_eq!(
+            TraceFilter::default().rounds(5, Some(2)),
+            Err(TraceFilterError::EmptyRoundRange { first: 5, last: 2 })
+        );
+
+        let filter = TraceFilter::default()
+            .event_types(&["dot_damage"])
+            .unwrap()
+            .rounds(1, None)
+            .unwrap();
+        let json = serde_json::to_value(filter).unwrap();
+        assert_eq!(json, serde_json::json!({ "event_types": ["dot_damage"], "first_round": 1 }));
+        assert_eq!(serde_json::from_value::<TraceFilter>(json).unwrap(), filter);
+        assert!(serde_json::from_str::<TraceFilter>(r#"{"phases":["nope"]}"#).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Multi-wave fights (territory capture defense, takedowns): the crewed ship meets consecutive
+//! opponents without a hull/shield reset, optionally repairing part of the damage between waves.
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::abilities::{CrewConfiguration, CrewEffects};
+use crate::combat::engine::{simulate_combat_with_scratch, CombatScratch};
+use crate::combat::rng::Rng;
+use crate::combat::types::{AttackerStartState, Combatant, SimulationConfig, SimulationResult};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WavesResult {
+    /// Opponents destroyed before the ship died, stalled at the round limit, or ran out of waves.
+    pub waves_cleared: u32,
+    /// Hull left after the last wave fought (before any repair).
+    pub attacker_hull_remaining: f64,
+    pub attacker_shield_remaining: f64,
+    /// One result per wave fought, in order; the last entry is the wave that ended the run.
+    pub waves: Vec<SimulationResult>,
+}
+
+/// Fight `defenders` in order with the same crew, carrying hull and shields from one wave into
+/// the next. Between waves, `repair_fraction` (0–1) of the missing hull and shields is restored.
+///
+/// Wave 0 uses `config.seed` unchanged, so a single-wave run matches [simulate_combat]; later
+/// waves use split seeds. `config.attacker_delay_rounds` only applies to the first wave.
+///
+/// [simulate_combat]: crate::combat::simulate_combat
+pub fn simulate_waves(
+    attacker: &Combatant,
+    defenders: &[Combatant],
+    config: SimulationConfig,
+    attacker_crew: &CrewConfiguration,
+    repair_fraction: f64,
+) -> WavesResult {
+    let effects = CrewEffects::new(attacker_crew);
+    let mut scratch = CombatScratch::default();
+    simulate_waves_with_effects(
+        attacker,
+        defenders,
+        config,
+        &effects,
+        AttackerStartState::fresh(attacker),
+        repair_fraction,
+        &mut scratch,
+    )
+}
+
+/// [simulate_waves] with the crew's abilities already grouped (see [CrewEffects]), entering the
+/// first wave with `start`'s hull and shields; every wave fights in `scratch` (see
+/// [CombatScratch]).
+pub fn simulate_waves_with_effects(
+    attacker: &Combatant,
+    defenders: &[Combatant],
+    config: SimulationConfig,
+    effects: &CrewEffects,
+    start: AttackerStartState,
+    repair_fraction: f64,
+    scratch: &mut CombatScratch,
+) -> WavesResult {
+    let repair = repair_fraction.clamp(0.0, 1.0);
+    let seeds = Rng::new(config.seed);
+    let mut state = start;
+    let mut waves = Vec::with_capacity(defenders.len());
+    let mut waves_cleared = 0u32;
+
+    for (wave_index, defender) in defenders.iter().enumerate() {
+        if wave_index > 0 {
+            state.hull += (attacker.hull_health - state.hull).max(0.0) * repair;
+            state.shield += (attacker.shield_health - state.shield).max(0.0) * repair;
+        }
+        let wave_config = SimulationConfig {
+            seed: if wave_index == 0 {
+                config.seed
+            } else {
+                seeds.split(wave_index as u64).next_u64()
+            },
+            attacker_delay_rounds: if wave_index == 0 {
+                config.attacker_delay_rounds
+            } else {
+                0
+            },
+            ..config
+        };
+        let result =
+            simulate_combat_with_scratch(attacker, defender, wave_config, effects, state, scratch);
+        state = AttackerStartState {
+            hull: result.attacker_hull_remaining,
+            shield: result.attacker_shield_remaining,
+        };
+        let cleared = result.defender_hull_remaining <= 0.0;
+        waves.push(result);
+        if !cleared || state.hull <= 0.0 {
+            break;
+        }
+        waves_cleared += 1;
+    }
+
+    WavesResult {
+        waves_cleared,
+        attacker_hull_remaining: state.hull,
+        attacker_shield_remaining: state.shield,
+        waves,
+    }
+}
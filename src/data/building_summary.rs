@@ -169,6 +169,9 @@ pub fn building_combat_summary_for_profile(profile_id: &str) -> BuildingCombatSu
         bonuses: HashMap::new(),
         forbidden_tech_override: None,
         chaos_tech_override: None,
+        officer_traits: false,
+        officer_stats: false,
+        target_bonuses: Default::default(),
     };
     merge_building_bonuses_into_profile(
         &mut scratch,
@@ -0,0 +1,179 @@
+//! Crew codes: a crew, its ship (and optionally the hostile) and a profile bonus summary packed
+//! into one short URL-safe string, so players share a crew that runs instead of a screenshot.
+//!
+//! A code is unpadded URL-safe base64 of a version byte ([CREW_CODE_VERSION]) followed by compact
+//! JSON with one-letter keys. `GET /api/crewcode/{code}` decodes it into a scenario file body for
+//! `POST /api/simulate/scenario`.
+
+use std::fmt;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::data::profile::PlayerBonuses;
+
+/// Leading byte of every encoded payload; bumped when the wire fields change meaning.
+pub const CREW_CODE_VERSION: u8 = 1;
+/// Longest code accepted, in characters.
+pub const MAX_CREW_CODE_LEN: usize = 2048;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CrewCode {
+    pub ship: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_tier: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_level: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub captain: Option<String>,
+    /// Bridge officer ids; None is an empty seat.
+    #[serde(default)]
+    pub bridge: Vec<Option<String>>,
+    #[serde(default)]
+    pub below_deck: Vec<Option<String>>,
+    /// Profile bonuses of the player who shared the crew.
+    #[serde(default)]
+    pub bonuses: PlayerBonuses,
+}
+
+/// [CrewCode] with the one-letter keys that keep codes short.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Wire {
+    s: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    t: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    l: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    h: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    c: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    b: Vec<Option<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    d: Vec<Option<String>>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    p: PlayerBonuses,
+}
+
+fn is_default(bonuses: &PlayerBonuses) -> bool {
+    *bonuses == PlayerBonuses::default()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrewCodeError {
+    TooLong(usize),
+    Base64(String),
+    UnsupportedVersion(u8),
+    Payload(String),
+}
+
+impl fmt::Display for CrewCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLong(len) => {
+                write!(f, "crew code is {len} characters; the limit is {MAX_CREW_CODE_LEN}")
+            }
+            Self::Base64(e) => write!(f, "crew code is not valid base64: {e}"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "crew code version {v} is not supported (this build reads {CREW_CODE_VERSION})"
+            ),
+            Self::Payload(e) => write!(f, "crew code payload is invalid: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CrewCodeError {}
+
+pub fn encode_crew_code(code: &CrewCode) -> String {
+    let wire = Wire {
+        s: code.ship.clone(),
+        t: code.ship_tier,
+        l: code.ship_level,
+        h: code.hostile.clone(),
+        c: code.captain.clone(),
+        b: code.bridge.clone(),
+        d: code.below_deck.clone(),
+        p: code.bonuses.clone(),
+    };
+    let mut bytes = vec![CREW_CODE_VERSION];
+    serde_json::to_writer(&mut bytes, &wire).expect("crew code fields serialize");
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+pub fn decode_crew_code(code: &str) -> Result<CrewCode, CrewCodeError> {
+    let code = code.trim();
+    if code.len() > MAX_CREW_CODE_LEN {
+        return Err(CrewCodeError::TooLong(code.len()));
+    }
+    let bytes = URL_SAFE_NO_PAD
+        .decode(code)
+        .map_err(|e| CrewCodeError::Base64(e.to_string()))?;
+    let (&version, payload) = bytes
+        .split_first()
+        .ok_or_else(|| CrewCodeError::Payload("empty".to_string()))?;
+    if version != CREW_CODE_VERSION {
+        return Err(CrewCodeError::UnsupportedVersion(version));
+    }
+    let wire: Wire =
+        serde_json::from_slice(payload).map_err(|e| CrewCodeError::Payload(e.to_string()))?;
+    Ok(CrewCode {
+        ship: wire.s,
+        ship_tier: wire.t,
+        ship_level: wire.l,
+        hostile: wire.h,
+        captain: wire.c,
+        bridge: wire.b,
+        below_deck: wire.d,
+        bonuses: wire.p,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip_and_stay_url_safe() {
+        let code = CrewCode {
+            ship: "saladin".to_string(),
+            ship_tier: Some(4),
+            ship_level: Some(30),
+            hostile: Some("swarm_33".to_string()),
+            captain: Some("kirk".to_string()),
+            bridge: vec![Some("spock".to_string()), None],
+            below_deck: vec![Some("uhura".to_string())],
+            bonuses: PlayerBonuses {
+                weapon_damage: Some(0.25),
+                ..PlayerBonuses::default()
+            },
+        };
+        let encoded = encode_crew_code(&code);
+        assert!(encoded.len() < 200, "{encoded}");
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode_crew_code(&encoded).unwrap(), code);
+
+        let bare = CrewCode { ship: "saladin".to_string(), ..CrewCode::default() };
+        assert_eq!(decode_crew_code(&encode_crew_code(&bare)).unwrap(), bare);
+    }
+
+    #[test]
+    fn malformed_codes_are_rejected() {
+        assert!(matches!(decode_crew_code("not base64!"), Err(CrewCodeError::Base64(_))));
+        assert!(matches!(decode_crew_code(""), Err(CrewCodeError::Payload(_))));
+        let future = URL_SAFE_NO_PAD.encode([2, b'{', b'}']);
+        assert_eq!(decode_crew_code(&future), Err(CrewCodeError::UnsupportedVersion(2)));
+        let unknown_key = URL_SAFE_NO_PAD.encode(b"\x01{\"s\":\"a\",\"x\":1}");
+        assert!(matches!(decode_crew_code(&unknown_key), Err(CrewCodeError::Payload(_))));
+        let long = "A".repeat(MAX_CREW_CODE_LEN + 1);
+        assert!(matches!(decode_crew_code(&long), Err(CrewCodeError::TooLong(_))));
+    }
+}
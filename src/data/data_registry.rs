@@ -1,9 +1,16 @@
 //! Startup-loaded data cache (DataRegistry) for the server.
 //! Load once at startup, pass via Arc to handlers and optimizer to avoid reloading on every request.
+//! [SharedRegistry] lets the server swap in a freshly loaded registry after the data on disk
+//! changed (remote data updates) without a restart.
 
 use std::collections::HashMap;
+use std::fs;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::UNIX_EPOCH;
+
+use schemars::JsonSchema;
+use serde::Serialize;
 
 use crate::data::forbidden_chaos::{
     load_forbidden_chaos, ForbiddenChaosList, DEFAULT_FORBIDDEN_CHAOS_PATH,
@@ -13,8 +20,14 @@ use crate::data::research::{
 };
 use crate::data::hostile::{load_hostile_index, HostileIndex, HostileRecord, DEFAULT_HOSTILES_INDEX_PATH};
 use crate::data::hostile_loca::load_hostile_loca_display_names;
-use crate::data::loader::{resolve_hostile_with_index, resolve_ship_with_tier_level};
-use crate::data::officer::{load_canonical_officers, Officer, DEFAULT_CANONICAL_OFFICERS_PATH};
+use crate::data::loader::{
+    fuzzy_hostile_id, fuzzy_ship_id, hostile_near_misses, resolve_hostile_with_index,
+    resolve_ship_with_tier_level, ship_near_misses,
+};
+use crate::data::officer::{
+    enrich_officers_from_summary, load_canonical_officers, Officer,
+    DEFAULT_CANONICAL_OFFICERS_PATH, DEFAULT_OFFICER_SUMMARY_PATH,
+};
 use crate::data::ship::{
     load_extended_ship_index, ExtendedShipIndex, ShipRecord, DEFAULT_SHIPS_EXTENDED_DIR,
 };
@@ -51,6 +64,65 @@ impl OfficerCache {
     }
 }
 
+/// Content hash, size and age of one dataset's files on disk.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DatasetFiles {
+    /// FNV-1a hash (hex) of the relative paths and contents of every file in the dataset.
+    pub content_hash: String,
+    /// Records loaded from the dataset (officers, ships or hostiles).
+    pub records: usize,
+    /// Files hashed.
+    pub files: usize,
+    /// Newest file modification time (unix seconds).
+    pub last_modified: Option<u64>,
+}
+
+impl DatasetFiles {
+    fn compute(path: &Path, records: usize) -> Self {
+        let (files, last_modified) = file_count_and_newest(path);
+        Self {
+            content_hash: format!(
+                "{:016x}",
+                crate::optimizer::result_cache::path_content_hash(path)
+            ),
+            records,
+            files,
+            last_modified,
+        }
+    }
+}
+
+fn file_count_and_newest(path: &Path) -> (usize, Option<u64>) {
+    let Ok(meta) = fs::metadata(path) else {
+        return (0, None);
+    };
+    if !meta.is_dir() {
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        return (1, modified);
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return (0, None);
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| file_count_and_newest(&e.path()))
+        .fold((0, None), |(count, newest), (n, modified)| {
+            (count + n, newest.max(modified))
+        })
+}
+
+/// [DatasetFiles] for the officer, ship and hostile data the registry was loaded from.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DataFileVersions {
+    pub officers: DatasetFiles,
+    pub ships: DatasetFiles,
+    pub hostiles: DatasetFiles,
+}
+
 /// Read-only registry of static game data loaded once at startup.
 /// Profile and import roster are intentionally excluded (loaded at use time).
 #[derive(Debug)]
@@ -66,6 +138,8 @@ pub struct DataRegistry {
     pub forbidden_chaos_catalog: Option<ForbiddenChaosList>,
     /// Research catalog for merging into profile with synced research levels.
     pub research_catalog: Option<ResearchCatalog>,
+    /// Hashed on first use (the hostile tree is tens of MB); see [DataRegistry::file_versions].
+    file_versions: OnceLock<DataFileVersions>,
 }
 
 impl DataRegistry {
@@ -74,7 +148,8 @@ impl DataRegistry {
     pub fn load() -> Result<Arc<DataRegistry>, std::io::Error> {
         const DEFAULT_LCARS_OFFICERS_DIR: &str = "data/officers";
 
-        let officers = load_canonical_officers(Path::new(DEFAULT_CANONICAL_OFFICERS_PATH))?;
+        let mut officers = load_canonical_officers(Path::new(DEFAULT_CANONICAL_OFFICERS_PATH))?;
+        enrich_officers_from_summary(&mut officers, DEFAULT_OFFICER_SUMMARY_PATH);
         let officers = OfficerCache::from_officers(officers);
 
         let ship_index = Path::new(DEFAULT_SHIPS_EXTENDED_DIR)
@@ -101,6 +176,7 @@ impl DataRegistry {
             lcars_officers,
             forbidden_chaos_catalog,
             research_catalog,
+            file_versions: OnceLock::new(),
         }))
     }
 
@@ -125,6 +201,30 @@ impl DataRegistry {
         self.research_catalog.as_ref()
     }
 
+    /// Content hashes, counts and modification times of the officer, ship and hostile files.
+    /// Computed once per registry, so a reload after a data update reports the new files.
+    pub fn file_versions(&self) -> &DataFileVersions {
+        self.file_versions.get_or_init(|| {
+            let officers_dir = Path::new(DEFAULT_CANONICAL_OFFICERS_PATH)
+                .parent()
+                .unwrap_or(Path::new("data/officers"));
+            let hostiles_dir = Path::new(DEFAULT_HOSTILES_INDEX_PATH)
+                .parent()
+                .unwrap_or(Path::new("data/hostiles"));
+            DataFileVersions {
+                officers: DatasetFiles::compute(officers_dir, self.officers().len()),
+                ships: DatasetFiles::compute(
+                    Path::new(DEFAULT_SHIPS_EXTENDED_DIR),
+                    self.ship_index().map_or(0, |i| i.ships.len()),
+                ),
+                hostiles: DatasetFiles::compute(
+                    hostiles_dir,
+                    self.hostile_index().map_or(0, |i| i.hostiles.len()),
+                ),
+            }
+        })
+    }
+
     /// Officer list for API listing and crew generator pool building.
     pub fn officers(&self) -> &[Officer] {
         &self.officers.officers
@@ -171,4 +271,76 @@ impl DataRegistry {
         let data_dir = Path::new(DEFAULT_HOSTILES_INDEX_PATH).parent()?;
         resolve_hostile_with_index(index, data_dir, name_or_id)
     }
+
+    /// Canonical ship id for an id or typed name, or up to [crate::data::loader::NEAR_MISS_LIMIT] suggestions.
+    pub fn ship_id_for(&self, query: &str) -> Result<String, Vec<String>> {
+        match self.ship_index.as_ref() {
+            Some(index) => fuzzy_ship_id(index, query),
+            None => Err(Vec::new()),
+        }
+    }
+
+    /// Canonical hostile id for an id, "name level" or typed display name ("swarm cluster 37"),
+    /// or up to [crate::data::loader::NEAR_MISS_LIMIT] suggestions.
+    pub fn hostile_id_for(&self, query: &str) -> Result<String, Vec<String>> {
+        match self.hostile_index.as_ref() {
+            Some(index) => fuzzy_hostile_id(index, &self.hostile_loca_display, query),
+            None => Err(Vec::new()),
+        }
+    }
+
+    /// Up to `limit` ship ids matching `query`, best first.
+    pub fn search_ships(&self, query: &str, limit: usize) -> Vec<String> {
+        self.ship_index
+            .as_ref()
+            .map(|index| ship_near_misses(index, query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Up to `limit` hostile ids matching `query` (ids, names and display names), best first.
+    pub fn search_hostiles(&self, query: &str, limit: usize) -> Vec<String> {
+        self.hostile_index
+            .as_ref()
+            .map(|index| hostile_near_misses(index, &self.hostile_loca_display, query, limit))
+            .unwrap_or_default()
+    }
+}
+
+/// The registry handed to requests, replaceable at runtime. Each request takes a snapshot with
+/// [SharedRegistry::current] and keeps using it even when a reload happens mid-run.
+#[derive(Debug)]
+pub struct SharedRegistry {
+    current: RwLock<Arc<DataRegistry>>,
+}
+
+impl SharedRegistry {
+    pub fn new(registry: Arc<DataRegistry>) -> Self {
+        Self {
+            current: RwLock::new(registry),
+        }
+    }
+
+    pub fn current(&self) -> Arc<DataRegistry> {
+        self.current
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn replace(&self, registry: Arc<DataRegistry>) {
+        *self
+            .current
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = registry;
+    }
+
+    /// Load the data files again and make them current. On error the previous registry stays.
+    /// Also drops the cached static data fingerprint, so result cache entries and schedules see
+    /// the new data.
+    pub fn reload(&self) -> Result<(), std::io::Error> {
+        let registry = DataRegistry::load()?;
+        crate::optimizer::result_cache::refresh_static_data_fingerprint();
+        self.replace(registry);
+        Ok(())
+    }
 }
@@ -84,7 +84,7 @@ pub fn load_seed_file(
     let content = match fs::read_to_string(&path) {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("heuristics: could not read '{path}': {e}", path = path.display());
+            tracing::warn!(path = %path.display(), error = %e, "heuristics: could not read seed file");
             return Vec::new();
         }
     };
@@ -256,13 +256,14 @@ fn resolve_name(
     match matches.len() {
         1 => Some(matches[0].clone()),
         0 => {
-            eprintln!("heuristics: no match for officer name '{trimmed}'; skipping");
+            tracing::warn!(officer = trimmed, "heuristics: no match for officer name; skipping");
             None
         }
         n => {
-            eprintln!(
-                "heuristics: ambiguous officer name '{trimmed}' ({n} matches); skipping. \
-                 Use a more specific name."
+            tracing::warn!(
+                officer = trimmed,
+                matches = n,
+                "heuristics: ambiguous officer name; skipping. Use a more specific name."
             );
             None
         }
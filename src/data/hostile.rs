@@ -4,13 +4,14 @@
 //! **Display names:** `normalize_hostiles_stfc_space` sets `hostile_name` to `Hostile {id}` until a
 //! `loca_id` → string map (e.g. `translations-hostiles` from data.stfc.space) is wired into that tool.
 
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::combat::{DefenderStats, ShipType};
+use crate::combat::{ChargedAttack, DefenderStats, ShipType};
 
 #[derive(Debug, Clone)]
 pub struct Hostile {
@@ -34,8 +35,26 @@ pub struct HostileResourceDrop {
     pub max: i64,
 }
 
-/// Normalized hostile record (KOBAYASHI schema). Written by normalizer, loaded at runtime.
+/// Armada mechanics on top of a hostile's stats (hand-entered; upstream has none). The armada's
+/// large Apex Barrier is the record's own `apex_barrier` (or the `armada` scaling curve).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmadaTarget {
+    /// Rounds the charge bar takes to fill; the charged attack fires at the end of each full bar.
+    pub charge_rounds: u32,
+    /// Damage of one charged attack before the player's mitigation, apex barrier and shields.
+    pub charge_damage: f64,
+    /// Ships fighting it together (1 = solo armada). Each is assumed to deal an equal share, so
+    /// one player's fight is against `1 / participants` of the hull and shields.
+    #[serde(default = "default_participants")]
+    pub participants: u32,
+}
+
+fn default_participants() -> u32 {
+    1
+}
+
+/// Normalized hostile record (KOBAYASHI schema). Written by normalizer, loaded at runtime.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct HostileRecord {
     pub id: String,
     pub hostile_name: String,
@@ -129,6 +148,24 @@ pub struct HostileRecord {
     pub ability: Vec<Value>,
     #[serde(default)]
     pub resources: Vec<HostileResourceDrop>,
+
+    // --- Reward metadata (hand-entered; upstream has no event scoring) ---
+    /// Estimated value of one kill's loot in a player-chosen unit (e.g. resource points).
+    #[serde(default)]
+    pub loot_value: Option<f64>,
+    /// Event points awarded per kill.
+    #[serde(default)]
+    pub event_points: Option<f64>,
+    /// Hand-entered tags for "vs tag" effects, e.g. `swarm`, `borg`, `eclipse` or a faction name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Charge-bar attack and group size for armada targets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub armada: Option<ArmadaTarget>,
+    /// Attacks per round observed in a recorded fight (records built from a fight export;
+    /// upstream records describe their weapons in `components`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shots_per_round: Option<f64>,
 }
 
 /// Index of all hostiles for name/level resolution. Includes data_version.
@@ -155,7 +192,109 @@ pub struct HostileIndexEntry {
     pub loca_id: Option<u64>,
 }
 
+/// Grouping key for hostiles that are the same opponent at different levels (e.g. every swarm
+/// interceptor from 25 to 45): the upstream display-name key (`loca_id`, else the name) plus hull
+/// class.
+pub fn hostile_family_key(entry: &HostileIndexEntry) -> String {
+    let class = entry.ship_class.to_ascii_lowercase();
+    match entry.loca_id {
+        Some(loca_id) => format!("{loca_id}:{class}"),
+        None => format!("{}:{class}", entry.hostile_name.trim().to_ascii_lowercase()),
+    }
+}
+
+impl HostileIndex {
+    /// One hostile per level from `hostile_id`'s family, lowest level first. When several share a
+    /// level, `hostile_id` itself is preferred, then the first in index order. None for unknown ids.
+    pub fn family_by_level(&self, hostile_id: &str) -> Option<Vec<&HostileIndexEntry>> {
+        let anchor = self.hostiles.iter().find(|e| e.id == hostile_id)?;
+        let family = hostile_family_key(anchor);
+        let mut ladder: Vec<&HostileIndexEntry> = Vec::new();
+        for entry in self
+            .hostiles
+            .iter()
+            .filter(|e| hostile_family_key(e) == family)
+        {
+            match ladder.iter_mut().find(|e| e.level == entry.level) {
+                Some(slot) if entry.id == hostile_id => *slot = entry,
+                Some(_) => {}
+                None => ladder.push(entry),
+            }
+        }
+        ladder.sort_by_key(|e| e.level);
+        Some(ladder)
+    }
+}
+
 impl HostileRecord {
+    /// Reward per kill for points-per-hour ranking: event points, else loot value, else 1 (so the
+    /// rate reads as kills per hour).
+    pub fn points_per_kill(&self) -> f64 {
+        self.event_points.or(self.loot_value).unwrap_or(1.0)
+    }
+
+    /// Tags conditional effects match against (e.g. [crate::data::ship::ShipAbility::against] and
+    /// officer "vs tag" conditions): the hull class, `scout` / `outpost`, `faction:<id>` when the
+    /// faction is known, then the hand-entered [HostileRecord::tags], lowercased.
+    pub fn target_tags(&self) -> Vec<String> {
+        let mut tags = vec![self.ship_class.trim().to_ascii_lowercase()];
+        if self.is_scout {
+            tags.push("scout".to_string());
+        }
+        if self.is_outpost {
+            tags.push("outpost".to_string());
+        }
+        if let Some(faction) = self.faction.as_ref().filter(|f| f.id != 0) {
+            tags.push(format!("faction:{}", faction.id));
+        }
+        for tag in &self.tags {
+            let tag = tag.trim().to_ascii_lowercase();
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags
+    }
+
+    /// Fill a missing (zero) Apex Barrier from `scaling` by class and level. Record values win.
+    pub fn apply_apex_barrier_scaling(&mut self, scaling: &ApexBarrierScaling) {
+        if self.apex_barrier > 0.0 {
+            return;
+        }
+        if let Some(barrier) = scaling.barrier_at(&self.ship_class, self.level) {
+            self.apex_barrier = barrier;
+        }
+    }
+
+    /// Share of the hull and shields one player has to remove: `1 / participants` for group
+    /// armadas, otherwise 1.
+    pub fn player_share(&self) -> f64 {
+        self.armada
+            .as_ref()
+            .map_or(1.0, |a| 1.0 / f64::from(a.participants.max(1)))
+    }
+
+    /// Hull one player fights through, see [HostileRecord::player_share].
+    pub fn fight_hull_health(&self) -> f64 {
+        self.hull_health * self.player_share()
+    }
+
+    /// Shields one player fights through, see [HostileRecord::player_share].
+    pub fn fight_shield_health(&self) -> f64 {
+        self.shield_health * self.player_share()
+    }
+
+    /// The armada's charge-bar attack, when it has one.
+    pub fn charged_attack(&self) -> Option<ChargedAttack> {
+        self.armada
+            .as_ref()
+            .filter(|a| a.charge_rounds > 0)
+            .map(|a| ChargedAttack {
+                every_rounds: a.charge_rounds,
+                damage: a.charge_damage,
+            })
+    }
+
     pub fn to_defender_stats(&self) -> DefenderStats {
         DefenderStats {
             armor: self.armor,
@@ -194,12 +333,95 @@ pub fn hull_type_raw_to_ship_class(hull_type: u32) -> Option<&'static str> {
 
 pub const DEFAULT_HOSTILES_INDEX_PATH: &str = "data/hostiles/index.json";
 
+/// Apex Barrier curves, next to the hostile index. See [ApexBarrierScaling].
+pub const APEX_BARRIER_SCALING_FILE: &str = "apex_barrier_scaling.json";
+
+/// Apex Barrier a hostile of `level` carries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApexBarrierPoint {
+    pub level: u32,
+    pub apex_barrier: f64,
+}
+
+/// Apex Barrier by hostile level for records that carry none of their own (upstream has no apex
+/// stat). Curves are keyed by ship class, with `default` for the rest; values between points are
+/// interpolated linearly, below the first point there is no barrier and past the last it holds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApexBarrierScaling {
+    #[serde(default)]
+    pub curves: HashMap<String, Vec<ApexBarrierPoint>>,
+}
+
+impl ApexBarrierScaling {
+    /// Barrier for a hostile of `ship_class` at `level`, or None when no curve covers it.
+    pub fn barrier_at(&self, ship_class: &str, level: u32) -> Option<f64> {
+        let curve = self
+            .curves
+            .get(&ship_class.to_ascii_lowercase())
+            .or_else(|| self.curves.get("default"))?;
+        let mut points: Vec<&ApexBarrierPoint> = curve.iter().collect();
+        points.sort_by_key(|p| p.level);
+        let first = points.first()?;
+        if level < first.level {
+            return None;
+        }
+        for pair in points.windows(2) {
+            let (lo, hi) = (pair[0], pair[1]);
+            if level <= hi.level {
+                let span = f64::from(hi.level - lo.level).max(1.0);
+                let t = f64::from(level.saturating_sub(lo.level)) / span;
+                return Some(lo.apex_barrier + t * (hi.apex_barrier - lo.apex_barrier));
+            }
+        }
+        points.last().map(|p| p.apex_barrier)
+    }
+}
+
+/// Load Apex Barrier curves from `<data_dir>/apex_barrier_scaling.json`. None if missing or invalid.
+pub fn load_apex_barrier_scaling(data_dir: &Path) -> Option<ApexBarrierScaling> {
+    let data = fs::read_to_string(data_dir.join(APEX_BARRIER_SCALING_FILE)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
 /// Load hostile index from data/hostiles/index.json. Returns None if file missing.
 pub fn load_hostile_index(path: &str) -> Option<HostileIndex> {
     let data = fs::read_to_string(path).ok()?;
     serde_json::from_str(&data).ok()
 }
 
+/// Write `record` to `<data_dir>/<id>.json` and add or replace its entry in
+/// `<data_dir>/index.json` (created when missing), so [crate::data::loader::resolve_hostile] and
+/// the optimizer find it by id or "name level".
+pub fn save_hostile_record(data_dir: &Path, record: &HostileRecord) -> std::io::Result<()> {
+    let to_io = |e: serde_json::Error| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+    fs::create_dir_all(data_dir)?;
+    let json = serde_json::to_string_pretty(record).map_err(to_io)?;
+    fs::write(data_dir.join(format!("{}.json", record.id)), json + "\n")?;
+
+    let index_path = data_dir.join("index.json");
+    let mut index = match fs::read_to_string(&index_path) {
+        Ok(raw) => serde_json::from_str(&raw).map_err(to_io)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => HostileIndex {
+            data_version: None,
+            source_note: None,
+            hostiles: Vec::new(),
+        },
+        Err(e) => return Err(e),
+    };
+    index.hostiles.retain(|e| e.id != record.id);
+    index.hostiles.push(HostileIndexEntry {
+        id: record.id.clone(),
+        hostile_name: record.hostile_name.clone(),
+        level: record.level,
+        ship_class: record.ship_class.clone(),
+        rarity: None,
+        upstream_ship_type: None,
+        loca_id: record.loca_id,
+    });
+    let json = serde_json::to_string_pretty(&index).map_err(to_io)?;
+    fs::write(index_path, json + "\n")
+}
+
 /// Load a single hostile record by id from data/hostiles/<id>.json.
 pub fn load_hostile_record(data_dir: &Path, id: &str) -> Option<HostileRecord> {
     let path = data_dir.join(format!("{}.json", id));
@@ -227,6 +449,112 @@ mod tests {
         assert_eq!(r.components.len(), 1);
     }
 
+    #[test]
+    fn points_per_kill_prefers_event_points_then_loot() {
+        let base = r#"{"id":"h","hostile_name":"H","level":1,"ship_class":"explorer","armor":1.0,"shield_deflection":1.0,"dodge":1.0,"hull_health":1.0,"shield_health":1.0"#;
+        let parse = |extra: &str| -> HostileRecord {
+            serde_json::from_str(&format!("{base}{extra}}}")).expect("hostile JSON")
+        };
+        assert_eq!(parse("").points_per_kill(), 1.0);
+        assert_eq!(parse(r#","loot_value":40.0"#).points_per_kill(), 40.0);
+        assert_eq!(
+            parse(r#","loot_value":40.0,"event_points":250.0"#).points_per_kill(),
+            250.0
+        );
+    }
+
+    #[test]
+    fn target_tags_cover_class_role_faction_and_hand_entered_tags() {
+        let j = r#"{"id":"h","hostile_name":"H","level":1,"ship_class":"Interceptor","armor":1.0,"shield_deflection":1.0,"dodge":1.0,"hull_health":1.0,"shield_health":1.0,"is_scout":true,"faction":{"id":42},"tags":["Swarm","scout"]}"#;
+        let r: HostileRecord = serde_json::from_str(j).expect("hostile JSON");
+        assert_eq!(
+            r.target_tags(),
+            ["interceptor", "scout", "faction:42", "swarm"]
+        );
+    }
+
+    #[test]
+    fn armada_target_sets_charge_attack_and_splits_hull_between_participants() {
+        let base = r#"{"id":"a","hostile_name":"A","level":50,"ship_class":"armada","armor":1.0,"shield_deflection":1.0,"dodge":1.0,"hull_health":9000.0,"shield_health":3000.0"#;
+        let solo: HostileRecord = serde_json::from_str(&format!(
+            r#"{base},"armada":{{"charge_rounds":3,"charge_damage":5000.0}}}}"#
+        ))
+        .unwrap();
+        assert_eq!(
+            solo.charged_attack(),
+            Some(ChargedAttack { every_rounds: 3, damage: 5000.0 })
+        );
+        assert_eq!(solo.fight_hull_health(), 9000.0);
+
+        let group: HostileRecord = serde_json::from_str(&format!(
+            r#"{base},"armada":{{"charge_rounds":0,"charge_damage":5000.0,"participants":3}}}}"#
+        ))
+        .unwrap();
+        assert_eq!(group.charged_attack(), None);
+        assert_eq!(group.fight_hull_health(), 3000.0);
+        assert_eq!(group.fight_shield_health(), 1000.0);
+
+        let plain: HostileRecord = serde_json::from_str(&format!("{base}}}")).unwrap();
+        assert!(plain.armada.is_none() && plain.charged_attack().is_none());
+        assert!(!serde_json::to_string(&plain).unwrap().contains("\"armada\":"));
+    }
+
+    #[test]
+    fn apex_barrier_scaling_interpolates_by_level_and_keeps_record_values() {
+        let scaling: ApexBarrierScaling = serde_json::from_str(
+            r#"{"curves": {
+                "default": [{"level": 60, "apex_barrier": 1000.0}, {"level": 70, "apex_barrier": 3000.0}],
+                "armada": [{"level": 50, "apex_barrier": 5000.0}]
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(scaling.barrier_at("explorer", 59), None);
+        assert_eq!(scaling.barrier_at("explorer", 65), Some(2000.0));
+        assert_eq!(scaling.barrier_at("Explorer", 80), Some(3000.0));
+        assert_eq!(scaling.barrier_at("armada", 55), Some(5000.0));
+
+        let base = r#"{"id":"h","hostile_name":"H","level":65,"ship_class":"explorer","armor":1.0,"shield_deflection":1.0,"dodge":1.0,"hull_health":1.0,"shield_health":1.0"#;
+        let mut scaled: HostileRecord = serde_json::from_str(&format!("{base}}}")).unwrap();
+        scaled.apply_apex_barrier_scaling(&scaling);
+        assert_eq!(scaled.apex_barrier, 2000.0);
+        let mut own: HostileRecord =
+            serde_json::from_str(&format!(r#"{base},"apex_barrier":400.0}}"#)).unwrap();
+        own.apply_apex_barrier_scaling(&scaling);
+        assert_eq!(own.apex_barrier, 400.0);
+    }
+
+    #[test]
+    fn family_by_level_groups_by_loca_and_class() {
+        let entry = |id: &str, level: u32, class: &str, loca_id: Option<u64>| HostileIndexEntry {
+            id: id.to_string(),
+            hostile_name: format!("Hostile {id}"),
+            level,
+            ship_class: class.to_string(),
+            rarity: None,
+            upstream_ship_type: None,
+            loca_id,
+        };
+        let index = HostileIndex {
+            data_version: None,
+            source_note: None,
+            hostiles: vec![
+                entry("a30", 30, "interceptor", Some(7)),
+                entry("a25", 25, "interceptor", Some(7)),
+                entry("b30", 30, "interceptor", Some(7)),
+                entry("c25", 25, "battleship", Some(7)),
+                entry("d25", 25, "interceptor", Some(8)),
+            ],
+        };
+
+        let ids = |ladder: Vec<&HostileIndexEntry>| -> Vec<String> {
+            ladder.into_iter().map(|e| e.id.clone()).collect()
+        };
+        assert_eq!(ids(index.family_by_level("a25").unwrap()), ["a25", "a30"]);
+        assert_eq!(ids(index.family_by_level("b30").unwrap()), ["a25", "b30"]);
+        assert_eq!(ids(index.family_by_level("c25").unwrap()), ["c25"]);
+        assert!(index.family_by_level("missing").is_none());
+    }
+
     #[test]
     fn hull_type_raw_mapping_known_values() {
         assert_eq!(hull_type_raw_to_ship_class(0), Some("battleship"));
@@ -82,6 +82,9 @@ pub struct RosterEntry {
     pub tier: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub level: Option<u16>,
+    /// Officer boost (shard / away-team boost); see [crate::combat::types::BOOSTED_EFFECTIVENESS_MULTIPLIER].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub boosted: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -251,6 +254,8 @@ struct SpocksOfficerRecord {
     tier: Option<u8>,
     #[serde(default, alias = "officerLevel")]
     level: Option<u16>,
+    #[serde(default, alias = "isBoosted")]
+    boosted: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -261,8 +266,8 @@ struct SpocksOfficerRef {
     name: Option<String>,
 }
 
-/// Raw record before name resolution: (raw_name, rank, tier, level).
-type RawRosterRecord = (String, Option<u8>, Option<u8>, Option<u16>);
+/// Raw record before name resolution: (raw_name, rank, tier, level, boosted).
+type RawRosterRecord = (String, Option<u8>, Option<u8>, Option<u16>, bool);
 
 fn resolve_and_write_roster_to(
     source_path: &str,
@@ -279,7 +284,7 @@ fn resolve_and_write_roster_to(
     let mut matched_records = 0usize;
     let mut ambiguous_records = 0usize;
 
-    for (index, (raw_name, rank, tier, level)) in raw_records.iter().enumerate() {
+    for (index, (raw_name, rank, tier, level, boosted)) in raw_records.iter().enumerate() {
         let raw_name = raw_name.trim();
         if raw_name.is_empty() {
             continue;
@@ -327,6 +332,7 @@ fn resolve_and_write_roster_to(
             rank: *rank,
             tier: *tier,
             level: *level,
+            boosted: *boosted,
         };
 
         if let Some((first_index, first_entry)) = resolved_by_id.get(&candidate.id) {
@@ -417,7 +423,7 @@ pub fn import_spocks_export_to(source_path: &str, output_path: &str) -> Result<I
                 .or(r.officer.as_ref().and_then(|o| o.id.as_ref()))
                 .map(|s| s.trim().to_string())
                 .unwrap_or_default();
-            (raw_name, r.rank, r.tier, r.level)
+            (raw_name, r.rank, r.tier, r.level, r.boosted)
         })
         .collect();
 
@@ -484,7 +490,7 @@ fn parse_roster_csv_content(content: &str) -> Result<Vec<RawRosterRecord>, Impor
             }
         }
 
-        raw_records.push((name.to_string(), None, tier, level));
+        raw_records.push((name.to_string(), None, tier, level, false));
     }
 
     Ok(raw_records)
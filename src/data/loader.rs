@@ -1,16 +1,100 @@
 //! Load and resolve hostiles and ships by name/id. Graceful fallback when data missing.
 //! Ships: data/ships_extended/ (extended schema with tiers/levels). Flat data/ships/ removed.
+//!
+//! [resolve_ship_with_tier_level] and [resolve_hostile] keep resolved records in a process-wide
+//! [RecordCache]. Each entry remembers the files it was read from and their content hash; a hit
+//! re-reads and hashes those files (no JSON parsing) and resolves again when they changed.
 
-use std::path::Path;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
 
 use crate::data::hostile::{
-    load_hostile_index, load_hostile_record, HostileIndex, HostileRecord,
-    DEFAULT_HOSTILES_INDEX_PATH,
+    load_apex_barrier_scaling, load_hostile_index, load_hostile_record, HostileIndex,
+    HostileRecord, APEX_BARRIER_SCALING_FILE, DEFAULT_HOSTILES_INDEX_PATH,
 };
 use crate::data::ship::{
-    load_extended_ship_index, load_extended_ship_record, ShipRecord, DEFAULT_SHIPS_EXTENDED_DIR,
+    load_extended_ship_index, load_extended_ship_record, ExtendedShipIndex, ShipRecord,
+    DEFAULT_SHIPS_EXTENDED_DIR,
 };
 
+/// Thread-safe cache of resolved records (including misses) keyed by lookup, invalidated by the
+/// content hash of each entry's source files.
+pub struct RecordCache<T> {
+    entries: RwLock<HashMap<String, CachedRecord<T>>>,
+}
+
+struct CachedRecord<T> {
+    sources: Vec<PathBuf>,
+    content_hash: u64,
+    record: Option<T>,
+}
+
+impl<T: Clone> RecordCache<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Cached record for `key`, or the result of `resolve` (the record plus the files it read)
+    /// when there is no entry or its files changed since.
+    pub fn get_or_resolve(
+        &self,
+        key: &str,
+        resolve: impl FnOnce() -> (Option<T>, Vec<PathBuf>),
+    ) -> Option<T> {
+        if let Ok(entries) = self.entries.read() {
+            if let Some(entry) = entries.get(key) {
+                if content_hash(&entry.sources) == entry.content_hash {
+                    return entry.record.clone();
+                }
+            }
+        }
+        let (record, sources) = resolve();
+        if let Ok(mut entries) = self.entries.write() {
+            entries.insert(
+                key.to_string(),
+                CachedRecord {
+                    content_hash: content_hash(&sources),
+                    sources,
+                    record: record.clone(),
+                },
+            );
+        }
+        record
+    }
+}
+
+impl<T: Clone> Default for RecordCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hash of the paths and contents of `files`; a missing file hashes as absent.
+fn content_hash(files: &[PathBuf]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for file in files {
+        file.hash(&mut hasher);
+        fs::read(file).ok().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn ship_cache() -> &'static RecordCache<ShipRecord> {
+    static CACHE: OnceLock<RecordCache<ShipRecord>> = OnceLock::new();
+    CACHE.get_or_init(RecordCache::new)
+}
+
+fn hostile_cache() -> &'static RecordCache<HostileRecord> {
+    static CACHE: OnceLock<RecordCache<HostileRecord>> = OnceLock::new();
+    CACHE.get_or_init(RecordCache::new)
+}
+
 /// Normalize a string for lookup: lowercase, collapse spaces/underscores.
 fn normalize_lookup(s: &str) -> String {
     s.to_lowercase()
@@ -22,25 +106,217 @@ fn normalize_lookup(s: &str) -> String {
         .join("_")
 }
 
-/// Resolve a hostile using a pre-loaded index. Used by DataRegistry.
+/// Near-miss ids listed when a ship or hostile does not resolve.
+pub const NEAR_MISS_LIMIT: usize = 5;
+
+/// Looser key for fuzzy matching than [normalize_lookup]: dots and apostrophes are dropped
+/// ("U.S.S." → "uss") and any other punctuation separates words.
+fn search_key(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .filter(|c| !matches!(c, '.' | '\'' | '\u{2019}'))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Every query word starts a different label word; numbers (levels) must match exactly.
+fn words_match(label: &str, query: &str) -> bool {
+    let mut label_words: Vec<&str> = label.split('_').collect();
+    query.split('_').all(|word| {
+        let numeric = word.chars().all(|c| c.is_ascii_digit());
+        let found = label_words.iter().position(|candidate| {
+            if numeric {
+                *candidate == word
+            } else {
+                candidate.starts_with(word)
+            }
+        });
+        found.map(|i| label_words.swap_remove(i)).is_some()
+    })
+}
+
+/// Candidates (id plus the labels it is looked up by) that match `query`, best first with their
+/// score: an exact label, then labels containing the query (or contained in it, or matching it
+/// word by word), then labels within a small edit distance. Ties go to the shorter difference,
+/// then the id.
+fn ranked_ids<'a, I>(query: &str, candidates: I) -> Vec<((u8, usize), &'a str)>
+where
+    I: IntoIterator<Item = (&'a str, Vec<String>)>,
+{
+    let query = search_key(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let max_distance = (query.chars().count() / 3).max(2);
+    let mut scored: Vec<((u8, usize), &str)> = candidates
+        .into_iter()
+        .filter_map(|(id, labels)| {
+            labels
+                .iter()
+                .map(|label| search_key(label))
+                .filter(|label| !label.is_empty())
+                .filter_map(|label| {
+                    if label == query {
+                        Some((0, 0))
+                    } else if label.contains(&query)
+                        || query.contains(&label)
+                        || words_match(&label, &query)
+                    {
+                        Some((1, label.len().abs_diff(query.len())))
+                    } else {
+                        let distance = edit_distance(&label, &query);
+                        (distance <= max_distance).then_some((2, distance))
+                    }
+                })
+                .min()
+                .map(|score| (score, id))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+}
+
+/// Up to `limit` ids from `candidates` closest to `query` (see [ranked_ids]).
+pub fn near_miss_ids<'a, I>(query: &str, candidates: I, limit: usize) -> Vec<String>
+where
+    I: IntoIterator<Item = (&'a str, Vec<String>)>,
+{
+    ranked_ids(query, candidates)
+        .into_iter()
+        .take(limit)
+        .map(|(_, id)| id.to_string())
+        .collect()
+}
+
+/// The one id `query` means, when no other candidate matches as well (exact, partial or
+/// misspelt: "enterprise" is ambiguous while both "USS Enterprise" and "Enterprise Refit"
+/// contain it); otherwise the near misses. A partial match needs at least three characters.
+pub fn fuzzy_resolve_id<'a, I>(query: &str, candidates: I) -> Result<String, Vec<String>>
+where
+    I: IntoIterator<Item = (&'a str, Vec<String>)>,
+{
+    let ranked = ranked_ids(query, candidates);
+    let long_enough = search_key(query).chars().count() >= 3;
+    match ranked.as_slice() {
+        [(best, id), rest @ ..]
+            if rest.first().is_none_or(|(next, _)| next.0 > best.0)
+                && (best.0 == 0 || long_enough) =>
+        {
+            Ok(id.to_string())
+        }
+        _ => Err(ranked
+            .into_iter()
+            .take(NEAR_MISS_LIMIT)
+            .map(|(_, id)| id.to_string())
+            .collect()),
+    }
+}
+
+/// Ships by id and ship name.
+fn ship_labels(index: &ExtendedShipIndex) -> impl Iterator<Item = (&str, Vec<String>)> {
+    index
+        .ships
+        .iter()
+        .map(|e| (e.id.as_str(), vec![e.id.clone(), e.ship_name.clone()]))
+}
+
+/// Hostiles by id, "name level" and, when `display` has a translation for the hostile's
+/// `loca_id`, "display name level" ("swarm cluster 37").
+fn hostile_labels<'a>(
+    index: &'a HostileIndex,
+    display: &'a HashMap<u64, String>,
+) -> impl Iterator<Item = (&'a str, Vec<String>)> {
+    index.hostiles.iter().map(|e| {
+        let mut labels = vec![e.id.clone(), format!("{} {}", e.hostile_name, e.level)];
+        if let Some(name) = e.loca_id.and_then(|id| display.get(&id)) {
+            labels.push(format!("{name} {}", e.level));
+        }
+        (e.id.as_str(), labels)
+    })
+}
+
+/// Ship ids closest to `query`, best first.
+pub fn ship_near_misses(index: &ExtendedShipIndex, query: &str, limit: usize) -> Vec<String> {
+    near_miss_ids(query, ship_labels(index), limit)
+}
+
+/// Hostile ids closest to `query`, best first. `display` is loca id → English name.
+pub fn hostile_near_misses(
+    index: &HostileIndex,
+    display: &HashMap<u64, String>,
+    query: &str,
+    limit: usize,
+) -> Vec<String> {
+    near_miss_ids(query, hostile_labels(index, display), limit)
+}
+
+/// Canonical ship id for a typed name ("USS Enterprise", "enterprise"), or the near misses.
+pub fn fuzzy_ship_id(index: &ExtendedShipIndex, query: &str) -> Result<String, Vec<String>> {
+    fuzzy_resolve_id(query, ship_labels(index))
+}
+
+/// Canonical hostile id for an id, "name level" or typed display name ("swarm cluster 37"), or
+/// the near misses. Exact lookups ([resolve_hostile]'s rules) win over fuzzy ones.
+pub fn fuzzy_hostile_id(
+    index: &HostileIndex,
+    display: &HashMap<u64, String>,
+    query: &str,
+) -> Result<String, Vec<String>> {
+    if let Some(id) = matching_hostile_id(index, query) {
+        return Ok(id.to_string());
+    }
+    fuzzy_resolve_id(query, hostile_labels(index, display))
+}
+
+/// Levenshtein distance over chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Resolve a hostile using a pre-loaded index. Used by DataRegistry. A record without its own
+/// Apex Barrier takes one from `apex_barrier_scaling.json` in `data_dir` when a curve covers it.
 pub fn resolve_hostile_with_index(
     index: &HostileIndex,
     data_dir: &Path,
     name_or_id: &str,
 ) -> Option<HostileRecord> {
+    let id = matching_hostile_id(index, name_or_id)?;
+    let mut record = load_hostile_record(data_dir, id)?;
+    if let Some(scaling) = load_apex_barrier_scaling(data_dir) {
+        record.apply_apex_barrier_scaling(&scaling);
+    }
+    Some(record)
+}
+
+fn matching_hostile_id<'a>(index: &'a HostileIndex, name_or_id: &str) -> Option<&'a str> {
     let normalized = normalize_lookup(name_or_id);
 
     if let Some(entry) = index.hostiles.iter().find(|e| normalize_lookup(&e.id) == normalized) {
-        return load_hostile_record(data_dir, &entry.id);
+        return Some(&entry.id);
     }
     for entry in &index.hostiles {
         let name_level = format!("{}_{}", normalize_lookup(&entry.hostile_name), entry.level);
         if name_level == normalized {
-            return load_hostile_record(data_dir, &entry.id);
+            return Some(&entry.id);
         }
         let name_space_level = format!("{} {}", normalize_lookup(&entry.hostile_name), entry.level);
         if normalize_lookup(&name_space_level) == normalized {
-            return load_hostile_record(data_dir, &entry.id);
+            return Some(&entry.id);
         }
     }
     let by_name: Vec<_> = index
@@ -49,16 +325,28 @@ pub fn resolve_hostile_with_index(
         .filter(|e| normalize_lookup(&e.hostile_name) == normalized)
         .collect();
     if by_name.len() == 1 {
-        return load_hostile_record(data_dir, &by_name[0].id);
+        return Some(&by_name[0].id);
     }
     None
 }
 
 /// Resolve a hostile by id or by "name level" / "name_level". Returns None if index missing or no match.
 pub fn resolve_hostile(name_or_id: &str) -> Option<HostileRecord> {
-    let index = load_hostile_index(DEFAULT_HOSTILES_INDEX_PATH)?;
-    let data_dir = Path::new(DEFAULT_HOSTILES_INDEX_PATH).parent()?;
-    resolve_hostile_with_index(&index, data_dir, name_or_id)
+    hostile_cache().get_or_resolve(&normalize_lookup(name_or_id), || {
+        let index_path = PathBuf::from(DEFAULT_HOSTILES_INDEX_PATH);
+        let data_dir = index_path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut sources = vec![index_path, data_dir.join(APEX_BARRIER_SCALING_FILE)];
+        let Some(index) = load_hostile_index(DEFAULT_HOSTILES_INDEX_PATH) else {
+            return (None, sources);
+        };
+        if let Some(id) = matching_hostile_id(&index, name_or_id) {
+            sources.push(data_dir.join(format!("{id}.json")));
+        }
+        (
+            resolve_hostile_with_index(&index, &data_dir, name_or_id),
+            sources,
+        )
+    })
 }
 
 /// Resolve a ship by id or ship_name. Returns None if index missing or no match.
@@ -75,19 +363,30 @@ pub fn resolve_ship_with_tier_level(
     level: Option<u32>,
 ) -> Option<ShipRecord> {
     let normalized = normalize_lookup(name_or_id);
-    let extended_dir = Path::new(DEFAULT_SHIPS_EXTENDED_DIR);
-
-    if !extended_dir.is_dir() {
-        return None;
-    }
-    let ext_index = load_extended_ship_index(extended_dir)?;
-    let id = ext_index
-        .ships
-        .iter()
-        .find(|e| normalize_lookup(&e.id) == normalized || normalize_lookup(&e.ship_name) == normalized)
-        .map(|e| e.id.as_str())?;
-    let extended = load_extended_ship_record(extended_dir, id)?;
-    extended.to_ship_record(tier.or(Some(1)), level.or(Some(1)))
+    let (tier, level) = (tier.unwrap_or(1), level.unwrap_or(1));
+    let key = format!("{normalized}|{tier}|{level}");
+    ship_cache().get_or_resolve(&key, || {
+        let extended_dir = Path::new(DEFAULT_SHIPS_EXTENDED_DIR);
+        let mut sources = vec![extended_dir.join("index.json")];
+        if !extended_dir.is_dir() {
+            return (None, sources);
+        }
+        let Some(ext_index) = load_extended_ship_index(extended_dir) else {
+            return (None, sources);
+        };
+        let Some(id) = ext_index
+            .ships
+            .iter()
+            .find(|e| normalize_lookup(&e.id) == normalized || normalize_lookup(&e.ship_name) == normalized)
+            .map(|e| e.id.as_str())
+        else {
+            return (None, sources);
+        };
+        sources.push(extended_dir.join(format!("{id}.json")));
+        let record = load_extended_ship_record(extended_dir, id)
+            .and_then(|extended| extended.to_ship_record(Some(tier), Some(level)));
+        (record, sources)
+    })
 }
 
 /// Return available tier and level numbers for a ship (by id or name). From data/ships_extended.
@@ -109,3 +408,88 @@ pub fn ship_tiers_levels(name_or_id: &str) -> Option<(Vec<u32>, Vec<u32>)> {
     let levels: Vec<u32> = extended.levels.iter().map(|l| l.level).collect();
     Some((tiers, levels))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn record_cache_resolves_again_only_when_sources_change() {
+        let dir = std::env::temp_dir().join(format!("kobayashi_loader_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("record.json");
+        fs::write(&file, "1").unwrap();
+
+        let cache: RecordCache<String> = RecordCache::new();
+        let resolves = Cell::new(0);
+        let lookup = || {
+            cache.get_or_resolve("key", || {
+                resolves.set(resolves.get() + 1);
+                (fs::read_to_string(&file).ok(), vec![file.clone()])
+            })
+        };
+        assert_eq!(lookup().as_deref(), Some("1"));
+        assert_eq!(lookup().as_deref(), Some("1"));
+        assert_eq!(resolves.get(), 1);
+
+        fs::write(&file, "2").unwrap();
+        assert_eq!(lookup().as_deref(), Some("2"));
+        assert_eq!(resolves.get(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(lookup(), None);
+        assert_eq!(resolves.get(), 3);
+    }
+
+    #[test]
+    fn near_misses_prefer_containing_labels_then_edit_distance() {
+        let candidates = [
+            ("uss_saladin", vec!["U.S.S. SALADIN".to_string()]),
+            ("saladin_refit", vec!["Saladin Refit".to_string()]),
+            ("sarcophagus", vec!["Sarcophagus".to_string()]),
+            ("salidan", vec!["Salidan".to_string()]),
+        ];
+        let ids = |query: &str| {
+            near_miss_ids(
+                query,
+                candidates.iter().map(|(id, labels)| {
+                    (*id, [vec![id.to_string()], labels.clone()].concat())
+                }),
+                NEAR_MISS_LIMIT,
+            )
+        };
+        assert_eq!(ids("saladin"), ["uss_saladin", "saladin_refit", "salidan"]);
+        assert!(ids("defiant").is_empty());
+        assert!(ids("  ").is_empty());
+    }
+    #[test]
+    fn fuzzy_ids_resolve_typed_names_and_leave_ties_as_suggestions() {
+        let candidates = [
+            ("uss_enterprise", vec!["U.S.S. ENTERPRISE".to_string()]),
+            ("enterprise_refit", vec!["Enterprise Refit".to_string()]),
+            ("4001", vec!["Swarm Cluster 37".to_string()]),
+            ("4002", vec!["Swarm Horde 37".to_string()]),
+            ("4003", vec!["Swarm Horde 38".to_string()]),
+        ];
+        let resolve = |query: &str| {
+            fuzzy_resolve_id(
+                query,
+                candidates.iter().map(|(id, labels)| {
+                    (*id, [vec![id.to_string()], labels.clone()].concat())
+                }),
+            )
+        };
+        assert_eq!(resolve("USS Enterprise").as_deref(), Ok("uss_enterprise"));
+        assert_eq!(resolve("u.s.s. enterprize").as_deref(), Ok("uss_enterprise"));
+        assert_eq!(resolve("swarm horde 38").as_deref(), Ok("4003"));
+        assert_eq!(resolve("horde 38").as_deref(), Ok("4003"));
+        assert_eq!(resolve("swarm 37").unwrap_err(), ["4002", "4001"]);
+        assert_eq!(
+            resolve("enterprise").unwrap_err(),
+            ["uss_enterprise", "enterprise_refit"]
+        );
+        assert!(resolve("sw").is_err());
+    }
+}
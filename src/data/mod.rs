@@ -1,6 +1,7 @@
 pub mod building;
 pub mod building_bid_resolver;
 pub mod building_summary;
+pub mod crew_code;
 pub mod data_registry;
 pub mod research;
 pub mod research_summary;
@@ -12,9 +13,14 @@ pub mod hostile_loca;
 pub mod import;
 pub mod loader;
 pub mod officer;
+pub mod power;
 pub mod profile;
 pub mod profile_index;
 pub mod registry;
+#[cfg(feature = "server")]
+pub mod remote;
+#[cfg(feature = "server")]
+pub mod sheets;
 pub mod ship;
 pub mod ship_ability_resolve;
 pub mod syndicate_combat;
@@ -1,9 +1,15 @@
 use std::fs;
 use std::path::Path;
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{AbilityEffect, ShipType, StackContribution};
 
 pub const DEFAULT_CANONICAL_OFFICERS_PATH: &str = "data/officers/officers.canonical.json";
+/// data.stfc.space officer summary; fills metadata the canonical file lacks (see
+/// [enrich_officers_from_summary]).
+pub const DEFAULT_OFFICER_SUMMARY_PATH: &str = "data/upstream/data-stfc-space/summary-officer.json";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Officer {
@@ -11,8 +17,241 @@ pub struct Officer {
     pub name: String,
     #[serde(default)]
     pub slot: Option<String>,
+    /// common / uncommon / rare / epic (canonical data); drives shard costs.
+    #[serde(default)]
+    pub rarity: Option<String>,
+    /// Faction name (e.g. "Federation"; "Unknown" when the source did not say).
+    #[serde(default)]
+    pub faction: Option<String>,
+    /// Crew group name as shown in game (e.g. "TNG CREW").
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Upstream synergy id; officers sharing it belong to the same synergy group.
+    #[serde(default)]
+    pub synergy_group: Option<u32>,
+    /// Upstream (data.stfc.space) officer id, as exported (may be float-formatted).
+    #[serde(default)]
+    pub source_officer_id: Option<String>,
     #[serde(default)]
     pub abilities: Vec<OfficerAbility>,
+    /// Permanent stat traits unlocked by officer level; only applied when the profile enables
+    /// [crate::data::profile::PlayerProfile::officer_traits].
+    #[serde(default)]
+    pub traits: Vec<OfficerTrait>,
+}
+
+/// Officer class (the canonical `slot` field: command / science / engineering).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfficerClass {
+    Command,
+    Science,
+    Engineering,
+}
+
+impl OfficerClass {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "command" => Some(Self::Command),
+            "science" => Some(Self::Science),
+            "engineering" => Some(Self::Engineering),
+            _ => None,
+        }
+    }
+
+    /// Ship class this officer class is paired with for in-class search ordering (see
+    /// `CandidateStrategy::prefer_in_class`); survey ships pair with none. No combat effect.
+    pub const fn in_class_ship(self) -> ShipType {
+        match self {
+            Self::Command => ShipType::Interceptor,
+            Self::Science => ShipType::Explorer,
+            Self::Engineering => ShipType::Battleship,
+        }
+    }
+}
+
+/// What a crew does with an officer name that matches no officer data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownOfficerMode {
+    /// Reject the crew, naming the unknown officers (the API default). Crews that reach the
+    /// simulator anyway seat them as [UnknownOfficerMode::Neutral].
+    Strict,
+    /// Seat the officer with an ability that has no effect.
+    #[default]
+    Neutral,
+    /// Seat the officer with a made-up attack or piercing bonus derived from a hash of the name
+    /// (the behaviour before unknown officers were handled explicitly). Known officers with no
+    /// modeled ability get the same made-up bonus, as they used to.
+    Legacy,
+}
+
+/// Shards needed to promote an officer to tiers 2..=5, by rarity.
+/// Assumption: approximate in-game requirements (the synced game data carries no promotion
+/// costs); only relative costs matter for the upgrade report.
+const SHARDS_TO_TIER: [(&str, [u32; 4]); 4] = [
+    ("common", [5, 10, 20, 40]),
+    ("uncommon", [10, 20, 40, 80]),
+    ("rare", [20, 40, 80, 160]),
+    ("epic", [40, 80, 160, 320]),
+];
+
+/// Officer stat points (attack + defense + health) per officer level, by rarity.
+/// Assumption: approximate in-game officer stats (the synced game data carries none); unknown
+/// rarities count as common.
+const OFFICER_STATS_PER_LEVEL: [(&str, f64); 4] = [
+    ("common", 30.0),
+    ("uncommon", 45.0),
+    ("rare", 70.0),
+    ("epic", 100.0),
+];
+
+/// Extra officer stats per rank above 1, as a share of the rank 1 value.
+/// Assumption: linear in rank, matching the roughly even steps of in-game promotions.
+const OFFICER_STATS_PER_RANK: f64 = 0.25;
+
+/// Share of an officer's stat points in its class's stat (command: attack, science: defense,
+/// engineering: health); the other two stats split the rest evenly.
+/// Assumption: officers lean toward their class's stat; classless officers split evenly.
+const OFFICER_CLASS_STAT_SHARE: f64 = 0.5;
+
+/// Canonical modifier name and the effect built from its value.
+type StatModifierEffect = (&'static str, fn(f64) -> AbilityEffect);
+
+/// Flat stat modifiers the engine models: canonical `modifier` → the effect its `value_by_rank`
+/// value becomes (see [OfficerAbility::stat_effect_for_tier]). Apex shred and barrier get their
+/// own seat rows instead. Piercing stats (`AllPiercing`, `ArmorPiercing`, `ShieldPiercing`,
+/// `Accuracy`) are left out: they change mitigation against the defender's armor, deflection
+/// and dodge, and there is no data yet for turning that into the engine's single pierce term.
+const STAT_MODIFIER_EFFECTS: [StatModifierEffect; 2] = [
+    ("AllDamage", AbilityEffect::AttackMultiplier),
+    ("IsolyticCascadeDamage", AbilityEffect::IsolyticCascadeDamageBonus),
+];
+
+/// An officer's attack, defense and health stats (see [Officer::estimated_stats]).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OfficerStats {
+    pub attack: f64,
+    pub defense: f64,
+    pub health: f64,
+}
+
+impl OfficerStats {
+    pub fn total(&self) -> f64 {
+        self.attack + self.defense + self.health
+    }
+}
+
+/// Crew seats an officer may occupy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeatEligibility {
+    pub captain: bool,
+    pub bridge: bool,
+    pub below_decks: bool,
+}
+
+impl Officer {
+    /// Highest tier the officer's ability data covers (length of the longest `value_by_rank` /
+    /// `chance_by_rank`); 1 when no ability scales.
+    pub fn max_tier(&self) -> u8 {
+        self.abilities
+            .iter()
+            .map(|a| a.value_by_rank.len().max(a.chance_by_rank.len()))
+            .max()
+            .unwrap_or(0)
+            .clamp(1, u8::MAX as usize) as u8
+    }
+
+    /// Shards needed to promote this officer from `tier - 1` to `tier`. None for tier 1, tiers
+    /// above 5, or an unknown rarity.
+    pub fn shards_to_reach_tier(&self, tier: u8) -> Option<u32> {
+        let rarity = self.rarity.as_deref()?.trim().to_ascii_lowercase();
+        let costs = SHARDS_TO_TIER
+            .iter()
+            .find(|(name, _)| *name == rarity)
+            .map(|(_, costs)| costs)?;
+        costs.get(usize::from(tier.checked_sub(2)?)).copied()
+    }
+
+    /// Estimated stats of this officer at `rank` and `level` (1 when unknown); see
+    /// [OFFICER_STATS_PER_LEVEL].
+    pub fn estimated_stats(&self, rank: Option<u8>, level: Option<u16>) -> OfficerStats {
+        let rarity = self.rarity.as_deref().unwrap_or("").trim().to_ascii_lowercase();
+        let per_level = OFFICER_STATS_PER_LEVEL
+            .iter()
+            .find(|(name, _)| *name == rarity)
+            .map_or(OFFICER_STATS_PER_LEVEL[0].1, |(_, points)| *points);
+        let rank_factor = 1.0 + OFFICER_STATS_PER_RANK * f64::from(rank.unwrap_or(1).max(1) - 1);
+        let points = per_level * f64::from(level.unwrap_or(1).max(1)) * rank_factor;
+        let (main, other) = (OFFICER_CLASS_STAT_SHARE, (1.0 - OFFICER_CLASS_STAT_SHARE) / 2.0);
+        let [attack, defense, health] = match self.officer_class() {
+            Some(OfficerClass::Command) => [main, other, other],
+            Some(OfficerClass::Science) => [other, main, other],
+            Some(OfficerClass::Engineering) => [other, other, main],
+            None => [1.0 / 3.0; 3],
+        };
+        OfficerStats {
+            attack: points * attack,
+            defense: points * defense,
+            health: points * health,
+        }
+    }
+
+    /// Stacking contributions of the traits unlocked at `level`.
+    pub fn trait_contributions(
+        &self,
+        level: Option<u16>,
+    ) -> impl Iterator<Item = StackContribution<String>> + '_ {
+        self.traits
+            .iter()
+            .filter(move |t| t.is_unlocked(level))
+            .map(OfficerTrait::contribution)
+    }
+
+    pub fn officer_class(&self) -> Option<OfficerClass> {
+        self.slot.as_deref().and_then(OfficerClass::parse)
+    }
+
+    /// True when the officer's class pairs with `ship_type` (see [OfficerClass::in_class_ship]).
+    pub fn is_in_class(&self, ship_type: ShipType) -> bool {
+        self.officer_class()
+            .is_some_and(|class| class.in_class_ship() == ship_type)
+    }
+
+    /// True when every ability is a below-decks ability (officer cannot take a bridge seat).
+    pub fn is_below_decks_only(&self) -> bool {
+        !self.abilities.is_empty()
+            && self
+                .abilities
+                .iter()
+                .all(|a| a.slot.eq_ignore_ascii_case("below_decks"))
+    }
+
+    /// Seats this officer may take. Legacy seat values in `slot` (captain / bridge / officer /
+    /// below_decks) pin the officer to those seats; class values defer to the ability slots.
+    pub fn seat_eligibility(&self) -> SeatEligibility {
+        let has_captain_ability = self
+            .abilities
+            .iter()
+            .any(|a| a.slot.eq_ignore_ascii_case("captain"));
+        let below_decks_only = self.is_below_decks_only();
+        match self.slot.as_deref().map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+            Some("captain") | Some("bridge") | Some("officer") => SeatEligibility {
+                captain: has_captain_ability,
+                bridge: true,
+                below_decks: false,
+            },
+            Some("below_decks") => SeatEligibility {
+                captain: false,
+                bridge: false,
+                below_decks: true,
+            },
+            _ => SeatEligibility {
+                captain: has_captain_ability && !below_decks_only,
+                bridge: !below_decks_only,
+                below_decks: true,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -32,6 +271,45 @@ pub struct OfficerAbility {
     pub value_by_rank: Vec<f64>,
 }
 
+/// Level-gated stat trait (e.g. +2% crit chance from level 20). `stat` uses the profile bonus
+/// keys (weapon_damage, crit_chance, ...) and `value` is a fraction like profile bonuses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OfficerTrait {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub stat: String,
+    pub value: f64,
+    /// "add" (default) or "multiply".
+    #[serde(default)]
+    pub operator: Option<String>,
+    /// Officer level at which the trait unlocks.
+    #[serde(default = "default_trait_unlock_level")]
+    pub unlock_level: u16,
+}
+
+fn default_trait_unlock_level() -> u16 {
+    1
+}
+
+impl OfficerTrait {
+    /// True when an officer at `level` has the trait. An unknown level counts as unlocked.
+    pub fn is_unlocked(&self, level: Option<u16>) -> bool {
+        level.is_none_or(|level| level >= self.unlock_level)
+    }
+
+    /// Stacking contribution of this trait: multiply traits are modifiers, the rest flat.
+    pub fn contribution(&self) -> StackContribution<String> {
+        let is_mult = self.operator.as_deref().is_some_and(|op| {
+            op.eq_ignore_ascii_case("multiply") || op.eq_ignore_ascii_case("mult")
+        });
+        if is_mult {
+            StackContribution::modifier(self.stat.clone(), self.value)
+        } else {
+            StackContribution::flat(self.stat.clone(), self.value)
+        }
+    }
+}
+
 impl OfficerAbility {
     pub fn applies_morale_state(&self) -> bool {
         let is_state_modifier = self
@@ -178,6 +456,29 @@ impl OfficerAbility {
             .unwrap_or(false)
     }
 
+    pub fn is_captain_maneuver(&self) -> bool {
+        self.slot.eq_ignore_ascii_case("captain")
+    }
+
+    /// Effect of a flat stat modifier at `tier` ([STAT_MODIFIER_EFFECTS]). None for modifiers the
+    /// engine does not model and for values given as a share of crew stats (`officer_stat=`).
+    /// Captain maneuvers do not rank up, so they always use their first value.
+    pub fn stat_effect_for_tier(&self, tier: Option<u8>) -> Option<AbilityEffect> {
+        let modifier = self.modifier.as_deref()?;
+        let (_, effect) = STAT_MODIFIER_EFFECTS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(modifier.trim()))?;
+        let scales_with_crew_stats = self
+            .attributes
+            .as_deref()
+            .is_some_and(|value| normalize_for_lookup(value).contains("officerstat"));
+        if scales_with_crew_stats || self.value_by_rank.is_empty() {
+            return None;
+        }
+        let tier = if self.is_captain_maneuver() { None } else { tier };
+        Some(effect(self.value_for_tier(tier)))
+    }
+
     /// Value at given tier (1-based); 0 if value_by_rank is empty or index out of range.
     pub fn value_for_tier(&self, tier: Option<u8>) -> f64 {
         let Some((&first, _rest)) = self.value_by_rank.split_first() else {
@@ -213,3 +514,54 @@ pub fn load_canonical_officers(path: impl AsRef<Path>) -> Result<Vec<Officer>, s
         serde_json::from_str(&raw).map_err(std::io::Error::other)?;
     Ok(parsed.officers)
 }
+
+#[derive(Debug, Deserialize)]
+struct OfficerSummaryEntry {
+    id: u64,
+    #[serde(default)]
+    rarity: Option<u8>,
+    #[serde(default)]
+    synergy_id: Option<u32>,
+}
+
+/// Fill officer metadata from the data.stfc.space officer summary, matched by
+/// `source_officer_id`: the synergy group, and the rarity when the canonical file has none.
+/// Returns how many officers matched; a missing or unreadable summary changes nothing.
+pub fn enrich_officers_from_summary(officers: &mut [Officer], path: impl AsRef<Path>) -> usize {
+    let Some(summary) = fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Vec<OfficerSummaryEntry>>(&raw).ok())
+    else {
+        return 0;
+    };
+    let by_id: std::collections::HashMap<u64, &OfficerSummaryEntry> =
+        summary.iter().map(|entry| (entry.id, entry)).collect();
+    let mut matched = 0;
+    for officer in officers {
+        let Some(entry) = officer
+            .source_officer_id
+            .as_deref()
+            .and_then(|id| id.trim().parse::<f64>().ok())
+            .and_then(|id| by_id.get(&(id as u64)))
+        else {
+            continue;
+        };
+        matched += 1;
+        officer.synergy_group = officer.synergy_group.or(entry.synergy_id);
+        if officer.rarity.is_none() {
+            officer.rarity = entry.rarity.and_then(rarity_name).map(str::to_string);
+        }
+    }
+    matched
+}
+
+/// Upstream rarity number as the canonical rarity name.
+fn rarity_name(rarity: u8) -> Option<&'static str> {
+    match rarity {
+        1 => Some("common"),
+        2 => Some("uncommon"),
+        3 => Some("rare"),
+        4 => Some("epic"),
+        _ => None,
+    }
+}
@@ -0,0 +1,112 @@
+//! Power estimates: the strength a ship and crew show to other players, for PvP-minded users
+//! choosing between crews that telegraph different numbers.
+//!
+//! Ship power follows the formula behind the upstream hostile `strength` figures
+//! (data.stfc.space), which it reproduces exactly: strength = attack + defense + health, where
+//! attack = damage per round + 2 × (accuracy + armor piercing + shield piercing),
+//! defense = 5 × (armor + absorption + dodge) and health = (hull + shield) / 2.
+//! Profile research, buildings and tech count through their untargeted `weapon_damage`,
+//! `hull_hp` and `shield_hp` bonuses.
+//!
+//! Officer power is the sum of the officer's estimated stats ([Officer::estimated_stats]); the
+//! synced officer data carries no officer stats.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::data::officer::Officer;
+use crate::data::ship::ShipRecord;
+
+/// Ship power and its parts, rounded to whole points.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+pub struct ShipPower {
+    pub attack: f64,
+    pub defense: f64,
+    pub health: f64,
+    pub total: f64,
+}
+
+/// Power of a ship with a crew seated ([ShipPower] plus the officers').
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, JsonSchema)]
+pub struct PowerEstimate {
+    pub total: f64,
+    pub ship: ShipPower,
+    pub crew: f64,
+}
+
+impl PowerEstimate {
+    pub fn new(ship: ShipPower, crew: f64) -> Self {
+        Self { total: ship.total + crew, ship, crew }
+    }
+}
+
+/// Power of `ship` (resolved at its tier and level) with `bonuses` (a profile's untargeted
+/// bonuses) applied. Defense is 0 for ship data without armor, absorption and dodge ratings.
+pub fn ship_power(ship: &ShipRecord, bonuses: &HashMap<String, f64>) -> ShipPower {
+    let bonus = |key: &str| bonuses.get(key).copied().unwrap_or(0.0);
+    let attack = ship.attack * (1.0 + bonus("weapon_damage"))
+        + 2.0 * (ship.accuracy + ship.armor_piercing + ship.shield_piercing);
+    let defense = 5.0
+        * [ship.armor, ship.absorption, ship.dodge]
+            .into_iter()
+            .flatten()
+            .sum::<f64>();
+    let health = (ship.hull_health * (1.0 + bonus("hull_hp"))
+        + ship.shield_health * (1.0 + bonus("shield_hp")))
+        / 2.0;
+    let (attack, defense, health) = (attack.round(), defense.round(), health.round());
+    ShipPower { attack, defense, health, total: attack + defense + health }
+}
+
+/// Estimated power of `officer` at roster `rank` and `level` (1 when unknown).
+pub fn officer_power(officer: &Officer, rank: Option<u8>, level: Option<u16>) -> f64 {
+    officer.estimated_stats(rank, level).total().round()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ship_power_matches_the_upstream_strength_formula() {
+        // Stats of upstream hostile 2788813820 (strength 16,622,898), as a ship record.
+        let ship: ShipRecord = serde_json::from_value(serde_json::json!({
+            "id": "h", "ship_name": "H", "ship_class": "battleship",
+            "armor_piercing": 156828.0, "shield_piercing": 156828.0, "accuracy": 327139.0,
+            "attack": 4813614.0, "crit_chance": 0.1, "crit_damage": 1.8,
+            "hull_health": 6752071.0, "shield_health": 9452906.0,
+            "armor": 29237.0, "absorption": 426567.0, "dodge": 29237.0
+        }))
+        .unwrap();
+        let power = ship_power(&ship, &HashMap::new());
+        assert_eq!(power.defense, 2425205.0);
+        assert_eq!(power.health, 8102489.0);
+        assert_eq!(power.total, 16622898.0);
+
+        let researched = ship_power(&ship, &HashMap::from([("hull_hp".to_string(), 0.5)]));
+        assert_eq!(researched.health - power.health, 1688017.0);
+    }
+
+    #[test]
+    fn officer_power_scales_with_rarity_level_and_rank() {
+        let officer = |rarity: Option<&str>| Officer {
+            rarity: rarity.map(str::to_string),
+            ..serde_json::from_str(r#"{"id":"o","name":"O"}"#).unwrap()
+        };
+        assert_eq!(officer_power(&officer(Some("Epic")), Some(5), Some(60)), 12000.0);
+        assert_eq!(officer_power(&officer(Some("rare")), None, Some(10)), 700.0);
+        assert_eq!(officer_power(&officer(None), None, None), 30.0);
+    }
+
+    #[test]
+    fn officer_stats_lean_toward_the_class_stat() {
+        let officer: Officer =
+            serde_json::from_str(r#"{"id":"o","name":"O","rarity":"rare","slot":"science"}"#)
+                .unwrap();
+        let stats = officer.estimated_stats(Some(3), Some(20));
+        assert_eq!((stats.attack, stats.defense, stats.health), (525.0, 1050.0, 525.0));
+        assert_eq!(officer_power(&officer, Some(3), Some(20)), stats.total());
+    }
+}
@@ -3,14 +3,17 @@
 //! Bonuses from synced forbidden/chaos tech (by fid) are merged in when [merge_forbidden_tech_bonuses_into_profile] is used.
 //! Bonuses from synced buildings (by bid) are merged in when [merge_building_bonuses_into_profile] is used.
 //! Bonuses from synced research (by rid) are merged in when [merge_research_bonuses_into_profile] is used.
+//! Catalog stats suffixed `_vs_hostile` / `_vs_armada` / `_vs_player` land in
+//! [PlayerProfile::target_bonuses].
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
-use crate::combat::Combatant;
+use crate::combat::{Combatant, ShipType};
 use crate::data::building::{self, BuildingBonusContext, BuildingIndex};
 use crate::data::forbidden_chaos::ForbiddenChaosList;
 use crate::data::import::{BuildingEntry, ForbiddenTechEntry, ResearchEntry};
@@ -32,10 +35,344 @@ pub struct PlayerProfile {
     /// forbidden_tech.imported.json. Enables UI to choose "Custom" chaos tech set per profile.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chaos_tech_override: Option<Vec<i64>>,
+    /// When true, seated officers' level-gated traits ([crate::data::officer::OfficerTrait]) are
+    /// added to the bonuses for each crew. Off by default: trait data is incomplete.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub officer_traits: bool,
+    /// When true, the bridge officers' stats at their rank and level
+    /// ([crate::data::officer::Officer::estimated_stats]) unlock the ship's
+    /// [crate::data::ship::OfficerBonus] for each crew. Off by default: the stats are estimates.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub officer_stats: bool,
+    /// Bonuses that only apply against one kind of defender (research such as "+damage vs
+    /// hostiles"), added on top of `bonuses` by [PlayerProfile::bonuses_against].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub target_bonuses: BTreeMap<TargetContext, HashMap<String, f64>>,
+}
+
+impl PlayerProfile {
+    /// `bonuses` plus the [PlayerProfile::target_bonuses] for `target`, summed per stat.
+    /// Borrows `bonuses` when there is nothing target-specific to add.
+    pub fn bonuses_against(&self, target: TargetContext) -> Cow<'_, HashMap<String, f64>> {
+        match self.target_bonuses.get(&target) {
+            Some(extra) if !extra.is_empty() => {
+                let mut out = self.bonuses.clone();
+                for (stat, value) in extra {
+                    *out.entry(stat.clone()).or_insert(0.0) += value;
+                }
+                Cow::Owned(out)
+            }
+            _ => Cow::Borrowed(&self.bonuses),
+        }
+    }
+}
+
+/// Which kind of defender a fight is against; selects [PlayerProfile::target_bonuses].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetContext {
+    /// Any hostile that is not an armada (the default for simulate and optimize).
+    Hostile,
+    Armada,
+    /// Another player's ship or station.
+    Player,
+}
+
+impl TargetContext {
+    pub const ALL: [TargetContext; 3] = [Self::Hostile, Self::Armada, Self::Player];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hostile => "hostile",
+            Self::Armada => "armada",
+            Self::Player => "player",
+        }
+    }
+
+    /// Path reported in profile validation errors.
+    pub fn field(self) -> &'static str {
+        match self {
+            Self::Hostile => "target_bonuses.hostile",
+            Self::Armada => "target_bonuses.armada",
+            Self::Player => "target_bonuses.player",
+        }
+    }
+
+    /// Context for a fight against a hostile record of `ship_type`: armada-class hostiles are
+    /// armadas, everything else is a hostile.
+    pub fn of_ship_type(ship_type: ShipType) -> Self {
+        match ship_type {
+            ShipType::Armada => Self::Armada,
+            _ => Self::Hostile,
+        }
+    }
+}
+
+/// Splits a catalog stat such as `weapon_damage_vs_hostiles` into the stat and the target it is
+/// limited to. Singular and plural suffixes are accepted; other stats have no target.
+pub fn split_target_stat(stat: &str) -> (&str, Option<TargetContext>) {
+    let Some((base, target)) = stat.rsplit_once("_vs_") else {
+        return (stat, None);
+    };
+    let target = match target.strip_suffix('s').unwrap_or(target) {
+        "hostile" => TargetContext::Hostile,
+        "armada" => TargetContext::Armada,
+        "player" => TargetContext::Player,
+        _ => return (stat, None),
+    };
+    (base, Some(target))
 }
 
 pub const DEFAULT_PROFILE_PATH: &str = "data/profile.json";
 
+/// How a [PlayerBonuses] stat is applied to the attacker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BonusUnit {
+    /// Scales the ship's stat by `1 + value` (0.25 = +25%).
+    Percent,
+    /// Added to the ship's stat as-is (crit chance and mitigation are decimals, 0.05 = 5 points).
+    Flat,
+}
+
+/// One stat a profile may carry a bonus for: its unit and the values accepted on save.
+#[derive(Debug, Clone, Copy)]
+pub struct BonusSpec {
+    pub stat: &'static str,
+    /// Path reported in validation errors (`bonuses.<stat>`).
+    pub field: &'static str,
+    pub unit: BonusUnit,
+    pub min: f64,
+    pub max: f64,
+}
+
+const fn spec(
+    stat: &'static str,
+    field: &'static str,
+    unit: BonusUnit,
+    min: f64,
+    max: f64,
+) -> BonusSpec {
+    BonusSpec { stat, field, unit, min, max }
+}
+
+/// Every stat [apply_bonuses_to_attacker] reads, in [PlayerBonuses] field order.
+pub const BONUS_SPECS: &[BonusSpec] = &[
+    spec("weapon_damage", "bonuses.weapon_damage", BonusUnit::Percent, -1.0, 100.0),
+    spec("hull_hp", "bonuses.hull_hp", BonusUnit::Percent, -1.0, 100.0),
+    spec("shield_hp", "bonuses.shield_hp", BonusUnit::Percent, -1.0, 100.0),
+    spec("crit_damage", "bonuses.crit_damage", BonusUnit::Percent, -1.0, 100.0),
+    spec("crit_chance", "bonuses.crit_chance", BonusUnit::Flat, -1.0, 1.0),
+    spec("pierce", "bonuses.pierce", BonusUnit::Flat, -100.0, 100.0),
+    spec("shield_mitigation", "bonuses.shield_mitigation", BonusUnit::Flat, -1.0, 1.0),
+    spec("armor", "bonuses.armor", BonusUnit::Flat, -1.0, 1.0),
+    spec("dodge", "bonuses.dodge", BonusUnit::Flat, -1.0, 1.0),
+    spec("damage_reduction", "bonuses.damage_reduction", BonusUnit::Flat, -1.0, 1.0),
+    spec("isolytic_damage", "bonuses.isolytic_damage", BonusUnit::Flat, -100.0, 100.0),
+    spec("isolytic_defense", "bonuses.isolytic_defense", BonusUnit::Flat, -100.0, 100.0),
+    spec("apex_shred", "bonuses.apex_shred", BonusUnit::Flat, -100.0, 100.0),
+    spec("apex_barrier", "bonuses.apex_barrier", BonusUnit::Flat, f64::MIN, f64::MAX),
+];
+
+/// Keys older profiles used, with the stat they now feed and the factor applied to the value.
+/// `weapon`/`shield`/`hull`/`mitigation` come from the first quick-mode form, which stored whole
+/// percents (10 = 10%) under names the engine never read.
+const LEGACY_BONUS_KEYS: &[(&str, &str, f64)] = &[
+    ("armor_pierce", "pierce", 1.0),
+    ("shield_pierce", "pierce", 1.0),
+    ("weapon", "weapon_damage", 0.01),
+    ("shield", "shield_hp", 0.01),
+    ("hull", "hull_hp", 0.01),
+    ("mitigation", "damage_reduction", 0.01),
+];
+
+/// Looks up the unit and accepted range for a canonical bonus stat.
+pub fn bonus_spec(stat: &str) -> Option<&'static BonusSpec> {
+    BONUS_SPECS.iter().find(|s| s.stat == stat)
+}
+
+/// A rejected bonus on save: `field` is `bonuses.<stat>`, or `bonuses` for an unknown key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BonusIssue {
+    pub field: &'static str,
+    /// The stat whose value is out of range; None for an unknown key.
+    pub stat: Option<&'static str>,
+    pub message: String,
+}
+
+/// The bonuses a player enters by hand, one optional value per combat stat (see [BONUS_SPECS]
+/// for units). Serializes to the same `{ stat: value }` object `profile.json` has always used;
+/// [PlayerProfile::bonuses] stays a plain map because synced tech, buildings and research merge
+/// into it before combat.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerBonuses {
+    /// Percent: attack multiplier (0.25 = +25% weapon damage).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weapon_damage: Option<f64>,
+    /// Percent: hull health multiplier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hull_hp: Option<f64>,
+    /// Percent: shield health multiplier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shield_hp: Option<f64>,
+    /// Percent: critical damage multiplier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crit_damage: Option<f64>,
+    /// Flat: added to crit chance (0–1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crit_chance: Option<f64>,
+    /// Flat: added to pierce. Older `armor_pierce` / `shield_pierce` keys fold into this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pierce: Option<f64>,
+    /// Flat: added to shield mitigation (0–1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shield_mitigation: Option<f64>,
+    /// Flat: added to mitigation (0–1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub armor: Option<f64>,
+    /// Flat: added to mitigation (0–1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dodge: Option<f64>,
+    /// Flat: added to mitigation (0–1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage_reduction: Option<f64>,
+    /// Flat: added to isolytic damage (decimal share of regular damage).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isolytic_damage: Option<f64>,
+    /// Flat: added to isolytic defense.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isolytic_defense: Option<f64>,
+    /// Flat: added to apex shred (decimal, 1.0 = 100%).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apex_shred: Option<f64>,
+    /// Flat: added to apex barrier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apex_barrier: Option<f64>,
+}
+
+impl PlayerBonuses {
+    fn slot_mut(&mut self, stat: &str) -> Option<&mut Option<f64>> {
+        Some(match stat {
+            "weapon_damage" => &mut self.weapon_damage,
+            "hull_hp" => &mut self.hull_hp,
+            "shield_hp" => &mut self.shield_hp,
+            "crit_damage" => &mut self.crit_damage,
+            "crit_chance" => &mut self.crit_chance,
+            "pierce" => &mut self.pierce,
+            "shield_mitigation" => &mut self.shield_mitigation,
+            "armor" => &mut self.armor,
+            "dodge" => &mut self.dodge,
+            "damage_reduction" => &mut self.damage_reduction,
+            "isolytic_damage" => &mut self.isolytic_damage,
+            "isolytic_defense" => &mut self.isolytic_defense,
+            "apex_shred" => &mut self.apex_shred,
+            "apex_barrier" => &mut self.apex_barrier,
+            _ => return None,
+        })
+    }
+
+    fn values(&self) -> [(&'static str, Option<f64>); 14] {
+        [
+            ("weapon_damage", self.weapon_damage),
+            ("hull_hp", self.hull_hp),
+            ("shield_hp", self.shield_hp),
+            ("crit_damage", self.crit_damage),
+            ("crit_chance", self.crit_chance),
+            ("pierce", self.pierce),
+            ("shield_mitigation", self.shield_mitigation),
+            ("armor", self.armor),
+            ("dodge", self.dodge),
+            ("damage_reduction", self.damage_reduction),
+            ("isolytic_damage", self.isolytic_damage),
+            ("isolytic_defense", self.isolytic_defense),
+            ("apex_shred", self.apex_shred),
+            ("apex_barrier", self.apex_barrier),
+        ]
+    }
+
+    /// Value set for a canonical stat, if any.
+    pub fn get(&self, stat: &str) -> Option<f64> {
+        self.values().into_iter().find(|(s, _)| *s == stat).and_then(|(_, v)| v)
+    }
+
+    /// The set stats as the `{ stat: value }` map the engine applies.
+    pub fn to_map(&self) -> HashMap<String, f64> {
+        self.values()
+            .into_iter()
+            .filter_map(|(stat, v)| v.map(|v| (stat.to_string(), v)))
+            .collect()
+    }
+
+    /// Reads a stored or submitted bonus map, migrating legacy keys ([LEGACY_BONUS_KEYS]) and
+    /// checking every value against its [BonusSpec]. All problems are returned together.
+    pub fn from_map(raw: &HashMap<String, f64>) -> Result<Self, Vec<BonusIssue>> {
+        let (bonuses, unknown) = Self::migrate(raw);
+        let mut issues: Vec<BonusIssue> = unknown
+            .into_iter()
+            .map(|key| BonusIssue {
+                field: "bonuses",
+                stat: None,
+                message: format!(
+                    "unknown stat '{key}'; expected one of: {}",
+                    BONUS_SPECS.iter().map(|s| s.stat).collect::<Vec<_>>().join(", ")
+                ),
+            })
+            .collect();
+        for spec in BONUS_SPECS {
+            let Some(value) = bonuses.get(spec.stat) else {
+                continue;
+            };
+            if !value.is_finite() {
+                issues.push(BonusIssue {
+                    field: spec.field,
+                    stat: Some(spec.stat),
+                    message: "must be a finite number".to_string(),
+                });
+            } else if value < spec.min || value > spec.max {
+                let hint = match spec.unit {
+                    BonusUnit::Percent => " (percent bonuses are decimals: 0.25 = +25%)",
+                    BonusUnit::Flat if spec.max <= 1.0 => " (a decimal: 0.05 = 5 points)",
+                    BonusUnit::Flat => "",
+                };
+                issues.push(BonusIssue {
+                    field: spec.field,
+                    stat: Some(spec.stat),
+                    message: format!("must be between {} and {}{hint}", spec.min, spec.max),
+                });
+            }
+        }
+        // Keep field order stable regardless of HashMap iteration.
+        issues.sort_by(|a, b| a.field.cmp(b.field).then_with(|| a.message.cmp(&b.message)));
+        if issues.is_empty() {
+            Ok(bonuses)
+        } else {
+            Err(issues)
+        }
+    }
+
+    /// Compatibility shim: canonical keys are kept, legacy keys are converted and summed into the
+    /// stat they replaced, and anything else is returned as unknown. Never fails.
+    pub fn migrate(raw: &HashMap<String, f64>) -> (Self, Vec<String>) {
+        let mut out = Self::default();
+        let mut unknown = Vec::new();
+        let mut keys: Vec<&String> = raw.keys().collect();
+        keys.sort();
+        for key in keys {
+            let value = raw[key];
+            let (stat, factor) = match LEGACY_BONUS_KEYS.iter().find(|(old, _, _)| old == key) {
+                Some((_, stat, factor)) => (*stat, *factor),
+                None => (key.as_str(), 1.0),
+            };
+            match out.slot_mut(stat) {
+                Some(slot) => *slot = Some(slot.unwrap_or(0.0) + value * factor),
+                None => unknown.push(key.clone()),
+            }
+        }
+        (out, unknown)
+    }
+}
+
 /// Applies one bonus to profile (add or mult). Mult: (1+current)*(1+value)-1; else additive.
 fn accumulate_forbidden_tech_bonus(out: &mut HashMap<String, f64>, stat: &str, operator: &str, value: f64) {
     let current = out.get(stat).copied().unwrap_or(0.0);
@@ -276,10 +613,26 @@ fn normalize_profile_combat_stat(stat: &str) -> Option<&'static str> {
         "armor" => Some("armor"),
         "dodge" => Some("dodge"),
         "damage_reduction" => Some("damage_reduction"),
+        "apex_shred" => Some("apex_shred"),
+        "apex_barrier" => Some("apex_barrier"),
         _ => None,
     }
 }
 
+/// Adds one catalog bonus to `profile.bonuses`, or to the matching [PlayerProfile::target_bonuses]
+/// entry when the stat carries a `_vs_<target>` suffix. Non-combat stats are ignored.
+fn add_combat_bonus(profile: &mut PlayerProfile, stat: &str, value: f64) {
+    let (stat, target) = split_target_stat(stat);
+    let Some(key) = normalize_profile_combat_stat(stat) else {
+        return;
+    };
+    let bonuses = match target {
+        Some(target) => profile.target_bonuses.entry(target).or_default(),
+        None => &mut profile.bonuses,
+    };
+    *bonuses.entry(key.to_string()).or_insert(0.0) += value;
+}
+
 /// Merges combat stat bonuses from player's synced buildings into `profile.bonuses`.
 /// Resolves bid → building id via `bid_to_id`, loads building records, computes cumulative
 /// bonuses, and adds only combat keys (weapon_damage, hull_hp, etc.). armor_pierce and
@@ -326,11 +679,7 @@ pub fn merge_building_bonuses_into_profile(
         building::cumulative_building_bonuses_with_context(&records, &levels_by_id, context);
 
     for (stat, value) in bonuses {
-        let Some(key) = normalize_profile_combat_stat(&stat) else {
-            continue;
-        };
-        let current = profile.bonuses.get(key).copied().unwrap_or(0.0);
-        profile.bonuses.insert(key.to_string(), current + value);
+        add_combat_bonus(profile, &stat, value);
     }
 }
 
@@ -373,11 +722,7 @@ pub fn merge_research_bonuses_into_profile(
     let bonuses = cumulative_research_bonuses(&records, &levels_by_rid);
 
     for (stat, value) in bonuses {
-        let Some(key) = normalize_profile_combat_stat(&stat) else {
-            continue;
-        };
-        let current = profile.bonuses.get(key).copied().unwrap_or(0.0);
-        profile.bonuses.insert(key.to_string(), current + value);
+        add_combat_bonus(profile, &stat, value);
     }
 }
 
@@ -388,15 +733,12 @@ pub fn accumulate_combat_only_bonuses_from_raw(
     raw: &HashMap<String, f64>,
 ) {
     for (stat, value) in raw {
-        let Some(key) = normalize_profile_combat_stat(stat) else {
-            continue;
-        };
-        let current = profile.bonuses.get(key).copied().unwrap_or(0.0);
-        profile.bonuses.insert(key.to_string(), current + value);
+        add_combat_bonus(profile, stat, *value);
     }
 }
 
 /// Load profile from JSON file. Returns default (empty bonuses) if file missing or invalid.
+/// Legacy bonus keys are migrated and unknown ones dropped (see [PlayerBonuses::migrate]).
 pub fn load_profile(path: &str) -> PlayerProfile {
     let path = Path::new(path);
     if !path.exists() {
@@ -406,18 +748,20 @@ pub fn load_profile(path: &str) -> PlayerProfile {
         Ok(s) => s,
         _ => return PlayerProfile::default(),
     };
-    serde_json::from_str(&raw).unwrap_or_default()
-}
-
-fn get_bonus(profile: &PlayerProfile, key: &str) -> f64 {
-    profile.bonuses.get(key).copied().unwrap_or(0.0)
+    let mut profile: PlayerProfile = serde_json::from_str(&raw).unwrap_or_default();
+    profile.bonuses = PlayerBonuses::migrate(&profile.bonuses).0.to_map();
+    for bonuses in profile.target_bonuses.values_mut() {
+        *bonuses = PlayerBonuses::migrate(bonuses).0.to_map();
+    }
+    profile
 }
 
 /// Apply LCARS/officer static buffs to a Combatant (e.g. from [BuffSet::static_buffs]).
 /// Intended for use when building a Combatant from ship/hostile + crew where crew is resolved via
 /// [crate::lcars::resolve_crew_to_buff_set]. Keys applied: isolytic_damage, isolytic_defense,
 /// shield_mitigation (additive; shield_mitigation clamped to [0, 1]), weapon_damage (mult to attack),
-/// hull_hp, shield_hp (mult), shield_pierce/armor_pierce (add to pierce), crit_chance (add), crit_damage (mult).
+/// hull_hp, shield_hp (mult), shield_pierce/armor_pierce (add to pierce), crit_chance (add), crit_damage (mult),
+/// apex_shred / apex_barrier (add).
 pub fn apply_static_buffs_to_combatant(
     combatant: Combatant,
     static_buffs: &HashMap<String, f64>,
@@ -441,6 +785,8 @@ pub fn apply_static_buffs_to_combatant(
     let armor_add = static_buffs.get("armor").copied().unwrap_or(0.0);
     let damage_reduction_add = static_buffs.get("damage_reduction").copied().unwrap_or(0.0);
     let dodge_add = static_buffs.get("dodge").copied().unwrap_or(0.0);
+    let apex_shred_add = static_buffs.get("apex_shred").copied().unwrap_or(0.0);
+    let apex_barrier_add = static_buffs.get("apex_barrier").copied().unwrap_or(0.0);
 
     Combatant {
         attack: combatant.attack * weapon_mult,
@@ -457,29 +803,46 @@ pub fn apply_static_buffs_to_combatant(
         mitigation: (combatant.mitigation + armor_add + damage_reduction_add + dodge_add)
             .max(0.0)
             .min(1.0),
+        apex_shred: (combatant.apex_shred + apex_shred_add).max(0.0),
+        apex_barrier: (combatant.apex_barrier + apex_barrier_add).max(0.0),
         ..combatant
     }
 }
 
-/// Apply effective_bonuses to attacker Combatant (multipliers and additive bonuses).
+/// Apply effective_bonuses to attacker Combatant (multipliers and additive bonuses), including
+/// the profile's bonuses against `target` ([PlayerProfile::bonuses_against]).
 /// Keys: weapon_damage, hull_hp, shield_hp, crit_chance, crit_damage, pierce (additive),
-/// shield_mitigation (additive to base), armor/dodge/damage_reduction (additive to mitigation).
-pub fn apply_profile_to_attacker(attacker: Combatant, profile: &PlayerProfile) -> Combatant {
-    if profile.bonuses.is_empty() {
+/// shield_mitigation (additive to base), armor/dodge/damage_reduction (additive to mitigation),
+/// apex_shred / apex_barrier (additive; shred is a decimal, barrier a flat value).
+pub fn apply_profile_to_attacker(
+    attacker: Combatant,
+    profile: &PlayerProfile,
+    target: TargetContext,
+) -> Combatant {
+    apply_bonuses_to_attacker(attacker, &profile.bonuses_against(target))
+}
+
+/// [apply_profile_to_attacker] for a bonus map other than a profile's own (e.g. profile bonuses
+/// plus a crew's officer traits, see [crate::data::officer::Officer::trait_contributions]).
+pub fn apply_bonuses_to_attacker(attacker: Combatant, bonuses: &HashMap<String, f64>) -> Combatant {
+    if bonuses.is_empty() {
         return attacker;
     }
-    let weapon = 1.0 + get_bonus(profile, "weapon_damage");
-    let hull_hp = 1.0 + get_bonus(profile, "hull_hp");
-    let shield_hp = 1.0 + get_bonus(profile, "shield_hp");
-    let isolytic_damage_add = get_bonus(profile, "isolytic_damage");
-    let isolytic_defense_add = get_bonus(profile, "isolytic_defense");
-    let crit_chance_add = get_bonus(profile, "crit_chance");
-    let crit_damage_mult = 1.0 + get_bonus(profile, "crit_damage");
-    let pierce_add = get_bonus(profile, "pierce");
-    let shield_mit_add = get_bonus(profile, "shield_mitigation");
-    let mitigation_add = get_bonus(profile, "armor")
-        + get_bonus(profile, "dodge")
-        + get_bonus(profile, "damage_reduction");
+    let get_bonus = |key: &str| bonuses.get(key).copied().unwrap_or(0.0);
+    let weapon = 1.0 + get_bonus("weapon_damage");
+    let hull_hp = 1.0 + get_bonus("hull_hp");
+    let shield_hp = 1.0 + get_bonus("shield_hp");
+    let isolytic_damage_add = get_bonus("isolytic_damage");
+    let isolytic_defense_add = get_bonus("isolytic_defense");
+    let crit_chance_add = get_bonus("crit_chance");
+    let crit_damage_mult = 1.0 + get_bonus("crit_damage");
+    let pierce_add = get_bonus("pierce");
+    let shield_mit_add = get_bonus("shield_mitigation");
+    let mitigation_add = get_bonus("armor")
+        + get_bonus("dodge")
+        + get_bonus("damage_reduction");
+    let apex_shred_add = get_bonus("apex_shred");
+    let apex_barrier_add = get_bonus("apex_barrier");
 
     Combatant {
         attack: attacker.attack * weapon,
@@ -494,6 +857,8 @@ pub fn apply_profile_to_attacker(attacker: Combatant, profile: &PlayerProfile) -
             .min(1.0),
         isolytic_damage: (attacker.isolytic_damage + isolytic_damage_add).max(0.0),
         isolytic_defense: (attacker.isolytic_defense + isolytic_defense_add).max(0.0),
+        apex_shred: (attacker.apex_shred + apex_shred_add).max(0.0),
+        apex_barrier: (attacker.apex_barrier + apex_barrier_add).max(0.0),
         ..attacker
     }
 }
@@ -670,25 +1035,12 @@ mod tests {
         isolytic_defense: f64,
         shield_mitigation: f64,
     ) -> Combatant {
-        Combatant {
-            id: "test".to_string(),
-            attack: 0.0,
-            mitigation: 0.0,
-            pierce: 0.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            end_of_round_damage: 0.0,
-            hull_health: 1000.0,
-            shield_health: 0.0,
-            shield_mitigation,
-            apex_barrier: 0.0,
-            weapons: vec![],
-            apex_shred: 0.0,
-            isolytic_damage,
-            isolytic_defense,
-        }
+        Combatant::builder("test")
+            .shield_mitigation(shield_mitigation)
+            .isolytic_damage(isolytic_damage)
+            .isolytic_defense(isolytic_defense)
+            .build()
+            .unwrap()
     }
 
     #[test]
@@ -715,34 +1067,112 @@ mod tests {
 
     #[test]
     fn apply_profile_to_attacker_applies_mitigation_stats() {
-        let attacker = Combatant {
-            id: "test".to_string(),
-            attack: 100.0,
-            mitigation: 0.10,
-            pierce: 0.05,
-            crit_chance: 0.10,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            end_of_round_damage: 0.0,
-            hull_health: 1000.0,
-            shield_health: 500.0,
-            shield_mitigation: 0.2,
-            apex_barrier: 0.0,
-            weapons: vec![],
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-        };
+        let attacker = Combatant::builder("test")
+            .attack(100.0)
+            .mitigation(0.10)
+            .pierce(0.05)
+            .crit_chance(0.10)
+            .shield_health(500.0)
+            .shield_mitigation(0.2)
+            .build()
+            .unwrap();
         let mut profile = PlayerProfile::default();
         profile.bonuses.insert("armor".to_string(), 0.04);
         profile.bonuses.insert("dodge".to_string(), 0.03);
         profile.bonuses.insert("damage_reduction".to_string(), 0.02);
 
-        let out = apply_profile_to_attacker(attacker, &profile);
+        let out = apply_profile_to_attacker(attacker, &profile, TargetContext::Hostile);
         assert!((out.mitigation - 0.19).abs() < 1e-9);
     }
 
+    #[test]
+    fn research_apex_shred_reaches_the_attacker() {
+        use crate::data::research::{
+            ResearchBonusEntry, ResearchCatalog, ResearchLevel, ResearchRecord,
+        };
+
+        let bonus = |stat: &str, value: f64| ResearchBonusEntry {
+            stat: stat.to_string(),
+            value,
+            operator: "add".to_string(),
+        };
+        let catalog = ResearchCatalog {
+            source: None,
+            last_updated: None,
+            items: vec![ResearchRecord {
+                rid: 7,
+                name: Some("Apex Shred".to_string()),
+                data_version: None,
+                source_note: None,
+                levels: vec![ResearchLevel {
+                    level: 1,
+                    bonuses: vec![bonus("apex_shred", 0.25), bonus("apex_barrier", 500.0)],
+                }],
+            }],
+        };
+        let mut profile = PlayerProfile::default();
+        merge_research_bonuses_into_profile(
+            &mut profile,
+            &[ResearchEntry { rid: 7, level: 1 }],
+            &catalog,
+        );
+
+        let attacker = Combatant::builder("test").apex_shred(0.5).build().unwrap();
+        let out = apply_profile_to_attacker(attacker, &profile, TargetContext::Hostile);
+        assert!((out.apex_shred - 0.75).abs() < 1e-12);
+        assert_eq!(out.apex_barrier, 500.0);
+    }
+
+    #[test]
+    fn research_vs_target_bonuses_only_apply_against_that_target() {
+        use crate::data::research::{
+            ResearchBonusEntry, ResearchCatalog, ResearchLevel, ResearchRecord,
+        };
+
+        let bonus = |stat: &str, value: f64| ResearchBonusEntry {
+            stat: stat.to_string(),
+            value,
+            operator: "add".to_string(),
+        };
+        let catalog = ResearchCatalog {
+            source: None,
+            last_updated: None,
+            items: vec![ResearchRecord {
+                rid: 8,
+                name: Some("Hostile Hunter".to_string()),
+                data_version: None,
+                source_note: None,
+                levels: vec![ResearchLevel {
+                    level: 1,
+                    bonuses: vec![
+                        bonus("weapon_damage", 0.1),
+                        bonus("weapon_damage_vs_hostiles", 0.2),
+                        bonus("armor_pierce_vs_player", 0.05),
+                    ],
+                }],
+            }],
+        };
+        let mut profile = PlayerProfile::default();
+        merge_research_bonuses_into_profile(
+            &mut profile,
+            &[ResearchEntry { rid: 8, level: 1 }],
+            &catalog,
+        );
+        assert_eq!(profile.bonuses.get("weapon_damage"), Some(&0.1));
+        assert_eq!(
+            profile.target_bonuses[&TargetContext::Player].get("pierce"),
+            Some(&0.05)
+        );
+
+        let attacker = || Combatant::builder("test").attack(100.0).build().unwrap();
+        let vs_hostile = apply_profile_to_attacker(attacker(), &profile, TargetContext::Hostile);
+        let vs_armada = apply_profile_to_attacker(attacker(), &profile, TargetContext::Armada);
+        assert!((vs_hostile.attack - 130.0).abs() < 1e-9);
+        assert!((vs_armada.attack - 110.0).abs() < 1e-9);
+        assert_eq!(TargetContext::of_ship_type(ShipType::Armada), TargetContext::Armada);
+        assert_eq!(split_target_stat("crit_chance"), ("crit_chance", None));
+    }
+
     #[test]
     fn merge_forbidden_tech_fids_scales_additive_by_level_tier_when_enabled() {
         let mut profile = PlayerProfile::default();
@@ -856,4 +1286,37 @@ mod tests {
 
         assert_eq!(profile.bonuses.get("weapon_damage"), Some(&0.1));
     }
+
+    #[test]
+    fn player_bonuses_migrate_legacy_keys_and_validate_ranges() {
+        let raw: HashMap<String, f64> = [
+            ("armor_pierce", 0.02),
+            ("shield_pierce", 0.03),
+            ("weapon", 45.0),
+            ("hull_hp", 0.1),
+            ("hull", 10.0),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        let bonuses = PlayerBonuses::from_map(&raw).expect("legacy keys migrate");
+        assert!((bonuses.pierce.unwrap() - 0.05).abs() < 1e-12);
+        assert!((bonuses.weapon_damage.unwrap() - 0.45).abs() < 1e-12);
+        assert!((bonuses.hull_hp.unwrap() - 0.2).abs() < 1e-12);
+        assert_eq!(bonuses.to_map().len(), 3);
+        assert_eq!(bonus_spec("hull_hp").map(|s| s.unit), Some(BonusUnit::Percent));
+
+        let raw: HashMap<String, f64> =
+            [("crit_chance", 1.5), ("shield_hp", f64::NAN), ("speed", 1.0)]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect();
+        let issues = PlayerBonuses::from_map(&raw).unwrap_err();
+        let fields: Vec<&str> = issues.iter().map(|i| i.field).collect();
+        assert_eq!(fields, vec!["bonuses", "bonuses.crit_chance", "bonuses.shield_hp"]);
+
+        let (lenient, unknown) = PlayerBonuses::migrate(&raw);
+        assert_eq!(unknown, vec!["speed".to_string()]);
+        assert_eq!(lenient.crit_chance, Some(1.5));
+    }
 }
@@ -0,0 +1,476 @@
+//! Opt-in refresh of hostile and officer data from a community mirror.
+//!
+//! `KOBAYASHI_DATA_REMOTE_URL` names a base URL serving `manifest.json`:
+//!
+//! ```json
+//! { "datasets": { "hostiles": { "version": "stfcspace-hostiles-2026-04-01", "bundle": "hostiles.json" } } }
+//! ```
+//!
+//! Each bundle (a path relative to the base URL, or an absolute URL) is
+//! `{ "version": "...", "files": { "index.json": {...}, "1000510818.json": {...} } }`: file name →
+//! JSON document (a string for non-JSON files). Applying a bundle copies the live dataset
+//! directory to `<dir>.staging`, overlays the bundle files, validates the result with the same
+//! checks as `kobayashi validate`, then swaps directories (the old one is kept as `<dir>.previous`
+//! for [roll_back]). Loading the swapped data into the server is the caller's job
+//! ([crate::data::data_registry::SharedRegistry::reload]).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::data::validate::{
+    validate_hostiles_dataset, validate_officer_dataset_canonical, ValidationReport,
+    ValidationSeverity,
+};
+
+pub const REMOTE_URL_ENV: &str = "KOBAYASHI_DATA_REMOTE_URL";
+/// Largest bundle accepted, in bytes (the full hostile set is a few tens of MB).
+pub const MAX_BUNDLE_BYTES: u64 = 256 * 1024 * 1024;
+const MANIFEST_TIMEOUT: Duration = Duration::from_secs(15);
+const BUNDLE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A dataset the mirror may update.
+#[derive(Debug, Clone, Copy)]
+pub struct RemoteDataset {
+    pub name: &'static str,
+    /// Directory relative to the data root.
+    pub dir: &'static str,
+    /// File in `dir` whose `data_version` names the installed version.
+    pub version_file: &'static str,
+    validate: fn(&Path) -> Result<ValidationReport, String>,
+}
+
+fn validate_hostiles_dir(dir: &Path) -> Result<ValidationReport, String> {
+    validate_hostiles_dataset(&dir.to_string_lossy())
+}
+
+fn validate_officers_dir(dir: &Path) -> Result<ValidationReport, String> {
+    validate_officer_dataset_canonical(&dir.join("officers.canonical.json").to_string_lossy())
+}
+
+pub const REMOTE_DATASETS: &[RemoteDataset] = &[
+    RemoteDataset {
+        name: "hostiles",
+        dir: "data/hostiles",
+        version_file: "index.json",
+        validate: validate_hostiles_dir,
+    },
+    RemoteDataset {
+        name: "officers",
+        dir: "data/officers",
+        version_file: "officers.canonical.json",
+        validate: validate_officers_dir,
+    },
+];
+
+pub fn remote_dataset(name: &str) -> Option<&'static RemoteDataset> {
+    REMOTE_DATASETS.iter().find(|d| d.name == name)
+}
+
+#[derive(Debug)]
+pub enum RemoteError {
+    /// `KOBAYASHI_DATA_REMOTE_URL` is not set.
+    Disabled,
+    Http(String),
+    Parse(String),
+    /// The bundle is malformed or the staged data failed validation.
+    Invalid(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "remote data updates are off; set {REMOTE_URL_ENV}"),
+            Self::Http(e) => write!(f, "download failed: {e}"),
+            Self::Parse(e) => write!(f, "unreadable remote data: {e}"),
+            Self::Invalid(e) => write!(f, "rejected remote data: {e}"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for RemoteError {}
+
+impl From<std::io::Error> for RemoteError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestEntry {
+    pub version: String,
+    pub bundle: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RemoteManifest {
+    #[serde(default)]
+    pub datasets: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DatasetBundle {
+    pub version: String,
+    pub files: BTreeMap<String, serde_json::Value>,
+}
+
+/// Installed vs mirrored version of one dataset.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DatasetUpdate {
+    pub dataset: &'static str,
+    pub current_version: Option<String>,
+    pub available_version: Option<String>,
+    pub update_available: bool,
+}
+
+/// Configured mirror base URL (without a trailing slash), if any.
+pub fn remote_base_url() -> Option<String> {
+    std::env::var(REMOTE_URL_ENV)
+        .ok()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+}
+
+fn resolve_url(base: &str, target: &str) -> String {
+    if target.starts_with("http://") || target.starts_with("https://") {
+        target.to_string()
+    } else {
+        format!("{base}/{}", target.trim_start_matches('/'))
+    }
+}
+
+fn fetch_json<T: serde::de::DeserializeOwned>(
+    url: &str,
+    timeout: Duration,
+) -> Result<T, RemoteError> {
+    let response = ureq::get(url)
+        .timeout(timeout)
+        .call()
+        .map_err(|e| RemoteError::Http(e.to_string()))?;
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_BUNDLE_BYTES + 1)
+        .read_to_end(&mut body)?;
+    if body.len() as u64 > MAX_BUNDLE_BYTES {
+        return Err(RemoteError::Invalid(format!(
+            "{url} is larger than {MAX_BUNDLE_BYTES} bytes"
+        )));
+    }
+    serde_json::from_slice(&body).map_err(|e| RemoteError::Parse(format!("{url}: {e}")))
+}
+
+pub fn fetch_manifest(base: &str) -> Result<RemoteManifest, RemoteError> {
+    fetch_json(&resolve_url(base, "manifest.json"), MANIFEST_TIMEOUT)
+}
+
+pub fn fetch_bundle(base: &str, entry: &ManifestEntry) -> Result<DatasetBundle, RemoteError> {
+    fetch_json(&resolve_url(base, &entry.bundle), BUNDLE_TIMEOUT)
+}
+
+/// `data_version` of the dataset installed under `root`.
+pub fn current_version(root: &Path, dataset: &RemoteDataset) -> Option<String> {
+    read_version(&root.join(dataset.dir), dataset)
+}
+
+fn read_version(dir: &Path, dataset: &RemoteDataset) -> Option<String> {
+    let raw = fs::read_to_string(dir.join(dataset.version_file)).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    value.get("data_version")?.as_str().map(str::to_string)
+}
+
+/// Installed and mirrored versions of every [REMOTE_DATASETS] entry.
+pub fn compare_versions(root: &Path, manifest: &RemoteManifest) -> Vec<DatasetUpdate> {
+    REMOTE_DATASETS
+        .iter()
+        .map(|dataset| {
+            let current_version = current_version(root, dataset);
+            let available_version = manifest
+                .datasets
+                .get(dataset.name)
+                .map(|entry| entry.version.clone());
+            DatasetUpdate {
+                dataset: dataset.name,
+                update_available: available_version
+                    .as_ref()
+                    .is_some_and(|v| Some(v) != current_version.as_ref()),
+                current_version,
+                available_version,
+            }
+        })
+        .collect()
+}
+
+/// Bundle file names must be plain names inside the dataset directory.
+fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\', ':'])
+        && Path::new(name).components().count() == 1
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    let mut name = dir.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    dir.with_file_name(name)
+}
+
+/// Write `bundle` over a copy of the installed dataset in `<dir>.staging` and validate it.
+/// Returns the staging directory; on error it is removed.
+pub fn stage_bundle(
+    root: &Path,
+    dataset: &RemoteDataset,
+    bundle: &DatasetBundle,
+) -> Result<PathBuf, RemoteError> {
+    if bundle.files.is_empty() {
+        return Err(RemoteError::Invalid(format!("{} bundle has no files", dataset.name)));
+    }
+    if let Some(bad) = bundle.files.keys().find(|name| !is_safe_file_name(name)) {
+        return Err(RemoteError::Invalid(format!("bad file name '{bad}'")));
+    }
+    let live = root.join(dataset.dir);
+    let staging = sibling(&live, ".staging");
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    let staged = (|| {
+        if live.is_dir() {
+            copy_dir(&live, &staging)?;
+        } else {
+            fs::create_dir_all(&staging)?;
+        }
+        for (name, content) in &bundle.files {
+            let body = match content {
+                serde_json::Value::String(text) if !name.ends_with(".json") => text.clone(),
+                value => serde_json::to_string_pretty(value)
+                    .map_err(|e| RemoteError::Parse(e.to_string()))?,
+            };
+            fs::write(staging.join(name), body)?;
+        }
+        check_staged(&staging, dataset, &bundle.version)
+    })();
+    match staged {
+        Ok(()) => Ok(staging),
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            Err(e)
+        }
+    }
+}
+
+fn check_staged(staging: &Path, dataset: &RemoteDataset, version: &str) -> Result<(), RemoteError> {
+    let report = (dataset.validate)(staging).map_err(RemoteError::Invalid)?;
+    if let Some(error) = report
+        .diagnostics
+        .iter()
+        .find(|d| d.severity == ValidationSeverity::Error)
+    {
+        return Err(RemoteError::Invalid(format!("{}: {}", error.context, error.message)));
+    }
+    match read_version(staging, dataset) {
+        Some(found) if found == version => Ok(()),
+        found => Err(RemoteError::Invalid(format!(
+            "bundle says version '{version}' but {} has {:?}",
+            dataset.version_file, found
+        ))),
+    }
+}
+
+/// Make `staging` the live dataset; the old directory becomes `<dir>.previous`.
+pub fn swap_in(root: &Path, dataset: &RemoteDataset, staging: &Path) -> Result<(), RemoteError> {
+    let live = root.join(dataset.dir);
+    let previous = sibling(&live, ".previous");
+    if previous.exists() {
+        fs::remove_dir_all(&previous)?;
+    }
+    if live.exists() {
+        fs::rename(&live, &previous)?;
+    }
+    if let Err(e) = fs::rename(staging, &live) {
+        if previous.exists() {
+            let _ = fs::rename(&previous, &live);
+        }
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Put `<dir>.previous` back (after the swapped data failed to load).
+pub fn roll_back(root: &Path, dataset: &RemoteDataset) -> Result<(), RemoteError> {
+    let live = root.join(dataset.dir);
+    let previous = sibling(&live, ".previous");
+    if !previous.is_dir() {
+        return Err(RemoteError::Invalid(format!("no previous {} data", dataset.name)));
+    }
+    let failed = sibling(&live, ".failed");
+    if failed.exists() {
+        fs::remove_dir_all(&failed)?;
+    }
+    if live.exists() {
+        fs::rename(&live, &failed)?;
+    }
+    fs::rename(&previous, &live)?;
+    Ok(())
+}
+
+/// Download, stage, validate and swap in every dataset in `names` (all datasets with a newer
+/// mirrored version when `None`). Returns the datasets swapped in, with their new versions;
+/// stops at the first failure, leaving datasets swapped before it in place.
+pub fn apply_updates(
+    root: &Path,
+    names: Option<&[String]>,
+) -> Result<Vec<(&'static str, String)>, RemoteError> {
+    let base = remote_base_url().ok_or(RemoteError::Disabled)?;
+    let manifest = fetch_manifest(&base)?;
+    let mut applied = Vec::new();
+    for update in compare_versions(root, &manifest) {
+        let wanted = match names {
+            Some(names) => names.iter().any(|n| n == update.dataset),
+            None => update.update_available,
+        };
+        let Some(entry) = manifest.datasets.get(update.dataset).filter(|_| wanted) else {
+            continue;
+        };
+        let dataset = remote_dataset(update.dataset).expect("compare_versions lists known datasets");
+        let bundle = fetch_bundle(&base, entry)?;
+        if bundle.version != entry.version {
+            return Err(RemoteError::Invalid(format!(
+                "manifest lists {} '{}' but the bundle is '{}'",
+                dataset.name, entry.version, bundle.version
+            )));
+        }
+        let staging = stage_bundle(root, dataset, &bundle)?;
+        swap_in(root, dataset, &staging)?;
+        tracing::info!(dataset = dataset.name, version = %bundle.version, "remote data swapped in");
+        applied.push((dataset.name, bundle.version));
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("kobayashi-remote-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("data/hostiles")).unwrap();
+        root
+    }
+
+    fn hostile_record(id: &str, hull: f64) -> serde_json::Value {
+        serde_json::json!({
+            "id": id, "hostile_name": "Test", "level": 10, "ship_class": "battleship",
+            "armor": 10.0, "shield_deflection": 10.0, "dodge": 10.0,
+            "hull_health": hull, "shield_health": 100.0,
+        })
+    }
+
+    fn bundle(version: &str, hull: f64) -> DatasetBundle {
+        let files = BTreeMap::from([
+            (
+                "index.json".to_string(),
+                serde_json::json!({
+                    "data_version": version,
+                    "hostiles": [{
+                        "id": "h1", "hostile_name": "Test", "level": 10,
+                        "ship_class": "battleship",
+                    }],
+                }),
+            ),
+            ("h1.json".to_string(), hostile_record("h1", hull)),
+        ]);
+        DatasetBundle { version: version.to_string(), files }
+    }
+
+    #[test]
+    fn staged_bundle_swaps_in_and_rolls_back() {
+        let root = temp_root("swap");
+        let hostiles = remote_dataset("hostiles").unwrap();
+        let old = bundle("v1", 500.0);
+        let staging = stage_bundle(&root, hostiles, &old).expect("v1 stages");
+        swap_in(&root, hostiles, &staging).unwrap();
+        assert_eq!(current_version(&root, hostiles).as_deref(), Some("v1"));
+
+        let staging = stage_bundle(&root, hostiles, &bundle("v2", 900.0)).expect("v2 stages");
+        swap_in(&root, hostiles, &staging).unwrap();
+        assert_eq!(current_version(&root, hostiles).as_deref(), Some("v2"));
+        assert!(!staging.exists());
+
+        roll_back(&root, hostiles).unwrap();
+        assert_eq!(current_version(&root, hostiles).as_deref(), Some("v1"));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn invalid_or_unsafe_bundles_never_reach_the_live_directory() {
+        let root = temp_root("invalid");
+        let hostiles = remote_dataset("hostiles").unwrap();
+        let bad_hull = stage_bundle(&root, hostiles, &bundle("v2", 0.0));
+        assert!(matches!(bad_hull, Err(RemoteError::Invalid(_))), "{bad_hull:?}");
+
+        let mut escaping = bundle("v2", 900.0);
+        escaping
+            .files
+            .insert("../officers.json".to_string(), serde_json::json!({}));
+        assert!(matches!(
+            stage_bundle(&root, hostiles, &escaping),
+            Err(RemoteError::Invalid(_))
+        ));
+
+        let mut mislabeled = bundle("v2", 900.0);
+        mislabeled.version = "v3".to_string();
+        assert!(stage_bundle(&root, hostiles, &mislabeled).is_err());
+        assert!(!root.join("data/hostiles.staging").exists());
+        assert_eq!(current_version(&root, hostiles), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn compare_flags_newer_mirrored_versions() {
+        let root = temp_root("compare");
+        fs::write(
+            root.join("data/hostiles/index.json"),
+            r#"{"data_version":"v1","hostiles":[]}"#,
+        )
+        .unwrap();
+        let manifest: RemoteManifest = serde_json::from_str(
+            r#"{"datasets":{"hostiles":{"version":"v2","bundle":"hostiles.json"}}}"#,
+        )
+        .unwrap();
+        let updates = compare_versions(&root, &manifest);
+        let hostiles = updates.iter().find(|u| u.dataset == "hostiles").unwrap();
+        assert!(hostiles.update_available);
+        assert_eq!(hostiles.current_version.as_deref(), Some("v1"));
+        let officers = updates.iter().find(|u| u.dataset == "officers").unwrap();
+        assert!(!officers.update_available);
+        assert_eq!(
+            resolve_url("https://m.example/data", "hostiles.json"),
+            "https://m.example/data/hostiles.json"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,13 +1,13 @@
 //! Read-only summary of synced research levels and effective ship-combat bonuses (profile + research catalog).
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::Serialize;
 
 use crate::data::import;
 use crate::data::profile::{
     accumulate_combat_only_bonuses_from_raw, load_profile, merge_research_bonuses_into_profile,
-    PlayerProfile,
+    PlayerProfile, TargetContext,
 };
 use crate::data::profile_index::{profile_path, PROFILE_JSON, RESEARCH_IMPORTED};
 use crate::data::research::{cumulative_research_level_bonuses, ResearchCatalog};
@@ -42,6 +42,9 @@ pub struct ResearchCombatSummary {
     /// Aggregated combat stat bonuses from all synced research (engine keys).
     #[serde(default, skip_serializing_if = "combat_bonuses_empty")]
     pub combat_bonuses_from_research: HashMap<String, f64>,
+    /// Research bonuses limited to one kind of defender (`*_vs_hostile` etc. catalog stats).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub target_combat_bonuses_from_research: BTreeMap<TargetContext, HashMap<String, f64>>,
     pub research: Vec<ResearchSummaryRow>,
 }
 
@@ -124,6 +127,9 @@ pub fn research_combat_summary_for_profile(
         bonuses: HashMap::new(),
         forbidden_tech_override: None,
         chaos_tech_override: None,
+        officer_traits: false,
+        officer_stats: false,
+        target_bonuses: Default::default(),
     };
     if let Some(cat) = catalog_nonempty {
         merge_research_bonuses_into_profile(&mut scratch, &imported, cat);
@@ -139,6 +145,7 @@ pub fn research_combat_summary_for_profile(
         synced_research_count: imported.len(),
         unmapped_rids,
         combat_bonuses_from_research: scratch.bonuses,
+        target_combat_bonuses_from_research: scratch.target_bonuses,
         research: rows,
     }
 }
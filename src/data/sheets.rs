@@ -0,0 +1,137 @@
+//! Opt-in roster import from a Google Sheets CSV, for alliances that keep rosters in a shared
+//! sheet. Off unless `KOBAYASHI_SHEETS_IMPORT=1`, since it makes the server fetch a URL on a
+//! client's behalf.
+//!
+//! Only `https://docs.google.com/spreadsheets/...` URLs are accepted. A published link
+//! (`/spreadsheets/d/e/<id>/pub`) is fetched with `output=csv`; a plain sheet link
+//! (`/spreadsheets/d/<id>/edit`) becomes its `export?format=csv` URL, which works for sheets
+//! shared with "anyone with the link". A `gid` (query or `#gid=` fragment) selects the tab.
+
+use std::fmt;
+use std::io::Read;
+use std::time::Duration;
+
+pub const SHEETS_IMPORT_ENV: &str = "KOBAYASHI_SHEETS_IMPORT";
+/// Largest CSV accepted, in bytes.
+pub const MAX_SHEET_BYTES: u64 = 4 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const SHEETS_PREFIX: &str = "https://docs.google.com/spreadsheets/d/";
+
+#[derive(Debug)]
+pub enum SheetsError {
+    Disabled,
+    InvalidUrl(String),
+    Http(String),
+    /// The sheet answered with something other than CSV (usually a sign-in page).
+    NotCsv,
+}
+
+impl fmt::Display for SheetsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "sheet import disabled; set {SHEETS_IMPORT_ENV}=1"),
+            Self::InvalidUrl(e) => write!(f, "invalid sheet URL: {e}"),
+            Self::Http(e) => write!(f, "sheet fetch failed: {e}"),
+            Self::NotCsv => write!(
+                f,
+                "sheet did not return CSV; publish it to the web or share it by link"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SheetsError {}
+
+pub fn is_enabled() -> bool {
+    std::env::var(SHEETS_IMPORT_ENV)
+        .map(|v| matches!(v.trim(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// CSV download URL for a Google Sheets link (see the module docs).
+pub fn csv_export_url(url: &str) -> Result<String, SheetsError> {
+    let invalid = |why: &str| SheetsError::InvalidUrl(why.to_string());
+    let rest = url
+        .trim()
+        .strip_prefix(SHEETS_PREFIX)
+        .ok_or_else(|| invalid("expected https://docs.google.com/spreadsheets/d/..."))?;
+    let (rest, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let gid = query
+        .split('&')
+        .chain(fragment.split('&'))
+        .find_map(|pair| pair.strip_prefix("gid="))
+        .filter(|gid| !gid.is_empty() && gid.bytes().all(|b| b.is_ascii_digit()));
+    let is_id = |s: &str| {
+        !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_'))
+    };
+
+    let mut segments = path.split('/');
+    let export = match (segments.next(), segments.next()) {
+        (Some("e"), Some(id)) if is_id(id) => format!("{SHEETS_PREFIX}e/{id}/pub?output=csv"),
+        (Some(id), _) if is_id(id) && id != "e" => {
+            format!("{SHEETS_PREFIX}{id}/export?format=csv")
+        }
+        _ => return Err(invalid("missing spreadsheet id")),
+    };
+    Ok(match gid {
+        Some(gid) => format!("{export}&gid={gid}"),
+        None => export,
+    })
+}
+
+/// Fetches the sheet at `url` (any link accepted by [csv_export_url]) as CSV text. Blocking.
+pub fn fetch_csv(url: &str) -> Result<String, SheetsError> {
+    if !is_enabled() {
+        return Err(SheetsError::Disabled);
+    }
+    let export = csv_export_url(url)?;
+    let response = ureq::get(&export)
+        .timeout(FETCH_TIMEOUT)
+        .call()
+        .map_err(|e| SheetsError::Http(e.to_string()))?;
+    if response.content_type().contains("html") {
+        return Err(SheetsError::NotCsv);
+    }
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_SHEET_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| SheetsError::Http(e.to_string()))?;
+    if body.len() as u64 > MAX_SHEET_BYTES {
+        return Err(SheetsError::Http(format!("sheet is larger than {MAX_SHEET_BYTES} bytes")));
+    }
+    String::from_utf8(body).map_err(|_| SheetsError::NotCsv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_links_map_to_csv_exports() {
+        assert_eq!(
+            csv_export_url("https://docs.google.com/spreadsheets/d/e/2PACX-1vAbc/pubhtml").unwrap(),
+            "https://docs.google.com/spreadsheets/d/e/2PACX-1vAbc/pub?output=csv"
+        );
+        assert_eq!(
+            csv_export_url("https://docs.google.com/spreadsheets/d/1AbC_x/edit#gid=42").unwrap(),
+            "https://docs.google.com/spreadsheets/d/1AbC_x/export?format=csv&gid=42"
+        );
+        assert_eq!(
+            csv_export_url("https://docs.google.com/spreadsheets/d/e/X/pub?gid=7&output=csv")
+                .unwrap(),
+            "https://docs.google.com/spreadsheets/d/e/X/pub?output=csv&gid=7"
+        );
+        for bad in [
+            "http://docs.google.com/spreadsheets/d/1AbC/edit",
+            "https://docs.google.com.evil.test/spreadsheets/d/1AbC",
+            "https://example.com/roster.csv",
+            "https://docs.google.com/spreadsheets/d/e/",
+            "https://docs.google.com/spreadsheets/d/../edit",
+        ] {
+            assert!(csv_export_url(bad).is_err(), "{bad}");
+        }
+    }
+}
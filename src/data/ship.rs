@@ -1,9 +1,11 @@
 //! Ship data: normalized combat stats for player ships (from STFCcommunity or manual).
 //! Used to build AttackerStats + Combatant when resolving by name/id.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::combat::{AttackerStats, ShipType, WeaponStats};
@@ -28,6 +30,21 @@ pub struct ShipAbility {
     pub effect_type: String,
     /// Effect magnitude (e.g. 0.1 for +10% pierce). Interpretation depends on effect_type.
     pub value: f64,
+    /// Hostile tags this ability applies against (see [crate::data::hostile::HostileRecord::target_tags]),
+    /// e.g. `["interceptor"]` or `["faction:2064723306"]`. Empty = every opponent.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub against: Vec<String>,
+}
+
+impl ShipAbility {
+    /// True when `against` is empty or shares a tag (case-insensitive) with `target_tags`.
+    pub fn applies_against(&self, target_tags: &[String]) -> bool {
+        self.against.is_empty()
+            || self
+                .against
+                .iter()
+                .any(|want| target_tags.iter().any(|tag| tag.eq_ignore_ascii_case(want.trim())))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -69,6 +86,88 @@ pub struct ShipRecord {
     /// Ship hull abilities (e.g. when hit, increase armor piercing). Evaluated per round in the combat engine.
     #[serde(default)]
     pub abilities: Option<Vec<ShipAbility>>,
+    /// Innate ship ability (e.g. Saladin or Jellyfish bonuses), usually limited to some hostiles via
+    /// [ShipAbility::against]. Resolved alongside `abilities`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_ability: Option<ShipAbility>,
+    /// Bridge seats besides the captain. None = the usual 2 (see
+    /// [crate::optimizer::crew_generator::CrewSlots]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bridge_slots: Option<u32>,
+    /// Below-decks seats at this tier. None = the usual 3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below_decks_slots: Option<u32>,
+    /// Armor plating, shield absorption and impulse dodge ratings at this tier. Only read by
+    /// [crate::data::power]; combat mitigation is derived separately. None in older ship data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub armor: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absorption: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dodge: Option<f64>,
+    /// Ship bonuses from the bridge officers' stats (see [OfficerBonus]). None in older ship data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub officer_bonus: Option<OfficerBonus>,
+    /// Full repair (destroyed to full hull) at this tier. None in older ship data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repair_cost: Option<RepairCost>,
+}
+
+/// Time and resources to repair the ship from destroyed to full hull at one tier: the sum of its
+/// components' upstream `repair_time` and `repair_cost`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RepairCost {
+    pub seconds: f64,
+    /// Amount per resource, keyed by lowercase name (e.g. "tritanium", "dilithium").
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resources: BTreeMap<String, f64>,
+}
+
+impl RepairCost {
+    /// Cost of repairing `hull_lost` (0–1) of the full hull, rounded to whole seconds and units.
+    /// Assumption: repair time and resources scale linearly with the hull missing.
+    pub fn scaled(&self, hull_lost: f64) -> Self {
+        let share = hull_lost.clamp(0.0, 1.0);
+        Self {
+            seconds: (self.seconds * share).round(),
+            resources: self
+                .resources
+                .iter()
+                .map(|(name, amount)| (name.clone(), (amount * share).round()))
+                .collect(),
+        }
+    }
+}
+
+/// One step of an [OfficerBonus] table: `bonus` (a fraction) once the officers' stat reaches
+/// `value`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OfficerBonusStep {
+    pub value: f64,
+    pub bonus: f64,
+}
+
+/// Ship bonuses unlocked by the summed attack, defense and health stats of the captain and
+/// bridge officers (data.stfc.space `officer_bonus`); each table is sorted by `value`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OfficerBonus {
+    #[serde(default)]
+    pub attack: Vec<OfficerBonusStep>,
+    #[serde(default)]
+    pub defense: Vec<OfficerBonusStep>,
+    #[serde(default)]
+    pub health: Vec<OfficerBonusStep>,
+}
+
+impl OfficerBonus {
+    /// Bonus of the highest step in `steps` that `stat` reaches; 0 below the first step.
+    pub fn bonus_at(steps: &[OfficerBonusStep], stat: f64) -> f64 {
+        steps
+            .iter()
+            .take_while(|step| step.value <= stat)
+            .last()
+            .map_or(0.0, |step| step.bonus)
+    }
 }
 
 /// Per-tier combat stats (from data-stfc.space or extended normalizer). Used to resolve ShipRecord for a given tier/level.
@@ -87,6 +186,19 @@ pub struct TierStats {
     pub shield_mitigation: Option<f64>,
     #[serde(default)]
     pub weapons: Option<Vec<WeaponRecord>>,
+    /// Below-decks seats unlocked at this tier; None = the usual 3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below_decks_slots: Option<u32>,
+    /// Armor plating, shield absorption and impulse dodge ratings (see [ShipRecord::armor]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub armor: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub absorption: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dodge: Option<f64>,
+    /// Full repair at this tier (see [ShipRecord::repair_cost]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repair_cost: Option<RepairCost>,
 }
 
 /// Per-level bonus to shield and hull (additive to tier base). Level 1 is typically 0,0.
@@ -106,8 +218,17 @@ pub struct ExtendedShipRecord {
     pub tiers: Vec<TierStats>,
     pub levels: Vec<LevelBonus>,
     /// Ship hull abilities from data.stfc.space ability array. Applied to all tiers.
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub abilities: Option<Vec<ShipAbility>>,
+    /// Innate ship ability; copied to every resolved tier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship_ability: Option<ShipAbility>,
+    /// Bridge seats besides the captain, the same at every tier; None = the usual 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bridge_slots: Option<u32>,
+    /// Officer stat bonuses, the same at every tier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub officer_bonus: Option<OfficerBonus>,
 }
 
 impl ExtendedShipRecord {
@@ -146,6 +267,14 @@ impl ExtendedShipRecord {
             isolytic_damage: 0.0,
             weapons: t.weapons.clone(),
             abilities: self.abilities.clone(),
+            ship_ability: self.ship_ability.clone(),
+            bridge_slots: self.bridge_slots,
+            below_decks_slots: t.below_decks_slots,
+            armor: t.armor,
+            absorption: t.absorption,
+            dodge: t.dodge,
+            officer_bonus: self.officer_bonus.clone(),
+            repair_cost: t.repair_cost.clone(),
         })
     }
 }
@@ -311,4 +440,62 @@ mod tests {
         assert_eq!(abilities.len(), 1);
         assert_eq!(abilities[0].effect_type, "pierce_bonus");
     }
+
+    #[test]
+    fn crew_slots_resolve_per_tier() {
+        let json = r#"{
+            "id": "fixture_ship_slots",
+            "ship_name": "Fixture",
+            "ship_class": "explorer",
+            "bridge_slots": 2,
+            "tiers": [
+                {"tier": 1, "armor_piercing": 1.0, "shield_piercing": 1.0, "accuracy": 1.0,
+                 "attack": 100.0, "crit_chance": 0.0, "crit_damage": 1.0, "hull_health": 1000.0,
+                 "shield_health": 0.0, "below_decks_slots": 1},
+                {"tier": 2, "armor_piercing": 1.0, "shield_piercing": 1.0, "accuracy": 1.0,
+                 "attack": 100.0, "crit_chance": 0.0, "crit_damage": 1.0, "hull_health": 1000.0,
+                 "shield_health": 0.0}
+            ],
+            "levels": [{ "level": 1, "shield": 0.0, "health": 0.0 }]
+        }"#;
+        let extended: ExtendedShipRecord = serde_json::from_str(json).expect("parse extended ship");
+        let tier1 = extended.to_ship_record(Some(1), None).expect("tier 1");
+        assert_eq!((tier1.bridge_slots, tier1.below_decks_slots), (Some(2), Some(1)));
+        let tier2 = extended.to_ship_record(Some(2), None).expect("tier 2");
+        assert_eq!(tier2.below_decks_slots, None);
+    }
+
+    #[test]
+    fn ship_ability_against_matches_any_listed_tag() {
+        let ability = ShipAbility {
+            id: "jellyfish_vs_interceptors".into(),
+            timing: "combat_begin".into(),
+            effect_type: "attack_multiplier".into(),
+            value: 0.2,
+            against: vec!["Interceptor".into(), "outpost".into()],
+        };
+        let tags = |t: &[&str]| t.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(ability.applies_against(&tags(&["interceptor", "faction:7"])));
+        assert!(!ability.applies_against(&tags(&["battleship"])));
+        assert!(!ability.applies_against(&[]));
+
+        let unconditional = ShipAbility {
+            against: Vec::new(),
+            ..ability
+        };
+        assert!(unconditional.applies_against(&[]));
+    }
+
+    #[test]
+    fn repair_cost_scales_with_hull_lost() {
+        let full = RepairCost {
+            seconds: 600.0,
+            resources: BTreeMap::from([("parsteel".to_string(), 1000.0)]),
+        };
+        let half = full.scaled(0.5);
+        assert_eq!(half.seconds, 300.0);
+        assert_eq!(half.resources["parsteel"], 500.0);
+        assert_eq!(full.scaled(2.0), full);
+        assert_eq!(full.scaled(-1.0).seconds, 0.0);
+    }
 }
@@ -7,12 +7,16 @@
 //! Unknown `timing` or `effect_type` → skipped (same as legacy behavior). Complex LCARS effects
 //! that need extra parameters (decay, accumulate, shots duration) are not representable with the
 //! single scalar `ShipAbility::value` and are omitted here until the schema grows.
+//!
+//! Abilities with [ShipAbility::against] only apply when the opponent carries one of those tags
+//! ([crate::data::hostile::HostileRecord::target_tags]); the ability id becomes the trace's `ship_ability_id`.
 
 use crate::combat::abilities::{
     Ability, AbilityClass, AbilityEffect, CrewSeat, CrewSeatContext, TimingWindow,
     NO_EXPLICIT_CONTRIBUTION_BATCH,
 };
-use crate::data::ship::ShipAbility;
+use crate::combat::dot::DotChannel;
+use crate::data::ship::{ShipAbility, ShipRecord};
 
 fn normalize_key(s: &str) -> String {
     s.trim().to_lowercase().replace('-', "_")
@@ -105,6 +109,16 @@ pub fn ship_ability_effect_from_catalog(
             duration_rounds: 1,
         }),
 
+        "plasma" | "isolytic_cascade_dot" => {
+            let channel = DotChannel::parse(effect_type)?;
+            Some(AbilityEffect::DamageOverTime {
+                channel,
+                chance: normalize_probability(value),
+                hull_pct: channel.default_hull_pct(),
+                duration_rounds: 1,
+            })
+        }
+
         "shots" | "weapon_shots" | "shots_per_weapon" | "shots_per_attack" | "shots_bonus" => {
             if matches!(timing, TimingWindow::RoundStart | TimingWindow::CombatBegin) {
                 Some(AbilityEffect::ShotsBonus {
@@ -134,6 +148,8 @@ pub fn ship_ability_to_crew_seat_context(ability: &ShipAbility) -> Option<CrewSe
             boostable: false,
             effect,
             condition: None,
+            max_activations: None,
+            cooldown_rounds: 0,
         },
         boosted: false,
         officer_id: None,
@@ -149,6 +165,20 @@ pub fn ship_abilities_to_crew_seat_contexts(abilities: &[ShipAbility]) -> Vec<Cr
         .collect()
 }
 
+/// A ship's innate [ShipRecord::ship_ability] and hull abilities that apply against an opponent
+/// with `target_tags` (empty for unresolved opponents, which keeps only unconditional abilities).
+pub fn ship_record_to_crew_seat_contexts(
+    rec: &ShipRecord,
+    target_tags: &[String],
+) -> Vec<CrewSeatContext> {
+    rec.ship_ability
+        .iter()
+        .chain(rec.abilities.iter().flatten())
+        .filter(|ability| ability.applies_against(target_tags))
+        .filter_map(ship_ability_to_crew_seat_context)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,4 +235,28 @@ mod tests {
             "each fixture row should resolve; missing mappings?"
         );
     }
+
+    #[test]
+    fn innate_ship_ability_applies_only_against_tagged_hostiles() {
+        let json = r#"{
+            "id": "saladin", "ship_name": "Saladin", "ship_class": "explorer",
+            "armor_piercing": 1.0, "shield_piercing": 1.0, "accuracy": 1.0, "attack": 10.0,
+            "crit_chance": 0.0, "crit_damage": 1.0, "hull_health": 100.0, "shield_health": 0.0,
+            "abilities": [{"id": "7", "timing": "round_start", "effect_type": "pierce_bonus", "value": 0.05}],
+            "ship_ability": {
+                "id": "saladin_vs_outposts", "timing": "combat_begin",
+                "effect_type": "attack_multiplier", "value": 0.3, "against": ["outpost"]
+            }
+        }"#;
+        let rec: ShipRecord = serde_json::from_str(json).expect("ship JSON");
+        let names = |tags: &[&str]| -> Vec<String> {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            ship_record_to_crew_seat_contexts(&rec, &tags)
+                .into_iter()
+                .map(|ctx| ctx.ability.name)
+                .collect()
+        };
+        assert_eq!(names(&["battleship", "outpost"]), ["saladin_vs_outposts", "7"]);
+        assert_eq!(names(&["battleship"]), ["7"]);
+    }
 }
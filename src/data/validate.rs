@@ -5,12 +5,15 @@ use std::path::Path;
 
 use serde_json::{Map, Value};
 
+use crate::combat::mechanics::{mechanic_for_key, mechanic_support_for_key, MechanicSupport};
+use crate::combat::monotonicity::{verify_monotonicity, MonotonicityScenario};
 use crate::data::hostile::{HostileIndex, HostileRecord, DEFAULT_HOSTILES_INDEX_PATH};
-use crate::data::officer::DEFAULT_CANONICAL_OFFICERS_PATH;
+use crate::data::officer::{load_canonical_officers, DEFAULT_CANONICAL_OFFICERS_PATH};
 use crate::data::ship::{
     ExtendedShipIndex, ExtendedShipRecord, ShipIndex, ShipRecord, DEFAULT_SHIPS_EXTENDED_DIR,
 };
 use crate::lcars;
+use crate::optimizer::monte_carlo::officer_captain_rows;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ValidationSeverity {
@@ -68,13 +71,6 @@ impl ValidationReport {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum MechanicSupport {
-    Implemented,
-    Partial,
-    Planned,
-}
-
 const TRIGGER_ENUM: &[&str] = &[
     "BattleWon",
     "CombatStart",
@@ -494,64 +490,8 @@ fn normalize_key(raw: &str) -> String {
     normalized
 }
 
-fn mechanic_support_for_key(key: &str) -> Option<MechanicSupport> {
-    if matches!(
-        key,
-        "shield_mitigation"
-            | "damage_reduction"
-            | "shield_pierce"
-            | "armor_pierce"
-            | "armor"
-            | "ship_armor"
-            | "crit_chance"
-            | "crit_damage"
-            | "on_critical"
-            | "extra_attack"
-            | "shots_per_attack"
-            | "shots_per_weapon"
-            | "weapon_shots"
-            | "ship_dodge"
-            | "accuracy"
-            | "all_defenses"
-            | "all_piercing"
-            | "isolytic_damage"
-            | "isolytic_defense"
-            | "isolytic_cascade"
-            | "isolytic_cascade_damage"
-    ) {
-        return Some(MechanicSupport::Implemented);
-    }
-
-    if key.contains("burn")
-        || key.contains("ignite")
-        || matches!(
-            key,
-            "shield_regen" | "hull_repair" | "hull_hp_repair" | "shield_hp_repair"
-        )
-    {
-        return Some(MechanicSupport::Partial);
-    }
-
-    if matches!(
-        key,
-        "mining_rate" | "repair_speed" | "warp_speed" | "cargo_capacity"
-    ) || key.contains("loot")
-    {
-        return Some(MechanicSupport::Planned);
-    }
-
-    if matches!(key, "apex_shred" | "apex_barrier") {
-        return Some(MechanicSupport::Implemented);
-    }
-
-    None
-}
-
 fn is_non_combat_key(key: &str) -> bool {
-    matches!(
-        key,
-        "mining_rate" | "repair_speed" | "warp_speed" | "cargo_capacity"
-    ) || key.contains("loot")
+    mechanic_for_key(key).is_some_and(|mechanic| !mechanic.combat)
 }
 
 fn normalize_building_condition(raw: &str) -> String {
@@ -831,9 +771,9 @@ pub fn validate_hostiles_dataset(path: &str) -> Result<ValidationReport, String>
     Ok(report)
 }
 
-/// Run all startup data validations and print per-category results to stdout.
+/// Run all startup data validations and log per-category results.
 ///
-/// Returns `Ok(())` when there are no errors (warnings are printed but allowed).
+/// Returns `Ok(())` when there are no errors (warnings are logged but allowed).
 /// Returns `Err(message)` when any category has errors; the caller should treat
 /// this as a fatal startup failure.
 pub fn validate_all_startup_data() -> Result<(), String> {
@@ -848,18 +788,18 @@ pub fn validate_all_startup_data() -> Result<(), String> {
     ) {
         match result {
             Err(e) => {
-                println!("    [error] {e}");
+                tracing::error!(category = label, "{e}");
                 *errors += 1;
             }
             Ok(report) => {
                 for d in &report.diagnostics {
                     match d.severity {
                         ValidationSeverity::Error => {
-                            println!("    [error] {}: {}", d.context, d.message);
+                            tracing::error!(category = label, "{}: {}", d.context, d.message);
                             *errors += 1;
                         }
                         ValidationSeverity::Warning => {
-                            println!("    [warn]  {}: {}", d.context, d.message);
+                            tracing::warn!(category = label, "{}: {}", d.context, d.message);
                             *warnings += 1;
                         }
                         ValidationSeverity::Info => {}
@@ -872,12 +812,12 @@ pub fn validate_all_startup_data() -> Result<(), String> {
                         .filter(|d| d.severity == ValidationSeverity::Warning)
                         .count();
                     if w == 0 {
-                        println!("  {label}: ok");
+                        tracing::info!(category = label, "ok");
                     } else {
-                        println!("  {label}: ok ({w} warning(s))");
+                        tracing::info!(category = label, warnings = w, "ok");
                     }
                 } else {
-                    println!("  {label}: ERRORS — see above");
+                    tracing::error!(category = label, "validation failed");
                 }
             }
         }
@@ -908,6 +848,39 @@ pub fn validate_all_startup_data() -> Result<(), String> {
     }
 }
 
+/// Buff monotonicity of every canonical officer at `path`: each positive effect row the officer
+/// adds as captain is fought in `scenario` ([verify_monotonicity]); a drop in damage or win rate
+/// is an error. Rows with a non-positive effect are listed as info and not checked.
+pub fn validate_officer_mechanics(
+    path: &str,
+    scenario: &MonotonicityScenario,
+) -> Result<ValidationReport, String> {
+    let officers = load_canonical_officers(path)
+        .map_err(|err| format!("failed to load officers from {path}: {err}"))?;
+    let mut report = ValidationReport::default();
+    let mut checked = 0usize;
+    for officer in &officers {
+        for row in officer_captain_rows(officer) {
+            let context = format!("{} ({})", officer.id, row.ability.name);
+            match verify_monotonicity(&row, scenario) {
+                Ok(check) => {
+                    checked += 1;
+                    for regression in &check.regressions {
+                        report.push(ValidationSeverity::Error, &context, regression);
+                    }
+                }
+                Err(err) => report.push(ValidationSeverity::Info, &context, err),
+            }
+        }
+    }
+    report.push(
+        ValidationSeverity::Info,
+        "mechanics",
+        format!("{checked} officer ability rows checked for buff monotonicity"),
+    );
+    Ok(report)
+}
+
 /// Validate building index + per-building files for basic structure and provenance.
 /// `path` should be the directory containing `index.json` (typically `data/buildings`).
 pub fn validate_buildings_dataset(path: &str) -> Result<ValidationReport, String> {
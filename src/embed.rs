@@ -0,0 +1,427 @@
+//! JSON entry points for running the engine inside other programs without the HTTP server or the
+//! `data/` directory: C callers (`ffi` feature), browsers (`wasm` feature) or any Rust host.
+//!
+//! Inputs carry every record a fight needs (combatants or ship/hostile records, LCARS officers,
+//! player profile), so nothing is read from disk. Every response is a JSON object with
+//! `"status": "ok"` or `"status": "error"` plus a `message`.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{simulate_combat, Combatant, CrewConfiguration, SimulationConfig};
+use crate::data::hostile::HostileRecord;
+use crate::data::profile::PlayerProfile;
+use crate::data::ship::ShipRecord;
+use crate::lcars::LcarsOfficer;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::scenario::{attacker_with_crew, LcarsOfficerData};
+use crate::optimizer::monte_carlo::{
+    normalize_lookup_key, run_monte_carlo_in_memory, split_name_tags, tag_name,
+};
+use crate::optimizer::ranking::{rank_results, RankedCrewResult};
+
+/// Iterations per crew for [optimize_json] when `sims` is unset.
+pub const DEFAULT_EMBED_SIMS: u32 = 1000;
+/// Upper bound for `sims`; same as the HTTP API.
+pub const MAX_EMBED_SIMS: u32 = 100_000;
+
+/// Officer names by seat; names (optionally `Name (T4)`) or ids of the supplied LCARS officers.
+/// `null` (or a missing trailing entry) is an empty seat.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EmbedCrew {
+    pub captain: String,
+    #[serde(default)]
+    pub bridge: Vec<Option<String>>,
+    #[serde(default)]
+    pub below_decks: Vec<Option<String>>,
+}
+
+/// Input for [simulate_json]: one fight between caller-built combatants.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulateInput {
+    /// Checked with [Combatant::validate], like [Combatant::builder] output.
+    pub attacker: Combatant,
+    pub defender: Combatant,
+    /// Round limit. None = [SimulationConfig::default].
+    pub rounds: Option<u32>,
+    pub seed: Option<u64>,
+    /// Include the event trace in the result.
+    #[serde(default)]
+    pub trace: bool,
+    /// LCARS definitions for the crew's officers (same fields as `*.lcars.yaml`).
+    #[serde(default)]
+    pub officers: Vec<LcarsOfficer>,
+    /// Crew seated on the attacker. None = no officers.
+    pub crew: Option<EmbedCrew>,
+}
+
+/// Input for [optimize_json]: rank the given crews on a ship against a hostile.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OptimizeInput {
+    pub ship: ShipRecord,
+    pub hostile: HostileRecord,
+    #[serde(default)]
+    pub officers: Vec<LcarsOfficer>,
+    pub candidates: Vec<EmbedCrew>,
+    /// Player bonuses applied to the ship. None = no bonuses.
+    #[serde(default)]
+    pub profile: PlayerProfile,
+    pub sims: Option<u32>,
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct OkResponse<T> {
+    status: &'static str,
+    #[serde(flatten)]
+    body: T,
+}
+
+#[derive(Debug, Serialize)]
+struct SimulateBody {
+    result: crate::combat::SimulationResult,
+}
+
+#[derive(Debug, Serialize)]
+struct OptimizeBody {
+    sims: u32,
+    seed: u64,
+    results: Vec<RankedCrewResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse<'a> {
+    status: &'static str,
+    message: &'a str,
+}
+
+/// Run one fight described by a [SimulateInput] JSON document.
+pub fn simulate_json(input: &str) -> Result<String, EmbedError> {
+    let input: SimulateInput = serde_json::from_str(input).map_err(EmbedError::Parse)?;
+    for (side, combatant) in [("attacker", &input.attacker), ("defender", &input.defender)] {
+        combatant
+            .validate()
+            .map_err(|e| EmbedError::Validation(format!("{side}: {e}")))?;
+    }
+    let defaults = SimulationConfig::default();
+    let config = SimulationConfig::builder()
+        .rounds(input.rounds.unwrap_or(defaults.rounds))
+        .seed(input.seed.unwrap_or(defaults.seed))
+        .trace(input.trace)
+        .build();
+
+    let (attacker, crew) = match &input.crew {
+        Some(crew) => {
+            let lcars = LcarsOfficerData::from_officers(input.officers);
+            let candidate = resolve_crew(crew, &lcars)?;
+            attacker_with_crew(input.attacker, &candidate, &lcars)
+        }
+        None => (input.attacker, CrewConfiguration::default()),
+    };
+    let result = simulate_combat(&attacker, &input.defender, config, &crew);
+    to_ok_json(SimulateBody { result })
+}
+
+/// Simulate and rank every crew of an [OptimizeInput] JSON document, best first.
+pub fn optimize_json(input: &str) -> Result<String, EmbedError> {
+    let input: OptimizeInput = serde_json::from_str(input).map_err(EmbedError::Parse)?;
+    if input.candidates.is_empty() {
+        return Err(EmbedError::Validation(
+            "candidates must list at least one crew".to_string(),
+        ));
+    }
+    let sims = input.sims.unwrap_or(DEFAULT_EMBED_SIMS);
+    if sims == 0 || sims > MAX_EMBED_SIMS {
+        return Err(EmbedError::Validation(format!(
+            "sims must be between 1 and {MAX_EMBED_SIMS}"
+        )));
+    }
+    let seed = input.seed.unwrap_or(0);
+
+    let lcars = LcarsOfficerData::from_officers(input.officers.clone());
+    let candidates = input
+        .candidates
+        .iter()
+        .map(|crew| resolve_crew(crew, &lcars))
+        .collect::<Result<Vec<_>, _>>()?;
+    let results = run_monte_carlo_in_memory(
+        &input.ship,
+        &input.hostile,
+        input.officers,
+        input.profile,
+        &candidates,
+        sims as usize,
+        seed,
+    );
+    to_ok_json(OptimizeBody {
+        sims,
+        seed,
+        results: rank_results(results),
+    })
+}
+
+/// Check every seat against the supplied officers; ids are mapped to names so tier and level tags
+/// still parse.
+fn resolve_crew(crew: &EmbedCrew, lcars: &LcarsOfficerData) -> Result<CrewCandidate, EmbedError> {
+    let resolve = |seat: &String| -> Result<String, EmbedError> {
+        let (name, tier, level) = split_name_tags(seat);
+        let officer = lcars.by_id.get(name.trim()).or_else(|| {
+            lcars
+                .name_to_id
+                .get(&normalize_lookup_key(&name))
+                .and_then(|id| lcars.by_id.get(id))
+        });
+        match officer {
+            Some(o) => Ok(tag_name(&o.name, tier, level)),
+            None => Err(EmbedError::Validation(format!(
+                "officer '{seat}' is not in officers"
+            ))),
+        }
+    };
+    let seats = |seats: &[Option<String>]| -> Result<Vec<Option<String>>, EmbedError> {
+        seats
+            .iter()
+            .map(|seat| seat.as_ref().map(resolve).transpose())
+            .collect()
+    };
+    Ok(CrewCandidate {
+        captain: resolve(&crew.captain)?,
+        bridge: seats(&crew.bridge)?,
+        below_decks: seats(&crew.below_decks)?,
+    })
+}
+
+fn to_ok_json<T: Serialize>(body: T) -> Result<String, EmbedError> {
+    serde_json::to_string(&OkResponse { status: "ok", body }).map_err(EmbedError::Parse)
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::to_string(&ErrorResponse {
+        status: "error",
+        message,
+    })
+    .unwrap_or_else(|_| r#"{"status":"error","message":"failed to serialize error"}"#.to_string())
+}
+
+/// Collapse a result into the response document handed across the FFI/WASM boundary. Panics are
+/// caught so they never unwind into the host.
+pub fn respond(
+    run: impl FnOnce() -> Result<String, EmbedError> + std::panic::UnwindSafe,
+) -> String {
+    match std::panic::catch_unwind(run) {
+        Ok(Ok(json)) => json,
+        Ok(Err(err)) => error_json(&err.to_string()),
+        Err(_) => error_json("internal error: engine panicked"),
+    }
+}
+
+#[derive(Debug)]
+pub enum EmbedError {
+    Parse(serde_json::Error),
+    Validation(String),
+}
+
+impl fmt::Display for EmbedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid input: {e}"),
+            Self::Validation(m) => write!(f, "{m}"),
+        }
+    }
+}
+
+impl std::error::Error for EmbedError {}
+
+/// C ABI. Inputs are NUL-terminated UTF-8 JSON; every returned string must be released with
+/// [kobayashi_free_string]. See `include/kobayashi.h`.
+#[cfg(feature = "ffi")]
+mod ffi {
+    use std::ffi::{c_char, CStr, CString};
+
+    use super::{error_json, optimize_json, respond, simulate_json, EmbedError};
+
+    unsafe fn call(
+        input: *const c_char,
+        run: fn(&str) -> Result<String, EmbedError>,
+    ) -> *mut c_char {
+        let response = if input.is_null() {
+            error_json("input is null")
+        } else {
+            match CStr::from_ptr(input).to_str() {
+                Ok(json) => respond(|| run(json)),
+                Err(_) => error_json("input is not valid UTF-8"),
+            }
+        };
+        // serde_json escapes NUL inside strings, so the response never contains an interior NUL.
+        CString::new(response).map_or(std::ptr::null_mut(), CString::into_raw)
+    }
+
+    /// # Safety
+    /// `input` must be null or point to a NUL-terminated string.
+    #[no_mangle]
+    pub unsafe extern "C" fn kobayashi_simulate_json(input: *const c_char) -> *mut c_char {
+        call(input, simulate_json)
+    }
+
+    /// # Safety
+    /// `input` must be null or point to a NUL-terminated string.
+    #[no_mangle]
+    pub unsafe extern "C" fn kobayashi_optimize_json(input: *const c_char) -> *mut c_char {
+        call(input, optimize_json)
+    }
+
+    /// # Safety
+    /// `response` must be null or a pointer returned by this library that was not freed yet.
+    #[no_mangle]
+    pub unsafe extern "C" fn kobayashi_free_string(response: *mut c_char) {
+        if !response.is_null() {
+            drop(CString::from_raw(response));
+        }
+    }
+}
+
+#[cfg(feature = "ffi")]
+pub use ffi::{kobayashi_free_string, kobayashi_optimize_json, kobayashi_simulate_json};
+
+/// wasm-bindgen exports: `simulateJson(input)` and `optimizeJson(input)` return the response JSON.
+#[cfg(feature = "wasm")]
+mod wasm {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    #[wasm_bindgen(js_name = simulateJson)]
+    pub fn simulate_json(input: &str) -> String {
+        super::respond(|| super::simulate_json(input))
+    }
+
+    #[wasm_bindgen(js_name = optimizeJson)]
+    pub fn optimize_json(input: &str) -> String {
+        super::respond(|| super::optimize_json(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn officers() -> Value {
+        json!([{
+            "id": "gunner-1",
+            "name": "Gunner",
+            "captain_ability": {
+                "name": "Double Damage",
+                "effects": [{
+                    "type": "stat_modify",
+                    "stat": "weapon_damage",
+                    "operator": "multiply",
+                    "value": 2.0,
+                    "trigger": "passive",
+                    "duration": "permanent"
+                }]
+            }
+        }])
+    }
+
+    fn simulate_input(crew: Option<Value>) -> String {
+        let combatant = |id: &str, attack: f64| {
+            serde_json::to_value(Combatant::builder(id).attack(attack).build().unwrap()).unwrap()
+        };
+        json!({
+            "attacker": combatant("player", 100.0),
+            "defender": combatant("hostile", 10.0),
+            "rounds": 3,
+            "seed": 5,
+            "officers": officers(),
+            "crew": crew,
+        })
+        .to_string()
+    }
+
+    fn total_damage(response: &str) -> f64 {
+        let v: Value = serde_json::from_str(response).unwrap();
+        assert_eq!(v["status"], "ok");
+        v["result"]["total_damage"].as_f64().unwrap()
+    }
+
+    #[test]
+    fn simulate_applies_the_supplied_crew() {
+        let bare = total_damage(&simulate_json(&simulate_input(None)).unwrap());
+        let crewed = total_damage(
+            &simulate_json(&simulate_input(Some(json!({ "captain": "Gunner" })))).unwrap(),
+        );
+        assert!(
+            crewed > bare,
+            "captain buff should raise damage: {crewed} vs {bare}"
+        );
+    }
+
+    #[test]
+    fn unknown_officer_and_bad_json_become_error_documents() {
+        let response =
+            respond(|| simulate_json(&simulate_input(Some(json!({ "captain": "Nobody" })))));
+        let v: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(v["status"], "error");
+        assert!(v["message"].as_str().unwrap().contains("Nobody"));
+
+        let mut input: Value = serde_json::from_str(&simulate_input(None)).unwrap();
+        input["defender"]["crit_chance"] = json!(1.5);
+        let response = respond(|| simulate_json(&input.to_string()));
+        let v: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(v["status"], "error");
+        assert_eq!(v["message"], "defender: crit_chance must be between 0 and 1, got 1.5");
+
+        let response = respond(|| optimize_json("{"));
+        let v: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(v["status"], "error");
+    }
+
+    #[test]
+    fn optimize_ranks_candidates_from_inline_records() {
+        let input = json!({
+            "ship": {
+                "id": "test_ship", "ship_name": "Test", "ship_class": "battleship",
+                "armor_piercing": 100.0, "shield_piercing": 100.0, "accuracy": 100.0,
+                "attack": 500.0, "crit_chance": 0.1, "crit_damage": 1.5,
+                "hull_health": 5000.0, "shield_health": 1000.0
+            },
+            "hostile": {
+                "id": "test_hostile", "hostile_name": "Target", "level": 5,
+                "ship_class": "explorer", "armor": 50.0, "shield_deflection": 50.0, "dodge": 50.0,
+                "hull_health": 3000.0, "shield_health": 500.0
+            },
+            "officers": officers(),
+            "candidates": [{ "captain": "Gunner" }, { "captain": "gunner-1 (T3)" }],
+            "sims": 20,
+            "seed": 9
+        });
+        let v: Value = serde_json::from_str(&optimize_json(&input.to_string()).unwrap()).unwrap();
+        assert_eq!(v["status"], "ok");
+        let results = v["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|r| r["captain"].as_str().unwrap().starts_with("Gunner")));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn c_abi_round_trips_and_frees() {
+        use std::ffi::{CStr, CString};
+
+        let input = CString::new(simulate_input(None)).unwrap();
+        unsafe {
+            let out = kobayashi_simulate_json(input.as_ptr());
+            let text = CStr::from_ptr(out).to_str().unwrap().to_string();
+            kobayashi_free_string(out);
+            assert!(total_damage(&text) > 0.0);
+
+            let out = kobayashi_optimize_json(std::ptr::null());
+            assert!(CStr::from_ptr(out)
+                .to_str()
+                .unwrap()
+                .contains("input is null"));
+            kobayashi_free_string(out);
+        }
+    }
+}
@@ -36,6 +36,12 @@ pub struct LcarsAbility {
     pub name: String,
     #[serde(default)]
     pub effects: Vec<LcarsEffect>,
+    /// Times the ability can trigger per combat (e.g. 1 for "once per combat").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_activations: Option<u32>,
+    /// Rounds between triggers (e.g. 3 for "every 3 rounds").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cooldown_rounds: Option<u32>,
 }
 
 /// Single effect within an ability. Unknown `type` values are preserved and
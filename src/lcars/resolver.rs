@@ -4,8 +4,9 @@ use std::collections::{HashMap, HashSet};
 
 use crate::combat::{
     Ability, AbilityClass, AbilityCondition, AbilityEffect, Combatant, CrewConfiguration, CrewSeat,
-    CrewSeatContext, TimingWindow,
+    CrewSeatContext, DotChannel, TimingWindow,
 };
+use crate::data::officer::UnknownOfficerMode;
 use crate::data::profile;
 use crate::lcars::parser::{LcarsAbility, LcarsCondition, LcarsEffect, LcarsOfficer};
 
@@ -16,6 +17,15 @@ pub struct ResolveOptions {
     pub tier: Option<u8>,
     /// Per-officer tier (canonical_officer_id → tier). When set, each officer uses their tier for scaling (base + per_rank, chance_at_rank).
     pub officer_tiers: Option<HashMap<String, u8>>,
+    /// Tags of the opponent (see [crate::data::hostile::HostileRecord::target_tags]). Passive
+    /// "vs tag" stat buffs only count when they match; empty when the opponent is unknown.
+    pub target_tags: Vec<String>,
+    /// Canonical ids of boosted officers (from the roster); their ability rows are boosted.
+    pub boosted_officers: HashSet<String>,
+    /// Roster officer levels (canonical_officer_id → level); gate officer traits.
+    pub officer_levels: HashMap<String, u16>,
+    /// Seats for names that match no canonical officer.
+    pub unknown_officers: UnknownOfficerMode,
 }
 
 impl Default for ResolveOptions {
@@ -23,6 +33,10 @@ impl Default for ResolveOptions {
         Self {
             tier: None,
             officer_tiers: None,
+            target_tags: Vec::new(),
+            boosted_officers: HashSet::new(),
+            officer_levels: HashMap::new(),
+            unknown_officers: UnknownOfficerMode::default(),
         }
     }
 }
@@ -82,6 +96,17 @@ fn lcars_condition_to_ability_condition(c: &LcarsCondition) -> Option<AbilityCon
             min: c.min.unwrap_or(1),
             max: c.max.unwrap_or(100),
         },
+        "vs_tag" | "target_tag" => {
+            AbilityCondition::TargetTag(c.tag.as_deref()?.trim().to_ascii_lowercase())
+        }
+        // Matches a hand-entered faction tag ("romulan") or the derived upstream id ("faction:<id>").
+        "vs_faction" => {
+            let faction = c.faction.as_deref()?.trim().to_ascii_lowercase();
+            AbilityCondition::Or(vec![
+                AbilityCondition::TargetTag(format!("faction:{faction}")),
+                AbilityCondition::TargetTag(faction),
+            ])
+        }
         "and" => {
             let conds: Vec<AbilityCondition> = c
                 .conditions
@@ -341,6 +366,19 @@ fn resolve_effect(
                 duration_rounds,
             }))
         }
+        "plasma" | "isolytic_cascade_dot" | "damage_over_time" => {
+            let channel = DotChannel::parse(effect.effect_type.as_str())
+                .or_else(|| effect.stat.as_deref().and_then(DotChannel::parse))?;
+            let chance = effect.chance.or_else(|| effect.scaling.as_ref().map(|s| s.chance_at_rank(tier))).unwrap_or(1.0);
+            let hull_pct = effect.value.unwrap_or_else(|| channel.default_hull_pct());
+            let duration_rounds = duration_rounds_or_default(effect, 1);
+            Some((timing, AbilityEffect::DamageOverTime {
+                channel,
+                chance,
+                hull_pct,
+                duration_rounds,
+            }))
+        }
         "tag" => None, // Non-combat; skip.
         _ => None,
     }
@@ -371,8 +409,10 @@ pub fn resolve_officer_ability(
                     boostable: true,
                     effect: effect_effect,
                     condition,
+                    max_activations: ability.max_activations,
+                    cooldown_rounds: ability.cooldown_rounds.unwrap_or(0),
                 },
-                boosted: false,
+                boosted: options.boosted_officers.contains(&officer.id),
                 officer_id: Some(officer.id.clone()),
                 contribution_batch,
             });
@@ -418,6 +458,14 @@ pub fn resolve_crew_to_buff_set(
             {
                 continue;
             }
+            let off_target = effect
+                .condition
+                .as_ref()
+                .and_then(lcars_condition_to_ability_condition)
+                .is_some_and(|c| !c.matches_target(&options.target_tags));
+            if off_target {
+                continue;
+            }
             let value = effect.value.or_else(|| effect.scaling.as_ref().map(|s| s.value_at_rank(officer_tier)));
             if let (Some(stat), Some(v)) = (effect.stat.as_deref(), value) {
                 if effect.operator.as_deref() == Some("multiply") {
@@ -587,10 +635,14 @@ mod tests {
             captain_ability: Some(LcarsAbility {
                 name: "Cap".to_string(),
                 effects: vec![lcars_effect_stat_modify("isolytic_damage", 0.11, "on_round_start")],
+                max_activations: None,
+                cooldown_rounds: None,
             }),
             bridge_ability: Some(LcarsAbility {
                 name: "Bridge".to_string(),
                 effects: vec![lcars_effect_stat_modify("isolytic_cascade_damage", 0.22, "on_combat_start")],
+                max_activations: None,
+                cooldown_rounds: None,
             }),
             below_decks_ability: None,
         };
@@ -608,11 +660,44 @@ mod tests {
         assert!(classes.contains(&AbilityClass::BridgeAbility));
     }
 
+    #[test]
+    fn ability_activation_limits_reach_every_effect_row() {
+        let officer = LcarsOfficer {
+            id: "limited".to_string(),
+            name: "Limited".to_string(),
+            faction: None,
+            rarity: None,
+            group: None,
+            captain_ability: Some(LcarsAbility {
+                name: "Once".to_string(),
+                effects: vec![
+                    lcars_effect_stat_modify("isolytic_damage", 0.1, "on_round_start"),
+                    lcars_effect_stat_modify("apex_shred", 0.1, "on_round_start"),
+                ],
+                max_activations: Some(1),
+                cooldown_rounds: Some(3),
+            }),
+            bridge_ability: None,
+            below_decks_ability: None,
+        };
+        let mut officers = HashMap::new();
+        officers.insert("limited".to_string(), officer);
+        let buff = resolve_crew_to_buff_set("limited", &[], &[], &officers, &ResolveOptions::default());
+        assert_eq!(buff.crew.seats.len(), 2);
+        assert!(buff
+            .crew
+            .seats
+            .iter()
+            .all(|s| s.ability.max_activations == Some(1) && s.ability.cooldown_rounds == 3));
+    }
+
     #[test]
     fn below_decks_does_not_apply_bridge_when_no_below_block() {
         let bridge = LcarsAbility {
             name: "Bd Bridge Only (Bridge)".to_string(),
             effects: vec![lcars_effect_stat_modify("shield_pierce", 10.0, "on_hit")],
+            max_activations: None,
+            cooldown_rounds: None,
         };
         let officer = LcarsOfficer {
             id: "bd_only_bridge".to_string(),
@@ -654,6 +739,8 @@ mod tests {
         let ability_iso = LcarsAbility {
             name: "iso".to_string(),
             effects: vec![lcars_effect_stat_modify("isolytic_damage", 0.15, "on_round_start")],
+            max_activations: None,
+            cooldown_rounds: None,
         };
         let contexts = resolve_officer_ability(
             &officer,
@@ -669,6 +756,8 @@ mod tests {
         let ability_def = LcarsAbility {
             name: "def".to_string(),
             effects: vec![lcars_effect_stat_modify("isolytic_defense", 20.0, "on_round_start")],
+            max_activations: None,
+            cooldown_rounds: None,
         };
         let contexts_def = resolve_officer_ability(
             &officer,
@@ -684,6 +773,8 @@ mod tests {
         let ability_shield = LcarsAbility {
             name: "shield".to_string(),
             effects: vec![lcars_effect_stat_modify("shield_mitigation", 0.05, "on_combat_start")],
+            max_activations: None,
+            cooldown_rounds: None,
         };
         let contexts_shield = resolve_officer_ability(
             &officer,
@@ -699,6 +790,8 @@ mod tests {
         let ability_cascade = LcarsAbility {
             name: "cascade".to_string(),
             effects: vec![lcars_effect_stat_modify("isolytic_cascade_damage", 0.2, "on_round_start")],
+            max_activations: None,
+            cooldown_rounds: None,
         };
         let contexts_cascade = resolve_officer_ability(
             &officer,
@@ -803,6 +896,8 @@ mod tests {
             captain_ability: Some(LcarsAbility {
                 name: "scaling".to_string(),
                 effects: vec![scaling_effect],
+                max_activations: None,
+                cooldown_rounds: None,
             }),
             bridge_ability: None,
             below_decks_ability: None,
@@ -889,6 +984,8 @@ mod tests {
                     decay: None,
                 },
             ],
+            max_activations: None,
+            cooldown_rounds: None,
         };
 
         let contexts = resolve_officer_ability(
@@ -966,6 +1063,8 @@ mod tests {
                     decay: None,
                 },
             ],
+            max_activations: None,
+            cooldown_rounds: None,
         };
 
         let contexts = resolve_officer_ability(
@@ -990,4 +1089,58 @@ mod tests {
             } if (bonus_pct - 0.5).abs() < 1e-12
         ));
     }
+
+    #[test]
+    fn vs_tag_effects_only_count_against_matching_targets() {
+        let effects: Vec<LcarsEffect> = serde_yaml::from_str(
+            r#"
+- type: stat_modify
+  stat: weapon_damage
+  operator: add
+  value: 0.3
+  trigger: passive
+  duration: permanent
+  condition: { type: vs_tag, tag: Swarm }
+- type: stat_modify
+  stat: weapon_damage
+  operator: multiply
+  value: 1.2
+  trigger: on_round_start
+  condition: { type: vs_faction, faction: romulan }
+"#,
+        )
+        .unwrap();
+        let officer = LcarsOfficer {
+            id: "specialist".to_string(),
+            name: "Specialist".to_string(),
+            faction: None,
+            rarity: None,
+            group: None,
+            captain_ability: Some(LcarsAbility {
+                name: "Hunter".to_string(),
+                effects,
+                max_activations: None,
+                cooldown_rounds: None,
+            }),
+            bridge_ability: None,
+            below_decks_ability: None,
+        };
+        let officers: HashMap<_, _> = [("specialist".to_string(), officer)].into_iter().collect();
+        let against = |tags: &[&str]| {
+            let options = ResolveOptions {
+                target_tags: tags.iter().map(|t| t.to_string()).collect(),
+                ..Default::default()
+            };
+            resolve_crew_to_buff_set("specialist", &[], &[], &officers, &options)
+        };
+
+        assert_eq!(against(&["interceptor", "swarm"]).static_buffs.get("weapon_damage"), Some(&0.3));
+        assert!(against(&["interceptor"]).static_buffs.is_empty());
+
+        let crew = against(&[]).crew;
+        let condition = crew.seats[0].ability.condition.as_ref().unwrap();
+        assert!(condition.matches_target(&["faction:romulan".to_string()]));
+        assert!(condition.matches_target(&["Romulan".to_string()]));
+        assert!(!condition.matches_target(&["klingon".to_string()]));
+    }
 }
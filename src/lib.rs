@@ -1,8 +1,22 @@
+//! Monte Carlo combat simulator and crew optimizer for Star Trek Fleet Command.
+//!
+//! Embedders should build against [prelude], the semver-stable surface: combatants and fight
+//! configuration, [prelude::simulate_combat], and the optimizer entry points. The other modules
+//! are public for the bundled binaries and may change between releases.
+
+#[cfg(feature = "server")]
 pub mod cli;
 pub mod combat;
 pub mod data;
+pub mod embed;
 pub mod lcars;
+#[cfg(feature = "server")]
+pub mod logging;
 pub mod optimizer;
 pub mod parallel;
 pub(crate) mod perf_log;
+pub mod prelude;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "server")]
 pub mod server;
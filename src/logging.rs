@@ -0,0 +1,22 @@
+//! Log output for the `kobayashi` binary. Library code emits `tracing` events and spans; this
+//! installs the stderr subscriber that prints them.
+//!
+//! `KOBAYASHI_LOG` takes `tracing-subscriber` env-filter directives (default `info`), e.g.
+//! `KOBAYASHI_LOG=debug` for optimize progress or `KOBAYASHI_LOG=kobayashi::server::sync=warn`.
+
+use tracing_subscriber::EnvFilter;
+
+/// Environment variable holding the filter directives.
+pub const LOG_ENV: &str = "KOBAYASHI_LOG";
+/// Filter used when [LOG_ENV] is unset or invalid.
+pub const DEFAULT_LOG_FILTER: &str = "info";
+
+/// Install the global subscriber. Safe to call more than once; later calls are no-ops.
+pub fn init() {
+    let filter =
+        EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_FILTER));
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
@@ -3,14 +3,23 @@ use std::process;
 
 use kobayashi::combat::{
     default_percent_sensitivity_rows, format_sensitivity_tsv, simulate_combat, Combatant,
-    CrewConfiguration, HostileMitigationBaseline, SimulationConfig, TraceMode, MITIGATION_CEILING,
-    MITIGATION_FLOOR,
+    export_to_hostile_record, generate_golden_matrix, load_log_mapping, parity_report,
+    parse_combat_log_json, parse_combat_log_json_with_mapping, parse_fight_export, run_fuzz,
+    verify_golden_matrix, CrewConfiguration, GoldenMatrix, HostileMitigationBaseline,
+    MonotonicityScenario, ParityStatus, SimulationConfig, SimulationSnapshot, TraceFilter,
+    TraceMode, DEFAULT_FUZZ_CASES, DEFAULT_GOLDEN_TOLERANCE, DEFAULT_PARITY_TOLERANCE,
+    MITIGATION_CEILING, MITIGATION_FLOOR,
 };
+use kobayashi::data::hostile::{save_hostile_record, DEFAULT_HOSTILES_INDEX_PATH};
 use kobayashi::data::loader::{resolve_hostile, resolve_ship};
 use kobayashi::data::import::{import_roster_csv_to, import_spocks_export_to};
-use kobayashi::data::profile::{apply_profile_to_attacker, load_profile};
+use kobayashi::data::profile::{apply_profile_to_attacker, load_profile, TargetContext};
 use kobayashi::data::profile_index::{migrate_from_legacy_if_needed, profile_path, resolve_profile_id_for_api, PROFILE_JSON, ROSTER_IMPORTED};
-use kobayashi::data::validate::{validate_officer_dataset, ValidationSeverity};
+use kobayashi::data::validate::{
+    validate_officer_dataset, validate_officer_mechanics, ValidationSeverity,
+};
+use kobayashi::optimizer::genetic::GeneticOverrides;
+use kobayashi::optimizer::ranking::{diff_rankings, RankedCrewResult};
 use kobayashi::server;
 
 #[derive(Debug, Clone, Copy)]
@@ -19,18 +28,40 @@ enum Command {
     Simulate,
     Optimize,
     Import,
+    ImportFight,
     Validate,
     GenerateLcars,
     MitigationSensitivity,
+    OfficerUpgrades,
+    OfficerLeaderboard,
+    Bracket,
+    Matrix,
+    DiffRankings,
+    Snapshot,
+    Golden,
+    Fuzz,
+    Parity,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 struct OptimizeCliArgs {
     ship: String,
     hostile: String,
     sims: u32,
     /// Optional cap on the number of candidate crews to evaluate.
     max_candidates: Option<u32>,
+    /// Optimizer strategy: exhaustive (default), genetic, tiered, two_phase, or auto.
+    strategy: Option<String>,
+    /// Tiered and two-phase: sims per crew (bridge prefix) in the scouting stage.
+    scout_sims: Option<u32>,
+    /// Tiered and two-phase: crews (bridge prefixes) confirmed with full sims.
+    top_k: Option<u32>,
+    /// Exhaustive: best crews kept while results stream in.
+    max_results: Option<u32>,
+    /// Below decks only from officers with a below-decks ability (smaller search space).
+    below_decks_with_ability: bool,
+    /// Genetic: `--ga-*` overrides of the GA config.
+    ga: GeneticOverrides,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -51,9 +82,19 @@ fn parse_command() -> Option<Command> {
         Some("simulate") => Some(Command::Simulate),
         Some("optimize") => Some(Command::Optimize),
         Some("import") => Some(Command::Import),
+        Some("import-fight") => Some(Command::ImportFight),
         Some("validate") => Some(Command::Validate),
         Some("generate-lcars") => Some(Command::GenerateLcars),
         Some("mitigation-sensitivity") => Some(Command::MitigationSensitivity),
+        Some("officer-upgrades") => Some(Command::OfficerUpgrades),
+        Some("officer-leaderboard") => Some(Command::OfficerLeaderboard),
+        Some("bracket") => Some(Command::Bracket),
+        Some("matrix") => Some(Command::Matrix),
+        Some("diff-rankings") => Some(Command::DiffRankings),
+        Some("snapshot") => Some(Command::Snapshot),
+        Some("golden") => Some(Command::Golden),
+        Some("fuzz") => Some(Command::Fuzz),
+        Some("parity") => Some(Command::Parity),
         _ => None,
     }
 }
@@ -78,6 +119,12 @@ fn parse_optimize_args(args: &[String]) -> Result<OptimizeCliArgs, String> {
                 .parse::<u32>()
                 .map_err(|_| "sims must be a positive integer".to_string())?,
             max_candidates: None,
+            strategy: None,
+            scout_sims: None,
+            top_k: None,
+            max_results: None,
+            below_decks_with_ability: false,
+            ga: GeneticOverrides::default(),
         });
     }
 
@@ -85,6 +132,12 @@ fn parse_optimize_args(args: &[String]) -> Result<OptimizeCliArgs, String> {
     let mut hostile = "2918121098".to_string();
     let mut sims: u32 = 5_000;
     let mut max_candidates: Option<u32> = None;
+    let mut strategy: Option<String> = None;
+    let mut scout_sims: Option<u32> = None;
+    let mut top_k: Option<u32> = None;
+    let mut max_results: Option<u32> = None;
+    let mut below_decks_with_ability = false;
+    let mut ga = GeneticOverrides::default();
 
     let mut idx = 0;
     while idx < args.len() {
@@ -123,6 +176,101 @@ fn parse_optimize_args(args: &[String]) -> Result<OptimizeCliArgs, String> {
                 );
                 idx += 2;
             }
+            "--strategy" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| "missing value for --strategy".to_string())?;
+                let value = value.trim().to_ascii_lowercase();
+                if !matches!(
+                    value.as_str(),
+                    "exhaustive" | "genetic" | "tiered" | "two_phase" | "auto"
+                ) {
+                    return Err(
+                        "--strategy must be one of exhaustive, genetic, tiered, two_phase, auto"
+                            .to_string(),
+                    );
+                }
+                strategy = Some(value);
+                idx += 2;
+            }
+            "--scout-sims" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| "missing value for --scout-sims".to_string())?;
+                scout_sims = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| "--scout-sims must be a positive integer".to_string())?,
+                );
+                idx += 2;
+            }
+            "--top-k" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| "missing value for --top-k".to_string())?;
+                top_k = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| "--top-k must be a positive integer".to_string())?,
+                );
+                idx += 2;
+            }
+            "--max-results" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| "missing value for --max-results".to_string())?;
+                max_results = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| "--max-results must be a positive integer".to_string())?,
+                );
+                idx += 2;
+            }
+            "--below-decks-with-ability" => {
+                below_decks_with_ability = true;
+                idx += 1;
+            }
+            "--ga-population" | "--ga-generations" | "--ga-elitism" | "--ga-stagnation"
+            | "--ga-islands" | "--ga-migration-interval" | "--ga-migration-count" => {
+                let flag = args[idx].as_str();
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| format!("missing value for {flag}"))?
+                    .parse::<usize>()
+                    .map_err(|_| format!("{flag} must be a non-negative integer"))?;
+                match flag {
+                    "--ga-population" => ga.population_size = Some(value),
+                    "--ga-generations" => ga.generations = Some(value),
+                    "--ga-elitism" => ga.elitism_count = Some(value),
+                    "--ga-stagnation" => ga.stagnation_limit = Some(value),
+                    "--ga-islands" => ga.islands = Some(value),
+                    "--ga-migration-interval" => ga.migration_interval = Some(value),
+                    _ => ga.migration_count = Some(value),
+                }
+                idx += 2;
+            }
+            "--ga-mutation-rate" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| "missing value for --ga-mutation-rate".to_string())?;
+                ga.mutation_rate = Some(
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| "--ga-mutation-rate must be a number".to_string())?,
+                );
+                idx += 2;
+            }
+            "--ga-target-fitness" => {
+                let value = args
+                    .get(idx + 1)
+                    .ok_or_else(|| "missing value for --ga-target-fitness".to_string())?;
+                ga.target_fitness = Some(
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| "--ga-target-fitness must be a number".to_string())?,
+                );
+                idx += 2;
+            }
             "--profile" => {
                 idx += 2;
             }
@@ -135,6 +283,12 @@ fn parse_optimize_args(args: &[String]) -> Result<OptimizeCliArgs, String> {
         hostile,
         sims,
         max_candidates,
+        strategy,
+        scout_sims,
+        top_k,
+        max_results,
+        below_decks_with_ability,
+        ga,
     })
 }
 
@@ -242,22 +396,50 @@ fn optimize_command(args: &[String]) -> Result<(), String> {
     let parsed = parse_optimize_args(args)?;
     let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
 
+    // Like the library, the CLI is not strict: unknown ids simulate placeholder stats.
     let mut payload = serde_json::json!({
         "ship": parsed.ship,
         "hostile": parsed.hostile,
         "sims": parsed.sims,
+        "strict": false,
     });
-    if let Some(cap) = parsed.max_candidates {
-        if let serde_json::Value::Object(ref mut map) = payload {
+    if let serde_json::Value::Object(ref mut map) = payload {
+        if let Some(cap) = parsed.max_candidates {
             map.insert("max_candidates".to_string(), serde_json::Value::from(cap));
         }
+        if let Some(strategy) = parsed.strategy {
+            map.insert("strategy".to_string(), serde_json::Value::from(strategy));
+        }
+        if let Some(scout_sims) = parsed.scout_sims {
+            map.insert("tiered_scout_sims".to_string(), serde_json::Value::from(scout_sims));
+        }
+        if let Some(top_k) = parsed.top_k {
+            map.insert("tiered_top_k".to_string(), serde_json::Value::from(top_k));
+        }
+        if let Some(max_results) = parsed.max_results {
+            map.insert("max_results".to_string(), serde_json::Value::from(max_results));
+        }
+        if !parsed.ga.is_empty() {
+            let ga = serde_json::to_value(&parsed.ga)
+                .map_err(|err| format!("failed to encode GA overrides: {err}"))?;
+            map.insert("ga".to_string(), ga);
+        }
+        if parsed.below_decks_with_ability {
+            map.insert("only_below_decks_with_ability".to_string(), serde_json::Value::Bool(true));
+        }
     }
     let body = payload.to_string();
 
     let registry = kobayashi::data::data_registry::DataRegistry::load()
         .map_err(|e| format!("Failed to load data registry: {e}"))?;
-    let payload = server::api::optimize_payload(registry.as_ref(), &body, Some(profile_id.as_str()))
-        .map_err(|err| format!("failed to build optimize response: {err}"))?;
+    let webhooks = server::notify::WebhookPolicy::from_env();
+    let payload = server::api::optimize_payload(
+        registry.as_ref(),
+        &body,
+        Some(profile_id.as_str()),
+        &webhooks,
+    )
+    .map_err(|err| format!("failed to build optimize response: {err}"))?;
     let response: serde_json::Value =
         serde_json::from_str(&payload).map_err(|err| format!("invalid optimize payload: {err}"))?;
 
@@ -266,65 +448,92 @@ fn optimize_command(args: &[String]) -> Result<(), String> {
         serde_json::to_string_pretty(&response["recommendations"])
             .map_err(|err| format!("failed to serialize recommendations: {err}"))?
     );
+    let pools = &response["scenario"]["officer_pools"];
+    if pools.is_object() {
+        eprintln!(
+            "# officer pools: {} captains, {} bridge, {} below decks{}",
+            pools["captains"],
+            pools["bridge"],
+            pools["below_decks"],
+            if pools["only_below_decks_with_ability"] == true {
+                " (with a below-decks ability)"
+            } else {
+                ""
+            }
+        );
+    }
+    eprint!("{}", render_explanations(&response["recommendations"]));
     Ok(())
 }
 
+/// One readable block per explained recommendation (stderr, so stdout stays JSON).
+fn render_explanations(recommendations: &serde_json::Value) -> String {
+    let pct = |v: &serde_json::Value| format!("{:+.0}%", v.as_f64().unwrap_or(0.0) * 100.0);
+    let mut out = String::new();
+    for (rank, rec) in recommendations.as_array().into_iter().flatten().enumerate() {
+        let Some(explanation) = rec.get("explanation") else {
+            continue;
+        };
+        out.push_str(&format!(
+            "# why #{} ({}): win rate {:.1}%\n",
+            rank + 1,
+            rec["captain"].as_str().unwrap_or_default(),
+            rec["win_rate"].as_f64().unwrap_or(0.0) * 100.0
+        ));
+        for row in explanation["contributions"].as_array().into_iter().flatten() {
+            out.push_str(&format!(
+                "#   {} ({}): damage {}, survival {}, win rate {}\n",
+                row["officer"].as_str().unwrap_or_default(),
+                row["seat"].as_str().unwrap_or_default(),
+                pct(&row["damage_share"]),
+                pct(&row["survival_share"]),
+                pct(&row["win_rate_delta"]),
+            ));
+        }
+        let uptimes: Vec<String> = explanation["status_uptimes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|u| {
+                let uptime = u["uptime"].as_f64().unwrap_or(0.0) * 100.0;
+                format!("{} {uptime:.0}%", u["status"].as_str().unwrap_or_default())
+            })
+            .collect();
+        if !uptimes.is_empty() {
+            out.push_str(&format!("#   status uptime: {}\n", uptimes.join(", ")));
+        }
+    }
+    out
+}
+
 fn simulate_command(args: &[String]) -> Result<(), String> {
+    if let Some(idx) = args.iter().position(|a| a == "--scenario") {
+        let path = args.get(idx + 1).ok_or("--scenario needs a file path")?;
+        return scenario_command(path, args);
+    }
     let parsed = parse_simulate_args(args)?;
     let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
     let profile_path_str = profile_path(&profile_id, PROFILE_JSON).to_string_lossy().to_string();
     let player_profile = load_profile(&profile_path_str);
 
     let attacker = apply_profile_to_attacker(
-        Combatant {
-            id: parsed.attacker_id,
-            attack: parsed.attacker_attack,
-            mitigation: 0.0,
-            pierce: parsed.attacker_pierce,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            end_of_round_damage: 0.0,
-            hull_health: 1000.0,
-            shield_health: 0.0,
-            shield_mitigation: 0.8,
-            apex_barrier: 0.0,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-            weapons: vec![],
-        },
+        Combatant::builder(parsed.attacker_id)
+            .attack(parsed.attacker_attack)
+            .pierce(parsed.attacker_pierce)
+            .build()
+            .map_err(|err| format!("invalid attacker: {err}"))?,
         &player_profile,
+        TargetContext::Hostile,
     );
-    let defender = Combatant {
-        id: parsed.defender_id,
-        attack: 0.0,
-        mitigation: parsed.defender_mitigation,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: parsed.rounds,
-        seed: parsed.seed,
-        trace_mode: if parsed.trace_events {
-            TraceMode::Events
-        } else {
-            TraceMode::Off
-        },
-    };
+    let defender = Combatant::builder(parsed.defender_id)
+        .mitigation(parsed.defender_mitigation)
+        .build()
+        .map_err(|err| format!("invalid defender: {err}"))?;
+    let config = SimulationConfig::builder()
+        .rounds(parsed.rounds)
+        .seed(parsed.seed)
+        .trace(parsed.trace_events)
+        .build();
 
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     println!(
@@ -335,6 +544,61 @@ fn simulate_command(args: &[String]) -> Result<(), String> {
     Ok(())
 }
 
+/// `simulate --scenario <file>`: runs a YAML or JSON scenario file and prints the result JSON.
+fn scenario_command(path: &str, args: &[String]) -> Result<(), String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|err| format!("failed to read scenario {path}: {err}"))?;
+    let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
+    let registry = kobayashi::data::data_registry::DataRegistry::load()
+        .map_err(|e| format!("Failed to load data registry: {e}"))?;
+    let body = server::api::simulate_scenario_payload(
+        registry.as_ref(),
+        &raw,
+        Some(profile_id.as_str()),
+    )
+    .map_err(|err| format!("scenario {path}: {err}"))?;
+    println!("{body}");
+    Ok(())
+}
+
+/// `matrix <file>`: runs every combination of a scenario file's matrix axes. The table goes to
+/// `--out` (JSON for a `.json` path, CSV otherwise) or to stdout as CSV.
+fn matrix_command(args: &[String]) -> Result<(), String> {
+    const USAGE: &str =
+        "usage: kobayashi matrix <scenario.yaml> [--out <file.csv|file.json>] [--profile <id>]";
+    let path = args
+        .first()
+        .filter(|s| !s.starts_with("--"))
+        .ok_or_else(|| USAGE.to_string())?;
+    let out = match args.iter().position(|a| a == "--out") {
+        Some(idx) => Some(args.get(idx + 1).ok_or_else(|| USAGE.to_string())?),
+        None => None,
+    };
+    let raw = std::fs::read_to_string(path)
+        .map_err(|err| format!("failed to read scenario {path}: {err}"))?;
+    let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
+    let registry = kobayashi::data::data_registry::DataRegistry::load()
+        .map_err(|e| format!("Failed to load data registry: {e}"))?;
+    let response = server::api::run_matrix(registry.as_ref(), &raw, Some(profile_id.as_str()))
+        .map_err(|err| format!("scenario {path}: {err}"))?;
+
+    let table = match out {
+        Some(out) if out.ends_with(".json") => serde_json::to_string_pretty(&response)
+            .map(|json| json + "\n")
+            .map_err(|err| format!("failed to serialize matrix: {err}"))?,
+        _ => server::api::matrix_csv(&response)
+            .map_err(|err| format!("failed to write matrix csv: {err}"))?,
+    };
+    match out {
+        Some(out) => {
+            std::fs::write(out, table).map_err(|err| format!("cannot write {out}: {err}"))?;
+            eprintln!("wrote {} runs to {out}", response.runs.len());
+        }
+        None => print!("{table}"),
+    }
+    Ok(())
+}
+
 /// Roster files live here; a bare filename is resolved as rosters/<filename>.
 const ROSTERS_DIR: &str = "rosters";
 
@@ -423,19 +687,90 @@ fn handle_import(args: &[String]) -> i32 {
     }
 }
 
+/// `import-fight <export> [--id <id>] [--data-dir <dir>]` turns the enemy of a game fight export
+/// into a hostile record and adds it to the hostile index, so `optimize` can target it by id.
+fn handle_import_fight(args: &[String]) -> i32 {
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+    let Some(path) = args.first().filter(|a| !a.starts_with("--")) else {
+        eprintln!(
+            "usage: kobayashi import-fight <fight export .csv|.tsv> [--id <id>] [--data-dir <dir>]"
+        );
+        return 2;
+    };
+    let export = match std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| parse_fight_export(&raw))
+    {
+        Ok(export) => export,
+        Err(err) => {
+            eprintln!("import-fight error: {path}: {err}");
+            return 1;
+        }
+    };
+    if export.enemy_fleet.is_empty() {
+        eprintln!("import-fight error: {path}: no enemy fleet row in the export");
+        return 1;
+    }
+    let mut record = export_to_hostile_record(&export);
+    if let Some(id) = flag("--id") {
+        record.id = id.to_string();
+    }
+    let default_dir = std::path::Path::new(DEFAULT_HOSTILES_INDEX_PATH)
+        .parent()
+        .unwrap_or(std::path::Path::new("."));
+    let data_dir = flag("--data-dir").map_or(default_dir, std::path::Path::new);
+    if let Err(err) = save_hostile_record(data_dir, &record) {
+        eprintln!("import-fight error: cannot write to {}: {err}", data_dir.display());
+        return 1;
+    }
+    println!(
+        "imported hostile '{}' ({} {} level {}, hull {}, shield {}) into {}",
+        record.id,
+        record.hostile_name,
+        record.ship_class,
+        record.level,
+        record.hull_health,
+        record.shield_health,
+        data_dir.display()
+    );
+    0
+}
+
 fn handle_validate(args: &[String]) -> i32 {
+    let mechanics = args.iter().any(|a| a == "--mechanics");
     let path = args
-        .first()
+        .iter()
         .map(String::as_str)
+        .find(|a| !a.starts_with("--"))
         .unwrap_or("data/officers/officers.canonical.json");
 
-    let report = match validate_officer_dataset(path) {
+    let mut report = match validate_officer_dataset(path) {
         Ok(report) => report,
         Err(err) => {
             eprintln!("validation failed: {err}");
             return 1;
         }
     };
+    if mechanics {
+        // LCARS directories are checked through the canonical JSON they are imported into.
+        let canonical = if std::path::Path::new(path).is_dir() {
+            "data/officers/officers.canonical.json"
+        } else {
+            path
+        };
+        match validate_officer_mechanics(canonical, &MonotonicityScenario::default()) {
+            Ok(mechanics) => report.diagnostics.extend(mechanics.diagnostics),
+            Err(err) => {
+                eprintln!("validation failed: {err}");
+                return 1;
+            }
+        }
+    }
 
     let errors: Vec<_> = report
         .diagnostics
@@ -572,19 +907,594 @@ fn mitigation_sensitivity_command(args: &[String]) -> Result<(), String> {
     Ok(())
 }
 
+fn officer_upgrades_command(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "usage: kobayashi officer-upgrades <ship> <hostile> [--sims <u32>] [--top-crews <u32>] [--profile <id>]";
+    let ship = args
+        .first()
+        .filter(|s| !s.starts_with("--"))
+        .ok_or_else(|| USAGE.to_string())?;
+    let hostile = args
+        .get(1)
+        .filter(|s| !s.starts_with("--"))
+        .ok_or_else(|| USAGE.to_string())?;
+    let mut payload = serde_json::json!({ "ship": ship, "hostile": hostile });
+    let mut i = 2;
+    while i < args.len() {
+        let key = match args[i].as_str() {
+            "--sims" => "sims",
+            "--top-crews" => "top_crews",
+            "--profile" => {
+                i += 2;
+                continue;
+            }
+            unknown => return Err(format!("unknown officer-upgrades argument: {unknown}")),
+        };
+        let value = args
+            .get(i + 1)
+            .and_then(|v| v.parse::<u32>().ok())
+            .ok_or_else(|| format!("{} must be a positive integer", args[i]))?;
+        payload[key] = serde_json::Value::from(value);
+        i += 2;
+    }
+    let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
+
+    let registry = kobayashi::data::data_registry::DataRegistry::load()
+        .map_err(|e| format!("Failed to load data registry: {e}"))?;
+    let body = server::api::officer_upgrades_payload(
+        registry.as_ref(),
+        &payload.to_string(),
+        Some(profile_id.as_str()),
+    )
+    .map_err(|err| format!("failed to build officer upgrade report: {err}"))?;
+    let response: serde_json::Value =
+        serde_json::from_str(&body).map_err(|err| format!("invalid report payload: {err}"))?;
+
+    println!("officer\ttier\tshards\twin_rate_before\twin_rate_after\tgain\tgain_per_100_shards");
+    for upgrade in response["upgrades"].as_array().into_iter().flatten() {
+        let number = |key: &str| {
+            upgrade[key]
+                .as_f64()
+                .map(|v| format!("{v:.4}"))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        println!(
+            "{}\tT{}->T{}\t{}\t{}\t{}\t{}\t{}",
+            upgrade["officer_name"].as_str().unwrap_or_default(),
+            upgrade["current_tier"],
+            upgrade["next_tier"],
+            upgrade["shard_cost"].as_u64().map_or("-".to_string(), |c| c.to_string()),
+            number("win_rate_before"),
+            number("win_rate_after"),
+            number("win_rate_gain"),
+            number("gain_per_100_shards"),
+        );
+    }
+    Ok(())
+}
+
+fn officer_leaderboard_command(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "usage: kobayashi officer-leaderboard <ship> <hostile> [--sims <u32>] \
+                         [--samples <u32>] [--seed <u64>] [--profile <id>]";
+    let ship = args
+        .first()
+        .filter(|s| !s.starts_with("--"))
+        .ok_or_else(|| USAGE.to_string())?;
+    let hostile = args
+        .get(1)
+        .filter(|s| !s.starts_with("--"))
+        .ok_or_else(|| USAGE.to_string())?;
+    let mut payload = serde_json::json!({ "ship": ship, "hostile": hostile });
+    let mut i = 2;
+    while i < args.len() {
+        let key = match args[i].as_str() {
+            "--sims" => "sims",
+            "--samples" => "samples",
+            "--seed" => "seed",
+            "--profile" => {
+                i += 2;
+                continue;
+            }
+            unknown => return Err(format!("unknown officer-leaderboard argument: {unknown}")),
+        };
+        let value = args
+            .get(i + 1)
+            .and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(|| format!("{} must be a non-negative integer", args[i]))?;
+        payload[key] = serde_json::Value::from(value);
+        i += 2;
+    }
+    let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
+
+    let registry = kobayashi::data::data_registry::DataRegistry::load()
+        .map_err(|e| format!("Failed to load data registry: {e}"))?;
+    let body = server::api::officer_leaderboard_payload(
+        registry.as_ref(),
+        &payload.to_string(),
+        Some(profile_id.as_str()),
+    )
+    .map_err(|err| format!("failed to build officer leaderboard: {err}"))?;
+    let response: serde_json::Value =
+        serde_json::from_str(&body).map_err(|err| format!("invalid report payload: {err}"))?;
+
+    println!(
+        "# {} sampled crews, empty crew win rate {:.4}, mean crew win rate {:.4}",
+        response["sampled_crews"],
+        response["empty_crew_win_rate"].as_f64().unwrap_or_default(),
+        response["mean_crew_win_rate"].as_f64().unwrap_or_default()
+    );
+    println!("seat\trank\tofficer\tsamples\tmarginal_win_rate\tstd_error");
+    for seat in response["seats"].as_array().into_iter().flatten() {
+        for (rank, officer) in seat["officers"].as_array().into_iter().flatten().enumerate() {
+            println!(
+                "{}\t{}\t{}\t{}\t{:.4}\t{:.4}",
+                seat["seat"].as_str().unwrap_or_default(),
+                rank + 1,
+                officer["officer_name"].as_str().unwrap_or_default(),
+                officer["samples"],
+                officer["marginal_win_rate"].as_f64().unwrap_or_default(),
+                officer["std_error"].as_f64().unwrap_or_default(),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn bracket_command(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "usage: kobayashi bracket <hostile> <preset> <preset>... [--sims <u32>] \
+                         [--seed <u64>] [--profile <id>]";
+    let hostile = args
+        .first()
+        .filter(|s| !s.starts_with("--"))
+        .ok_or_else(|| USAGE.to_string())?;
+    let mut payload = serde_json::json!({ "hostile": hostile });
+    let mut presets = Vec::new();
+    let mut i = 1;
+    while i < args.len() {
+        let key = match args[i].as_str() {
+            "--sims" => "num_sims",
+            "--seed" => "seed",
+            "--profile" => {
+                i += 2;
+                continue;
+            }
+            flag if flag.starts_with("--") => {
+                return Err(format!("unknown bracket argument: {flag}"))
+            }
+            preset => {
+                presets.push(preset.to_string());
+                i += 1;
+                continue;
+            }
+        };
+        let value = args
+            .get(i + 1)
+            .and_then(|v| v.parse::<u64>().ok())
+            .ok_or_else(|| format!("{} must be a positive integer", args[i]))?;
+        payload[key] = serde_json::Value::from(value);
+        i += 2;
+    }
+    if presets.len() < 2 {
+        return Err(USAGE.to_string());
+    }
+    payload["presets"] = serde_json::Value::from(presets);
+    let profile_id = resolve_profile_id_for_api(parse_profile_arg(args).as_deref());
+
+    let registry = kobayashi::data::data_registry::DataRegistry::load()
+        .map_err(|e| format!("Failed to load data registry: {e}"))?;
+    let body = server::api::bracket_payload(
+        registry.as_ref(),
+        &payload.to_string(),
+        Some(profile_id.as_str()),
+    )
+    .map_err(|err| format!("failed to run bracket: {err}"))?;
+    let response: serde_json::Value =
+        serde_json::from_str(&body).map_err(|err| format!("invalid bracket payload: {err}"))?;
+
+    println!("rank\tpreset\tship\twin_rate\tavg_hull_remaining\twins\tlosses\tdraws");
+    for row in response["standings"].as_array().into_iter().flatten() {
+        println!(
+            "{}\t{}\t{}\t{:.4}\t{:.4}\t{}\t{}\t{}",
+            row["rank"],
+            row["name"].as_str().unwrap_or_default(),
+            row["ship"].as_str().unwrap_or_default(),
+            row["win_rate"].as_f64().unwrap_or_default(),
+            row["avg_hull_remaining"].as_f64().unwrap_or_default(),
+            row["wins"],
+            row["losses"],
+            row["draws"],
+        );
+    }
+    println!();
+    println!("a\tb\twin_rate_a\twin_rate_b\tp_value\twinner");
+    for matchup in response["matchups"].as_array().into_iter().flatten() {
+        println!(
+            "{}\t{}\t{:.4}\t{:.4}\t{:.4}\t{}",
+            matchup["a"].as_str().unwrap_or_default(),
+            matchup["b"].as_str().unwrap_or_default(),
+            matchup["win_rate_a"].as_f64().unwrap_or_default(),
+            matchup["win_rate_b"].as_f64().unwrap_or_default(),
+            matchup["p_value"].as_f64().unwrap_or_default(),
+            matchup["winner"].as_str().unwrap_or("draw"),
+        );
+    }
+    Ok(())
+}
+
+/// Read saved optimize output: the `kobayashi optimize` recommendations array or a full
+/// `/api/optimize` response.
+fn read_ranking_file(path: &str) -> Result<Vec<RankedCrewResult>, String> {
+    let raw = std::fs::read_to_string(path).map_err(|err| format!("cannot read {path}: {err}"))?;
+    let mut value: serde_json::Value =
+        serde_json::from_str(&raw).map_err(|err| format!("{path} is not JSON: {err}"))?;
+    if let Some(recommendations) = value.get_mut("recommendations") {
+        value = recommendations.take();
+    }
+    serde_json::from_value(value)
+        .map_err(|err| format!("{path} does not contain ranked crews: {err}"))
+}
+
+fn diff_rankings_command(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "usage: kobayashi diff-rankings <before.json> <after.json>";
+    let (Some(before_path), Some(after_path)) = (args.first(), args.get(1)) else {
+        return Err(USAGE.to_string());
+    };
+    let before = read_ranking_file(before_path)?;
+    let after = read_ranking_file(after_path)?;
+    let diff = diff_rankings(&before, &after);
+
+    println!(
+        "# top crew {}; {} unchanged, {} changed",
+        if diff.top_unchanged { "unchanged" } else { "changed" },
+        diff.unchanged,
+        diff.changes.len()
+    );
+    println!("movement\trank_before\trank_after\twin_rate_before\twin_rate_after\tcrew_id\tcrew");
+    let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+    for change in &diff.changes {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{} | {} | {}",
+            change.movement.as_str(),
+            or_dash(change.rank_before.map(|r| r.to_string())),
+            or_dash(change.rank_after.map(|r| r.to_string())),
+            or_dash(change.win_rate_before.map(|w| format!("{w:.4}"))),
+            or_dash(change.win_rate_after.map(|w| format!("{w:.4}"))),
+            change.crew_id,
+            change.captain,
+            change.bridge.join(", "),
+            change.below_decks.join(", "),
+        );
+    }
+    Ok(())
+}
+
+/// Replays a [SimulationSnapshot] file (saved from a failed job or a bug report) and prints the
+/// result JSON.
+fn snapshot_command(args: &[String]) -> Result<(), String> {
+    const USAGE: &str = "usage: kobayashi snapshot run <file>";
+    let (Some("run"), Some(path)) = (args.first().map(String::as_str), args.get(1)) else {
+        return Err(USAGE.to_string());
+    };
+    let snapshot = SimulationSnapshot::load(std::path::Path::new(path))
+        .map_err(|err| format!("{path}: {err}"))?;
+    if snapshot.kobayashi_version != env!("CARGO_PKG_VERSION") {
+        eprintln!(
+            "note: snapshot was written by kobayashi {}; replaying with {}",
+            snapshot.kobayashi_version,
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+    let result = snapshot.run();
+    let json = serde_json::to_string_pretty(&result)
+        .map_err(|err| format!("failed to serialize result: {err}"))?;
+    println!("{json}");
+    Ok(())
+}
+
+/// `golden generate [--out <file>]` writes the golden-value matrix; `golden verify <file>
+/// [--tolerance <f64>]` re-evaluates another implementation's matrix and exits 1 on any difference.
+fn handle_golden(args: &[String]) -> i32 {
+    const USAGE: &str =
+        "usage: kobayashi golden <generate [--out <file>] | verify <file> [--tolerance <f64>]>";
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+    match args.first().map(String::as_str) {
+        Some("generate") => {
+            let json = match serde_json::to_string_pretty(&generate_golden_matrix()) {
+                Ok(json) => json,
+                Err(err) => {
+                    eprintln!("golden error: {err}");
+                    return 1;
+                }
+            };
+            match flag("--out") {
+                Some(path) => {
+                    if let Err(err) = std::fs::write(path, json + "\n") {
+                        eprintln!("golden error: cannot write {path}: {err}");
+                        return 1;
+                    }
+                }
+                None => println!("{json}"),
+            }
+            0
+        }
+        Some("verify") => {
+            let Some(path) = args.get(1).filter(|a| !a.starts_with("--")) else {
+                eprintln!("{USAGE}");
+                return 2;
+            };
+            let tolerance = match flag("--tolerance").map(str::parse::<f64>) {
+                None => DEFAULT_GOLDEN_TOLERANCE,
+                Some(Ok(t)) if t >= 0.0 => t,
+                Some(_) => {
+                    eprintln!("golden error: --tolerance must be a non-negative number");
+                    return 2;
+                }
+            };
+            let matrix: GoldenMatrix = match std::fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|raw| serde_json::from_str(&raw).map_err(|err| err.to_string()))
+            {
+                Ok(matrix) => matrix,
+                Err(err) => {
+                    eprintln!("golden error: {path}: {err}");
+                    return 1;
+                }
+            };
+            let report = verify_golden_matrix(&matrix, tolerance);
+            if !report.mismatches.is_empty() {
+                println!("case\toutput\texpected\tactual\tdiff");
+            }
+            for m in &report.mismatches {
+                println!(
+                    "{}\t{}\t{}\t{}\t{:e}",
+                    m.case_id,
+                    m.output,
+                    m.expected,
+                    m.actual,
+                    m.actual - m.expected
+                );
+            }
+            for key in &report.unknown_outputs {
+                println!("# unknown output {key}");
+            }
+            let summary = format!(
+                "golden: {} outputs compared against {}, {} mismatched, {} unknown",
+                report.compared,
+                matrix.generator,
+                report.mismatches.len(),
+                report.unknown_outputs.len()
+            );
+            if report.passed() {
+                println!("{summary}");
+                0
+            } else {
+                eprintln!("{summary}");
+                1
+            }
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            2
+        }
+    }
+}
+
+/// `fuzz [--cases <n>] [--seed <u64>] [--out-dir <dir>]` checks engine invariants on random
+/// fights, writes each minimized failing snapshot to `<dir>/fuzz-<seed>-<case>.json` and exits 1
+/// when any case fails.
+fn handle_fuzz(args: &[String]) -> i32 {
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+    let cases = match flag("--cases").map(str::parse::<u32>) {
+        None => DEFAULT_FUZZ_CASES,
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => {
+            eprintln!("fuzz error: --cases must be a positive integer");
+            return 2;
+        }
+    };
+    let seed = match flag("--seed").map(str::parse::<u64>) {
+        None => 0,
+        Some(Ok(seed)) => seed,
+        Some(Err(_)) => {
+            eprintln!("fuzz error: --seed must be an unsigned integer");
+            return 2;
+        }
+    };
+    let out_dir = std::path::Path::new(flag("--out-dir").unwrap_or("fuzz-failures"));
+
+    let report = run_fuzz(seed, cases);
+    if !report.failures.is_empty() {
+        if let Err(err) = std::fs::create_dir_all(out_dir) {
+            eprintln!("fuzz error: cannot create {}: {err}", out_dir.display());
+            return 1;
+        }
+        println!("case\tinvariant\tmessage\tsnapshot");
+    }
+    for failure in &report.failures {
+        let path = out_dir.join(format!("fuzz-{seed}-{}.json", failure.case));
+        let written = failure
+            .snapshot
+            .to_json_pretty()
+            .map_err(|err| err.to_string())
+            .and_then(|json| std::fs::write(&path, json + "\n").map_err(|err| err.to_string()));
+        if let Err(err) = written {
+            eprintln!("fuzz error: cannot write {}: {err}", path.display());
+            return 1;
+        }
+        println!(
+            "{}\t{}\t{}\t{}",
+            failure.case,
+            failure.violation.invariant.as_str(),
+            failure.violation.message,
+            path.display()
+        );
+    }
+    let summary = format!(
+        "fuzz: {} cases from seed {seed}, {} failed",
+        report.cases,
+        report.failures.len()
+    );
+    if report.failures.is_empty() {
+        println!("{summary}");
+        0
+    } else {
+        eprintln!("{summary}");
+        1
+    }
+}
+
+/// `parity <log.json> --scenario <snapshot.json> [--sims <n>] [--tolerance <f64>] [--mapping
+/// <file>] [--json]` replays the snapshot `n` times (seeds `seed..seed + n`) with tracing on,
+/// scores each mechanic against the log and exits 1 when any of them diverges.
+fn handle_parity(args: &[String]) -> i32 {
+    const USAGE: &str = "usage: kobayashi parity <log.json> --scenario <snapshot.json> \
+        [--sims <u32>] [--tolerance <f64>] [--mapping <file>] [--json]";
+    let flag = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+    let (Some(log_path), Some(scenario_path)) =
+        (args.first().filter(|a| !a.starts_with("--")), flag("--scenario"))
+    else {
+        eprintln!("{USAGE}");
+        return 2;
+    };
+    let sims = match flag("--sims").map(str::parse::<u32>) {
+        None => 100,
+        Some(Ok(n)) if n > 0 => n,
+        Some(_) => {
+            eprintln!("parity error: --sims must be a positive integer");
+            return 2;
+        }
+    };
+    let tolerance = match flag("--tolerance").map(str::parse::<f64>) {
+        None => DEFAULT_PARITY_TOLERANCE,
+        Some(Ok(t)) if t >= 0.0 => t,
+        Some(_) => {
+            eprintln!("parity error: --tolerance must be a non-negative number");
+            return 2;
+        }
+    };
+
+    let log = std::fs::read_to_string(log_path)
+        .map_err(|err| err.to_string())
+        .and_then(|raw| match flag("--mapping") {
+            Some(mapping) => load_log_mapping(std::path::Path::new(mapping))
+                .and_then(|mapping| parse_combat_log_json_with_mapping(&raw, &mapping)),
+            None => parse_combat_log_json(&raw),
+        });
+    let log = match log {
+        Ok(log) => log,
+        Err(err) => {
+            eprintln!("parity error: {log_path}: {err}");
+            return 1;
+        }
+    };
+    let snapshot = match SimulationSnapshot::load(std::path::Path::new(scenario_path)) {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            eprintln!("parity error: {scenario_path}: {err}");
+            return 1;
+        }
+    };
+
+    let results: Vec<_> = (0..u64::from(sims))
+        .map(|i| {
+            let mut config = snapshot.config;
+            config.seed = snapshot.config.seed.wrapping_add(i);
+            config.trace_mode = TraceMode::Events;
+            config.trace_filter = TraceFilter::default();
+            simulate_combat(&snapshot.attacker, &snapshot.defender, config, &snapshot.crew)
+        })
+        .collect();
+    let report = parity_report(&log, &results, tolerance);
+
+    if args.iter().any(|a| a == "--json") {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("parity error: {err}");
+                return 1;
+            }
+        }
+    } else {
+        println!("mechanic\tstatus\tlog\tsim\tscore\tsubsystem");
+        for m in &report.mechanics {
+            let status = match m.status {
+                ParityStatus::Agree => "agree",
+                ParityStatus::Diverges => "DIVERGES",
+                ParityStatus::NoData => "no data",
+            };
+            println!(
+                "{}\t{status}\t{:.4}\t{:.4}\t{:.3}\t{}",
+                m.mechanic.as_str(),
+                m.log_value,
+                m.sim_value,
+                m.score,
+                m.subsystem
+            );
+        }
+    }
+    let divergent: Vec<&str> = report.divergent().map(|m| m.subsystem.as_str()).collect();
+    let summary = format!(
+        "parity: score {:.3} over {} sims, {} mechanics diverge",
+        report.overall_score,
+        report.simulations,
+        divergent.len()
+    );
+    if divergent.is_empty() {
+        println!("{summary}");
+        0
+    } else {
+        eprintln!("{summary}; check {}", divergent.join(", "));
+        1
+    }
+}
+
 fn print_usage() {
     eprintln!(
-        "usage: kobayashi <serve|simulate|optimize|import|validate|generate-lcars|mitigation-sensitivity> [args]\n\
+        "usage: kobayashi <serve|simulate|optimize|import|import-fight|validate|generate-lcars|mitigation-sensitivity|officer-upgrades|officer-leaderboard|bracket|diff-rankings|snapshot|golden|fuzz|parity> [args]\n\
 simulate: kobayashi simulate <rounds> <seed> [--profile <id>]\n\
   or kobayashi simulate --attacker-id <id> --attacker-attack <f64> ... [--profile <id>]\n\
+  or kobayashi simulate --scenario <file.yaml|file.json> [--profile <id>]\n\
 optimize: kobayashi optimize <ship> <hostile> <sims> [--profile <id>]\n\
-  or kobayashi optimize --ship <id> --hostile <id> --sims <u32> [--max-candidates <u32>] [--profile <id>]\n\
+  or kobayashi optimize --ship <id> --hostile <id> --sims <u32> [--max-candidates <u32>]\n\
+    [--strategy <exhaustive|genetic|tiered|two_phase|auto>] [--scout-sims <u32>]\n\
+    [--top-k <u32>]\n\
+    [--max-results <u32>] [--below-decks-with-ability] [--profile <id>]\n\
+    genetic: [--ga-population <n>] [--ga-generations <n>] [--ga-mutation-rate <0-1>]\n\
+    [--ga-elitism <n>] [--ga-stagnation <n, 0 = off>] [--ga-target-fitness <score>]\n\
+    [--ga-islands <n>] [--ga-migration-interval <n>] [--ga-migration-count <n>]\n\
 import: kobayashi import <path> [--profile <id>]\n\
-mitigation-sensitivity: kobayashi mitigation-sensitivity <ship> <hostile> [--delta-pct <f64>]"
+import-fight: kobayashi import-fight <fight export .csv|.tsv> [--id <id>] [--data-dir <dir>]\n\
+validate: kobayashi validate [<officers.json|lcars dir>] [--mechanics]\n\
+mitigation-sensitivity: kobayashi mitigation-sensitivity <ship> <hostile> [--delta-pct <f64>]\n\
+officer-upgrades: kobayashi officer-upgrades <ship> <hostile> [--sims <u32>] [--top-crews <u32>] [--profile <id>]\n\
+officer-leaderboard: kobayashi officer-leaderboard <ship> <hostile> [--sims <u32>] [--samples <u32>] [--seed <u64>] [--profile <id>]\n\
+bracket: kobayashi bracket <hostile> <preset> <preset>... [--sims <u32>] [--seed <u64>] [--profile <id>]\n\
+matrix: kobayashi matrix <scenario.yaml> [--out <file.csv|file.json>] [--profile <id>]\n\
+diff-rankings: kobayashi diff-rankings <before.json> <after.json>\n\
+snapshot: kobayashi snapshot run <file>\n\
+golden: kobayashi golden generate [--out <file>] | golden verify <file> [--tolerance <f64>]\n\
+fuzz: kobayashi fuzz [--cases <u32>] [--seed <u64>] [--out-dir <dir>]\n\
+parity: kobayashi parity <log.json> --scenario <snapshot.json> [--sims <u32>] [--tolerance <f64>]
+    [--mapping <file>] [--json]"
     );
 }
 
 fn main() {
+    kobayashi::logging::init();
     let _ = migrate_from_legacy_if_needed();
     kobayashi::parallel::init_from_env();
 
@@ -617,6 +1527,9 @@ fn main() {
         Some(Command::Import) => {
             exit_code = handle_import(&command_args);
         }
+        Some(Command::ImportFight) => {
+            exit_code = handle_import_fight(&command_args);
+        }
         Some(Command::Validate) => {
             exit_code = handle_validate(&command_args);
         }
@@ -630,6 +1543,57 @@ fn main() {
                 exit_code = 2;
             }
         }
+        Some(Command::OfficerUpgrades) => {
+            if let Err(err) = officer_upgrades_command(&command_args) {
+                eprintln!("officer-upgrades error: {err}");
+                print_usage();
+                exit_code = 2;
+            }
+        }
+        Some(Command::OfficerLeaderboard) => {
+            if let Err(err) = officer_leaderboard_command(&command_args) {
+                eprintln!("officer-leaderboard error: {err}");
+                print_usage();
+                exit_code = 2;
+            }
+        }
+        Some(Command::Bracket) => {
+            if let Err(err) = bracket_command(&command_args) {
+                eprintln!("bracket error: {err}");
+                print_usage();
+                exit_code = 2;
+            }
+        }
+        Some(Command::Matrix) => {
+            if let Err(err) = matrix_command(&command_args) {
+                eprintln!("matrix error: {err}");
+                print_usage();
+                exit_code = 2;
+            }
+        }
+        Some(Command::DiffRankings) => {
+            if let Err(err) = diff_rankings_command(&command_args) {
+                eprintln!("diff-rankings error: {err}");
+                print_usage();
+                exit_code = 2;
+            }
+        }
+        Some(Command::Snapshot) => {
+            if let Err(err) = snapshot_command(&command_args) {
+                eprintln!("snapshot error: {err}");
+                print_usage();
+                exit_code = 2;
+            }
+        }
+        Some(Command::Golden) => {
+            exit_code = handle_golden(&command_args);
+        }
+        Some(Command::Fuzz) => {
+            exit_code = handle_fuzz(&command_args);
+        }
+        Some(Command::Parity) => {
+            exit_code = handle_parity(&command_args);
+        }
         None => {
             print_usage();
             exit_code = 2;
@@ -658,6 +1622,52 @@ mod tests {
         assert_eq!(parsed.sims, 9000);
     }
 
+    #[test]
+    fn parse_optimize_args_reads_tiered_flags() {
+        let args: Vec<String> = [
+            "--ship", "enterprise", "--strategy", "Tiered", "--scout-sims", "200", "--top-k", "10",
+            "--max-results", "25", "--below-decks-with-ability",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let parsed = parse_optimize_args(&args).expect("parse should succeed");
+        assert_eq!(parsed.strategy.as_deref(), Some("tiered"));
+        assert_eq!(parsed.scout_sims, Some(200));
+        assert_eq!(parsed.top_k, Some(10));
+        assert_eq!(parsed.max_results, Some(25));
+        assert!(parsed.below_decks_with_ability);
+
+        let bad = vec!["--strategy".to_string(), "annealing".to_string()];
+        assert!(parse_optimize_args(&bad).is_err());
+    }
+
+    #[test]
+    fn parse_optimize_args_reads_genetic_overrides() {
+        let args: Vec<String> = [
+            "--strategy", "genetic", "--ga-population", "120", "--ga-generations", "80",
+            "--ga-mutation-rate", "0.2", "--ga-elitism", "4", "--ga-stagnation", "0",
+            "--ga-target-fitness", "0.9", "--ga-islands", "4", "--ga-migration-interval", "3",
+            "--ga-migration-count", "2",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let ga = parse_optimize_args(&args).expect("parse should succeed").ga;
+        assert_eq!(ga.population_size, Some(120));
+        assert_eq!(ga.generations, Some(80));
+        assert_eq!(ga.mutation_rate, Some(0.2));
+        assert_eq!(ga.elitism_count, Some(4));
+        assert_eq!(ga.stagnation_limit, Some(0));
+        assert_eq!(ga.target_fitness, Some(0.9));
+        assert_eq!(ga.islands, Some(4));
+        assert_eq!(ga.migration_interval, Some(3));
+        assert_eq!(ga.migration_count, Some(2));
+
+        let bad = vec!["--ga-population".to_string(), "many".to_string()];
+        assert!(parse_optimize_args(&bad).is_err());
+    }
+
     #[test]
     fn parse_simulate_args_enables_trace_flag() {
         let args = vec!["5".to_string(), "99".to_string()];
@@ -73,44 +73,20 @@ mod tests {
 
     fn minimal_input(attacker_attack: f64) -> CombatSimulationInput {
         CombatSimulationInput {
-            attacker: Combatant {
-                id: "a".to_string(),
-                attack: attacker_attack,
-                mitigation: 0.0,
-                pierce: 0.1,
-                crit_chance: 0.2,
-                crit_multiplier: 2.0,
-                proc_chance: 0.1,
-                proc_multiplier: 1.5,
-                end_of_round_damage: 0.0,
-                hull_health: 1000.0,
-                shield_health: 0.0,
-                shield_mitigation: 0.8,
-                apex_barrier: 0.0,
-                apex_shred: 0.0,
-                isolytic_damage: 0.0,
-                isolytic_defense: 0.0,
-                weapons: vec![],
-            },
-            defender: Combatant {
-                id: "d".to_string(),
-                attack: 0.0,
-                mitigation: 0.5,
-                pierce: 0.0,
-                crit_chance: 0.0,
-                crit_multiplier: 1.0,
-                proc_chance: 0.0,
-                proc_multiplier: 1.0,
-                end_of_round_damage: 0.0,
-                hull_health: 500.0,
-                shield_health: 0.0,
-                shield_mitigation: 0.8,
-                apex_barrier: 0.0,
-                apex_shred: 0.0,
-                isolytic_damage: 0.0,
-                isolytic_defense: 0.0,
-                weapons: vec![],
-            },
+            attacker: Combatant::builder("a")
+                .attack(attacker_attack)
+                .pierce(0.1)
+                .crit_chance(0.2)
+                .crit_multiplier(2.0)
+                .proc_chance(0.1)
+                .proc_multiplier(1.5)
+                .build()
+                .unwrap(),
+            defender: Combatant::builder("d")
+                .mitigation(0.5)
+                .hull_health(500.0)
+                .build()
+                .unwrap(),
             crew: CrewConfiguration { seats: vec![] },
             rounds: 3,
             defender_hull: 500.0,
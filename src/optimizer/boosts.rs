@@ -0,0 +1,140 @@
+//! "Which officer should I boost": for one scenario, re-simulates the best crews with each of
+//! their officers boosted in turn and reports the best choice per crew.
+//!
+//! The search replaces the roster's boost state: every run boosts at most one officer, and all runs
+//! share seeds, so the gain reflects the boost alone. What a boost does numerically is
+//! [crate::combat::types::BOOSTED_EFFECTIVENESS_MULTIPLIER].
+
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::data::data_registry::DataRegistry;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::scenario::SharedScenarioData;
+use crate::optimizer::monte_carlo::{crew_officers, run_monte_carlo_with_shared};
+use crate::optimizer::{
+    optimize_scenario_with_registry, scenario_shared_data_from_registry, OptimizationScenario,
+};
+
+/// Best crews (by the scenario's ranking) searched when the caller does not say.
+pub const DEFAULT_BOOST_TOP_CREWS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BoostOption {
+    pub officer_id: String,
+    pub officer_name: String,
+    /// Win rate of the crew with only this officer boosted.
+    pub win_rate: f64,
+    /// `win_rate` minus the crew's unboosted win rate.
+    pub win_rate_gain: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CrewBoostReport {
+    pub captain: String,
+    pub bridge: Vec<String>,
+    pub below_decks: Vec<String>,
+    pub unboosted_win_rate: f64,
+    /// Officers of this crew, best boost first.
+    pub options: Vec<BoostOption>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BoostReport {
+    /// Officers boosted on the profile roster (ignored by the search).
+    pub roster_boosted: Vec<String>,
+    pub crews: Vec<CrewBoostReport>,
+}
+
+/// For each of the scenario's `top_crews` best crews, rank its officers by win-rate gain when boosted.
+pub fn officer_boost_report(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    top_crews: usize,
+) -> BoostReport {
+    let crews: Vec<CrewCandidate> = optimize_scenario_with_registry(registry, scenario)
+        .into_iter()
+        .take(top_crews.max(1))
+        .map(|r| CrewCandidate::new(r.captain, r.bridge, r.below_decks))
+        .collect();
+    let shared = scenario_shared_data_from_registry(registry, scenario);
+    let mut roster_boosted: Vec<String> = shared
+        .resolve_options
+        .boosted_officers
+        .iter()
+        .cloned()
+        .collect();
+    roster_boosted.sort();
+
+    let unboosted = simulate_win_rates(&shared, scenario, &crews, None);
+    let crews = crews
+        .into_iter()
+        .zip(unboosted)
+        .map(|(crew, unboosted_win_rate)| {
+            let mut options: Vec<BoostOption> = crew_officers(&crew, &shared.officer_index)
+                .into_iter()
+                .cloned()
+                .map(|officer| {
+                    let win_rate = simulate_win_rates(
+                        &shared,
+                        scenario,
+                        std::slice::from_ref(&crew),
+                        Some(&officer.id),
+                    )[0];
+                    BoostOption {
+                        officer_id: officer.id,
+                        officer_name: officer.name,
+                        win_rate,
+                        win_rate_gain: win_rate - unboosted_win_rate,
+                    }
+                })
+                .collect();
+            options.sort_by(|a, b| {
+                b.win_rate
+                    .total_cmp(&a.win_rate)
+                    .then_with(|| a.officer_name.cmp(&b.officer_name))
+            });
+            CrewBoostReport {
+                bridge: crew.bridge_names(),
+                below_decks: crew.below_deck_names(),
+                captain: crew.captain,
+                unboosted_win_rate,
+                options,
+            }
+        })
+        .collect();
+
+    BoostReport {
+        roster_boosted,
+        crews,
+    }
+}
+
+/// Win rate per crew with only `boosted` (a canonical officer id) boosted.
+fn simulate_win_rates(
+    shared: &SharedScenarioData,
+    scenario: &OptimizationScenario<'_>,
+    crews: &[CrewCandidate],
+    boosted: Option<&str>,
+) -> Vec<f64> {
+    if crews.is_empty() {
+        return Vec::new();
+    }
+    let mut shared = shared.clone();
+    shared.resolve_options.boosted_officers = boosted
+        .map(str::to_string)
+        .into_iter()
+        .collect::<HashSet<_>>();
+    run_monte_carlo_with_shared(
+        shared,
+        crews,
+        scenario.simulation_count,
+        scenario.seed,
+        true,
+    )
+    .into_iter()
+    .map(|r| r.win_rate)
+    .collect()
+}
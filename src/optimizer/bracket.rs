@@ -0,0 +1,243 @@
+//! Head-to-head bracket: several fixed crews (usually saved presets, each on its own ship) against
+//! one hostile, compared round-robin. A pairing is only won when the win-rate gap is significant,
+//! so "which of our standard crews is best" is not settled by Monte Carlo noise.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::data::data_registry::DataRegistry;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::run_monte_carlo_with_shared;
+use crate::optimizer::{scenario_shared_data_from_registry, OptimizationScenario};
+
+/// Two-sided p-value below which a pairing has a winner.
+pub const BRACKET_SIGNIFICANCE: f64 = 0.05;
+
+/// One crew entered in the bracket.
+#[derive(Debug, Clone)]
+pub struct BracketEntrant {
+    /// Label shown in the table (the preset name).
+    pub name: String,
+    pub ship: String,
+    pub crew: CrewCandidate,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BracketStanding {
+    /// 1-based; entrants with the same record and win rate share a rank.
+    pub rank: u32,
+    pub name: String,
+    pub ship: String,
+    pub captain: String,
+    pub bridge: Vec<String>,
+    pub below_decks: Vec<String>,
+    pub win_rate: f64,
+    pub avg_hull_remaining: f64,
+    /// Pairings this entrant won with p < `significance`.
+    pub wins: u32,
+    pub losses: u32,
+    /// Pairings without a significant difference.
+    pub draws: u32,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BracketMatchup {
+    pub a: String,
+    pub b: String,
+    pub win_rate_a: f64,
+    pub win_rate_b: f64,
+    /// Two-sided p-value of the two-proportion z-test on the win rates.
+    pub p_value: f64,
+    /// Name of the significantly better entrant; None for a draw.
+    pub winner: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BracketReport {
+    pub significance: f64,
+    /// Best first: most pairings won, then fewest lost, then highest win rate.
+    pub standings: Vec<BracketStanding>,
+    /// Every pairing once, in entry order.
+    pub matchups: Vec<BracketMatchup>,
+}
+
+/// Complementary error function (Abramowitz & Stegun 7.1.26, absolute error below 1.5e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let value = poly * (-z * z).exp();
+    if x >= 0.0 {
+        value
+    } else {
+        2.0 - value
+    }
+}
+
+/// Two-sided p-value that win rates `a` and `b`, each over `sims` fights, differ.
+pub fn two_proportion_p_value(a: f64, b: f64, sims: usize) -> f64 {
+    if sims == 0 {
+        return 1.0;
+    }
+    let n = sims as f64;
+    let pooled = (a + b) / 2.0;
+    let variance = pooled * (1.0 - pooled) * (2.0 / n);
+    if variance <= 0.0 {
+        // Both crews always won or always lost.
+        return if (a - b).abs() < f64::EPSILON { 1.0 } else { 0.0 };
+    }
+    let z = (a - b) / variance.sqrt();
+    erfc(z.abs() / std::f64::consts::SQRT_2).clamp(0.0, 1.0)
+}
+
+/// Runs every entrant against `scenario.hostile` (each on its own ship, same seed) and compares
+/// them pairwise. `scenario.ship` is ignored.
+pub fn run_bracket(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    entrants: &[BracketEntrant],
+) -> BracketReport {
+    let results: Vec<(f64, f64)> = entrants
+        .iter()
+        .map(|entrant| {
+            let entrant_scenario = OptimizationScenario {
+                ship: &entrant.ship,
+                ..scenario.clone()
+            };
+            let shared = scenario_shared_data_from_registry(registry, &entrant_scenario);
+            run_monte_carlo_with_shared(
+                shared,
+                std::slice::from_ref(&entrant.crew),
+                scenario.simulation_count,
+                scenario.seed,
+                false,
+            )
+            .into_iter()
+            .next()
+            .map_or((0.0, 0.0), |r| (r.win_rate, r.avg_hull_remaining))
+        })
+        .collect();
+    bracket_from_results(entrants, &results, scenario.simulation_count)
+}
+
+/// Builds the round-robin from each entrant's (win rate, average hull remaining).
+fn bracket_from_results(
+    entrants: &[BracketEntrant],
+    results: &[(f64, f64)],
+    sims: usize,
+) -> BracketReport {
+    let mut records = vec![(0u32, 0u32, 0u32); entrants.len()];
+    let mut matchups = Vec::new();
+    for i in 0..entrants.len() {
+        for j in (i + 1)..entrants.len() {
+            let (rate_a, rate_b) = (results[i].0, results[j].0);
+            let p_value = two_proportion_p_value(rate_a, rate_b, sims);
+            let winner = if p_value >= BRACKET_SIGNIFICANCE {
+                records[i].2 += 1;
+                records[j].2 += 1;
+                None
+            } else if rate_a > rate_b {
+                records[i].0 += 1;
+                records[j].1 += 1;
+                Some(i)
+            } else {
+                records[j].0 += 1;
+                records[i].1 += 1;
+                Some(j)
+            };
+            matchups.push(BracketMatchup {
+                a: entrants[i].name.clone(),
+                b: entrants[j].name.clone(),
+                win_rate_a: rate_a,
+                win_rate_b: rate_b,
+                p_value,
+                winner: winner.map(|k| entrants[k].name.clone()),
+            });
+        }
+    }
+
+    let mut order: Vec<usize> = (0..entrants.len()).collect();
+    let key = |i: usize| (records[i].0, std::cmp::Reverse(records[i].1));
+    order.sort_by(|&x, &y| {
+        key(y)
+            .cmp(&key(x))
+            .then_with(|| results[y].0.total_cmp(&results[x].0))
+    });
+
+    let mut standings: Vec<BracketStanding> = Vec::with_capacity(order.len());
+    for (position, &i) in order.iter().enumerate() {
+        let rank = match standings.last() {
+            Some(prev)
+                if key(order[position - 1]) == key(i) && prev.win_rate == results[i].0 =>
+            {
+                prev.rank
+            }
+            _ => position as u32 + 1,
+        };
+        let entrant = &entrants[i];
+        standings.push(BracketStanding {
+            rank,
+            name: entrant.name.clone(),
+            ship: entrant.ship.clone(),
+            captain: entrant.crew.captain.clone(),
+            bridge: entrant.crew.bridge_names(),
+            below_decks: entrant.crew.below_deck_names(),
+            win_rate: results[i].0,
+            avg_hull_remaining: results[i].1,
+            wins: records[i].0,
+            losses: records[i].1,
+            draws: records[i].2,
+        });
+    }
+
+    BracketReport {
+        significance: BRACKET_SIGNIFICANCE,
+        standings,
+        matchups,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entrant(name: &str) -> BracketEntrant {
+        BracketEntrant {
+            name: name.to_string(),
+            ship: "saladin".to_string(),
+            crew: CrewCandidate::new(format!("{name} captain"), Vec::new(), Vec::new()),
+        }
+    }
+
+    #[test]
+    fn p_value_separates_real_gaps_from_noise() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-6);
+        assert!((erfc(1.959_964 / std::f64::consts::SQRT_2) - 0.05).abs() < 1e-4);
+        assert!(two_proportion_p_value(0.90, 0.70, 1000) < 1e-6);
+        assert!(two_proportion_p_value(0.52, 0.50, 100) > 0.5);
+        assert_eq!(two_proportion_p_value(1.0, 1.0, 500), 1.0);
+        assert_eq!(two_proportion_p_value(0.3, 0.3, 0), 1.0);
+    }
+
+    #[test]
+    fn standings_rank_by_significant_pairings_then_win_rate() {
+        let entrants = [entrant("a"), entrant("b"), entrant("c"), entrant("d")];
+        // b clearly best; a and c are indistinguishable; d clearly worst.
+        let results = [(0.60, 0.3), (0.95, 0.8), (0.61, 0.3), (0.10, 0.0)];
+        let report = bracket_from_results(&entrants, &results, 1000);
+
+        assert_eq!(report.matchups.len(), 6);
+        let names: Vec<&str> = report.standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["b", "c", "a", "d"]);
+        let b = &report.standings[0];
+        assert_eq!((b.rank, b.wins, b.losses, b.draws), (1, 3, 0, 0));
+        let c = &report.standings[1];
+        assert_eq!((c.rank, c.wins, c.losses, c.draws), (2, 1, 1, 1));
+        assert_eq!(report.standings[2].rank, 3);
+        let draw = report.matchups.iter().find(|m| m.a == "a" && m.b == "c").unwrap();
+        assert!(draw.winner.is_none());
+    }
+}
@@ -1,20 +1,101 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
 use crate::data::data_registry::DataRegistry;
 use crate::perf_log;
 use crate::data::import::load_imported_roster_ids_unlocked_only;
 use crate::data::profile_index::{profile_path, resolve_profile_id_for_api, ROSTER_IMPORTED};
+use crate::data::loader::{resolve_ship, resolve_ship_with_tier_level};
 use crate::data::officer::{load_canonical_officers, Officer, DEFAULT_CANONICAL_OFFICERS_PATH};
+use crate::data::ship::ShipRecord;
+use crate::combat::ShipType;
 
-/// Number of bridge officer slots (in addition to captain). Players typically crew 1 captain + 2 bridge.
+/// Bridge officer slots (in addition to captain) when ship data does not say otherwise.
 pub const BRIDGE_SLOTS: usize = 2;
-/// Number of below-decks officer slots. Assumed fixed for now; will be configurable by ship level later.
+/// Below-decks officer slots when ship data does not say otherwise.
 pub const BELOW_DECKS_SLOTS: usize = 3;
 
+/// Seats on one ship besides the captain's. Ships set their own counts
+/// ([ShipRecord::bridge_slots], [ShipRecord::below_decks_slots], which vary by tier); the
+/// default is [BRIDGE_SLOTS] and [BELOW_DECKS_SLOTS].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+pub struct CrewSlots {
+    pub bridge: usize,
+    pub below_decks: usize,
+}
+
+impl Default for CrewSlots {
+    fn default() -> Self {
+        Self {
+            bridge: BRIDGE_SLOTS,
+            below_decks: BELOW_DECKS_SLOTS,
+        }
+    }
+}
+
+impl CrewSlots {
+    pub fn for_ship(ship: &ShipRecord) -> Self {
+        let defaults = Self::default();
+        Self {
+            bridge: ship.bridge_slots.map_or(defaults.bridge, |n| n as usize),
+            below_decks: ship.below_decks_slots.map_or(defaults.below_decks, |n| n as usize),
+        }
+    }
+
+    /// `candidate` without seats past these counts: officers a ship has no seat for do not fight.
+    pub fn fit<'a>(&self, candidate: &'a CrewCandidate) -> Cow<'a, CrewCandidate> {
+        if candidate.bridge.len() <= self.bridge && candidate.below_decks.len() <= self.below_decks
+        {
+            return Cow::Borrowed(candidate);
+        }
+        let mut fitted = candidate.clone();
+        fitted.bridge.truncate(self.bridge);
+        fitted.below_decks.truncate(self.below_decks);
+        Cow::Owned(fitted)
+    }
+}
+
+/// Seats on `ship` at `tier` / `level`; the defaults when the ship does not resolve.
+pub fn ship_crew_slots(ship: &str, tier: Option<u32>, level: Option<u32>) -> CrewSlots {
+    resolve_ship_with_tier_level(ship, tier, level)
+        .map_or_else(CrewSlots::default, |record| CrewSlots::for_ship(&record))
+}
+/// Most partial crews generated for a roster too small to fill every seat when
+/// [CandidateStrategy::max_candidates] is `None`.
+pub const MAX_PARTIAL_CANDIDATES: usize = 100_000;
+
 /// Officer pools by slot, as names. Shared by crew generator and genetic optimizer.
 #[derive(Debug, Clone)]
 pub struct OfficerPools {
     pub captains: Vec<String>,
     pub bridge: Vec<String>,
     pub below_decks: Vec<String>,
+    /// Class of the ship being crewed, when it could be resolved.
+    pub ship_type: Option<ShipType>,
+    /// Officers whose class pairs with `ship_type`, used only to order the search; empty when
+    /// unknown.
+    pub in_class: Vec<String>,
+}
+
+impl OfficerPools {
+    /// Pools without the officers named in `excluded` (e.g. committed to a saved loadout);
+    /// `None` when no captain is left.
+    pub fn without(mut self, excluded: &[String]) -> Option<Self> {
+        if !excluded.is_empty() {
+            for pool in [
+                &mut self.captains,
+                &mut self.bridge,
+                &mut self.below_decks,
+                &mut self.in_class,
+            ] {
+                pool.retain(|name| !excluded.contains(name));
+            }
+        }
+        (!self.captains.is_empty()).then_some(self)
+    }
 }
 
 /// True if the officer has at least one ability with slot "below_decks".
@@ -25,11 +106,17 @@ fn has_below_decks_ability(officer: &Officer) -> bool {
         .any(|a| a.slot.eq_ignore_ascii_case("below_decks"))
 }
 
+/// Resolves the class of `ship` (id or name) from ship data; `None` when unknown.
+pub fn resolve_ship_type(ship: &str) -> Option<ShipType> {
+    resolve_ship(ship).map(|record| record.ship_type())
+}
+
 /// Builds officer pools from registry (no officer reload). Still loads roster for filter.
 pub fn build_officer_pools_from_registry(
     registry: &DataRegistry,
     only_below_decks_with_ability: bool,
     profile_id: Option<&str>,
+    ship_type: Option<ShipType>,
 ) -> Option<OfficerPools> {
     let officers: Vec<Officer> = registry
         .officers()
@@ -37,75 +124,26 @@ pub fn build_officer_pools_from_registry(
         .filter(|o| !o.name.trim().is_empty())
         .cloned()
         .collect();
-
-    const MIN_OFFICERS: usize = 1 + BRIDGE_SLOTS + BELOW_DECKS_SLOTS;
-    let mut officers = officers;
     let roster_path = profile_path(&resolve_profile_id_for_api(profile_id), ROSTER_IMPORTED)
         .to_string_lossy()
         .to_string();
-    if let Some(roster_ids) = load_imported_roster_ids_unlocked_only(&roster_path) {
-        if roster_ids.len() >= MIN_OFFICERS {
-            officers.retain(|officer| roster_ids.contains(&officer.id));
-        }
-    }
-
-    if officers.is_empty() {
-        return None;
-    }
-
-    let mut captains: Vec<String> = officers
-        .iter()
-        .filter(|officer| is_captain_eligible(officer))
-        .map(|o| o.name.clone())
-        .collect();
-    let mut bridge: Vec<String> = officers
-        .iter()
-        .filter(|officer| can_fill_position(officer, Position::Bridge))
-        .map(|o| o.name.clone())
-        .collect();
-    let mut below_decks: Vec<String> = officers
-        .iter()
-        .filter(|officer| can_fill_position(officer, Position::BelowDecks))
-        .map(|o| o.name.clone())
-        .collect();
-
-    if only_below_decks_with_ability {
-        below_decks = officers
-            .iter()
-            .filter(|officer| {
-                can_fill_position(officer, Position::BelowDecks)
-                    && has_below_decks_ability(officer)
-            })
-            .map(|o| o.name.clone())
-            .collect();
-    } else if below_decks.is_empty() {
-        below_decks = officers.iter().map(|o| o.name.clone()).collect();
-    }
-
-    if captains.is_empty() {
-        captains = officers.iter().map(|o| o.name.clone()).collect();
-    }
-    if bridge.is_empty() {
-        bridge = officers.iter().map(|o| o.name.clone()).collect();
-    }
-
-    if captains.is_empty() || bridge.len() < BRIDGE_SLOTS || below_decks.len() < BELOW_DECKS_SLOTS {
-        return None;
-    }
-
-    Some(OfficerPools {
-        captains,
-        bridge,
-        below_decks,
-    })
+    pools_from_officers(officers, &roster_path, only_below_decks_with_ability, ship_type)
 }
 
 /// Builds captain, bridge, and below-decks pools from loaded officers and roster filter.
 /// When `only_below_decks_with_ability` is true, the below-decks pool is restricted to officers
 /// that have a below-decks ability; no fallback to all officers is applied in that case.
-/// Returns `None` if there are not enough officers to form any valid crew.
+/// Returns `None` if there is no officer to captain; smaller pools yield partial crews.
 pub fn build_officer_pools(only_below_decks_with_ability: bool) -> Option<OfficerPools> {
-    let mut officers = load_canonical_officers(DEFAULT_CANONICAL_OFFICERS_PATH)
+    build_officer_pools_for_ship(only_below_decks_with_ability, None)
+}
+
+/// Like [build_officer_pools], also recording which officers are in-class for `ship_type`.
+pub fn build_officer_pools_for_ship(
+    only_below_decks_with_ability: bool,
+    ship_type: Option<ShipType>,
+) -> Option<OfficerPools> {
+    let officers = load_canonical_officers(DEFAULT_CANONICAL_OFFICERS_PATH)
         .map(|loaded| {
             loaded
                 .into_iter()
@@ -113,74 +151,162 @@ pub fn build_officer_pools(only_below_decks_with_ability: bool) -> Option<Office
                 .collect::<Vec<_>>()
         })
         .unwrap_or_default();
-
-    const MIN_OFFICERS: usize = 1 + BRIDGE_SLOTS + BELOW_DECKS_SLOTS;
     let roster_path = profile_path(&resolve_profile_id_for_api(None), ROSTER_IMPORTED)
         .to_string_lossy()
         .to_string();
-    if let Some(roster_ids) = load_imported_roster_ids_unlocked_only(&roster_path) {
+    pools_from_officers(officers, &roster_path, only_below_decks_with_ability, ship_type)
+}
+
+/// Splits officers into seat pools. Seat restrictions come from [Officer::seat_eligibility]:
+/// below-decks-only officers never reach the captain or bridge pools.
+fn pools_from_officers(
+    mut officers: Vec<Officer>,
+    roster_path: &str,
+    only_below_decks_with_ability: bool,
+    ship_type: Option<ShipType>,
+) -> Option<OfficerPools> {
+    const MIN_OFFICERS: usize = 1 + BRIDGE_SLOTS + BELOW_DECKS_SLOTS;
+    if let Some(roster_ids) = load_imported_roster_ids_unlocked_only(roster_path) {
         if roster_ids.len() >= MIN_OFFICERS {
             officers.retain(|officer| roster_ids.contains(&officer.id));
         }
     }
 
+    // Crews identify officers by name; keep one officer per name so candidate counts are exact.
+    let mut seen_names = HashSet::new();
+    officers.retain(|officer| seen_names.insert(officer.name.clone()));
     if officers.is_empty() {
         return None;
     }
 
-    let mut captains: Vec<String> = officers
-        .iter()
-        .filter(|officer| is_captain_eligible(officer))
-        .map(|o| o.name.clone())
-        .collect();
-    let mut bridge: Vec<String> = officers
-        .iter()
-        .filter(|officer| can_fill_position(officer, Position::Bridge))
-        .map(|o| o.name.clone())
-        .collect();
-    let mut below_decks: Vec<String> = officers
-        .iter()
-        .filter(|officer| can_fill_position(officer, Position::BelowDecks))
-        .map(|o| o.name.clone())
-        .collect();
-
-    if only_below_decks_with_ability {
-        below_decks = officers
+    let names_where = |pred: &dyn Fn(&Officer) -> bool| -> Vec<String> {
+        officers
             .iter()
-            .filter(|officer| {
-                can_fill_position(officer, Position::BelowDecks)
-                    && has_below_decks_ability(officer)
-            })
+            .filter(|officer| pred(officer))
             .map(|o| o.name.clone())
-            .collect();
+            .collect()
+    };
+
+    let mut captains = names_where(&|officer| officer.seat_eligibility().captain);
+    let mut bridge = names_where(&|officer| officer.seat_eligibility().bridge);
+    let mut below_decks = if only_below_decks_with_ability {
         // Do not fallback to all officers when user requested this filter.
-    } else if below_decks.is_empty() {
-        below_decks = officers.iter().map(|o| o.name.clone()).collect();
+        names_where(&|officer| {
+            officer.seat_eligibility().below_decks && has_below_decks_ability(officer)
+        })
+    } else {
+        names_where(&|officer| officer.seat_eligibility().below_decks)
+    };
+    if !only_below_decks_with_ability && below_decks.is_empty() {
+        below_decks = names_where(&|_| true);
     }
 
     if captains.is_empty() {
-        captains = officers.iter().map(|o| o.name.clone()).collect();
+        captains = names_where(&|officer| !officer.is_below_decks_only());
     }
     if bridge.is_empty() {
-        bridge = officers.iter().map(|o| o.name.clone()).collect();
+        bridge = names_where(&|_| true);
     }
 
-    if captains.is_empty() || bridge.len() < BRIDGE_SLOTS || below_decks.len() < BELOW_DECKS_SLOTS {
+    if captains.is_empty() {
         return None;
     }
 
+    let in_class = match ship_type {
+        Some(ship_type) => names_where(&|officer| officer.is_in_class(ship_type)),
+        None => Vec::new(),
+    };
+
     Some(OfficerPools {
         captains,
         bridge,
         below_decks,
+        ship_type,
+        in_class,
     })
 }
 
+/// One crew. Seats are positional: `None` is an empty seat (a roster too small to fill the ship),
+/// and seats past the end of `bridge` / `below_decks` are empty too. Empty seats contribute no
+/// ability; they are never filled with a repeat of another officer. Build one with
+/// [CrewCandidate::new] or, with empty seats, [CrewCandidate::padded].
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct CrewCandidate {
     pub captain: String,
-    pub bridge: Vec<String>,
-    pub below_decks: Vec<String>,
+    pub bridge: Vec<Option<String>>,
+    pub below_decks: Vec<Option<String>>,
+}
+
+impl CrewCandidate {
+    /// Crew with every listed officer seated.
+    pub fn new(captain: impl Into<String>, bridge: Vec<String>, below_decks: Vec<String>) -> Self {
+        Self {
+            captain: captain.into(),
+            bridge: bridge.into_iter().map(Some).collect(),
+            below_decks: below_decks.into_iter().map(Some).collect(),
+        }
+    }
+
+    /// Officers in occupied bridge seats, in seat order.
+    pub fn bridge_officers(&self) -> impl Iterator<Item = &str> + '_ {
+        occupied(&self.bridge)
+    }
+
+    /// Officers in occupied below-decks seats, in seat order.
+    pub fn below_deck_officers(&self) -> impl Iterator<Item = &str> + '_ {
+        occupied(&self.below_decks)
+    }
+
+    /// Crew from occupied seat lists, padded with empty seats to `slots` (longer lists are cut).
+    pub fn padded(
+        captain: impl Into<String>,
+        bridge: Vec<String>,
+        below_decks: Vec<String>,
+        slots: CrewSlots,
+    ) -> Self {
+        let seats = |names: Vec<String>, slots: usize| -> Vec<Option<String>> {
+            let mut seats: Vec<Option<String>> = names.into_iter().take(slots).map(Some).collect();
+            seats.resize(slots, None);
+            seats
+        };
+        Self {
+            captain: captain.into(),
+            bridge: seats(bridge, slots.bridge),
+            below_decks: seats(below_decks, slots.below_decks),
+        }
+    }
+
+    /// Occupied bridge seats as owned names (the shape ranked results and API responses use).
+    pub fn bridge_names(&self) -> Vec<String> {
+        self.bridge_officers().map(str::to_string).collect()
+    }
+
+    /// Occupied below-decks seats as owned names.
+    pub fn below_deck_names(&self) -> Vec<String> {
+        self.below_deck_officers().map(str::to_string).collect()
+    }
+
+    /// Captain, then bridge, then below-decks officers, skipping empty seats.
+    pub fn officers(&self) -> impl Iterator<Item = &str> + '_ {
+        std::iter::once(self.captain.as_str())
+            .filter(|name| !name.is_empty())
+            .chain(self.bridge_officers())
+            .chain(self.below_deck_officers())
+    }
+
+    /// True when any bridge or below-decks seat is empty.
+    pub fn is_partial(&self) -> bool {
+        self.bridge_officers().count() < self.bridge.len()
+            || self.below_deck_officers().count() < self.below_decks.len()
+    }
+}
+
+fn occupied(seats: &[Option<String>]) -> impl Iterator<Item = &str> + '_ {
+    seats
+        .iter()
+        .filter_map(|seat| seat.as_deref())
+        .filter(|name| !name.trim().is_empty())
 }
 
 #[derive(Debug, Clone)]
@@ -193,6 +319,13 @@ pub struct CandidateStrategy {
     pub use_seeded_shuffle: bool,
     /// When true, below-decks pool only includes officers that have a below-decks ability.
     pub only_below_decks_with_ability: bool,
+    /// When true and the ship class is known, in-class captains and bridge officers are tried
+    /// first. Off by default: the reordering changes which crews a `max_candidates` cap keeps.
+    pub prefer_in_class: bool,
+    /// Seats to fill. None = the ship's at its default tier (see [ship_crew_slots]).
+    pub crew_slots: Option<CrewSlots>,
+    /// Officer names never placed in any seat.
+    pub excluded_officers: Vec<String>,
 }
 
 impl Default for CandidateStrategy {
@@ -204,6 +337,9 @@ impl Default for CandidateStrategy {
             large_pool_bridge_limit: 12,
             use_seeded_shuffle: true,
             only_below_decks_with_ability: false,
+            prefer_in_class: false,
+            crew_slots: None,
+            excluded_officers: Vec::new(),
         }
     }
 }
@@ -225,11 +361,10 @@ impl CrewGenerator {
     }
 
     pub fn generate_candidates(&self, ship: &str, hostile: &str, seed: u64) -> Vec<CrewCandidate> {
-        let mut pools = match build_officer_pools(self.strategy.only_below_decks_with_ability) {
-            Some(p) => p,
-            None => return Vec::new(),
-        };
-        self.generate_candidates_from_pools(&mut pools, ship, hostile, seed)
+        let t0 = perf_log::perf_start();
+        let out = self.candidate_iter(ship, hostile, seed).collect();
+        perf_log::log_duration("crew_generator.generate_candidates", t0);
+        out
     }
 
     /// Like [generate_candidates] but uses registry for officers (no reload).
@@ -241,65 +376,75 @@ impl CrewGenerator {
         seed: u64,
         profile_id: Option<&str>,
     ) -> Vec<CrewCandidate> {
-        let mut pools = match build_officer_pools_from_registry(
+        let t0 = perf_log::perf_start();
+        let out = self
+            .candidate_iter_from_registry(registry, ship, hostile, seed, profile_id)
+            .collect();
+        perf_log::log_duration("crew_generator.generate_candidates", t0);
+        out
+    }
+
+    /// Lazily yields the same candidates as [generate_candidates], in the same order.
+    pub fn candidate_iter(&self, ship: &str, hostile: &str, seed: u64) -> CandidateIterator {
+        let record = resolve_ship(ship);
+        let pools = build_officer_pools_for_ship(
+            self.strategy.only_below_decks_with_ability,
+            record.as_ref().map(ShipRecord::ship_type),
+        )
+        .and_then(|pools| pools.without(&self.strategy.excluded_officers));
+        let slots = self.crew_slots(record.as_ref());
+        self.iter_from_pools(pools, slots, ship, hostile, seed)
+    }
+
+    /// Like [candidate_iter] but uses registry for officers (no reload).
+    pub fn candidate_iter_from_registry(
+        &self,
+        registry: &DataRegistry,
+        ship: &str,
+        hostile: &str,
+        seed: u64,
+        profile_id: Option<&str>,
+    ) -> CandidateIterator {
+        let record = registry.resolve_ship(ship);
+        let pools = build_officer_pools_from_registry(
             registry,
             self.strategy.only_below_decks_with_ability,
             profile_id,
-        ) {
-            Some(p) => p,
-            None => return Vec::new(),
-        };
-        self.generate_candidates_from_pools(&mut pools, ship, hostile, seed)
+            record.as_ref().map(ShipRecord::ship_type),
+        )
+        .and_then(|pools| pools.without(&self.strategy.excluded_officers));
+        let slots = self.crew_slots(record.as_ref());
+        self.iter_from_pools(pools, slots, ship, hostile, seed)
+    }
+
+    /// [CandidateStrategy::crew_slots], else the seats of `record` at its default tier.
+    fn crew_slots(&self, record: Option<&ShipRecord>) -> CrewSlots {
+        self.strategy
+            .crew_slots
+            .unwrap_or_else(|| record.map_or_else(CrewSlots::default, CrewSlots::for_ship))
     }
 
-    fn generate_candidates_from_pools(
+    fn iter_from_pools(
         &self,
-        pools: &mut OfficerPools,
+        pools: Option<OfficerPools>,
+        slots: CrewSlots,
         ship: &str,
         hostile: &str,
         seed: u64,
-    ) -> Vec<CrewCandidate> {
-        let t0 = perf_log::perf_start();
-        if self.strategy.use_seeded_shuffle {
-            let base_seed = mix_seed(seed, ship, hostile);
-            deterministic_shuffle(&mut pools.captains, base_seed);
-            deterministic_shuffle(&mut pools.bridge, base_seed ^ 0x9E37_79B9_7F4A_7C15);
-            deterministic_shuffle(&mut pools.below_decks, base_seed ^ 0x517C_C1B7_2722_0A95);
+    ) -> CandidateIterator {
+        match pools {
+            Some(mut pools) => {
+                self.order_pools(&mut pools, ship, hostile, seed);
+                CandidateIterator::new(pools, &self.strategy, slots, ship, hostile, seed)
+            }
+            None => CandidateIterator::empty(),
         }
-
-        let min_pool = pools
-            .captains
-            .len()
-            .min(pools.bridge.len())
-            .min(pools.below_decks.len());
-        let out = if min_pool <= self.strategy.exhaustive_pool_threshold {
-            exhaustive_candidates(
-                &pools.captains,
-                &pools.bridge,
-                &pools.below_decks,
-                self.strategy.max_candidates,
-            )
-        } else {
-            sampled_candidates(
-                &pools.captains,
-                &pools.bridge,
-                &pools.below_decks,
-                &self.strategy,
-                mix_seed(seed ^ 0xA5A5_A5A5_A5A5_A5A5, ship, hostile),
-            )
-        };
-        perf_log::log_duration("crew_generator.generate_candidates_from_pools", t0);
-        out
     }
 
-    /// Returns the number of crew combinations without allocating candidates.
-    /// Used for estimate when no cap is set. Uses same exhaustive/sampled branch as generate_candidates.
+    /// Number of candidates [generate_candidates] would return (honoring `max_candidates`),
+    /// computed from pool sizes without enumerating crews.
     pub fn count_candidates(&self, ship: &str, hostile: &str, seed: u64) -> usize {
-        let mut pools = match build_officer_pools(self.strategy.only_below_decks_with_ability) {
-            Some(p) => p,
-            None => return 0,
-        };
-        self.count_candidates_from_pools(&mut pools, ship, hostile, seed)
+        self.candidate_iter(ship, hostile, seed).len()
     }
 
     /// Like [count_candidates] but uses registry for officers (no reload).
@@ -311,318 +456,418 @@ impl CrewGenerator {
         seed: u64,
         profile_id: Option<&str>,
     ) -> usize {
-        let mut pools = match build_officer_pools_from_registry(
-            registry,
-            self.strategy.only_below_decks_with_ability,
-            profile_id,
-        ) {
-            Some(p) => p,
-            None => return 0,
-        };
-        self.count_candidates_from_pools(&mut pools, ship, hostile, seed)
+        self.candidate_iter_from_registry(registry, ship, hostile, seed, profile_id)
+            .len()
     }
 
-    fn count_candidates_from_pools(
-        &self,
-        pools: &mut OfficerPools,
-        ship: &str,
-        hostile: &str,
-        seed: u64,
-    ) -> usize {
+    fn order_pools(&self, pools: &mut OfficerPools, ship: &str, hostile: &str, seed: u64) {
         if self.strategy.use_seeded_shuffle {
             let base_seed = mix_seed(seed, ship, hostile);
             deterministic_shuffle(&mut pools.captains, base_seed);
             deterministic_shuffle(&mut pools.bridge, base_seed ^ 0x9E37_79B9_7F4A_7C15);
             deterministic_shuffle(&mut pools.below_decks, base_seed ^ 0x517C_C1B7_2722_0A95);
         }
+        if self.strategy.prefer_in_class {
+            in_class_first(pools);
+        }
+    }
+}
+
+/// How [CandidateIterator] walks the pools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Enumeration {
+    /// Every captain, bridge combination, and below-decks combination (indices increasing).
+    Exhaustive,
+    /// Captain and bridge prefixes, every `stride`-th below-decks officer, and below-decks
+    /// combinations; three below-decks seats use triples `j > i`, `k >= i + 2`, `k != j` (the
+    /// historical sampled walk).
+    Sampled { stride: usize },
+}
+
+/// Lazy crew enumeration over ordered pools. Yields exactly the sequence the former
+/// Vec-building generator produced, without holding more than one crew at a time.
+/// [ExactSizeIterator::len] is computed from pool sizes, so counting never enumerates.
+///
+/// When the pools cannot fill every seat of any crew (a low-level roster), the iterator instead
+/// yields partial crews (see [partial_crews]), built up front since there are few of them.
+#[derive(Debug, Clone)]
+pub struct CandidateIterator {
+    pools: OfficerPools,
+    slots: CrewSlots,
+    enumeration: Enumeration,
+    captain_limit: usize,
+    bridge_limit: usize,
+    /// Current captain index and bridge indices; `None` before the first prefix.
+    prefix: Option<(usize, Vec<usize>)>,
+    /// Below-decks indices eligible for the current prefix.
+    below: Vec<usize>,
+    /// Positions in `below` of the last yielded below-decks combination.
+    below_combo: Option<Vec<usize>>,
+    /// Partial crews still to yield, last first; empty when full crews exist.
+    partial: Vec<CrewCandidate>,
+    remaining: usize,
+}
 
+impl CandidateIterator {
+    fn new(
+        pools: OfficerPools,
+        strategy: &CandidateStrategy,
+        slots: CrewSlots,
+        ship: &str,
+        hostile: &str,
+        seed: u64,
+    ) -> Self {
         let min_pool = pools
             .captains
             .len()
             .min(pools.bridge.len())
             .min(pools.below_decks.len());
-        if min_pool <= self.strategy.exhaustive_pool_threshold {
-            exhaustive_count(
-                &pools.captains,
-                &pools.bridge,
-                &pools.below_decks,
-                None,
-            )
-        } else {
-            sampled_count(
-                &pools.captains,
-                &pools.bridge,
-                &pools.below_decks,
-                &self.strategy,
-                mix_seed(seed ^ 0xA5A5_A5A5_A5A5_A5A5, ship, hostile),
-                None,
-            )
+        let (enumeration, captain_limit, bridge_limit) =
+            if min_pool <= strategy.exhaustive_pool_threshold {
+                (Enumeration::Exhaustive, pools.captains.len(), pools.bridge.len())
+            } else {
+                let sample_seed = mix_seed(seed ^ 0xA5A5_A5A5_A5A5_A5A5, ship, hostile);
+                (
+                    Enumeration::Sampled {
+                        stride: ((sample_seed as usize) % 5) + 1,
+                    },
+                    strategy.large_pool_captain_limit.max(1).min(pools.captains.len()),
+                    strategy
+                        .large_pool_bridge_limit
+                        .max(slots.bridge)
+                        .min(pools.bridge.len()),
+                )
+            };
+        let mut iter = Self {
+            pools,
+            slots,
+            enumeration,
+            captain_limit,
+            bridge_limit,
+            prefix: None,
+            below: Vec::new(),
+            below_combo: None,
+            partial: Vec::new(),
+            remaining: 0,
+        };
+        let total = iter.uncapped_total();
+        if total == 0 {
+            let cap = strategy.max_candidates.unwrap_or(MAX_PARTIAL_CANDIDATES);
+            iter.partial = partial_crews(&iter.pools, slots, cap);
+            iter.partial.reverse();
+            iter.remaining = iter.partial.len();
+            return iter;
         }
+        iter.remaining = strategy.max_candidates.map_or(total, |cap| total.min(cap));
+        iter
     }
-}
 
-#[derive(Copy, Clone)]
-enum Position {
-    Bridge,
-    BelowDecks,
-}
+    fn empty() -> Self {
+        Self {
+            pools: OfficerPools {
+                captains: Vec::new(),
+                bridge: Vec::new(),
+                below_decks: Vec::new(),
+                ship_type: None,
+                in_class: Vec::new(),
+            },
+            slots: CrewSlots::default(),
+            enumeration: Enumeration::Exhaustive,
+            captain_limit: 0,
+            bridge_limit: 0,
+            prefix: None,
+            below: Vec::new(),
+            below_combo: None,
+            partial: Vec::new(),
+            remaining: 0,
+        }
+    }
 
-fn is_captain_eligible(officer: &Officer) -> bool {
-    officer
-        .abilities
-        .iter()
-        .any(|ability| ability.slot == "captain")
-}
+    /// Ordered pools the iterator walks (after shuffle and in-class ordering).
+    pub fn pools(&self) -> &OfficerPools {
+        &self.pools
+    }
 
-/// True if `name` equals captain or any bridge officer (distinct-officer checks).
-#[inline]
-fn name_conflicts_bridge_captain(name: &str, captain: &str, b1: &str, b2: &str) -> bool {
-    name == captain || name == b1 || name == b2
-}
+    /// Seats each yielded crew fills.
+    pub fn slots(&self) -> CrewSlots {
+        self.slots
+    }
 
-/// True if `d3` is already used in captain, bridge, or first two below-decks picks.
-#[inline]
-fn below_third_conflicts(
-    d3: &str,
-    captain: &str,
-    b1: &str,
-    b2: &str,
-    d1: &str,
-    d2: &str,
-) -> bool {
-    d3 == captain || d3 == b1 || d3 == b2 || d3 == d1 || d3 == d2
-}
+    fn below_selected(&self) -> impl Iterator<Item = usize> + '_ {
+        let step = match self.enumeration {
+            Enumeration::Exhaustive => 1,
+            Enumeration::Sampled { stride } => stride,
+        };
+        (0..self.pools.below_decks.len()).step_by(step)
+    }
 
-fn can_fill_position(officer: &Officer, position: Position) -> bool {
-    let Some(slot) = officer.slot.as_deref() else {
-        return true;
-    };
+    /// True when below-decks combinations follow the historical sampled triple walk.
+    fn sampled_triples(&self) -> bool {
+        matches!(self.enumeration, Enumeration::Sampled { .. }) && self.slots.below_decks == 3
+    }
 
-    match slot.to_ascii_lowercase().as_str() {
-        "captain" => matches!(position, Position::Bridge),
-        "bridge" | "officer" => matches!(position, Position::Bridge),
-        "below_decks" => matches!(position, Position::BelowDecks),
-        _ => true,
+    /// Below-decks combinations available from `m` eligible officers.
+    fn below_combos_for(&self, m: usize) -> u128 {
+        if self.sampled_triples() {
+            // Sum over i of (r - 1)^2 with r = m - 1 - i officers after position i.
+            let m = m as u128;
+            return if m >= 2 { (m - 2) * (m - 1) * (2 * m - 3) / 6 } else { 0 };
+        }
+        choose(m, self.slots.below_decks)
     }
-}
 
-fn exhaustive_candidates(
-    captains: &[String],
-    bridge: &[String],
-    below_decks: &[String],
-    max_candidates: Option<usize>,
-) -> Vec<CrewCandidate> {
-    let reserve = max_candidates.unwrap_or(256).min(4096);
-    let mut candidates = Vec::with_capacity(reserve);
-
-    for captain in captains {
-        for (i, b1) in bridge.iter().enumerate() {
-            if b1 == captain {
-                continue;
-            }
-            for b2 in bridge.iter().skip(i + 1) {
-                if b2 == captain || b2 == b1 {
+    /// Total crews in the walk, from pool sizes: for each captain, bridge combinations are
+    /// grouped by how many of their names also sit in the selected below-decks pool.
+    fn uncapped_total(&self) -> usize {
+        let selected: HashSet<&str> = self
+            .below_selected()
+            .map(|i| self.pools.below_decks[i].as_str())
+            .collect();
+        let s = selected.len();
+        let b = self.slots.bridge;
+        let mut total: u128 = 0;
+        for captain in self.pools.captains.iter().take(self.captain_limit) {
+            let captain_in = usize::from(selected.contains(captain.as_str()));
+            let (mut p, mut q) = (0usize, 0usize);
+            for name in self.pools.bridge.iter().take(self.bridge_limit) {
+                if name == captain {
                     continue;
                 }
-                for (di, d1) in below_decks.iter().enumerate() {
-                    if name_conflicts_bridge_captain(d1, captain, b1, b2) {
-                        continue;
-                    }
-                    for (dj, d2) in below_decks.iter().enumerate().skip(di + 1) {
-                        if name_conflicts_bridge_captain(d2, captain, b1, b2) || d2 == d1 {
-                            continue;
-                        }
-                        for d3 in below_decks.iter().skip(dj + 1) {
-                            if below_third_conflicts(d3, captain, b1, b2, d1, d2) {
-                                continue;
-                            }
-                            candidates.push(CrewCandidate {
-                                captain: captain.clone(),
-                                bridge: vec![b1.clone(), b2.clone()],
-                                below_decks: vec![d1.clone(), d2.clone(), d3.clone()],
-                            });
-                            if let Some(cap) = max_candidates {
-                                if candidates.len() >= cap {
-                                    return candidates;
-                                }
-                            }
-                        }
-                    }
+                if selected.contains(name.as_str()) {
+                    p += 1;
+                } else {
+                    q += 1;
                 }
             }
+            let base = s - captain_in;
+            for shared in 0..=b {
+                total += choose(p, shared)
+                    * choose(q, b - shared)
+                    * self.below_combos_for(base.saturating_sub(shared));
+            }
         }
+        usize::try_from(total).unwrap_or(usize::MAX)
     }
 
-    candidates
-}
-
-fn exhaustive_count(
-    captains: &[String],
-    bridge: &[String],
-    below_decks: &[String],
-    max_count: Option<usize>,
-) -> usize {
-    const ESTIMATE_CAP: usize = 2_000_000;
-    let mut count = 0_usize;
-
-    for captain in captains {
-        for (i, b1) in bridge.iter().enumerate() {
-            if b1 == captain {
-                continue;
-            }
-            for b2 in bridge.iter().skip(i + 1) {
-                if b2 == captain || b2 == b1 {
-                    continue;
+    /// Moves to the next valid (captain, bridge combination) and rebuilds the eligible
+    /// below-decks list.
+    fn advance_prefix(&mut self) -> bool {
+        let (b, n) = (self.slots.bridge, self.bridge_limit);
+        if b > n {
+            return false;
+        }
+        let first = || (0..b).collect::<Vec<usize>>();
+        let (mut c, mut bridge) = match self.prefix.take() {
+            None => (0, first()),
+            Some((c, mut bridge)) => {
+                if next_combination(&mut bridge, n) {
+                    (c, bridge)
+                } else {
+                    (c + 1, first())
                 }
-                for (di, d1) in below_decks.iter().enumerate() {
-                    if name_conflicts_bridge_captain(d1, captain, b1, b2) {
-                        continue;
-                    }
-                    for (dj, d2) in below_decks.iter().enumerate().skip(di + 1) {
-                        if name_conflicts_bridge_captain(d2, captain, b1, b2) || d2 == d1 {
-                            continue;
-                        }
-                        for d3 in below_decks.iter().skip(dj + 1) {
-                            if below_third_conflicts(d3, captain, b1, b2, d1, d2) {
-                                continue;
-                            }
-                            count += 1;
-                            if let Some(cap) = max_count {
-                                if count >= cap {
-                                    return count;
-                                }
-                            }
-                            if count >= ESTIMATE_CAP {
-                                return ESTIMATE_CAP;
-                            }
-                        }
-                    }
+            }
+        };
+        loop {
+            if c >= self.captain_limit {
+                return false;
+            }
+            let captain = &self.pools.captains[c];
+            let names = &self.pools.bridge;
+            let clash = bridge.iter().enumerate().any(|(x, &i)| {
+                names[i] == *captain || bridge[..x].iter().any(|&j| names[j] == names[i])
+            });
+            if clash {
+                if !next_combination(&mut bridge, n) {
+                    c += 1;
+                    bridge = first();
                 }
+                continue;
             }
+            let below: Vec<usize> = self
+                .below_selected()
+                .filter(|&i| {
+                    let name = &self.pools.below_decks[i];
+                    name != captain && !bridge.iter().any(|&j| names[j] == *name)
+                })
+                .collect();
+            self.below = below;
+            self.prefix = Some((c, bridge));
+            self.below_combo = None;
+            return true;
         }
     }
 
-    count
-}
+    fn k_start(&self, i: usize, j: usize) -> usize {
+        match self.enumeration {
+            Enumeration::Exhaustive => j + 1,
+            Enumeration::Sampled { .. } => i + 2,
+        }
+    }
+
+    /// Next below-decks combination (positions in `below`) for the current prefix.
+    fn advance_below(&mut self) -> bool {
+        if self.sampled_triples() {
+            return self.advance_sampled_triple();
+        }
+        let m = self.below.len();
+        match &mut self.below_combo {
+            Some(combo) => next_combination(combo, m),
+            None if self.slots.below_decks <= m => {
+                self.below_combo = Some((0..self.slots.below_decks).collect());
+                true
+            }
+            None => false,
+        }
+    }
 
-fn sampled_candidates(
-    captains: &[String],
-    bridge: &[String],
-    below_decks: &[String],
-    strategy: &CandidateStrategy,
-    seed: u64,
-) -> Vec<CrewCandidate> {
-    let captain_limit = strategy.large_pool_captain_limit.max(1).min(captains.len());
-    let bridge_limit = strategy.large_pool_bridge_limit.max(2).min(bridge.len());
-    let reserve = strategy.max_candidates.unwrap_or(256).min(4096);
-    let mut candidates = Vec::with_capacity(reserve);
-    let stride = ((seed as usize) % 5) + 1;
-
-    for captain in captains.iter().take(captain_limit) {
-        for (bi, b1) in bridge.iter().take(bridge_limit).enumerate() {
-            if b1 == captain {
+    fn advance_sampled_triple(&mut self) -> bool {
+        let m = self.below.len();
+        let [mut i, mut j, mut k] = match self.below_combo.as_deref() {
+            Some(&[i, j, k]) => [i, j, k + 1],
+            _ => [0, 1, self.k_start(0, 1)],
+        };
+        loop {
+            if i >= m {
+                return false;
+            }
+            if j >= m {
+                i += 1;
+                j = i + 1;
+                k = self.k_start(i, j);
                 continue;
             }
-            for b2 in bridge.iter().take(bridge_limit).skip(bi + 1) {
-                if b2 == captain || b2 == b1 {
-                    continue;
-                }
-                let below_indices: Vec<usize> = (0..below_decks.len())
-                    .step_by(stride)
-                    .filter(|&i| {
-                        !name_conflicts_bridge_captain(below_decks[i].as_str(), captain, b1, b2)
-                    })
-                    .collect();
-                for (ii, &di) in below_indices.iter().enumerate() {
-                    let d1 = &below_decks[di];
-                    for &dj in below_indices.iter().skip(ii + 1) {
-                        let d2 = &below_decks[dj];
-                        if d2 == d1 || name_conflicts_bridge_captain(d2, captain, b1, b2) {
-                            continue;
-                        }
-                        for &dk in below_indices.iter().skip(ii + 2) {
-                            let d3 = &below_decks[dk];
-                            if below_third_conflicts(d3, captain, b1, b2, d1, d2) {
-                                continue;
-                            }
-                            candidates.push(CrewCandidate {
-                                captain: captain.clone(),
-                                bridge: vec![b1.clone(), b2.clone()],
-                                below_decks: vec![d1.clone(), d2.clone(), d3.clone()],
-                            });
-                            if let Some(cap) = strategy.max_candidates {
-                                if candidates.len() >= cap {
-                                    return candidates;
-                                }
-                            }
-                        }
-                    }
-                }
+            if k >= m {
+                j += 1;
+                k = self.k_start(i, j);
+                continue;
             }
+            if k == j {
+                k += 1;
+                continue;
+            }
+            self.below_combo = Some(vec![i, j, k]);
+            return true;
         }
     }
-
-    candidates
 }
 
-fn sampled_count(
-    captains: &[String],
-    bridge: &[String],
-    below_decks: &[String],
-    strategy: &CandidateStrategy,
-    seed: u64,
-    max_count: Option<usize>,
-) -> usize {
-    let captain_limit = strategy.large_pool_captain_limit.max(1).min(captains.len());
-    let bridge_limit = strategy.large_pool_bridge_limit.max(2).min(bridge.len());
-    let mut count = 0_usize;
-    let stride = ((seed as usize) % 5) + 1;
-    const ESTIMATE_CAP: usize = 2_000_000;
-
-    for captain in captains.iter().take(captain_limit) {
-        for (bi, b1) in bridge.iter().take(bridge_limit).enumerate() {
-            if b1 == captain {
-                continue;
+impl Iterator for CandidateIterator {
+    type Item = CrewCandidate;
+
+    fn next(&mut self) -> Option<CrewCandidate> {
+        if self.remaining == 0 {
+            return None;
+        }
+        if let Some(candidate) = self.partial.pop() {
+            self.remaining -= 1;
+            return Some(candidate);
+        }
+        loop {
+            if self.prefix.is_some() && self.advance_below() {
+                let (c, bridge) = self.prefix.as_ref().expect("prefix set");
+                let combo = self.below_combo.as_ref().expect("combination set");
+                let pools = &self.pools;
+                self.remaining -= 1;
+                return Some(CrewCandidate::new(
+                    pools.captains[*c].clone(),
+                    bridge.iter().map(|&b| pools.bridge[b].clone()).collect(),
+                    combo
+                        .iter()
+                        .map(|&i| pools.below_decks[self.below[i]].clone())
+                        .collect(),
+                ));
             }
-            for b2 in bridge.iter().take(bridge_limit).skip(bi + 1) {
-                if b2 == captain || b2 == b1 {
-                    continue;
-                }
-                let below_indices: Vec<usize> = (0..below_decks.len())
-                    .step_by(stride)
-                    .filter(|&i| {
-                        !name_conflicts_bridge_captain(below_decks[i].as_str(), captain, b1, b2)
-                    })
-                    .collect();
-                for (ii, &di) in below_indices.iter().enumerate() {
-                    let d1 = &below_decks[di];
-                    for &dj in below_indices.iter().skip(ii + 1) {
-                        let d2 = &below_decks[dj];
-                        if d2 == d1 || name_conflicts_bridge_captain(d2, captain, b1, b2) {
-                            continue;
-                        }
-                        for &dk in below_indices.iter().skip(ii + 2) {
-                            let d3 = &below_decks[dk];
-                            if below_third_conflicts(d3, captain, b1, b2, d1, d2) {
-                                continue;
-                            }
-                            count += 1;
-                            if let Some(cap) = max_count {
-                                if count >= cap {
-                                    return count;
-                                }
-                            }
-                            if count >= ESTIMATE_CAP {
-                                return ESTIMATE_CAP;
-                            }
-                        }
-                    }
+            if !self.advance_prefix() {
+                self.remaining = 0;
+                return None;
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for CandidateIterator {}
+
+/// Crews for pools too small to seat a full crew: every captain with as many distinct bridge and
+/// then below-decks officers as remain (all combinations), the other seats left empty. At most
+/// `cap` crews.
+fn partial_crews(pools: &OfficerPools, slots: CrewSlots, cap: usize) -> Vec<CrewCandidate> {
+    let owned = |names: Vec<&String>| names.into_iter().cloned().collect::<Vec<_>>();
+    let mut out = Vec::new();
+    for captain in &pools.captains {
+        let bridge_pool: Vec<&String> = pools.bridge.iter().filter(|n| *n != captain).collect();
+        for bridge in combinations(&bridge_pool, slots.bridge.min(bridge_pool.len())) {
+            let below_pool: Vec<&String> = pools
+                .below_decks
+                .iter()
+                .filter(|n| *n != captain && !bridge.contains(n))
+                .collect();
+            for below in combinations(&below_pool, slots.below_decks.min(below_pool.len())) {
+                if out.len() >= cap {
+                    return out;
                 }
+                out.push(CrewCandidate::padded(
+                    captain.clone(),
+                    owned(bridge.clone()),
+                    owned(below),
+                    slots,
+                ));
             }
         }
     }
+    out
+}
+
+/// All `k`-element combinations of `items`, in index order.
+fn combinations<'a>(items: &[&'a String], k: usize) -> Vec<Vec<&'a String>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut out = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, *item);
+            out.push(rest);
+        }
+    }
+    out
+}
+
+/// Advances `combo` (increasing indices below `n`) to the next combination in lexicographic
+/// order; false, leaving it unchanged, after the last.
+fn next_combination(combo: &mut [usize], n: usize) -> bool {
+    let k = combo.len();
+    let Some(pos) = (0..k).rev().find(|&pos| combo[pos] < n - k + pos) else {
+        return false;
+    };
+    combo[pos] += 1;
+    for next in pos + 1..k {
+        combo[next] = combo[next - 1] + 1;
+    }
+    true
+}
+
+/// Binomial coefficient C(n, k).
+fn choose(n: usize, k: usize) -> u128 {
+    if k > n {
+        return 0;
+    }
+    (0..k).fold(1u128, |acc, i| acc * (n - i) as u128 / (i as u128 + 1))
+}
 
-    count
+/// Stable-partitions captains and bridge so in-class officers come first.
+fn in_class_first(pools: &mut OfficerPools) {
+    if pools.in_class.is_empty() {
+        return;
+    }
+    let in_class = std::mem::take(&mut pools.in_class);
+    let is_in = |name: &String| in_class.contains(name);
+    pools.captains.sort_by_key(|name| !is_in(name));
+    pools.bridge.sort_by_key(|name| !is_in(name));
+    pools.in_class = in_class;
 }
 
 fn deterministic_shuffle<T>(items: &mut [T], seed: u64) {
@@ -653,7 +898,76 @@ fn mix_seed(seed: u64, ship: &str, hostile: &str) -> u64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{CandidateStrategy, CrewGenerator};
+    use super::{
+        in_class_first, pools_from_officers, CandidateIterator, CandidateStrategy, CrewCandidate,
+        CrewGenerator, CrewSlots, OfficerPools,
+    };
+    use crate::combat::ShipType;
+    use crate::data::officer::{Officer, OfficerAbility};
+
+    fn officer(name: &str, class: &str, ability_slots: &[&str]) -> Officer {
+        Officer {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            slot: Some(class.to_string()),
+            rarity: None,
+            faction: None,
+            group: None,
+            synergy_group: None,
+            source_officer_id: None,
+            abilities: ability_slots
+                .iter()
+                .map(|slot| OfficerAbility {
+                    slot: slot.to_string(),
+                    trigger: None,
+                    modifier: None,
+                    attributes: None,
+                    description: None,
+                    chance_by_rank: vec![],
+                    value_by_rank: vec![],
+                })
+                .collect(),
+            traits: Vec::new(),
+        }
+    }
+
+    fn sample_officers() -> Vec<Officer> {
+        vec![
+            officer("Kirk", "command", &["captain", "officer", "below_decks"]),
+            officer("Spock", "science", &["captain", "officer", "below_decks"]),
+            officer("Scotty", "engineering", &["officer", "below_decks"]),
+            officer("Uhura", "command", &["officer", "below_decks"]),
+            officer("Chapel", "science", &["below_decks"]),
+            officer("Rand", "engineering", &["below_decks"]),
+        ]
+    }
+
+    #[test]
+    fn below_decks_only_officers_are_kept_off_the_bridge() {
+        let pools =
+            pools_from_officers(sample_officers(), "missing/roster.json", false, None).unwrap();
+        assert_eq!(pools.captains, vec!["Kirk", "Spock"]);
+        assert_eq!(pools.bridge, vec!["Kirk", "Spock", "Scotty", "Uhura"]);
+        assert_eq!(pools.below_decks.len(), 6);
+        assert!(pools.in_class.is_empty());
+    }
+
+    #[test]
+    fn in_class_officers_follow_ship_type_and_lead_the_pools() {
+        let mut pools = pools_from_officers(
+            sample_officers(),
+            "missing/roster.json",
+            false,
+            Some(ShipType::Explorer),
+        )
+        .unwrap();
+        assert_eq!(pools.in_class, vec!["Spock", "Chapel"]);
+
+        assert!(!CandidateStrategy::default().prefer_in_class);
+        in_class_first(&mut pools);
+        assert_eq!(pools.captains, vec!["Spock", "Kirk"]);
+        assert_eq!(pools.bridge, vec!["Spock", "Kirk", "Scotty", "Uhura"]);
+    }
 
     #[test]
     fn generation_is_deterministic_for_same_seed() {
@@ -685,4 +999,149 @@ mod tests {
             candidates.len()
         );
     }
+
+    fn synthetic_pools(captains: usize, bridge: usize, below: usize) -> OfficerPools {
+        let names = |prefix: &str, n: usize| -> Vec<String> {
+            (0..n).map(|i| format!("{prefix}{i}")).collect()
+        };
+        let mut pools = OfficerPools {
+            captains: names("C", captains),
+            bridge: names("B", bridge),
+            below_decks: names("D", below),
+            ship_type: None,
+            in_class: Vec::new(),
+        };
+        // Officers that can sit anywhere exercise the name-conflict terms of the count.
+        for i in 0..3 {
+            let shared = format!("S{i}");
+            pools.captains[i] = shared.clone();
+            pools.bridge[i + 1] = shared.clone();
+            pools.below_decks[i * 2] = shared;
+        }
+        pools
+    }
+
+    #[test]
+    fn excluded_officers_leave_every_pool() {
+        let excluded = vec!["S0".to_string(), "B4".to_string()];
+        let pools = synthetic_pools(4, 5, 6).without(&excluded).expect("captains remain");
+        for pool in [&pools.captains, &pools.bridge, &pools.below_decks] {
+            assert!(pool.iter().all(|name| !excluded.contains(name)), "{pool:?}");
+        }
+        assert_eq!((pools.captains.len(), pools.bridge.len()), (3, 3));
+
+        let every_captain: Vec<String> = synthetic_pools(3, 4, 6).captains;
+        assert!(synthetic_pools(3, 4, 6).without(&every_captain).is_none());
+    }
+
+    #[test]
+    fn candidate_count_matches_enumeration_for_exhaustive_and_sampled_pools() {
+        for (captains, bridge, below) in [(4, 5, 6), (8, 9, 12), (15, 18, 24)] {
+            for seed in 0..5 {
+                let strategy = CandidateStrategy {
+                    max_candidates: None,
+                    ..CandidateStrategy::default()
+                };
+                let iter = CandidateIterator::new(
+                    synthetic_pools(captains, bridge, below),
+                    &strategy,
+                    CrewSlots::default(),
+                    "ship",
+                    "hostile",
+                    seed,
+                );
+                let expected = iter.len();
+                assert_eq!(iter.count(), expected, "pools {captains}/{bridge}/{below} seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn candidate_count_matches_enumeration_for_ship_specific_slots() {
+        let strategy = CandidateStrategy {
+            max_candidates: None,
+            ..CandidateStrategy::default()
+        };
+        for slots in [
+            CrewSlots { bridge: 2, below_decks: 1 },
+            CrewSlots { bridge: 2, below_decks: 0 },
+            CrewSlots { bridge: 1, below_decks: 4 },
+        ] {
+            for (captains, bridge, below) in [(4, 5, 6), (15, 18, 24)] {
+                let pools = synthetic_pools(captains, bridge, below);
+                let iter = CandidateIterator::new(pools, &strategy, slots, "ship", "hostile", 3);
+                let expected = iter.len();
+                let candidates: Vec<CrewCandidate> = iter.collect();
+                assert!(expected > 0);
+                let label = format!("{slots:?} pools {captains}/{bridge}/{below}");
+                assert_eq!(candidates.len(), expected, "{label}");
+                for candidate in &candidates {
+                    assert_eq!(candidate.bridge.len(), slots.bridge);
+                    assert_eq!(candidate.below_decks.len(), slots.below_decks);
+                    assert!(!candidate.is_partial());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fit_drops_seats_the_ship_lacks() {
+        let candidate = CrewCandidate::new(
+            "Kirk",
+            vec!["Spock".into(), "Uhura".into()],
+            vec!["Chapel".into(), "Rand".into(), "Scotty".into()],
+        );
+        assert!(matches!(CrewSlots::default().fit(&candidate), std::borrow::Cow::Borrowed(_)));
+        let fitted = CrewSlots { bridge: 2, below_decks: 1 }.fit(&candidate).into_owned();
+        assert_eq!(fitted.below_deck_names(), vec!["Chapel"]);
+        assert_eq!(fitted.bridge_names(), vec!["Spock", "Uhura"]);
+    }
+
+    #[test]
+    fn candidate_iterator_honors_cap_and_reports_remaining() {
+        let strategy = CandidateStrategy {
+            max_candidates: Some(10),
+            ..CandidateStrategy::default()
+        };
+        let mut iter = CandidateIterator::new(
+            synthetic_pools(5, 6, 8),
+            &strategy,
+            CrewSlots::default(),
+            "s",
+            "h",
+            1,
+        );
+        assert_eq!(iter.len(), 10);
+        let first: Vec<CrewCandidate> = iter.by_ref().take(4).collect();
+        assert_eq!(first.len(), 4);
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.count(), 6);
+    }
+
+    #[test]
+    fn small_rosters_yield_partial_crews_with_empty_seats() {
+        let pools = OfficerPools {
+            captains: vec!["Kirk".into(), "Spock".into()],
+            bridge: vec!["Kirk".into(), "Spock".into()],
+            below_decks: vec!["Kirk".into(), "Spock".into(), "Rand".into()],
+            ship_type: None,
+            in_class: Vec::new(),
+        };
+        let strategy = CandidateStrategy::default();
+        let candidates: Vec<CrewCandidate> =
+            CandidateIterator::new(pools, &strategy, CrewSlots::default(), "s", "h", 1).collect();
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(
+            candidates[0],
+            CrewCandidate {
+                captain: "Kirk".into(),
+                bridge: vec![Some("Spock".into()), None],
+                below_decks: vec![Some("Rand".into()), None, None],
+            }
+        );
+        for candidate in &candidates {
+            assert!(candidate.is_partial());
+            assert_eq!(candidate.officers().count(), 3);
+        }
+    }
 }
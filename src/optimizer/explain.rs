@@ -0,0 +1,294 @@
+//! "Why is this crew ranked #1": for one recommended crew, each officer's share of the crew's
+//! damage and survivability, and how often the crew's statuses (morale, hull breach, burning,
+//! assimilated, other damage-over-time) were up.
+//!
+//! Shares are leave-one-out: the crew is re-simulated with each officer's seat left empty, on the
+//! same seeds as the full crew, and the share is the fraction of the full crew's result lost.
+//! Synergies mean shares need not add up to 1. Uptimes come from a few traced fights.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::combat::{simulate_combat, CombatEvent, SimulationConfig};
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::scenario::{
+    scenario_to_combat_input_from_shared, CombatSimulationInput, SharedScenarioData,
+};
+use crate::parallel::iteration_seed;
+
+/// Recommendations explained when the request does not say.
+pub const DEFAULT_EXPLAIN_TOP: u32 = 3;
+/// Fights per leave-one-out run; explanations rank officers, they do not need the full sims.
+pub const EXPLAIN_SIMS_CAP: usize = 200;
+/// Traced fights status uptimes are measured over.
+const UPTIME_FIGHTS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct CrewExplanation {
+    /// Fights per leave-one-out run.
+    pub sims: u32,
+    /// One row per seated officer, largest damage share first.
+    pub contributions: Vec<OfficerContribution>,
+    /// Statuses that triggered at least once, highest uptime first.
+    pub status_uptimes: Vec<StatusUptime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct OfficerContribution {
+    pub officer: String,
+    /// "captain", "bridge" or "below_decks".
+    pub seat: &'static str,
+    /// Fraction of the crew's average damage lost without this officer (negative: costs damage).
+    pub damage_share: f64,
+    /// Fraction of the crew's average remaining hull lost without this officer.
+    pub survival_share: f64,
+    /// Crew win rate minus the win rate without this officer.
+    pub win_rate_delta: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct StatusUptime {
+    /// "morale", "hull_breach", "burning", "assimilated" or a damage-over-time channel.
+    pub status: String,
+    /// Share of rounds fought with the status active.
+    pub uptime: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Outcome {
+    damage: f64,
+    hull_remaining: f64,
+    win_rate: f64,
+}
+
+/// Average damage, remaining hull share and win rate of `input` over `sims` fights seeded from
+/// `base_seed` (the full crew's, so every leave-one-out run sees the same rolls).
+fn outcome(
+    input: &CombatSimulationInput,
+    shared: &SharedScenarioData,
+    base_seed: u64,
+    sims: usize,
+) -> Outcome {
+    let mut config = SimulationConfig::builder()
+        .rounds(input.rounds)
+        .attacker_delay_rounds(shared.attacker_delay_rounds)
+        .initiative(shared.initiative)
+        .build();
+    let mut sum = Outcome::default();
+    for i in 0..sims {
+        config.seed = iteration_seed(base_seed, i);
+        let result = simulate_combat(&input.attacker, &input.defender, config, &input.crew);
+        sum.damage += result.total_damage;
+        sum.hull_remaining +=
+            (result.attacker_hull_remaining / input.attacker.hull_health.max(1.0)).clamp(0.0, 1.0);
+        if result.attacker_won {
+            sum.win_rate += 1.0;
+        }
+    }
+    let n = sims.max(1) as f64;
+    Outcome {
+        damage: sum.damage / n,
+        hull_remaining: sum.hull_remaining / n,
+        win_rate: sum.win_rate / n,
+    }
+}
+
+/// `candidate` with one seat emptied, labelled with the officer and seat it removed.
+fn without_each_officer(candidate: &CrewCandidate) -> Vec<(String, &'static str, CrewCandidate)> {
+    let mut out = Vec::new();
+    if !candidate.captain.is_empty() {
+        let mut crew = candidate.clone();
+        crew.captain = String::new();
+        out.push((candidate.captain.clone(), "captain", crew));
+    }
+    for (seat, officers) in [("bridge", &candidate.bridge), ("below_decks", &candidate.below_decks)]
+    {
+        for (i, officer) in officers.iter().enumerate() {
+            let Some(officer) = officer.as_ref().filter(|name| !name.is_empty()) else {
+                continue;
+            };
+            let mut crew = candidate.clone();
+            match seat {
+                "bridge" => crew.bridge[i] = None,
+                _ => crew.below_decks[i] = None,
+            };
+            out.push((officer.clone(), seat, crew));
+        }
+    }
+    out
+}
+
+fn share_lost(full: f64, without: f64) -> f64 {
+    if full.abs() < f64::EPSILON {
+        0.0
+    } else {
+        (full - without) / full
+    }
+}
+
+/// Status name and rounds covered by one trace event, when the event is a status that took hold.
+fn status_trigger(event: &CombatEvent) -> Option<(String, u32)> {
+    if event.values.get("triggered").and_then(|v| v.as_bool()) != Some(true) {
+        return None;
+    }
+    let duration = || {
+        event.values.get("duration_rounds").and_then(|v| v.as_u64()).unwrap_or(1).max(1) as u32
+    };
+    match event.event_type.as_str() {
+        "morale_activation" => Some(("morale".to_string(), 1)),
+        "hull_breach_trigger" => Some(("hull_breach".to_string(), duration())),
+        "burning_trigger" => Some(("burning".to_string(), duration())),
+        "assimilated_trigger" => Some(("assimilated".to_string(), duration())),
+        "dot_trigger" => {
+            let channel = event.values.get("channel").and_then(|v| v.as_str())?;
+            Some((channel.to_string(), duration()))
+        }
+        _ => None,
+    }
+}
+
+/// Share of fought rounds each status was active over [UPTIME_FIGHTS] traced fights.
+fn status_uptimes(
+    input: &CombatSimulationInput,
+    shared: &SharedScenarioData,
+    base_seed: u64,
+) -> Vec<StatusUptime> {
+    let mut config = SimulationConfig::builder()
+        .rounds(input.rounds)
+        .attacker_delay_rounds(shared.attacker_delay_rounds)
+        .initiative(shared.initiative)
+        .trace(true)
+        .build();
+    let mut active_rounds: BTreeMap<String, usize> = BTreeMap::new();
+    let mut rounds_fought = 0usize;
+    for i in 0..UPTIME_FIGHTS {
+        config.seed = iteration_seed(base_seed, i);
+        let result = simulate_combat(&input.attacker, &input.defender, config, &input.crew);
+        let rounds: BTreeSet<u32> = result
+            .events
+            .iter()
+            .filter(|e| e.event_type == "round_start")
+            .map(|e| e.round_index)
+            .collect();
+        rounds_fought += rounds.len();
+        let mut covered: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+        for event in &result.events {
+            if let Some((status, duration)) = status_trigger(event) {
+                let start = event.round_index;
+                covered
+                    .entry(status)
+                    .or_default()
+                    .extend(rounds.range(start..start.saturating_add(duration)));
+            }
+        }
+        for (status, set) in covered {
+            *active_rounds.entry(status).or_default() += set.len();
+        }
+    }
+    let mut uptimes: Vec<StatusUptime> = active_rounds
+        .into_iter()
+        .map(|(status, active)| StatusUptime {
+            status,
+            uptime: active as f64 / rounds_fought.max(1) as f64,
+        })
+        .collect();
+    uptimes.sort_by(|a, b| b.uptime.total_cmp(&a.uptime));
+    uptimes
+}
+
+/// Explains `candidate` in `shared`'s scenario with up to [EXPLAIN_SIMS_CAP] fights per run.
+/// Wave scenarios are explained on a single wave.
+pub(crate) fn explain_crew(
+    shared: &SharedScenarioData,
+    candidate: &CrewCandidate,
+    seed: u64,
+    sims: usize,
+) -> CrewExplanation {
+    let sims = sims.clamp(1, EXPLAIN_SIMS_CAP);
+    let input = scenario_to_combat_input_from_shared(shared, candidate, seed);
+    let base_seed = input.base_seed;
+    let full = outcome(&input, shared, base_seed, sims);
+
+    let mut contributions: Vec<OfficerContribution> = without_each_officer(candidate)
+        .into_iter()
+        .map(|(officer, seat, crew)| {
+            let ablated = scenario_to_combat_input_from_shared(shared, &crew, seed);
+            let without = outcome(&ablated, shared, base_seed, sims);
+            OfficerContribution {
+                officer,
+                seat,
+                damage_share: share_lost(full.damage, without.damage),
+                survival_share: share_lost(full.hull_remaining, without.hull_remaining),
+                win_rate_delta: full.win_rate - without.win_rate,
+            }
+        })
+        .collect();
+    contributions.sort_by(|a, b| b.damage_share.total_cmp(&a.damage_share));
+
+    CrewExplanation {
+        sims: sims as u32,
+        contributions,
+        status_uptimes: status_uptimes(&input, shared, base_seed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::{EventSource, TRACE_SCHEMA_VERSION};
+    use serde_json::{Map, Value};
+
+    fn event(event_type: &str, round_index: u32, values: &[(&str, Value)]) -> CombatEvent {
+        CombatEvent {
+            event_type: event_type.to_string(),
+            round_index,
+            phase: "round_start".to_string(),
+            source: EventSource::default(),
+            weapon_index: None,
+            values: values.iter().map(|(k, v)| (k.to_string(), v.clone())).collect::<Map<_, _>>(),
+            schema_version: TRACE_SCHEMA_VERSION,
+        }
+    }
+
+    #[test]
+    fn status_triggers_cover_their_duration_and_ignore_failed_rolls() {
+        let breach = event(
+            "hull_breach_trigger",
+            2,
+            &[("triggered", Value::Bool(true)), ("duration_rounds", Value::from(3))],
+        );
+        assert_eq!(status_trigger(&breach), Some(("hull_breach".to_string(), 3)));
+        let morale = event("morale_activation", 1, &[("triggered", Value::Bool(true))]);
+        assert_eq!(status_trigger(&morale), Some(("morale".to_string(), 1)));
+        let missed = event("burning_trigger", 1, &[("triggered", Value::Bool(false))]);
+        assert_eq!(status_trigger(&missed), None);
+        assert_eq!(status_trigger(&event("round_start", 1, &[])), None);
+    }
+
+    #[test]
+    fn leave_one_out_drops_each_seat_once() {
+        let candidate = CrewCandidate {
+            captain: "Kirk".to_string(),
+            bridge: vec![Some("Spock".to_string()), Some("McCoy".to_string())],
+            below_decks: vec![Some("Scotty".to_string()), None],
+        };
+        let rows = without_each_officer(&candidate);
+        let labels: Vec<(&str, &str)> =
+            rows.iter().map(|(o, seat, _)| (o.as_str(), *seat)).collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("Kirk", "captain"),
+                ("Spock", "bridge"),
+                ("McCoy", "bridge"),
+                ("Scotty", "below_decks")
+            ]
+        );
+        assert_eq!(rows[2].2.bridge, vec![Some("Spock".to_string()), None]);
+        assert!(rows[0].2.captain.is_empty());
+        assert_eq!(share_lost(0.0, 5.0), 0.0);
+        assert!((share_lost(200.0, 150.0) - 0.25).abs() < 1e-12);
+    }
+}
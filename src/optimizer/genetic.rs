@@ -10,21 +10,53 @@
 //! When `adaptive_mutation` is true and the population is seeded, the mutation rate starts
 //! low (`mutation_rate_floor`) and increases on stagnation up to `mutation_rate_ceiling`,
 //! balancing gentle exploration around good seeds with escape from local optima.
+//!
+//! # Island Model
+//! With `islands` > 1, that many sub-populations of `population_size` crews evolve side by
+//! side: each generation scores every island in one batched Monte Carlo pass, then breeds the
+//! islands in parallel on the [WorkerPool]. Every `migration_interval` generations each island
+//! sends its `migration_count` best crews to the next island in a ring, replacing that island's
+//! worst.
+//! Islands explore different regions of a large roster before sharing their best finds, which
+//! keeps one early leader from taking over the whole population. Island `i` derives its RNG
+//! seeds from the run seed and `i` only, so results are deterministic for a fixed seed whatever
+//! the thread count.
+//!
+//! # Fitness Cache
+//! Crews reappear across generations (elites, migrants, crossover of similar parents). A
+//! [FitnessCache] keyed by [crew_stable_id] keeps the objective score of every crew simulated in
+//! the run, so only unseen crews are simulated; a cached crew keeps the score from the
+//! generation that first evaluated it. [FitnessCacheStats] reports how often it hit.
+//!
+//! # Overrides
+//! [GeneticOverrides] (the optimize request's `ga` object) replaces population size,
+//! generations, mutation rate, elitism and the early-stopping criteria on top of either preset.
 
 use crate::combat::rng::Rng;
+use crate::combat::AttackerCondition;
 use crate::optimizer::crew_generator::{
-    build_officer_pools, OfficerPools, CrewCandidate, BRIDGE_SLOTS, BELOW_DECKS_SLOTS,
+    build_officer_pools_for_ship, resolve_ship_type, OfficerPools, CrewCandidate,
+    CrewSlots,
 };
-use crate::optimizer::monte_carlo::{
-    run_monte_carlo_parallel, run_monte_carlo_parallel_deduped, SimulationResult,
+use crate::optimizer::monte_carlo::scenario::build_shared_scenario_data_standalone;
+use crate::optimizer::monte_carlo::run_monte_carlo_with_shared;
+use crate::optimizer::monte_carlo::scenario::SharedScenarioData;
+use crate::optimizer::ranking::{
+    crew_stable_id, objective_score, rank_results_for, RankedCrewResult, ScenarioType,
 };
-use crate::optimizer::ranking::{rank_results, RankedCrewResult};
-use std::collections::HashSet;
+use crate::optimizer::OptimizePhase;
+use crate::parallel::WorkerPool;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
-/// Same scalar as ranking: win_rate * 0.8 + avg_hull_remaining * 0.2
-fn fitness_from_result(result: &SimulationResult) -> f32 {
-    (result.win_rate * 0.8 + result.avg_hull_remaining * 0.2) as f32
-}
+/// Upper bound for [GeneticOverrides::population_size].
+pub const MAX_GA_POPULATION: usize = 1000;
+/// Upper bound for [GeneticOverrides::generations] and [GeneticOverrides::stagnation_limit].
+pub const MAX_GA_GENERATIONS: usize = 1000;
+/// Upper bound for [GeneticOverrides::islands].
+pub const MAX_GA_ISLANDS: usize = 16;
 
 /// Configuration for the genetic algorithm.
 #[derive(Debug, Clone)]
@@ -37,6 +69,15 @@ pub struct GeneticConfig {
     pub elitism_count: usize,
     /// Stop early if best fitness has not improved for this many generations.
     pub stagnation_limit: Option<usize>,
+    /// Stop early once the best fitness reaches this objective score.
+    pub target_fitness: Option<f32>,
+    /// Sub-populations evolved in parallel, each of `population_size` crews. 1 = a single
+    /// population.
+    pub islands: usize,
+    /// Generations between migrations (island model only).
+    pub migration_interval: usize,
+    /// Best crews each island sends to the next one per migration.
+    pub migration_count: usize,
     /// When true, below-decks pool only includes officers that have a below-decks ability.
     pub only_below_decks_with_ability: bool,
 
@@ -53,6 +94,32 @@ pub struct GeneticConfig {
 
     /// Maximum mutation rate for adaptive schedule. Defaults to 0.40.
     pub mutation_rate_ceiling: f64,
+
+    /// Fitness objective; same scalar as [crate::optimizer::ranking::objective_score].
+    pub scenario_type: ScenarioType,
+
+    /// Opening rounds the player's ship spends mining (holding fire). Defaults to 0.
+    pub mining_rounds: u32,
+
+    /// Consecutive fights against the hostile per evaluation (0 or 1 = single fight).
+    pub waves: u32,
+
+    /// Share of missing hull and shields repaired between waves. Defaults to 0.
+    pub wave_repair: f64,
+
+    /// Hull and shields the player's ship starts each evaluation with. Defaults to full health.
+    pub attacker_condition: AttackerCondition,
+
+    /// Seats each crew fills. None = the ship's at its default tier (the ship the fitness fights
+    /// use).
+    pub crew_slots: Option<CrewSlots>,
+
+    /// Officer names never placed in any seat.
+    pub excluded_officers: Vec<String>,
+
+    /// Pool fitness scoring and breeding run on. Its priority is the caller's, so a background
+    /// optimize job's GA does not outrank (and stall) other background jobs.
+    pub pool: WorkerPool,
 }
 
 impl Default for GeneticConfig {
@@ -65,11 +132,23 @@ impl Default for GeneticConfig {
             tournament_size: 3,
             elitism_count: 2,
             stagnation_limit: Some(10),
+            target_fitness: None,
+            islands: 1,
+            migration_interval: 5,
+            migration_count: 2,
             only_below_decks_with_ability: false,
             seed_population: Vec::new(),
             adaptive_mutation: true,
             mutation_rate_floor: 0.05,
             mutation_rate_ceiling: 0.40,
+            scenario_type: ScenarioType::Offense,
+            mining_rounds: 0,
+            waves: 0,
+            wave_repair: 0.0,
+            attacker_condition: AttackerCondition::default(),
+            crew_slots: None,
+            excluded_officers: Vec::new(),
+            pool: WorkerPool::default(),
         }
     }
 }
@@ -91,6 +170,77 @@ impl GeneticConfig {
             ..Self::default()
         }
     }
+
+    /// This config with every field set in `overrides` replaced.
+    pub fn with_overrides(mut self, overrides: &GeneticOverrides) -> Self {
+        if let Some(size) = overrides.population_size {
+            self.population_size = size;
+        }
+        if let Some(generations) = overrides.generations {
+            self.generations = generations;
+        }
+        if let Some(rate) = overrides.mutation_rate {
+            self.mutation_rate = rate;
+            self.adaptive_mutation = false;
+        }
+        if let Some(count) = overrides.elitism_count {
+            self.elitism_count = count;
+        }
+        if let Some(limit) = overrides.stagnation_limit {
+            self.stagnation_limit = (limit > 0).then_some(limit);
+        }
+        if overrides.target_fitness.is_some() {
+            self.target_fitness = overrides.target_fitness;
+        }
+        if let Some(islands) = overrides.islands {
+            self.islands = islands;
+        }
+        if let Some(interval) = overrides.migration_interval {
+            self.migration_interval = interval;
+        }
+        if let Some(count) = overrides.migration_count {
+            self.migration_count = count;
+        }
+        self
+    }
+}
+
+/// Caller overrides for [GeneticConfig]; unset fields keep the preset's value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GeneticOverrides {
+    /// Crews per generation. Default 64 (seeded: twice the seeds, 80–200).
+    #[schemars(range(min = 2, max = "MAX_GA_POPULATION"))]
+    pub population_size: Option<usize>,
+    /// Most generations to evolve. Default 40 (seeded: 60).
+    #[schemars(range(min = 1, max = "MAX_GA_GENERATIONS"))]
+    pub generations: Option<usize>,
+    /// Chance (0–1) that a child gets one seat replaced. Setting it turns adaptive mutation off.
+    #[schemars(range(min = 0, max = 1))]
+    pub mutation_rate: Option<f64>,
+    /// Best crews copied unchanged into the next generation; less than `population_size`.
+    pub elitism_count: Option<usize>,
+    /// Stop after this many generations without a better best crew; 0 never stops early.
+    /// Default 10 (seeded: 15).
+    #[schemars(range(max = "MAX_GA_GENERATIONS"))]
+    pub stagnation_limit: Option<usize>,
+    /// Stop once the best crew's objective score (win rate for offense) reaches this value.
+    pub target_fitness: Option<f32>,
+    /// Sub-populations of `population_size` crews evolved in parallel with migration. Default 1.
+    #[schemars(range(min = 1, max = "MAX_GA_ISLANDS"))]
+    pub islands: Option<usize>,
+    /// Generations between migrations between islands. Default 5.
+    #[schemars(range(min = 1, max = "MAX_GA_GENERATIONS"))]
+    pub migration_interval: Option<usize>,
+    /// Best crews each island sends to the next per migration; less than `population_size`.
+    /// Default 2.
+    pub migration_count: Option<usize>,
+}
+
+impl GeneticOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
 /// Extension trait providing additional RNG methods used by the genetic algorithm.
@@ -115,12 +265,14 @@ impl RngExt for Rng {
     }
 }
 
-/// Build one random valid crew from pools with distinct officers in every seat.
-fn random_crew(rng: &mut Rng, pools: &OfficerPools) -> Option<CrewCandidate> {
-    if pools.captains.is_empty()
-        || pools.bridge.len() < BRIDGE_SLOTS
-        || pools.below_decks.len() < BELOW_DECKS_SLOTS
-    {
+/// Build one random valid crew from pools with distinct officers in every seat. Seats the pools
+/// cannot fill (small rosters) stay empty.
+pub(crate) fn random_crew(
+    rng: &mut Rng,
+    pools: &OfficerPools,
+    slots: CrewSlots,
+) -> Option<CrewCandidate> {
+    if pools.captains.is_empty() {
         return None;
     }
 
@@ -128,43 +280,40 @@ fn random_crew(rng: &mut Rng, pools: &OfficerPools) -> Option<CrewCandidate> {
     let mut used: HashSet<String> = HashSet::new();
     used.insert(captain.clone());
 
-    let mut bridge = Vec::with_capacity(BRIDGE_SLOTS);
-    for _ in 0..BRIDGE_SLOTS {
+    let mut bridge = Vec::with_capacity(slots.bridge);
+    for _ in 0..slots.bridge {
         let available: Vec<&String> = pools.bridge.iter().filter(|s| !used.contains(*s)).collect();
         if available.is_empty() {
-            return None;
+            break;
         }
         let name = available[rng.index(available.len())].clone();
         bridge.push(name.clone());
         used.insert(name);
     }
 
-    let mut below_decks = Vec::with_capacity(BELOW_DECKS_SLOTS);
-    for _ in 0..BELOW_DECKS_SLOTS {
+    let mut below_decks = Vec::with_capacity(slots.below_decks);
+    for _ in 0..slots.below_decks {
         let available: Vec<&String> = pools
             .below_decks
             .iter()
             .filter(|s| !used.contains(*s))
             .collect();
         if available.is_empty() {
-            return None;
+            break;
         }
         let name = available[rng.index(available.len())].clone();
         below_decks.push(name.clone());
         used.insert(name);
     }
 
-    Some(CrewCandidate {
-        captain,
-        bridge,
-        below_decks,
-    })
+    Some(CrewCandidate::padded(captain, bridge, below_decks, slots))
 }
 
 /// Initialize population with optional seed candidates, filling remaining slots randomly.
 /// When `seed_candidates` is empty, this behaves identically to pure random initialization.
 fn init_population_seeded(
     pools: &OfficerPools,
+    slots: CrewSlots,
     population_size: usize,
     seed_candidates: &[CrewCandidate],
     seed: u64,
@@ -181,7 +330,7 @@ fn init_population_seeded(
     let mut attempts = 0;
     const MAX_ATTEMPTS: usize = 50_000;
     while pop.len() < population_size && attempts < MAX_ATTEMPTS {
-        if let Some(crew) = random_crew(&mut rng, pools) {
+        if let Some(crew) = random_crew(&mut rng, pools, slots) {
             pop.push(crew);
         }
         attempts += 1;
@@ -217,6 +366,7 @@ fn crossover(
     a: &CrewCandidate,
     b: &CrewCandidate,
     pools: &OfficerPools,
+    slots: CrewSlots,
     rng: &mut Rng,
 ) -> CrewCandidate {
     let captain = if rng.next_f64() < 0.5 { &a.captain } else { &b.captain };
@@ -224,16 +374,8 @@ fn crossover(
     let mut used: HashSet<String> = HashSet::new();
     used.insert(captain.clone());
 
-    let bridge_union: Vec<String> = a
-        .bridge
-        .iter()
-        .chain(b.bridge.iter())
-        .map(String::clone)
-        .filter(|s| !used.contains(s))
-        .collect();
-    let bridge_set: HashSet<String> = bridge_union.into_iter().collect();
-    let mut bridge_vec: Vec<String> = bridge_set.into_iter().collect();
-    while bridge_vec.len() < BRIDGE_SLOTS {
+    let mut bridge_vec = parent_union(a.bridge_officers().chain(b.bridge_officers()), &used, rng);
+    while bridge_vec.len() < slots.bridge {
         let available: Vec<&String> = pools.bridge.iter().filter(|s| !used.contains(*s)).collect();
         if available.is_empty() {
             break;
@@ -242,23 +384,19 @@ fn crossover(
         bridge_vec.push(pick.clone());
         used.insert(pick);
     }
-    if bridge_vec.len() > BRIDGE_SLOTS {
-        bridge_vec.truncate(BRIDGE_SLOTS);
+    if bridge_vec.len() > slots.bridge {
+        bridge_vec.truncate(slots.bridge);
     }
     for s in bridge_vec.iter() {
         used.insert(s.clone());
     }
 
-    let below_union: Vec<String> = a
-        .below_decks
-        .iter()
-        .chain(b.below_decks.iter())
-        .map(String::clone)
-        .filter(|s| !used.contains(s))
-        .collect();
-    let below_set: HashSet<String> = below_union.into_iter().collect();
-    let mut below_vec: Vec<String> = below_set.into_iter().collect();
-    while below_vec.len() < BELOW_DECKS_SLOTS {
+    let mut below_vec = parent_union(
+        a.below_deck_officers().chain(b.below_deck_officers()),
+        &used,
+        rng,
+    );
+    while below_vec.len() < slots.below_decks {
         let available: Vec<&String> = pools
             .below_decks
             .iter()
@@ -271,40 +409,50 @@ fn crossover(
         below_vec.push(pick.clone());
         used.insert(pick);
     }
-    if below_vec.len() > BELOW_DECKS_SLOTS {
-        below_vec.truncate(BELOW_DECKS_SLOTS);
+    if below_vec.len() > slots.below_decks {
+        below_vec.truncate(slots.below_decks);
     }
 
-    CrewCandidate {
-        captain,
-        bridge: bridge_vec,
-        below_decks: below_vec,
-    }
+    CrewCandidate::padded(captain, bridge_vec, below_vec, slots)
 }
 
-/// Ensure crew has exactly BRIDGE_SLOTS and BELOW_DECKS_SLOTS. Fills from pools; enforces distinct officers.
-fn repair_crew(crew: &mut CrewCandidate, pools: &OfficerPools, rng: &mut Rng) {
-    let mut used: HashSet<String> = HashSet::new();
-    used.insert(crew.captain.clone());
-    for s in crew.bridge.iter() {
-        used.insert(s.clone());
+/// Distinct `officers` not in `used`, shuffled with `rng` so either parent's officers can survive
+/// truncation to the seat count. The order only depends on the seed, not on hashing.
+fn parent_union<'a>(
+    officers: impl Iterator<Item = &'a str>,
+    used: &HashSet<String>,
+    rng: &mut Rng,
+) -> Vec<String> {
+    let mut union: Vec<String> = Vec::new();
+    for officer in officers {
+        if !used.contains(officer) && !union.iter().any(|s| s == officer) {
+            union.push(officer.to_string());
+        }
     }
-    for s in crew.below_decks.iter() {
-        used.insert(s.clone());
+    for i in (1..union.len()).rev() {
+        union.swap(i, rng.index(i + 1));
     }
+    union
+}
 
-    while crew.bridge.len() < BRIDGE_SLOTS {
+/// Fill empty seats from the pools where officers remain (seats the pools cannot fill stay
+/// empty); enforces distinct officers.
+fn repair_crew(crew: &mut CrewCandidate, pools: &OfficerPools, slots: CrewSlots, rng: &mut Rng) {
+    let mut bridge = crew.bridge_names();
+    let mut below_decks = crew.below_deck_names();
+    let mut used: HashSet<String> = crew.officers().map(str::to_string).collect();
+
+    while bridge.len() < slots.bridge {
         let available: Vec<&String> = pools.bridge.iter().filter(|s| !used.contains(*s)).collect();
         if available.is_empty() {
             break;
         }
         let pick = available[rng.index(available.len())].clone();
-        crew.bridge.push(pick.clone());
-        used.insert(pick.clone());
+        bridge.push(pick.clone());
+        used.insert(pick);
     }
-    crew.bridge.truncate(BRIDGE_SLOTS);
 
-    while crew.below_decks.len() < BELOW_DECKS_SLOTS {
+    while below_decks.len() < slots.below_decks {
         let available: Vec<&String> = pools
             .below_decks
             .iter()
@@ -314,31 +462,28 @@ fn repair_crew(crew: &mut CrewCandidate, pools: &OfficerPools, rng: &mut Rng) {
             break;
         }
         let pick = available[rng.index(available.len())].clone();
-        crew.below_decks.push(pick.clone());
-        used.insert(pick.clone());
+        below_decks.push(pick.clone());
+        used.insert(pick);
     }
-    crew.below_decks.truncate(BELOW_DECKS_SLOTS);
+    *crew = CrewCandidate::padded(std::mem::take(&mut crew.captain), bridge, below_decks, slots);
 }
 
 /// Mutate one slot: replace with random officer from the appropriate pool.
 fn mutate(
     crew: &mut CrewCandidate,
     pools: &OfficerPools,
+    slots: CrewSlots,
     rate: f64,
     rng: &mut Rng,
 ) {
     if rng.next_f64() >= rate {
         return;
     }
-    let slot = rng.index(6);
-    let mut used: HashSet<&str> = HashSet::new();
-    used.insert(crew.captain.as_str());
-    for s in crew.bridge.iter() {
-        used.insert(s.as_str());
-    }
-    for s in crew.below_decks.iter() {
-        used.insert(s.as_str());
-    }
+    // 0 = captain, then bridge seats, then below-decks seats.
+    let slot = rng.index(1 + slots.bridge + slots.below_decks);
+    let used: HashSet<String> = crew.officers().map(str::to_string).collect();
+    let mut bridge = crew.bridge_names();
+    let mut below_decks = crew.below_deck_names();
 
     match slot {
         0 => {
@@ -347,101 +492,215 @@ fn mutate(
                 crew.captain = available[rng.index(available.len())].clone();
             }
         }
-        1 => {
-            let available: Vec<&String> = pools.bridge.iter().filter(|s| !used.contains(s.as_str())).collect();
-            if !available.is_empty() && !crew.bridge.is_empty() {
-                crew.bridge[0] = available[rng.index(available.len())].clone();
-            }
-        }
-        2 => {
-            let available: Vec<&String> = pools.bridge.iter().filter(|s| !used.contains(s.as_str())).collect();
-            if !available.is_empty() && crew.bridge.len() > 1 {
-                crew.bridge[1] = available[rng.index(available.len())].clone();
+        bi if bi <= slots.bridge => {
+            let bi = bi - 1;
+            let available: Vec<&String> = pools
+                .bridge
+                .iter()
+                .filter(|s| !used.contains(s.as_str()))
+                .collect();
+            if !available.is_empty() && bi < bridge.len() {
+                bridge[bi] = available[rng.index(available.len())].clone();
             }
         }
-        3..=5 => {
-            let di = slot - 3;
+        _ => {
+            let di = slot - 1 - slots.bridge;
             let available: Vec<&String> = pools
                 .below_decks
                 .iter()
                 .filter(|s| !used.contains(s.as_str()))
                 .collect();
-            if !available.is_empty() && di < crew.below_decks.len() {
-                crew.below_decks[di] = available[rng.index(available.len())].clone();
+            if !available.is_empty() && di < below_decks.len() {
+                below_decks[di] = available[rng.index(available.len())].clone();
+            }
+        }
+    }
+    crew.bridge = bridge.into_iter().map(Some).collect();
+    crew.below_decks = below_decks.into_iter().map(Some).collect();
+    repair_crew(crew, pools, slots, rng);
+}
+
+/// How often a [FitnessCache] served a crew without simulating it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct FitnessCacheStats {
+    /// Crews scored, counting every population entry of every generation.
+    pub lookups: usize,
+    /// Lookups answered from the cache (or by an identical crew in the same generation).
+    pub hits: usize,
+}
+
+impl FitnessCacheStats {
+    /// Share of lookups that were hits; 0 before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups as f64
+        }
+    }
+}
+
+/// Objective scores of crews already simulated in one GA run, keyed by [crew_stable_id].
+#[derive(Debug, Default)]
+pub struct FitnessCache {
+    scores: HashMap<String, f32>,
+    stats: FitnessCacheStats,
+}
+
+impl FitnessCache {
+    pub fn stats(&self) -> FitnessCacheStats {
+        self.stats
+    }
+
+    /// Sets every island's `fitness`, simulating (once each) only crews not scored before.
+    fn score_islands(
+        &mut self,
+        islands: &mut [Island],
+        shared: &SharedScenarioData,
+        config: &GeneticConfig,
+        seed: u64,
+    ) {
+        let keys: Vec<Vec<String>> = islands
+            .iter()
+            .map(|island| island.population.iter().map(crew_key).collect())
+            .collect();
+        let mut pending: HashSet<&String> = HashSet::new();
+        let mut misses: Vec<CrewCandidate> = Vec::new();
+        for (island, keys) in islands.iter().zip(&keys) {
+            for (crew, key) in island.population.iter().zip(keys) {
+                self.stats.lookups += 1;
+                if self.scores.contains_key(key) || !pending.insert(key) {
+                    self.stats.hits += 1;
+                } else {
+                    misses.push(crew.clone());
+                }
+            }
+        }
+        if !misses.is_empty() {
+            let sims = config.sims_per_eval;
+            let results = run_monte_carlo_with_shared(shared.clone(), &misses, sims, seed, true);
+            for (crew, result) in misses.iter().zip(&results) {
+                self.scores
+                    .insert(crew_key(crew), objective_score(result, config.scenario_type));
             }
         }
-        _ => {}
+        for (island, keys) in islands.iter_mut().zip(&keys) {
+            island.fitness = keys.iter().map(|key| self.scores[key]).collect();
+        }
     }
-    repair_crew(crew, pools, rng);
 }
 
-/// Run genetic optimization. Returns top individuals for final ranking.
+fn crew_key(crew: &CrewCandidate) -> String {
+    crew_stable_id(&crew.captain, &crew.bridge_names(), &crew.below_deck_names())
+}
+
+/// Run genetic optimization. Returns top individuals for final ranking. Crews already in `cache`
+/// are not simulated again.
 /// Progress callback: (generation, max_generations, best_fitness); returns false to abort.
 pub fn run_genetic_optimizer(
     ship: &str,
     hostile: &str,
     config: &GeneticConfig,
     seed: u64,
+    cache: &mut FitnessCache,
     mut on_progress: impl FnMut(usize, usize, f32) -> bool,
 ) -> Vec<CrewCandidate> {
-    let pools = match build_officer_pools(config.only_below_decks_with_ability) {
+    let pools = match build_officer_pools_for_ship(
+        config.only_below_decks_with_ability,
+        resolve_ship_type(ship),
+    )
+    .and_then(|pools| pools.without(&config.excluded_officers))
+    {
         Some(p) => p,
         None => return Vec::new(),
     };
-
-    let mut population = init_population_seeded(
-        &pools,
-        config.population_size,
-        &config.seed_population,
-        seed,
-    );
-    if population.is_empty() {
-        return Vec::new();
-    }
+    let shared = build_shared_scenario_data_standalone(ship, hostile)
+        .with_attacker_delay(config.mining_rounds)
+        .with_initiative(config.scenario_type.initiative())
+        .with_waves(config.waves, config.wave_repair)
+        .with_attacker_condition(config.attacker_condition);
+    let slots = config.crew_slots.unwrap_or(shared.crew_slots);
 
     // Adaptive mutation: start low when seeded, ramp up on stagnation.
     let is_seeded = !config.seed_population.is_empty();
-    let mut current_mutation_rate = if is_seeded && config.adaptive_mutation {
+    let initial_mutation_rate = if is_seeded && config.adaptive_mutation {
         config.mutation_rate_floor
     } else {
         config.mutation_rate
     };
 
+    // Seeds are dealt round-robin so each island starts from different known crews.
+    let island_count = config.islands.max(1);
+    let mut islands: Vec<Island> = (0..island_count)
+        .map(|i| {
+            let seeds: Vec<CrewCandidate> = config
+                .seed_population
+                .iter()
+                .skip(i)
+                .step_by(island_count)
+                .cloned()
+                .collect();
+            Island {
+                population: init_population_seeded(
+                    &pools,
+                    slots,
+                    config.population_size,
+                    &seeds,
+                    island_seed(seed, i),
+                ),
+                fitness: Vec::new(),
+                mutation_rate: initial_mutation_rate,
+                best_fitness: -1.0,
+                stagnation: 0,
+            }
+        })
+        .collect();
+    if islands[0].population.is_empty() {
+        return Vec::new();
+    }
+    let pool = config.pool;
+
     let mut best_fitness = -1.0f32;
     let mut best_individuals: Vec<CrewCandidate> = Vec::new();
     let mut stagnation = 0_usize;
 
     for generation in 0..config.generations {
-        let sim_results = run_monte_carlo_parallel_deduped(
-            ship,
-            hostile,
-            &population,
-            config.sims_per_eval,
-            seed.wrapping_add(generation as u64),
-        );
-        let fitness: Vec<f32> = sim_results.iter().map(fitness_from_result).collect();
+        let eval_seed = seed.wrapping_add(generation as u64);
+        pool.install(|| cache.score_islands(&mut islands, &shared, config, eval_seed));
 
-        let mut indexed: Vec<(usize, f32)> = fitness.iter().copied().enumerate().collect();
-        indexed.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        let gen_best = indexed.first().map(|(_, f)| *f).unwrap_or(-1.0);
+        if island_count > 1
+            && config.migration_interval > 0
+            && (generation + 1) % config.migration_interval == 0
+        {
+            migrate(&mut islands, config.migration_count);
+        }
+
+        let mut gen_best = -1.0f32;
+        for island in &mut islands {
+            let island_best = ranked_indices(&island.fitness)
+                .first()
+                .map_or(-1.0, |&i| island.fitness[i]);
+            if island_best > island.best_fitness {
+                island.best_fitness = island_best;
+                island.stagnation = 0;
+            } else {
+                island.stagnation += 1;
+            }
+            // Adaptive mutation: bump rate by 1.5× every 3 stagnant generations.
+            if config.adaptive_mutation && island.stagnation > 0 && island.stagnation % 3 == 0 {
+                island.mutation_rate =
+                    (island.mutation_rate * 1.5).min(config.mutation_rate_ceiling);
+            }
+            gen_best = gen_best.max(island_best);
+        }
         if gen_best > best_fitness {
             best_fitness = gen_best;
             stagnation = 0;
-            best_individuals = indexed
-                .iter()
-                .take(config.elitism_count.max(10))
-                .map(|(i, _)| population[*i].clone())
-                .collect();
+            best_individuals = best_across_islands(&islands, config.elitism_count.max(10));
         } else {
             stagnation += 1;
         }
 
-        // Adaptive mutation: bump rate by 1.5× every 3 stagnant generations.
-        if config.adaptive_mutation && stagnation > 0 && stagnation % 3 == 0 {
-            current_mutation_rate =
-                (current_mutation_rate * 1.5).min(config.mutation_rate_ceiling);
-        }
-
         if !on_progress(generation + 1, config.generations, best_fitness) {
             break;
         }
@@ -451,79 +710,170 @@ pub fn run_genetic_optimizer(
                 break;
             }
         }
+        if config.target_fitness.is_some_and(|target| best_fitness >= target) {
+            break;
+        }
 
-        let mut rng = Rng::new(seed.wrapping_add(0x1234_5678).wrapping_add((generation as u64) << 32));
+        let breed_seed = seed.wrapping_add(0x1234_5678).wrapping_add((generation as u64) << 32);
+        pool.install(|| {
+            islands.par_iter_mut().enumerate().for_each(|(i, island)| {
+                let mut rng = Rng::new(island_seed(breed_seed, i));
+                island.population = next_generation(island, &pools, slots, config, &mut rng);
+            })
+        });
+    }
 
-        let mut next_pop = Vec::with_capacity(config.population_size);
-        for i in 0..config.elitism_count {
-            if i < population.len() {
-                next_pop.push(population[indexed[i].0].clone());
-            }
+    best_individuals
+}
+
+/// One sub-population of the island model. A single island is the classic GA.
+struct Island {
+    population: Vec<CrewCandidate>,
+    /// Objective score per `population` entry from the latest evaluation.
+    fitness: Vec<f32>,
+    mutation_rate: f64,
+    best_fitness: f32,
+    stagnation: usize,
+}
+
+/// Seed for island `island`'s RNG streams; island 0 uses `seed` itself, so a one-island run
+/// reproduces the single-population GA.
+fn island_seed(seed: u64, island: usize) -> u64 {
+    seed ^ (island as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// Population indices from best to worst fitness (stable for ties).
+fn ranked_indices(fitness: &[f32]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..fitness.len()).collect();
+    indices.sort_by(|&a, &b| {
+        fitness[b]
+            .partial_cmp(&fitness[a])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    indices
+}
+
+/// Ring migration: each island's `count` best crews replace the next island's `count` worst.
+/// Emigrants are picked before any island changes, so the result does not depend on order.
+fn migrate(islands: &mut [Island], count: usize) {
+    let emigrants: Vec<Vec<(CrewCandidate, f32)>> = islands
+        .iter()
+        .map(|island| {
+            ranked_indices(&island.fitness)
+                .into_iter()
+                .take(count)
+                .map(|i| (island.population[i].clone(), island.fitness[i]))
+                .collect()
+        })
+        .collect();
+    let n = islands.len();
+    for (from, arrivals) in emigrants.into_iter().enumerate() {
+        let target = &mut islands[(from + 1) % n];
+        if arrivals.len() >= target.population.len() {
+            continue;
+        }
+        let worst = ranked_indices(&target.fitness).into_iter().rev();
+        for (slot, (crew, fitness)) in worst.zip(arrivals) {
+            target.population[slot] = crew;
+            target.fitness[slot] = fitness;
         }
-        while next_pop.len() < config.population_size {
-            let pa = tournament_select(&population, &fitness, config.tournament_size, &mut rng);
-            let pb = tournament_select(&population, &fitness, config.tournament_size, &mut rng);
-            let mut child = crossover(
-                &population[pa],
-                &population[pb],
-                &pools,
-                &mut rng,
-            );
-            repair_crew(
-                &mut child,
-                &pools,
-                &mut rng,
-            );
-            mutate(
-                &mut child,
-                &pools,
-                current_mutation_rate,
-                &mut rng,
-            );
-            next_pop.push(child);
-        }
-        population = next_pop;
     }
+}
 
-    best_individuals
+/// The `count` best distinct crews over all islands' latest evaluation.
+fn best_across_islands(islands: &[Island], count: usize) -> Vec<CrewCandidate> {
+    let mut scored: Vec<(&CrewCandidate, f32)> = islands
+        .iter()
+        .flat_map(|island| island.population.iter().zip(island.fitness.iter().copied()))
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    let mut best: Vec<CrewCandidate> = Vec::with_capacity(count);
+    for (crew, _) in scored {
+        if best.len() == count {
+            break;
+        }
+        if !best.contains(crew) {
+            best.push(crew.clone());
+        }
+    }
+    best
+}
+
+/// Elites, then children bred by tournament selection, crossover and mutation.
+fn next_generation(
+    island: &Island,
+    pools: &OfficerPools,
+    slots: CrewSlots,
+    config: &GeneticConfig,
+    rng: &mut Rng,
+) -> Vec<CrewCandidate> {
+    let population = &island.population;
+    let ranked = ranked_indices(&island.fitness);
+    let mut next_pop = Vec::with_capacity(config.population_size);
+    for &i in ranked.iter().take(config.elitism_count) {
+        next_pop.push(population[i].clone());
+    }
+    while next_pop.len() < config.population_size {
+        let pa = tournament_select(population, &island.fitness, config.tournament_size, rng);
+        let pb = tournament_select(population, &island.fitness, config.tournament_size, rng);
+        let mut child = crossover(&population[pa], &population[pb], pools, slots, rng);
+        repair_crew(&mut child, pools, slots, rng);
+        mutate(&mut child, pools, slots, island.mutation_rate, rng);
+        next_pop.push(child);
+    }
+    next_pop
 }
 
-/// Run genetic optimization and return ranked results (same shape as optimize_scenario).
-/// Runs a final Monte Carlo pass on top candidates with requested sim count, then ranks.
-/// Progress callback returns false to abort.
+/// Run genetic optimization and return ranked results (same shape as optimize_scenario) with
+/// the run's fitness cache statistics. Runs a final Monte Carlo pass on top candidates with
+/// requested sim count, then ranks.
+/// Progress callback `(phase, generation, generations, best_fitness)` reports
+/// [OptimizePhase::GaGeneration] once per generation and [OptimizePhase::FinalRanking] (with
+/// `generation == generations`) before the final pass; returning false aborts evolution.
 pub fn run_genetic_optimizer_ranked(
     ship: &str,
     hostile: &str,
     config: &GeneticConfig,
     seed: u64,
     final_sims: usize,
-    mut on_progress: impl FnMut(usize, usize, f32) -> bool,
-) -> Vec<RankedCrewResult> {
-    let top = run_genetic_optimizer(ship, hostile, config, seed, &mut on_progress);
+    mut on_progress: impl FnMut(OptimizePhase, usize, usize, f32) -> bool,
+) -> (Vec<RankedCrewResult>, FitnessCacheStats) {
+    let mut cache = FitnessCache::default();
+    let mut best_fitness = 0.0;
+    let top = run_genetic_optimizer(ship, hostile, config, seed, &mut cache, |gen, max_gen, best| {
+        best_fitness = best;
+        on_progress(OptimizePhase::GaGeneration, gen, max_gen, best)
+    });
     if top.is_empty() {
-        return Vec::new();
+        return (Vec::new(), cache.stats());
     }
-    let final_results = run_monte_carlo_parallel(
-        ship,
-        hostile,
-        &top,
-        final_sims.max(1),
-        seed,
-    );
-    rank_results(final_results)
+    let generations = config.generations;
+    on_progress(OptimizePhase::FinalRanking, generations, generations, best_fitness);
+    let shared = build_shared_scenario_data_standalone(ship, hostile)
+        .with_attacker_delay(config.mining_rounds)
+        .with_initiative(config.scenario_type.initiative())
+        .with_waves(config.waves, config.wave_repair)
+        .with_attacker_condition(config.attacker_condition);
+    let final_results = run_monte_carlo_with_shared(shared, &top, final_sims.max(1), seed, true);
+    (rank_results_for(final_results, config.scenario_type), cache.stats())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{crossover, init_population_seeded, mutate, random_crew, repair_crew, GeneticConfig};
+    use super::{
+        crossover, init_population_seeded, migrate, mutate, random_crew, repair_crew,
+        run_genetic_optimizer, FitnessCache, GeneticConfig, GeneticOverrides, Island,
+    };
     use crate::combat::rng::Rng;
-    use crate::optimizer::crew_generator::{CrewCandidate, OfficerPools};
+    use crate::optimizer::crew_generator::{CrewCandidate, CrewSlots, OfficerPools};
 
     fn small_pools() -> OfficerPools {
         OfficerPools {
             captains: vec!["CapA".into(), "CapB".into()],
             bridge: vec!["B1".into(), "B2".into(), "B3".into(), "B4".into()],
             below_decks: vec!["D1".into(), "D2".into(), "D3".into(), "D4".into(), "D5".into()],
+            ship_type: None,
+            in_class: Vec::new(),
         }
     }
 
@@ -532,13 +882,8 @@ mod tests {
         if !seen.insert(c.captain.as_str()) {
             return false;
         }
-        for b in &c.bridge {
-            if !seen.insert(b.as_str()) {
-                return false;
-            }
-        }
-        for d in &c.below_decks {
-            if !seen.insert(d.as_str()) {
+        for officer in c.bridge_officers().chain(c.below_deck_officers()) {
+            if !seen.insert(officer) {
                 return false;
             }
         }
@@ -546,11 +891,11 @@ mod tests {
     }
 
     fn make_crew(cap: &str, b: &[&str], bd: &[&str]) -> CrewCandidate {
-        CrewCandidate {
-            captain: cap.into(),
-            bridge: b.iter().map(|s| (*s).into()).collect(),
-            below_decks: bd.iter().map(|s| (*s).into()).collect(),
-        }
+        CrewCandidate::new(
+            cap,
+            b.iter().map(|s| (*s).into()).collect(),
+            bd.iter().map(|s| (*s).into()).collect(),
+        )
     }
 
     #[test]
@@ -558,7 +903,7 @@ mod tests {
         let pools = small_pools();
         let mut rng = Rng::new(42);
         for _ in 0..20 {
-            let crew = random_crew(&mut rng, &pools).unwrap();
+            let crew = random_crew(&mut rng, &pools, CrewSlots::default()).unwrap();
             assert!(valid_crew(&crew), "crew should be valid: {:?}", crew);
         }
     }
@@ -570,7 +915,7 @@ mod tests {
         let b = make_crew("CapB", &["B3", "B4"], &["D4", "D5", "D1"]);
         let mut rng = Rng::new(99);
         for _ in 0..10 {
-            let child = crossover(&a, &b, &pools, &mut rng);
+            let child = crossover(&a, &b, &pools, CrewSlots::default(), &mut rng);
             assert!(valid_crew(&child), "child should be valid: {:?}", child);
         }
     }
@@ -581,8 +926,8 @@ mod tests {
         let mut crew = make_crew("CapA", &["B1", "B2"], &["D1", "D2", "D3"]);
         let mut rng = Rng::new(77);
         for _ in 0..20 {
-            mutate(&mut crew, &pools, 1.0, &mut rng);
-            repair_crew(&mut crew, &pools, &mut rng);
+            mutate(&mut crew, &pools, CrewSlots::default(), 1.0, &mut rng);
+            repair_crew(&mut crew, &pools, CrewSlots::default(), &mut rng);
             assert!(valid_crew(&crew), "crew should remain valid: {:?}", crew);
         }
     }
@@ -602,6 +947,80 @@ mod tests {
         assert!(c.mutation_rate_ceiling > c.mutation_rate);
     }
 
+    #[test]
+    fn overrides_replace_only_set_fields() {
+        let overrides = GeneticOverrides {
+            population_size: Some(120),
+            mutation_rate: Some(0.3),
+            stagnation_limit: Some(0),
+            target_fitness: Some(0.95),
+            ..GeneticOverrides::default()
+        };
+        let c = GeneticConfig::seeded(Vec::new()).with_overrides(&overrides);
+        assert_eq!(c.population_size, 120);
+        assert_eq!(c.generations, 60);
+        assert_eq!(c.mutation_rate, 0.3);
+        assert!(!c.adaptive_mutation);
+        assert_eq!(c.stagnation_limit, None);
+        assert_eq!(c.target_fitness, Some(0.95));
+        assert_eq!(c.elitism_count, GeneticConfig::default().elitism_count);
+
+        let untouched = GeneticConfig::default().with_overrides(&GeneticOverrides::default());
+        assert!(untouched.adaptive_mutation);
+        assert_eq!(untouched.stagnation_limit, Some(10));
+    }
+
+    #[test]
+    fn migrate_replaces_next_islands_worst_crews() {
+        let island = |captains: &[&str], fitness: Vec<f32>| Island {
+            population: captains
+                .iter()
+                .map(|c| make_crew(c, &["B1", "B2"], &["D1", "D2", "D3"]))
+                .collect(),
+            fitness,
+            mutation_rate: 0.1,
+            best_fitness: -1.0,
+            stagnation: 0,
+        };
+        let mut islands = vec![
+            island(&["A0", "A1", "A2"], vec![0.9, 0.1, 0.5]),
+            island(&["B0", "B1", "B2"], vec![0.2, 0.8, 0.3]),
+        ];
+        migrate(&mut islands, 1);
+        // A's best (A0) replaced B's worst (B0); B's best (B1) replaced A's worst (A1).
+        let captains = |i: &Island| i.population.iter().map(|c| c.captain.clone()).collect::<Vec<_>>();
+        assert_eq!(captains(&islands[0]), ["A0", "B1", "A2"]);
+        assert_eq!(islands[0].fitness, [0.9, 0.8, 0.5]);
+        assert_eq!(captains(&islands[1]), ["A0", "B1", "B2"]);
+        assert_eq!(islands[1].fitness, [0.9, 0.8, 0.3]);
+    }
+
+    #[test]
+    fn island_model_is_deterministic_for_fixed_seed() {
+        let config = GeneticConfig {
+            population_size: 8,
+            generations: 4,
+            sims_per_eval: 20,
+            islands: 3,
+            migration_interval: 2,
+            migration_count: 1,
+            ..GeneticConfig::default()
+        };
+        let run = |cache: &mut FitnessCache| {
+            run_genetic_optimizer("saladin", "2918121098", &config, 11, cache, |_, _, _| true)
+        };
+        let mut cache = FitnessCache::default();
+        let first = run(&mut cache);
+        assert!(!first.is_empty());
+        assert_eq!(first, run(&mut FitnessCache::default()));
+
+        // Every island's crews are looked up each generation; elites always hit.
+        let stats = cache.stats();
+        assert_eq!(stats.lookups, 3 * 8 * 4);
+        assert!(stats.hits >= 3 * 2 * 3, "{stats:?}");
+        assert!(stats.hit_rate() > 0.0 && stats.hit_rate() < 1.0);
+    }
+
     #[test]
     fn seeded_config_scales_population() {
         // 5 seeds → pop_size = max(10, 80) = 80
@@ -628,7 +1047,7 @@ mod tests {
         let seed_b = make_crew("CapB", &["B3", "B4"], &["D4", "D5", "D1"]);
         let seeds = vec![seed_a.clone(), seed_b.clone()];
 
-        let pop = init_population_seeded(&pools, 6, &seeds, 42);
+        let pop = init_population_seeded(&pools, CrewSlots::default(), 6, &seeds, 42);
         assert_eq!(pop.len(), 6, "population should be full");
         // First two should be our seeds.
         assert_eq!(pop[0].captain, seed_a.captain);
@@ -645,14 +1064,14 @@ mod tests {
         let seeds: Vec<CrewCandidate> = (0..10)
             .map(|i| make_crew(if i % 2 == 0 { "CapA" } else { "CapB" }, &["B1", "B2"], &["D1", "D2", "D3"]))
             .collect();
-        let pop = init_population_seeded(&pools, 4, &seeds, 99);
+        let pop = init_population_seeded(&pools, CrewSlots::default(), 4, &seeds, 99);
         assert_eq!(pop.len(), 4, "population should be capped at population_size");
     }
 
     #[test]
     fn init_population_seeded_empty_is_random() {
         let pools = small_pools();
-        let pop_seeded = init_population_seeded(&pools, 8, &[], 42);
+        let pop_seeded = init_population_seeded(&pools, CrewSlots::default(), 8, &[], 42);
         assert_eq!(pop_seeded.len(), 8);
         for crew in &pop_seeded {
             assert!(valid_crew(crew));
@@ -672,6 +1091,7 @@ mod tests {
             "swarm",
             &config,
             12345,
+            &mut FitnessCache::default(),
             |_, _, _| true,
         );
         let b = super::run_genetic_optimizer(
@@ -679,6 +1099,7 @@ mod tests {
             "swarm",
             &config,
             12345,
+            &mut FitnessCache::default(),
             |_, _, _| true,
         );
         if a.is_empty() && b.is_empty() {
@@ -706,13 +1127,13 @@ mod integration_tests {
             ..GeneticConfig::default()
         };
         let mut progress_calls = 0;
-        let results = run_genetic_optimizer_ranked(
+        let (results, _) = run_genetic_optimizer_ranked(
             "enterprise",
             "swarm",
             &config,
             99,
             50,
-            |gen, max_gen, _| {
+            |_, gen, max_gen, _| {
                 progress_calls += 1;
                 assert!(gen <= max_gen);
                 true
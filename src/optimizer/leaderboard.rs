@@ -0,0 +1,207 @@
+//! "Which officers should I recruit": for one scenario, estimates each officer's average marginal
+//! win-rate contribution per seat over randomly sampled crews (a Shapley-value approximation).
+//!
+//! Each sample draws a random crew from the officer pools and seats its officers one at a time in
+//! a random order, starting from an empty crew. An officer's contribution in that sample is the
+//! win-rate change when it took its seat; averaging over samples and orders approximates its
+//! Shapley value within the crews it appears in. Unlike crew ranking, this scores officers rather
+//! than whole crews, so it points at officers worth recruiting even when they are not in the best
+//! crew today.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::combat::rng::Rng;
+use crate::data::data_registry::DataRegistry;
+use crate::optimizer::crew_generator::{
+    build_officer_pools_from_registry, resolve_ship_type, CrewCandidate,
+};
+use crate::optimizer::genetic::random_crew;
+use crate::optimizer::monte_carlo::{
+    normalize_lookup_key, run_monte_carlo_deduped_with_shared, split_name_and_tier,
+};
+use crate::optimizer::{scenario_shared_data_from_registry, OptimizationScenario};
+
+/// Random crews sampled when the caller does not say.
+pub const DEFAULT_LEADERBOARD_SAMPLES: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SeatKind {
+    Captain,
+    Bridge,
+    BelowDecks,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OfficerContribution {
+    pub officer_id: String,
+    pub officer_name: String,
+    /// Sampled crews that seated this officer in this kind of seat.
+    pub samples: usize,
+    /// Mean win-rate change when the officer took its seat (the Shapley estimate).
+    pub marginal_win_rate: f64,
+    /// Standard error of `marginal_win_rate`; 0 with fewer than two samples.
+    pub std_error: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SeatLeaderboard {
+    pub seat: SeatKind,
+    /// Officers sampled in this seat, largest contribution first.
+    pub officers: Vec<OfficerContribution>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LeaderboardReport {
+    pub sampled_crews: usize,
+    /// Win rate with every seat empty (the baseline contributions are measured from).
+    pub empty_crew_win_rate: f64,
+    /// Mean win rate of the sampled full crews.
+    pub mean_crew_win_rate: f64,
+    pub seats: Vec<SeatLeaderboard>,
+}
+
+#[derive(Debug, Default)]
+struct Contribution {
+    sum: f64,
+    sum_sq: f64,
+    samples: usize,
+}
+
+/// Per-seat officer leaderboard for `scenario` from `samples` random crews. Every crew built along
+/// the way is simulated once with `scenario.simulation_count` fights.
+pub fn officer_leaderboard(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    samples: usize,
+) -> LeaderboardReport {
+    let shared = scenario_shared_data_from_registry(registry, scenario);
+    let pools = build_officer_pools_from_registry(
+        registry,
+        scenario.only_below_decks_with_ability,
+        scenario.profile_id,
+        resolve_ship_type(scenario.ship),
+    );
+    let mut rng = Rng::new(scenario.seed);
+    let slots = shared.crew_slots;
+    let empty = CrewCandidate {
+        captain: String::new(),
+        bridge: vec![None; slots.bridge],
+        below_decks: vec![None; slots.below_decks],
+    };
+
+    // Each sample: its seating order and the crew after each step (index 0 = empty crew).
+    let mut seatings: Vec<Vec<(SeatKind, String)>> = Vec::new();
+    let mut crews: Vec<CrewCandidate> = Vec::new();
+    for _ in 0..samples {
+        let Some(crew) = pools.as_ref().and_then(|p| random_crew(&mut rng, p, slots)) else {
+            break;
+        };
+        let mut order: Vec<(SeatKind, usize)> = vec![(SeatKind::Captain, 0)];
+        order.extend((0..crew.bridge.len()).filter_map(|i| {
+            crew.bridge[i].is_some().then_some((SeatKind::Bridge, i))
+        }));
+        order.extend((0..crew.below_decks.len()).filter_map(|i| {
+            crew.below_decks[i].is_some().then_some((SeatKind::BelowDecks, i))
+        }));
+        for i in (1..order.len()).rev() {
+            order.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+        }
+
+        let mut partial = empty.clone();
+        crews.push(partial.clone());
+        let mut seating = Vec::with_capacity(order.len());
+        for (seat, i) in order {
+            let name = match seat {
+                SeatKind::Captain => {
+                    partial.captain = crew.captain.clone();
+                    crew.captain.clone()
+                }
+                SeatKind::Bridge => {
+                    partial.bridge[i] = crew.bridge[i].clone();
+                    crew.bridge[i].clone().unwrap_or_default()
+                }
+                SeatKind::BelowDecks => {
+                    partial.below_decks[i] = crew.below_decks[i].clone();
+                    crew.below_decks[i].clone().unwrap_or_default()
+                }
+            };
+            crews.push(partial.clone());
+            seating.push((seat, name));
+        }
+        seatings.push(seating);
+    }
+
+    let win_rates: Vec<f64> = run_monte_carlo_deduped_with_shared(
+        shared.clone(),
+        &crews,
+        scenario.simulation_count,
+        scenario.seed,
+    )
+    .into_iter()
+    .map(|r| r.win_rate)
+    .collect();
+
+    let mut contributions: HashMap<(SeatKind, String), Contribution> = HashMap::new();
+    let mut full_crew_total = 0.0;
+    let mut start = 0;
+    for seating in &seatings {
+        for (step, (seat, name)) in seating.iter().enumerate() {
+            let gain = win_rates[start + step + 1] - win_rates[start + step];
+            let entry = contributions.entry((*seat, name.clone())).or_default();
+            entry.sum += gain;
+            entry.sum_sq += gain * gain;
+            entry.samples += 1;
+        }
+        full_crew_total += win_rates[start + seating.len()];
+        start += seating.len() + 1;
+    }
+
+    let seats = [SeatKind::Captain, SeatKind::Bridge, SeatKind::BelowDecks]
+        .into_iter()
+        .map(|seat| {
+            let mut officers: Vec<OfficerContribution> = contributions
+                .iter()
+                .filter(|((kind, _), _)| *kind == seat)
+                .map(|((_, name), c)| {
+                    let n = c.samples as f64;
+                    let mean = c.sum / n;
+                    let std_error = if c.samples > 1 {
+                        ((c.sum_sq - n * mean * mean).max(0.0) / (n - 1.0) / n).sqrt()
+                    } else {
+                        0.0
+                    };
+                    let base = split_name_and_tier(name).0;
+                    let officer = shared.officer_index.get(&normalize_lookup_key(&base));
+                    OfficerContribution {
+                        officer_id: officer.map_or_else(|| base.clone(), |o| o.id.clone()),
+                        officer_name: officer.map_or(base, |o| o.name.clone()),
+                        samples: c.samples,
+                        marginal_win_rate: mean,
+                        std_error,
+                    }
+                })
+                .collect();
+            officers.sort_by(|a, b| {
+                b.marginal_win_rate
+                    .total_cmp(&a.marginal_win_rate)
+                    .then_with(|| a.officer_name.cmp(&b.officer_name))
+            });
+            SeatLeaderboard { seat, officers }
+        })
+        .collect();
+
+    LeaderboardReport {
+        sampled_crews: seatings.len(),
+        empty_crew_win_rate: win_rates.first().copied().unwrap_or(0.0),
+        mean_crew_win_rate: if seatings.is_empty() {
+            0.0
+        } else {
+            full_crew_total / seatings.len() as f64
+        },
+        seats,
+    }
+}
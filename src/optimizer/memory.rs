@@ -0,0 +1,314 @@
+//! Approximate memory accounting for optimize runs.
+//!
+//! A [MemoryTracker] records the most crew candidates and simulation results a run held at once,
+//! against its [MemoryBudget]; [MemoryTracker::usage] turns those peaks into approximate bytes.
+//! The budget is enforced where results pile up: stages that would buffer every result (tiered
+//! scouting, two-phase bridge prefixes) fold them into the streaming [TopResults] reducer instead
+//! when the full buffer would not fit (see [ResultBuffer]), and the exhaustive reducer keeps at
+//! most as many crews as fit ([MemoryBudget::max_results]).
+//!
+//! Sizes are estimates: [APPROX_CANDIDATE_BYTES] and [APPROX_RESULT_BYTES] assume seven officer
+//! names of about 16 bytes per crew and ignore allocator overhead.
+
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::SimulationResult;
+use crate::optimizer::ranking::{rank_results_for, RankedCrewResult, ScenarioType, TopResults};
+
+/// Budget in MiB for one optimize run; unset or invalid → [DEFAULT_MEMORY_BUDGET_MB].
+pub const MEMORY_BUDGET_ENV: &str = "KOBAYASHI_MEMORY_BUDGET_MB";
+pub const DEFAULT_MEMORY_BUDGET_MB: u64 = 1024;
+
+/// Officer names held per crew: captain, bridge and below decks (a seven-seat ship).
+const NAMES_PER_CREW: usize = 7;
+/// Typical heap bytes of one officer name.
+const NAME_BYTES: usize = 16;
+const CREW_NAME_BYTES: usize = NAMES_PER_CREW * (size_of::<Option<String>>() + NAME_BYTES);
+
+/// Approximate bytes one buffered [CrewCandidate] occupies.
+pub const APPROX_CANDIDATE_BYTES: u64 = (size_of::<CrewCandidate>() + CREW_NAME_BYTES) as u64;
+/// Approximate bytes one buffered [SimulationResult] occupies.
+pub const APPROX_RESULT_BYTES: u64 = (size_of::<SimulationResult>() + CREW_NAME_BYTES) as u64;
+
+/// Memory an optimize run may use for buffered candidates and results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    pub bytes: u64,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::from_env_or_default()
+    }
+}
+
+impl MemoryBudget {
+    pub const fn from_mb(mb: u64) -> Self {
+        Self {
+            bytes: mb.saturating_mul(1024 * 1024),
+        }
+    }
+
+    /// Budget from `KOBAYASHI_MEMORY_BUDGET_MB` (empty, zero or invalid → the default).
+    pub fn from_env_or_default() -> Self {
+        let mb = std::env::var(MEMORY_BUDGET_ENV)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .filter(|&mb| mb > 0)
+            .unwrap_or(DEFAULT_MEMORY_BUDGET_MB);
+        Self::from_mb(mb)
+    }
+
+    /// Whether `results` buffered results fit.
+    pub fn fits_results(&self, results: usize) -> bool {
+        (results as u64).saturating_mul(APPROX_RESULT_BYTES) <= self.bytes
+    }
+
+    /// `requested` capped to the results that fit (at least one).
+    pub fn max_results(&self, requested: usize) -> usize {
+        let fitting = (self.bytes / APPROX_RESULT_BYTES).max(1);
+        requested.min(usize::try_from(fitting).unwrap_or(usize::MAX))
+    }
+}
+
+/// Peak buffers of an optimize run; see [MemoryTracker]. Shared by clones of a scenario, so
+/// follow-up runs (e.g. the auto strategy's GA cross-check) add to the same peaks.
+#[derive(Debug)]
+pub struct MemoryTracker {
+    budget: MemoryBudget,
+    peak_candidates: AtomicU64,
+    peak_results: AtomicU64,
+    streaming_top_k: AtomicBool,
+}
+
+impl Default for MemoryTracker {
+    fn default() -> Self {
+        Self::new(MemoryBudget::default())
+    }
+}
+
+impl MemoryTracker {
+    pub fn new(budget: MemoryBudget) -> Self {
+        Self {
+            budget,
+            peak_candidates: AtomicU64::new(0),
+            peak_results: AtomicU64::new(0),
+            streaming_top_k: AtomicBool::new(false),
+        }
+    }
+
+    pub fn budget(&self) -> MemoryBudget {
+        self.budget
+    }
+
+    /// Notes that `count` candidates are held at once.
+    pub fn hold_candidates(&self, count: usize) {
+        self.peak_candidates.fetch_max(count as u64, Ordering::Relaxed);
+    }
+
+    /// Notes that `count` simulation results are held at once.
+    pub fn hold_results(&self, count: usize) {
+        self.peak_results.fetch_max(count as u64, Ordering::Relaxed);
+    }
+
+    /// Notes that the budget moved a stage to the streaming top-K reducer or capped its K.
+    pub fn mark_streaming(&self) {
+        self.streaming_top_k.store(true, Ordering::Relaxed);
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        let peak_candidates = self.peak_candidates.load(Ordering::Relaxed);
+        let peak_results = self.peak_results.load(Ordering::Relaxed);
+        let result_buffer_bytes = peak_results.saturating_mul(APPROX_RESULT_BYTES);
+        MemoryUsage {
+            peak_candidates,
+            peak_results,
+            result_buffer_bytes,
+            approx_bytes: peak_candidates
+                .saturating_mul(APPROX_CANDIDATE_BYTES)
+                .saturating_add(result_buffer_bytes),
+            budget_bytes: self.budget.bytes,
+            streaming_top_k: self.streaming_top_k.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Approximate memory an optimize run used, reported in the optimize notes and job status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct MemoryUsage {
+    /// Most crew candidates held at once.
+    pub peak_candidates: u64,
+    /// Most simulation results held at once.
+    pub peak_results: u64,
+    /// Approximate bytes of the result buffer at its peak.
+    pub result_buffer_bytes: u64,
+    /// Approximate bytes of candidates and results at their peaks.
+    pub approx_bytes: u64,
+    pub budget_bytes: u64,
+    /// Whether the budget moved a stage to the streaming top-K reducer or capped its K.
+    pub streaming_top_k: bool,
+}
+
+impl MemoryUsage {
+    /// Whether anything was tracked (runs served from the result cache track nothing).
+    pub fn is_empty(&self) -> bool {
+        self.peak_candidates == 0 && self.peak_results == 0
+    }
+
+    /// One line for the optimize response notes.
+    pub fn note(&self) -> String {
+        let mib = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+        let mut note = format!(
+            "Memory: peak {} candidates and {} results buffered ({:.1} MiB of results), \
+             about {:.1} MiB of {:.0} MiB budget.",
+            self.peak_candidates,
+            self.peak_results,
+            mib(self.result_buffer_bytes),
+            mib(self.approx_bytes),
+            mib(self.budget_bytes)
+        );
+        if self.streaming_top_k {
+            note.push_str(
+                " Results were reduced to the best crews as they streamed in to stay within \
+                 budget.",
+            );
+        }
+        note
+    }
+}
+
+/// Results of one simulated stage that only needs its best `keep`: every result while the full
+/// stage fits the budget, otherwise a [TopResults] reducer. Both rank the same crews first.
+pub enum ResultBuffer<'a> {
+    All(Vec<SimulationResult>, &'a MemoryTracker),
+    Top(TopResults, &'a MemoryTracker),
+}
+
+impl<'a> ResultBuffer<'a> {
+    /// Buffer for a stage expecting `expected` results of which the best `keep` are used.
+    pub fn for_stage(
+        expected: usize,
+        keep: usize,
+        scenario_type: ScenarioType,
+        memory: &'a MemoryTracker,
+    ) -> Self {
+        if memory.budget().fits_results(expected) {
+            Self::All(Vec::with_capacity(expected), memory)
+        } else {
+            memory.mark_streaming();
+            let keep = memory.budget().max_results(keep);
+            Self::Top(TopResults::new(keep, scenario_type), memory)
+        }
+    }
+
+    /// Adds a simulated batch; the batch counts toward the peak while it is folded in.
+    pub fn extend(&mut self, batch: Vec<SimulationResult>) {
+        match self {
+            Self::All(results, memory) => {
+                results.extend(batch);
+                memory.hold_results(results.len());
+            }
+            Self::Top(top, memory) => {
+                memory.hold_results(top.len() + batch.len());
+                top.extend(batch);
+            }
+        }
+    }
+
+    /// Ranked best first; the caller takes as many as it keeps.
+    pub fn into_ranked(self, scenario_type: ScenarioType) -> Vec<RankedCrewResult> {
+        match self {
+            Self::All(results, _) => rank_results_for(results, scenario_type),
+            Self::Top(top, _) => top.into_ranked(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(captain: &str, win_rate: f64) -> SimulationResult {
+        SimulationResult {
+            candidate: CrewCandidate::new(captain, vec![], vec![]),
+            win_rate,
+            stall_rate: 0.0,
+            loss_rate: 1.0 - win_rate,
+            avg_hull_remaining: win_rate,
+            avg_survival_share: 1.0,
+            avg_damage_dealt: win_rate,
+            survival_rate: win_rate,
+            avg_waves_cleared: 0.0,
+            median_rounds_to_kill: None,
+            p95_rounds_to_kill: None,
+            avg_damage_per_round: 0.0,
+            avg_hull_lost: 1.0 - win_rate,
+        }
+    }
+
+    #[test]
+    fn budget_caps_results_to_what_fits() {
+        let budget = MemoryBudget {
+            bytes: APPROX_RESULT_BYTES * 10,
+        };
+        assert!(budget.fits_results(10));
+        assert!(!budget.fits_results(11));
+        assert_eq!(budget.max_results(1000), 10);
+        assert_eq!(budget.max_results(3), 3);
+        assert_eq!(MemoryBudget { bytes: 0 }.max_results(5), 1);
+    }
+
+    #[test]
+    fn over_budget_stage_streams_and_ranks_like_the_full_buffer() {
+        let results: Vec<_> =
+            (0..50).map(|i| result(&format!("c{i}"), (i % 7) as f64 / 7.0)).collect();
+        let ranked_all = {
+            let memory = MemoryTracker::new(MemoryBudget::from_mb(64));
+            let mut buffer = ResultBuffer::for_stage(50, 5, ScenarioType::Offense, &memory);
+            assert!(matches!(buffer, ResultBuffer::All(..)));
+            buffer.extend(results.clone());
+            assert!(!memory.usage().streaming_top_k);
+            buffer.into_ranked(ScenarioType::Offense)
+        };
+        let memory = MemoryTracker::new(MemoryBudget {
+            bytes: APPROX_RESULT_BYTES * 20,
+        });
+        let mut buffer = ResultBuffer::for_stage(50, 5, ScenarioType::Offense, &memory);
+        assert!(matches!(buffer, ResultBuffer::Top(..)));
+        for batch in results.chunks(10) {
+            buffer.extend(batch.to_vec());
+        }
+        let streamed = buffer.into_ranked(ScenarioType::Offense);
+        let usage = memory.usage();
+        assert!(usage.streaming_top_k);
+        assert_eq!(usage.peak_results, 15);
+        assert_eq!(usage.result_buffer_bytes, 15 * APPROX_RESULT_BYTES);
+        let names = |ranked: &[RankedCrewResult]| -> Vec<String> {
+            ranked.iter().map(|r| r.captain.clone()).collect()
+        };
+        assert_eq!(names(&streamed), names(&ranked_all[..5]));
+    }
+
+    #[test]
+    fn usage_reports_peaks_and_approximate_bytes() {
+        let memory = MemoryTracker::new(MemoryBudget::from_mb(1));
+        memory.hold_candidates(100);
+        memory.hold_candidates(40);
+        memory.hold_results(25);
+        let usage = memory.usage();
+        assert_eq!(usage.peak_candidates, 100);
+        assert_eq!(usage.peak_results, 25);
+        assert_eq!(
+            usage.approx_bytes,
+            100 * APPROX_CANDIDATE_BYTES + 25 * APPROX_RESULT_BYTES
+        );
+        assert_eq!(usage.budget_bytes, 1024 * 1024);
+        assert!(usage.note().starts_with("Memory: peak 100 candidates and 25 results"));
+        assert!(MemoryUsage::default().is_empty());
+    }
+}
@@ -1,29 +1,108 @@
 pub mod analytical;
+pub mod boosts;
+pub mod bracket;
 pub mod crew_generator;
+#[cfg(feature = "server")]
+pub mod explain;
 pub mod genetic;
+pub mod leaderboard;
+pub mod memory;
 pub mod monte_carlo;
 pub mod ranking;
+pub mod result_cache;
+pub mod stat_sensitivity;
+pub mod sweep;
 pub mod tiered;
+pub mod two_phase;
+pub mod upgrades;
 
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::combat::AttackerCondition;
 use crate::data::data_registry::DataRegistry;
+use crate::data::officer::UnknownOfficerMode;
 use crate::optimizer::analytical::expected_damage;
-use crate::optimizer::crew_generator::{CandidateStrategy, CrewCandidate, CrewGenerator};
-use crate::optimizer::genetic::{run_genetic_optimizer_ranked, GeneticConfig};
-use crate::optimizer::monte_carlo::{
-    run_monte_carlo_parallel, run_monte_carlo_parallel_with_registry, SimulationResult,
+use crate::optimizer::crew_generator::{
+    CandidateIterator, CandidateStrategy, CrewCandidate, CrewGenerator, CrewSlots,
 };
-use crate::optimizer::ranking::{rank_results, RankedCrewResult};
-use crate::optimizer::tiered::{
-    run_tiered_with_registry_with_progress, DEFAULT_SCOUT_SIMS, DEFAULT_TOP_K,
+use crate::optimizer::memory::MemoryTracker;
+use crate::optimizer::genetic::{
+    run_genetic_optimizer_ranked, FitnessCacheStats, GeneticConfig, GeneticOverrides,
 };
+use crate::optimizer::monte_carlo::run_monte_carlo_with_shared;
+use crate::optimizer::ranking::{RankedCrewResult, ScenarioType, TopResults, DEFAULT_MAX_RESULTS};
+use crate::optimizer::result_cache::{cached_ranked_results, OptimizePath};
+use crate::optimizer::tiered::{run_tiered_pipeline, StageProgress, TieredConfig, TieredStage};
+use crate::optimizer::two_phase::{run_two_phase_pipeline, TwoPhaseConfig};
+use crate::parallel::{AdaptiveBatches, WorkerPool};
 use crate::optimizer::monte_carlo::scenario::{
     build_shared_scenario_data_from_registry, build_shared_scenario_data_standalone,
     scenario_to_combat_input_from_shared, SharedScenarioData,
 };
-use crate::parallel::batch_ranges;
 
-/// Number of progress-reporting batches for optimize-with-progress (UI jobs).
-const OPTIMIZE_PROGRESS_BATCH_COUNT: usize = 40;
+/// Provisional leaders an exhaustive run reports with each completed progress batch.
+pub const PROVISIONAL_LEADERS: usize = 5;
+
+/// What an optimize run is doing when it reports progress. `done / total` stay in the strategy's
+/// overall units (crews, generations or pipeline units); the phase names the work behind them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizePhase {
+    /// Enumerating or analytically pre-scoring candidates; nothing simulated yet.
+    Generating,
+    /// Monte Carlo over candidate crews (or bridge prefixes, or scouted crews).
+    Simulating,
+    /// Genetic algorithm evolution; `done / total` are generations.
+    GaGeneration,
+    /// Full-sim scoring of the finalists and the final ranking.
+    FinalRanking,
+}
+
+/// Phase a tiered or two-phase pipeline stage reports as. Tiered's analytical stage pre-scores
+/// every candidate before simulating; two-phase's fills below decks between simulated phases.
+fn pipeline_phase(strategy: OptimizerStrategy, stage: TieredStage) -> OptimizePhase {
+    match (strategy, stage) {
+        (_, TieredStage::Confirm) => OptimizePhase::FinalRanking,
+        (OptimizerStrategy::Tiered, TieredStage::Analytical) => OptimizePhase::Generating,
+        _ => OptimizePhase::Simulating,
+    }
+}
+
+/// Registry-backed scenario data for `scenario`, with its fight modifiers (mining delay, waves).
+pub(crate) fn scenario_shared_data_from_registry(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+) -> SharedScenarioData {
+    build_shared_scenario_data_from_registry(
+        registry,
+        scenario.ship,
+        scenario.hostile,
+        scenario.ship_tier,
+        scenario.ship_level,
+        scenario.profile_id,
+    )
+    .with_attacker_delay(scenario.mining_rounds)
+    .with_initiative(scenario.scenario_type.initiative())
+    .with_waves(scenario.waves, scenario.wave_repair)
+    .with_attacker_condition(scenario.attacker_condition)
+    .with_unknown_officers(scenario.unknown_officers)
+}
+
+/// Candidate generation for `scenario`, filling the seats of the ship the fights will use.
+fn scenario_candidate_strategy(
+    scenario: &OptimizationScenario<'_>,
+    shared: &SharedScenarioData,
+) -> CandidateStrategy {
+    CandidateStrategy {
+        max_candidates: scenario.max_candidates,
+        only_below_decks_with_ability: scenario.only_below_decks_with_ability,
+        crew_slots: Some(shared.crew_slots),
+        excluded_officers: scenario.excluded_officers.to_vec(),
+        ..CandidateStrategy::default()
+    }
+}
 
 /// Order candidates by closed-form expected hull damage (high first) so limited `max_candidates`
 /// slices and progress batches prioritize analytically stronger crews. See [crate::optimizer::analytical].
@@ -41,6 +120,30 @@ fn sort_candidates_by_analytical_expected_damage(
     indexed.into_iter().map(|(_, c)| c).collect()
 }
 
+/// Exhaustive runs pull candidates from the generator in chunks of this size, order each chunk
+/// analytically, and simulate it, so unbounded runs never materialize the full candidate set.
+const EXHAUSTIVE_STREAM_CHUNK: usize = 4096;
+
+/// Next chunk of streamed candidates, ordered by [sort_candidates_by_analytical_expected_damage].
+fn next_sorted_chunk(
+    candidates: &mut CandidateIterator,
+    shared: &SharedScenarioData,
+    seed: u64,
+) -> Vec<CrewCandidate> {
+    let chunk: Vec<CrewCandidate> = candidates.by_ref().take(EXHAUSTIVE_STREAM_CHUNK).collect();
+    sort_candidates_by_analytical_expected_damage(shared, chunk, seed)
+}
+
+/// Bounded reducer the exhaustive loops fold each simulated batch into.
+fn exhaustive_top_results(scenario: &OptimizationScenario<'_>) -> TopResults {
+    let requested = scenario.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+    let k = scenario.budgeted_max_results().unwrap_or(DEFAULT_MAX_RESULTS);
+    if k < requested {
+        scenario.memory.mark_streaming();
+    }
+    TopResults::new(k, scenario.scenario_type)
+}
+
 /// Optimizer strategy: exhaustive/sampled (candidate generation), genetic, or tiered (scout → confirm).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OptimizerStrategy {
@@ -50,6 +153,24 @@ pub enum OptimizerStrategy {
     Genetic,
     /// Two-pass: cheap scouting sims then full MC on top K.
     Tiered,
+    /// Bridge prefixes by simulation, then below decks by stat value (see [two_phase]).
+    TwoPhase,
+}
+
+/// `strategy: "auto"` searches exhaustively up to this many candidates, and with the GA above it.
+pub const AUTO_EXHAUSTIVE_MAX_CANDIDATES: usize = 20_000;
+
+/// An auto run that chose exhaustive also runs the GA to cross-check its top crew when neither
+/// search needs more than this many sims.
+pub const AUTO_AGREEMENT_MAX_SIMS: u64 = 2_000_000;
+
+/// Strategy `"auto"` runs for a search space of `candidate_count` exhaustive candidates.
+pub fn auto_strategy(candidate_count: usize) -> OptimizerStrategy {
+    if candidate_count <= AUTO_EXHAUSTIVE_MAX_CANDIDATES {
+        OptimizerStrategy::Exhaustive
+    } else {
+        OptimizerStrategy::Genetic
+    }
 }
 
 impl Default for OptimizerStrategy {
@@ -58,7 +179,10 @@ impl Default for OptimizerStrategy {
     }
 }
 
+/// One optimizer run. Outside the crate, start from [OptimizationScenario::new] and set the
+/// fields to change: new options are added as fields.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct OptimizationScenario<'a> {
     pub ship: &'a str,
     pub hostile: &'a str,
@@ -79,10 +203,61 @@ pub struct OptimizationScenario<'a> {
     pub seed_population: Vec<CrewCandidate>,
     /// Profile id for roster/profile/forbidden-tech paths. None = use default profile.
     pub profile_id: Option<&'a str>,
-    /// Tiered only: sims per crew in scouting pass. None = use default (500).
+    /// Tiered and two-phase only: sims per crew (two-phase: per bridge prefix) in the scouting
+    /// pass. None = use default (500).
     pub tiered_scout_sims: Option<usize>,
-    /// Tiered only: number of top crews to run full confirmation. None = use default (20).
+    /// Tiered and two-phase only: number of top crews (two-phase: bridge prefixes) to run full
+    /// confirmation. None = use default (50).
     pub tiered_top_k: Option<usize>,
+    /// Offense (default) ranks by kill rate; Defense lets the hostile fire first and ranks by
+    /// survival and damage dealt; Mining ranks by survival rate; Waves ranks by waves cleared.
+    pub scenario_type: ScenarioType,
+    /// Opening rounds the player's ship spends mining (holding fire) before fighting back.
+    pub mining_rounds: u32,
+    /// Consecutive copies of the hostile fought without a reset (0 or 1 = single fight).
+    pub waves: u32,
+    /// Share of missing hull and shields repaired between waves (0–1).
+    pub wave_repair: f64,
+    /// Hull and shields the player's ship starts with (a ship that is already damaged).
+    pub attacker_condition: AttackerCondition,
+    /// Exhaustive only: best crews kept while results stream in; the rest are dropped as they
+    /// are simulated. None = [DEFAULT_MAX_RESULTS].
+    pub max_results: Option<usize>,
+    /// Genetic only: replaces fields of the default or seeded [GeneticConfig].
+    pub genetic: GeneticOverrides,
+    /// Officer names kept out of every crew (officers committed to other loadouts).
+    pub excluded_officers: &'a [String],
+    /// Seats for crew names that match no officer (seeded or caller-built crews).
+    pub unknown_officers: UnknownOfficerMode,
+    /// Memory budget for buffered candidates and results, and the run's peak buffers
+    /// (see [memory]).
+    pub memory: Arc<MemoryTracker>,
+    /// Pool the genetic optimizer scores and breeds on; its priority is the caller's (background
+    /// for server optimize jobs).
+    pub pool: WorkerPool,
+}
+
+impl<'a> OptimizationScenario<'a> {
+    /// Default run (exhaustive, 5000 sims per crew, offense) of `ship` against `hostile`.
+    pub fn new(ship: &'a str, hostile: &'a str) -> Self {
+        Self {
+            ship,
+            hostile,
+            ..Self::default()
+        }
+    }
+
+    /// [max_results](Self::max_results) lowered to what the memory budget holds; unchanged
+    /// (including None) when it fits.
+    pub fn budgeted_max_results(&self) -> Option<usize> {
+        let requested = self.max_results.unwrap_or(DEFAULT_MAX_RESULTS);
+        let kept = self.memory.budget().max_results(requested);
+        if kept < requested {
+            Some(kept)
+        } else {
+            self.max_results
+        }
+    }
 }
 
 impl Default for OptimizationScenario<'_> {
@@ -101,28 +276,175 @@ impl Default for OptimizationScenario<'_> {
             profile_id: None,
             tiered_scout_sims: None,
             tiered_top_k: None,
+            scenario_type: ScenarioType::Offense,
+            mining_rounds: 0,
+            waves: 0,
+            wave_repair: 0.0,
+            attacker_condition: AttackerCondition::default(),
+            max_results: None,
+            genetic: GeneticOverrides::default(),
+            excluded_officers: &[],
+            unknown_officers: UnknownOfficerMode::default(),
+            memory: Arc::default(),
+            pool: WorkerPool::default(),
         }
     }
 }
 
+/// Run the scenario's optimizer. Identical scenarios are served from [result_cache] when enabled.
 pub fn optimize_scenario(scenario: &OptimizationScenario<'_>) -> Vec<RankedCrewResult> {
-    match scenario.strategy {
-        OptimizerStrategy::Exhaustive => optimize_scenario_exhaustive(scenario),
-        OptimizerStrategy::Genetic => optimize_scenario_genetic(scenario, |_, _, _| true),
-        OptimizerStrategy::Tiered => optimize_scenario_exhaustive(scenario), // Tiered requires registry; fallback when none
+    cached_ranked_results(scenario, OptimizePath::Standalone, || {
+        let results = match scenario.strategy {
+            OptimizerStrategy::Exhaustive => optimize_scenario_exhaustive(scenario),
+            OptimizerStrategy::Genetic => optimize_scenario_genetic(scenario, |_, _, _, _| true).0,
+            OptimizerStrategy::Tiered => {
+                optimize_scenario_tiered_standalone(scenario, |_, _, _| true)
+            }
+            OptimizerStrategy::TwoPhase => {
+                optimize_scenario_two_phase_standalone(scenario, |_, _, _| true)
+            }
+        };
+        (results, true)
+    })
+}
+
+/// Pipeline knobs from the scenario; unset fields keep the [TieredConfig] defaults.
+fn tiered_config(scenario: &OptimizationScenario<'_>) -> TieredConfig {
+    let defaults = TieredConfig::default();
+    TieredConfig {
+        scout_sims: scenario.tiered_scout_sims.unwrap_or(defaults.scout_sims),
+        top_k: scenario.tiered_top_k.unwrap_or(defaults.top_k),
+        ..defaults
     }
 }
 
-/// Tiered path with registry: generate candidates, then scouting → top K → full MC.
-fn optimize_scenario_tiered_with_registry(
-    registry: &DataRegistry,
+/// Two-phase knobs from the scenario's tiered fields; unset fields keep the defaults.
+fn two_phase_config(scenario: &OptimizationScenario<'_>) -> TwoPhaseConfig {
+    let defaults = TwoPhaseConfig::default();
+    TwoPhaseConfig {
+        bridge_sims: scenario.tiered_scout_sims.unwrap_or(defaults.bridge_sims),
+        top_bridges: scenario.tiered_top_k.unwrap_or(defaults.top_bridges),
+    }
+}
+
+/// Captain + bridge prefixes (below decks empty) for the two-phase path, and the ordered
+/// below-decks pool phase 2 fills from.
+fn two_phase_prefixes(
     scenario: &OptimizationScenario<'_>,
-) -> Vec<RankedCrewResult> {
+    shared: &SharedScenarioData,
+    candidates: impl FnOnce(&CrewGenerator) -> CandidateIterator,
+) -> (Vec<CrewCandidate>, Vec<String>) {
     let generator = CrewGenerator::with_strategy(CandidateStrategy {
-        max_candidates: scenario.max_candidates,
-        only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-        ..CandidateStrategy::default()
+        crew_slots: Some(CrewSlots {
+            below_decks: 0,
+            ..shared.crew_slots
+        }),
+        ..scenario_candidate_strategy(scenario, shared)
+    });
+    let prefixes = candidates(&generator);
+    let below_decks_pool = prefixes.pools().below_decks.clone();
+    (prefixes.collect(), below_decks_pool)
+}
+
+/// Two-phase path without registry: bridge prefixes → below-decks fill → confirm.
+fn optimize_scenario_two_phase_standalone<F>(
+    scenario: &OptimizationScenario<'_>,
+    on_progress: F,
+) -> Vec<RankedCrewResult>
+where
+    F: FnMut(u32, u32, StageProgress) -> bool,
+{
+    let shared = build_shared_scenario_data_standalone(scenario.ship, scenario.hostile)
+        .with_attacker_delay(scenario.mining_rounds)
+        .with_initiative(scenario.scenario_type.initiative())
+        .with_waves(scenario.waves, scenario.wave_repair)
+        .with_attacker_condition(scenario.attacker_condition);
+    let (prefixes, pool) = two_phase_prefixes(scenario, &shared, |generator| {
+        generator.candidate_iter(scenario.ship, scenario.hostile, scenario.seed)
+    });
+    run_two_phase_pipeline(
+        shared,
+        prefixes,
+        &pool,
+        two_phase_config(scenario),
+        scenario.simulation_count.max(1),
+        scenario.seed,
+        scenario.scenario_type,
+        &scenario.memory,
+        on_progress,
+    )
+}
+
+/// Two-phase path with registry: bridge prefixes → below-decks fill → confirm.
+fn optimize_scenario_two_phase_with_registry<F>(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    on_progress: F,
+) -> Vec<RankedCrewResult>
+where
+    F: FnMut(u32, u32, StageProgress) -> bool,
+{
+    let shared = scenario_shared_data_from_registry(registry, scenario);
+    let (prefixes, pool) = two_phase_prefixes(scenario, &shared, |generator| {
+        generator.candidate_iter_from_registry(
+            registry,
+            scenario.ship,
+            scenario.hostile,
+            scenario.seed,
+            scenario.profile_id,
+        )
     });
+    run_two_phase_pipeline(
+        shared,
+        prefixes,
+        &pool,
+        two_phase_config(scenario),
+        scenario.simulation_count.max(1),
+        scenario.seed,
+        scenario.scenario_type,
+        &scenario.memory,
+        on_progress,
+    )
+}
+
+/// Tiered path without registry: generate candidates, then analytical → scout → confirm.
+fn optimize_scenario_tiered_standalone<F>(
+    scenario: &OptimizationScenario<'_>,
+    on_progress: F,
+) -> Vec<RankedCrewResult>
+where
+    F: FnMut(u32, u32, StageProgress) -> bool,
+{
+    let shared = build_shared_scenario_data_standalone(scenario.ship, scenario.hostile)
+        .with_attacker_delay(scenario.mining_rounds)
+        .with_initiative(scenario.scenario_type.initiative())
+        .with_waves(scenario.waves, scenario.wave_repair)
+        .with_attacker_condition(scenario.attacker_condition);
+    let generator = CrewGenerator::with_strategy(scenario_candidate_strategy(scenario, &shared));
+    let candidates = generator.generate_candidates(scenario.ship, scenario.hostile, scenario.seed);
+    run_tiered_pipeline(
+        shared,
+        candidates,
+        tiered_config(scenario),
+        scenario.simulation_count.max(1),
+        scenario.seed,
+        scenario.scenario_type,
+        &scenario.memory,
+        on_progress,
+    )
+}
+
+/// Tiered path with registry: generate candidates, then analytical → scout → confirm.
+fn optimize_scenario_tiered_with_registry<F>(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    on_progress: F,
+) -> Vec<RankedCrewResult>
+where
+    F: FnMut(u32, u32, StageProgress) -> bool,
+{
+    let shared = scenario_shared_data_from_registry(registry, scenario);
+    let generator = CrewGenerator::with_strategy(scenario_candidate_strategy(scenario, &shared));
     let candidates = generator.generate_candidates_from_registry(
         registry,
         scenario.ship,
@@ -130,29 +452,15 @@ fn optimize_scenario_tiered_with_registry(
         scenario.seed,
         scenario.profile_id,
     );
-    let shared_tiered = build_shared_scenario_data_from_registry(
-        registry,
-        scenario.ship,
-        scenario.hostile,
-        None,
-        None,
-        scenario.profile_id,
-    );
-    let candidates =
-        sort_candidates_by_analytical_expected_damage(&shared_tiered, candidates, scenario.seed);
-    let scout_sims = scenario.tiered_scout_sims.unwrap_or(DEFAULT_SCOUT_SIMS);
-    let top_k = scenario.tiered_top_k.unwrap_or(DEFAULT_TOP_K);
-    run_tiered_with_registry_with_progress(
-        registry,
-        scenario.ship,
-        scenario.hostile,
+    run_tiered_pipeline(
+        shared,
         candidates,
-        scout_sims,
+        tiered_config(scenario),
         scenario.simulation_count.max(1),
-        top_k,
         scenario.seed,
-        scenario.profile_id,
-        |_, _| true,
+        scenario.scenario_type,
+        &scenario.memory,
+        on_progress,
     )
 }
 
@@ -161,11 +469,21 @@ pub fn optimize_scenario_with_registry(
     registry: &DataRegistry,
     scenario: &OptimizationScenario<'_>,
 ) -> Vec<RankedCrewResult> {
-    match scenario.strategy {
-        OptimizerStrategy::Exhaustive => optimize_scenario_exhaustive_with_registry(registry, scenario),
-        OptimizerStrategy::Genetic => optimize_scenario_genetic(scenario, |_, _, _| true),
-        OptimizerStrategy::Tiered => optimize_scenario_tiered_with_registry(registry, scenario),
-    }
+    cached_ranked_results(scenario, OptimizePath::Registry, || {
+        let results = match scenario.strategy {
+            OptimizerStrategy::Exhaustive => {
+                optimize_scenario_exhaustive_with_registry(registry, scenario)
+            }
+            OptimizerStrategy::Genetic => optimize_scenario_genetic(scenario, |_, _, _, _| true).0,
+            OptimizerStrategy::Tiered => {
+                optimize_scenario_tiered_with_registry(registry, scenario, |_, _, _| true)
+            }
+            OptimizerStrategy::TwoPhase => {
+                optimize_scenario_two_phase_with_registry(registry, scenario, |_, _, _| true)
+            }
+        };
+        (results, true)
+    })
 }
 
 /// Exhaustive path using registry (no officer/ship/hostile reload).
@@ -173,280 +491,336 @@ fn optimize_scenario_exhaustive_with_registry(
     registry: &DataRegistry,
     scenario: &OptimizationScenario<'_>,
 ) -> Vec<RankedCrewResult> {
-    let generator = CrewGenerator::with_strategy(crate::optimizer::crew_generator::CandidateStrategy {
-        max_candidates: scenario.max_candidates,
-        only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-        ..crate::optimizer::crew_generator::CandidateStrategy::default()
-    });
-    let candidates = generator.generate_candidates_from_registry(
-        registry,
-        scenario.ship,
-        scenario.hostile,
-        scenario.seed,
-        scenario.profile_id,
-    );
-    let shared_ex = build_shared_scenario_data_from_registry(
-        registry,
-        scenario.ship,
-        scenario.hostile,
-        scenario.ship_tier,
-        scenario.ship_level,
-        scenario.profile_id,
-    );
-    let candidates =
-        sort_candidates_by_analytical_expected_damage(&shared_ex, candidates, scenario.seed);
-    let (simulation_results, _) = run_monte_carlo_parallel_with_registry(
+    let shared_ex = scenario_shared_data_from_registry(registry, scenario);
+    let generator =
+        CrewGenerator::with_strategy(scenario_candidate_strategy(scenario, &shared_ex));
+    let mut candidates = generator.candidate_iter_from_registry(
         registry,
         scenario.ship,
         scenario.hostile,
-        scenario.ship_tier,
-        scenario.ship_level,
-        &candidates,
-        scenario.simulation_count.max(1),
         scenario.seed,
         scenario.profile_id,
     );
-    rank_results(simulation_results)
+    let mut top = exhaustive_top_results(scenario);
+    loop {
+        let chunk = next_sorted_chunk(&mut candidates, &shared_ex, scenario.seed);
+        if chunk.is_empty() {
+            break;
+        }
+        scenario.memory.hold_candidates(chunk.len());
+        let results = run_monte_carlo_with_shared(
+            shared_ex.clone(),
+            &chunk,
+            scenario.simulation_count.max(1),
+            scenario.seed,
+            true,
+        );
+        scenario.memory.hold_results(top.len() + results.len());
+        top.extend(results);
+    }
+    top.into_ranked()
 }
 
 /// Exhaustive/sampled path: generator → Monte Carlo → rank.
 fn optimize_scenario_exhaustive(scenario: &OptimizationScenario<'_>) -> Vec<RankedCrewResult> {
-    let generator = CrewGenerator::with_strategy(crate::optimizer::crew_generator::CandidateStrategy {
-        max_candidates: scenario.max_candidates,
-        only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-        ..crate::optimizer::crew_generator::CandidateStrategy::default()
-    });
-    let candidates = generator.generate_candidates(scenario.ship, scenario.hostile, scenario.seed);
-    let shared = build_shared_scenario_data_standalone(
-        scenario.ship,
-        scenario.hostile,
-    );
-    let candidates =
-        sort_candidates_by_analytical_expected_damage(&shared, candidates, scenario.seed);
-    let simulation_results = run_monte_carlo_parallel(
-        scenario.ship,
-        scenario.hostile,
-        &candidates,
-        scenario.simulation_count.max(1),
-        scenario.seed,
-    );
-    rank_results(simulation_results)
+    let shared = build_shared_scenario_data_standalone(scenario.ship, scenario.hostile)
+        .with_attacker_delay(scenario.mining_rounds)
+        .with_initiative(scenario.scenario_type.initiative())
+        .with_waves(scenario.waves, scenario.wave_repair)
+        .with_attacker_condition(scenario.attacker_condition);
+    let generator = CrewGenerator::with_strategy(scenario_candidate_strategy(scenario, &shared));
+    let mut candidates = generator.candidate_iter(scenario.ship, scenario.hostile, scenario.seed);
+    let mut top = exhaustive_top_results(scenario);
+    loop {
+        let chunk = next_sorted_chunk(&mut candidates, &shared, scenario.seed);
+        if chunk.is_empty() {
+            break;
+        }
+        scenario.memory.hold_candidates(chunk.len());
+        let results = run_monte_carlo_with_shared(
+            shared.clone(),
+            &chunk,
+            scenario.simulation_count.max(1),
+            scenario.seed,
+            true,
+        );
+        scenario.memory.hold_results(top.len() + results.len());
+        top.extend(results);
+    }
+    top.into_ranked()
 }
 
 /// Genetic path: GA with progress callback, then final MC on top candidates, then rank.
 /// When `scenario.seed_population` is non-empty, uses seeded config (larger pop, adaptive mutation).
-/// Progress callback returns true to continue, false to abort.
+/// `scenario.genetic` is applied on top of either config. Also returns the GA's fitness cache
+/// statistics.
+/// Progress callback `(phase, generation, generations, best_fitness)` returns true to continue,
+/// false to abort.
 pub fn optimize_scenario_genetic<F>(
     scenario: &OptimizationScenario<'_>,
     on_progress: F,
-) -> Vec<RankedCrewResult>
+) -> (Vec<RankedCrewResult>, FitnessCacheStats)
 where
-    F: FnMut(usize, usize, f32) -> bool,
+    F: FnMut(OptimizePhase, usize, usize, f32) -> bool,
 {
     let config = if scenario.seed_population.is_empty() {
         GeneticConfig {
             only_below_decks_with_ability: scenario.only_below_decks_with_ability,
+            scenario_type: scenario.scenario_type,
+            mining_rounds: scenario.mining_rounds,
+            waves: scenario.waves,
+            wave_repair: scenario.wave_repair,
+            attacker_condition: scenario.attacker_condition,
+            excluded_officers: scenario.excluded_officers.to_vec(),
+            pool: scenario.pool,
             ..GeneticConfig::default()
         }
     } else {
         let mut cfg = GeneticConfig::seeded(scenario.seed_population.clone());
         cfg.only_below_decks_with_ability = scenario.only_below_decks_with_ability;
+        cfg.scenario_type = scenario.scenario_type;
+        cfg.mining_rounds = scenario.mining_rounds;
+        cfg.waves = scenario.waves;
+        cfg.wave_repair = scenario.wave_repair;
+        cfg.attacker_condition = scenario.attacker_condition;
+        cfg.excluded_officers = scenario.excluded_officers.to_vec();
+        cfg.pool = scenario.pool;
         cfg
     };
-    run_genetic_optimizer_ranked(
+    let config = config.with_overrides(&scenario.genetic);
+    scenario.memory.hold_candidates(config.islands.max(1) * config.population_size);
+    let (results, stats) = run_genetic_optimizer_ranked(
         scenario.ship,
         scenario.hostile,
         &config,
         scenario.seed,
         scenario.simulation_count.max(1),
         on_progress,
-    )
+    );
+    scenario.memory.hold_results(results.len());
+    (results, stats)
 }
 
-/// Like [optimize_scenario] but runs in batches and invokes `on_progress(done, total)`.
-/// For exhaustive: done/total = crews. For genetic: done/total = generations. Tiered requires registry.
+/// Like [optimize_scenario] but runs in batches and invokes `on_progress(phase, done, total)`.
+/// For exhaustive: done/total = crews. For genetic: done/total = generations. For tiered and
+/// two-phase: done/total = pipeline units (see [TieredConfig::total_work],
+/// [TwoPhaseConfig::total_work]).
 pub fn optimize_scenario_with_progress<F>(
     scenario: &OptimizationScenario<'_>,
     mut on_progress: F,
 ) -> Vec<RankedCrewResult>
 where
-    F: FnMut(u32, u32),
+    F: FnMut(OptimizePhase, u32, u32),
 {
     match scenario.strategy {
         OptimizerStrategy::Tiered => {
-            // No registry; fall back to exhaustive with progress
-            let scenario_ex = OptimizationScenario {
-                ship: scenario.ship,
-                hostile: scenario.hostile,
-                ship_tier: scenario.ship_tier,
-                ship_level: scenario.ship_level,
-                simulation_count: scenario.simulation_count,
-                seed: scenario.seed,
-                max_candidates: scenario.max_candidates,
-                strategy: OptimizerStrategy::Exhaustive,
-                only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-                seed_population: scenario.seed_population.clone(),
-                profile_id: scenario.profile_id,
-                tiered_scout_sims: scenario.tiered_scout_sims,
-                tiered_top_k: scenario.tiered_top_k,
-            };
-            optimize_scenario_with_progress(&scenario_ex, on_progress)
+            optimize_scenario_tiered_standalone(scenario, |done, total, stage| {
+                on_progress(pipeline_phase(scenario.strategy, stage.stage), done, total);
+                true
+            })
+        }
+        OptimizerStrategy::TwoPhase => {
+            optimize_scenario_two_phase_standalone(scenario, |done, total, stage| {
+                on_progress(pipeline_phase(scenario.strategy, stage.stage), done, total);
+                true
+            })
         }
         OptimizerStrategy::Exhaustive => {
-            let generator = CrewGenerator::with_strategy(
-                crate::optimizer::crew_generator::CandidateStrategy {
-                    max_candidates: scenario.max_candidates,
-                    only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-                    ..crate::optimizer::crew_generator::CandidateStrategy::default()
-                },
-            );
-            let candidates =
-                generator.generate_candidates(scenario.ship, scenario.hostile, scenario.seed);
-            let shared = build_shared_scenario_data_standalone(
-                scenario.ship,
-                scenario.hostile,
-            );
-            let candidates =
-                sort_candidates_by_analytical_expected_damage(&shared, candidates, scenario.seed);
+            let shared = build_shared_scenario_data_standalone(scenario.ship, scenario.hostile)
+                .with_attacker_delay(scenario.mining_rounds)
+                .with_initiative(scenario.scenario_type.initiative())
+                .with_waves(scenario.waves, scenario.wave_repair)
+                .with_attacker_condition(scenario.attacker_condition);
+            let generator =
+                CrewGenerator::with_strategy(scenario_candidate_strategy(scenario, &shared));
+            let mut candidates =
+                generator.candidate_iter(scenario.ship, scenario.hostile, scenario.seed);
             let total = candidates.len();
             if total == 0 {
                 return Vec::new();
             }
             // Report total immediately so UI shows "0 / total" while first batch runs.
-            on_progress(0, total as u32);
+            on_progress(OptimizePhase::Generating, 0, total as u32);
 
-            let num_batches = OPTIMIZE_PROGRESS_BATCH_COUNT.min(total);
-            let ranges = batch_ranges(total, num_batches);
-            let mut all_results: Vec<SimulationResult> = Vec::with_capacity(total);
+            let mut batches = AdaptiveBatches::new(total);
+            let mut top = exhaustive_top_results(scenario);
             let sim_count = scenario.simulation_count.max(1);
+            let mut done = 0usize;
 
-            for (start, end) in ranges {
-                let batch = &candidates[start..end];
-                let batch_results = run_monte_carlo_parallel(
-                    scenario.ship,
-                    scenario.hostile,
-                    batch,
-                    sim_count,
-                    scenario.seed,
-                );
-                all_results.extend(batch_results);
-                on_progress(end as u32, total as u32);
+            loop {
+                let chunk = next_sorted_chunk(&mut candidates, &shared, scenario.seed);
+                if chunk.is_empty() {
+                    break;
+                }
+                scenario.memory.hold_candidates(chunk.len());
+                let mut rest = chunk.as_slice();
+                while !rest.is_empty() {
+                    let (batch, tail) = rest.split_at(batches.next_len(rest.len()).max(1));
+                    rest = tail;
+                    let batch_results = batches.measure(batch.len(), || {
+                        run_monte_carlo_with_shared(
+                            shared.clone(),
+                            batch,
+                            sim_count,
+                            scenario.seed,
+                            true,
+                        )
+                    });
+                    scenario.memory.hold_results(top.len() + batch_results.len());
+                    top.extend(batch_results);
+                    done += batch.len();
+                    on_progress(OptimizePhase::Simulating, done as u32, total as u32);
+                }
             }
 
-            rank_results(all_results)
+            on_progress(OptimizePhase::FinalRanking, done as u32, total as u32);
+            top.into_ranked()
         }
         OptimizerStrategy::Genetic => {
-            optimize_scenario_genetic(scenario, |gen, max_gen, _| {
-                on_progress(gen as u32, max_gen as u32);
+            optimize_scenario_genetic(scenario, |phase, gen, max_gen, _| {
+                on_progress(phase, gen as u32, max_gen as u32);
                 true
             })
+            .0
         }
     }
 }
 
 /// Like [optimize_scenario_with_progress] but uses [DataRegistry] for exhaustive path (no reload).
-/// Progress callback returns true to continue, false to abort (e.g. user cancelled).
+/// Progress callback `(phase, done, total, stage, leaders)` returns true to continue, false to
+/// abort (e.g. user cancelled). `stage` is set only for the tiered and two-phase pipelines;
+/// `leaders` holds the exhaustive run's provisional best [PROVISIONAL_LEADERS] crews from
+/// completed batches and is empty otherwise.
+/// Cache hits (see [result_cache]) report `(FinalRanking, 1, 1, None, [])` once; aborted runs are
+/// not cached.
+/// The second value is the GA's fitness cache statistics for a genetic run that was computed
+/// (not served from the result cache).
 pub fn optimize_scenario_with_progress_with_registry<F>(
     registry: &DataRegistry,
     scenario: &OptimizationScenario<'_>,
     mut on_progress: F,
-) -> Vec<RankedCrewResult>
+) -> (Vec<RankedCrewResult>, Option<FitnessCacheStats>)
+where
+    F: FnMut(OptimizePhase, u32, u32, Option<StageProgress>, &[RankedCrewResult]) -> bool,
+{
+    let mut computed = false;
+    let mut aborted = false;
+    let mut fitness_cache = None;
+    let results = cached_ranked_results(scenario, OptimizePath::Registry, || {
+        computed = true;
+        let (results, stats) = optimize_scenario_with_progress_with_registry_uncached(
+            registry,
+            scenario,
+            |phase, done, total, stage, leaders| {
+                let keep_going = on_progress(phase, done, total, stage, leaders);
+                aborted |= !keep_going;
+                keep_going
+            },
+        );
+        fitness_cache = stats;
+        (results, !aborted)
+    });
+    if !computed {
+        on_progress(OptimizePhase::FinalRanking, 1, 1, None, &[]);
+    }
+    (results, fitness_cache)
+}
+
+fn optimize_scenario_with_progress_with_registry_uncached<F>(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    mut on_progress: F,
+) -> (Vec<RankedCrewResult>, Option<FitnessCacheStats>)
 where
-    F: FnMut(u32, u32) -> bool,
+    F: FnMut(OptimizePhase, u32, u32, Option<StageProgress>, &[RankedCrewResult]) -> bool,
 {
     match scenario.strategy {
         OptimizerStrategy::Tiered => {
-            let generator = CrewGenerator::with_strategy(CandidateStrategy {
-                max_candidates: scenario.max_candidates,
-                only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-                ..CandidateStrategy::default()
-            });
-            let candidates = generator.generate_candidates_from_registry(
+            let results = optimize_scenario_tiered_with_registry(
                 registry,
-                scenario.ship,
-                scenario.hostile,
-                scenario.seed,
-                scenario.profile_id,
+                scenario,
+                |done, total, stage| {
+                    let phase = pipeline_phase(scenario.strategy, stage.stage);
+                    on_progress(phase, done, total, Some(stage), &[])
+                },
             );
-            let scout_sims = scenario.tiered_scout_sims.unwrap_or(DEFAULT_SCOUT_SIMS);
-            let top_k = scenario.tiered_top_k.unwrap_or(DEFAULT_TOP_K);
-            run_tiered_with_registry_with_progress(
-                registry,
-                scenario.ship,
-                scenario.hostile,
-                candidates,
-                scout_sims,
-                scenario.simulation_count.max(1),
-                top_k,
-                scenario.seed,
-                scenario.profile_id,
-                &mut on_progress,
-            )
+            (results, None)
         }
-        OptimizerStrategy::Exhaustive => {
-            let generator = CrewGenerator::with_strategy(
-                crate::optimizer::crew_generator::CandidateStrategy {
-                    max_candidates: scenario.max_candidates,
-                    only_below_decks_with_ability: scenario.only_below_decks_with_ability,
-                    ..crate::optimizer::crew_generator::CandidateStrategy::default()
+        OptimizerStrategy::TwoPhase => {
+            let results = optimize_scenario_two_phase_with_registry(
+                registry,
+                scenario,
+                |done, total, stage| {
+                    let phase = pipeline_phase(scenario.strategy, stage.stage);
+                    on_progress(phase, done, total, Some(stage), &[])
                 },
             );
-            let candidates = generator.generate_candidates_from_registry(
+            (results, None)
+        }
+        OptimizerStrategy::Exhaustive => {
+            let shared_ex = scenario_shared_data_from_registry(registry, scenario);
+            let generator =
+                CrewGenerator::with_strategy(scenario_candidate_strategy(scenario, &shared_ex));
+            let mut candidates = generator.candidate_iter_from_registry(
                 registry,
                 scenario.ship,
                 scenario.hostile,
                 scenario.seed,
                 scenario.profile_id,
             );
-            let shared_ex = build_shared_scenario_data_from_registry(
-                registry,
-                scenario.ship,
-                scenario.hostile,
-                scenario.ship_tier,
-                scenario.ship_level,
-                scenario.profile_id,
-            );
-            let candidates =
-                sort_candidates_by_analytical_expected_damage(&shared_ex, candidates, scenario.seed);
+            // Exact total from pool sizes; candidates themselves are streamed chunk by chunk.
             let total = candidates.len();
             if total == 0 {
-                return Vec::new();
+                return (Vec::new(), None);
             }
-            if !on_progress(0, total as u32) {
-                return Vec::new();
+            if !on_progress(OptimizePhase::Generating, 0, total as u32, None, &[]) {
+                return (Vec::new(), None);
             }
 
-            let num_batches = OPTIMIZE_PROGRESS_BATCH_COUNT.min(total);
-            let ranges = batch_ranges(total, num_batches);
-            let mut all_results: Vec<SimulationResult> = Vec::with_capacity(total);
+            let mut batches = AdaptiveBatches::new(total);
+            let mut top = exhaustive_top_results(scenario);
             let sim_count = scenario.simulation_count.max(1);
+            let mut done = 0usize;
 
-            for (start, end) in ranges {
-                let batch = &candidates[start..end];
-                let (batch_results, _) = run_monte_carlo_parallel_with_registry(
-                    registry,
-                    scenario.ship,
-                    scenario.hostile,
-                    scenario.ship_tier,
-                    scenario.ship_level,
-                    batch,
-                    sim_count,
-                    scenario.seed,
-                    scenario.profile_id,
-                );
-                all_results.extend(batch_results);
-                if !on_progress(end as u32, total as u32) {
+            'stream: loop {
+                let chunk = next_sorted_chunk(&mut candidates, &shared_ex, scenario.seed);
+                if chunk.is_empty() {
                     break;
                 }
+                scenario.memory.hold_candidates(chunk.len());
+                let mut rest = chunk.as_slice();
+                while !rest.is_empty() {
+                    let (batch, tail) = rest.split_at(batches.next_len(rest.len()).max(1));
+                    rest = tail;
+                    let batch_results = batches.measure(batch.len(), || {
+                        run_monte_carlo_with_shared(
+                            shared_ex.clone(),
+                            batch,
+                            sim_count,
+                            scenario.seed,
+                            true,
+                        )
+                    });
+                    scenario.memory.hold_results(top.len() + batch_results.len());
+                    top.extend(batch_results);
+                    done += batch.len();
+                    let leaders = top.leaders(PROVISIONAL_LEADERS);
+                    let phase = OptimizePhase::Simulating;
+                    if !on_progress(phase, done as u32, total as u32, None, &leaders) {
+                        break 'stream;
+                    }
+                }
             }
 
-            rank_results(all_results)
+            on_progress(OptimizePhase::FinalRanking, done as u32, total as u32, None, &[]);
+            (top.into_ranked(), None)
         }
         OptimizerStrategy::Genetic => {
-            optimize_scenario_genetic(scenario, |gen, max_gen, _| {
-                on_progress(gen as u32, max_gen as u32);
+            let (results, stats) = optimize_scenario_genetic(scenario, |phase, gen, max_gen, _| {
+                on_progress(phase, gen as u32, max_gen as u32, None, &[]);
                 true
-            })
+            });
+            (results, Some(stats))
         }
     }
 }
@@ -471,12 +845,44 @@ pub fn optimize_crew(
         profile_id,
         tiered_scout_sims: None,
         tiered_top_k: None,
+        scenario_type: ScenarioType::Offense,
+        mining_rounds: 0,
+        waves: 0,
+        wave_repair: 0.0,
+        attacker_condition: AttackerCondition::default(),
+        max_results: None,
+        genetic: GeneticOverrides::default(),
+        excluded_officers: &[],
+        unknown_officers: UnknownOfficerMode::default(),
+        memory: Arc::default(),
+        pool: WorkerPool::default(),
     })
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{OptimizationScenario, OptimizerStrategy};
+    use super::{
+        auto_strategy, GeneticOverrides, OptimizationScenario, OptimizerStrategy, ScenarioType,
+        AUTO_EXHAUSTIVE_MAX_CANDIDATES,
+    };
+    use crate::combat::AttackerCondition;
+    use crate::data::officer::UnknownOfficerMode;
+    use crate::optimizer::memory::{MemoryBudget, MemoryTracker, APPROX_RESULT_BYTES};
+    use crate::optimizer::ranking::RankedCrewResult;
+    use std::sync::Arc;
+
+    #[test]
+    fn auto_strategy_switches_to_genetic_above_threshold() {
+        assert_eq!(auto_strategy(0), OptimizerStrategy::Exhaustive);
+        assert_eq!(
+            auto_strategy(AUTO_EXHAUSTIVE_MAX_CANDIDATES),
+            OptimizerStrategy::Exhaustive
+        );
+        assert_eq!(
+            auto_strategy(AUTO_EXHAUSTIVE_MAX_CANDIDATES + 1),
+            OptimizerStrategy::Genetic
+        );
+    }
 
     #[test]
     fn genetic_strategy_returns_ranked_results_shape() {
@@ -494,6 +900,17 @@ mod tests {
             profile_id: None,
             tiered_scout_sims: None,
             tiered_top_k: None,
+            scenario_type: ScenarioType::Offense,
+            mining_rounds: 0,
+            waves: 0,
+            wave_repair: 0.0,
+            attacker_condition: AttackerCondition::default(),
+            max_results: None,
+            genetic: GeneticOverrides::default(),
+            excluded_officers: &[],
+            unknown_officers: UnknownOfficerMode::default(),
+            memory: Default::default(),
+            pool: Default::default(),
         };
         let results = super::optimize_scenario(&scenario);
         for r in &results {
@@ -501,4 +918,32 @@ mod tests {
             assert_eq!(r.below_decks.len(), 3, "each result must have 3 below_decks");
         }
     }
+    #[test]
+    fn memory_budget_caps_exhaustive_results() {
+        let run = |budget: MemoryBudget| {
+            let scenario = OptimizationScenario {
+                ship: "enterprise",
+                hostile: "swarm",
+                simulation_count: 10,
+                max_candidates: Some(20),
+                memory: Arc::new(MemoryTracker::new(budget)),
+                ..OptimizationScenario::default()
+            };
+            let results = super::optimize_scenario_exhaustive(&scenario);
+            (results, scenario.memory.usage())
+        };
+        let (full, usage) = run(MemoryBudget::from_mb(64));
+        assert!(full.len() > 5);
+        assert!(!usage.streaming_top_k);
+        assert_eq!(usage.peak_candidates, full.len() as u64);
+
+        let (capped, usage) = run(MemoryBudget {
+            bytes: APPROX_RESULT_BYTES * 5,
+        });
+        assert!(usage.streaming_top_k);
+        let crews = |results: &[RankedCrewResult]| -> Vec<String> {
+            results.iter().map(|r| r.stable_id()).collect()
+        };
+        assert_eq!(crews(&capped), crews(&full[..5]));
+    }
 }
@@ -6,12 +6,15 @@ use crate::combat::{
     Ability, AbilityClass, AbilityEffect, CrewConfiguration, CrewSeat, CrewSeatContext,
     TimingWindow,
 };
-use crate::data::officer::{load_canonical_officers, Officer, DEFAULT_CANONICAL_OFFICERS_PATH};
-use crate::optimizer::crew_generator::{CrewCandidate, BRIDGE_SLOTS, BELOW_DECKS_SLOTS};
+use crate::data::officer::{
+    load_canonical_officers, Officer, UnknownOfficerMode, DEFAULT_CANONICAL_OFFICERS_PATH,
+};
+use crate::lcars::ResolveOptions;
+use crate::optimizer::crew_generator::CrewCandidate;
 
 /// Build a [CrewConfiguration] from officer names (e.g. from a fight export).
 /// Convention: captain = Officer One, bridge = Officer Two then Officer Three, below_decks = [].
-/// Empty or "--" names are skipped. Uses canonical officers from [DEFAULT_CANONICAL_OFFICERS_PATH].
+/// Empty or "--" names are empty seats. Uses canonical officers from [DEFAULT_CANONICAL_OFFICERS_PATH].
 pub fn crew_from_officer_names(
     captain: Option<&str>,
     bridge: Vec<String>,
@@ -21,30 +24,27 @@ pub fn crew_from_officer_names(
         .filter(|s| !is_empty_or_placeholder(s))
         .unwrap_or("")
         .to_string();
-    let bridge_filtered: Vec<String> = bridge
-        .into_iter()
-        .filter(|s| !is_empty_or_placeholder(s))
-        .collect();
-    let below_filtered: Vec<String> = below_decks
-        .into_iter()
-        .filter(|s| !is_empty_or_placeholder(s))
-        .collect();
+    let seat = |name: String| (!is_empty_or_placeholder(&name)).then_some(name);
     let candidate = CrewCandidate {
         captain: captain_str,
-        bridge: bridge_filtered,
-        below_decks: below_filtered,
+        bridge: bridge.into_iter().map(seat).collect(),
+        below_decks: below_decks.into_iter().map(seat).collect(),
     };
     let officers = load_canonical_officers(DEFAULT_CANONICAL_OFFICERS_PATH).unwrap_or_default();
     let officers_by_name = index_officers_by_name(officers);
-    let seats = build_crew_seats(&candidate, &officers_by_name);
+    let seats = build_crew_seats(&candidate, &officers_by_name, &ResolveOptions::default());
     CrewConfiguration { seats }
 }
 
+/// Seats for `candidate` with the roster state in `roster`: officers without a "(T#)" tag
+/// scale their abilities at their roster tier ([ResolveOptions::tier_for]), and rows of
+/// [ResolveOptions::boosted_officers] are boosted.
 pub(crate) fn build_crew_seats(
     candidate: &CrewCandidate,
     officers_by_name: &HashMap<String, Officer>,
+    roster: &ResolveOptions,
 ) -> Vec<CrewSeatContext> {
-    let mut seats = Vec::with_capacity(1 + BRIDGE_SLOTS + BELOW_DECKS_SLOTS);
+    let mut seats = Vec::with_capacity(1 + candidate.bridge.len() + candidate.below_decks.len());
     let mut next_batch: u32 = 0;
 
     let cap_batch = next_batch;
@@ -54,6 +54,7 @@ pub(crate) fn build_crew_seats(
         CrewSeat::Captain,
         AbilityClass::CaptainManeuver,
         officers_by_name,
+        roster,
         cap_batch,
     ));
     seats.extend(apex_ability_contexts(
@@ -61,18 +62,10 @@ pub(crate) fn build_crew_seats(
         CrewSeat::Captain,
         AbilityClass::CaptainManeuver,
         officers_by_name,
+        roster,
         cap_batch,
     ));
-    for i in 0..BRIDGE_SLOTS {
-        let name = candidate
-            .bridge
-            .get(i)
-            .or_else(|| candidate.bridge.first())
-            .map(String::as_str)
-            .unwrap_or("");
-        if name.is_empty() {
-            continue;
-        }
+    for name in candidate.bridge_officers() {
         let b = next_batch;
         next_batch = next_batch.saturating_add(1);
         seats.push(seat_from_officer(
@@ -80,6 +73,7 @@ pub(crate) fn build_crew_seats(
             CrewSeat::Bridge,
             AbilityClass::BridgeAbility,
             officers_by_name,
+            roster,
             b,
         ));
         seats.extend(apex_ability_contexts(
@@ -87,19 +81,11 @@ pub(crate) fn build_crew_seats(
             CrewSeat::Bridge,
             AbilityClass::BridgeAbility,
             officers_by_name,
+            roster,
             b,
         ));
     }
-    for i in 0..BELOW_DECKS_SLOTS {
-        let name = candidate
-            .below_decks
-            .get(i)
-            .or_else(|| candidate.below_decks.first())
-            .map(String::as_str)
-            .unwrap_or("");
-        if name.is_empty() {
-            continue;
-        }
+    for name in candidate.below_deck_officers() {
         let b = next_batch;
         next_batch = next_batch.saturating_add(1);
         seats.push(seat_from_officer(
@@ -107,6 +93,7 @@ pub(crate) fn build_crew_seats(
             CrewSeat::BelowDeck,
             AbilityClass::BelowDeck,
             officers_by_name,
+            roster,
             b,
         ));
         seats.extend(apex_ability_contexts(
@@ -114,21 +101,35 @@ pub(crate) fn build_crew_seats(
             CrewSeat::BelowDeck,
             AbilityClass::BelowDeck,
             officers_by_name,
+            roster,
             b,
         ));
     }
+    for seat in &mut seats {
+        seat.boosted = seat
+            .officer_id
+            .as_ref()
+            .is_some_and(|id| roster.boosted_officers.contains(id));
+    }
     seats
 }
 
+/// Seat for `id`: its officer's state ability (assimilate, hull breach, burning, morale) or,
+/// failing that, its flat stat modifier for this seat (see
+/// [crate::data::officer::OfficerAbility::stat_effect_for_tier]: the captain maneuver for
+/// captains, the officer ability elsewhere). Officers with neither, and names that match no
+/// officer, get an ability with no effect unless [ResolveOptions::unknown_officers] is
+/// [UnknownOfficerMode::Legacy].
 fn seat_from_officer(
     id: &str,
     seat: CrewSeat,
     class: AbilityClass,
     officers_by_name: &HashMap<String, Officer>,
+    roster: &ResolveOptions,
     contribution_batch: u32,
 ) -> CrewSeatContext {
-    let hash = hash_identifier(id);
-    let (lookup_name, tier) = split_name_and_tier(id);
+    let (lookup_name, tier) = name_and_tier(id, officers_by_name, roster);
+    let hash = hash_identifier(&lookup_name);
     let officer = officers_by_name.get(&normalize_lookup_key(&lookup_name));
     let officer_id = officer.map(|o| o.id.clone());
     let morale_chance = officer.and_then(|officer| {
@@ -199,6 +200,18 @@ fn seat_from_officer(
                 )
             })
     });
+    let stat_modifier = officer.and_then(|officer| {
+        officer
+            .abilities
+            .iter()
+            .filter(|ability| {
+                ability.is_captain_maneuver() == (class == AbilityClass::CaptainManeuver)
+            })
+            .find_map(|ability| {
+                let timing = trigger_to_timing_window(ability.trigger.as_deref())?;
+                Some((timing, ability.stat_effect_for_tier(tier)?))
+            })
+    });
 
     let (timing, effect) = if let Some((timing, effect)) = assimilated {
         (timing, effect)
@@ -208,6 +221,10 @@ fn seat_from_officer(
         (timing, effect)
     } else if let Some(chance) = morale_chance {
         (TimingWindow::RoundStart, AbilityEffect::Morale(chance))
+    } else if let Some((timing, effect)) = stat_modifier {
+        (timing, effect)
+    } else if roster.unknown_officers != UnknownOfficerMode::Legacy {
+        (TimingWindow::AttackPhase, AbilityEffect::AttackMultiplier(0.0))
     } else if hash % 2 == 0 {
         (
             TimingWindow::AttackPhase,
@@ -233,13 +250,22 @@ fn seat_from_officer(
             boostable: true,
             effect,
             condition: None,
+            max_activations: None,
+            cooldown_rounds: 0,
         },
-        boosted: hash % 5 == 0,
+        boosted: false,
         officer_id,
         contribution_batch,
     }
 }
 
+/// Rows `officer` adds when seated as captain, as the optimizer resolves them.
+pub fn officer_captain_rows(officer: &Officer) -> Vec<CrewSeatContext> {
+    let candidate = CrewCandidate::new(officer.name.clone(), Vec::new(), Vec::new());
+    let officers_by_name = index_officers_by_name(vec![officer.clone()]);
+    build_crew_seats(&candidate, &officers_by_name, &ResolveOptions::default())
+}
+
 pub(crate) fn index_officers_by_name(officers: Vec<Officer>) -> HashMap<String, Officer> {
     officers
         .into_iter()
@@ -247,6 +273,64 @@ pub(crate) fn index_officers_by_name(officers: Vec<Officer>) -> HashMap<String,
         .collect()
 }
 
+/// Distinct known officers of `candidate`, in seat order (captain, bridge, below decks).
+pub(crate) fn crew_officers<'a>(
+    candidate: &CrewCandidate,
+    officers_by_name: &'a HashMap<String, Officer>,
+) -> Vec<&'a Officer> {
+    let mut officers: Vec<&Officer> = Vec::new();
+    for name in candidate.officers() {
+        let key = normalize_lookup_key(&split_name_and_tier(name).0);
+        if let Some(officer) = officers_by_name.get(&key) {
+            if officers.iter().all(|o| o.id != officer.id) {
+                officers.push(officer);
+            }
+        }
+    }
+    officers
+}
+
+/// A seated officer with the rank and level it plays at.
+pub(crate) struct SeatedOfficer<'a> {
+    pub officer: &'a Officer,
+    pub rank: Option<u8>,
+    pub level: Option<u16>,
+    /// Captain or bridge seat; only these officers' stats count toward
+    /// [crate::data::ship::OfficerBonus].
+    pub on_bridge: bool,
+}
+
+/// Distinct known officers of `candidate` in seat order, each at the rank and level of its
+/// name's "(T# L#)" tag, falling back to its `roster` tier and level.
+pub(crate) fn seated_officers<'a>(
+    candidate: &CrewCandidate,
+    officers_by_name: &'a HashMap<String, Officer>,
+    roster: &ResolveOptions,
+) -> Vec<SeatedOfficer<'a>> {
+    let bridge = std::iter::once(candidate.captain.as_str())
+        .filter(|name| !name.is_empty())
+        .chain(candidate.bridge_officers())
+        .map(|name| (name, true));
+    let below_decks = candidate.below_deck_officers().map(|name| (name, false));
+    let mut seated: Vec<SeatedOfficer> = Vec::new();
+    for (name, on_bridge) in bridge.chain(below_decks) {
+        let (lookup_name, tier, level) = split_name_tags(name);
+        let Some(officer) = officers_by_name.get(&normalize_lookup_key(&lookup_name)) else {
+            continue;
+        };
+        if seated.iter().any(|s| s.officer.id == officer.id) {
+            continue;
+        }
+        seated.push(SeatedOfficer {
+            officer,
+            rank: tier.or_else(|| roster.tier_for(&officer.id)),
+            level: level.or_else(|| roster.officer_levels.get(&officer.id).copied()),
+            on_bridge,
+        });
+    }
+    seated
+}
+
 fn is_empty_or_placeholder(s: &str) -> bool {
     let t = s.trim();
     t.is_empty() || t.eq_ignore_ascii_case("--")
@@ -261,18 +345,59 @@ pub(crate) fn normalize_lookup_key(value: &str) -> String {
 }
 
 pub(crate) fn split_name_and_tier(input: &str) -> (String, Option<u8>) {
+    let (name, tier, _) = split_name_tags(input);
+    (name, tier)
+}
+
+/// `input` without its trailing "(T# L#)" tag, and the tag's tier and level. Either part may be
+/// left out ("(T3)", "(L40)"); text in parentheses that is not such a tag stays in the name.
+pub(crate) fn split_name_tags(input: &str) -> (String, Option<u8>, Option<u16>) {
     let trimmed = input.trim();
     if let Some(open) = trimmed.rfind('(') {
         if trimmed.ends_with(')') {
-            let inner = &trimmed[open + 1..trimmed.len() - 1];
-            if let Some(rest) = inner.strip_prefix('T').or_else(|| inner.strip_prefix('t')) {
-                if let Ok(tier) = rest.parse::<u8>() {
-                    return (trimmed[..open].trim().to_string(), Some(tier));
-                }
+            let (mut tier, mut level) = (None, None);
+            let is_tag = trimmed[open + 1..trimmed.len() - 1]
+                .split_whitespace()
+                .all(|tag| {
+                    let mut chars = tag.chars();
+                    match chars.next().map(|c| c.to_ascii_uppercase()) {
+                        Some('T') => chars.as_str().parse().map(|t| tier = Some(t)).is_ok(),
+                        Some('L') => chars.as_str().parse().map(|l| level = Some(l)).is_ok(),
+                        _ => false,
+                    }
+                });
+            if is_tag && (tier.is_some() || level.is_some()) {
+                return (trimmed[..open].trim().to_string(), tier, level);
             }
         }
     }
-    (trimmed.to_string(), None)
+    (trimmed.to_string(), None, None)
+}
+
+/// `name` with a "(T# L#)" tag for whichever of `tier` and `level` is set (see
+/// [split_name_tags]).
+pub(crate) fn tag_name(name: &str, tier: Option<u8>, level: Option<u16>) -> String {
+    match (tier, level) {
+        (Some(tier), Some(level)) => format!("{name} (T{tier} L{level})"),
+        (Some(tier), None) => format!("{name} (T{tier})"),
+        (None, Some(level)) => format!("{name} (L{level})"),
+        (None, None) => name.to_string(),
+    }
+}
+
+/// `name` without its "(T#)" tag, and the tier its abilities scale at: the tag when present,
+/// otherwise the officer's roster tier.
+fn name_and_tier(
+    name: &str,
+    officers_by_name: &HashMap<String, Officer>,
+    roster: &ResolveOptions,
+) -> (String, Option<u8>) {
+    let (lookup_name, tagged) = split_name_and_tier(name);
+    let tier = tagged.or_else(|| {
+        let officer = officers_by_name.get(&normalize_lookup_key(&lookup_name))?;
+        roster.tier_for(&officer.id)
+    });
+    (lookup_name, tier)
 }
 
 pub(crate) fn hash_identifier(value: &str) -> u64 {
@@ -294,6 +419,7 @@ fn trigger_to_timing_window(trigger: Option<&str>) -> Option<TimingWindow> {
     match trigger.as_ref().and_then(|t| Some(t.trim())) {
         Some("CombatStart") => Some(TimingWindow::CombatBegin),
         Some("RoundStart") => Some(TimingWindow::RoundStart),
+        Some("ShipLaunched") => Some(TimingWindow::CombatBegin),
         _ => None,
     }
 }
@@ -303,9 +429,10 @@ fn apex_ability_contexts(
     seat: CrewSeat,
     class: AbilityClass,
     officers_by_name: &HashMap<String, Officer>,
+    roster: &ResolveOptions,
     contribution_batch: u32,
 ) -> Vec<CrewSeatContext> {
-    let (lookup_name, tier) = split_name_and_tier(officer_id);
+    let (lookup_name, tier) = name_and_tier(officer_id, officers_by_name, roster);
     let Some(officer) = officers_by_name.get(&normalize_lookup_key(&lookup_name)) else {
         return Vec::new();
     };
@@ -336,6 +463,8 @@ fn apex_ability_contexts(
                 boostable: false,
                 effect,
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: Some(officer.id.clone()),
@@ -359,6 +488,11 @@ mod tests {
                 id: "harry-kim-a79fdf".to_string(),
                 name: "Harry Kim".to_string(),
                 slot: Some("science".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("RoundStart".to_string()),
@@ -368,6 +502,7 @@ mod tests {
                     chance_by_rank: vec![0.1, 0.15, 0.3, 0.6, 1.0],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -376,11 +511,140 @@ mod tests {
             CrewSeat::BelowDeck,
             AbilityClass::BelowDeck,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
 
         assert_eq!(seat.ability.timing, TimingWindow::RoundStart);
         assert!(matches!(seat.ability.effect, AbilityEffect::Morale(1.0)));
+
+        // Untagged names scale at the roster tier; a tag still wins.
+        let roster = ResolveOptions {
+            officer_tiers: Some(HashMap::from([("harry-kim-a79fdf".to_string(), 4)])),
+            ..ResolveOptions::default()
+        };
+        let chance = |name: &str, roster: &ResolveOptions| {
+            let seat = seat_from_officer(
+                name,
+                CrewSeat::BelowDeck,
+                AbilityClass::BelowDeck,
+                &officers,
+                roster,
+                0,
+            );
+            match seat.ability.effect {
+                AbilityEffect::Morale(chance) => chance,
+                other => panic!("expected morale, got {other:?}"),
+            }
+        };
+        assert_eq!(chance("Harry Kim", &roster), 0.6);
+        assert_eq!(chance("Harry Kim (T2)", &roster), 0.15);
+        assert_eq!(chance("Harry Kim", &ResolveOptions::default()), 0.1);
+    }
+
+    #[test]
+    fn name_tags_carry_tier_and_level() {
+        let split = |name: &str| split_name_tags(name);
+        assert_eq!(split("Kirk (T5 L40)"), ("Kirk".to_string(), Some(5), Some(40)));
+        assert_eq!(split("Kirk (l40)"), ("Kirk".to_string(), None, Some(40)));
+        assert_eq!(split("Kirk (T2)"), ("Kirk".to_string(), Some(2), None));
+        assert_eq!(split("Kirk (Mirror)"), ("Kirk (Mirror)".to_string(), None, None));
+        assert_eq!(split("Kirk ()"), ("Kirk ()".to_string(), None, None));
+        assert_eq!(tag_name("Kirk", Some(5), Some(40)), "Kirk (T5 L40)");
+        assert_eq!(tag_name("Kirk", None, Some(40)), "Kirk (L40)");
+    }
+
+    #[test]
+    fn seat_from_officer_maps_flat_stat_modifiers() {
+        let ability = |slot: &str, modifier: &str, attributes: &str, values: Vec<f64>| {
+            OfficerAbility {
+                slot: slot.to_string(),
+                trigger: Some("CombatStart".to_string()),
+                modifier: Some(modifier.to_string()),
+                attributes: Some(attributes.to_string()),
+                description: None,
+                chance_by_rank: vec![],
+                value_by_rank: values,
+            }
+        };
+        let officer = |name: &str, abilities: Vec<OfficerAbility>| Officer {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            slot: None,
+            rarity: None,
+            faction: None,
+            group: None,
+            synergy_group: None,
+            source_officer_id: None,
+            abilities,
+            traits: Vec::new(),
+        };
+        let officers = index_officers_by_name(vec![
+            officer(
+                "Gunner",
+                vec![
+                    ability("captain", "AllDamage", "", vec![0.25, 0.025, 0.05]),
+                    ability("officer", "AllDamage", "", vec![0.1, 0.2, 0.3, 0.4, 0.5]),
+                ],
+            ),
+            officer("Piercer", vec![ability("officer", "AllPiercing", "", vec![1.0; 5])]),
+            officer(
+                "Crew Scaled",
+                vec![ability("officer", "AllDamage", "officer_stat=1", vec![2.0; 5])],
+            ),
+        ]);
+        let seat = |name: &str, seat: CrewSeat, class: AbilityClass| {
+            let seat =
+                seat_from_officer(name, seat, class, &officers, &ResolveOptions::default(), 0);
+            (seat.ability.timing, seat.ability.effect)
+        };
+
+        let (timing, effect) = seat("Gunner (T3)", CrewSeat::Bridge, AbilityClass::BridgeAbility);
+        assert_eq!(timing, TimingWindow::CombatBegin);
+        assert!(matches!(effect, AbilityEffect::AttackMultiplier(v) if v == 0.3));
+        // Captain maneuvers do not rank up.
+        let (_, effect) = seat("Gunner (T3)", CrewSeat::Captain, AbilityClass::CaptainManeuver);
+        assert!(matches!(effect, AbilityEffect::AttackMultiplier(v) if v == 0.25));
+        // No modeled ability (piercing stats, crew-stat shares): no effect, not a made-up bonus.
+        let (_, effect) = seat("Piercer", CrewSeat::BelowDeck, AbilityClass::BelowDeck);
+        assert!(matches!(effect, AbilityEffect::AttackMultiplier(v) if v == 0.0));
+        let (_, effect) = seat("Crew Scaled", CrewSeat::Bridge, AbilityClass::BridgeAbility);
+        assert!(matches!(effect, AbilityEffect::AttackMultiplier(v) if v == 0.0));
+    }
+
+    #[test]
+    fn unknown_officers_have_no_effect_unless_legacy() {
+        let effect = |unknown_officers| {
+            let roster = ResolveOptions { unknown_officers, ..ResolveOptions::default() };
+            let seat = seat_from_officer(
+                "Nobody In Particular",
+                CrewSeat::Bridge,
+                AbilityClass::BridgeAbility,
+                &HashMap::new(),
+                &roster,
+                0,
+            );
+            seat.ability.effect
+        };
+        for mode in [UnknownOfficerMode::Neutral, UnknownOfficerMode::Strict] {
+            assert!(matches!(effect(mode), AbilityEffect::AttackMultiplier(v) if v == 0.0));
+        }
+        match effect(UnknownOfficerMode::Legacy) {
+            AbilityEffect::AttackMultiplier(v) | AbilityEffect::PierceBonus(v) => assert!(v > 0.0),
+            other => panic!("expected the hashed bonus, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn partial_crew_seats_only_occupied_seats() {
+        let candidate = CrewCandidate {
+            captain: "Kirk".to_string(),
+            bridge: vec![None, Some("Spock".to_string())],
+            below_decks: vec![Some("Scotty".to_string())],
+        };
+        let seats = build_crew_seats(&candidate, &HashMap::new(), &ResolveOptions::default());
+        let kinds: Vec<CrewSeat> = seats.iter().map(|seat| seat.seat).collect();
+        assert_eq!(kinds, vec![CrewSeat::Captain, CrewSeat::Bridge, CrewSeat::BelowDeck]);
     }
 
     #[test]
@@ -392,6 +656,11 @@ mod tests {
                 id: "dezoc".to_string(),
                 name: "Dezoc".to_string(),
                 slot: Some("science".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("RoundStart".to_string()),
@@ -401,6 +670,7 @@ mod tests {
                     chance_by_rank: vec![0.4, 0.45, 0.5],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -409,6 +679,7 @@ mod tests {
             CrewSeat::BelowDeck,
             AbilityClass::BelowDeck,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
 
@@ -431,6 +702,11 @@ mod tests {
                 id: "lorca".to_string(),
                 name: "Lorca".to_string(),
                 slot: Some("officer".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("RoundStart".to_string()),
@@ -440,6 +716,7 @@ mod tests {
                     chance_by_rank: vec![0.5, 0.6, 0.7],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -448,6 +725,7 @@ mod tests {
             CrewSeat::Bridge,
             AbilityClass::BridgeAbility,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
         assert_eq!(lorca.ability.timing, TimingWindow::RoundStart);
@@ -466,6 +744,11 @@ mod tests {
                 id: "gorkon".to_string(),
                 name: "Gorkon".to_string(),
                 slot: Some("officer".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("CriticalShotFired".to_string()),
@@ -475,6 +758,7 @@ mod tests {
                     chance_by_rank: vec![0.7, 0.75, 0.8],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -483,6 +767,7 @@ mod tests {
             CrewSeat::Captain,
             AbilityClass::CaptainManeuver,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
         assert_eq!(gorkon.ability.timing, TimingWindow::AttackPhase);
@@ -501,6 +786,11 @@ mod tests {
                 id: "belanna".to_string(),
                 name: "B'Elanna Torres".to_string(),
                 slot: Some("below_decks".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("RoundStart".to_string()),
@@ -510,6 +800,7 @@ mod tests {
                     chance_by_rank: vec![0.1, 0.15, 0.3],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -518,6 +809,7 @@ mod tests {
             CrewSeat::BelowDeck,
             AbilityClass::BelowDeck,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
         assert_eq!(belanna.ability.timing, TimingWindow::RoundStart);
@@ -540,6 +832,11 @@ mod tests {
                 id: "nero".to_string(),
                 name: "Nero".to_string(),
                 slot: Some("captain".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("EnemyTakesHit".to_string()),
@@ -549,6 +846,7 @@ mod tests {
                     chance_by_rank: vec![0.25, 0.3, 0.35],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -557,6 +855,7 @@ mod tests {
             CrewSeat::Captain,
             AbilityClass::CaptainManeuver,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
 
@@ -579,6 +878,11 @@ mod tests {
                 id: "harry-kim-a79fdf".to_string(),
                 name: "Harry Kim".to_string(),
                 slot: Some("science".to_string()),
+                rarity: None,
+                faction: None,
+                group: None,
+                synergy_group: None,
+                source_officer_id: None,
                 abilities: vec![OfficerAbility {
                     slot: "officer".to_string(),
                     trigger: Some("RoundStart".to_string()),
@@ -588,6 +892,7 @@ mod tests {
                     chance_by_rank: vec![0.1, 0.15, 0.3, 0.6, 1.0],
                     value_by_rank: vec![],
                 }],
+                traits: Vec::new(),
             },
         );
 
@@ -596,6 +901,7 @@ mod tests {
             CrewSeat::BelowDeck,
             AbilityClass::BelowDeck,
             &officers,
+            &ResolveOptions::default(),
             0,
         );
 
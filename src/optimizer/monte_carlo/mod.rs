@@ -8,10 +8,19 @@ mod crew_resolution;
 pub(crate) mod scenario;
 mod simulation;
 
-pub use crew_resolution::crew_from_officer_names;
-pub(crate) use simulation::{run_monte_carlo_scout_phase_with_shared, run_monte_carlo_with_shared};
+pub use crew_resolution::{crew_from_officer_names, officer_captain_rows};
+pub(crate) use crew_resolution::{
+    crew_officers, normalize_lookup_key, split_name_and_tier, split_name_tags, tag_name,
+};
+pub(crate) use simulation::{
+    run_monte_carlo_deduped_with_shared, run_monte_carlo_scout_phase_with_shared,
+    run_monte_carlo_with_shared,
+};
+#[cfg(feature = "server")]
+pub(crate) use simulation::snapshot_candidate_fight;
 pub use simulation::{
     crew_candidate_stable_hash, run_monte_carlo, run_monte_carlo_parallel,
-    run_monte_carlo_parallel_deduped, run_monte_carlo_parallel_with_registry,
-    run_monte_carlo_with_registry, SimulationResult,
+    run_monte_carlo_in_memory, run_monte_carlo_parallel_deduped,
+    run_monte_carlo_parallel_with_registry, run_monte_carlo_with_registry,
+    stream_fight_with_registry, SimulationResult,
 };
@@ -1,11 +1,12 @@
 //! Scenario and candidate → combat input: SharedScenarioData, scenario_to_combat_input, build_crew_and_buffs.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 use crate::combat::{
-    mitigation, mitigation_for_hostile, pierce_damage_through_bonus, AttackerStats, Combatant,
-    CrewConfiguration, CrewSeatContext, DefenderStats, ShipType, MITIGATION_CEILING,
-    MITIGATION_FLOOR,
+    mitigation, mitigation_for_hostile, pierce_damage_through_bonus, AttackerCondition,
+    AttackerStats, CategoryTotals, Combatant, CrewConfiguration, CrewSeatContext, DefenderStats,
+    InitiativeMode, ShipType, StatStacking, MITIGATION_CEILING, MITIGATION_FLOOR,
 };
 use crate::data::building::{
     self, BuildingBonusContext, BuildingMode, DEFAULT_BUILDINGS_INDEX_PATH,
@@ -17,37 +18,48 @@ use crate::data::forbidden_chaos;
 use crate::data::hostile::HostileRecord;
 use crate::data::import;
 use crate::data::loader::{resolve_hostile, resolve_ship};
-use crate::data::officer::{load_canonical_officers, Officer, DEFAULT_CANONICAL_OFFICERS_PATH};
+use crate::data::officer::{
+    load_canonical_officers, Officer, OfficerStats, UnknownOfficerMode,
+    DEFAULT_CANONICAL_OFFICERS_PATH,
+};
+use crate::data::power::{self, PowerEstimate};
 use crate::data::profile::{
-    apply_profile_to_attacker, apply_static_buffs_to_combatant, load_profile,
+    apply_bonuses_to_attacker, apply_profile_to_attacker, apply_static_buffs_to_combatant, load_profile,
     merge_building_bonuses_into_profile, merge_research_bonuses_into_profile,
     forbidden_tech_level_tier_scaling_enabled_from_env, merge_tech_fids_into_profile,
     merge_tech_fids_into_profile_with_level_tier, resolve_effective_tech_fids, PlayerProfile,
+    TargetContext,
 };
 use crate::data::profile_index::{
     self, profile_path, BUILDINGS_IMPORTED, FORBIDDEN_TECH_IMPORTED, PROFILE_JSON, RESEARCH_IMPORTED,
     ROSTER_IMPORTED,
 };
-use crate::data::ship::ShipRecord;
-use crate::data::ship_ability_resolve::ship_abilities_to_crew_seat_contexts;
+use crate::data::ship::{OfficerBonus, ShipRecord};
+use crate::data::ship_ability_resolve::ship_record_to_crew_seat_contexts;
 use crate::lcars::{index_lcars_officers_by_id, load_lcars_dir, resolve_crew_to_buff_set, ResolveOptions};
-use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::crew_generator::{CrewCandidate, CrewSlots};
+use crate::optimizer::stat_sensitivity::AttackerStat;
 use std::path::Path;
 
 use super::crew_resolution::{
-    build_crew_seats, hash_identifier, index_officers_by_name, normalize_lookup_key, split_name_and_tier,
+    build_crew_seats, hash_identifier, index_officers_by_name, normalize_lookup_key,
+    seated_officers, split_name_and_tier,
 };
 
 const DEFAULT_LCARS_OFFICERS_DIR_STANDALONE: &str = "data/officers";
 
-/// Append [ShipRecord::abilities] as [CrewSeatContext] (supported id/timing/effect combinations only).
-fn extend_crew_with_ship_abilities(seats: &mut Vec<CrewSeatContext>, ship_rec: Option<&ShipRecord>) {
+/// Append the ship's innate ability and [ShipRecord::abilities] as [CrewSeatContext] (supported
+/// id/timing/effect combinations only). Abilities limited to hostile tags need a resolved hostile.
+fn extend_crew_with_ship_abilities(
+    seats: &mut Vec<CrewSeatContext>,
+    ship_rec: Option<&ShipRecord>,
+    hostile_rec: Option<&HostileRecord>,
+) {
     let Some(rec) = ship_rec else {
         return;
     };
-    seats.extend(ship_abilities_to_crew_seat_contexts(
-        rec.abilities.as_deref().unwrap_or(&[]),
-    ));
+    let target_tags = hostile_rec.map(HostileRecord::target_tags).unwrap_or_default();
+    seats.extend(ship_record_to_crew_seat_contexts(rec, &target_tags));
 }
 
 fn use_lcars_officer_source_standalone() -> bool {
@@ -62,6 +74,18 @@ pub(crate) struct LcarsOfficerData {
     pub name_to_id: HashMap<String, String>,
 }
 
+impl LcarsOfficerData {
+    /// Index officers by id and by normalized display name.
+    pub(crate) fn from_officers(officers: Vec<crate::lcars::LcarsOfficer>) -> Self {
+        let by_id = index_lcars_officers_by_id(officers);
+        let name_to_id = by_id
+            .values()
+            .map(|o| (normalize_lookup_key(&o.name), o.id.clone()))
+            .collect();
+        Self { by_id, name_to_id }
+    }
+}
+
 /// Pre-resolved data for (ship, hostile) shared across all candidates in one Monte Carlo run.
 #[derive(Clone)]
 pub(crate) struct SharedScenarioData {
@@ -72,17 +96,100 @@ pub(crate) struct SharedScenarioData {
     pub lcars_data: Option<LcarsOfficerData>,
     pub resolve_options: ResolveOptions,
     pub ship_rec: Option<ShipRecord>,
-    #[allow(dead_code)]
     pub hostile_rec: Option<HostileRecord>,
+    /// Seats on the ship; candidate seats past these counts are dropped before the fight.
+    pub crew_slots: CrewSlots,
     pub cached_defender: Option<Combatant>,
     pub cached_rounds: Option<u32>,
     pub cached_defender_hull: Option<f64>,
     pub cached_pierce: Option<f64>,
-    #[allow(dead_code)]
+    /// Defender mitigation; for placeholder combatants, [computed_defender_mitigation] resolved
+    /// once per scenario instead of once per candidate.
     pub cached_defender_mitigation: Option<f64>,
     /// True when ship or hostile did not resolve from data and [`scenario_to_combat_input_from_shared`]
     /// uses hashed placeholder combatants instead of registry-backed stats.
     pub using_placeholder_combatants: bool,
+    /// Opening rounds in which the player's ship holds fire (mining protection scenarios).
+    pub attacker_delay_rounds: u32,
+    /// Who fires first in each sub-round; the opponent in defense scenarios.
+    pub initiative: InitiativeMode,
+    /// Consecutive copies of the hostile fought per iteration; 0 or 1 = a single fight.
+    pub waves: u32,
+    /// Share of missing hull and shields restored between waves (0–1).
+    pub wave_repair: f64,
+    /// Hull and shields the player's ship starts the first fight with.
+    pub attacker_condition: AttackerCondition,
+    /// What-if slider: one ship stat multiplied by a factor after profile and crew buffs.
+    pub attacker_scale: Option<(AttackerStat, f64)>,
+    /// Kind of defender, selecting the profile's target-specific bonuses.
+    pub target: TargetContext,
+}
+
+impl SharedScenarioData {
+    /// Same scenario with the player's ship holding fire for the first `rounds` rounds.
+    pub(crate) fn with_attacker_delay(mut self, rounds: u32) -> Self {
+        self.attacker_delay_rounds = rounds;
+        self
+    }
+
+    /// Same scenario with `initiative` deciding who fires first in each sub-round.
+    pub(crate) fn with_initiative(mut self, initiative: InitiativeMode) -> Self {
+        self.initiative = initiative;
+        self
+    }
+
+    /// Same scenario fought as `waves` consecutive fights against the hostile (see
+    /// [crate::combat::simulate_waves]), repairing `repair` of the damage between waves.
+    pub(crate) fn with_waves(mut self, waves: u32, repair: f64) -> Self {
+        self.waves = waves;
+        self.wave_repair = repair;
+        self
+    }
+
+    /// Same scenario with the player's ship starting damaged as `condition` says.
+    pub(crate) fn with_attacker_condition(mut self, condition: AttackerCondition) -> Self {
+        self.attacker_condition = condition;
+        self
+    }
+
+    /// Power the ship shows with `candidate` seated ([crate::data::power]): the ship at its tier
+    /// and level with the profile's untargeted bonuses, plus each officer at its rank and level
+    /// (name tag or roster). None when the ship did not resolve from data.
+    #[cfg(feature = "server")]
+    pub(crate) fn crew_power(&self, candidate: &CrewCandidate) -> Option<PowerEstimate> {
+        let ship = power::ship_power(self.ship_rec.as_ref()?, &self.profile.bonuses);
+        let crew = seated_officers(candidate, &self.officer_index, &self.resolve_options)
+            .into_iter()
+            .map(|seated| power::officer_power(seated.officer, seated.rank, seated.level))
+            .sum();
+        Some(PowerEstimate::new(ship, crew))
+    }
+
+    /// Same scenario seating crew names that match no officer as `mode` says.
+    pub(crate) fn with_unknown_officers(mut self, mode: UnknownOfficerMode) -> Self {
+        self.resolve_options.unknown_officers = mode;
+        self
+    }
+
+    /// Same scenario with the fight capped at `rounds` rounds instead of the hostile's limit.
+    #[cfg(feature = "server")]
+    pub(crate) fn with_rounds(mut self, rounds: u32) -> Self {
+        self.cached_rounds = Some(rounds);
+        self
+    }
+
+    /// Same scenario with the player's ship's `stat` multiplied by `factor`.
+    pub(crate) fn with_attacker_scale(mut self, stat: AttackerStat, factor: f64) -> Self {
+        self.attacker_scale = Some((stat, factor));
+        self
+    }
+
+    fn scale_attacker(&self, attacker: Combatant) -> Combatant {
+        match self.attacker_scale {
+            Some((stat, factor)) => stat.scale(attacker, factor),
+            None => attacker,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -101,14 +208,8 @@ pub(crate) fn scenario_to_combat_input_from_shared(
     candidate: &CrewCandidate,
     seed: u64,
 ) -> CombatSimulationInput {
-    let base_seed = stable_seed(
-        &shared.ship,
-        &shared.hostile,
-        &candidate.captain,
-        &candidate.bridge,
-        &candidate.below_decks,
-        seed,
-    );
+    let candidate = &*shared.crew_slots.fit(candidate);
+    let base_seed = stable_seed(&shared.ship, &shared.hostile, candidate, seed);
 
     let (crew_seats, static_buffs, proc_chance, proc_multiplier) = build_crew_and_buffs(
         candidate,
@@ -116,6 +217,7 @@ pub(crate) fn scenario_to_combat_input_from_shared(
         shared.lcars_data.as_ref(),
         &shared.resolve_options,
     );
+    let bonuses = attacker_bonuses(shared, candidate);
 
     if let (Some(ref ship_rec), Some(ref defender), Some(rounds), Some(defender_hull)) = (
         &shared.ship_rec,
@@ -123,35 +225,30 @@ pub(crate) fn scenario_to_combat_input_from_shared(
         shared.cached_rounds,
         shared.cached_defender_hull,
     ) {
-        let mut attacker = apply_profile_to_attacker(
-            Combatant {
-                id: shared.ship.clone(),
-                attack: ship_rec.attack,
-                mitigation: 0.0,
-                pierce: shared.cached_pierce.unwrap_or(0.0),
-                crit_chance: ship_rec.crit_chance,
-                crit_multiplier: ship_rec.crit_damage,
-                proc_chance,
-                proc_multiplier,
-                end_of_round_damage: 0.0,
-                hull_health: ship_rec.hull_health,
-                shield_health: ship_rec.shield_health,
-                shield_mitigation: ship_rec.shield_mitigation.unwrap_or(0.8),
-                apex_barrier: 0.0,
-                apex_shred: ship_rec.apex_shred,
-                isolytic_damage: ship_rec.isolytic_damage,
-                isolytic_defense: 0.0,
-                weapons: ship_rec.to_weapons(),
-            },
-            &shared.profile,
+        let mut attacker = apply_bonuses_to_attacker(
+            Combatant::builder(shared.ship.clone())
+                .attack(ship_rec.attack)
+                .pierce(shared.cached_pierce.unwrap_or(0.0))
+                .crit_chance(ship_rec.crit_chance)
+                .crit_multiplier(ship_rec.crit_damage)
+                .proc_chance(proc_chance)
+                .proc_multiplier(proc_multiplier)
+                .hull_health(ship_rec.hull_health)
+                .shield_health(ship_rec.shield_health)
+                .shield_mitigation(ship_rec.shield_mitigation.unwrap_or(0.8))
+                .apex_shred(ship_rec.apex_shred)
+                .isolytic_damage(ship_rec.isolytic_damage)
+                .weapons(ship_rec.to_weapons())
+                .build_unchecked(),
+            &bonuses,
         );
         if !static_buffs.is_empty() {
             attacker = apply_static_buffs_to_combatant(attacker, &static_buffs);
         }
         let mut seats = crew_seats.clone();
-        extend_crew_with_ship_abilities(&mut seats, Some(ship_rec));
+        extend_crew_with_ship_abilities(&mut seats, Some(ship_rec), shared.hostile_rec.as_ref());
         return CombatSimulationInput {
-            attacker,
+            attacker: shared.scale_attacker(attacker),
             defender: defender.clone(),
             crew: CrewConfiguration { seats },
             rounds,
@@ -163,65 +260,102 @@ pub(crate) fn scenario_to_combat_input_from_shared(
     let ship_hash = hash_identifier(&shared.ship);
     let hostile_hash = hash_identifier(&shared.hostile);
     let defender_hull = 260.0 + ((hostile_hash >> 16) % 280) as f64;
-    let defender_mitigation = computed_defender_mitigation(&shared.ship, &shared.hostile);
-
-    let mut attacker = apply_profile_to_attacker(
-        Combatant {
-            id: shared.ship.clone(),
-            attack: 95.0 + (ship_hash % 70) as f64,
-            mitigation: 0.0,
-            pierce: 0.08 + ((ship_hash >> 8) % 14) as f64 / 100.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance,
-            proc_multiplier,
-            end_of_round_damage: 0.0,
-            hull_health: 1000.0,
-            shield_health: 0.0,
-            shield_mitigation: 0.8,
-            apex_barrier: 0.0,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-            weapons: vec![],
-        },
-        &shared.profile,
+    let defender_mitigation = shared
+        .cached_defender_mitigation
+        .unwrap_or_else(|| computed_defender_mitigation(&shared.ship, &shared.hostile));
+
+    let mut attacker = apply_bonuses_to_attacker(
+        Combatant::builder(shared.ship.clone())
+            .attack(95.0 + (ship_hash % 70) as f64)
+            .pierce(0.08 + ((ship_hash >> 8) % 14) as f64 / 100.0)
+            .proc_chance(proc_chance)
+            .proc_multiplier(proc_multiplier)
+            .build_unchecked(),
+        &bonuses,
     );
     if !static_buffs.is_empty() {
         attacker = apply_static_buffs_to_combatant(attacker, &static_buffs);
     }
 
     let mut seats = crew_seats.clone();
-    extend_crew_with_ship_abilities(&mut seats, shared.ship_rec.as_ref());
+    extend_crew_with_ship_abilities(
+        &mut seats,
+        shared.ship_rec.as_ref(),
+        shared.hostile_rec.as_ref(),
+    );
 
     CombatSimulationInput {
-        attacker,
-        defender: Combatant {
-            id: shared.hostile.clone(),
-            attack: 0.0,
-            mitigation: defender_mitigation,
-            pierce: 0.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            weapons: vec![],
-            end_of_round_damage: 0.0,
-            hull_health: defender_hull,
-            shield_health: 400.0,
-            shield_mitigation: 0.8,
-            apex_barrier: 0.0,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-        },
+        attacker: shared.scale_attacker(attacker),
+        defender: Combatant::builder(shared.hostile.clone())
+            .mitigation(defender_mitigation)
+            .hull_health(defender_hull)
+            .shield_health(400.0)
+            .build_unchecked(),
         crew: CrewConfiguration { seats },
-        rounds: 3 + (hostile_hash % 4) as u32,
+        rounds: shared.cached_rounds.unwrap_or(3 + (hostile_hash % 4) as u32),
         defender_hull,
         base_seed,
     }
 }
 
+/// Profile bonuses against [SharedScenarioData::target] for `candidate`'s ship, plus what its
+/// officers add when the profile turns it on:
+/// - [PlayerProfile::officer_stats]: the ship's [OfficerBonus] steps reached by the bridge
+///   officers' summed stats, added to `weapon_damage` (attack) and to `hull_hp` and `shield_hp`
+///   (health). The defense bonus is not simulated.
+/// - [PlayerProfile::officer_traits]: the unlocked traits. Add traits stack onto the profile
+///   bonus; multiply traits scale the resulting factor, as multiply tech bonuses do.
+///
+/// Officers count at the level of their name's "(L#)" tag, otherwise their roster level.
+fn attacker_bonuses<'a>(
+    shared: &'a SharedScenarioData,
+    candidate: &CrewCandidate,
+) -> Cow<'a, HashMap<String, f64>> {
+    let mut bonuses = shared.profile.bonuses_against(shared.target);
+    if !shared.profile.officer_stats && !shared.profile.officer_traits {
+        return bonuses;
+    }
+    let crew = seated_officers(candidate, &shared.officer_index, &shared.resolve_options);
+    let officer_bonus = shared.ship_rec.as_ref().and_then(|s| s.officer_bonus.as_ref());
+    if let Some(table) = officer_bonus.filter(|_| shared.profile.officer_stats) {
+        let stats = crew
+            .iter()
+            .filter(|seated| seated.on_bridge)
+            .map(|seated| seated.officer.estimated_stats(seated.rank, seated.level))
+            .fold(OfficerStats::default(), |sum, stats| OfficerStats {
+                attack: sum.attack + stats.attack,
+                defense: sum.defense + stats.defense,
+                health: sum.health + stats.health,
+            });
+        let attack = OfficerBonus::bonus_at(&table.attack, stats.attack);
+        let health = OfficerBonus::bonus_at(&table.health, stats.health);
+        let stat_bonuses = [("weapon_damage", attack), ("hull_hp", health), ("shield_hp", health)];
+        for (stat, bonus) in stat_bonuses {
+            if bonus != 0.0 {
+                *bonuses.to_mut().entry(stat.to_string()).or_insert(0.0) += bonus;
+            }
+        }
+    }
+    if !shared.profile.officer_traits {
+        return bonuses;
+    }
+    let mut traits: StatStacking<String> = StatStacking::new();
+    for seated in &crew {
+        traits.add_many(seated.officer.trait_contributions(seated.level));
+    }
+    for (stat, totals) in traits.iter_totals() {
+        let current = bonuses.get(stat).copied().unwrap_or(0.0);
+        let factor = CategoryTotals {
+            base: 1.0 + current + totals.flat,
+            modifier: totals.modifier,
+            flat: 0.0,
+        }
+        .compose();
+        bonuses.to_mut().insert(stat.clone(), factor - 1.0);
+    }
+    bonuses
+}
+
 /// Build (crew_seats, static_buffs, proc_chance, proc_multiplier) from candidate and officer data.
 fn build_crew_and_buffs(
     candidate: &CrewCandidate,
@@ -237,8 +371,7 @@ fn build_crew_and_buffs(
             ))
             .cloned();
         let bridge_ids: Vec<String> = candidate
-            .bridge
-            .iter()
+            .bridge_officers()
             .filter_map(|n| {
                 lcars
                     .name_to_id
@@ -247,8 +380,7 @@ fn build_crew_and_buffs(
             })
             .collect();
         let below_ids: Vec<String> = candidate
-            .below_decks
-            .iter()
+            .below_deck_officers()
             .filter_map(|n| {
                 lcars
                     .name_to_id
@@ -273,7 +405,7 @@ fn build_crew_and_buffs(
             )
         } else {
             (
-                build_crew_seats(candidate, officers_by_name),
+                build_crew_seats(candidate, officers_by_name, resolve_options),
                 HashMap::new(),
                 0.0,
                 1.0,
@@ -281,7 +413,7 @@ fn build_crew_and_buffs(
         }
     } else {
         (
-            build_crew_seats(candidate, officers_by_name),
+            build_crew_seats(candidate, officers_by_name, resolve_options),
             HashMap::new(),
             0.0,
             1.0,
@@ -289,6 +421,30 @@ fn build_crew_and_buffs(
     }
 }
 
+/// Seat `candidate` on a caller-built attacker: officer abilities become crew seats, static buffs
+/// are applied to the stats and extra-attack procs replace the attacker's own.
+pub(crate) fn attacker_with_crew(
+    attacker: Combatant,
+    candidate: &CrewCandidate,
+    lcars_data: &LcarsOfficerData,
+) -> (Combatant, CrewConfiguration) {
+    let (seats, static_buffs, proc_chance, proc_multiplier) = build_crew_and_buffs(
+        candidate,
+        &HashMap::new(),
+        Some(lcars_data),
+        &ResolveOptions::default(),
+    );
+    let attacker = apply_static_buffs_to_combatant(
+        Combatant {
+            proc_chance,
+            proc_multiplier,
+            ..attacker
+        },
+        &static_buffs,
+    );
+    (attacker, CrewConfiguration { seats })
+}
+
 #[allow(dead_code)] // used by unit tests (computed_mitigation_is_deterministic_for_same_inputs)
 pub(crate) fn scenario_to_combat_input(
     ship: &str,
@@ -299,14 +455,7 @@ pub(crate) fn scenario_to_combat_input(
     profile: &PlayerProfile,
     lcars_data: Option<&LcarsOfficerData>,
 ) -> CombatSimulationInput {
-    let base_seed = stable_seed(
-        ship,
-        hostile,
-        &candidate.captain,
-        &candidate.bridge,
-        &candidate.below_decks,
-        seed,
-    );
+    let base_seed = stable_seed(ship, hostile, candidate, seed);
 
     let resolve_opts = ResolveOptions::default();
     let (crew_seats, static_buffs, proc_chance, proc_multiplier) = build_crew_and_buffs(
@@ -332,58 +481,34 @@ pub(crate) fn scenario_to_combat_input(
             attacker_stats,
             hostile_rec.ship_type(),
         );
-        let defender_hull = hostile_rec.hull_health;
+        let defender_hull = hostile_rec.fight_hull_health();
         let rounds = 100u32.min(10u32.saturating_add(hostile_rec.level as u32));
         let mut attacker = apply_profile_to_attacker(
-            Combatant {
-                id: ship.to_string(),
-                attack: ship_rec.attack,
-                mitigation: 0.0,
-                pierce,
-                crit_chance: ship_rec.crit_chance,
-                crit_multiplier: ship_rec.crit_damage,
-                proc_chance,
-                proc_multiplier,
-                end_of_round_damage: 0.0,
-                hull_health: ship_rec.hull_health,
-                shield_health: ship_rec.shield_health,
-                shield_mitigation: ship_rec.shield_mitigation.unwrap_or(0.8),
-                apex_barrier: 0.0,
-                apex_shred: ship_rec.apex_shred,
-                isolytic_damage: ship_rec.isolytic_damage,
-                isolytic_defense: 0.0,
-                weapons: ship_rec.to_weapons(),
-            },
+            Combatant::builder(ship.to_string())
+                .attack(ship_rec.attack)
+                .pierce(pierce)
+                .crit_chance(ship_rec.crit_chance)
+                .crit_multiplier(ship_rec.crit_damage)
+                .proc_chance(proc_chance)
+                .proc_multiplier(proc_multiplier)
+                .hull_health(ship_rec.hull_health)
+                .shield_health(ship_rec.shield_health)
+                .shield_mitigation(ship_rec.shield_mitigation.unwrap_or(0.8))
+                .apex_shred(ship_rec.apex_shred)
+                .isolytic_damage(ship_rec.isolytic_damage)
+                .weapons(ship_rec.to_weapons())
+                .build_unchecked(),
             profile,
+            TargetContext::of_ship_type(hostile_rec.ship_type()),
         );
         if !static_buffs.is_empty() {
             attacker = apply_static_buffs_to_combatant(attacker, &static_buffs);
         }
         let mut seats = crew_seats.clone();
-        extend_crew_with_ship_abilities(&mut seats, Some(&ship_rec));
+        extend_crew_with_ship_abilities(&mut seats, Some(&ship_rec), Some(&hostile_rec));
         return CombatSimulationInput {
             attacker,
-            // Hostile as defender: offensive stats and per-weapon data exist on `HostileRecord` (data.stfc.space)
-            // but are not yet mapped into `Combatant` / sub-round resolution.
-            defender: Combatant {
-                id: hostile.to_string(),
-                attack: 0.0,
-                mitigation: defender_mitigation,
-                pierce: 0.0,
-                crit_chance: 0.0,
-                crit_multiplier: 1.0,
-                proc_chance: 0.0,
-                proc_multiplier: 1.0,
-                end_of_round_damage: 0.0,
-                hull_health: defender_hull,
-                shield_health: hostile_rec.shield_health,
-                shield_mitigation: hostile_rec.shield_mitigation.unwrap_or(0.8),
-                apex_barrier: hostile_rec.apex_barrier,
-                apex_shred: 0.0,
-                isolytic_damage: 0.0,
-                isolytic_defense: hostile_rec.isolytic_defense,
-                weapons: vec![],
-            },
+            defender: hostile_defender(hostile, &hostile_rec, defender_mitigation),
             crew: CrewConfiguration { seats },
             rounds,
             defender_hull,
@@ -397,55 +522,29 @@ pub(crate) fn scenario_to_combat_input(
     let defender_mitigation = computed_defender_mitigation(ship, hostile);
 
     let mut attacker = apply_profile_to_attacker(
-        Combatant {
-            id: ship.to_string(),
-            attack: 95.0 + (ship_hash % 70) as f64,
-            mitigation: 0.0,
-            pierce: 0.08 + ((ship_hash >> 8) % 14) as f64 / 100.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance,
-            proc_multiplier,
-            end_of_round_damage: 0.0,
-            hull_health: 1000.0,
-            shield_health: 0.0,
-            shield_mitigation: 0.8,
-            apex_barrier: 0.0,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-            weapons: vec![],
-        },
+        Combatant::builder(ship.to_string())
+            .attack(95.0 + (ship_hash % 70) as f64)
+            .pierce(0.08 + ((ship_hash >> 8) % 14) as f64 / 100.0)
+            .proc_chance(proc_chance)
+            .proc_multiplier(proc_multiplier)
+            .build_unchecked(),
         profile,
+        TargetContext::Hostile,
     );
     if !static_buffs.is_empty() {
         attacker = apply_static_buffs_to_combatant(attacker, &static_buffs);
     }
 
     let mut seats = crew_seats.clone();
-    extend_crew_with_ship_abilities(&mut seats, resolve_ship(ship).as_ref());
+    extend_crew_with_ship_abilities(&mut seats, resolve_ship(ship).as_ref(), None);
 
     CombatSimulationInput {
         attacker,
-        defender: Combatant {
-            id: hostile.to_string(),
-            attack: 0.0,
-            mitigation: defender_mitigation,
-            pierce: 0.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            weapons: vec![],
-            end_of_round_damage: 0.0,
-            hull_health: defender_hull,
-            shield_health: 400.0,
-            shield_mitigation: 0.8,
-            apex_barrier: 0.0,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: 0.0,
-        },
+        defender: Combatant::builder(hostile.to_string())
+            .mitigation(defender_mitigation)
+            .hull_health(defender_hull)
+            .shield_health(400.0)
+            .build_unchecked(),
         crew: CrewConfiguration { seats },
         rounds: 3 + (hostile_hash % 4) as u32,
         defender_hull,
@@ -453,6 +552,25 @@ pub(crate) fn scenario_to_combat_input(
     }
 }
 
+/// Defender for a resolved hostile: its defensive stats, one player's share of a group armada's
+/// hull and shields, and the armada charge-bar attack. Offensive stats and per-weapon data exist
+/// on `HostileRecord` (data.stfc.space) but are not yet mapped into `Combatant` / sub-round
+/// resolution, so the charged attack is the only fire a resolved hostile returns.
+fn hostile_defender(hostile: &str, hostile_rec: &HostileRecord, mitigation: f64) -> Combatant {
+    let mut builder = Combatant::builder(hostile.to_string())
+        .mitigation(mitigation)
+        .hull_health(hostile_rec.fight_hull_health())
+        .shield_health(hostile_rec.fight_shield_health())
+        .shield_mitigation(hostile_rec.shield_mitigation.unwrap_or(0.8))
+        .apex_barrier(hostile_rec.apex_barrier)
+        .isolytic_defense(hostile_rec.isolytic_defense)
+        .tags(hostile_rec.target_tags());
+    if let Some(charge) = hostile_rec.charged_attack() {
+        builder = builder.charged_attack(charge.every_rounds, charge.damage);
+    }
+    builder.build_unchecked()
+}
+
 fn synthetic_ship_type(identifier_hash: u64) -> ShipType {
     match identifier_hash % 4 {
         0 => ShipType::Battleship,
@@ -496,23 +614,19 @@ pub(crate) fn computed_defender_mitigation(ship: &str, hostile: &str) -> f64 {
     mitigation(defender, attacker, ship_type)
 }
 
-pub(crate) fn stable_seed(
-    ship: &str,
-    hostile: &str,
-    captain: &str,
-    bridge: &[String],
-    below_decks: &[String],
-    seed: u64,
-) -> u64 {
+/// Per-candidate base seed. Officer names are hashed without their "(T#)" tier suffix, so the
+/// same crew at different tiers shares a random stream (tier comparisons see only the tier).
+pub(crate) fn stable_seed(ship: &str, hostile: &str, candidate: &CrewCandidate, seed: u64) -> u64 {
     let mut acc = seed;
-    for s in [ship, hostile, captain]
-        .into_iter()
-        .chain(bridge.iter().map(String::as_str))
-        .chain(below_decks.iter().map(String::as_str))
-    {
+    let mut mix = |s: &str| {
         for b in s.bytes() {
             acc = acc.wrapping_mul(37).wrapping_add(u64::from(b));
         }
+    };
+    mix(ship);
+    mix(hostile);
+    for name in candidate.officers() {
+        mix(&split_name_and_tier(name).0);
     }
     acc
 }
@@ -545,14 +659,7 @@ pub(crate) fn build_shared_scenario_data_standalone(ship: &str, hostile: &str) -
     let lcars_data = if use_lcars_officer_source_standalone() {
         load_lcars_dir(DEFAULT_LCARS_OFFICERS_DIR_STANDALONE)
             .ok()
-            .map(|officers| {
-                let by_id = index_lcars_officers_by_id(officers);
-                let name_to_id: HashMap<String, String> = by_id
-                    .values()
-                    .map(|o| (normalize_lookup_key(&o.name), o.id.clone()))
-                    .collect();
-                LcarsOfficerData { by_id, name_to_id }
-            })
+            .map(LcarsOfficerData::from_officers)
     } else {
         None
     };
@@ -561,26 +668,68 @@ pub(crate) fn build_shared_scenario_data_standalone(ship: &str, hostile: &str) -
         .to_string_lossy()
         .to_string();
     let resolve_options = import::load_imported_roster(&roster_path)
-        .map(|entries| {
-            let officer_tiers: HashMap<String, u8> = entries
-                .into_iter()
-                .filter_map(|e| e.tier.map(|t| (e.canonical_officer_id, t)))
-                .collect();
-            ResolveOptions {
-                tier: None,
-                officer_tiers: if officer_tiers.is_empty() {
-                    None
-                } else {
-                    Some(officer_tiers)
-                },
-                ..Default::default()
-            }
-        })
+        .map(resolve_options_from_roster)
         .unwrap_or_default();
 
     let ship_rec = resolve_ship(ship);
     let hostile_rec = resolve_hostile(hostile);
 
+    shared_scenario_data_from_records(
+        ship,
+        hostile,
+        ship_rec,
+        hostile_rec,
+        officer_index,
+        profile,
+        lcars_data,
+        resolve_options,
+    )
+}
+
+/// Per-officer tiers, levels and boost state from the profile roster.
+fn resolve_options_from_roster(entries: Vec<import::RosterEntry>) -> ResolveOptions {
+    let boosted_officers = entries
+        .iter()
+        .filter(|e| e.boosted)
+        .map(|e| e.canonical_officer_id.clone())
+        .collect();
+    let officer_levels = entries
+        .iter()
+        .filter_map(|e| e.level.map(|l| (e.canonical_officer_id.clone(), l)))
+        .collect();
+    let officer_tiers: HashMap<String, u8> = entries
+        .into_iter()
+        .filter_map(|e| e.tier.map(|t| (e.canonical_officer_id, t)))
+        .collect();
+    ResolveOptions {
+        tier: None,
+        officer_tiers: if officer_tiers.is_empty() {
+            None
+        } else {
+            Some(officer_tiers)
+        },
+        boosted_officers,
+        officer_levels,
+        ..Default::default()
+    }
+}
+
+/// Assemble shared scenario data from already-loaded records and officers. Touches no files or
+/// environment, so embedders (see [crate::embed]) can run the optimizer on data they supply.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn shared_scenario_data_from_records(
+    ship: &str,
+    hostile: &str,
+    ship_rec: Option<ShipRecord>,
+    hostile_rec: Option<HostileRecord>,
+    officer_index: HashMap<String, Officer>,
+    profile: PlayerProfile,
+    lcars_data: Option<LcarsOfficerData>,
+    mut resolve_options: ResolveOptions,
+) -> SharedScenarioData {
+    if let Some(hostile_r) = &hostile_rec {
+        resolve_options.target_tags = hostile_r.target_tags();
+    }
     let (
         cached_defender,
         cached_rounds,
@@ -602,39 +751,25 @@ pub(crate) fn build_shared_scenario_data_standalone(ship: &str, hostile: &str) -
             attacker_stats,
             hostile_r.ship_type(),
         );
-        // Hostile offensive stats on `HostileRecord` are not yet mapped into `Combatant`.
-        let defender = Combatant {
-            id: hostile.to_string(),
-            attack: 0.0,
-            mitigation: defender_mitigation,
-            pierce: 0.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            end_of_round_damage: 0.0,
-            hull_health: hostile_r.hull_health,
-            shield_health: hostile_r.shield_health,
-            shield_mitigation: hostile_r.shield_mitigation.unwrap_or(0.8),
-            apex_barrier: hostile_r.apex_barrier,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: hostile_r.isolytic_defense,
-            weapons: vec![],
-        };
+        let defender = hostile_defender(hostile, hostile_r, defender_mitigation);
         let rounds = 100u32.min(10u32.saturating_add(hostile_r.level as u32));
         (
             Some(defender),
             Some(rounds),
-            Some(hostile_r.hull_health),
+            Some(hostile_r.fight_hull_health()),
             Some(pierce),
             Some(defender_mitigation),
         )
     } else {
-        (None, None, None, None, None)
+        let placeholder_mitigation = computed_defender_mitigation(ship, hostile);
+        (None, None, None, None, Some(placeholder_mitigation))
     };
 
     let using_placeholder_combatants = cached_defender.is_none();
+    let target = hostile_rec
+        .as_ref()
+        .map_or(TargetContext::Hostile, |h| TargetContext::of_ship_type(h.ship_type()));
+    let crew_slots = ship_rec.as_ref().map_or_else(CrewSlots::default, CrewSlots::for_ship);
 
     SharedScenarioData {
         ship: ship.to_string(),
@@ -645,12 +780,20 @@ pub(crate) fn build_shared_scenario_data_standalone(ship: &str, hostile: &str) -
         resolve_options,
         ship_rec,
         hostile_rec,
+        crew_slots,
         cached_defender,
         cached_rounds,
         cached_defender_hull,
         cached_pierce,
         cached_defender_mitigation,
         using_placeholder_combatants,
+        attacker_delay_rounds: 0,
+        initiative: InitiativeMode::default(),
+        waves: 0,
+        wave_repair: 0.0,
+        attacker_condition: AttackerCondition::default(),
+        attacker_scale: None,
+        target,
     }
 }
 
@@ -666,13 +809,43 @@ pub(crate) fn build_shared_scenario_data_from_registry(
     let officer_index = registry.officer_index().clone();
 
     let pid = profile_index::resolve_profile_id_for_api(profile_id);
-    let profile_path_str = profile_path(&pid, PROFILE_JSON)
+    let roster_path = profile_path(&pid, ROSTER_IMPORTED)
         .to_string_lossy()
         .to_string();
-    let roster_path = profile_path(&pid, ROSTER_IMPORTED)
+    let profile = load_effective_profile(registry, &pid);
+
+    let lcars_data = registry
+        .lcars_officers()
+        .map(|officers| LcarsOfficerData::from_officers(officers.to_vec()));
+
+    let resolve_options = import::load_imported_roster(&roster_path)
+        .map(resolve_options_from_roster)
+        .unwrap_or_default();
+
+    let ship_rec = registry.resolve_ship_with_tier_level(ship, ship_tier, ship_level);
+    let hostile_rec = registry.resolve_hostile(hostile);
+
+    shared_scenario_data_from_records(
+        ship,
+        hostile,
+        ship_rec,
+        hostile_rec,
+        officer_index,
+        profile,
+        lcars_data,
+        resolve_options,
+    )
+}
+
+/// Profile `pid` with its synced forbidden tech, buildings and research merged into the bonuses.
+pub(crate) fn load_effective_profile(
+    registry: &crate::data::data_registry::DataRegistry,
+    pid: &str,
+) -> PlayerProfile {
+    let profile_path_str = profile_path(pid, PROFILE_JSON)
         .to_string_lossy()
         .to_string();
-    let ft_path = profile_path(&pid, FORBIDDEN_TECH_IMPORTED)
+    let ft_path = profile_path(pid, FORBIDDEN_TECH_IMPORTED)
         .to_string_lossy()
         .to_string();
 
@@ -694,9 +867,7 @@ pub(crate) fn build_shared_scenario_data_from_registry(
     }
 
     if let Some(imported_buildings) = import::load_imported_buildings(
-        &profile_path(&pid, BUILDINGS_IMPORTED)
-            .to_string_lossy()
-            .to_string(),
+        profile_path(pid, BUILDINGS_IMPORTED).to_string_lossy().as_ref(),
     ) {
         if !imported_buildings.is_empty() {
             if let Some(building_index) =
@@ -729,115 +900,14 @@ pub(crate) fn build_shared_scenario_data_from_registry(
     }
 
     if let Some(imported_research) = import::load_imported_research(
-        &profile_path(&pid, RESEARCH_IMPORTED).to_string_lossy().to_string(),
+        profile_path(pid, RESEARCH_IMPORTED).to_string_lossy().as_ref(),
     ) {
         if let Some(catalog) = registry.research_catalog() {
             merge_research_bonuses_into_profile(&mut profile, &imported_research, catalog);
         }
     }
 
-    let lcars_data = registry.lcars_officers().map(|officers| {
-        let officers_vec = officers.to_vec();
-        let by_id = index_lcars_officers_by_id(officers_vec);
-        let name_to_id: HashMap<String, String> = by_id
-            .values()
-            .map(|o| (normalize_lookup_key(&o.name), o.id.clone()))
-            .collect();
-        LcarsOfficerData { by_id, name_to_id }
-    });
-
-    let resolve_options = import::load_imported_roster(&roster_path)
-        .map(|entries| {
-            let officer_tiers: HashMap<String, u8> = entries
-                .into_iter()
-                .filter_map(|e| e.tier.map(|t| (e.canonical_officer_id, t)))
-                .collect();
-            ResolveOptions {
-                tier: None,
-                officer_tiers: if officer_tiers.is_empty() {
-                    None
-                } else {
-                    Some(officer_tiers)
-                },
-                ..Default::default()
-            }
-        })
-        .unwrap_or_default();
-
-    let ship_rec = registry.resolve_ship_with_tier_level(ship, ship_tier, ship_level);
-    let hostile_rec = registry.resolve_hostile(hostile);
-
-    let (
-        cached_defender,
-        cached_rounds,
-        cached_defender_hull,
-        cached_pierce,
-        cached_defender_mitigation,
-    ) = if let (Some(ref ship_r), Some(ref hostile_r)) = (&ship_rec, &hostile_rec) {
-        let attacker_stats = ship_r.to_attacker_stats();
-        let defender_mitigation = mitigation_for_hostile(
-            hostile_r.to_defender_stats(),
-            attacker_stats,
-            hostile_r.ship_type(),
-            hostile_r.mystery_mitigation_factor.unwrap_or(0.0),
-            hostile_r.mitigation_floor.unwrap_or(MITIGATION_FLOOR),
-            hostile_r.mitigation_ceiling.unwrap_or(MITIGATION_CEILING),
-        );
-        let pierce = pierce_damage_through_bonus(
-            hostile_r.to_defender_stats(),
-            attacker_stats,
-            hostile_r.ship_type(),
-        );
-        // Hostile offensive stats on `HostileRecord` are not yet mapped into `Combatant`.
-        let defender = Combatant {
-            id: hostile.to_string(),
-            attack: 0.0,
-            mitigation: defender_mitigation,
-            pierce: 0.0,
-            crit_chance: 0.0,
-            crit_multiplier: 1.0,
-            proc_chance: 0.0,
-            proc_multiplier: 1.0,
-            end_of_round_damage: 0.0,
-            hull_health: hostile_r.hull_health,
-            shield_health: hostile_r.shield_health,
-            shield_mitigation: hostile_r.shield_mitigation.unwrap_or(0.8),
-            apex_barrier: hostile_r.apex_barrier,
-            apex_shred: 0.0,
-            isolytic_damage: 0.0,
-            isolytic_defense: hostile_r.isolytic_defense,
-            weapons: vec![],
-        };
-        let rounds = 100u32.min(10u32.saturating_add(hostile_r.level as u32));
-        (
-            Some(defender),
-            Some(rounds),
-            Some(hostile_r.hull_health),
-            Some(pierce),
-            Some(defender_mitigation),
-        )
-    } else {
-        (None, None, None, None, None)
-    };
-
-    let using_placeholder_combatants = cached_defender.is_none();
-
-    SharedScenarioData {
-        ship: ship.to_string(),
-        hostile: hostile.to_string(),
-        officer_index,
-        profile,
-        lcars_data,
-        resolve_options,
-        ship_rec,
-        hostile_rec,
-        cached_defender,
-        cached_rounds,
-        cached_defender_hull,
-        cached_pierce,
-        cached_defender_mitigation,
-        using_placeholder_combatants,
-    }
+    profile
 }
 
 fn infer_ops_level(
@@ -871,11 +941,98 @@ mod tests {
         RESEARCH_IMPORTED,
     };
     use crate::data::ship::{ShipAbility, ShipRecord};
-    use crate::optimizer::crew_generator::CrewCandidate;
+    use crate::optimizer::crew_generator::{CrewCandidate, CrewSlots};
     use uuid::Uuid;
 
     static SHARED_SCENARIO_RESEARCH_LOCK: Mutex<()> = Mutex::new(());
 
+    #[test]
+    fn roster_boost_state_reaches_resolve_options() {
+        let roster: Vec<import::RosterEntry> = serde_json::from_str(
+            r#"[{"canonical_officer_id":"kirk","canonical_name":"Kirk","tier":3,"boosted":true},
+                {"canonical_officer_id":"spock","canonical_name":"Spock","tier":2}]"#,
+        )
+        .unwrap();
+        let options = resolve_options_from_roster(roster);
+        assert_eq!(
+            options.boosted_officers,
+            std::collections::HashSet::from(["kirk".to_string()])
+        );
+        assert_eq!(options.tier_for("spock"), Some(2));
+    }
+
+    #[test]
+    fn officer_traits_follow_profile_toggle_and_roster_level() {
+        let kirk: Officer = serde_json::from_str(
+            r#"{"id":"kirk","name":"Kirk","traits":[
+                {"stat":"crit_chance","value":0.05},
+                {"stat":"weapon_damage","value":0.1,"unlock_level":30}]}"#,
+        )
+        .unwrap();
+        let mut shared = build_shared_scenario_data_standalone("trait_ship", "trait_hostile");
+        shared.officer_index = index_officers_by_name(vec![kirk]);
+        shared.profile = PlayerProfile {
+            bonuses: HashMap::from([("weapon_damage".to_string(), 0.1)]),
+            ..PlayerProfile::default()
+        };
+        shared.resolve_options.officer_levels = HashMap::from([("kirk".to_string(), 20)]);
+        let candidate = CrewCandidate::new("Kirk".to_string(), Vec::new(), Vec::new());
+
+        let off = scenario_to_combat_input_from_shared(&shared, &candidate, 1).attacker;
+        shared.profile.officer_traits = true;
+        let level_20 = scenario_to_combat_input_from_shared(&shared, &candidate, 1).attacker;
+        assert!((level_20.crit_chance - off.crit_chance - 0.05).abs() < 1e-12);
+        assert_eq!(level_20.attack, off.attack);
+
+        shared.resolve_options.officer_levels.insert("kirk".to_string(), 30);
+        let level_30 = scenario_to_combat_input_from_shared(&shared, &candidate, 1).attacker;
+        assert!((level_30.attack - off.attack * 1.2 / 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn officer_stats_unlock_ship_bonus_by_bridge_officer_level() {
+        let officer = |id: &str, name: &str| -> Officer {
+            serde_json::from_value(serde_json::json!({
+                "id": id, "name": name, "rarity": "epic", "slot": "command"
+            }))
+            .unwrap()
+        };
+        let mut shared = build_shared_scenario_data_standalone("stats_ship", "stats_hostile");
+        shared.officer_index =
+            index_officers_by_name(vec![officer("kirk", "Kirk"), officer("spock", "Spock")]);
+        shared.ship_rec = Some(
+            serde_json::from_value(serde_json::json!({
+                "id": "stats_ship", "ship_name": "Stats", "ship_class": "battleship",
+                "armor_piercing": 0.0, "shield_piercing": 0.0, "accuracy": 0.0, "attack": 100.0,
+                "crit_chance": 0.0, "crit_damage": 1.0, "hull_health": 1000.0,
+                "shield_health": 0.0,
+                "officer_bonus": {"attack": [
+                    {"value": 400.0, "bonus": 0.1}, {"value": 1000.0, "bonus": 0.2}
+                ]}
+            }))
+            .unwrap(),
+        );
+        shared.resolve_options.officer_levels = HashMap::from([("kirk".to_string(), 10)]);
+        let attack = |shared: &SharedScenarioData, captain: &str, below_decks: Vec<String>| {
+            let candidate = CrewCandidate::new(captain.to_string(), Vec::new(), below_decks);
+            scenario_to_combat_input_from_shared(shared, &candidate, 1).attacker.attack
+        };
+
+        let off = attack(&shared, "Kirk", Vec::new());
+        shared.profile.officer_stats = true;
+        // An epic command officer at level 10 has 500 attack; at level 20, 1000.
+        let level_10 = attack(&shared, "Kirk", Vec::new());
+        assert!((level_10 - off * 1.1).abs() < 1e-9);
+        shared.resolve_options.officer_levels.insert("kirk".to_string(), 20);
+        assert!((attack(&shared, "Kirk", Vec::new()) - off * 1.2).abs() < 1e-9);
+        assert!((attack(&shared, "Kirk (L10)", Vec::new()) - level_10).abs() < 1e-9);
+
+        // Below-decks officers' stats do not count.
+        shared.resolve_options.officer_levels.insert("spock".to_string(), 20);
+        let with_spock = attack(&shared, "Kirk (L5)", vec!["Spock".to_string()]);
+        assert!((with_spock - off).abs() < 1e-9);
+    }
+
     #[test]
     fn ship_abilities_merged_when_shared_scenario_fallback_no_cached_defender() {
         let ship_rec = ShipRecord {
@@ -899,7 +1056,22 @@ mod tests {
                 timing: "round_start".into(),
                 effect_type: "pierce_bonus".into(),
                 value: 0.05,
+                against: Vec::new(),
             }]),
+            ship_ability: Some(ShipAbility {
+                id: "vs_interceptors".into(),
+                timing: "combat_begin".into(),
+                effect_type: "attack_multiplier".into(),
+                value: 0.2,
+                against: vec!["interceptor".into()],
+            }),
+            bridge_slots: None,
+            below_decks_slots: None,
+            armor: None,
+            absorption: None,
+            dodge: None,
+            officer_bonus: None,
+            repair_cost: None,
         };
 
         let shared = SharedScenarioData {
@@ -915,23 +1087,31 @@ mod tests {
             },
             ship_rec: Some(ship_rec),
             hostile_rec: None,
+            crew_slots: CrewSlots::default(),
             cached_defender: None,
             cached_rounds: None,
             cached_defender_hull: None,
             cached_pierce: None,
             cached_defender_mitigation: None,
             using_placeholder_combatants: true,
+            attacker_delay_rounds: 0,
+            initiative: InitiativeMode::default(),
+            waves: 0,
+            wave_repair: 0.0,
+            attacker_condition: AttackerCondition::default(),
+            attacker_scale: None,
+            target: TargetContext::Hostile,
         };
 
-        let candidate = CrewCandidate {
-            captain: "Kirk".to_string(),
-            bridge: vec!["Spock".to_string(), "Uhura".to_string()],
-            below_decks: vec![
+        let candidate = CrewCandidate::new(
+            "Kirk".to_string(),
+            vec!["Spock".to_string(), "Uhura".to_string()],
+            vec![
                 "Scotty".to_string(),
                 "McCoy".to_string(),
                 "Rand".to_string(),
             ],
-        };
+        );
 
         let input = scenario_to_combat_input_from_shared(&shared, &candidate, 1);
         let ship_seats: Vec<_> = input
@@ -942,6 +1122,56 @@ mod tests {
             .collect();
         assert_eq!(ship_seats.len(), 1);
         assert_eq!(ship_seats[0].ability.class, AbilityClass::ShipAbility);
+
+        let interceptor: HostileRecord = serde_json::from_str(
+            r#"{"id":"h","hostile_name":"H","level":1,"ship_class":"interceptor","armor":1.0,"shield_deflection":1.0,"dodge":1.0,"hull_health":100.0,"shield_health":0.0}"#,
+        )
+        .expect("hostile JSON");
+        let shared = SharedScenarioData {
+            hostile_rec: Some(interceptor),
+            ..shared
+        };
+        let input = scenario_to_combat_input_from_shared(&shared, &candidate, 1);
+        let ship_ability_names: Vec<_> = input
+            .crew
+            .seats
+            .iter()
+            .filter(|s| s.seat == CrewSeat::Ship)
+            .map(|s| s.ability.name.as_str())
+            .collect();
+        assert_eq!(ship_ability_names, ["vs_interceptors", "1"]);
+    }
+
+    #[test]
+    fn group_armada_defender_carries_player_share_and_charge_attack() {
+        let armada: HostileRecord = serde_json::from_str(
+            r#"{"id":"a","hostile_name":"A","level":50,"ship_class":"armada","armor":1.0,"shield_deflection":1.0,"dodge":1.0,"hull_health":8000.0,"shield_health":2000.0,"apex_barrier":9000.0,"armada":{"charge_rounds":4,"charge_damage":12000.0,"participants":4}}"#,
+        )
+        .expect("armada JSON");
+        let defender = hostile_defender("a", &armada, 0.3);
+        assert_eq!(defender.hull_health, 2000.0);
+        assert_eq!(defender.shield_health, 500.0);
+        assert_eq!(defender.apex_barrier, 9000.0);
+        assert_eq!(defender.charged_attack.map(|c| c.every_rounds), Some(4));
+    }
+
+    #[test]
+    fn attacker_scale_applies_after_crew_and_profile() {
+        let shared = build_shared_scenario_data_standalone("scale_ship", "scale_hostile");
+        let candidate = CrewCandidate::new(
+            "Kirk".to_string(),
+            vec!["Spock".to_string(), "Uhura".to_string()],
+            Vec::new(),
+        );
+        let base = scenario_to_combat_input_from_shared(&shared, &candidate, 3);
+        let scaled = scenario_to_combat_input_from_shared(
+            &shared.clone().with_attacker_scale(AttackerStat::HullHealth, 1.25),
+            &candidate,
+            3,
+        );
+        assert!((scaled.attacker.hull_health - base.attacker.hull_health * 1.25).abs() < 1e-9);
+        assert_eq!(scaled.attacker.attack, base.attacker.attack);
+        assert_eq!(scaled.base_seed, base.base_seed);
     }
 
     #[test]
@@ -1001,15 +1231,15 @@ mod tests {
         let second = computed_defender_mitigation("Franklin", "Hostile Miner");
         assert_eq!(first, second);
 
-        let candidate = CrewCandidate {
-            captain: "Kirk".to_string(),
-            bridge: vec!["Spock".to_string(), "Spock".to_string()],
-            below_decks: vec![
+        let candidate = CrewCandidate::new(
+            "Kirk".to_string(),
+            vec!["Spock".to_string(), "Spock".to_string()],
+            vec![
                 "Scotty".to_string(),
                 "Scotty".to_string(),
                 "Scotty".to_string(),
             ],
-        };
+        );
         let officers = HashMap::new();
         let profile = PlayerProfile::default();
 
@@ -3,24 +3,56 @@
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
-use crate::combat::{simulate_combat, SimulationConfig, TraceMode};
+use crate::combat::types::MAX_COMBAT_ROUNDS;
+use crate::combat::{
+    simulate_combat_streaming_from_state, simulate_combat_with_scratch,
+    simulate_waves_with_effects, AttackerCondition, CombatEvent, CombatScratch, CrewEffects,
+    SimulationConfig, TraceFilter,
+};
 use crate::data::data_registry::DataRegistry;
+use crate::data::hostile::HostileRecord;
+use crate::data::officer::UnknownOfficerMode;
+use crate::data::profile::PlayerProfile;
+use crate::data::ship::ShipRecord;
+use crate::lcars::{LcarsOfficer, ResolveOptions};
 use crate::optimizer::crew_generator::CrewCandidate;
+use crate::parallel::iteration_seed;
 use crate::perf_log;
 
 use super::crew_resolution::seeded_variance;
 use super::scenario::{
     build_shared_scenario_data_from_registry, build_shared_scenario_data_standalone,
-    scenario_to_combat_input_from_shared, SharedScenarioData,
+    scenario_to_combat_input_from_shared, shared_scenario_data_from_records, LcarsOfficerData,
+    SharedScenarioData,
 };
 
 #[derive(Debug, Clone)]
 pub struct SimulationResult {
     pub candidate: CrewCandidate,
+    /// Share of fights where the hostile was destroyed.
     pub win_rate: f64,
+    /// Share of fights that hit the round limit with both ships still up.
     pub stall_rate: f64,
+    /// Share of fights where the player's ship was destroyed. The three rates sum to 1.
     pub loss_rate: f64,
     pub avg_hull_remaining: f64,
+    /// Mean share of the round limit the player's ship stayed alive (1.0 = never destroyed).
+    pub avg_survival_share: f64,
+    /// Mean share of the opponent's hull + shields destroyed.
+    pub avg_damage_dealt: f64,
+    /// Share of fights the player's ship ends with hull left (won, stalled, or outlasted the limit).
+    pub survival_rate: f64,
+    /// Mean opponents destroyed per iteration in wave scenarios (0 or 1 for single fights).
+    pub avg_waves_cleared: f64,
+    /// Median rounds to destroy the hostile (all waves), over winning fights. None without wins.
+    pub median_rounds_to_kill: Option<u32>,
+    /// 95th-percentile rounds to destroy the hostile, over winning fights.
+    pub p95_rounds_to_kill: Option<u32>,
+    /// Mean damage dealt per round fought, over all fights.
+    pub avg_damage_per_round: f64,
+    /// Mean share of full hull missing when the run ends (1.0 = destroyed), i.e. what needs
+    /// repairing afterwards.
+    pub avg_hull_lost: f64,
 }
 
 /// Stable hash for deduplicating identical crews in GA populations (same process = deterministic).
@@ -36,6 +68,20 @@ pub fn crew_candidate_stable_hash(c: &CrewCandidate) -> u64 {
     h.finish()
 }
 
+/// Nearest-rank percentile (`q` in 0–1) of values recorded in `histogram[value]`; None when empty.
+fn histogram_percentile(histogram: &[u32], q: f64) -> Option<u32> {
+    let count: u64 = histogram.iter().map(|&n| n as u64).sum();
+    if count == 0 {
+        return None;
+    }
+    let rank = ((q * count as f64).ceil() as u64).clamp(1, count);
+    let mut seen = 0u64;
+    histogram.iter().enumerate().find_map(|(value, &n)| {
+        seen += n as u64;
+        (seen >= rank).then_some(value as u32)
+    })
+}
+
 /// Wilson score upper bound (approx. 95% interval) for binomial win proportion.
 /// Used to drop scout iterations for crews that are very unlikely to rank in the top K.
 fn win_rate_upper_wilson_95(wins: usize, trials: usize) -> f64 {
@@ -71,6 +117,24 @@ impl ScoutEarlyStopCfg {
     }
 }
 
+/// First iteration of `candidate`'s Monte Carlo run as a replayable
+/// [crate::combat::SimulationSnapshot]. Wave scenarios record the opening wave only.
+#[cfg(feature = "server")]
+pub(crate) fn snapshot_candidate_fight(
+    shared: &SharedScenarioData,
+    candidate: &CrewCandidate,
+    seed: u64,
+) -> crate::combat::SimulationSnapshot {
+    let input = scenario_to_combat_input_from_shared(shared, candidate, seed);
+    let config = SimulationConfig::builder()
+        .rounds(input.rounds)
+        .seed(iteration_seed(input.base_seed, 0))
+        .attacker_delay_rounds(shared.attacker_delay_rounds)
+        .initiative(shared.initiative)
+        .build();
+    crate::combat::SimulationSnapshot::new(input.attacker, input.defender, input.crew, config)
+}
+
 fn run_candidate_monte_carlo(
     shared: &SharedScenarioData,
     candidate: &CrewCandidate,
@@ -79,33 +143,86 @@ fn run_candidate_monte_carlo(
     early_scout: Option<ScoutEarlyStopCfg>,
 ) -> SimulationResult {
     let input = scenario_to_combat_input_from_shared(shared, candidate, seed);
+    // Grouped once: every iteration fights with the same crew.
+    let crew_effects = CrewEffects::new(&input.crew);
+    // Reused by every iteration so fights do not allocate their working buffers.
+    let mut scratch = CombatScratch::default();
+    let waves = shared.waves.max(1);
+    let wave_defenders = vec![input.defender.clone(); waves as usize];
     let mut wins = 0usize;
     let mut stalls = 0usize;
     let mut losses = 0usize;
     let mut surviving_hull_sum = 0.0f64;
-
-    let mut combat_config = SimulationConfig {
-        rounds: input.rounds,
-        seed: 0,
-        trace_mode: TraceMode::Off,
-    };
+    let mut survival_share_sum = 0.0f64;
+    let mut damage_dealt_sum = 0.0f64;
+    let mut survived = 0usize;
+    let mut waves_cleared_sum = 0.0f64;
+    let mut damage_per_round_sum = 0.0f64;
+    let mut hull_lost_sum = 0.0f64;
+    let mut rounds_to_kill =
+        vec![0u32; (input.rounds.clamp(1, MAX_COMBAT_ROUNDS) * waves) as usize + 1];
+    let round_limit = input.rounds.clamp(1, MAX_COMBAT_ROUNDS) as f64 * waves as f64;
+    let opponent_pool = (input.defender.hull_health + input.defender.shield_health.max(0.0))
+        .max(1.0)
+        * waves as f64;
+
+    let mut combat_config = SimulationConfig::builder()
+        .rounds(input.rounds)
+        .seed(0)
+        .attacker_delay_rounds(shared.attacker_delay_rounds)
+        .initiative(shared.initiative)
+        .build();
+
+    let start = shared.attacker_condition.start_state(&input.attacker);
 
     let mut n_done = 0usize;
     while n_done < max_iterations {
-        let iteration_seed = input.base_seed.wrapping_add(n_done as u64);
+        let iteration_seed = iteration_seed(input.base_seed, n_done);
         combat_config.seed = iteration_seed;
-        let result = simulate_combat(
-            &input.attacker,
-            &input.defender,
-            combat_config,
-            &input.crew,
-        );
+        // Wave scenarios score the run as a whole: the last wave decides win/stall/loss, while
+        // rounds survived and damage dealt accumulate over every wave fought.
+        let (result, rounds_fought, damage_total) = if waves > 1 {
+            let run = simulate_waves_with_effects(
+                &input.attacker,
+                &wave_defenders,
+                combat_config,
+                &crew_effects,
+                start,
+                shared.wave_repair,
+                &mut scratch,
+            );
+            waves_cleared_sum += run.waves_cleared as f64;
+            let rounds_fought: u32 = run.waves.iter().map(|w| w.rounds_simulated).sum();
+            let damage_total: f64 = run.waves.iter().map(|w| w.total_damage).sum();
+            let last = run.waves.into_iter().last().expect("at least one wave is fought");
+            (last, rounds_fought, damage_total)
+        } else {
+            let result = simulate_combat_with_scratch(
+                &input.attacker,
+                &input.defender,
+                combat_config,
+                &crew_effects,
+                start,
+                &mut scratch,
+            );
+            if result.defender_hull_remaining <= 0.0 && result.attacker_hull_remaining > 0.0 {
+                waves_cleared_sum += 1.0;
+            }
+            let (rounds, damage) = (result.rounds_simulated, result.total_damage);
+            (result, rounds, damage)
+        };
         let effective_hull = input.defender_hull * seeded_variance(iteration_seed);
 
-        if result.winner_by_round_limit {
+        // Both ships still up means the fight ran out of rounds. Scenario round limits are usually
+        // below MAX_COMBAT_ROUNDS, where `winner_by_round_limit` is never set, so check hulls.
+        let stalled = result.attacker_hull_remaining > 0.0 && result.defender_hull_remaining > 0.0;
+        if stalled {
             stalls += 1;
         } else if result.attacker_won {
             wins += 1;
+            if let Some(slot) = rounds_to_kill.get_mut(rounds_fought as usize) {
+                *slot += 1;
+            }
         } else {
             losses += 1;
         }
@@ -119,6 +236,17 @@ fn run_candidate_monte_carlo(
             surviving_hull_sum += remaining;
         }
 
+        survival_share_sum += if result.attacker_hull_remaining <= 0.0 {
+            rounds_fought as f64 / round_limit
+        } else {
+            survived += 1;
+            1.0
+        };
+        damage_dealt_sum += (damage_total / opponent_pool).clamp(0.0, 1.0);
+        damage_per_round_sum += damage_total / rounds_fought.max(1) as f64;
+        let hull_share = result.attacker_hull_remaining / input.attacker.hull_health.max(1.0);
+        hull_lost_sum += 1.0 - hull_share.clamp(0.0, 1.0);
+
         n_done += 1;
 
         if let Some(cfg) = early_scout {
@@ -136,18 +264,22 @@ fn run_candidate_monte_carlo(
     let win_rate = if n_done == 0 { 0.0 } else { wins as f64 / n };
     let stall_rate = if n_done == 0 { 0.0 } else { stalls as f64 / n };
     let loss_rate = if n_done == 0 { 0.0 } else { losses as f64 / n };
-    let avg_hull_remaining = if n_done == 0 {
-        0.0
-    } else {
-        surviving_hull_sum / n
-    };
+    let mean = |sum: f64| if n_done == 0 { 0.0 } else { sum / n };
 
     SimulationResult {
         candidate: candidate.clone(),
         win_rate,
         stall_rate,
         loss_rate,
-        avg_hull_remaining,
+        avg_hull_remaining: mean(surviving_hull_sum),
+        avg_survival_share: mean(survival_share_sum),
+        avg_damage_dealt: mean(damage_dealt_sum),
+        survival_rate: mean(survived as f64),
+        avg_waves_cleared: mean(waves_cleared_sum),
+        median_rounds_to_kill: histogram_percentile(&rounds_to_kill, 0.5),
+        p95_rounds_to_kill: histogram_percentile(&rounds_to_kill, 0.95),
+        avg_damage_per_round: mean(damage_per_round_sum),
+        avg_hull_lost: mean(hull_lost_sum),
     }
 }
 
@@ -169,7 +301,8 @@ pub fn run_monte_carlo(
 }
 
 /// Like [run_monte_carlo] but distributes candidates across all CPU cores via Rayon.
-/// Use for large candidate lists (e.g. optimizer sweeps). Results order matches input order.
+/// Use for large candidate lists (e.g. optimizer sweeps). Results order matches input order and
+/// per-candidate statistics are bit-identical to the serial path (see [crate::parallel::batch]).
 pub fn run_monte_carlo_parallel(
     ship: &str,
     hostile: &str,
@@ -199,6 +332,24 @@ pub fn run_monte_carlo_parallel_deduped(
     if candidates.is_empty() {
         return Vec::new();
     }
+    run_monte_carlo_deduped_with_shared(
+        build_shared_scenario_data_standalone(ship, hostile),
+        candidates,
+        iterations,
+        seed,
+    )
+}
+
+/// [run_monte_carlo_parallel_deduped] on pre-built scenario data.
+pub(crate) fn run_monte_carlo_deduped_with_shared(
+    shared: SharedScenarioData,
+    candidates: &[CrewCandidate],
+    iterations: usize,
+    seed: u64,
+) -> Vec<SimulationResult> {
+    if candidates.is_empty() {
+        return Vec::new();
+    }
 
     let mut seen_hashes: HashSet<u64> = HashSet::with_capacity(candidates.len());
     let mut unique_indices: Vec<usize> = Vec::new();
@@ -214,13 +365,7 @@ pub fn run_monte_carlo_parallel_deduped(
         .map(|&i| candidates[i].clone())
         .collect();
 
-    let uniq_results = run_monte_carlo_parallel(
-        ship,
-        hostile,
-        &uniq,
-        iterations,
-        seed,
-    );
+    let uniq_results = run_monte_carlo_with_shared(shared, &uniq, iterations, seed, true);
 
     let mut by_hash: HashMap<u64, SimulationResult> =
         HashMap::with_capacity(uniq_results.len());
@@ -235,6 +380,14 @@ pub fn run_monte_carlo_parallel_deduped(
                 stall_rate: r.stall_rate,
                 loss_rate: r.loss_rate,
                 avg_hull_remaining: r.avg_hull_remaining,
+                avg_survival_share: r.avg_survival_share,
+                avg_damage_dealt: r.avg_damage_dealt,
+                survival_rate: r.survival_rate,
+                avg_waves_cleared: r.avg_waves_cleared,
+                median_rounds_to_kill: r.median_rounds_to_kill,
+                p95_rounds_to_kill: r.p95_rounds_to_kill,
+                avg_damage_per_round: r.avg_damage_per_round,
+                avg_hull_lost: r.avg_hull_lost,
             },
         );
     }
@@ -306,6 +459,57 @@ pub fn run_monte_carlo_with_registry(
     )
 }
 
+/// One traced fight of `candidate`, with each event passed to `on_event` as it happens. The fight
+/// is iteration 0 of [run_monte_carlo_with_registry] for the same inputs, so it replays the
+/// first fight behind a `/api/simulate` result; `filter` only drops events, not rolls. Crew
+/// names that match no officer are seated as `unknown_officers` says, and the ship starts as
+/// damaged as `attacker_condition` says. Returns the fight result and whether placeholder
+/// combatants were used.
+#[allow(clippy::too_many_arguments)]
+pub fn stream_fight_with_registry(
+    registry: &DataRegistry,
+    ship: &str,
+    hostile: &str,
+    ship_tier: Option<u32>,
+    ship_level: Option<u32>,
+    candidate: &CrewCandidate,
+    seed: u64,
+    profile_id: Option<&str>,
+    unknown_officers: UnknownOfficerMode,
+    attacker_condition: AttackerCondition,
+    filter: TraceFilter,
+    on_event: &mut dyn FnMut(CombatEvent),
+) -> (crate::combat::SimulationResult, bool) {
+    let shared = build_shared_scenario_data_from_registry(
+        registry,
+        ship,
+        hostile,
+        ship_tier,
+        ship_level,
+        profile_id,
+    )
+    .with_unknown_officers(unknown_officers)
+    .with_attacker_condition(attacker_condition);
+    let input = scenario_to_combat_input_from_shared(&shared, candidate, seed);
+    let config = SimulationConfig::builder()
+        .rounds(input.rounds)
+        .seed(iteration_seed(input.base_seed, 0))
+        .trace(true)
+        .trace_filter(filter)
+        .attacker_delay_rounds(shared.attacker_delay_rounds)
+        .initiative(shared.initiative)
+        .build();
+    let result = simulate_combat_streaming_from_state(
+        &input.attacker,
+        &input.defender,
+        config,
+        &input.crew,
+        shared.attacker_condition.start_state(&input.attacker),
+        on_event,
+    );
+    (result, shared.using_placeholder_combatants)
+}
+
 fn run_monte_carlo_with_parallelism(
     ship: &str,
     hostile: &str,
@@ -318,6 +522,31 @@ fn run_monte_carlo_with_parallelism(
     run_monte_carlo_with_shared(shared, candidates, iterations, seed, parallel)
 }
 
+/// Sequential Monte Carlo on caller-supplied records: `officers` stand in for the LCARS data
+/// directory and `profile` for the saved player profile. Reads no files and spawns no threads, so
+/// it runs in WASM and FFI hosts.
+pub fn run_monte_carlo_in_memory(
+    ship: &ShipRecord,
+    hostile: &HostileRecord,
+    officers: Vec<LcarsOfficer>,
+    profile: PlayerProfile,
+    candidates: &[CrewCandidate],
+    iterations: usize,
+    seed: u64,
+) -> Vec<SimulationResult> {
+    let shared = shared_scenario_data_from_records(
+        &ship.id,
+        &hostile.id,
+        Some(ship.clone()),
+        Some(hostile.clone()),
+        HashMap::new(),
+        profile,
+        Some(LcarsOfficerData::from_officers(officers)),
+        ResolveOptions::default(),
+    );
+    run_monte_carlo_with_shared(shared, candidates, iterations, seed, false)
+}
+
 /// Run Monte Carlo using pre-built SharedScenarioData (used by both legacy and registry paths).
 pub(crate) fn run_monte_carlo_with_shared(
     shared: SharedScenarioData,
@@ -389,13 +618,23 @@ mod tests {
         assert!(u200 < u50, "more data should tighten upper bound: {u50} vs {u200}");
     }
 
+    #[test]
+    fn histogram_percentile_uses_nearest_rank() {
+        // Values 2, 3, 3, 5 (x1, x2, x1).
+        let hist = [0, 0, 1, 2, 0, 1];
+        assert_eq!(histogram_percentile(&hist, 0.5), Some(3));
+        assert_eq!(histogram_percentile(&hist, 0.95), Some(5));
+        assert_eq!(histogram_percentile(&hist, 0.0), Some(2));
+        assert_eq!(histogram_percentile(&[0, 0, 0], 0.5), None);
+    }
+
     #[test]
     fn deduped_mc_matches_full_for_duplicate_crews() {
-        let a = CrewCandidate {
-            captain: "A".into(),
-            bridge: vec!["B".into(), "C".into()],
-            below_decks: vec!["D".into(), "E".into(), "F".into()],
-        };
+        let a = CrewCandidate::new(
+            "A",
+            vec!["B".into(), "C".into()],
+            vec!["D".into(), "E".into(), "F".into()],
+        );
         let pop = vec![a.clone(), a.clone()];
         let full = run_monte_carlo_parallel(
             "enterprise",
@@ -416,4 +655,66 @@ mod tests {
         assert_eq!(full[1].win_rate, deduped[1].win_rate);
         assert_eq!(full[0].stall_rate, deduped[0].stall_rate);
     }
+
+    #[test]
+    fn parallel_and_serial_statistics_are_bit_identical() {
+        let candidates: Vec<CrewCandidate> = ["A", "B", "C", "D", "E", "F", "G", "H"]
+            .iter()
+            .map(|captain| {
+                CrewCandidate::new(
+                    captain.to_string(),
+                    vec!["B".into(), "C".into()],
+                    vec!["D".into(), "E".into(), "F".into()],
+                )
+            })
+            .collect();
+        let serial = run_monte_carlo("enterprise", "swarm", &candidates, 32, 1234);
+        let parallel = run_monte_carlo_parallel("enterprise", "swarm", &candidates, 32, 1234);
+        assert_eq!(serial.len(), parallel.len());
+        for (s, p) in serial.iter().zip(&parallel) {
+            assert_eq!(s.candidate, p.candidate);
+            assert_eq!(s.win_rate.to_bits(), p.win_rate.to_bits());
+            assert_eq!(s.stall_rate.to_bits(), p.stall_rate.to_bits());
+            assert_eq!(s.loss_rate.to_bits(), p.loss_rate.to_bits());
+            assert_eq!(s.avg_hull_remaining.to_bits(), p.avg_hull_remaining.to_bits());
+        }
+    }
+
+    #[test]
+    fn outcome_rates_partition_fights_and_count_round_limit_draws_as_stalls() {
+        let candidate = CrewCandidate::new(
+            "A",
+            vec!["B".into(), "C".into()],
+            vec!["D".into(), "E".into(), "F".into()],
+        );
+        let shared = build_shared_scenario_data_standalone("enterprise", "swarm");
+        let input = scenario_to_combat_input_from_shared(&shared, &candidate, 9);
+        assert!(input.rounds < MAX_COMBAT_ROUNDS);
+        let result = &run_monte_carlo("enterprise", "swarm", &[candidate], 64, 9)[0];
+        let total = result.win_rate + result.stall_rate + result.loss_rate;
+        assert!((total - 1.0).abs() < 1e-12, "{result:?}");
+        assert!(result.stall_rate > 0.0, "{result:?}");
+        assert!(result.loss_rate <= 1.0 - result.survival_rate + 1e-12, "{result:?}");
+    }
+
+    #[test]
+    fn candidate_statistics_do_not_depend_on_batch_neighbours() {
+        let candidates: Vec<CrewCandidate> = ["A", "B", "C"]
+            .iter()
+            .map(|captain| {
+                CrewCandidate::new(
+                    captain.to_string(),
+                    vec!["B".into(), "C".into()],
+                    vec!["D".into(), "E".into(), "F".into()],
+                )
+            })
+            .collect();
+        let together = run_monte_carlo_parallel("enterprise", "swarm", &candidates, 16, 5);
+        let alone = run_monte_carlo_parallel("enterprise", "swarm", &candidates[2..], 16, 5);
+        assert_eq!(together[2].win_rate.to_bits(), alone[0].win_rate.to_bits());
+        assert_eq!(
+            together[2].avg_hull_remaining.to_bits(),
+            alone[0].avg_hull_remaining.to_bits()
+        );
+    }
 }
@@ -1,49 +1,654 @@
-use crate::optimizer::monte_carlo::SimulationResult;
-use serde::Serialize;
+use crate::combat::InitiativeMode;
+use crate::optimizer::monte_carlo::{normalize_lookup_key, split_name_and_tier, SimulationResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
 
-#[derive(Debug, Clone, Copy, Serialize)]
+/// Which side of the fight the player's crewed ship is on; selects the ranking objective.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioType {
+    /// Player attacks the hostile: rank by kill rate and hull kept.
+    #[default]
+    Offense,
+    /// Player holds out against an attacker (station defense, base raids): the attacker fires
+    /// first in each sub-round; rank by rounds survived and damage dealt before dying.
+    Defense,
+    /// Survey-ship protection: the ship mines for a few rounds before fighting back; rank by
+    /// the chance of surviving (or defeating) the attacker.
+    Mining,
+    /// Territory capture defense / takedowns: consecutive hostiles without a full repair;
+    /// rank by waves cleared.
+    Waves,
+    /// Grinding: rank by expected kills per hour of play ([kills_per_hour]), which orders crews
+    /// the same as event points or loot per hour against one hostile.
+    PointsPerHour,
+}
+
+impl ScenarioType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScenarioType::Offense => "offense",
+            ScenarioType::Defense => "defense",
+            ScenarioType::Mining => "mining",
+            ScenarioType::Waves => "waves",
+            ScenarioType::PointsPerHour => "points_per_hour",
+        }
+    }
+
+    /// Who fires first in each sub-round of the scenario's fights. In Defense the player's ship
+    /// is the one attacked, so the opponent's volley resolves first; the other scenarios keep
+    /// simultaneous fire.
+    pub fn initiative(self) -> InitiativeMode {
+        match self {
+            ScenarioType::Defense => InitiativeMode::DefenderFirst,
+            _ => InitiativeMode::Simultaneous,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct RankingScore {
     pub value: f32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RankedCrewResult {
+    /// Stable identifier for the crew's composition; see [crew_stable_id].
+    #[serde(default)]
+    pub crew_id: String,
     pub captain: String,
+    /// Occupied bridge seats; a partial crew lists fewer than the ship has
+    /// ([crate::optimizer::crew_generator::CrewSlots]).
     pub bridge: Vec<String>,
+    /// Occupied below-decks seats; fewer than the ship has for a partial crew.
     pub below_decks: Vec<String>,
     pub win_rate: f64,
     pub stall_rate: f64,
     pub loss_rate: f64,
     pub avg_hull_remaining: f64,
+    /// Mean share of the round limit the player's ship stayed alive (0–1).
+    #[serde(default)]
+    pub avg_survival_share: f64,
+    /// Mean share of the opponent's hull + shields destroyed (0–1).
+    #[serde(default)]
+    pub avg_damage_dealt: f64,
+    /// Share of fights the player's ship survives.
+    #[serde(default)]
+    pub survival_rate: f64,
+    /// Mean opponents destroyed per run in wave scenarios.
+    #[serde(default)]
+    pub avg_waves_cleared: f64,
+    /// Median rounds to kill over winning fights (None when the crew never won).
+    #[serde(default)]
+    pub median_rounds_to_kill: Option<u32>,
+    /// 95th-percentile rounds to kill over winning fights.
+    #[serde(default)]
+    pub p95_rounds_to_kill: Option<u32>,
+    /// Mean damage dealt per round fought.
+    #[serde(default)]
+    pub avg_damage_per_round: f64,
+    /// Mean share of full hull missing after the run (1.0 = destroyed).
+    #[serde(default)]
+    pub avg_hull_lost: f64,
+    #[serde(default)]
     pub score: RankingScore,
 }
 
+impl RankedCrewResult {
+    /// `crew_id`, or the id recomputed from the seats when the field was not saved.
+    pub fn stable_id(&self) -> String {
+        if self.crew_id.is_empty() {
+            crew_stable_id(&self.captain, &self.bridge, &self.below_decks)
+        } else {
+            self.crew_id.clone()
+        }
+    }
+}
+
+/// Canonical hash of a crew: normalized officer names (tier tags dropped) per seat, with bridge
+/// and below-decks order ignored. The same officers in the same seats get the same id across
+/// runs, strategies and data versions. 16 hex digits (FNV-1a 64).
+pub fn crew_stable_id(captain: &str, bridge: &[String], below_decks: &[String]) -> String {
+    let key = |name: &str| normalize_lookup_key(&split_name_and_tier(name).0);
+    let sorted = |names: &[String]| {
+        let mut keys: Vec<String> = names.iter().map(|n| key(n)).collect();
+        keys.sort();
+        keys.join(",")
+    };
+    let canonical = format!(
+        "c:{}|b:{}|d:{}",
+        key(captain),
+        sorted(bridge),
+        sorted(below_decks)
+    );
+    let hash = canonical.bytes().fold(0xcbf2_9ce4_8422_2325u64, |acc, b| {
+        (acc ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Real time spent per fight, for [ScenarioType::PointsPerHour]. The engine resolves rounds, not
+/// seconds, so these are play-time estimates: warp/targeting between hostiles, the fight itself,
+/// and the repair (or tow) after the ship is destroyed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FightTiming {
+    pub overhead_seconds: f64,
+    pub seconds_per_round: f64,
+    pub loss_penalty_seconds: f64,
+}
+
+/// Seconds between fights spent finding, warping to and engaging the next hostile.
+pub const DEFAULT_FIGHT_OVERHEAD_SECONDS: f64 = 20.0;
+/// Seconds per combat round.
+pub const DEFAULT_SECONDS_PER_ROUND: f64 = 2.0;
+/// Extra seconds lost when the ship is destroyed (repair with speedups, return to the system).
+pub const DEFAULT_LOSS_PENALTY_SECONDS: f64 = 120.0;
+
+impl Default for FightTiming {
+    fn default() -> Self {
+        Self {
+            overhead_seconds: DEFAULT_FIGHT_OVERHEAD_SECONDS,
+            seconds_per_round: DEFAULT_SECONDS_PER_ROUND,
+            loss_penalty_seconds: DEFAULT_LOSS_PENALTY_SECONDS,
+        }
+    }
+}
+
+/// Expected hostiles destroyed per hour: win_rate over the mean seconds per fight, where a fight
+/// lasts the median rounds to kill and losses add the penalty. 0 when the crew never wins.
+pub fn kills_per_hour(result: &SimulationResult, timing: &FightTiming) -> f64 {
+    let Some(rounds) = result.median_rounds_to_kill else {
+        return 0.0;
+    };
+    let seconds = timing.overhead_seconds
+        + f64::from(rounds) * timing.seconds_per_round
+        + result.loss_rate * timing.loss_penalty_seconds;
+    if seconds <= 0.0 {
+        return 0.0;
+    }
+    result.win_rate * 3600.0 / seconds
+}
+
+/// Objective scalar for one crew. Offense: win_rate * 0.8 + avg_hull_remaining * 0.2.
+/// Defense: avg_survival_share * 0.6 + avg_damage_dealt * 0.4. Mining: survival_rate.
+/// Waves: avg_waves_cleared (unbounded; a count, not a share). PointsPerHour: [kills_per_hour]
+/// with [FightTiming::default].
+pub fn objective_score(result: &SimulationResult, scenario_type: ScenarioType) -> f32 {
+    match scenario_type {
+        ScenarioType::Offense => (result.win_rate * 0.8 + result.avg_hull_remaining * 0.2) as f32,
+        ScenarioType::Defense => {
+            (result.avg_survival_share * 0.6 + result.avg_damage_dealt * 0.4) as f32
+        }
+        ScenarioType::Mining => result.survival_rate as f32,
+        ScenarioType::Waves => result.avg_waves_cleared as f32,
+        ScenarioType::PointsPerHour => kills_per_hour(result, &FightTiming::default()) as f32,
+    }
+}
+
+/// Rank for the default offensive objective; see [rank_results_for].
 pub fn rank_results(simulation_results: Vec<SimulationResult>) -> Vec<RankedCrewResult> {
-    let mut ranked: Vec<RankedCrewResult> = simulation_results
+    rank_results_for(simulation_results, ScenarioType::Offense)
+}
+
+/// Rank by [objective_score] for `scenario_type`, best first.
+pub fn rank_results_for(
+    simulation_results: Vec<SimulationResult>,
+    scenario_type: ScenarioType,
+) -> Vec<RankedCrewResult> {
+    let mut keyed: Vec<(RankKey, SimulationResult)> = simulation_results
         .into_iter()
-        .map(|result| {
-            let score = (result.win_rate * 0.8 + result.avg_hull_remaining * 0.2) as f32;
-            RankedCrewResult {
-                captain: result.candidate.captain,
-                bridge: result.candidate.bridge.clone(),
-                below_decks: result.candidate.below_decks.clone(),
-                win_rate: result.win_rate,
-                stall_rate: result.stall_rate,
-                loss_rate: result.loss_rate,
-                avg_hull_remaining: result.avg_hull_remaining,
-                score: RankingScore { value: score },
-            }
-        })
+        .map(|result| (RankKey::of(&result, scenario_type), result))
         .collect();
+    keyed.sort_by(|(left, _), (right, _)| left.best_first(right));
+    keyed
+        .into_iter()
+        .map(|(key, result)| RankedCrewResult {
+            crew_id: crew_stable_id(
+                &result.candidate.captain,
+                &result.candidate.bridge_names(),
+                &result.candidate.below_deck_names(),
+            ),
+            bridge: result.candidate.bridge_names(),
+            below_decks: result.candidate.below_deck_names(),
+            captain: result.candidate.captain,
+            win_rate: result.win_rate,
+            stall_rate: result.stall_rate,
+            loss_rate: result.loss_rate,
+            avg_hull_remaining: result.avg_hull_remaining,
+            avg_survival_share: result.avg_survival_share,
+            avg_damage_dealt: result.avg_damage_dealt,
+            survival_rate: result.survival_rate,
+            avg_waves_cleared: result.avg_waves_cleared,
+            median_rounds_to_kill: result.median_rounds_to_kill,
+            p95_rounds_to_kill: result.p95_rounds_to_kill,
+            avg_damage_per_round: result.avg_damage_per_round,
+            avg_hull_lost: result.avg_hull_lost,
+            score: RankingScore { value: key.score },
+        })
+        .collect()
+}
 
-    ranked.sort_by(|left, right| {
-        right
+/// What [rank_results_for] orders by: the objective score, then two scenario-specific
+/// tie-breaks.
+#[derive(Debug, Clone, Copy)]
+struct RankKey {
+    score: f32,
+    tie_breaks: [f64; 2],
+}
+
+impl RankKey {
+    fn of(result: &SimulationResult, scenario_type: ScenarioType) -> Self {
+        let tie_breaks = match scenario_type {
+            ScenarioType::Offense | ScenarioType::PointsPerHour => {
+                [result.win_rate, result.avg_hull_remaining]
+            }
+            ScenarioType::Defense => [result.avg_survival_share, result.avg_damage_dealt],
+            ScenarioType::Mining => [result.win_rate, result.avg_survival_share],
+            ScenarioType::Waves => [result.survival_rate, result.avg_damage_dealt],
+        };
+        Self {
+            score: objective_score(result, scenario_type),
+            tie_breaks,
+        }
+    }
+
+    /// `Less` when `self` ranks ahead of `other`.
+    fn best_first(&self, other: &Self) -> std::cmp::Ordering {
+        other
             .score
-            .value
-            .total_cmp(&left.score.value)
-            .then_with(|| right.win_rate.total_cmp(&left.win_rate))
-            .then_with(|| right.avg_hull_remaining.total_cmp(&left.avg_hull_remaining))
-    });
+            .total_cmp(&self.score)
+            .then_with(|| other.tie_breaks[0].total_cmp(&self.tie_breaks[0]))
+            .then_with(|| other.tie_breaks[1].total_cmp(&self.tie_breaks[1]))
+    }
+}
+
+/// Ranked crews an exhaustive run keeps when the request does not say.
+pub const DEFAULT_MAX_RESULTS: usize = 1_000;
+
+/// A result held by [TopResults]; `seq` is arrival order, so ties keep the order
+/// [rank_results_for] would give them.
+struct HeldResult {
+    key: RankKey,
+    seq: u64,
+    result: SimulationResult,
+}
+
+impl HeldResult {
+    fn best_first(&self, other: &Self) -> std::cmp::Ordering {
+        self.key.best_first(&other.key).then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl PartialEq for HeldResult {
+    fn eq(&self, other: &Self) -> bool {
+        self.best_first(other).is_eq()
+    }
+}
+
+impl Eq for HeldResult {}
+
+impl PartialOrd for HeldResult {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Worse results compare greater, so the heap's top is the first to evict.
+impl Ord for HeldResult {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.best_first(other)
+    }
+}
+
+/// Streaming top-K reducer: folds simulated batches into a bounded heap holding the best `k`
+/// results, so memory stays at `k` results plus one batch however many candidates are simulated.
+/// [TopResults::into_ranked] gives the same crews, in the same order, as [rank_results_for] over
+/// every result truncated to `k`.
+pub struct TopResults {
+    k: usize,
+    scenario_type: ScenarioType,
+    seen: u64,
+    heap: BinaryHeap<HeldResult>,
+}
+
+impl TopResults {
+    /// Keeps the best `k` (at least 1) results for `scenario_type`.
+    pub fn new(k: usize, scenario_type: ScenarioType) -> Self {
+        let k = k.max(1);
+        Self {
+            k,
+            scenario_type,
+            seen: 0,
+            heap: BinaryHeap::with_capacity(k.min(4096) + 1),
+        }
+    }
+
+    pub fn push(&mut self, result: SimulationResult) {
+        let held = HeldResult {
+            key: RankKey::of(&result, self.scenario_type),
+            seq: self.seen,
+            result,
+        };
+        self.seen += 1;
+        if self.heap.len() < self.k {
+            self.heap.push(held);
+        } else if let Some(mut worst) = self.heap.peek_mut() {
+            if held < *worst {
+                *worst = held;
+            }
+        }
+    }
+
+    /// Results currently held (at most `k`).
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The best `n` results so far, ranked best first; the reducer keeps running.
+    pub fn leaders(&self, n: usize) -> Vec<RankedCrewResult> {
+        let mut held: Vec<&HeldResult> = self.heap.iter().collect();
+        if n < held.len() {
+            held.select_nth_unstable_by(n, |left, right| left.best_first(right));
+            held.truncate(n);
+        }
+        held.sort_by(|left, right| left.best_first(right));
+        rank_results_for(held.into_iter().map(|h| h.result.clone()).collect(), self.scenario_type)
+    }
+
+    /// The kept results ranked best first.
+    pub fn into_ranked(self) -> Vec<RankedCrewResult> {
+        let held = self.heap.into_sorted_vec();
+        rank_results_for(held.into_iter().map(|h| h.result).collect(), self.scenario_type)
+    }
+}
+
+impl Extend<SimulationResult> for TopResults {
+    fn extend<I: IntoIterator<Item = SimulationResult>>(&mut self, batch: I) {
+        for result in batch {
+            self.push(result);
+        }
+    }
+}
+
+/// How a crew's position changed between two rankings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RankMovement {
+    Up,
+    Down,
+    /// Only in the second ranking.
+    New,
+    /// Only in the first ranking.
+    Dropped,
+}
+
+impl RankMovement {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RankMovement::Up => "up",
+            RankMovement::Down => "down",
+            RankMovement::New => "new",
+            RankMovement::Dropped => "dropped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RankChange {
+    pub crew_id: String,
+    pub captain: String,
+    pub bridge: Vec<String>,
+    pub below_decks: Vec<String>,
+    pub movement: RankMovement,
+    /// 1-based rank in the first ranking.
+    pub rank_before: Option<usize>,
+    /// 1-based rank in the second ranking.
+    pub rank_after: Option<usize>,
+    /// Positions gained (positive) or lost (negative); None for new and dropped crews.
+    pub rank_delta: Option<i64>,
+    pub win_rate_before: Option<f64>,
+    pub win_rate_after: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RankingDiff {
+    /// Whether the best crew is the same in both rankings.
+    pub top_unchanged: bool,
+    /// Crews present in both rankings at the same position.
+    pub unchanged: usize,
+    /// Moved, new and dropped crews, in second-ranking order (dropped crews last).
+    pub changes: Vec<RankChange>,
+}
+
+/// Compare two rankings of the same scenario (e.g. before and after a data update), matching
+/// crews by [RankedCrewResult::stable_id].
+pub fn diff_rankings(before: &[RankedCrewResult], after: &[RankedCrewResult]) -> RankingDiff {
+    let before_ranks: HashMap<String, (usize, &RankedCrewResult)> = before
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.stable_id(), (i + 1, r)))
+        .collect();
+    let after_ids: Vec<String> = after.iter().map(RankedCrewResult::stable_id).collect();
+
+    let change = |crew_id: String, crew: &RankedCrewResult, movement| RankChange {
+        crew_id,
+        captain: crew.captain.clone(),
+        bridge: crew.bridge.clone(),
+        below_decks: crew.below_decks.clone(),
+        movement,
+        rank_before: None,
+        rank_after: None,
+        rank_delta: None,
+        win_rate_before: None,
+        win_rate_after: None,
+    };
+
+    let mut unchanged = 0;
+    let mut changes = Vec::new();
+    for (i, (crew, crew_id)) in after.iter().zip(&after_ids).enumerate() {
+        let rank_after = i + 1;
+        match before_ranks.get(crew_id) {
+            Some(&(rank_before, _)) if rank_before == rank_after => unchanged += 1,
+            Some(&(rank_before, old)) => {
+                let movement = if rank_after < rank_before {
+                    RankMovement::Up
+                } else {
+                    RankMovement::Down
+                };
+                changes.push(RankChange {
+                    rank_before: Some(rank_before),
+                    rank_after: Some(rank_after),
+                    rank_delta: Some(rank_before as i64 - rank_after as i64),
+                    win_rate_before: Some(old.win_rate),
+                    win_rate_after: Some(crew.win_rate),
+                    ..change(crew_id.clone(), crew, movement)
+                });
+            }
+            None => changes.push(RankChange {
+                rank_after: Some(rank_after),
+                win_rate_after: Some(crew.win_rate),
+                ..change(crew_id.clone(), crew, RankMovement::New)
+            }),
+        }
+    }
+    for (i, crew) in before.iter().enumerate() {
+        let crew_id = crew.stable_id();
+        if !after_ids.contains(&crew_id) {
+            changes.push(RankChange {
+                rank_before: Some(i + 1),
+                win_rate_before: Some(crew.win_rate),
+                ..change(crew_id, crew, RankMovement::Dropped)
+            });
+        }
+    }
+
+    RankingDiff {
+        top_unchanged: match (before.first(), after.first()) {
+            (Some(b), Some(a)) => b.stable_id() == a.stable_id(),
+            (None, None) => true,
+            _ => false,
+        },
+        unchanged,
+        changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::crew_generator::CrewCandidate;
+
+    fn result(captain: &str, win_rate: f64, survival: f64, damage: f64) -> SimulationResult {
+        SimulationResult {
+            candidate: CrewCandidate::new(captain, Vec::new(), Vec::new()),
+            win_rate,
+            stall_rate: 0.0,
+            loss_rate: 1.0 - win_rate,
+            avg_hull_remaining: win_rate,
+            avg_survival_share: survival,
+            avg_damage_dealt: damage,
+            survival_rate: survival,
+            avg_waves_cleared: 0.0,
+            median_rounds_to_kill: None,
+            p95_rounds_to_kill: None,
+            avg_damage_per_round: 0.0,
+            avg_hull_lost: 1.0 - win_rate,
+        }
+    }
+
+    #[test]
+    fn defense_ranks_by_survival_and_damage_not_kill_rate() {
+        let results = vec![
+            result("glass_cannon", 0.4, 0.2, 0.9),
+            result("tank", 0.0, 1.0, 0.5),
+        ];
+        let offense = rank_results(results.clone());
+        assert_eq!(offense[0].captain, "glass_cannon");
+        let defense = rank_results_for(results, ScenarioType::Defense);
+        assert_eq!(defense[0].captain, "tank");
+        assert!((defense[0].score.value - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mining_ranks_by_survival_rate_then_win_rate() {
+        let results = vec![
+            result("fragile", 0.9, 0.5, 1.0),
+            result("sturdy", 0.1, 0.95, 0.3),
+            result("sturdy_killer", 0.3, 0.95, 0.6),
+        ];
+        let mining = rank_results_for(results, ScenarioType::Mining);
+        let order: Vec<&str> = mining.iter().map(|r| r.captain.as_str()).collect();
+        assert_eq!(order, ["sturdy_killer", "sturdy", "fragile"]);
+    }
+
+    #[test]
+    fn points_per_hour_prefers_fast_kills_over_a_slightly_higher_win_rate() {
+        let with_rounds = |captain: &str, win_rate: f64, rounds: Option<u32>| SimulationResult {
+            median_rounds_to_kill: rounds,
+            ..result(captain, win_rate, 1.0, 1.0)
+        };
+        let results = vec![
+            with_rounds("slow", 0.95, Some(10)),
+            with_rounds("fast", 0.9, Some(2)),
+            with_rounds("never", 0.0, None),
+        ];
+        assert_eq!(rank_results(results.clone())[0].captain, "slow");
+        let grind = rank_results_for(results, ScenarioType::PointsPerHour);
+        let order: Vec<&str> = grind.iter().map(|r| r.captain.as_str()).collect();
+        assert_eq!(order, ["fast", "slow", "never"]);
+        // 0.9 wins over 20 s overhead + 2 rounds * 2 s + 0.1 losses * 120 s.
+        assert!((grind[0].score.value - 90.0).abs() < 1e-3);
+        assert_eq!(grind[2].score.value, 0.0);
+    }
+
+    #[test]
+    fn top_results_match_the_full_ranking_truncated_including_ties() {
+        let results: Vec<SimulationResult> = (0..200)
+            .map(|i| {
+                let win_rate = f64::from((i * 37) % 23) / 23.0;
+                result(&format!("c{i}"), win_rate, 0.5, f64::from(i % 5) / 5.0)
+            })
+            .collect();
+        for scenario_type in [ScenarioType::Offense, ScenarioType::Defense] {
+            let full = rank_results_for(results.clone(), scenario_type);
+            let captains = |ranked: &[RankedCrewResult]| -> Vec<String> {
+                ranked.iter().map(|r| r.captain.clone()).collect()
+            };
+            let mut top = TopResults::new(17, scenario_type);
+            for batch in results.chunks(30) {
+                top.extend(batch.iter().cloned());
+            }
+            assert_eq!(captains(&top.leaders(5)), captains(&full[..5]));
+            let kept = top.into_ranked();
+            assert_eq!(captains(&kept), captains(&full[..17]));
+        }
+        assert_eq!(TopResults::new(0, ScenarioType::Offense).k, 1);
+    }
+
+    #[test]
+    fn crew_stable_id_ignores_seat_order_and_tier_tags() {
+        let bridge = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let a = crew_stable_id("Kirk", &bridge(&["Spock", "McCoy"]), &bridge(&["Uhura"]));
+        let b = crew_stable_id("kirk (T3)", &bridge(&["McCoy", "Spock"]), &bridge(&["Uhura"]));
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 16);
+        // Same officers, different seats.
+        let swapped = crew_stable_id("Spock", &bridge(&["Kirk", "McCoy"]), &bridge(&["Uhura"]));
+        assert_ne!(a, swapped);
+        let moved_below = crew_stable_id("Kirk", &bridge(&["Spock", "Uhura"]), &bridge(&["McCoy"]));
+        assert_ne!(a, moved_below);
+    }
+
+    #[test]
+    fn diff_rankings_reports_moves_new_and_dropped_crews() {
+        let before = rank_results(vec![
+            result("a", 0.9, 1.0, 1.0),
+            result("b", 0.8, 1.0, 1.0),
+            result("c", 0.7, 1.0, 1.0),
+        ]);
+        let after = rank_results(vec![
+            result("b", 0.95, 1.0, 1.0),
+            result("a", 0.9, 1.0, 1.0),
+            result("c", 0.7, 1.0, 1.0),
+            result("d", 0.1, 1.0, 1.0),
+        ]);
+        let diff = diff_rankings(&before, &after);
+        assert!(!diff.top_unchanged);
+        assert_eq!(diff.unchanged, 1);
+        let summary: Vec<(&str, RankMovement, Option<i64>)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.captain.as_str(), c.movement, c.rank_delta))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("b", RankMovement::Up, Some(1)),
+                ("a", RankMovement::Down, Some(-1)),
+                ("d", RankMovement::New, None),
+            ]
+        );
+
+        let diff = diff_rankings(&after, &before);
+        let dropped = diff.changes.last().unwrap();
+        assert_eq!((dropped.captain.as_str(), dropped.movement), ("d", RankMovement::Dropped));
+        assert_eq!(dropped.rank_before, Some(4));
+    }
 
-    ranked
+    #[test]
+    fn saved_recommendations_without_crew_id_still_match() {
+        let ranked = rank_results(vec![result("a", 0.9, 1.0, 1.0)]);
+        let json = r#"[{"captain":"a","bridge":[],"below_decks":[],"win_rate":0.5,
+            "stall_rate":0.0,"loss_rate":0.5,"avg_hull_remaining":0.5}]"#;
+        let saved: Vec<RankedCrewResult> = serde_json::from_str(json).unwrap();
+        assert!(saved[0].crew_id.is_empty());
+        let diff = diff_rankings(&saved, &ranked);
+        assert_eq!(diff.unchanged, 1);
+        assert!(diff.top_unchanged);
+    }
 }
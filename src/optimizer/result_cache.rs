@@ -0,0 +1,461 @@
+//! On-disk cache of optimizer and simulate results keyed by scenario hash.
+//!
+//! Identical requests (e.g. after a UI refresh) are answered from `cache/results/` instead of
+//! re-running Monte Carlo. Each entry records the data fingerprint it was computed against: a
+//! content hash of the static data files plus the profile's files. When that fingerprint no
+//! longer matches, the entry is removed and treated as a miss.
+//!
+//! The cache is off by default for library and CLI use; `serve` enables it at startup.
+//! `KOBAYASHI_RESULT_CACHE=0|1` overrides either default and `KOBAYASHI_RESULT_CACHE_DIR`
+//! relocates the entries.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{AttackerCondition, InitiativeMode};
+use crate::data::officer::UnknownOfficerMode;
+use crate::data::profile_index::{profile_data_dir, resolve_profile_id_for_api};
+use crate::optimizer::genetic::GeneticOverrides;
+use crate::optimizer::ranking::RankedCrewResult;
+use crate::optimizer::{OptimizationScenario, OptimizerStrategy};
+use crate::parallel::SEED_SCHEME_VERSION;
+
+pub const DEFAULT_RESULT_CACHE_DIR: &str = "cache/results";
+
+/// Namespace for ranked optimizer results.
+pub const OPTIMIZE_NAMESPACE: &str = "optimize";
+/// Namespace for `/api/simulate` payloads.
+pub const SIMULATE_NAMESPACE: &str = "simulate";
+
+/// Static data files and directories whose contents affect simulation results.
+const STATIC_DATA_PATHS: &[&str] = &[
+    "data/officers",
+    "data/ships_extended",
+    "data/hostiles",
+    "data/buildings",
+    "data/heuristics",
+    "data/forbidden_chaos_tech.json",
+    "data/research_catalog.json",
+    "data/syndicate_reputation.json",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn the process-wide cache on or off (ignored when `KOBAYASHI_RESULT_CACHE` is set).
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    match std::env::var("KOBAYASHI_RESULT_CACHE") {
+        Ok(v) => matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+        Err(_) => ENABLED.load(Ordering::Relaxed),
+    }
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// FNV-1a over `bytes`, continuing from `state`. Stable across processes and toolchains.
+fn fnv1a64(state: u64, bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(state, |h, b| (h ^ u64::from(*b)).wrapping_mul(FNV_PRIME))
+}
+
+/// Hash relative paths and contents of a file or directory tree (sorted, so order is stable).
+fn hash_path_into(root: &Path, path: &Path, state: u64) -> u64 {
+    let rel = path.strip_prefix(root).unwrap_or(path).to_string_lossy();
+    let mut h = fnv1a64(state, rel.as_bytes());
+    if path.is_dir() {
+        let Ok(entries) = fs::read_dir(path) else {
+            return h;
+        };
+        let mut children: Vec<PathBuf> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+        children.sort();
+        for child in children {
+            h = hash_path_into(root, &child, h);
+        }
+    } else if let Ok(bytes) = fs::read(path) {
+        h = fnv1a64(h, &bytes);
+    }
+    h
+}
+
+/// Content hash of one file or directory tree, hashed the same way as [static_data_fingerprint].
+pub fn path_content_hash(path: &Path) -> u64 {
+    hash_path_into(path, path, FNV_OFFSET)
+}
+
+static STATIC_FINGERPRINT: RwLock<Option<u64>> = RwLock::new(None);
+
+/// Content hash of [STATIC_DATA_PATHS]. Computed once and kept until
+/// [refresh_static_data_fingerprint], matching the lifetime of the loaded
+/// [crate::data::data_registry::DataRegistry].
+pub fn static_data_fingerprint() -> u64 {
+    if let Some(h) = *STATIC_FINGERPRINT.read().unwrap_or_else(|p| p.into_inner()) {
+        return h;
+    }
+    let h = STATIC_DATA_PATHS.iter().fold(FNV_OFFSET, |h, p| {
+        let path = Path::new(p);
+        hash_path_into(path, path, fnv1a64(h, p.as_bytes()))
+    });
+    *STATIC_FINGERPRINT.write().unwrap_or_else(|p| p.into_inner()) = Some(h);
+    h
+}
+
+/// Forget the cached [static_data_fingerprint]; called when the data files are reloaded.
+pub fn refresh_static_data_fingerprint() {
+    *STATIC_FINGERPRINT.write().unwrap_or_else(|p| p.into_inner()) = None;
+}
+
+/// Content hash of the profile's JSON files (profile, roster, imported research/buildings/...).
+/// Presets are excluded since they never feed into a simulation.
+pub fn profile_fingerprint(profile_id: Option<&str>) -> u64 {
+    let id = resolve_profile_id_for_api(profile_id);
+    let dir = profile_data_dir(&id);
+    let mut h = fnv1a64(FNV_OFFSET, id.as_bytes());
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return h;
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    for file in files {
+        h = hash_path_into(&dir, &file, h);
+    }
+    h
+}
+
+/// Combined fingerprint an entry must match to be served.
+pub fn data_fingerprint(profile_id: Option<&str>) -> String {
+    format!(
+        "{:016x}{:016x}",
+        static_data_fingerprint(),
+        profile_fingerprint(profile_id)
+    )
+}
+
+/// Hash of the request parameters plus engine identity (crate version, seed scheme).
+pub fn scenario_hash<K: Serialize>(namespace: &str, key: &K) -> String {
+    let body = serde_json::to_string(key).unwrap_or_default();
+    let mut h = fnv1a64(FNV_OFFSET, namespace.as_bytes());
+    h = fnv1a64(h, env!("CARGO_PKG_VERSION").as_bytes());
+    h = fnv1a64(h, &SEED_SCHEME_VERSION.to_le_bytes());
+    h = fnv1a64(h, body.as_bytes());
+    format!("{h:016x}")
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry<T> {
+    scenario_hash: String,
+    data_fingerprint: String,
+    value: T,
+}
+
+/// A cache directory. Use [ResultCache::from_env] for the process-wide location.
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    dir: PathBuf,
+}
+
+impl ResultCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        let dir = std::env::var("KOBAYASHI_RESULT_CACHE_DIR")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_RESULT_CACHE_DIR.to_string());
+        Self::new(dir)
+    }
+
+    fn entry_path(&self, namespace: &str, scenario_hash: &str) -> PathBuf {
+        self.dir.join(format!("{namespace}-{scenario_hash}.json"))
+    }
+
+    /// Cached value for `scenario_hash`, or `None` on miss. Entries computed against a
+    /// different `data_fingerprint` (or unreadable entries) are deleted.
+    pub fn get<T: DeserializeOwned>(
+        &self,
+        namespace: &str,
+        scenario_hash: &str,
+        data_fingerprint: &str,
+    ) -> Option<T> {
+        let path = self.entry_path(namespace, scenario_hash);
+        let raw = fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<CacheEntry<T>>(&raw) {
+            Ok(entry)
+                if entry.data_fingerprint == data_fingerprint
+                    && entry.scenario_hash == scenario_hash =>
+            {
+                Some(entry.value)
+            }
+            _ => {
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    /// Write an entry atomically (temp file + rename). Failures are ignored: the cache is advisory.
+    pub fn put<T: Serialize>(
+        &self,
+        namespace: &str,
+        scenario_hash: &str,
+        data_fingerprint: &str,
+        value: &T,
+    ) {
+        let entry = CacheEntry {
+            scenario_hash: scenario_hash.to_string(),
+            data_fingerprint: data_fingerprint.to_string(),
+            value,
+        };
+        let Ok(body) = serde_json::to_string(&entry) else {
+            return;
+        };
+        if fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        let path = self.entry_path(namespace, scenario_hash);
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        if fs::write(&tmp, body).is_ok() && fs::rename(&tmp, &path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+}
+
+/// Look up `key` in the process-wide cache. Returns `None` when the cache is disabled.
+pub fn lookup<K: Serialize, T: DeserializeOwned>(
+    namespace: &str,
+    key: &K,
+    profile_id: Option<&str>,
+) -> Option<T> {
+    if !is_enabled() {
+        return None;
+    }
+    ResultCache::from_env().get(
+        namespace,
+        &scenario_hash(namespace, key),
+        &data_fingerprint(profile_id),
+    )
+}
+
+/// Store `value` for `key` in the process-wide cache (no-op when disabled).
+pub fn store<K: Serialize, T: Serialize>(
+    namespace: &str,
+    key: &K,
+    profile_id: Option<&str>,
+    value: &T,
+) {
+    if !is_enabled() {
+        return;
+    }
+    ResultCache::from_env().put(
+        namespace,
+        &scenario_hash(namespace, key),
+        &data_fingerprint(profile_id),
+        value,
+    );
+}
+
+/// Which optimizer entry point produced the results (standalone paths ignore tier/level).
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OptimizePath {
+    Standalone,
+    Registry,
+}
+
+/// Seed crew as captain, bridge and below decks officer names.
+type SeedCrewKey<'a> = (&'a str, &'a [Option<String>], &'a [Option<String>]);
+
+/// Every [OptimizationScenario] input that can change the ranked output.
+#[derive(Debug, Serialize)]
+pub(crate) struct OptimizeCacheKey<'a> {
+    path: OptimizePath,
+    ship: &'a str,
+    hostile: &'a str,
+    ship_tier: Option<u32>,
+    ship_level: Option<u32>,
+    simulation_count: usize,
+    seed: u64,
+    max_candidates: Option<usize>,
+    strategy: &'static str,
+    only_below_decks_with_ability: bool,
+    seed_population: Vec<SeedCrewKey<'a>>,
+    profile_id: String,
+    tiered_scout_sims: Option<usize>,
+    tiered_top_k: Option<usize>,
+    scenario_type: &'static str,
+    mining_rounds: u32,
+    waves: u32,
+    wave_repair: f64,
+    max_results: Option<usize>,
+    genetic: &'a GeneticOverrides,
+    officer_source: Option<String>,
+    /// Skipped when empty so keys stored before exclusions existed still match.
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    excluded_officers: &'a [String],
+    /// Skipped for the default mode so keys stored before the mode existed still match.
+    #[serde(skip_serializing_if = "is_default_unknown_officers")]
+    unknown_officers: UnknownOfficerMode,
+    /// Skipped at full health so keys stored before the override existed still match.
+    #[serde(skip_serializing_if = "AttackerCondition::is_fresh")]
+    attacker_condition: AttackerCondition,
+    /// Skipped for simultaneous fire so only defense keys, whose fights changed, stop matching
+    /// results stored before scenarios set initiative.
+    #[serde(skip_serializing_if = "InitiativeMode::is_simultaneous")]
+    initiative: InitiativeMode,
+}
+
+fn is_default_unknown_officers(mode: &UnknownOfficerMode) -> bool {
+    *mode == UnknownOfficerMode::default()
+}
+
+impl<'a> OptimizeCacheKey<'a> {
+    pub(crate) fn new(scenario: &'a OptimizationScenario<'a>, path: OptimizePath) -> Self {
+        Self {
+            path,
+            ship: scenario.ship,
+            hostile: scenario.hostile,
+            ship_tier: scenario.ship_tier,
+            ship_level: scenario.ship_level,
+            simulation_count: scenario.simulation_count,
+            seed: scenario.seed,
+            max_candidates: scenario.max_candidates,
+            strategy: match scenario.strategy {
+                OptimizerStrategy::Exhaustive => "exhaustive",
+                OptimizerStrategy::Genetic => "genetic",
+                OptimizerStrategy::Tiered => "tiered",
+                OptimizerStrategy::TwoPhase => "two_phase",
+            },
+            only_below_decks_with_ability: scenario.only_below_decks_with_ability,
+            seed_population: scenario
+                .seed_population
+                .iter()
+                .map(|c| (c.captain.as_str(), c.bridge.as_slice(), c.below_decks.as_slice()))
+                .collect(),
+            profile_id: resolve_profile_id_for_api(scenario.profile_id),
+            tiered_scout_sims: scenario.tiered_scout_sims,
+            tiered_top_k: scenario.tiered_top_k,
+            scenario_type: scenario.scenario_type.as_str(),
+            mining_rounds: scenario.mining_rounds,
+            waves: scenario.waves,
+            wave_repair: scenario.wave_repair,
+            max_results: scenario.budgeted_max_results(),
+            genetic: &scenario.genetic,
+            officer_source: std::env::var("KOBAYASHI_OFFICER_SOURCE").ok(),
+            excluded_officers: scenario.excluded_officers,
+            unknown_officers: scenario.unknown_officers,
+            attacker_condition: scenario.attacker_condition,
+            initiative: scenario.scenario_type.initiative(),
+        }
+    }
+}
+
+/// Serve ranked results from the cache, or compute and store them. `compute` returns the
+/// results and whether they are complete (cancelled runs are never cached).
+pub(crate) fn cached_ranked_results<F>(
+    scenario: &OptimizationScenario<'_>,
+    path: OptimizePath,
+    compute: F,
+) -> Vec<RankedCrewResult>
+where
+    F: FnOnce() -> (Vec<RankedCrewResult>, bool),
+{
+    if !is_enabled() {
+        return compute().0;
+    }
+    let key = OptimizeCacheKey::new(scenario, path);
+    if let Some(hit) = lookup(OPTIMIZE_NAMESPACE, &key, scenario.profile_id) {
+        return hit;
+    }
+    let (results, complete) = compute();
+    if complete && !results.is_empty() {
+        store(OPTIMIZE_NAMESPACE, &key, scenario.profile_id, &results);
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> ResultCache {
+        let dir = std::env::temp_dir().join(format!(
+            "kobayashi-result-cache-{name}-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        ResultCache::new(dir)
+    }
+
+    #[test]
+    fn round_trips_value_for_matching_fingerprint() {
+        let cache = temp_cache("roundtrip");
+        cache.put("optimize", "abc", "fp1", &vec![1u32, 2, 3]);
+        let hit: Option<Vec<u32>> = cache.get("optimize", "abc", "fp1");
+        assert_eq!(hit, Some(vec![1, 2, 3]));
+        let miss: Option<Vec<u32>> = cache.get("optimize", "other", "fp1");
+        assert!(miss.is_none());
+    }
+
+    #[test]
+    fn stale_fingerprint_invalidates_entry() {
+        let cache = temp_cache("stale");
+        cache.put("simulate", "abc", "fp1", &"payload".to_string());
+        let stale: Option<String> = cache.get("simulate", "abc", "fp2");
+        assert!(stale.is_none());
+        assert!(!cache.entry_path("simulate", "abc").exists());
+    }
+
+    #[test]
+    fn scenario_hash_depends_on_every_key_field() {
+        let base = OptimizationScenario {
+            ship: "saladin",
+            hostile: "swarm",
+            ..OptimizationScenario::default()
+        };
+        let other_seed = OptimizationScenario {
+            seed: 1,
+            ..base.clone()
+        };
+        let h1 = scenario_hash(
+            OPTIMIZE_NAMESPACE,
+            &OptimizeCacheKey::new(&base, OptimizePath::Registry),
+        );
+        let h2 = scenario_hash(
+            OPTIMIZE_NAMESPACE,
+            &OptimizeCacheKey::new(&other_seed, OptimizePath::Registry),
+        );
+        let h3 = scenario_hash(
+            OPTIMIZE_NAMESPACE,
+            &OptimizeCacheKey::new(&base, OptimizePath::Standalone),
+        );
+        assert_ne!(h1, h2);
+        assert_ne!(h1, h3);
+        assert_eq!(
+            h1,
+            scenario_hash(
+                OPTIMIZE_NAMESPACE,
+                &OptimizeCacheKey::new(&base, OptimizePath::Registry)
+            )
+        );
+    }
+
+    #[test]
+    fn fnv_is_stable() {
+        assert_eq!(fnv1a64(FNV_OFFSET, b""), FNV_OFFSET);
+        assert_eq!(fnv1a64(FNV_OFFSET, b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -0,0 +1,164 @@
+//! What-if stat sliders: how a fixed crew's win rate responds to the player's ship gaining or
+//! losing a share of one stat (attack, pierce, crit chance, hull). Answers "should my next
+//! research be damage or survivability".
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::Combatant;
+use crate::data::data_registry::DataRegistry;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::run_monte_carlo_with_shared;
+use crate::optimizer::{scenario_shared_data_from_registry, OptimizationScenario};
+
+/// Ship stat scaled by the what-if sliders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackerStat {
+    /// Base attack and every weapon's attack.
+    Attack,
+    Pierce,
+    CritChance,
+    HullHealth,
+}
+
+impl AttackerStat {
+    pub const ALL: [AttackerStat; 4] = [
+        AttackerStat::Attack,
+        AttackerStat::Pierce,
+        AttackerStat::CritChance,
+        AttackerStat::HullHealth,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AttackerStat::Attack => "attack",
+            AttackerStat::Pierce => "pierce",
+            AttackerStat::CritChance => "crit_chance",
+            AttackerStat::HullHealth => "hull_health",
+        }
+    }
+
+    /// `combatant` with this stat multiplied by `factor` (crit chance stays within 0–1).
+    pub fn scale(self, mut combatant: Combatant, factor: f64) -> Combatant {
+        match self {
+            AttackerStat::Attack => {
+                combatant.attack *= factor;
+                for weapon in &mut combatant.weapons {
+                    weapon.attack *= factor;
+                }
+            }
+            AttackerStat::Pierce => combatant.pierce *= factor,
+            AttackerStat::CritChance => {
+                combatant.crit_chance = (combatant.crit_chance * factor).clamp(0.0, 1.0)
+            }
+            AttackerStat::HullHealth => combatant.hull_health *= factor,
+        }
+        combatant
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StatSensitivity {
+    pub stat: AttackerStat,
+    /// Win rate with the stat lowered by `delta_pct`.
+    pub win_rate_down: f64,
+    /// Win rate with the stat raised by `delta_pct`.
+    pub win_rate_up: f64,
+    /// Central difference: win-rate change per +1% of the stat.
+    pub win_rate_per_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StatSensitivityReport {
+    /// Relative perturbation applied to each stat (0.1 = ±10%).
+    pub delta_pct: f64,
+    pub baseline_win_rate: f64,
+    /// One entry per stat, largest `win_rate_per_pct` first.
+    pub stats: Vec<StatSensitivity>,
+}
+
+/// Re-simulate `crew` with each [AttackerStat] scaled by `1 ± delta_pct`, one at a time. All runs
+/// share the scenario seed, so differences come from the stat change rather than the rolls.
+pub fn attacker_stat_sensitivity(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    crew: &CrewCandidate,
+    delta_pct: f64,
+) -> StatSensitivityReport {
+    let shared = scenario_shared_data_from_registry(registry, scenario);
+    let crews = std::slice::from_ref(crew);
+    let win_rate = |stat: Option<(AttackerStat, f64)>| {
+        let shared = match stat {
+            Some((stat, factor)) => shared.clone().with_attacker_scale(stat, factor),
+            None => shared.clone(),
+        };
+        run_monte_carlo_with_shared(
+            shared,
+            crews,
+            scenario.simulation_count,
+            scenario.seed,
+            false,
+        )
+        .first()
+        .map_or(0.0, |r| r.win_rate)
+    };
+
+    let baseline_win_rate = win_rate(None);
+    let mut stats: Vec<StatSensitivity> = AttackerStat::ALL
+        .into_iter()
+        .map(|stat| {
+            let win_rate_down = win_rate(Some((stat, 1.0 - delta_pct)));
+            let win_rate_up = win_rate(Some((stat, 1.0 + delta_pct)));
+            StatSensitivity {
+                stat,
+                win_rate_down,
+                win_rate_up,
+                win_rate_per_pct: if delta_pct > 0.0 {
+                    (win_rate_up - win_rate_down) / (2.0 * delta_pct * 100.0)
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.win_rate_per_pct.total_cmp(&a.win_rate_per_pct));
+
+    StatSensitivityReport {
+        delta_pct,
+        baseline_win_rate,
+        stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_touches_only_the_selected_stat() {
+        let base = Combatant::builder("ship")
+            .attack(100.0)
+            .mitigation(0.1)
+            .pierce(0.2)
+            .crit_chance(0.6)
+            .crit_multiplier(1.5)
+            .shield_health(500.0)
+            .weapon(40.0, Some(2))
+            .build()
+            .unwrap();
+
+        let attack = AttackerStat::Attack.scale(base.clone(), 1.1);
+        assert!((attack.attack - 110.0).abs() < 1e-9);
+        assert!((attack.weapons[0].attack - 44.0).abs() < 1e-9);
+        assert_eq!(attack.hull_health, base.hull_health);
+
+        let crit = AttackerStat::CritChance.scale(base.clone(), 2.0);
+        assert_eq!(crit.crit_chance, 1.0);
+        assert_eq!(crit.attack, base.attack);
+
+        let hull = AttackerStat::HullHealth.scale(base.clone(), 0.9);
+        assert!((hull.hull_health - 900.0).abs() < 1e-9);
+        assert_eq!(hull.pierce, base.pierce);
+    }
+}
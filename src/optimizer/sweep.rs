@@ -0,0 +1,88 @@
+//! Hostile difficulty sweep: one ship + crew against every level of a hostile family (e.g. swarm
+//! 25–45), to find the highest level the crew still farms reliably.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::data::data_registry::DataRegistry;
+use crate::data::hostile::hostile_family_key;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::run_monte_carlo_with_shared;
+use crate::optimizer::{scenario_shared_data_from_registry, OptimizationScenario};
+
+/// Win rate a level must reach to count as farmable when the caller does not say.
+pub const DEFAULT_SWEEP_TARGET_WIN_RATE: f64 = 0.95;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SweepLevel {
+    pub level: u32,
+    /// Hostile simulated for this level (one per level; see [crate::data::hostile::HostileIndex::family_by_level]).
+    pub hostile_id: String,
+    pub win_rate: f64,
+    pub avg_hull_remaining: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SweepReport {
+    pub family: String,
+    pub target_win_rate: f64,
+    /// Highest swept level whose win rate reaches `target_win_rate`; None when no level does.
+    pub highest_level_at_target: Option<u32>,
+    /// Lowest level first.
+    pub levels: Vec<SweepLevel>,
+}
+
+/// Simulate `crew` against each level of `scenario.hostile`'s family within
+/// `min_level..=max_level`. None when the hostile is not in the hostile index.
+pub fn hostile_level_sweep(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    crew: &CrewCandidate,
+    min_level: u32,
+    max_level: u32,
+    target_win_rate: f64,
+) -> Option<SweepReport> {
+    let index = registry.hostile_index()?;
+    let anchor = index.hostiles.iter().find(|e| e.id == scenario.hostile)?;
+    let ladder = index.family_by_level(scenario.hostile)?;
+
+    let levels: Vec<SweepLevel> = ladder
+        .into_iter()
+        .filter(|entry| (min_level..=max_level).contains(&entry.level))
+        .map(|entry| {
+            let level_scenario = OptimizationScenario {
+                hostile: &entry.id,
+                ..scenario.clone()
+            };
+            let shared = scenario_shared_data_from_registry(registry, &level_scenario);
+            let result = run_monte_carlo_with_shared(
+                shared,
+                std::slice::from_ref(crew),
+                scenario.simulation_count,
+                scenario.seed,
+                false,
+            )
+            .into_iter()
+            .next();
+            SweepLevel {
+                level: entry.level,
+                hostile_id: entry.id.clone(),
+                win_rate: result.as_ref().map_or(0.0, |r| r.win_rate),
+                avg_hull_remaining: result.as_ref().map_or(0.0, |r| r.avg_hull_remaining),
+            }
+        })
+        .collect();
+
+    let highest_level_at_target = levels
+        .iter()
+        .filter(|l| l.win_rate >= target_win_rate)
+        .map(|l| l.level)
+        .max();
+
+    Some(SweepReport {
+        family: hostile_family_key(anchor),
+        target_win_rate,
+        highest_level_at_target,
+        levels,
+    })
+}
@@ -1,106 +1,218 @@
-//! Tiered simulation: two-pass strategy (cheap scouting pass → expensive confirmation).
-//! Phase 1: low sims per crew to prune; Phase 2: full Monte Carlo on top N only.
+//! Tiered simulation: coarse-to-fine pipeline.
+//! Stage 1: analytical expected damage for every candidate; Stage 2: low-sim Monte Carlo on the
+//! analytically strongest share; Stage 3: full Monte Carlo on the top K scouted crews.
 
-use crate::data::data_registry::DataRegistry;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::optimizer::analytical::expected_damage;
 use crate::optimizer::crew_generator::CrewCandidate;
-use crate::optimizer::monte_carlo::scenario::build_shared_scenario_data_from_registry;
+use crate::optimizer::monte_carlo::scenario::{
+    scenario_to_combat_input_from_shared, SharedScenarioData,
+};
 use crate::optimizer::monte_carlo::{
     run_monte_carlo_scout_phase_with_shared, run_monte_carlo_with_shared, SimulationResult,
 };
-use crate::optimizer::ranking::{rank_results, RankedCrewResult};
-use crate::parallel::{batch_ranges, monte_carlo_batch_count_for_candidates};
+use crate::optimizer::memory::{MemoryTracker, ResultBuffer};
+use crate::optimizer::ranking::{rank_results_for, RankedCrewResult, ScenarioType};
+use crate::parallel::AdaptiveBatches;
 
 /// Default sims per crew for the scouting pass.
 pub const DEFAULT_SCOUT_SIMS: usize = 500;
 /// Default number of top crews to run full confirmation.
-pub const DEFAULT_TOP_K: usize = 20;
-
-/// Runs tiered optimization with registry: scouting pass then full MC on top K.
-/// Progress callback: (crews_done, total_crews) where total_crews = num_candidates + top_k;
-/// during scouting, crews_done is 0..num_candidates; during confirmation, crews_done is num_candidates + 0..confirmed.
-/// Returns false to abort.
-pub fn run_tiered_with_registry_with_progress<F>(
-    registry: &DataRegistry,
-    ship: &str,
-    hostile: &str,
+pub const DEFAULT_TOP_K: usize = 50;
+/// Default share of analytically ranked candidates that reach the scouting pass.
+pub const DEFAULT_SCOUT_FRACTION: f64 = 0.10;
+
+/// Candidates scored per analytical progress update.
+const ANALYTICAL_PROGRESS_CHUNK: usize = 1024;
+
+/// Pipeline stage reported in progress updates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TieredStage {
+    Analytical,
+    Scout,
+    Confirm,
+}
+
+/// Progress within the current stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct StageProgress {
+    pub stage: TieredStage,
+    pub done: u32,
+    pub total: u32,
+}
+
+/// Tiered pipeline knobs. Stage 2 scouts `scout_fraction` of the candidates (never fewer than
+/// `top_k`); stage 3 confirms the best `top_k` scouted crews.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TieredConfig {
+    pub scout_fraction: f64,
+    pub scout_sims: usize,
+    pub top_k: usize,
+}
+
+impl Default for TieredConfig {
+    fn default() -> Self {
+        Self {
+            scout_fraction: DEFAULT_SCOUT_FRACTION,
+            scout_sims: DEFAULT_SCOUT_SIMS,
+            top_k: DEFAULT_TOP_K,
+        }
+    }
+}
+
+impl TieredConfig {
+    /// Candidates that reach the scouting pass out of `total`.
+    pub fn scout_count(&self, total: usize) -> usize {
+        let share = (total as f64 * self.scout_fraction.clamp(0.0, 1.0)).ceil() as usize;
+        share.max(self.top_k).min(total)
+    }
+
+    /// Crews confirmed with full sims out of `total` candidates.
+    pub fn confirm_count(&self, total: usize) -> usize {
+        self.top_k.max(1).min(self.scout_count(total))
+    }
+
+    /// Overall progress units: every candidate scored, every scouted crew, every confirmed crew.
+    pub fn total_work(&self, total: usize) -> usize {
+        total + self.scout_count(total) + self.confirm_count(total)
+    }
+}
+
+/// Runs the three-stage pipeline on pre-built scenario data; stages 2 and 3 rank by the
+/// `scenario_type` objective.
+/// `on_progress(done, total, stage)` reports overall units (see [TieredConfig::total_work]) plus
+/// progress within the current stage; returning false aborts and yields no results.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_tiered_pipeline<F>(
+    shared: SharedScenarioData,
     candidates: Vec<CrewCandidate>,
-    scout_sims: usize,
+    config: TieredConfig,
     full_sims: usize,
-    top_k: usize,
     seed: u64,
-    profile_id: Option<&str>,
+    scenario_type: ScenarioType,
+    memory: &MemoryTracker,
     mut on_progress: F,
 ) -> Vec<RankedCrewResult>
 where
-    F: FnMut(u32, u32) -> bool,
+    F: FnMut(u32, u32, StageProgress) -> bool,
 {
     let total_candidates = candidates.len();
     if total_candidates == 0 {
         return Vec::new();
     }
+    memory.hold_candidates(total_candidates);
+    let scout_n = config.scout_count(total_candidates);
+    let k = config.confirm_count(total_candidates);
+    let total_work = config.total_work(total_candidates) as u32;
+    let mut done = 0u32;
+    let mut report = |done: u32, stage: TieredStage, stage_done: usize, stage_total: usize| {
+        on_progress(
+            done,
+            total_work,
+            StageProgress {
+                stage,
+                done: stage_done as u32,
+                total: stage_total as u32,
+            },
+        )
+    };
 
-    let k = top_k.min(total_candidates);
-    let total_work = total_candidates + k;
-    if !on_progress(0, total_work as u32) {
+    // Stage 1: closed-form expected damage for every candidate, strongest first.
+    if !report(done, TieredStage::Analytical, 0, total_candidates) {
         return Vec::new();
     }
+    let mut scored: Vec<(f32, usize)> = Vec::with_capacity(total_candidates);
+    for (chunk_index, chunk) in candidates.chunks(ANALYTICAL_PROGRESS_CHUNK).enumerate() {
+        let offset = chunk_index * ANALYTICAL_PROGRESS_CHUNK;
+        scored.extend(chunk.iter().enumerate().map(|(i, candidate)| {
+            let input = scenario_to_combat_input_from_shared(&shared, candidate, seed);
+            (expected_damage(&input), offset + i)
+        }));
+        done += chunk.len() as u32;
+        if !report(done, TieredStage::Analytical, scored.len(), total_candidates) {
+            return Vec::new();
+        }
+    }
+    scored.sort_by(|(sa, ia), (sb, ib)| sb.total_cmp(sa).then_with(|| ia.cmp(ib)));
+    let mut candidates: Vec<Option<CrewCandidate>> = candidates.into_iter().map(Some).collect();
+    let scouts: Vec<CrewCandidate> = scored
+        .iter()
+        .take(scout_n)
+        .filter_map(|&(_, index)| candidates[index].take())
+        .collect();
 
-    // Build scenario once per phase; avoids reloading officers/profile for every batch.
-    let shared = build_shared_scenario_data_from_registry(
-        registry,
-        ship,
-        hostile,
-        None,
-        None,
-        profile_id,
-    );
-
-    // Phase 1: scouting with few sims (Wilson early-stop may reduce per-crew iterations).
-    let scout_sims = scout_sims.max(1);
-    let num_batches = monte_carlo_batch_count_for_candidates(total_candidates);
-    let ranges = batch_ranges(total_candidates, num_batches);
-    let mut scout_results: Vec<SimulationResult> = Vec::with_capacity(total_candidates);
-
-    for (start, end) in ranges {
-        let batch = &candidates[start..end];
-        let batch_results = run_monte_carlo_scout_phase_with_shared(
-            shared.clone(),
-            batch,
-            scout_sims,
-            seed,
-            true,
-        );
+    // Stage 2: scouting with few sims (Wilson early-stop may reduce per-crew iterations).
+    let scout_sims = config.scout_sims.max(1);
+    let mut batches = AdaptiveBatches::new(scout_n);
+    let mut scout_results = ResultBuffer::for_stage(scout_n, k, scenario_type, memory);
+    if !report(done, TieredStage::Scout, 0, scout_n) {
+        return Vec::new();
+    }
+    while let Some((start, end)) = batches.next_range() {
+        let batch_results = batches.measure(end - start, || {
+            run_monte_carlo_scout_phase_with_shared(
+                shared.clone(),
+                &scouts[start..end],
+                scout_sims,
+                seed,
+                true,
+            )
+        });
         scout_results.extend(batch_results);
-        if !on_progress(end as u32, total_work as u32) {
+        done += (end - start) as u32;
+        if !report(done, TieredStage::Scout, end, scout_n) {
             return Vec::new();
         }
     }
 
-    // Rank scouting results and take top K
-    let ranked_scout = rank_results(scout_results);
-    let top_crews: Vec<CrewCandidate> = ranked_scout
+    // Stage 3: full MC on the top K scouted crews.
+    let top_crews: Vec<CrewCandidate> = scout_results
+        .into_ranked(scenario_type)
         .into_iter()
         .take(k)
-        .map(|r| CrewCandidate {
-            captain: r.captain,
-            bridge: r.bridge,
-            below_decks: r.below_decks,
-        })
+        .map(|r| CrewCandidate::new(r.captain, r.bridge, r.below_decks))
         .collect();
-
-    // Phase 2: full MC on top K
     let full_sims = full_sims.max(1);
-    let confirmation_results = run_monte_carlo_with_shared(
-        shared,
-        &top_crews,
-        full_sims,
-        seed.wrapping_add(1), // distinct seed for confirmation phase
-        true,
-    );
-
-    if !on_progress(total_work as u32, total_work as u32) {
+    let mut batches = AdaptiveBatches::new(top_crews.len());
+    let mut confirmation_results: Vec<SimulationResult> = Vec::with_capacity(top_crews.len());
+    if !report(done, TieredStage::Confirm, 0, top_crews.len()) {
         return Vec::new();
     }
+    while let Some((start, end)) = batches.next_range() {
+        confirmation_results.extend(batches.measure(end - start, || {
+            run_monte_carlo_with_shared(
+                shared.clone(),
+                &top_crews[start..end],
+                full_sims,
+                seed.wrapping_add(1), // distinct seed for confirmation phase
+                true,
+            )
+        }));
+        done += (end - start) as u32;
+        if !report(done, TieredStage::Confirm, end, top_crews.len()) {
+            return Vec::new();
+        }
+    }
 
-    rank_results(confirmation_results)
+    memory.hold_results(confirmation_results.len());
+    rank_results_for(confirmation_results, scenario_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TieredConfig;
+
+    #[test]
+    fn scout_share_never_drops_below_top_k() {
+        let config = TieredConfig::default();
+        assert_eq!(config.scout_count(10_000), 1_000);
+        assert_eq!(config.scout_count(200), 50);
+        assert_eq!(config.scout_count(30), 30);
+        assert_eq!(config.confirm_count(10_000), 50);
+        assert_eq!(config.confirm_count(30), 30);
+        assert_eq!(config.total_work(200), 200 + 50 + 50);
+    }
 }
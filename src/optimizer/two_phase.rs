@@ -0,0 +1,252 @@
+//! Two-phase optimization: bridge by simulation, below decks by stats.
+//!
+//! Below-decks officers mostly contribute stats, so simulating every below-decks combination spends
+//! most of the sims on crews that differ only in stat sticks. Phase 1 simulates each captain +
+//! bridge prefix with the below-decks seats empty and keeps the best `top_bridges`. Phase 2 fills
+//! each kept prefix's below decks as a knapsack over the officers' stat value (one seat each):
+//! seats go, one at a time, to the officer adding the most [stat_strength], re-valued after every
+//! pick so overlapping buffs are not counted twice. Phase 3 runs full Monte Carlo on the filled
+//! crews. Candidates drop from `captains × bridge pairs × below-decks triples` to the prefixes.
+
+use std::collections::HashSet;
+
+use crate::optimizer::analytical::expected_damage;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::scenario::{
+    scenario_to_combat_input_from_shared, CombatSimulationInput, SharedScenarioData,
+};
+use crate::optimizer::monte_carlo::{
+    run_monte_carlo_scout_phase_with_shared, run_monte_carlo_with_shared, SimulationResult,
+};
+use crate::optimizer::memory::{MemoryTracker, ResultBuffer};
+use crate::optimizer::ranking::{rank_results_for, RankedCrewResult, ScenarioType};
+use crate::optimizer::tiered::{StageProgress, TieredStage};
+use crate::parallel::AdaptiveBatches;
+
+/// Default sims per captain + bridge prefix in phase 1.
+pub const DEFAULT_BRIDGE_SIMS: usize = 500;
+/// Default number of prefixes whose below decks are filled and confirmed.
+pub const DEFAULT_TOP_BRIDGES: usize = 50;
+
+/// Two-phase knobs: phase 1 runs `bridge_sims` per prefix; the best `top_bridges` prefixes get
+/// below decks and full sims.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwoPhaseConfig {
+    pub bridge_sims: usize,
+    pub top_bridges: usize,
+}
+
+impl Default for TwoPhaseConfig {
+    fn default() -> Self {
+        Self {
+            bridge_sims: DEFAULT_BRIDGE_SIMS,
+            top_bridges: DEFAULT_TOP_BRIDGES,
+        }
+    }
+}
+
+impl TwoPhaseConfig {
+    /// Prefixes kept after phase 1 out of `prefixes`.
+    pub fn kept_count(&self, prefixes: usize) -> usize {
+        self.top_bridges.max(1).min(prefixes)
+    }
+
+    /// Overall progress units: every prefix simulated, every kept prefix filled and confirmed.
+    pub fn total_work(&self, prefixes: usize) -> usize {
+        prefixes + 2 * self.kept_count(prefixes)
+    }
+}
+
+/// Stat value of a built fight for the below-decks knapsack: closed-form expected damage times the
+/// attacker's effective health (hull + shields), so attack and defense stat sticks both count.
+pub(crate) fn stat_strength(input: &CombatSimulationInput) -> f64 {
+    let health = (input.attacker.hull_health + input.attacker.shield_health).max(1.0);
+    f64::from(expected_damage(input)).max(f64::MIN_POSITIVE) * health
+}
+
+/// `prefix` with `seats` below-decks officers from `pool`, chosen greedily by [stat_strength].
+/// Officers already seated are skipped; ties go to the earlier pool entry. Seats the pool cannot
+/// fill stay empty.
+pub(crate) fn fill_below_decks(
+    shared: &SharedScenarioData,
+    prefix: &CrewCandidate,
+    pool: &[String],
+    seats: usize,
+    seed: u64,
+) -> CrewCandidate {
+    let seated: HashSet<&str> = prefix.officers().collect();
+    let mut available: Vec<&String> = pool
+        .iter()
+        .filter(|name| !seated.contains(name.as_str()))
+        .collect();
+    let mut crew = prefix.clone();
+    crew.below_decks = Vec::with_capacity(seats);
+    for _ in 0..seats {
+        let mut best: Option<(f64, usize)> = None;
+        for (i, name) in available.iter().enumerate() {
+            crew.below_decks.push(Some((*name).clone()));
+            let value = stat_strength(&scenario_to_combat_input_from_shared(shared, &crew, seed));
+            crew.below_decks.pop();
+            if best.is_none_or(|(top, _)| value > top) {
+                best = Some((value, i));
+            }
+        }
+        let Some((_, i)) = best else {
+            break;
+        };
+        crew.below_decks.push(Some(available.remove(i).clone()));
+    }
+    crew.below_decks.resize(seats, None);
+    crew
+}
+
+/// Runs the three phases on pre-built scenario data. `prefixes` are crews with empty below decks;
+/// `below_decks_pool` lists the officers phase 2 may seat, preferred first.
+/// `on_progress(done, total, stage)` reports overall units (see [TwoPhaseConfig::total_work]);
+/// phases report as [TieredStage::Scout], [TieredStage::Analytical] and [TieredStage::Confirm].
+/// Returning false aborts and yields no results.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_two_phase_pipeline<F>(
+    shared: SharedScenarioData,
+    prefixes: Vec<CrewCandidate>,
+    below_decks_pool: &[String],
+    config: TwoPhaseConfig,
+    full_sims: usize,
+    seed: u64,
+    scenario_type: ScenarioType,
+    memory: &MemoryTracker,
+    mut on_progress: F,
+) -> Vec<RankedCrewResult>
+where
+    F: FnMut(u32, u32, StageProgress) -> bool,
+{
+    let total_prefixes = prefixes.len();
+    if total_prefixes == 0 {
+        return Vec::new();
+    }
+    memory.hold_candidates(total_prefixes);
+    let kept = config.kept_count(total_prefixes);
+    let total_work = config.total_work(total_prefixes) as u32;
+    let mut done = 0u32;
+    let mut report = |done: u32, stage: TieredStage, stage_done: usize, stage_total: usize| {
+        on_progress(
+            done,
+            total_work,
+            StageProgress {
+                stage,
+                done: stage_done as u32,
+                total: stage_total as u32,
+            },
+        )
+    };
+
+    // Phase 1: captain + bridge prefixes with below decks empty.
+    let bridge_sims = config.bridge_sims.max(1);
+    let mut batches = AdaptiveBatches::new(total_prefixes);
+    let mut prefix_results =
+        ResultBuffer::for_stage(total_prefixes, kept, scenario_type, memory);
+    if !report(done, TieredStage::Scout, 0, total_prefixes) {
+        return Vec::new();
+    }
+    while let Some((start, end)) = batches.next_range() {
+        prefix_results.extend(batches.measure(end - start, || {
+            run_monte_carlo_scout_phase_with_shared(
+                shared.clone(),
+                &prefixes[start..end],
+                bridge_sims,
+                seed,
+                true,
+            )
+        }));
+        done += (end - start) as u32;
+        if !report(done, TieredStage::Scout, end, total_prefixes) {
+            return Vec::new();
+        }
+    }
+    let best_prefixes: Vec<CrewCandidate> = prefix_results
+        .into_ranked(scenario_type)
+        .into_iter()
+        .take(kept)
+        .map(|r| CrewCandidate::new(r.captain, r.bridge, Vec::new()))
+        .collect();
+
+    // Phase 2: below decks as a stat knapsack per kept prefix.
+    let seats = shared.crew_slots.below_decks;
+    let mut crews: Vec<CrewCandidate> = Vec::with_capacity(best_prefixes.len());
+    if !report(done, TieredStage::Analytical, 0, best_prefixes.len()) {
+        return Vec::new();
+    }
+    for prefix in &best_prefixes {
+        crews.push(fill_below_decks(&shared, prefix, below_decks_pool, seats, seed));
+        done += 1;
+        if !report(done, TieredStage::Analytical, crews.len(), best_prefixes.len()) {
+            return Vec::new();
+        }
+    }
+
+    // Phase 3: full MC on the filled crews.
+    let full_sims = full_sims.max(1);
+    let mut batches = AdaptiveBatches::new(crews.len());
+    let mut confirmation_results: Vec<SimulationResult> = Vec::with_capacity(crews.len());
+    if !report(done, TieredStage::Confirm, 0, crews.len()) {
+        return Vec::new();
+    }
+    while let Some((start, end)) = batches.next_range() {
+        confirmation_results.extend(batches.measure(end - start, || {
+            run_monte_carlo_with_shared(
+                shared.clone(),
+                &crews[start..end],
+                full_sims,
+                seed.wrapping_add(1), // distinct seed for confirmation phase
+                true,
+            )
+        }));
+        done += (end - start) as u32;
+        if !report(done, TieredStage::Confirm, end, crews.len()) {
+            return Vec::new();
+        }
+    }
+
+    memory.hold_results(confirmation_results.len());
+    rank_results_for(confirmation_results, scenario_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{fill_below_decks, TwoPhaseConfig};
+    use crate::data::profile::PlayerProfile;
+    use crate::lcars::ResolveOptions;
+    use crate::optimizer::crew_generator::CrewCandidate;
+    use crate::optimizer::monte_carlo::scenario::shared_scenario_data_from_records;
+
+    #[test]
+    fn work_counts_prefixes_then_kept_prefixes_twice() {
+        let config = TwoPhaseConfig::default();
+        assert_eq!(config.kept_count(1_000), 50);
+        assert_eq!(config.kept_count(20), 20);
+        assert_eq!(config.total_work(1_000), 1_000 + 2 * 50);
+    }
+
+    #[test]
+    fn below_decks_fill_skips_seated_officers_and_leaves_unfillable_seats_empty() {
+        let shared = shared_scenario_data_from_records(
+            "ship",
+            "hostile",
+            None,
+            None,
+            HashMap::new(),
+            PlayerProfile::default(),
+            None,
+            ResolveOptions::default(),
+        );
+        let prefix = CrewCandidate::new("Kirk", vec!["Spock".into(), "Uhura".into()], Vec::new());
+        let pool: Vec<String> = ["Spock", "Chapel", "Kirk", "Rand"].map(String::from).to_vec();
+
+        let crew = fill_below_decks(&shared, &prefix, &pool, 3, 1);
+        assert_eq!(crew.bridge_names(), vec!["Spock", "Uhura"]);
+        assert_eq!(crew.below_decks, vec![Some("Chapel".into()), Some("Rand".into()), None]);
+        assert!(crew.is_partial());
+    }
+}
@@ -0,0 +1,199 @@
+//! "What should I upgrade next": for one scenario, re-simulates the best crews with each of their
+//! officers promoted one tier and ranks officers by win-rate gain per shard spent.
+//!
+//! Current tiers come from the profile roster (`roster.imported.json`); officers without a roster
+//! tier count as tier 1. Before and after runs share seeds, so the gain reflects the tier alone.
+
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::data::data_registry::DataRegistry;
+use crate::data::officer::Officer;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::scenario::SharedScenarioData;
+use crate::optimizer::monte_carlo::{
+    normalize_lookup_key, run_monte_carlo_with_shared, split_name_and_tier, split_name_tags,
+    tag_name,
+};
+use crate::optimizer::{
+    optimize_scenario_with_registry, scenario_shared_data_from_registry, OptimizationScenario,
+};
+
+/// Best crews (by the scenario's ranking) re-simulated per upgrade when the caller does not say.
+pub const DEFAULT_UPGRADE_TOP_CREWS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OfficerUpgrade {
+    pub officer_id: String,
+    pub officer_name: String,
+    pub current_tier: u8,
+    pub next_tier: u8,
+    /// Shards to reach `next_tier`; None when the officer's rarity is unknown.
+    pub shard_cost: Option<u32>,
+    /// Best win rate among the top crews containing this officer, at the current tier.
+    pub win_rate_before: f64,
+    /// Same crews with this officer at `next_tier`.
+    pub win_rate_after: f64,
+    /// Gain in the scenario's best win rate (all top crews considered) from this upgrade alone.
+    pub win_rate_gain: f64,
+    /// `win_rate_gain` per 100 shards; None when `shard_cost` is unknown.
+    pub gain_per_100_shards: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct UpgradeReport {
+    /// Best win rate among the top crews at current roster tiers.
+    pub baseline_win_rate: f64,
+    pub crews_considered: usize,
+    /// Officers that can still be promoted, best value (gain per shard) first.
+    pub upgrades: Vec<OfficerUpgrade>,
+}
+
+/// Rank the officers in the scenario's `top_crews` best crews by marginal win-rate gain per shard.
+pub fn officer_upgrade_report(
+    registry: &DataRegistry,
+    scenario: &OptimizationScenario<'_>,
+    top_crews: usize,
+) -> UpgradeReport {
+    let crews: Vec<CrewCandidate> = optimize_scenario_with_registry(registry, scenario)
+        .into_iter()
+        .take(top_crews.max(1))
+        .map(|r| CrewCandidate::new(r.captain, r.bridge, r.below_decks))
+        .collect();
+    let shared = scenario_shared_data_from_registry(registry, scenario);
+    let roster_tiers = shared
+        .resolve_options
+        .officer_tiers
+        .clone()
+        .unwrap_or_default();
+
+    // Distinct officers in crew order, with their current roster tier.
+    let mut officers: Vec<(Officer, u8)> = Vec::new();
+    for name in crews.iter().flat_map(CrewCandidate::officers) {
+        let key = normalize_lookup_key(&split_name_and_tier(name).0);
+        let Some(officer) = shared.officer_index.get(&key) else {
+            continue;
+        };
+        if officers.iter().all(|(o, _)| o.id != officer.id) {
+            let tier = roster_tiers.get(&officer.id).copied().unwrap_or(1).max(1);
+            officers.push((officer.clone(), tier));
+        }
+    }
+    let tiers: HashMap<String, u8> = officers
+        .iter()
+        .map(|(o, tier)| (o.id.clone(), *tier))
+        .collect();
+
+    let baseline = simulate_win_rates(&shared, scenario, &crews, &tiers);
+    let baseline_win_rate = baseline.iter().copied().fold(0.0, f64::max);
+
+    let mut upgrades = Vec::new();
+    for (officer, tier) in &officers {
+        if *tier >= officer.max_tier() {
+            continue;
+        }
+        let next_tier = tier + 1;
+        let containing: Vec<usize> = (0..crews.len())
+            .filter(|&i| crew_contains(&crews[i], &shared, &officer.id))
+            .collect();
+        let subset: Vec<CrewCandidate> = containing.iter().map(|&i| crews[i].clone()).collect();
+
+        let mut promoted_tiers = tiers.clone();
+        promoted_tiers.insert(officer.id.clone(), next_tier);
+        let mut promoted = shared.clone();
+        let mut resolve_tiers = roster_tiers.clone();
+        resolve_tiers.insert(officer.id.clone(), next_tier);
+        promoted.resolve_options.officer_tiers = Some(resolve_tiers);
+        let after = simulate_win_rates(&promoted, scenario, &subset, &promoted_tiers);
+
+        let win_rate_before = containing.iter().map(|&i| baseline[i]).fold(0.0, f64::max);
+        let win_rate_after = after.iter().copied().fold(0.0, f64::max);
+        let best_after = (0..crews.len())
+            .filter(|i| !containing.contains(i))
+            .map(|i| baseline[i])
+            .chain(after.iter().copied())
+            .fold(0.0, f64::max);
+        let win_rate_gain = best_after - baseline_win_rate;
+        let shard_cost = officer.shards_to_reach_tier(next_tier);
+        upgrades.push(OfficerUpgrade {
+            officer_id: officer.id.clone(),
+            officer_name: officer.name.clone(),
+            current_tier: *tier,
+            next_tier,
+            shard_cost,
+            win_rate_before,
+            win_rate_after,
+            win_rate_gain,
+            gain_per_100_shards: shard_cost
+                .filter(|&cost| cost > 0)
+                .map(|cost| win_rate_gain * 100.0 / f64::from(cost)),
+        });
+    }
+
+    upgrades.sort_by(|a, b| {
+        let value = |u: &OfficerUpgrade| u.gain_per_100_shards.unwrap_or(f64::NEG_INFINITY);
+        value(b)
+            .total_cmp(&value(a))
+            .then(b.win_rate_gain.total_cmp(&a.win_rate_gain))
+            .then_with(|| a.officer_name.cmp(&b.officer_name))
+    });
+
+    UpgradeReport {
+        baseline_win_rate,
+        crews_considered: crews.len(),
+        upgrades,
+    }
+}
+
+fn crew_contains(crew: &CrewCandidate, shared: &SharedScenarioData, officer_id: &str) -> bool {
+    crew.officers().any(|name| {
+        shared
+            .officer_index
+            .get(&normalize_lookup_key(&split_name_and_tier(name).0))
+            .is_some_and(|o| o.id == officer_id)
+    })
+}
+
+/// Win rate per crew with every known officer tagged "(T#)" at `tiers` (level tags are kept).
+fn simulate_win_rates(
+    shared: &SharedScenarioData,
+    scenario: &OptimizationScenario<'_>,
+    crews: &[CrewCandidate],
+    tiers: &HashMap<String, u8>,
+) -> Vec<f64> {
+    if crews.is_empty() {
+        return Vec::new();
+    }
+    let tag = |name: &String| {
+        let (base, _, level) = split_name_tags(name);
+        let tier = shared
+            .officer_index
+            .get(&normalize_lookup_key(&base))
+            .and_then(|o| tiers.get(&o.id).copied());
+        tag_name(&base, tier, level)
+    };
+    let tagged: Vec<CrewCandidate> = crews
+        .iter()
+        .map(|c| CrewCandidate {
+            captain: tag(&c.captain),
+            bridge: c.bridge.iter().map(|seat| seat.as_ref().map(tag)).collect(),
+            below_decks: c
+                .below_decks
+                .iter()
+                .map(|seat| seat.as_ref().map(tag))
+                .collect(),
+        })
+        .collect();
+    run_monte_carlo_with_shared(
+        shared.clone(),
+        &tagged,
+        scenario.simulation_count,
+        scenario.seed,
+        true,
+    )
+    .into_iter()
+    .map(|r| r.win_rate)
+    .collect()
+}
@@ -3,6 +3,40 @@
 //! Splits work into batches for parallel execution or progress reporting.
 //! The Monte Carlo runner uses one candidate per parallel task; this module
 //! provides helpers for batch boundaries and optional chunked iteration.
+//!
+//! # Seed partitioning (stability contract)
+//!
+//! Monte Carlo randomness is partitioned by **candidate**, never by batch, thread, or core:
+//!
+//! 1. Each candidate gets a base seed derived only from the scenario seed, ship, hostile, and the
+//!    crew's officer names (see `scenario::stable_seed`).
+//! 2. Iteration `n` of that candidate uses [iteration_seed]`(base, n)`.
+//! 3. A candidate's iterations always run sequentially on one worker, so per-candidate
+//!    statistics are reduced in iteration order.
+//! 4. Parallel runs collect results in input order.
+//!
+//! Consequently batch boundaries from [batch_ranges], the Rayon worker count, and serial vs
+//! parallel execution only affect scheduling: per-candidate statistics are bit-identical.
+//! Any change to these rules must bump [SEED_SCHEME_VERSION] so persisted results keyed on it
+//! are not compared against incompatible runs.
+
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::scenario::build_shared_scenario_data_standalone;
+use crate::optimizer::monte_carlo::{run_monte_carlo_with_shared, SimulationResult};
+use crate::parallel::pool::{CancelToken, Cancelled, WorkerPool};
+use std::time::{Duration, Instant};
+
+/// Version of the seed-partitioning scheme documented above.
+/// v2: rolls come from the counter-based generator with one stream per round.
+/// v3: each round stream is split further into named roll channels.
+/// v4: each round stream is split per side (attacker/defender) before channels.
+pub const SEED_SCHEME_VERSION: u32 = 4;
+
+/// Seed for iteration `iteration` of a candidate whose base seed is `candidate_base_seed`.
+#[inline]
+pub fn iteration_seed(candidate_base_seed: u64, iteration: usize) -> u64 {
+    candidate_base_seed.wrapping_add(iteration as u64)
+}
 
 /// Target batch count for tiered / progress-chunked Monte Carlo: balances Rayon parallelism vs
 /// fewer `SharedScenarioData` clones and progress updates.
@@ -22,6 +56,7 @@ pub fn monte_carlo_batch_count_for_candidates(total: usize) -> usize {
 
 /// Split `total` items into up to `num_batches` ranges `[start, end)`.
 /// Batches are as equal in size as possible; later batches may be smaller.
+/// Boundaries never influence seeds (see the module-level stability contract).
 ///
 /// # Example
 /// ```
@@ -47,25 +82,165 @@ pub fn batch_ranges(total: usize, num_batches: usize) -> Vec<(usize, usize)> {
     ranges
 }
 
+/// Wall time [AdaptiveBatches] aims for per batch: short enough for prompt progress, cancellation
+/// and yielding (see [WorkerPool::checkpoint]), long enough that per-batch overhead stays small.
+pub const TARGET_BATCH_TIME: Duration = Duration::from_millis(250);
+
+/// Cost-aware batch boundaries over `total` items, handed out as `[start, end)` ranges.
+///
+/// The first batch is sized like [batch_ranges] with [monte_carlo_batch_count_for_candidates].
+/// It serves as a probe: its cost, reported through [AdaptiveBatches::record] (or
+/// [AdaptiveBatches::measure]), gives a per-item cost, and the next batch is sized to take about
+/// [TARGET_BATCH_TIME] at that cost. Each recorded batch updates the cost, so expensive candidates
+/// (many sims, long fights) get small batches and cheap ones large batches. A batch never holds
+/// fewer items than worker threads unless fewer remain. Until a cost is recorded, batches keep
+/// the probe size. Boundaries never influence seeds (see the module-level stability contract).
+///
+/// Only the simulation itself should be recorded: time spent yielding, reporting progress or
+/// generating the next candidates says nothing about how long the next batch will take.
+#[derive(Debug, Clone)]
+pub struct AdaptiveBatches {
+    total: usize,
+    start: usize,
+    probe_len: usize,
+    min_len: usize,
+    target: Duration,
+    /// Seconds per item of the batch recorded last.
+    per_item: Option<f64>,
+}
+
+impl AdaptiveBatches {
+    pub fn new(total: usize) -> Self {
+        let probe_len = batch_ranges(total, monte_carlo_batch_count_for_candidates(total))
+            .first()
+            .map_or(0, |&(start, end)| end - start);
+        Self {
+            total,
+            start: 0,
+            probe_len,
+            min_len: rayon::current_num_threads().max(1),
+            target: TARGET_BATCH_TIME,
+            per_item: None,
+        }
+    }
+
+    /// Aim for `target` per batch instead of [TARGET_BATCH_TIME].
+    pub fn with_target(mut self, target: Duration) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Length of the next batch when only `available` items are ready (e.g. a streamed chunk);
+    /// 0 once `total` items have been handed out.
+    pub fn next_len(&mut self, available: usize) -> usize {
+        let remaining = (self.total - self.start).min(available);
+        if remaining == 0 {
+            return 0;
+        }
+        let len = match self.per_item {
+            None => self.probe_len,
+            Some(per_item) if per_item > 0.0 => (self.target.as_secs_f64() / per_item) as usize,
+            Some(_) => remaining,
+        };
+        let len = len.max(self.min_len).min(remaining);
+        self.start += len;
+        len
+    }
+
+    /// Next `[start, end)` range, or `None` once every item has been handed out.
+    pub fn next_range(&mut self) -> Option<(usize, usize)> {
+        let start = self.start;
+        match self.next_len(usize::MAX) {
+            0 => None,
+            len => Some((start, start + len)),
+        }
+    }
+
+    /// Reports that a batch of `len` items took `elapsed`; later batches are sized from it.
+    pub fn record(&mut self, len: usize, elapsed: Duration) {
+        if len > 0 {
+            self.per_item = Some(elapsed.as_secs_f64() / len as f64);
+        }
+    }
+
+    /// Runs `run` for a batch of `len` items and [records](AdaptiveBatches::record) its time.
+    pub fn measure<T>(&mut self, len: usize, run: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let out = run();
+        self.record(len, started.elapsed());
+        out
+    }
+}
+
+/// `[start, end)` chunks for [WorkerPool::run_chunks], which reports each chunk's run time back
+/// through [ChunkSource::record].
+pub trait ChunkSource {
+    fn next_chunk(&mut self) -> Option<(usize, usize)>;
+
+    /// Called after each chunk with the time its work took (not the wait before it).
+    fn record(&mut self, _len: usize, _elapsed: Duration) {}
+}
+
+impl ChunkSource for AdaptiveBatches {
+    fn next_chunk(&mut self) -> Option<(usize, usize)> {
+        self.next_range()
+    }
+
+    fn record(&mut self, len: usize, elapsed: Duration) {
+        AdaptiveBatches::record(self, len, elapsed);
+    }
+}
+
+/// Fixed chunks, e.g. from [batch_ranges].
+impl ChunkSource for std::vec::IntoIter<(usize, usize)> {
+    fn next_chunk(&mut self) -> Option<(usize, usize)> {
+        self.next()
+    }
+}
+
 /// Run parallel Monte Carlo simulation distributed across workers.
-/// This is a convenience that calls [crate::optimizer::monte_carlo::run_monte_carlo_parallel]
-/// inside [crate::parallel::pool::WorkerPool::install] when a custom worker count is set.
+/// Candidates are split with [AdaptiveBatches] and each batch runs in parallel inside
+/// [WorkerPool::install]. Scenario data is built once and shared by all batches.
+/// Results are in input order and bit-identical to
+/// [crate::optimizer::monte_carlo::run_monte_carlo] for any pool size (see module docs).
 pub fn run_simulation_batches(
     ship: &str,
     hostile: &str,
-    candidates: &[crate::optimizer::crew_generator::CrewCandidate],
+    candidates: &[CrewCandidate],
     iterations: usize,
     seed: u64,
-    pool: &crate::parallel::pool::WorkerPool,
-) -> Vec<crate::optimizer::monte_carlo::SimulationResult> {
-    pool.install(|| {
-        crate::optimizer::monte_carlo::run_monte_carlo_parallel(
-            ship,
-            hostile,
-            candidates,
-            iterations,
-            seed,
-        )
+    pool: &WorkerPool,
+) -> Vec<SimulationResult> {
+    run_simulation_batches_cancellable(
+        ship,
+        hostile,
+        candidates,
+        iterations,
+        seed,
+        pool,
+        &CancelToken::new(),
+    )
+    .expect("batches without a cancel do not stop early")
+}
+
+/// Like [run_simulation_batches], but checks `cancel` (and yields to higher-priority work, see
+/// [WorkerPool::checkpoint]) between batches. A cancel stops the run before its next batch.
+pub fn run_simulation_batches_cancellable(
+    ship: &str,
+    hostile: &str,
+    candidates: &[CrewCandidate],
+    iterations: usize,
+    seed: u64,
+    pool: &WorkerPool,
+    cancel: &CancelToken,
+) -> Result<Vec<SimulationResult>, Cancelled> {
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let shared = build_shared_scenario_data_standalone(ship, hostile);
+    let batches = AdaptiveBatches::new(candidates.len());
+    pool.run_chunks(batches, cancel, |start, end| {
+        run_monte_carlo_with_shared(shared.clone(), &candidates[start..end], iterations, seed, true)
     })
 }
 
@@ -98,6 +273,143 @@ mod tests {
         assert!(batch_ranges(10, 0).is_empty());
     }
 
+    #[test]
+    fn adaptive_batches_cover_every_item_once() {
+        let total = 1000;
+        let mut batches = AdaptiveBatches::new(total);
+        let mut ranges = Vec::new();
+        while let Some((start, end)) = batches.next_range() {
+            batches.record(end - start, Duration::from_micros(50 * (end - start) as u64));
+            ranges.push((start, end));
+        }
+        let probe = batch_ranges(total, monte_carlo_batch_count_for_candidates(total))[0];
+        assert_eq!(ranges[0], probe);
+        assert!(ranges.windows(2).all(|w| w[0].1 == w[1].0), "{ranges:?}");
+        assert_eq!(ranges.last().map(|r| r.1), Some(total));
+        assert_eq!(AdaptiveBatches::new(0).next_range(), None);
+    }
+
+    #[test]
+    fn adaptive_batches_shrink_for_expensive_items() {
+        let target = Duration::from_millis(20);
+        let second_len = |probe_cost: Duration| {
+            let mut batches = AdaptiveBatches::new(100_000).with_target(target);
+            let (start, end) = batches.next_range().unwrap();
+            batches.record(end - start, probe_cost);
+            let (next_start, next_end) = batches.next_range().unwrap();
+            assert_eq!(next_start, end);
+            (end - start, next_end - next_start)
+        };
+        let (probe, slow) = second_len(Duration::from_millis(40));
+        let (_, fast) = second_len(Duration::from_millis(2));
+        // The probe took twice the target, so the next batch is at most half its size.
+        assert!(slow <= (probe / 2).max(rayon::current_num_threads()), "{probe} {slow}");
+        assert!(fast > slow, "{fast} {slow}");
+    }
+
+    #[test]
+    fn adaptive_batches_ignore_time_outside_measured_work() {
+        let next_after = |pause: Duration| {
+            let mut batches = AdaptiveBatches::new(100_000).with_target(Duration::from_millis(20));
+            let (start, end) = batches.next_range().unwrap();
+            batches.measure(end - start, || std::thread::sleep(Duration::from_millis(2)));
+            // E.g. a progress callback waiting on higher-priority work.
+            std::thread::sleep(pause);
+            batches.next_len(usize::MAX)
+        };
+        let paused = next_after(Duration::from_millis(60));
+        assert!(paused > next_after(Duration::ZERO) / 2, "{paused}");
+        assert!(paused > rayon::current_num_threads(), "{paused}");
+    }
+
+    #[test]
+    fn adaptive_batches_respect_available_items() {
+        let mut batches = AdaptiveBatches::new(10);
+        let first = batches.next_len(3);
+        assert!(first <= 3 && first > 0);
+        let mut handed_out = first;
+        while let len @ 1.. = batches.next_len(usize::MAX) {
+            handed_out += len;
+        }
+        assert_eq!(handed_out, 10);
+    }
+
+    #[test]
+    fn iteration_seed_is_offset_from_candidate_base() {
+        assert_eq!(iteration_seed(10, 0), 10);
+        assert_eq!(iteration_seed(10, 5), 15);
+        assert_eq!(iteration_seed(u64::MAX, 1), 0);
+    }
+
+    fn sample_candidates() -> Vec<CrewCandidate> {
+        ["A", "B", "C", "D", "E"]
+            .iter()
+            .map(|captain| {
+                CrewCandidate::new(
+                    captain.to_string(),
+                    vec!["X".into(), "Y".into()],
+                    vec!["P".into(), "Q".into(), "R".into()],
+                )
+            })
+            .collect()
+    }
+
+    fn stat_bits(results: &[SimulationResult]) -> Vec<(String, [u64; 4])> {
+        results
+            .iter()
+            .map(|r| {
+                (
+                    r.candidate.captain.clone(),
+                    [
+                        r.win_rate.to_bits(),
+                        r.stall_rate.to_bits(),
+                        r.loss_rate.to_bits(),
+                        r.avg_hull_remaining.to_bits(),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn batched_results_are_bit_identical_across_pool_sizes() {
+        let candidates = sample_candidates();
+        let serial = crate::optimizer::monte_carlo::run_monte_carlo(
+            "enterprise",
+            "swarm",
+            &candidates,
+            16,
+            9,
+        );
+        for workers in [1, 2, 3] {
+            let batched = run_simulation_batches(
+                "enterprise",
+                "swarm",
+                &candidates,
+                16,
+                9,
+                &WorkerPool::with_workers(workers),
+            );
+            assert_eq!(stat_bits(&serial), stat_bits(&batched), "workers={workers}");
+        }
+    }
+
+    #[test]
+    fn cancelled_simulation_batches_run_nothing() {
+        let cancel = CancelToken::new();
+        cancel.cancel();
+        let result = run_simulation_batches_cancellable(
+            "enterprise",
+            "swarm",
+            &sample_candidates(),
+            16,
+            9,
+            &WorkerPool::with_workers(1),
+            &cancel,
+        );
+        assert!(matches!(result, Err(Cancelled)));
+    }
+
     #[test]
     fn monte_carlo_batch_count_nonzero_for_work() {
         assert_eq!(super::monte_carlo_batch_count_for_candidates(0), 0);
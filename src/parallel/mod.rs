@@ -2,6 +2,13 @@ pub mod batch;
 pub mod pool;
 pub mod progress;
 
-pub use batch::{batch_ranges, monte_carlo_batch_count_for_candidates, run_simulation_batches};
-pub use pool::{init_from_env, WorkerPool};
-pub use progress::Progress;
+pub use batch::{
+    batch_ranges, iteration_seed, monte_carlo_batch_count_for_candidates, run_simulation_batches,
+    run_simulation_batches_cancellable, AdaptiveBatches, ChunkSource, SEED_SCHEME_VERSION,
+    TARGET_BATCH_TIME,
+};
+pub use pool::{init_from_env, CancelToken, Cancelled, Priority, PriorityGuard, WorkerPool};
+pub use progress::{
+    observed_sims_per_second, record_observed_sims_per_second, throughput_from_elapsed, Progress,
+    Throughput, ThroughputTracker,
+};
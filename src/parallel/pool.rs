@@ -5,9 +5,18 @@
 //!
 //! Use [WorkerPool::install] to run work on a separate fixed-size pool (e.g. helpers in
 //! [crate::parallel::batch]); when `workers` is 0, that uses the same global pool.
+//!
+//! Work is scheduled cooperatively: [WorkerPool::run_chunks] checks a [CancelToken] before each
+//! chunk and, while work of a higher [Priority] is in flight, waits instead of starting the next
+//! chunk. A running chunk is never interrupted, so cancellation and yielding take effect within
+//! one chunk.
 
 use rayon::ThreadPoolBuilder;
-use std::sync::Once;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Once};
+use std::time::{Duration, Instant};
+
+use crate::parallel::batch::ChunkSource;
 
 static INIT_PARALLEL_RUNTIME: Once = Once::new();
 
@@ -36,8 +45,10 @@ fn init_rayon_global_pool_from_env() {
     match ThreadPoolBuilder::new().num_threads(threads).build_global() {
         Ok(_) => {}
         Err(e) => {
-            eprintln!(
-                "kobayashi: KOBAYASHI_RAYON_THREADS={threads} not applied (Rayon global pool already initialized): {e}"
+            tracing::warn!(
+                threads,
+                error = %e,
+                "KOBAYASHI_RAYON_THREADS not applied (Rayon global pool already initialized)"
             );
         }
     }
@@ -63,11 +74,94 @@ fn init_low_priority_on_windows_from_env() {
     }
 }
 
+/// How long a yielding batch sleeps before checking again whether higher-priority work is done.
+const YIELD_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Scheduling priority of work run through a [WorkerPool]. Between chunks, work waits while any
+/// higher-priority work is in flight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Long-running jobs such as `/api/optimize/start`.
+    Background,
+    #[default]
+    Normal,
+    /// Calls a user is waiting on, such as `/api/simulate`.
+    Interactive,
+}
+
+/// Work in flight per [Priority], indexed by `priority as usize`.
+static IN_FLIGHT: [AtomicUsize; 3] = [const { AtomicUsize::new(0) }; 3];
+
+impl Priority {
+    /// Marks work at this priority as in flight until the returned guard is dropped.
+    pub fn enter(self) -> PriorityGuard {
+        IN_FLIGHT[self as usize].fetch_add(1, Ordering::SeqCst);
+        PriorityGuard(self)
+    }
+
+    /// Whether any work at exactly this priority is in flight.
+    pub fn is_in_flight(self) -> bool {
+        IN_FLIGHT[self as usize].load(Ordering::SeqCst) > 0
+    }
+
+    /// Whether any work of a higher priority is in flight.
+    pub fn is_outranked(self) -> bool {
+        IN_FLIGHT[self as usize + 1..]
+            .iter()
+            .any(|count| count.load(Ordering::SeqCst) > 0)
+    }
+}
+
+/// Keeps its [Priority] counted as in flight; see [Priority::enter].
+#[must_use = "the priority is only in flight while the guard is alive"]
+#[derive(Debug)]
+pub struct PriorityGuard(Priority);
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        IN_FLIGHT[self.0 as usize].fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Cooperative cancellation flag for a batch. Clones share the flag, so the job cancel endpoint
+/// can hold one while the worker checks another between chunks.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks the batch to stop before its next chunk. Idempotent.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returned by [WorkerPool::run_chunks] when its token was cancelled before every chunk ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
 /// Configures how many worker threads are used for parallel batch execution.
 #[derive(Debug, Clone, Copy)]
 pub struct WorkerPool {
     /// Number of worker threads. If 0, use Rayon default (num_cpus).
     pub workers: usize,
+    /// Priority of work run on this pool; see [WorkerPool::checkpoint].
+    pub priority: Priority,
 }
 
 impl Default for WorkerPool {
@@ -83,7 +177,10 @@ impl WorkerPool {
             .ok()
             .and_then(|s| s.parse::<usize>().ok())
             .unwrap_or(0);
-        Self { workers }
+        Self {
+            workers,
+            priority: Priority::Normal,
+        }
     }
 
     /// Use all available CPU cores (Rayon default).
@@ -92,17 +189,27 @@ impl WorkerPool {
     }
 
     /// Use exactly `n` worker threads.
-    pub fn with_workers(n: usize) -> Self {
-        Self { workers: n }
+    pub const fn with_workers(n: usize) -> Self {
+        Self {
+            workers: n,
+            priority: Priority::Normal,
+        }
+    }
+
+    /// Same pool, running its work at `priority`.
+    pub const fn with_priority(self, priority: Priority) -> Self {
+        Self { priority, ..self }
     }
 
     /// Run a closure on a thread pool with this worker count. If [workers](WorkerPool::workers) is 0,
     /// uses the global Rayon pool (all cores). Otherwise builds a temporary pool with that many threads.
+    /// The closure counts as in flight at [priority](WorkerPool::priority) until it returns.
     pub fn install<F, R>(&self, f: F) -> R
     where
         F: FnOnce() -> R + Send,
         R: Send,
     {
+        let _in_flight = self.priority.enter();
         if self.workers == 0 {
             f()
         } else {
@@ -113,4 +220,168 @@ impl WorkerPool {
             pool.install(f)
         }
     }
+
+    /// Call between chunks: waits while higher-priority work is in flight, then returns whether
+    /// the next chunk should run (false once `cancel` is set, including while waiting).
+    pub fn checkpoint(&self, cancel: &CancelToken) -> bool {
+        while !cancel.is_cancelled() && self.priority.is_outranked() {
+            std::thread::sleep(YIELD_POLL_INTERVAL);
+        }
+        !cancel.is_cancelled()
+    }
+
+    /// Run `run(start, end)` for each chunk in order inside [WorkerPool::install], passing
+    /// [WorkerPool::checkpoint] before each one. Only `run` is timed for [ChunkSource::record], so
+    /// waiting at a checkpoint never shrinks later chunks. Results are concatenated in chunk
+    /// order; once `cancel` is set no further chunk starts and the partial results are dropped.
+    pub fn run_chunks<C, T, F>(
+        &self,
+        mut chunks: C,
+        cancel: &CancelToken,
+        mut run: F,
+    ) -> Result<Vec<T>, Cancelled>
+    where
+        C: ChunkSource + Send,
+        F: FnMut(usize, usize) -> Vec<T> + Send,
+        T: Send,
+    {
+        self.install(|| {
+            let mut out = Vec::new();
+            while let Some((start, end)) = chunks.next_chunk() {
+                if !self.checkpoint(cancel) {
+                    return Err(Cancelled);
+                }
+                let started = Instant::now();
+                out.extend(run(start, end));
+                chunks.record(end - start, started.elapsed());
+            }
+            Ok(out)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parallel::batch_ranges;
+
+    #[test]
+    fn cancelled_batch_stops_within_one_chunk() {
+        let cancel = CancelToken::new();
+        let started = AtomicUsize::new(0);
+        let chunks = batch_ranges(100, 10).into_iter();
+        let result = WorkerPool::with_workers(2).run_chunks(chunks, &cancel, |start, end| {
+            // The cancel arrives while the fourth chunk is running.
+            if started.fetch_add(1, Ordering::SeqCst) == 3 {
+                cancel.clone().cancel();
+            }
+            (start..end).collect::<Vec<_>>()
+        });
+        assert_eq!(result, Err(Cancelled));
+        assert_eq!(started.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn cancel_from_another_thread_stops_the_next_chunk() {
+        let cancel = CancelToken::new();
+        let started = Arc::new(AtomicUsize::new(0));
+        let chunks = batch_ranges(1000, 1000).into_iter();
+        let worker = {
+            let (cancel, started) = (cancel.clone(), started.clone());
+            std::thread::spawn(move || {
+                WorkerPool::with_workers(1).run_chunks(chunks, &cancel, |start, _| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(2));
+                    vec![start]
+                })
+            })
+        };
+        while started.load(Ordering::SeqCst) < 3 {
+            std::thread::yield_now();
+        }
+        cancel.cancel();
+        let at_cancel = started.load(Ordering::SeqCst);
+        assert_eq!(worker.join().unwrap(), Err(Cancelled));
+        assert!(started.load(Ordering::SeqCst) <= at_cancel + 1);
+    }
+
+    #[test]
+    fn uncancelled_chunks_concatenate_in_order() {
+        let chunks = batch_ranges(10, 3).into_iter();
+        let out = WorkerPool::with_workers(0)
+            .run_chunks(chunks, &CancelToken::new(), |start, end| (start..end).collect())
+            .unwrap();
+        assert_eq!(out, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn background_work_waits_for_interactive_work_between_chunks() {
+        let interactive = Priority::Interactive.enter();
+        assert!(Priority::Background.is_outranked());
+        assert!(!Priority::Interactive.is_outranked());
+
+        let started = Arc::new(AtomicUsize::new(0));
+        let cancel = CancelToken::new();
+        let worker = {
+            let (started, cancel) = (started.clone(), cancel.clone());
+            std::thread::spawn(move || {
+                let pool = WorkerPool::with_workers(0).with_priority(Priority::Background);
+                pool.run_chunks(batch_ranges(4, 2).into_iter(), &cancel, |start, end| {
+                    started.fetch_add(1, Ordering::SeqCst);
+                    (start..end).collect::<Vec<_>>()
+                })
+            })
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(started.load(Ordering::SeqCst), 0);
+        drop(interactive);
+        assert_eq!(worker.join().unwrap(), Ok(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn background_pools_do_not_stall_each_other() {
+        let background = WorkerPool::with_workers(0).with_priority(Priority::Background);
+        // Another background job (e.g. a GA scoring a generation) stays in flight meanwhile.
+        let other = background.priority.enter();
+        assert!(Priority::Background.is_in_flight());
+
+        let done = Arc::new(AtomicBool::new(false));
+        let worker = {
+            let done = done.clone();
+            std::thread::spawn(move || {
+                let out = background.run_chunks(
+                    batch_ranges(4, 2).into_iter(),
+                    &CancelToken::new(),
+                    |start, end| (start..end).collect::<Vec<_>>(),
+                );
+                done.store(true, Ordering::SeqCst);
+                out
+            })
+        };
+        // Generous: unrelated tests may briefly run higher-priority work in parallel.
+        let deadline = std::time::Instant::now() + Duration::from_secs(30);
+        while !done.load(Ordering::SeqCst) && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        let finished = done.load(Ordering::SeqCst);
+        drop(other);
+        assert!(finished, "a background pool waited on another background pool");
+        assert_eq!(worker.join().unwrap(), Ok(vec![0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn cancel_releases_a_yielding_batch() {
+        let _interactive = Priority::Interactive.enter();
+        let cancel = CancelToken::new();
+        let worker = {
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                let pool = WorkerPool::with_workers(0).with_priority(Priority::Background);
+                pool.run_chunks(batch_ranges(4, 2).into_iter(), &cancel, |start, _| vec![start])
+            })
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        cancel.cancel();
+        assert_eq!(worker.join().unwrap(), Err(Cancelled));
+    }
 }
@@ -1,4 +1,119 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Progress {
     pub percent: f32,
 }
+
+/// Live throughput sample for a running job.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Throughput {
+    pub sims_per_second: f64,
+    /// Seconds until `done == total` at the current rate; `None` until the first unit completes.
+    pub eta_seconds: Option<f64>,
+}
+
+/// Tracks wall time against completed work units (crews, generations, ...). Each unit is
+/// weighted by `sims_per_unit` so different optimizer strategies report comparable rates.
+#[derive(Debug, Clone, Copy)]
+pub struct ThroughputTracker {
+    started: Instant,
+    sims_per_unit: u64,
+}
+
+impl ThroughputTracker {
+    pub fn new(sims_per_unit: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            sims_per_unit,
+        }
+    }
+
+    pub fn set_sims_per_unit(&mut self, sims_per_unit: u64) {
+        self.sims_per_unit = sims_per_unit;
+    }
+
+    pub fn sims_per_unit(&self) -> u64 {
+        self.sims_per_unit
+    }
+
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    pub fn sample(&self, done: u32, total: u32) -> Option<Throughput> {
+        throughput_from_elapsed(self.elapsed_seconds(), done, total, self.sims_per_unit)
+    }
+}
+
+/// Pure throughput/ETA computation. Returns `None` when nothing can be said yet
+/// (no elapsed time or no completed units).
+pub fn throughput_from_elapsed(
+    elapsed_seconds: f64,
+    done: u32,
+    total: u32,
+    sims_per_unit: u64,
+) -> Option<Throughput> {
+    if elapsed_seconds <= 0.0 || done == 0 {
+        return None;
+    }
+    let units_per_second = done as f64 / elapsed_seconds;
+    let remaining = total.saturating_sub(done) as f64;
+    Some(Throughput {
+        sims_per_second: units_per_second * sims_per_unit as f64,
+        eta_seconds: Some(remaining / units_per_second),
+    })
+}
+
+/// Most recent sims/second observed by a finished job (f64 bits; 0 = none yet).
+static LAST_OBSERVED_SIMS_PER_SECOND: AtomicU64 = AtomicU64::new(0);
+
+/// Record measured throughput so pre-run estimates can use live numbers instead of a constant.
+pub fn record_observed_sims_per_second(sims_per_second: f64) {
+    if sims_per_second.is_finite() && sims_per_second > 0.0 {
+        LAST_OBSERVED_SIMS_PER_SECOND.store(sims_per_second.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Last recorded throughput, if any job has finished in this process.
+pub fn observed_sims_per_second() -> Option<f64> {
+    let bits = LAST_OBSERVED_SIMS_PER_SECOND.load(Ordering::Relaxed);
+    if bits == 0 {
+        None
+    } else {
+        Some(f64::from_bits(bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throughput_is_none_before_first_unit() {
+        assert!(throughput_from_elapsed(1.0, 0, 10, 100).is_none());
+        assert!(throughput_from_elapsed(0.0, 5, 10, 100).is_none());
+    }
+
+    #[test]
+    fn throughput_scales_units_by_sims_and_projects_eta() {
+        let t = throughput_from_elapsed(2.0, 4, 10, 500).unwrap();
+        assert!((t.sims_per_second - 1000.0).abs() < 1e-9);
+        assert!((t.eta_seconds.unwrap() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn throughput_eta_is_zero_when_complete() {
+        let t = throughput_from_elapsed(3.0, 10, 10, 1).unwrap();
+        assert_eq!(t.eta_seconds, Some(0.0));
+    }
+
+    #[test]
+    fn observed_rate_ignores_non_positive_values() {
+        record_observed_sims_per_second(-1.0);
+        record_observed_sims_per_second(f64::NAN);
+        record_observed_sims_per_second(2500.0);
+        assert_eq!(observed_sims_per_second(), Some(2500.0));
+    }
+}
@@ -1,4 +1,5 @@
-//! Optional wall-clock logging for optimizer hot paths (set `KOBAYASHI_PERF_LOG=1`).
+//! Optional wall-clock logging for optimizer hot paths (set `KOBAYASHI_PERF_LOG=1`); events use
+//! the `kobayashi::perf` tracing target.
 
 use std::time::Instant;
 
@@ -16,6 +17,6 @@ pub(crate) fn perf_start() -> Option<Instant> {
 
 pub(crate) fn log_duration(label: &str, start: Option<Instant>) {
     if let Some(t0) = start {
-        eprintln!("[kobayashi-perf] {label}: {:?}", t0.elapsed());
+        tracing::info!(target: "kobayashi::perf", label, elapsed = ?t0.elapsed());
     }
 }
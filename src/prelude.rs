@@ -0,0 +1,24 @@
+//! Stable library surface for tools that embed the simulator: `use kobayashi::prelude::*;`.
+//!
+//! Everything re-exported here follows semver: a breaking change to these items bumps the minor
+//! version while the crate is 0.x (the major version from 1.0). Items reached only through the
+//! module tree (`kobayashi::combat::engine`, `kobayashi::optimizer::tiered`, ...) are engine and
+//! server internals and may change in any release.
+//!
+//! [Combatant], [SimulationConfig], [CrewCandidate] and [OptimizationScenario] are
+//! `#[non_exhaustive]`: adding a public field is not a breaking change, so build them with
+//! [Combatant::builder], [SimulationConfig::builder], [CrewCandidate::new] and
+//! [OptimizationScenario::new] (then set fields) rather than with struct literals.
+
+pub use crate::combat::{
+    simulate_combat, simulate_combat_streaming, CombatEvent, Combatant, CombatantBuilder,
+    CombatantError, CrewConfiguration, InitiativeMode, RngMode, SimulationConfig,
+    SimulationConfigBuilder, SimulationResult, TraceMode, WeaponStats,
+};
+pub use crate::optimizer::crew_generator::CrewCandidate;
+/// Monte Carlo statistics for one crew, as consumed by [rank_results] and [rank_results_for].
+pub use crate::optimizer::monte_carlo::SimulationResult as CrewSimulationResult;
+pub use crate::optimizer::ranking::{
+    rank_results, rank_results_for, RankedCrewResult, RankingScore, ScenarioType,
+};
+pub use crate::optimizer::{optimize_scenario, OptimizationScenario, OptimizerStrategy};
@@ -0,0 +1,248 @@
+//! Python module `kobayashi` (PyO3), for scripting sweeps from notebooks against the Rust engine.
+//!
+//! `simulate` and `optimize` take the same documents as [crate::embed] (a dict or a JSON string)
+//! and return plain dicts/lists; input errors raise `ValueError`. The GIL is released while the
+//! engine runs. Build the wheel with `maturin build --release` (see `pyproject.toml`).
+
+// The `#[pyfunction]` expansion of PyO3 0.22 converts `PyErr` into itself.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+
+use crate::combat::mitigation::{
+    self as formulas, MITIGATION_CEILING, MITIGATION_FLOOR, PIERCE_CAP,
+};
+use crate::combat::types::{AttackerStats, DefenderStats};
+use crate::data::hostile::ship_class_to_type;
+use crate::embed::{self, EmbedError};
+
+/// Serialize a dict (or pass through a str) with Python's `json` module.
+fn request_json(py: Python<'_>, request: &Bound<'_, PyAny>) -> PyResult<String> {
+    if let Ok(text) = request.downcast::<PyString>() {
+        return Ok(text.to_str()?.to_owned());
+    }
+    py.import_bound("json")?
+        .call_method1("dumps", (request,))?
+        .extract()
+}
+
+/// Run `entry` without the GIL and return `field` of the decoded response.
+fn call(
+    py: Python<'_>,
+    request: &Bound<'_, PyAny>,
+    entry: fn(&str) -> Result<String, EmbedError>,
+    field: &str,
+) -> PyResult<PyObject> {
+    let input = request_json(py, request)?;
+    let output = py
+        .allow_threads(|| entry(&input))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let response = py.import_bound("json")?.call_method1("loads", (output,))?;
+    Ok(response.get_item(field)?.unbind())
+}
+
+/// One fight; returns the `SimulationResult` as a dict. See [embed::SimulateInput].
+#[pyfunction]
+fn simulate(py: Python<'_>, request: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    call(py, request, embed::simulate_json, "result")
+}
+
+/// Ranked crews, best first, as a list of dicts. See [embed::OptimizeInput].
+#[pyfunction]
+fn optimize(py: Python<'_>, request: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+    call(py, request, embed::optimize_json, "results")
+}
+
+/// f(x) = 1 / (1 + 4^(1.1 - x)) for one defense/piercing pair.
+#[pyfunction]
+fn component_mitigation(defense: f64, piercing: f64) -> f64 {
+    formulas::component_mitigation(defense, piercing)
+}
+
+/// Total mitigation without the hostile clamp. `ship_class` is the defender's class.
+#[pyfunction]
+#[pyo3(signature = (*, armor, shield_deflection, dodge, armor_piercing, shield_piercing, accuracy, ship_class = "battleship"))]
+fn mitigation(
+    armor: f64,
+    shield_deflection: f64,
+    dodge: f64,
+    armor_piercing: f64,
+    shield_piercing: f64,
+    accuracy: f64,
+    ship_class: &str,
+) -> f64 {
+    formulas::mitigation(
+        DefenderStats {
+            armor,
+            shield_deflection,
+            dodge,
+        },
+        AttackerStats {
+            armor_piercing,
+            shield_piercing,
+            accuracy,
+        },
+        ship_class_to_type(ship_class),
+    )
+}
+
+/// Mitigation against a hostile: mystery factor, then clamped to [floor, ceiling].
+#[pyfunction]
+#[pyo3(signature = (*, armor, shield_deflection, dodge, armor_piercing, shield_piercing, accuracy, ship_class = "battleship", mystery = 0.0, floor = MITIGATION_FLOOR, ceiling = MITIGATION_CEILING))]
+#[allow(clippy::too_many_arguments)]
+fn mitigation_for_hostile(
+    armor: f64,
+    shield_deflection: f64,
+    dodge: f64,
+    armor_piercing: f64,
+    shield_piercing: f64,
+    accuracy: f64,
+    ship_class: &str,
+    mystery: f64,
+    floor: f64,
+    ceiling: f64,
+) -> f64 {
+    formulas::mitigation_for_hostile(
+        DefenderStats {
+            armor,
+            shield_deflection,
+            dodge,
+        },
+        AttackerStats {
+            armor_piercing,
+            shield_piercing,
+            accuracy,
+        },
+        ship_class_to_type(ship_class),
+        mystery,
+        floor,
+        ceiling,
+    )
+}
+
+/// Pierce damage-through bonus, in [0, PIERCE_CAP].
+#[pyfunction]
+#[pyo3(signature = (*, armor, shield_deflection, dodge, armor_piercing, shield_piercing, accuracy, ship_class = "battleship"))]
+fn pierce_damage_through_bonus(
+    armor: f64,
+    shield_deflection: f64,
+    dodge: f64,
+    armor_piercing: f64,
+    shield_piercing: f64,
+    accuracy: f64,
+    ship_class: &str,
+) -> f64 {
+    formulas::pierce_damage_through_bonus(
+        DefenderStats {
+            armor,
+            shield_deflection,
+            dodge,
+        },
+        AttackerStats {
+            armor_piercing,
+            shield_piercing,
+            accuracy,
+        },
+        ship_class_to_type(ship_class),
+    )
+}
+
+#[pymodule]
+fn kobayashi(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(simulate, m)?)?;
+    m.add_function(wrap_pyfunction!(optimize, m)?)?;
+    m.add_function(wrap_pyfunction!(component_mitigation, m)?)?;
+    m.add_function(wrap_pyfunction!(mitigation, m)?)?;
+    m.add_function(wrap_pyfunction!(mitigation_for_hostile, m)?)?;
+    m.add_function(wrap_pyfunction!(pierce_damage_through_bonus, m)?)?;
+    m.add("MITIGATION_FLOOR", MITIGATION_FLOOR)?;
+    m.add("MITIGATION_CEILING", MITIGATION_CEILING)?;
+    m.add("PIERCE_CAP", PIERCE_CAP)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    fn with_module<F: FnOnce(Python<'_>, &Bound<'_, PyModule>)>(f: F) {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let m = PyModule::new_bound(py, "kobayashi").unwrap();
+            kobayashi(&m).unwrap();
+            f(py, &m);
+        });
+    }
+
+    #[test]
+    fn simulate_accepts_a_dict_and_returns_the_result() {
+        with_module(|py, m| {
+            let combatant = |id: &str| {
+                serde_json::to_string(
+                    &crate::combat::Combatant::builder(id)
+                        .attack(50.0)
+                        .build()
+                        .unwrap(),
+                )
+                .unwrap()
+            };
+            let request = format!(
+                r#"{{"attacker": {}, "defender": {}, "seed": 1}}"#,
+                combatant("player"),
+                combatant("hostile")
+            );
+            let dict = py
+                .import_bound("json")
+                .unwrap()
+                .call_method1("loads", (request,))
+                .unwrap();
+            let result = m.getattr("simulate").unwrap().call1((dict,)).unwrap();
+            let result = result.downcast::<PyDict>().unwrap();
+            assert!(result.get_item("rounds_simulated").unwrap().is_some());
+        });
+    }
+
+    #[test]
+    fn bad_input_raises_value_error_and_helpers_match_rust() {
+        with_module(|py, m| {
+            let err = m.getattr("optimize").unwrap().call1(("{",)).unwrap_err();
+            assert!(err.is_instance_of::<PyValueError>(py));
+
+            let kwargs = PyDict::new_bound(py);
+            for (k, v) in [
+                ("armor", 100.0),
+                ("shield_deflection", 80.0),
+                ("dodge", 60.0),
+                ("armor_piercing", 90.0),
+                ("shield_piercing", 90.0),
+                ("accuracy", 90.0),
+            ] {
+                kwargs.set_item(k, v).unwrap();
+            }
+            let got: f64 = m
+                .getattr("mitigation")
+                .unwrap()
+                .call((), Some(&kwargs))
+                .unwrap()
+                .extract()
+                .unwrap();
+            let expected = formulas::mitigation(
+                DefenderStats {
+                    armor: 100.0,
+                    shield_deflection: 80.0,
+                    dodge: 60.0,
+                },
+                AttackerStats {
+                    armor_piercing: 90.0,
+                    shield_piercing: 90.0,
+                    accuracy: 90.0,
+                },
+                ship_class_to_type("battleship"),
+            );
+            assert_eq!(got, expected);
+        });
+    }
+}
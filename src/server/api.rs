@@ -1,49 +1,176 @@
+mod analysis;
+mod crew_code;
 mod execution;
 mod requests;
-
+mod scenario;
+mod simulate_cache;
+mod stream;
+
+pub use analysis::{
+    bracket_payload, officer_boosts_payload, officer_leaderboard_payload, officer_upgrades_payload,
+    stat_sensitivity_payload, sweep_payload, AnalyzeError, BracketRequest, BracketResponse,
+    OfficerBoostsRequest, OfficerBoostsResponse, OfficerLeaderboardRequest,
+    OfficerLeaderboardResponse, OfficerUpgradesRequest, OfficerUpgradesResponse,
+    StatSensitivityRequest, StatSensitivityResponse, SweepRequest, SweepResponse,
+    DEFAULT_STAT_DELTA_PCT, MAX_BRACKET_PRESETS, MAX_LEADERBOARD_SAMPLES, MAX_STAT_DELTA_PCT,
+    MAX_UPGRADE_TOP_CREWS,
+};
+pub use crew_code::{
+    crew_code_decode_payload, crew_code_encode_payload, CrewCodeDecodeResponse, CrewCodeRequest,
+    CrewCodeResponse,
+};
 pub use execution::{
-    cancel_job, get_job_status, run_optimize, start_optimize_job, CrewRecommendation,
-    OptimizeJobState, OptimizeResponse, OptimizeStartResponse, OptimizeStatusError,
-    OptimizeStatusResponse, ScenarioSummary,
+    cancel_job, check_seed_officers, get_job_status, run_optimize, running_job_count,
+    start_optimize_job, AutoStrategyReport,
+    CrewRecommendation, OfficerPoolSizes, OptimizeJobState, OptimizeResponse,
+    OptimizeStartResponse, OptimizeStatusError, OptimizeStatusResponse, ScenarioSummary,
+    StrategyAgreement,
+};
+pub use scenario::{
+    matrix_csv, run_matrix, simulate_matrix_payload, simulate_scenario_payload, MatrixFile,
+    MatrixResponse, MatrixRun, ScenarioFile, ScenarioProfileOverrides, ScenarioResponse,
+    ScenarioRules, MAX_MATRIX_RUNS,
+};
+pub use stream::{
+    simulate_stream, simulate_trace_payload, SimulateStreamFrame, TraceFilterRequest,
+    TraceRequest, TraceResponse, DEFAULT_TRACE_PAGE_SIZE, MAX_TRACE_PAGE_SIZE,
 };
 pub use requests::{
-    validate_request, OptimizePayloadError, OptimizeRequest, ValidationErrorResponse,
-    ValidationIssue, DEFAULT_SIMS, MAX_CANDIDATES, MAX_SIMS,
+    default_sims, max_candidates_limit, validate_request, OptimizePayloadError, OptimizeRequest,
+    UnknownIdIssue, UnknownIdsResponse, ValidationErrorResponse, ValidationIssue, DEFAULT_SIMS,
+    MAX_CANDIDATES, MAX_SIMS,
 };
 
-use crate::data::data_registry::DataRegistry;
+use crate::combat::mechanics::{MechanicSupport, MECHANICS};
+use crate::combat::AttackerCondition;
+use crate::data::data_registry::{DataFileVersions, DataRegistry};
+use crate::data::hostile::hostile_family_key;
 use crate::data::hostile_loca::resolve_hostile_display_name;
-use crate::data::loader::ship_tiers_levels;
+use crate::data::loader::{near_miss_ids, ship_tiers_levels, NEAR_MISS_LIMIT};
+use crate::data::officer::UnknownOfficerMode;
 use crate::data::heuristics::{list_heuristics_seeds, DEFAULT_HEURISTICS_DIR};
 use crate::data::import::{
     import_roster_csv_to, import_spocks_export_to, load_imported_roster_ids_unlocked_only,
 };
 use crate::data::building_summary::building_combat_summary_for_profile;
 use crate::data::research_summary::research_combat_summary_for_profile;
+use crate::data::power::{ship_power, ShipPower};
+use crate::data::profile::{PlayerBonuses, TargetContext};
 use crate::data::profile_index::{
     create_profile, delete_profile, effective_profile_id, load_profile_index,
     profile_path, PRESETS_SUBDIR, PROFILE_JSON, ROSTER_IMPORTED, SHIPS_IMPORTED,
 };
 use crate::data::import::load_imported_ships;
 use crate::optimizer::crew_generator::{
-    CandidateStrategy, CrewCandidate, CrewGenerator, BELOW_DECKS_SLOTS, BRIDGE_SLOTS,
+    CandidateStrategy, CrewCandidate, CrewGenerator, CrewSlots,
 };
 use crate::optimizer::monte_carlo::{
-    run_monte_carlo_with_registry, SimulationResult,
+    normalize_lookup_key, run_monte_carlo_with_shared, split_name_and_tier, SimulationResult,
+};
+use crate::optimizer::monte_carlo::scenario::load_effective_profile;
+use crate::optimizer::result_cache::{self, SIMULATE_NAMESPACE};
+use crate::optimizer::{
+    auto_strategy, scenario_shared_data_from_registry, OptimizationScenario, OptimizerStrategy,
 };
+use crate::parallel::{Priority, WorkerPool};
+use crate::server::notify::WebhookPolicy;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io::Write;
 use std::fmt;
 use std::sync::Arc;
 
-pub fn health_payload() -> Result<String, serde_json::Error> {
-    serde_json::to_string_pretty(&serde_json::json!({
-        "status": "ok",
-        "service": "kobayashi-api",
-        "version": env!("CARGO_PKG_VERSION")
-    }))
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DatasetHealth {
+    /// False when the dataset is missing or has no records; simulations need all three.
+    pub loaded: bool,
+    pub records: usize,
+    /// Same hash as `files` in `/api/data/version`.
+    pub content_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HealthDatasets {
+    pub officers: DatasetHealth,
+    pub ships: DatasetHealth,
+    pub hostiles: DatasetHealth,
+}
+
+impl HealthDatasets {
+    pub fn all_loaded(&self) -> bool {
+        self.officers.loaded && self.ships.loaded && self.hostiles.loaded
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct WorkerHealth {
+    /// Rayon threads available to simulations (`KOBAYASHI_RAYON_THREADS`).
+    pub simulation_threads: usize,
+    /// Concurrent CPU-heavy request slots (`KOBAYASHI_MAX_CONCURRENT_CPU_JOBS`).
+    pub cpu_job_slots: usize,
+    /// Slots currently taken by simulate/analyze/optimize requests or jobs.
+    pub cpu_jobs_running: usize,
+    /// `/api/optimize/start` jobs still running.
+    pub optimize_jobs_running: usize,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HealthResponse {
+    /// "ok", or "degraded" when a dataset failed to load.
+    pub status: &'static str,
+    pub service: &'static str,
+    pub version: &'static str,
+    /// Always true: the server is up and answering.
+    pub live: bool,
+    /// False when degraded; simulate and optimize requests would fail.
+    pub ready: bool,
+    pub datasets: HealthDatasets,
+    pub workers: WorkerHealth,
+}
+
+/// Liveness and readiness: dataset load state and hashes plus worker and job counts.
+/// `cpu_job_slots`/`cpu_jobs_running` come from the server's CPU job semaphore.
+pub fn health_report(
+    registry: &DataRegistry,
+    cpu_job_slots: usize,
+    cpu_jobs_running: usize,
+) -> HealthResponse {
+    let files = registry.file_versions();
+    let dataset = |loaded: bool, files: &crate::data::data_registry::DatasetFiles| DatasetHealth {
+        loaded: loaded && files.records > 0,
+        records: files.records,
+        content_hash: files.content_hash.clone(),
+    };
+    let datasets = HealthDatasets {
+        officers: dataset(true, &files.officers),
+        ships: dataset(registry.ship_index().is_some(), &files.ships),
+        hostiles: dataset(registry.hostile_index().is_some(), &files.hostiles),
+    };
+    let ready = datasets.all_loaded();
+    HealthResponse {
+        status: if ready { "ok" } else { "degraded" },
+        service: "kobayashi-api",
+        version: env!("CARGO_PKG_VERSION"),
+        live: true,
+        ready,
+        datasets,
+        workers: WorkerHealth {
+            simulation_threads: rayon::current_num_threads(),
+            cpu_job_slots,
+            cpu_jobs_running,
+            optimize_jobs_running: running_job_count(),
+        },
+    }
+}
+
+pub fn health_payload(
+    registry: &DataRegistry,
+    cpu_job_slots: usize,
+    cpu_jobs_running: usize,
+) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&health_report(registry, cpu_job_slots, cpu_jobs_running))
 }
 
 /// Parse query string for owned_only=1
@@ -54,12 +181,23 @@ fn parse_owned_only(path: &str) -> bool {
         .any(|p| p.trim().eq_ignore_ascii_case("owned_only=1") || p.trim().eq_ignore_ascii_case("owned_only=true"))
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct OfficerListItem {
     pub id: String,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub slot: Option<String>,
+    /// common / uncommon / rare / epic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rarity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub faction: Option<String>,
+    /// Crew group name (e.g. "TNG CREW").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Officers with the same synergy group boost each other in game.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synergy_group: Option<u32>,
 }
 
 pub fn officers_payload(
@@ -86,12 +224,16 @@ pub fn officers_payload(
             id: o.id.clone(),
             name: o.name.clone(),
             slot: o.slot.clone(),
+            rarity: o.rarity.clone(),
+            faction: o.faction.clone(),
+            group: o.group.clone(),
+            synergy_group: o.synergy_group,
         })
         .collect();
     serde_json::to_string_pretty(&serde_json::json!({ "officers": list }))
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct ShipListItem {
     pub id: String,
     pub ship_name: String,
@@ -102,6 +244,10 @@ pub struct ShipListItem {
     /// From roster when owned_only: level of first roster entry for this ship.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub level: Option<u32>,
+    /// From roster when owned_only: ship power at that tier and level with the profile's
+    /// bonuses ([ShipPower::total]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power: Option<f64>,
 }
 
 const HULL_ID_REGISTRY_PATH: &str = "data/hull_id_registry.json";
@@ -129,10 +275,12 @@ fn load_hull_id_registry() -> HashMap<i64, String> {
     out
 }
 
+/// `query` (`?q=`) keeps only ships matching it by id or name, best match first.
 pub fn ships_payload(
     registry: &DataRegistry,
     owned_only: bool,
     profile_id: Option<&str>,
+    query: Option<&str>,
 ) -> Result<String, serde_json::Error> {
     let idx = match registry.ship_index() {
         Some(i) => i,
@@ -181,6 +329,11 @@ pub fn ships_payload(
     } else {
         (None, std::collections::HashMap::new())
     };
+    let bonuses = if roster_tier_level.is_empty() {
+        HashMap::new()
+    } else {
+        load_effective_profile(registry, &resolve_profile_id(profile_id)).bonuses
+    };
 
     let list: Vec<ShipListItem> = idx
         .ships
@@ -196,19 +349,42 @@ pub fn ships_payload(
                 .copied()
                 .map(|(t, l)| (Some(t), Some(l)))
                 .unwrap_or((None, None));
+            let power = tier
+                .and_then(|_| registry.resolve_ship_with_tier_level(&e.id, tier, level))
+                .map(|record| ship_power(&record, &bonuses).total);
             ShipListItem {
                 id: e.id.clone(),
                 ship_name: e.ship_name.clone(),
                 ship_class: e.ship_class.clone(),
                 tier,
                 level,
+                power,
             }
         })
         .collect();
+    let list = match query {
+        Some(q) => ranked_by_search(list, &registry.search_ships(q, usize::MAX), |s| &s.id),
+        None => list,
+    };
 
     serde_json::to_string_pretty(&serde_json::json!({ "ships": list }))
 }
 
+/// `items` reordered as `ranked` (ids, best first), dropping items it does not list.
+fn ranked_by_search<T>(items: Vec<T>, ranked: &[String], id: impl Fn(&T) -> &str) -> Vec<T> {
+    let rank: HashMap<&str, usize> = ranked
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    let mut items: Vec<(usize, T)> = items
+        .into_iter()
+        .filter_map(|item| rank.get(id(&item)).map(|&i| (i, item)))
+        .collect();
+    items.sort_by_key(|(i, _)| *i);
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
 /// Default tier/level options when extended ship data is missing (e.g. no data/ships_extended).
 const DEFAULT_TIERS: &[u32] = &[1];
 const DEFAULT_LEVELS: &[u32] = &[1, 10, 20, 30, 40, 50, 60];
@@ -229,7 +405,83 @@ pub fn ship_tiers_levels_payload(ship_id: &str) -> Result<String, serde_json::Er
     serde_json::to_string_pretty(&serde_json::json!({ "tiers": tiers, "levels": levels }))
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TierCrewSlots {
+    pub tier: u32,
+    pub crew_slots: CrewSlots,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ShipDetailResponse {
+    pub id: String,
+    pub ship_name: String,
+    pub ship_class: String,
+    /// Tier and level `crew_slots` was resolved at (1 when not requested).
+    pub tier: u32,
+    pub level: u32,
+    /// Seats besides the captain's at `tier`; crews for this ship fill exactly these.
+    pub crew_slots: CrewSlots,
+    /// Seats at every tier the ship data lists, lowest tier first.
+    pub slots_by_tier: Vec<TierCrewSlots>,
+    /// Power at `tier` and `level` with the profile's bonuses, before any crew is seated.
+    pub power: ShipPower,
+}
+
+#[derive(Debug)]
+pub enum ShipDetailError {
+    NotFound,
+    Serialize(serde_json::Error),
+}
+
+impl fmt::Display for ShipDetailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "Ship not found"),
+            Self::Serialize(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ShipDetailError {}
+
+/// GET /api/ships/{id}: the ship at `tier` / `level` with its crew seats.
+pub fn ship_detail_payload(
+    registry: &DataRegistry,
+    ship_id: &str,
+    tier: Option<u32>,
+    level: Option<u32>,
+    profile_id: Option<&str>,
+) -> Result<String, ShipDetailError> {
+    let record = registry
+        .resolve_ship_with_tier_level(ship_id, tier, level)
+        .ok_or(ShipDetailError::NotFound)?;
+    let slots_by_tier = ship_tiers_levels(ship_id)
+        .map(|(tiers, _)| tiers)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|tier| {
+            let record = registry.resolve_ship_with_tier_level(ship_id, Some(tier), None)?;
+            Some(TierCrewSlots {
+                tier,
+                crew_slots: CrewSlots::for_ship(&record),
+            })
+        })
+        .collect();
+    let profile = load_effective_profile(registry, &resolve_profile_id(profile_id));
+    let response = ShipDetailResponse {
+        power: ship_power(&record, &profile.bonuses),
+        crew_slots: CrewSlots::for_ship(&record),
+        id: record.id,
+        ship_name: record.ship_name,
+        ship_class: record.ship_class,
+        tier: tier.unwrap_or(1).max(1),
+        level: level.unwrap_or(1).max(1),
+        slots_by_tier,
+    };
+    serde_json::to_string_pretty(&response).map_err(ShipDetailError::Serialize)
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct HostileListItem {
     pub id: String,
     /// Raw name from `data/hostiles` (may be a placeholder when using numeric upstream ids).
@@ -238,9 +490,16 @@ pub struct HostileListItem {
     pub display_name: String,
     pub level: u32,
     pub ship_class: String,
+    /// Same opponent at other levels shares this key (see `data::hostile::hostile_family_key`).
+    pub family: String,
 }
 
-pub fn hostiles_payload(registry: &DataRegistry) -> Result<String, serde_json::Error> {
+/// `query` (`?q=`) keeps only hostiles matching it by id, name or display name with level
+/// ("swarm 37"), best match first.
+pub fn hostiles_payload(
+    registry: &DataRegistry,
+    query: Option<&str>,
+) -> Result<String, serde_json::Error> {
     let loca_map = registry.hostile_loca_display();
     let list: Vec<HostileListItem> = registry
         .hostile_index()
@@ -256,29 +515,42 @@ pub fn hostiles_payload(registry: &DataRegistry) -> Result<String, serde_json::E
                         display_name,
                         level: e.level,
                         ship_class: e.ship_class.clone(),
+                        family: hostile_family_key(e),
                     }
                 })
                 .collect()
         })
         .unwrap_or_default();
+    let list = match query {
+        Some(q) => ranked_by_search(list, &registry.search_hostiles(q, usize::MAX), |h| &h.id),
+        None => list,
+    };
     serde_json::to_string_pretty(&serde_json::json!({ "hostiles": list }))
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct MechanicStatus {
     pub name: String,
-    pub status: String,
+    pub status: MechanicSupport,
+    /// Ability modifier/stat keys (snake_case) that map to this mechanic.
+    pub keys: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct DataVersionResponse {
+    /// Content hash of the officer files (there is no upstream officer version).
     pub officer_version: Option<String>,
+    /// `data_version` of data/hostiles/index.json.
     pub hostile_version: Option<String>,
+    /// `data_version` of data/ships_extended/index.json.
     pub ship_version: Option<String>,
+    /// Content hashes, record counts and modification times of the loaded data; changes whenever
+    /// any file does, so clients can cache-bust and bug reports can pin the exact data.
+    pub files: DataFileVersions,
     pub mechanics: Vec<MechanicStatus>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct SimulateRequest {
     pub ship: String,
     pub hostile: String,
@@ -289,18 +561,57 @@ pub struct SimulateRequest {
     pub crew: SimulateCrew,
     pub num_sims: Option<u32>,
     pub seed: Option<u64>,
+    /// Reject a ship or hostile that does not resolve (see [OptimizeRequest::strict]). None = true.
+    pub strict: Option<bool>,
+    /// Crew officers that match no officer data: "strict" rejects them (see
+    /// [check_known_officers]), "neutral" seats them with no effect, "legacy" with a made-up
+    /// bonus from the name's hash. None = strict.
+    pub unknown_officers: Option<UnknownOfficerMode>,
+    /// Share (0–1, above 0) of hull the ship starts with (see
+    /// [OptimizeRequest::attacker_hull_pct]). None = 1.
+    #[schemars(range(min = 0, max = 1))]
+    pub attacker_hull_pct: Option<f64>,
+    /// Share (0–1) of shields up when the fight starts; 0 = shields down. None = 1.
+    #[schemars(range(min = 0, max = 1))]
+    pub attacker_shield_pct: Option<f64>,
+}
+
+impl SimulateRequest {
+    /// Hull and shields the ship starts with; unset shares are full. Err names the share that is
+    /// out of range.
+    pub(crate) fn attacker_condition(&self) -> Result<AttackerCondition, SimulateError> {
+        let hull_pct = self.attacker_hull_pct.unwrap_or(1.0);
+        if !(hull_pct > 0.0 && hull_pct <= 1.0) {
+            return Err(SimulateError::Validation(
+                "attacker_hull_pct must be above 0 and at most 1".to_string(),
+            ));
+        }
+        let shield_pct = self.attacker_shield_pct.unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&shield_pct) {
+            return Err(SimulateError::Validation(
+                "attacker_shield_pct must be between 0 and 1".to_string(),
+            ));
+        }
+        Ok(AttackerCondition {
+            hull_pct,
+            shield_pct,
+        })
+    }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SimulateCrew {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub captain: Option<String>,
     /// Bridge officer IDs; null entries mean "no officer" in that slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub bridge: Option<Vec<Option<String>>>,
     /// Below-deck officer IDs; null entries mean "no officer" in that slot.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub below_deck: Option<Vec<Option<String>>>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SimulateResponse {
     pub status: &'static str,
     pub stats: SimulateStats,
@@ -309,7 +620,7 @@ pub struct SimulateResponse {
     pub warnings: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SimulateStats {
     pub win_rate: f64,
     pub stall_rate: f64,
@@ -329,15 +640,6 @@ fn officer_id_to_name(id: &str, officers: &[(String, String)]) -> String {
         .to_string()
 }
 
-fn pad_to_len(mut v: Vec<String>, len: usize) -> Vec<String> {
-    let first = v.first().cloned().unwrap_or_default();
-    while v.len() < len {
-        v.push(first.clone());
-    }
-    v.truncate(len);
-    v
-}
-
 fn binomial_95_ci(wins: u32, n: u32) -> [f64; 2] {
     if n == 0 {
         return [0.0, 0.0];
@@ -350,85 +652,256 @@ fn binomial_95_ci(wins: u32, n: u32) -> [f64; 2] {
     [lo, hi]
 }
 
-pub fn simulate_payload(
+/// Resolve request officer ids to names. `null`, empty and missing seats stay empty (a partial
+/// crew); seats past the ship's counts are dropped when the fight is built ([CrewSlots::fit]).
+fn crew_candidate_from_request(
     registry: &DataRegistry,
-    body: &str,
-    profile_id: Option<&str>,
-) -> Result<String, SimulateError> {
-    let req: SimulateRequest = serde_json::from_str(body).map_err(SimulateError::Parse)?;
-    let num_sims = req.num_sims.unwrap_or(5000).min(100_000).max(1);
-    let seed = req.seed.unwrap_or(0);
-
+    crew: &SimulateCrew,
+) -> Result<CrewCandidate, String> {
     let officers: Vec<(String, String)> = registry
         .officers()
         .iter()
         .map(|o| (o.id.clone(), o.name.clone()))
         .collect();
 
-    let captain = req
-        .crew
+    let captain = crew
         .captain
         .as_ref()
         .map(|s| officer_id_to_name(s, &officers))
         .unwrap_or_else(|| "".to_string());
-    let bridge_names: Vec<String> = req
-        .crew
-        .bridge
-        .as_ref()
-        .map(|v| {
-            v.iter()
-                .take(BRIDGE_SLOTS)
-                .map(|s| s.as_ref().map(|id| officer_id_to_name(id, &officers)).unwrap_or_default())
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default();
-    let below_names: Vec<String> = req
-        .crew
-        .below_deck
-        .as_ref()
-        .map(|v| {
-            v.iter()
-                .take(BELOW_DECKS_SLOTS)
-                .map(|s| s.as_ref().map(|id| officer_id_to_name(id, &officers)).unwrap_or_default())
-                .collect::<Vec<_>>()
-        })
-        .unwrap_or_default();
+    let seats = |seats: Option<&Vec<Option<String>>>| -> Vec<Option<String>> {
+        seats
+            .map(|v| {
+                v.iter()
+                    .map(|s| {
+                        s.as_deref()
+                            .map(str::trim)
+                            .filter(|id| !id.is_empty())
+                            .map(|id| officer_id_to_name(id, &officers))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let bridge = seats(crew.bridge.as_ref());
+    let below_decks = seats(crew.below_deck.as_ref());
 
     if captain.is_empty() {
-        return Err(SimulateError::Validation("crew.captain is required".to_string()));
+        return Err("crew.captain is required".to_string());
     }
 
-    // Pad to fixed slot counts: 2 bridge, 3 below decks (repeat first if fewer provided).
-    let bridge = pad_to_len(bridge_names, BRIDGE_SLOTS);
-    let below_decks = pad_to_len(below_names, BELOW_DECKS_SLOTS);
+    Ok(CrewCandidate {
+        captain,
+        bridge,
+        below_decks,
+    })
+}
 
+/// Strict mode, the API default (`strict` unset or true): rewrites the request's ship and hostile
+/// to canonical ids when they name exactly one (typed names like "USS Enterprise", see
+/// [DataRegistry::ship_id_for]), and rejects a ship (at the requested tier and level) or hostile
+/// that still does not resolve with the closest known ids, instead of simulating placeholder
+/// stats. `strict: false` leaves both as sent. `hostile: None` skips the hostile (for endpoints
+/// that resolve it themselves).
+pub fn resolve_known_ids(
+    registry: &DataRegistry,
+    strict: Option<bool>,
+    ship: &mut String,
+    ship_tier: Option<u32>,
+    ship_level: Option<u32>,
+    hostile: Option<&mut String>,
+) -> Result<(), UnknownIdsResponse> {
+    if strict == Some(false) {
+        return Ok(());
+    }
+    let ship_suggestions = canonicalize_id(ship, registry.ship_id_for(ship));
+    let hostile = hostile.map(|hostile| {
+        let suggestions = canonicalize_id(hostile, registry.hostile_id_for(hostile));
+        (hostile, suggestions)
+    });
+    let mut errors = Vec::new();
+    if registry
+        .resolve_ship_with_tier_level(ship, ship_tier, ship_level)
+        .is_none()
+    {
+        errors.push(UnknownIdIssue {
+            field: "ship",
+            value: ship.clone(),
+            suggestions: ship_suggestions,
+        });
+    }
+    if let Some((hostile, suggestions)) = hostile {
+        if registry.resolve_hostile(hostile).is_none() {
+            errors.push(UnknownIdIssue {
+                field: "hostile",
+                value: hostile.clone(),
+                suggestions,
+            });
+        }
+    }
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(UnknownIdsResponse {
+        status: "error",
+        message: "Unknown ship or hostile",
+        errors,
+    })
+}
+
+/// Strict unknown-officer mode, the API default (`unknown_officers` unset or "strict"): rejects a
+/// crew naming officers (after "(T# L#)" tags are dropped) that match no canonical officer, with
+/// the closest known officer ids, instead of seating them. Other modes accept the crew as is.
+pub fn check_known_officers(
+    registry: &DataRegistry,
+    mode: Option<UnknownOfficerMode>,
+    crew: &CrewCandidate,
+) -> Result<(), UnknownIdsResponse> {
+    if mode.unwrap_or(UnknownOfficerMode::Strict) != UnknownOfficerMode::Strict {
+        return Ok(());
+    }
+    let seats = std::iter::once(("crew.captain", crew.captain.as_str()))
+        .filter(|(_, name)| !name.is_empty())
+        .chain(crew.bridge_officers().map(|name| ("crew.bridge", name)))
+        .chain(crew.below_deck_officers().map(|name| ("crew.below_deck", name)));
+    let errors: Vec<UnknownIdIssue> = seats
+        .filter_map(|(field, name)| {
+            let lookup_name = split_name_and_tier(name).0;
+            if registry.officer_index().contains_key(&normalize_lookup_key(&lookup_name)) {
+                return None;
+            }
+            let labels = registry
+                .officers()
+                .iter()
+                .map(|o| (o.id.as_str(), vec![o.id.clone(), o.name.clone()]));
+            Some(UnknownIdIssue {
+                field,
+                value: name.to_string(),
+                suggestions: near_miss_ids(&lookup_name, labels, NEAR_MISS_LIMIT),
+            })
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(UnknownIdsResponse {
+        status: "error",
+        message: "Unknown officer",
+        errors,
+    })
+}
+
+/// Replace `value` with its resolved id; otherwise keep it and return the suggestions.
+fn canonicalize_id(value: &mut String, resolved: Result<String, Vec<String>>) -> Vec<String> {
+    match resolved {
+        Ok(id) => {
+            *value = id;
+            Vec::new()
+        }
+        Err(suggestions) => suggestions,
+    }
+}
+
+const PLACEHOLDER_COMBATANTS_WARNING: &str = "Ship or hostile did not resolve from loaded data; combat used deterministic placeholder stats. Results do not reflect real ship/hostile values.";
+
+pub fn simulate_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, SimulateError> {
+    let mut req: SimulateRequest = serde_json::from_str(body).map_err(SimulateError::Parse)?;
+    let num_sims = req.num_sims.unwrap_or_else(default_sims).clamp(1, MAX_SIMS);
+    let seed = req.seed.unwrap_or(0);
+    let attacker_condition = req.attacker_condition()?;
+
+    let CrewCandidate {
+        captain,
+        bridge,
+        below_decks,
+    } = crew_candidate_from_request(registry, &req.crew).map_err(SimulateError::Validation)?;
+    resolve_known_ids(
+        registry,
+        req.strict,
+        &mut req.ship,
+        req.ship_tier,
+        req.ship_level,
+        Some(&mut req.hostile),
+    )
+    .map_err(SimulateError::UnknownIds)?;
     let candidate = CrewCandidate {
         captain: captain.clone(),
         bridge: bridge.clone(),
         below_decks: below_decks.clone(),
     };
-    let candidates = vec![candidate];
-    let (results, using_placeholder_combatants) = run_monte_carlo_with_registry(
-        registry,
-        &req.ship,
-        &req.hostile,
-        req.ship_tier,
-        req.ship_level,
-        &candidates,
-        num_sims as usize,
+    check_known_officers(registry, req.unknown_officers, &candidate)
+        .map_err(SimulateError::UnknownIds)?;
+    let unknown_officers = req.unknown_officers.unwrap_or(UnknownOfficerMode::Strict);
+
+    let cache_key = SimulateCacheKey {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        captain: &captain,
+        bridge: &bridge,
+        below_decks: &below_decks,
+        num_sims,
         seed,
+        profile_id: resolve_profile_id(profile_id),
+        unknown_officers,
+        attacker_condition,
+    };
+    let memo = simulate_cache::MemoKey::new(&cache_key, profile_id);
+    if let Some(payload) = memo.as_ref().and_then(simulate_cache::MemoKey::lookup) {
+        return Ok(payload);
+    }
+    if let Some(payload) = result_cache::lookup::<_, String>(SIMULATE_NAMESPACE, &cache_key, profile_id) {
+        if let Some(memo) = &memo {
+            memo.store(&payload);
+        }
+        return Ok(payload);
+    }
+
+    let scenario = OptimizationScenario {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
         profile_id,
-    );
-    let result = results.into_iter().next().unwrap_or(SimulationResult {
+        unknown_officers,
+        attacker_condition,
+        ..OptimizationScenario::default()
+    };
+    let shared = scenario_shared_data_from_registry(registry, &scenario);
+    let using_placeholder_combatants = shared.using_placeholder_combatants;
+    // A user is waiting on this call: background optimize jobs pause between batches meanwhile.
+    let results = WorkerPool::with_workers(0).with_priority(Priority::Interactive).install(|| {
+        run_monte_carlo_with_shared(
+            shared,
+            std::slice::from_ref(&candidate),
+            num_sims as usize,
+            seed,
+            false,
+        )
+    });
+    let result = results.into_iter().next().unwrap_or_else(|| SimulationResult {
         candidate: CrewCandidate {
-            captain,
-            bridge,
-            below_decks,
+            captain: captain.clone(),
+            bridge: bridge.clone(),
+            below_decks: below_decks.clone(),
         },
         win_rate: 0.0,
         stall_rate: 0.0,
         loss_rate: 0.0,
         avg_hull_remaining: 0.0,
+        avg_survival_share: 0.0,
+        avg_damage_dealt: 0.0,
+        survival_rate: 0.0,
+        avg_waves_cleared: 0.0,
+        median_rounds_to_kill: None,
+        p95_rounds_to_kill: None,
+        avg_damage_per_round: 0.0,
+        avg_hull_lost: 0.0,
     });
 
     let wins = (result.win_rate * num_sims as f64).round() as u32;
@@ -436,10 +909,7 @@ pub fn simulate_payload(
 
     let mut warnings = Vec::new();
     if using_placeholder_combatants {
-        warnings.push(
-            "Ship or hostile did not resolve from loaded data; combat used deterministic placeholder stats. Results do not reflect real ship/hostile values."
-                .to_string(),
-        );
+        warnings.push(PLACEHOLDER_COMBATANTS_WARNING.to_string());
     }
 
     let response = SimulateResponse {
@@ -455,13 +925,38 @@ pub fn simulate_payload(
         seed,
         warnings,
     };
-    serde_json::to_string_pretty(&response).map_err(SimulateError::Parse)
+    let payload = serde_json::to_string_pretty(&response).map_err(SimulateError::Parse)?;
+    result_cache::store(SIMULATE_NAMESPACE, &cache_key, profile_id, &payload);
+    if let Some(memo) = &memo {
+        memo.store(&payload);
+    }
+    Ok(payload)
+}
+
+/// Inputs that determine a `/api/simulate` response (after officer id → name resolution).
+#[derive(Serialize)]
+struct SimulateCacheKey<'a> {
+    ship: &'a str,
+    hostile: &'a str,
+    ship_tier: Option<u32>,
+    ship_level: Option<u32>,
+    captain: &'a str,
+    bridge: &'a [Option<String>],
+    below_decks: &'a [Option<String>],
+    num_sims: u32,
+    seed: u64,
+    profile_id: String,
+    unknown_officers: UnknownOfficerMode,
+    /// Skipped at full health so keys stored before the override existed still match.
+    #[serde(skip_serializing_if = "AttackerCondition::is_fresh")]
+    attacker_condition: AttackerCondition,
 }
 
 #[derive(Debug)]
 pub enum SimulateError {
     Parse(serde_json::Error),
     Validation(String),
+    UnknownIds(UnknownIdsResponse),
 }
 
 impl fmt::Display for SimulateError {
@@ -469,18 +964,68 @@ impl fmt::Display for SimulateError {
         match self {
             Self::Parse(e) => write!(f, "{e}"),
             Self::Validation(m) => write!(f, "{m}"),
+            Self::UnknownIds(unknown) => write!(f, "{unknown}"),
         }
     }
 }
 
 impl std::error::Error for SimulateError {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PlayerProfile {
+    /// Hand-entered bonuses; each stat's unit (percent or flat) is documented on [PlayerBonuses].
+    #[serde(default)]
+    pub bonuses: PlayerBonuses,
+    /// Apply seated officers' level-gated traits (see [crate::data::profile::PlayerProfile::officer_traits]).
+    #[serde(default)]
+    pub officer_traits: bool,
+    /// Apply the ship's bonuses for its bridge officers' stats (see
+    /// [crate::data::profile::PlayerProfile::officer_stats]).
+    #[serde(default)]
+    pub officer_stats: bool,
+    /// Extra bonuses only applied against `hostile`, `armada` or `player` defenders.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub target_bonuses: BTreeMap<TargetContext, PlayerBonuses>,
+}
+
+/// `profile.json` as stored, before bonus keys are migrated or checked.
+#[derive(Debug, Default, Deserialize)]
+struct StoredProfile {
+    #[serde(default)]
+    bonuses: HashMap<String, f64>,
+    #[serde(default)]
+    officer_traits: bool,
+    #[serde(default)]
+    officer_stats: bool,
     #[serde(default)]
-    pub bonuses: std::collections::HashMap<String, f64>,
+    target_bonuses: BTreeMap<TargetContext, HashMap<String, f64>>,
 }
 
+/// The profile's `profile.json`; empty when it is missing or unreadable.
+fn read_stored_profile(profile_id: &str) -> StoredProfile {
+    fs::read_to_string(profile_path(profile_id, PROFILE_JSON))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+pub enum ProfilePutError {
+    Parse(serde_json::Error),
+    Validation(ValidationErrorResponse),
+}
+
+impl fmt::Display for ProfilePutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Validation(_) => write!(f, "invalid profile bonuses"),
+        }
+    }
+}
+
+impl std::error::Error for ProfilePutError {}
+
 /// Resolve profile id from optional param; falls back to index default.
 fn resolve_profile_id(profile_id: Option<&str>) -> String {
     let index = load_profile_index();
@@ -490,31 +1035,94 @@ fn resolve_profile_id(profile_id: Option<&str>) -> String {
         .unwrap_or_else(|| effective_profile_id(&index))
 }
 
+/// Older profiles are migrated on read; keys that are no longer stats are dropped.
 pub fn profile_get_payload(profile_id: Option<&str>) -> Result<String, serde_json::Error> {
-    let id = resolve_profile_id(profile_id);
-    let path = profile_path(&id, PROFILE_JSON);
-    let profile: PlayerProfile = if path.exists() {
-        let raw = fs::read_to_string(&path).unwrap_or_else(|_| "{}".to_string());
-        serde_json::from_str(&raw).unwrap_or(PlayerProfile {
-            bonuses: std::collections::HashMap::new(),
-        })
-    } else {
-        PlayerProfile {
-            bonuses: std::collections::HashMap::new(),
-        }
+    let stored = read_stored_profile(&resolve_profile_id(profile_id));
+    let profile = PlayerProfile {
+        bonuses: PlayerBonuses::migrate(&stored.bonuses).0,
+        officer_traits: stored.officer_traits,
+        officer_stats: stored.officer_stats,
+        target_bonuses: stored
+            .target_bonuses
+            .iter()
+            .map(|(target, raw)| (*target, PlayerBonuses::migrate(raw).0))
+            .collect(),
     };
     serde_json::to_string_pretty(&profile)
 }
 
-pub fn profile_put_payload(body: &str, profile_id: Option<&str>) -> Result<String, serde_json::Error> {
-    let _: PlayerProfile = serde_json::from_str(body).map_err(|e| e)?;
+/// Checks `bonuses` and each `target_bonuses` entry against [crate::data::profile::BONUS_SPECS]
+/// (legacy keys are accepted and rewritten) and stores the canonical form. Other top-level keys
+/// are kept as sent.
+pub fn profile_put_payload(
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, ProfilePutError> {
+    let mut document: serde_json::Value =
+        serde_json::from_str(body).map_err(ProfilePutError::Parse)?;
+    let stored: StoredProfile =
+        serde_json::from_value(document.clone()).map_err(ProfilePutError::Parse)?;
+    let mut errors: Vec<ValidationIssue> = Vec::new();
+    let mut push_issue = |field: &'static str, message: String| {
+        match errors.iter_mut().find(|e| e.field == field) {
+            Some(existing) => existing.messages.push(message),
+            None => errors.push(ValidationIssue {
+                field,
+                messages: vec![message],
+            }),
+        }
+    };
+    let bonuses = match PlayerBonuses::from_map(&stored.bonuses) {
+        Ok(bonuses) => Some(bonuses),
+        Err(issues) => {
+            for issue in issues {
+                push_issue(issue.field, issue.message);
+            }
+            None
+        }
+    };
+    let mut target_bonuses = BTreeMap::new();
+    for (target, raw) in &stored.target_bonuses {
+        match PlayerBonuses::from_map(raw) {
+            Ok(bonuses) => {
+                target_bonuses.insert(*target, bonuses);
+            }
+            Err(issues) => {
+                for issue in issues {
+                    let message = match issue.stat {
+                        Some(stat) => format!("{stat}: {}", issue.message),
+                        None => issue.message,
+                    };
+                    push_issue(target.field(), message);
+                }
+            }
+        }
+    }
+    let Some(bonuses) = bonuses.filter(|_| errors.is_empty()) else {
+        return Err(ProfilePutError::Validation(ValidationErrorResponse {
+            status: "error",
+            message: "Validation failed",
+            errors,
+        }));
+    };
+    document["bonuses"] = serde_json::to_value(&bonuses).map_err(ProfilePutError::Parse)?;
+    if target_bonuses.is_empty() {
+        if let Some(object) = document.as_object_mut() {
+            object.remove("target_bonuses");
+        }
+    } else {
+        document["target_bonuses"] =
+            serde_json::to_value(&target_bonuses).map_err(ProfilePutError::Parse)?;
+    }
     let id = resolve_profile_id(profile_id);
     let path = profile_path(&id, PROFILE_JSON);
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
     }
-    fs::write(&path, body).map_err(serde_json::Error::io)?;
+    let text = serde_json::to_string_pretty(&document).map_err(ProfilePutError::Parse)?;
+    fs::write(&path, text).map_err(|e| ProfilePutError::Parse(serde_json::Error::io(e)))?;
     serde_json::to_string_pretty(&serde_json::json!({ "status": "ok" }))
+        .map_err(ProfilePutError::Parse)
 }
 
 /// GET /api/profile/buildings-summary — synced module levels and building-derived combat bonuses.
@@ -586,11 +1194,32 @@ fn write_temp_import_file(body: &[u8], ext: &str) -> Result<std::path::PathBuf,
     Ok(path)
 }
 
+/// `POST /api/officers/import` body that names a Google Sheet instead of carrying the roster.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OfficersImportUrl {
+    url: String,
+}
+
+/// Imports a roster from CSV text, a Spocks JSON export, or `{"url": "..."}` naming a Google
+/// Sheets CSV ([crate::data::sheets], opt-in). Blocking when given a URL.
 pub fn officers_import_payload(body: &str, profile_id: Option<&str>) -> Result<String, ImportError> {
     let body = body.trim();
     let id = resolve_profile_id(profile_id);
     let output_path = profile_path(&id, ROSTER_IMPORTED).to_string_lossy().to_string();
-    let report = if body.starts_with('{') || body.starts_with('[') {
+    let sheet = body
+        .starts_with('{')
+        .then(|| serde_json::from_str::<OfficersImportUrl>(body).ok())
+        .flatten();
+    let report = if let Some(OfficersImportUrl { url }) = sheet {
+        let csv = crate::data::sheets::fetch_csv(&url).map_err(ImportError::Sheet)?;
+        let p = write_temp_import_file(csv.as_bytes(), "txt").map_err(ImportError::Io)?;
+        let out = import_roster_csv_to(p.to_str().unwrap(), &output_path);
+        let _ = fs::remove_file(&p);
+        let mut out = out?;
+        out.source_path = url;
+        out
+    } else if body.starts_with('{') || body.starts_with('[') {
         let p = write_temp_import_file(body.as_bytes(), "json").map_err(ImportError::Io)?;
         let out = import_spocks_export_to(p.to_str().unwrap(), &output_path)?;
         let _ = fs::remove_file(&p);
@@ -608,6 +1237,7 @@ pub fn officers_import_payload(body: &str, profile_id: Option<&str>) -> Result<S
 pub enum ImportError {
     Io(std::io::Error),
     Import(crate::data::import::ImportError),
+    Sheet(crate::data::sheets::SheetsError),
     Serialize(serde_json::Error),
 }
 
@@ -616,6 +1246,7 @@ impl fmt::Display for ImportError {
         match self {
             Self::Io(e) => write!(f, "{e}"),
             Self::Import(e) => write!(f, "{e}"),
+            Self::Sheet(e) => write!(f, "{e}"),
             Self::Serialize(e) => write!(f, "{e}"),
         }
     }
@@ -706,14 +1337,14 @@ fn presets_dir_for_profile(profile_id: &str) -> std::path::PathBuf {
     profile_path(profile_id, PRESETS_SUBDIR)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PresetCrew {
     pub captain: Option<String>,
     pub bridge: Option<Vec<String>>,
     pub below_deck: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Preset {
     pub id: String,
     pub name: String,
@@ -722,7 +1353,7 @@ pub struct Preset {
     pub crew: PresetCrew,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PresetSummary {
     pub id: String,
     pub name: String,
@@ -842,20 +1473,25 @@ pub fn preset_post_payload(body: &str, profile_id: Option<&str>) -> Result<Strin
 pub fn data_version_payload(registry: &DataRegistry) -> Result<String, serde_json::Error> {
     let hostile_index = registry.hostile_index();
     let ship_index = registry.ship_index();
-    let mechanics = vec![
-        MechanicStatus { name: "Mitigation".to_string(), status: "implemented".to_string() },
-        MechanicStatus { name: "Piercing".to_string(), status: "implemented".to_string() },
-        MechanicStatus { name: "Armor".to_string(), status: "implemented".to_string() },
-        MechanicStatus { name: "Critical".to_string(), status: "implemented".to_string() },
-        MechanicStatus { name: "Burn".to_string(), status: "partial".to_string() },
-        MechanicStatus { name: "Regeneration".to_string(), status: "partial".to_string() },
-        MechanicStatus { name: "Isolytic".to_string(), status: "planned".to_string() },
-        MechanicStatus { name: "Apex".to_string(), status: "planned".to_string() },
-    ];
+    let mechanics = MECHANICS
+        .iter()
+        .map(|m| MechanicStatus {
+            name: m.name.to_string(),
+            status: m.support,
+            keys: m
+                .keys
+                .iter()
+                .chain(m.key_fragments)
+                .map(|k| k.to_string())
+                .collect(),
+        })
+        .collect();
+    let files = registry.file_versions().clone();
     let response = DataVersionResponse {
-        officer_version: Some("canonical".to_string()),
+        officer_version: Some(files.officers.content_hash.clone()),
         hostile_version: hostile_index.and_then(|i| i.data_version.clone()),
         ship_version: ship_index.and_then(|i| i.data_version.clone()),
+        files,
         mechanics,
     };
     serde_json::to_string_pretty(&response)
@@ -882,11 +1518,23 @@ pub fn optimize_payload(
     registry: &DataRegistry,
     body: &str,
     profile_id: Option<&str>,
+    webhooks: &WebhookPolicy,
 ) -> Result<String, OptimizePayloadError> {
-    let request: OptimizeRequest =
+    let mut request: OptimizeRequest =
         serde_json::from_str(body).map_err(OptimizePayloadError::Parse)?;
-    let sims = request.sims.unwrap_or(DEFAULT_SIMS);
-    validate_request(&request, sims)?;
+    // Pinned here so a later default_sims change can't split one run (or its cache key).
+    let sims = *request.sims.get_or_insert_with(default_sims);
+    validate_request(&request, sims, webhooks)?;
+    resolve_known_ids(
+        registry,
+        request.strict,
+        &mut request.ship,
+        request.ship_tier,
+        request.ship_level,
+        Some(&mut request.hostile),
+    )
+    .map_err(OptimizePayloadError::UnknownIds)?;
+    execution::check_seed_officers(registry, &request).map_err(OptimizePayloadError::UnknownIds)?;
     let response = execution::run_optimize(registry, &request, profile_id)?;
     serde_json::to_string_pretty(&response).map_err(OptimizePayloadError::Parse)
 }
@@ -896,13 +1544,26 @@ pub fn optimize_start_payload(
     registry: Arc<DataRegistry>,
     body: &str,
     profile_id: Option<&str>,
+    webhooks: Arc<WebhookPolicy>,
 ) -> Result<String, OptimizePayloadError> {
-    let request: OptimizeRequest =
+    let mut request: OptimizeRequest =
         serde_json::from_str(body).map_err(OptimizePayloadError::Parse)?;
-    let sims = request.sims.unwrap_or(DEFAULT_SIMS);
-    validate_request(&request, sims)?;
+    // Pinned here so a later default_sims change can't split one run (or its cache key).
+    let sims = *request.sims.get_or_insert_with(default_sims);
+    validate_request(&request, sims, &webhooks)?;
+    resolve_known_ids(
+        &registry,
+        request.strict,
+        &mut request.ship,
+        request.ship_tier,
+        request.ship_level,
+        Some(&mut request.hostile),
+    )
+    .map_err(OptimizePayloadError::UnknownIds)?;
+    execution::check_seed_officers(&registry, &request)
+        .map_err(OptimizePayloadError::UnknownIds)?;
     let start_response =
-        execution::start_optimize_job(registry, request, profile_id, cpu_permit)?;
+        execution::start_optimize_job(registry, request, profile_id, cpu_permit, webhooks)?;
     serde_json::to_string_pretty(&start_response).map_err(OptimizePayloadError::Parse)
 }
 
@@ -949,16 +1610,15 @@ pub fn optimize_estimate_payload(
             }],
         }));
     }
+    let limit = max_candidates_limit();
     let estimated_candidates = match max_candidates {
-        Some(cap) if cap <= MAX_CANDIDATES => {
+        Some(cap) if cap <= limit => {
             let generator = CrewGenerator::with_strategy(CandidateStrategy {
                 max_candidates: Some(cap as usize),
                 only_below_decks_with_ability: prioritize_below_decks_ability,
                 ..CandidateStrategy::default()
             });
-            generator
-                .generate_candidates_from_registry(registry, &ship, &hostile, 0, profile_id)
-                .len()
+            generator.count_candidates_from_registry(registry, &ship, &hostile, 0, profile_id)
         }
         Some(_) => {
             return Err(OptimizePayloadError::Validation(ValidationErrorResponse {
@@ -966,24 +1626,39 @@ pub fn optimize_estimate_payload(
                 message: "Validation failed",
                 errors: vec![ValidationIssue {
                     field: "max_candidates",
-                    messages: vec![format!("must be at most {MAX_CANDIDATES}")],
+                    messages: vec![format!("must be at most {limit}")],
                 }],
             }));
         }
         None => {
             let generator = CrewGenerator::with_strategy(CandidateStrategy {
+                max_candidates: None,
                 only_below_decks_with_ability: prioritize_below_decks_ability,
                 ..CandidateStrategy::default()
             });
             generator.count_candidates_from_registry(registry, &ship, &hostile, 0, profile_id)
         }
     };
-    let estimated_seconds = (estimated_candidates as f64) * (sims as f64) * ESTIMATE_SEC_PER_CANDIDATE_SIM;
+    let total_sims = (estimated_candidates as f64) * (sims as f64);
+    // Prefer throughput measured by a finished job in this process; fall back to the constant.
+    let observed = crate::parallel::observed_sims_per_second();
+    let (estimated_seconds, basis) = match observed {
+        Some(rate) => (total_sims / rate, "observed"),
+        None => (total_sims * ESTIMATE_SEC_PER_CANDIDATE_SIM, "heuristic"),
+    };
     let estimated_seconds = estimated_seconds.max(0.1).min(3600.0); // clamp to 0.1s–1h for display
-    let payload = serde_json::json!({
+    let mut payload = serde_json::json!({
         "estimated_candidates": estimated_candidates,
         "sims_per_crew": sims,
         "estimated_seconds": (estimated_seconds * 10.0).round() / 10.0,
+        "estimate_basis": basis,
+        "auto_strategy": match auto_strategy(estimated_candidates) {
+            OptimizerStrategy::Genetic => "genetic",
+            _ => "exhaustive",
+        },
     });
+    if let Some(rate) = observed {
+        payload["sims_per_second"] = serde_json::Value::from(rate.round());
+    }
     serde_json::to_string_pretty(&payload).map_err(OptimizePayloadError::Parse)
 }
@@ -0,0 +1,667 @@
+//! Analysis endpoints built on the optimizer: officer upgrade value, boost choice, officer
+//! leaderboards, stat sensitivity, hostile difficulty sweeps and preset brackets.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::data::data_registry::DataRegistry;
+use crate::data::officer::UnknownOfficerMode;
+use crate::optimizer::boosts::{officer_boost_report, BoostReport, DEFAULT_BOOST_TOP_CREWS};
+use crate::optimizer::bracket::{run_bracket, BracketEntrant, BracketReport};
+use crate::optimizer::leaderboard::{
+    officer_leaderboard, LeaderboardReport, DEFAULT_LEADERBOARD_SAMPLES,
+};
+use crate::optimizer::stat_sensitivity::{attacker_stat_sensitivity, StatSensitivityReport};
+use crate::optimizer::sweep::{hostile_level_sweep, SweepReport, DEFAULT_SWEEP_TARGET_WIN_RATE};
+use crate::optimizer::upgrades::{officer_upgrade_report, UpgradeReport, DEFAULT_UPGRADE_TOP_CREWS};
+use crate::optimizer::OptimizationScenario;
+
+use super::requests::{
+    default_sims, max_candidates_limit, UnknownIdsResponse, MAX_CANDIDATES, MAX_SIMS,
+};
+use super::{
+    check_known_officers, crew_candidate_from_request, preset_get_payload, resolve_known_ids,
+    Preset, SimulateCrew,
+};
+
+/// Upper bound for `top_crews` (each upgrade re-simulates this many crews).
+pub const MAX_UPGRADE_TOP_CREWS: u32 = 20;
+/// Upper bound for a leaderboard's `samples` (each simulates up to one crew per seat plus one).
+pub const MAX_LEADERBOARD_SAMPLES: u32 = 512;
+/// Stat perturbation when `delta_pct` is unset (±10%).
+pub const DEFAULT_STAT_DELTA_PCT: f64 = 0.1;
+/// Upper bound for `delta_pct`; larger cuts would zero out the stat.
+pub const MAX_STAT_DELTA_PCT: f64 = 0.9;
+/// Upper bound for a bracket's presets (the round-robin grows with the square).
+pub const MAX_BRACKET_PRESETS: u32 = 16;
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct OfficerUpgradesRequest {
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    pub ship_tier: Option<u32>,
+    pub ship_level: Option<u32>,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub sims: Option<u32>,
+    pub seed: Option<u64>,
+    #[schemars(range(min = 1, max = "MAX_CANDIDATES"))]
+    pub max_candidates: Option<u32>,
+    /// Best crews re-simulated with each officer promoted. None = 5.
+    #[schemars(range(min = 1, max = "MAX_UPGRADE_TOP_CREWS"))]
+    pub top_crews: Option<u32>,
+    /// Reject an unknown ship or hostile (404 with near-miss ids). None = true.
+    pub strict: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OfficerUpgradesResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    pub sims: u32,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub report: UpgradeReport,
+}
+
+/// POST /api/analyze/officer-upgrades: rank roster officers by win-rate gain per shard.
+pub fn officer_upgrades_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, AnalyzeError> {
+    let mut req: OfficerUpgradesRequest = serde_json::from_str(body).map_err(AnalyzeError::Parse)?;
+    if req.ship.trim().is_empty() || req.hostile.trim().is_empty() {
+        return Err(AnalyzeError::Validation(
+            "ship and hostile are required".to_string(),
+        ));
+    }
+    let sims = req.sims.unwrap_or_else(default_sims);
+    if sims == 0 || sims > MAX_SIMS {
+        return Err(AnalyzeError::Validation(format!(
+            "sims must be between 1 and {MAX_SIMS}"
+        )));
+    }
+    let top_crews = req.top_crews.unwrap_or(DEFAULT_UPGRADE_TOP_CREWS as u32);
+    if top_crews == 0 || top_crews > MAX_UPGRADE_TOP_CREWS {
+        return Err(AnalyzeError::Validation(format!(
+            "top_crews must be between 1 and {MAX_UPGRADE_TOP_CREWS}"
+        )));
+    }
+    let limit = max_candidates_limit();
+    if req.max_candidates.is_some_and(|n| n == 0 || n > limit) {
+        return Err(AnalyzeError::Validation(format!(
+            "max_candidates must be between 1 and {limit}"
+        )));
+    }
+    resolve_known_ids(
+        registry,
+        req.strict,
+        &mut req.ship,
+        req.ship_tier,
+        req.ship_level,
+        Some(&mut req.hostile),
+    )
+    .map_err(AnalyzeError::UnknownIds)?;
+    let seed = req.seed.unwrap_or(0);
+
+    let defaults = OptimizationScenario::default();
+    let scenario = OptimizationScenario {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        simulation_count: sims as usize,
+        seed,
+        max_candidates: req
+            .max_candidates
+            .map(|n| n as usize)
+            .or(defaults.max_candidates),
+        profile_id,
+        ..defaults
+    };
+    let report = officer_upgrade_report(registry, &scenario, top_crews as usize);
+
+    let response = OfficerUpgradesResponse {
+        status: "ok",
+        ship: req.ship.clone(),
+        hostile: req.hostile.clone(),
+        sims,
+        seed,
+        report,
+    };
+    serde_json::to_string_pretty(&response).map_err(AnalyzeError::Parse)
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct OfficerBoostsRequest {
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    pub ship_tier: Option<u32>,
+    pub ship_level: Option<u32>,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub sims: Option<u32>,
+    pub seed: Option<u64>,
+    #[schemars(range(min = 1, max = "MAX_CANDIDATES"))]
+    pub max_candidates: Option<u32>,
+    /// Best crews re-simulated with each of their officers boosted. None = 5.
+    #[schemars(range(min = 1, max = "MAX_UPGRADE_TOP_CREWS"))]
+    pub top_crews: Option<u32>,
+    /// Reject an unknown ship or hostile (404 with near-miss ids). None = true.
+    pub strict: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OfficerBoostsResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    pub sims: u32,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub report: BoostReport,
+}
+
+/// POST /api/analyze/officer-boosts: for the best crews, which officer is worth boosting.
+pub fn officer_boosts_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, AnalyzeError> {
+    let mut req: OfficerBoostsRequest = serde_json::from_str(body).map_err(AnalyzeError::Parse)?;
+    if req.ship.trim().is_empty() || req.hostile.trim().is_empty() {
+        return Err(AnalyzeError::Validation(
+            "ship and hostile are required".to_string(),
+        ));
+    }
+    let sims = req.sims.unwrap_or_else(default_sims);
+    if sims == 0 || sims > MAX_SIMS {
+        return Err(AnalyzeError::Validation(format!(
+            "sims must be between 1 and {MAX_SIMS}"
+        )));
+    }
+    let top_crews = req.top_crews.unwrap_or(DEFAULT_BOOST_TOP_CREWS as u32);
+    if top_crews == 0 || top_crews > MAX_UPGRADE_TOP_CREWS {
+        return Err(AnalyzeError::Validation(format!(
+            "top_crews must be between 1 and {MAX_UPGRADE_TOP_CREWS}"
+        )));
+    }
+    let limit = max_candidates_limit();
+    if req.max_candidates.is_some_and(|n| n == 0 || n > limit) {
+        return Err(AnalyzeError::Validation(format!(
+            "max_candidates must be between 1 and {limit}"
+        )));
+    }
+    resolve_known_ids(
+        registry,
+        req.strict,
+        &mut req.ship,
+        req.ship_tier,
+        req.ship_level,
+        Some(&mut req.hostile),
+    )
+    .map_err(AnalyzeError::UnknownIds)?;
+    let seed = req.seed.unwrap_or(0);
+
+    let defaults = OptimizationScenario::default();
+    let scenario = OptimizationScenario {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        simulation_count: sims as usize,
+        seed,
+        max_candidates: req
+            .max_candidates
+            .map(|n| n as usize)
+            .or(defaults.max_candidates),
+        profile_id,
+        ..defaults
+    };
+    let report = officer_boost_report(registry, &scenario, top_crews as usize);
+
+    let response = OfficerBoostsResponse {
+        status: "ok",
+        ship: req.ship.clone(),
+        hostile: req.hostile.clone(),
+        sims,
+        seed,
+        report,
+    };
+    serde_json::to_string_pretty(&response).map_err(AnalyzeError::Parse)
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct OfficerLeaderboardRequest {
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    pub ship_tier: Option<u32>,
+    pub ship_level: Option<u32>,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub sims: Option<u32>,
+    pub seed: Option<u64>,
+    /// Random crews sampled from the officer pools. None = 64.
+    #[schemars(range(min = 1, max = "MAX_LEADERBOARD_SAMPLES"))]
+    pub samples: Option<u32>,
+    /// Reject an unknown ship or hostile (404 with near-miss ids). None = true.
+    pub strict: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OfficerLeaderboardResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    pub sims: u32,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub report: LeaderboardReport,
+}
+
+/// POST /api/analyze/officer-leaderboard: per-seat officer ranking by average marginal win rate
+/// over sampled crews.
+pub fn officer_leaderboard_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, AnalyzeError> {
+    let mut req: OfficerLeaderboardRequest =
+        serde_json::from_str(body).map_err(AnalyzeError::Parse)?;
+    if req.ship.trim().is_empty() || req.hostile.trim().is_empty() {
+        return Err(AnalyzeError::Validation(
+            "ship and hostile are required".to_string(),
+        ));
+    }
+    let sims = req.sims.unwrap_or_else(default_sims);
+    if sims == 0 || sims > MAX_SIMS {
+        return Err(AnalyzeError::Validation(format!(
+            "sims must be between 1 and {MAX_SIMS}"
+        )));
+    }
+    let samples = req.samples.unwrap_or(DEFAULT_LEADERBOARD_SAMPLES as u32);
+    if samples == 0 || samples > MAX_LEADERBOARD_SAMPLES {
+        return Err(AnalyzeError::Validation(format!(
+            "samples must be between 1 and {MAX_LEADERBOARD_SAMPLES}"
+        )));
+    }
+    resolve_known_ids(
+        registry,
+        req.strict,
+        &mut req.ship,
+        req.ship_tier,
+        req.ship_level,
+        Some(&mut req.hostile),
+    )
+    .map_err(AnalyzeError::UnknownIds)?;
+    let seed = req.seed.unwrap_or(0);
+
+    let scenario = OptimizationScenario {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        simulation_count: sims as usize,
+        seed,
+        profile_id,
+        ..OptimizationScenario::default()
+    };
+    let report = officer_leaderboard(registry, &scenario, samples as usize);
+
+    let response = OfficerLeaderboardResponse {
+        status: "ok",
+        ship: req.ship.clone(),
+        hostile: req.hostile.clone(),
+        sims,
+        seed,
+        report,
+    };
+    serde_json::to_string_pretty(&response).map_err(AnalyzeError::Parse)
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct StatSensitivityRequest {
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    pub ship_tier: Option<u32>,
+    pub ship_level: Option<u32>,
+    pub crew: SimulateCrew,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub num_sims: Option<u32>,
+    pub seed: Option<u64>,
+    /// Relative change applied to each stat in both directions (0.1 = ±10%). None = 0.1.
+    #[schemars(range(max = "MAX_STAT_DELTA_PCT"))]
+    pub delta_pct: Option<f64>,
+    /// Reject an unknown ship or hostile (404 with near-miss ids). None = true.
+    pub strict: Option<bool>,
+    /// Crew officers that match no officer data
+    /// (see [super::SimulateRequest::unknown_officers]). None = strict.
+    pub unknown_officers: Option<UnknownOfficerMode>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StatSensitivityResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    pub num_sims: u32,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub report: StatSensitivityReport,
+}
+
+/// POST /api/analyze/stats: win-rate sensitivity of a fixed crew to ±`delta_pct` ship stats.
+pub fn stat_sensitivity_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, AnalyzeError> {
+    let mut req: StatSensitivityRequest = serde_json::from_str(body).map_err(AnalyzeError::Parse)?;
+    if req.ship.trim().is_empty() || req.hostile.trim().is_empty() {
+        return Err(AnalyzeError::Validation(
+            "ship and hostile are required".to_string(),
+        ));
+    }
+    let num_sims = req.num_sims.unwrap_or_else(default_sims);
+    if num_sims == 0 || num_sims > MAX_SIMS {
+        return Err(AnalyzeError::Validation(format!(
+            "num_sims must be between 1 and {MAX_SIMS}"
+        )));
+    }
+    let delta_pct = req.delta_pct.unwrap_or(DEFAULT_STAT_DELTA_PCT);
+    if !(delta_pct > 0.0 && delta_pct <= MAX_STAT_DELTA_PCT) {
+        return Err(AnalyzeError::Validation(format!(
+            "delta_pct must be greater than 0 and at most {MAX_STAT_DELTA_PCT}"
+        )));
+    }
+    let crew =
+        crew_candidate_from_request(registry, &req.crew).map_err(AnalyzeError::Validation)?;
+    resolve_known_ids(
+        registry,
+        req.strict,
+        &mut req.ship,
+        req.ship_tier,
+        req.ship_level,
+        Some(&mut req.hostile),
+    )
+    .map_err(AnalyzeError::UnknownIds)?;
+    check_known_officers(registry, req.unknown_officers, &crew)
+        .map_err(AnalyzeError::UnknownIds)?;
+    let seed = req.seed.unwrap_or(0);
+
+    let scenario = OptimizationScenario {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        simulation_count: num_sims as usize,
+        seed,
+        profile_id,
+        unknown_officers: req.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+        ..OptimizationScenario::default()
+    };
+    let report = attacker_stat_sensitivity(registry, &scenario, &crew, delta_pct);
+
+    let response = StatSensitivityResponse {
+        status: "ok",
+        ship: req.ship.clone(),
+        hostile: req.hostile.clone(),
+        num_sims,
+        seed,
+        report,
+    };
+    serde_json::to_string_pretty(&response).map_err(AnalyzeError::Parse)
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct SweepRequest {
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    /// Any hostile of the family to sweep (its id from `/api/hostiles`).
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    pub ship_tier: Option<u32>,
+    pub ship_level: Option<u32>,
+    pub crew: SimulateCrew,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub num_sims: Option<u32>,
+    pub seed: Option<u64>,
+    /// Lowest hostile level swept. None = the family's lowest.
+    pub min_level: Option<u32>,
+    /// Highest hostile level swept. None = the family's highest.
+    pub max_level: Option<u32>,
+    /// Win rate that counts as farmable (0–1). None = 0.95.
+    #[schemars(range(max = 1))]
+    pub target_win_rate: Option<f64>,
+    /// Reject an unknown ship (404 with near-miss ids). None = true. The hostile must always be
+    /// in the hostile index.
+    pub strict: Option<bool>,
+    /// Crew officers that match no officer data
+    /// (see [super::SimulateRequest::unknown_officers]). None = strict.
+    pub unknown_officers: Option<UnknownOfficerMode>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SweepResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub num_sims: u32,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub report: SweepReport,
+}
+
+/// POST /api/analyze/sweep: a fixed crew's win rate against each level of a hostile family.
+pub fn sweep_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, AnalyzeError> {
+    let mut req: SweepRequest = serde_json::from_str(body).map_err(AnalyzeError::Parse)?;
+    if req.ship.trim().is_empty() || req.hostile.trim().is_empty() {
+        return Err(AnalyzeError::Validation(
+            "ship and hostile are required".to_string(),
+        ));
+    }
+    let num_sims = req.num_sims.unwrap_or_else(default_sims);
+    if num_sims == 0 || num_sims > MAX_SIMS {
+        return Err(AnalyzeError::Validation(format!(
+            "num_sims must be between 1 and {MAX_SIMS}"
+        )));
+    }
+    let min_level = req.min_level.unwrap_or(0);
+    let max_level = req.max_level.unwrap_or(u32::MAX);
+    if min_level > max_level {
+        return Err(AnalyzeError::Validation(
+            "min_level must not exceed max_level".to_string(),
+        ));
+    }
+    let target_win_rate = req.target_win_rate.unwrap_or(DEFAULT_SWEEP_TARGET_WIN_RATE);
+    if !(target_win_rate > 0.0 && target_win_rate <= 1.0) {
+        return Err(AnalyzeError::Validation(
+            "target_win_rate must be greater than 0 and at most 1".to_string(),
+        ));
+    }
+    let crew =
+        crew_candidate_from_request(registry, &req.crew).map_err(AnalyzeError::Validation)?;
+    resolve_known_ids(
+        registry,
+        req.strict,
+        &mut req.ship,
+        req.ship_tier,
+        req.ship_level,
+        None,
+    )
+    .map_err(AnalyzeError::UnknownIds)?;
+    check_known_officers(registry, req.unknown_officers, &crew)
+        .map_err(AnalyzeError::UnknownIds)?;
+    if let Ok(id) = registry.hostile_id_for(&req.hostile) {
+        req.hostile = id;
+    }
+    let seed = req.seed.unwrap_or(0);
+
+    let scenario = OptimizationScenario {
+        ship: &req.ship,
+        hostile: &req.hostile,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        simulation_count: num_sims as usize,
+        seed,
+        profile_id,
+        unknown_officers: req.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+        ..OptimizationScenario::default()
+    };
+    let report = hostile_level_sweep(
+        registry,
+        &scenario,
+        &crew,
+        min_level,
+        max_level,
+        target_win_rate,
+    )
+    .ok_or_else(|| {
+        AnalyzeError::Validation(format!(
+            "hostile '{}' is not in the hostile index",
+            req.hostile
+        ))
+    })?;
+
+    let response = SweepResponse {
+        status: "ok",
+        ship: req.ship.clone(),
+        num_sims,
+        seed,
+        report,
+    };
+    serde_json::to_string_pretty(&response).map_err(AnalyzeError::Parse)
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct BracketRequest {
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    /// Saved preset ids (`/api/presets`) of the profile; each fights on its own ship.
+    #[schemars(length(min = 2, max = "MAX_BRACKET_PRESETS"))]
+    pub presets: Vec<String>,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub num_sims: Option<u32>,
+    pub seed: Option<u64>,
+    /// Reject unknown ships or hostile (404 with near-miss ids). None = true.
+    pub strict: Option<bool>,
+    /// Preset officers that match no officer data
+    /// (see [super::SimulateRequest::unknown_officers]). None = strict.
+    pub unknown_officers: Option<UnknownOfficerMode>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BracketResponse {
+    pub status: &'static str,
+    pub hostile: String,
+    pub num_sims: u32,
+    pub seed: u64,
+    #[serde(flatten)]
+    pub report: BracketReport,
+}
+
+/// POST /api/analyze/bracket: saved presets round-robin against one hostile.
+pub fn bracket_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, AnalyzeError> {
+    let mut req: BracketRequest = serde_json::from_str(body).map_err(AnalyzeError::Parse)?;
+    if req.hostile.trim().is_empty() {
+        return Err(AnalyzeError::Validation("hostile is required".to_string()));
+    }
+    if req.presets.len() < 2 || req.presets.len() > MAX_BRACKET_PRESETS as usize {
+        return Err(AnalyzeError::Validation(format!(
+            "presets must list between 2 and {MAX_BRACKET_PRESETS} preset ids"
+        )));
+    }
+    let num_sims = req.num_sims.unwrap_or_else(default_sims);
+    if num_sims == 0 || num_sims > MAX_SIMS {
+        return Err(AnalyzeError::Validation(format!(
+            "num_sims must be between 1 and {MAX_SIMS}"
+        )));
+    }
+
+    let mut entrants = Vec::with_capacity(req.presets.len());
+    for (i, id) in req.presets.iter().enumerate() {
+        let preset: Preset = preset_get_payload(id, profile_id)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+            .map_err(|e| AnalyzeError::Validation(format!("preset '{id}': {e}")))?;
+        let seats = |officers: &Option<Vec<String>>| {
+            officers.as_ref().map(|ids| {
+                ids.iter()
+                    .map(|id| Some(id.clone()).filter(|id| !id.is_empty()))
+                    .collect()
+            })
+        };
+        let crew = SimulateCrew {
+            captain: preset.crew.captain.clone(),
+            bridge: seats(&preset.crew.bridge),
+            below_deck: seats(&preset.crew.below_deck),
+        };
+        let crew = crew_candidate_from_request(registry, &crew)
+            .map_err(|e| AnalyzeError::Validation(format!("preset '{id}': {e}")))?;
+        let mut ship = preset.ship;
+        let hostile = (i == 0).then_some(&mut req.hostile);
+        resolve_known_ids(registry, req.strict, &mut ship, None, None, hostile)
+            .map_err(AnalyzeError::UnknownIds)?;
+        check_known_officers(registry, req.unknown_officers, &crew)
+            .map_err(AnalyzeError::UnknownIds)?;
+        entrants.push(BracketEntrant {
+            name: preset.name,
+            ship,
+            crew,
+        });
+    }
+    let seed = req.seed.unwrap_or(0);
+
+    let scenario = OptimizationScenario {
+        hostile: &req.hostile,
+        simulation_count: num_sims as usize,
+        seed,
+        profile_id,
+        unknown_officers: req.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+        ..OptimizationScenario::default()
+    };
+    let report = run_bracket(registry, &scenario, &entrants);
+
+    let response = BracketResponse {
+        status: "ok",
+        hostile: req.hostile.clone(),
+        num_sims,
+        seed,
+        report,
+    };
+    serde_json::to_string_pretty(&response).map_err(AnalyzeError::Parse)
+}
+
+#[derive(Debug)]
+pub enum AnalyzeError {
+    Parse(serde_json::Error),
+    Validation(String),
+    UnknownIds(UnknownIdsResponse),
+}
+
+impl fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Validation(m) => write!(f, "{m}"),
+            Self::UnknownIds(unknown) => write!(f, "{unknown}"),
+        }
+    }
+}
+
+impl std::error::Error for AnalyzeError {}
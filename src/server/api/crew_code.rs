@@ -0,0 +1,109 @@
+//! Crew code endpoints: `POST /api/crewcode` packs a crew, ship and the profile's bonuses into a
+//! shareable code ([crate::data::crew_code]); `GET /api/crewcode/{code}` turns a code back into a
+//! scenario file body that `POST /api/simulate/scenario` runs as is.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::data::crew_code::{decode_crew_code, encode_crew_code, CrewCode};
+use crate::data::profile::PlayerBonuses;
+
+use super::{
+    read_stored_profile, resolve_profile_id, ScenarioFile, ScenarioProfileOverrides, SimulateCrew,
+    SimulateError,
+};
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CrewCodeRequest {
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    pub ship_tier: Option<u32>,
+    pub ship_level: Option<u32>,
+    /// Optional: a code without a hostile needs `?hostile=` when decoded.
+    pub hostile: Option<String>,
+    pub crew: SimulateCrew,
+    /// Pack the request profile's bonuses into the code. None = true.
+    pub include_profile: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CrewCodeResponse {
+    pub status: &'static str,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CrewCodeDecodeResponse {
+    pub status: &'static str,
+    /// Body for `POST /api/simulate/scenario`; the sharer's bonuses are its profile overrides.
+    pub scenario: ScenarioFile,
+}
+
+pub fn crew_code_encode_payload(
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, SimulateError> {
+    let req: CrewCodeRequest = serde_json::from_str(body).map_err(SimulateError::Parse)?;
+    if req.ship.trim().is_empty() {
+        return Err(SimulateError::Validation("ship: is required".to_string()));
+    }
+    let bonuses = if req.include_profile.unwrap_or(true) {
+        let stored = read_stored_profile(&resolve_profile_id(profile_id));
+        PlayerBonuses::migrate(&stored.bonuses).0
+    } else {
+        PlayerBonuses::default()
+    };
+    let code = encode_crew_code(&CrewCode {
+        ship: req.ship,
+        ship_tier: req.ship_tier,
+        ship_level: req.ship_level,
+        hostile: req.hostile.filter(|h| !h.trim().is_empty()),
+        captain: req.crew.captain,
+        bridge: req.crew.bridge.unwrap_or_default(),
+        below_deck: req.crew.below_deck.unwrap_or_default(),
+        bonuses,
+    });
+    serde_json::to_string_pretty(&CrewCodeResponse { status: "ok", code })
+        .map_err(SimulateError::Parse)
+}
+
+/// Decodes `code`; `hostile` replaces the code's hostile (and is required when it has none).
+pub fn crew_code_decode_payload(
+    code: &str,
+    hostile: Option<&str>,
+) -> Result<String, SimulateError> {
+    let decoded = decode_crew_code(code).map_err(|e| SimulateError::Validation(e.to_string()))?;
+    let hostile = hostile.filter(|h| !h.trim().is_empty()).map(str::to_string);
+    let Some(hostile) = hostile.or(decoded.hostile) else {
+        return Err(SimulateError::Validation(
+            "hostile: the code names no hostile; pass ?hostile=<id>".to_string(),
+        ));
+    };
+    let scenario = ScenarioFile {
+        name: None,
+        ship: decoded.ship,
+        ship_tier: decoded.ship_tier,
+        ship_level: decoded.ship_level,
+        hostile,
+        crew: SimulateCrew {
+            captain: decoded.captain,
+            bridge: Some(decoded.bridge),
+            below_deck: Some(decoded.below_deck),
+        },
+        profile: None,
+        profile_overrides: ScenarioProfileOverrides {
+            bonuses: decoded.bonuses,
+            officer_traits: None,
+            officer_stats: None,
+        },
+        rules: Default::default(),
+        objective: Default::default(),
+        num_sims: None,
+        seed: None,
+        strict: None,
+        unknown_officers: None,
+    };
+    serde_json::to_string_pretty(&CrewCodeDecodeResponse { status: "ok", scenario })
+        .map_err(SimulateError::Parse)
+}
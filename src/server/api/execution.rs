@@ -1,35 +1,60 @@
 //! Execution layer: run optimize, job store, and response types.
 
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use tokio::sync::OwnedSemaphorePermit;
 
+use crate::combat::{AttackerCondition, SimulationSnapshot};
 use crate::data::data_registry::DataRegistry;
 use crate::data::heuristics::{
     expand_crews, load_seed_file, BelowDecksStrategy, DEFAULT_HEURISTICS_DIR,
 };
-use crate::optimizer::crew_generator::{CrewCandidate, BELOW_DECKS_SLOTS};
+use crate::data::officer::UnknownOfficerMode;
+use crate::data::power::PowerEstimate;
+use crate::data::ship::RepairCost;
+use crate::optimizer::crew_generator::{
+    build_officer_pools_from_registry, resolve_ship_type, CandidateStrategy, CrewCandidate,
+    CrewGenerator, CrewSlots,
+};
+use crate::optimizer::explain::{explain_crew, CrewExplanation, DEFAULT_EXPLAIN_TOP};
+use crate::optimizer::memory::{MemoryTracker, MemoryUsage};
+use crate::optimizer::genetic::{FitnessCacheStats, GeneticConfig, GeneticOverrides};
 use crate::optimizer::monte_carlo::{
-    run_monte_carlo_parallel_with_registry,
-    scenario::build_shared_scenario_data_from_registry,
-    SimulationResult,
+    run_monte_carlo_with_shared,
+    scenario::{build_shared_scenario_data_from_registry, SharedScenarioData},
+    snapshot_candidate_fight, SimulationResult,
 };
-use crate::optimizer::ranking::{rank_results, RankedCrewResult};
+use crate::optimizer::ranking::{rank_results_for, RankedCrewResult, ScenarioType};
+use crate::optimizer::tiered::StageProgress;
 use crate::optimizer::{
-    optimize_scenario_with_progress_with_registry, OptimizationScenario, OptimizerStrategy,
+    auto_strategy, optimize_scenario_with_progress_with_registry, OptimizationScenario,
+    OptimizePhase, OptimizerStrategy, AUTO_AGREEMENT_MAX_SIMS, AUTO_EXHAUSTIVE_MAX_CANDIDATES,
+};
+use crate::parallel::{
+    record_observed_sims_per_second, CancelToken, Priority, ThroughputTracker, WorkerPool,
 };
+use crate::server::history::record_optimize;
+use crate::server::loadouts::excluded_officers;
+use crate::server::notify::{post_webhook, OptimizeWebhookSummary, WebhookPolicy};
 
 use super::requests::{
-    parse_below_decks_strategy, parse_strategy, OptimizePayloadError, OptimizeRequest,
-    DEFAULT_SIMS,
+    is_auto_strategy, parse_below_decks_strategy, parse_scenario_type, parse_strategy,
+    OptimizePayloadError,
+    OptimizeRequest, UnknownIdIssue, UnknownIdsResponse,
+    default_sims, DEFAULT_WAVES,
 };
+use super::check_known_officers;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct CrewRecommendation {
+    /// Stable crew identifier ([crate::optimizer::ranking::crew_stable_id]) for comparing runs.
+    pub crew_id: String,
     pub captain: String,
     pub bridge: Vec<String>,
     pub below_decks: Vec<String>,
@@ -37,17 +62,68 @@ pub struct CrewRecommendation {
     pub stall_rate: f64,
     pub loss_rate: f64,
     pub avg_hull_remaining: f64,
+    pub avg_survival_share: f64,
+    pub avg_damage_dealt: f64,
+    pub survival_rate: f64,
+    pub avg_waves_cleared: f64,
+    pub median_rounds_to_kill: Option<u32>,
+    pub p95_rounds_to_kill: Option<u32>,
+    pub avg_damage_per_round: f64,
+    /// Mean share of full hull missing after the fight (1.0 = destroyed).
+    pub avg_hull_lost: f64,
+    /// Expected repair after one fight: the ship tier's full repair scaled by `avg_hull_lost`
+    /// ([RepairCost::scaled]). Absent when the ship data has no repair cost.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_repair: Option<RepairCost>,
+    /// points_per_hour scenarios only: expected reward per hour of grinding
+    /// ([crate::optimizer::ranking::kills_per_hour] × the hostile's points per kill).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points_per_hour: Option<f64>,
+    /// Top `explain_top` recommendations only: why the crew scores as it does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<CrewExplanation>,
+    /// Power the crew would show on the ship ([crate::data::power]); absent when the ship did not
+    /// resolve from data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power: Option<PowerEstimate>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct ScenarioSummary {
     pub ship: String,
     pub hostile: String,
     pub sims: u32,
     pub seed: u64,
+    pub scenario_type: ScenarioType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mining_rounds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waves: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wave_repair: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attacker_hull_pct: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attacker_shield_pct: Option<f64>,
+    /// points_per_hour scenarios only: reward per kill from the hostile record.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points_per_kill: Option<f64>,
+    /// Officers the optimizer chose from per seat; absent for heuristics-only runs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub officer_pools: Option<OfficerPoolSizes>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// Size of each officer pool the optimizer searched, after the roster filter.
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema)]
+pub struct OfficerPoolSizes {
+    pub captains: usize,
+    pub bridge: usize,
+    pub below_decks: usize,
+    /// Whether the below-decks pool was limited to officers with a below-decks ability.
+    pub only_below_decks_with_ability: bool,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct OptimizeResponse {
     pub status: &'static str,
     pub engine: &'static str,
@@ -55,61 +131,216 @@ pub struct OptimizeResponse {
     pub recommendations: Vec<CrewRecommendation>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration_ms: Option<u64>,
-    pub notes: Vec<&'static str>,
+    /// `strategy: "auto"` only: which search ran and how it was chosen.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_strategy: Option<AutoStrategyReport>,
+    pub notes: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub warnings: Vec<String>,
 }
 
-/// Load heuristics seeds and expand them into CrewCandidates.
+/// How `strategy: "auto"` resolved for one run.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AutoStrategyReport {
+    /// "exhaustive" or "genetic".
+    pub chosen: &'static str,
+    /// Exhaustive candidate count the choice was made from (after `max_candidates`).
+    pub estimated_candidates: usize,
+    /// Exhaustive runs at or below this many candidates.
+    pub exhaustive_max_candidates: usize,
+    /// Set when exhaustive ran and a GA run was cheap enough to cross-check it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agreement: Option<StrategyAgreement>,
+}
+
+/// Top exhaustive crew against the GA's best; both scored with the request's sims and seed.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StrategyAgreement {
+    pub exhaustive_crew_id: String,
+    pub exhaustive_score: f32,
+    pub genetic_crew_id: String,
+    pub genetic_score: f32,
+    /// Whether both searches put the same crew first.
+    pub agrees: bool,
+}
+
+/// Load heuristics seeds and expand them into CrewCandidates, filling `slots.below_decks` seats.
 pub fn load_heuristics_candidates(
     registry: &DataRegistry,
     seed_names: &[String],
     bd_strategy: BelowDecksStrategy,
+    slots: CrewSlots,
 ) -> Vec<CrewCandidate> {
     let canonical_names: Vec<String> = registry.officers().iter().map(|o| o.name.clone()).collect();
     seed_names
         .iter()
         .flat_map(|name| {
             let parsed = load_seed_file(name, DEFAULT_HEURISTICS_DIR, Some(&canonical_names));
-            let candidates = expand_crews(parsed, BELOW_DECKS_SLOTS, bd_strategy);
-            candidates.into_iter().map(|c| CrewCandidate {
-                captain: c.captain,
-                bridge: c.bridge,
-                below_decks: c.below_decks,
-            })
+            let candidates = expand_crews(parsed, slots.below_decks, bd_strategy);
+            candidates
+                .into_iter()
+                .map(|c| CrewCandidate::new(c.captain, c.bridge, c.below_decks))
         })
         .collect()
 }
 
+/// Strict unknown-officer check ([check_known_officers]) over the crews `heuristics_seeds`
+/// expand to, so a seed naming an officer that matches no data is rejected up front instead of
+/// seated with no effect.
+pub fn check_seed_officers(
+    registry: &DataRegistry,
+    request: &OptimizeRequest,
+) -> Result<(), UnknownIdsResponse> {
+    let seeds = request.heuristics_seeds.as_deref().unwrap_or(&[]);
+    if seeds.is_empty() || request.unknown_officers.is_some_and(|m| m != UnknownOfficerMode::Strict)
+    {
+        return Ok(());
+    }
+    let bd_strategy = parse_below_decks_strategy(request.below_decks_strategy.as_ref());
+    let slots = request_crew_slots(registry, request);
+    let crews = load_heuristics_candidates(registry, seeds, bd_strategy, slots);
+    check_seed_crews(registry, request.unknown_officers, &crews)
+}
+
+/// [check_known_officers] over every seed crew; each unknown officer is reported once, under
+/// `heuristics_seeds`.
+fn check_seed_crews(
+    registry: &DataRegistry,
+    mode: Option<UnknownOfficerMode>,
+    crews: &[CrewCandidate],
+) -> Result<(), UnknownIdsResponse> {
+    let mut errors: Vec<UnknownIdIssue> = Vec::new();
+    for crew in crews {
+        let Err(unknown) = check_known_officers(registry, mode, crew) else {
+            continue;
+        };
+        for issue in unknown.errors {
+            if !errors.iter().any(|known| known.value == issue.value) {
+                errors.push(UnknownIdIssue {
+                    field: "heuristics_seeds",
+                    ..issue
+                });
+            }
+        }
+    }
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(UnknownIdsResponse {
+        status: "error",
+        message: "Unknown officer",
+        errors,
+    })
+}
+
 /// Metadata from the shared optimize gather path (sync + async jobs).
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 struct OptimizeGatherMeta {
     strategy: OptimizerStrategy,
+    scenario_type: ScenarioType,
     is_seeded_genetic: bool,
     heuristics_only: bool,
     heuristics_seeds_nonempty: bool,
     using_placeholder_combatants: bool,
+    /// Set for [ScenarioType::PointsPerHour]; see [crate::data::hostile::HostileRecord::points_per_kill].
+    points_per_kill: Option<f64>,
+    officer_pools: Option<OfficerPoolSizes>,
+    /// Genetic runs that were simulated (not served from the result cache).
+    fitness_cache: Option<FitnessCacheStats>,
+    auto_strategy: Option<AutoStrategyReport>,
+    /// Peak buffers of the run; empty when every result came from the result cache.
+    memory: MemoryUsage,
 }
 
+/// Pool for background optimize jobs: between batches they yield to interactive simulate calls.
+const OPTIMIZE_JOB_POOL: WorkerPool =
+    WorkerPool::with_workers(0).with_priority(Priority::Background);
+
 /// Progress / cancellation hooks for optimize. Sync path uses [`OptimizeProgressSink::None`].
 enum OptimizeProgressSink {
     None,
     Job {
         job_id: String,
-        cancel: Arc<AtomicBool>,
+        cancel: CancelToken,
         heuristics_seeds_nonempty: bool,
         /// Filled by [`gather_optimize_simulation_results`] once candidates are loaded.
         is_seeded_genetic: bool,
+        /// Filled with the scenario so provisional leaders report points per hour.
+        points_per_kill: Option<f64>,
+        /// Wall-clock throughput for the optimizer phase; reset when that phase starts.
+        throughput: ThroughputTracker,
+        /// Filled with the run's tracker so status reports memory while the job runs.
+        memory: Option<Arc<MemoryTracker>>,
     },
 }
 
+/// Approximate sims behind one optimizer progress unit (a crew, or a GA generation).
+fn sims_per_progress_unit(
+    strategy: OptimizerStrategy,
+    sims: u32,
+    is_seeded_genetic: bool,
+    overrides: &GeneticOverrides,
+) -> u64 {
+    match strategy {
+        OptimizerStrategy::Genetic => {
+            let config = if is_seeded_genetic {
+                GeneticConfig::seeded(Vec::new())
+            } else {
+                GeneticConfig::default()
+            };
+            let config = config.with_overrides(overrides);
+            (config.islands.max(1) * config.population_size * config.sims_per_eval) as u64
+        }
+        OptimizerStrategy::Exhaustive | OptimizerStrategy::Tiered | OptimizerStrategy::TwoPhase => {
+            sims as u64
+        }
+    }
+}
+
+/// Whether an auto run that chose exhaustive can afford a GA cross-check: both searches must fit
+/// in [AUTO_AGREEMENT_MAX_SIMS].
+fn agreement_check_is_cheap(candidates: usize, sims: u32, overrides: &GeneticOverrides) -> bool {
+    let config = GeneticConfig::default().with_overrides(overrides);
+    let genetic_sims = (config.islands.max(1)
+        * config.population_size
+        * config.generations
+        * config.sims_per_eval) as u64;
+    candidates as u64 * u64::from(sims) <= AUTO_AGREEMENT_MAX_SIMS
+        && genetic_sims <= AUTO_AGREEMENT_MAX_SIMS
+}
+
+fn strategy_agreement(
+    exhaustive: &[RankedCrewResult],
+    genetic: &[RankedCrewResult],
+) -> Option<StrategyAgreement> {
+    let (exhaustive_top, genetic_top) = (exhaustive.first()?, genetic.first()?);
+    let exhaustive_crew_id = exhaustive_top.stable_id();
+    let genetic_crew_id = genetic_top.stable_id();
+    Some(StrategyAgreement {
+        agrees: exhaustive_crew_id == genetic_crew_id,
+        exhaustive_crew_id,
+        exhaustive_score: exhaustive_top.score.value,
+        genetic_crew_id,
+        genetic_score: genetic_top.score.value,
+    })
+}
+
 impl OptimizeProgressSink {
+    /// Pool optimizer work runs on: background for jobs, the default for synchronous requests.
+    fn pool(&self) -> WorkerPool {
+        match self {
+            Self::None => WorkerPool::default(),
+            Self::Job { .. } => OPTIMIZE_JOB_POOL,
+        }
+    }
+
     fn on_heuristics_start(&self, h_total: u32) {
         let Self::Job { job_id, .. } = self else {
             return;
         };
         if let Ok(mut map) = optimize_jobs().lock() {
             if let Some(state) = map.get_mut(job_id) {
+                state.phase = Some(OptimizePhase::Simulating);
                 state.total_crews = h_total;
             }
         }
@@ -127,7 +358,20 @@ impl OptimizeProgressSink {
         }
     }
 
-    fn on_optimize_progress(&mut self, crews_done: u32, total_crews: u32) -> bool {
+    fn start_optimizer_phase(&mut self, sims_per_unit: u64) {
+        if let Self::Job { throughput, .. } = self {
+            *throughput = ThroughputTracker::new(sims_per_unit);
+        }
+    }
+
+    fn on_optimize_progress(
+        &mut self,
+        phase: OptimizePhase,
+        crews_done: u32,
+        total_crews: u32,
+        stage: Option<StageProgress>,
+        leaders: &[RankedCrewResult],
+    ) -> bool {
         match self {
             Self::None => true,
             Self::Job {
@@ -135,8 +379,11 @@ impl OptimizeProgressSink {
                 cancel,
                 heuristics_seeds_nonempty,
                 is_seeded_genetic,
+                points_per_kill,
+                throughput,
+                memory,
             } => {
-                if cancel.load(Ordering::Relaxed) {
+                if !OPTIMIZE_JOB_POOL.checkpoint(cancel) {
                     return false;
                 }
                 let base_progress = if *heuristics_seeds_nonempty && !*is_seeded_genetic {
@@ -151,11 +398,29 @@ impl OptimizeProgressSink {
                         * (100.0 - base_progress as f64);
                     (base_progress as f64 + pct).round().min(100.0) as u8
                 };
+                let sample = throughput.sample(crews_done, total_crews);
                 if let Ok(mut map) = optimize_jobs().lock() {
                     if let Some(state) = map.get_mut(job_id) {
+                        if progress != state.progress {
+                            tracing::debug!(progress, crews_done, total_crews, "optimize progress");
+                        }
                         state.progress = progress;
                         state.crews_done = crews_done;
                         state.total_crews = total_crews;
+                        state.phase = Some(phase);
+                        state.stage = stage;
+                        state.memory = memory.as_ref().map(|memory| memory.usage());
+                        if !leaders.is_empty() {
+                            state.provisional = leaders
+                                .iter()
+                                .cloned()
+                                .map(|leader| crew_recommendation(leader, *points_per_kill))
+                                .collect();
+                        }
+                        if let Some(sample) = sample {
+                            state.sims_per_second = Some(sample.sims_per_second);
+                            state.eta_seconds = sample.eta_seconds;
+                        }
                     }
                 }
                 true
@@ -166,42 +431,148 @@ impl OptimizeProgressSink {
     fn job_cancelled(&self) -> bool {
         match self {
             Self::None => false,
-            Self::Job { cancel, .. } => cancel.load(Ordering::Relaxed),
+            Self::Job { cancel, .. } => cancel.is_cancelled(),
         }
     }
 }
 
 fn ranked_crew_to_simulation_result(r: RankedCrewResult) -> SimulationResult {
     SimulationResult {
-        candidate: CrewCandidate {
-            captain: r.captain,
-            bridge: r.bridge,
-            below_decks: r.below_decks,
-        },
+        candidate: CrewCandidate::new(r.captain, r.bridge, r.below_decks),
         win_rate: r.win_rate,
         stall_rate: r.stall_rate,
         loss_rate: r.loss_rate,
         avg_hull_remaining: r.avg_hull_remaining,
+        avg_survival_share: r.avg_survival_share,
+        avg_damage_dealt: r.avg_damage_dealt,
+        survival_rate: r.survival_rate,
+        avg_waves_cleared: r.avg_waves_cleared,
+        median_rounds_to_kill: r.median_rounds_to_kill,
+        p95_rounds_to_kill: r.p95_rounds_to_kill,
+        avg_damage_per_round: r.avg_damage_per_round,
+        avg_hull_lost: r.avg_hull_lost,
+    }
+}
+
+/// Wave count and repair share for the request; waves default to [DEFAULT_WAVES] for
+/// `scenario_type: "waves"` and to a single fight otherwise.
+fn wave_settings(request: &OptimizeRequest, scenario_type: ScenarioType) -> (u32, f64) {
+    if scenario_type != ScenarioType::Waves {
+        return (0, 0.0);
+    }
+    (
+        request.waves.unwrap_or(DEFAULT_WAVES),
+        request.wave_repair.unwrap_or(0.0),
+    )
+}
+
+/// Hull and shields the request's ship starts with; unset shares are full.
+fn attacker_condition(request: &OptimizeRequest) -> AttackerCondition {
+    AttackerCondition {
+        hull_pct: request.attacker_hull_pct.unwrap_or(1.0),
+        shield_pct: request.attacker_shield_pct.unwrap_or(1.0),
     }
 }
 
 /// Shared Monte Carlo + optimizer scenario execution. Sync and background jobs use the same logic.
+/// Registry-backed scenario data for `request`, with its fight modifiers (mining delay, waves).
+fn request_shared_scenario(
+    registry: &DataRegistry,
+    request: &OptimizeRequest,
+    profile_id: Option<&str>,
+) -> SharedScenarioData {
+    let scenario_type = parse_scenario_type(request.scenario_type.as_ref()).unwrap_or_default();
+    let (waves, wave_repair) = wave_settings(request, scenario_type);
+    build_shared_scenario_data_from_registry(
+        registry,
+        &request.ship,
+        &request.hostile,
+        request.ship_tier,
+        request.ship_level,
+        profile_id,
+    )
+    .with_attacker_delay(request.mining_rounds.unwrap_or(0))
+    .with_initiative(scenario_type.initiative())
+    .with_waves(waves, wave_repair)
+    .with_attacker_condition(attacker_condition(request))
+}
+
+/// Seats on the request's ship at its tier and level.
+fn request_crew_slots(registry: &DataRegistry, request: &OptimizeRequest) -> CrewSlots {
+    registry
+        .resolve_ship_with_tier_level(&request.ship, request.ship_tier, request.ship_level)
+        .map_or_else(CrewSlots::default, |record| CrewSlots::for_ship(&record))
+}
+
+/// Fills [CrewRecommendation::power] and [CrewRecommendation::expected_repair] for every
+/// recommendation and [CrewRecommendation::explanation] for the first `explain_top`.
+fn annotate_recommendations(
+    registry: &DataRegistry,
+    request: &OptimizeRequest,
+    profile_id: Option<&str>,
+    response: &mut OptimizeResponse,
+) {
+    if response.recommendations.is_empty() {
+        return;
+    }
+    let top = request.explain_top.unwrap_or(DEFAULT_EXPLAIN_TOP) as usize;
+    let shared = request_shared_scenario(registry, request, profile_id);
+    let sims = request.sims.unwrap_or_else(default_sims) as usize;
+    let seed = request.seed.unwrap_or(0);
+    let repair_cost = shared.ship_rec.as_ref().and_then(|ship| ship.repair_cost.as_ref());
+    for (i, rec) in response.recommendations.iter_mut().enumerate() {
+        let candidate = CrewCandidate::new(
+            rec.captain.clone(),
+            rec.bridge.clone(),
+            rec.below_decks.clone(),
+        );
+        rec.power = shared.crew_power(&candidate);
+        rec.expected_repair = repair_cost.map(|cost| cost.scaled(rec.avg_hull_lost));
+        if i < top {
+            rec.explanation = Some(explain_crew(&shared, &candidate, seed, sims));
+        }
+    }
+}
+
 fn gather_optimize_simulation_results(
     registry: &DataRegistry,
     request: &OptimizeRequest,
     profile_id: Option<&str>,
     sink: &mut OptimizeProgressSink,
 ) -> Result<(Vec<SimulationResult>, OptimizeGatherMeta), ()> {
-    let sims = request.sims.unwrap_or(DEFAULT_SIMS);
+    let sims = request.sims.unwrap_or_else(default_sims);
     let seed = request.seed.unwrap_or(0);
-    let strategy = parse_strategy(request.strategy.as_ref());
+    let scenario_type = parse_scenario_type(request.scenario_type.as_ref()).unwrap_or_default();
+    let mining_rounds = request.mining_rounds.unwrap_or(0);
+    let (waves, wave_repair) = wave_settings(request, scenario_type);
     let heuristics_only = request.heuristics_only.unwrap_or(false);
+    let only_below_decks_with_ability = request.prioritize_below_decks_ability.unwrap_or(false);
+    let excluded_officers = excluded_officers(request.exclude_loadouts.as_deref().unwrap_or(&[]));
+    let auto_candidates = (is_auto_strategy(request.strategy.as_ref()) && !heuristics_only)
+        .then(|| {
+            CrewGenerator::with_strategy(CandidateStrategy {
+                max_candidates: request.max_candidates.map(|n| n as usize),
+                only_below_decks_with_ability,
+                excluded_officers: excluded_officers.clone(),
+                ..CandidateStrategy::default()
+            })
+            .count_candidates_from_registry(
+                registry,
+                &request.ship,
+                &request.hostile,
+                0,
+                profile_id,
+            )
+        });
+    let strategy = auto_candidates
+        .map_or_else(|| parse_strategy(request.strategy.as_ref()), auto_strategy);
     let bd_strategy = parse_below_decks_strategy(request.below_decks_strategy.as_ref());
     let heuristics_seeds = request.heuristics_seeds.as_deref().unwrap_or(&[]);
     let heuristics_seeds_nonempty = !heuristics_seeds.is_empty();
 
     let h_candidates = if heuristics_seeds_nonempty {
-        load_heuristics_candidates(registry, heuristics_seeds, bd_strategy)
+        let slots = request_crew_slots(registry, request);
+        load_heuristics_candidates(registry, heuristics_seeds, bd_strategy, slots)
     } else {
         Vec::new()
     };
@@ -216,39 +587,81 @@ fn gather_optimize_simulation_results(
         *sink_sg = is_seeded_genetic;
     }
 
-    let using_placeholder_combatants = build_shared_scenario_data_from_registry(
-        registry,
-        &request.ship,
-        &request.hostile,
-        request.ship_tier,
-        request.ship_level,
-        profile_id,
-    )
-    .using_placeholder_combatants;
+    let shared = request_shared_scenario(registry, request, profile_id);
+    let using_placeholder_combatants = shared.using_placeholder_combatants;
+    let memory = Arc::new(MemoryTracker::new(crate::server::settings::current().memory_budget()));
+    if let OptimizeProgressSink::Job {
+        memory: sink_memory,
+        ..
+    } = sink
+    {
+        *sink_memory = Some(memory.clone());
+    }
+
+    let points_per_kill = (scenario_type == ScenarioType::PointsPerHour).then(|| {
+        registry
+            .resolve_hostile(&request.hostile)
+            .map_or(1.0, |hostile| hostile.points_per_kill())
+    });
+    if let OptimizeProgressSink::Job {
+        points_per_kill: sink_points,
+        ..
+    } = sink
+    {
+        *sink_points = points_per_kill;
+    }
 
-    let meta = OptimizeGatherMeta {
+    let officer_pools = (!heuristics_only)
+        .then(|| {
+            build_officer_pools_from_registry(
+                registry,
+                only_below_decks_with_ability,
+                profile_id,
+                resolve_ship_type(&request.ship),
+            )
+            .and_then(|pools| pools.without(&excluded_officers))
+        })
+        .map(|pools| {
+            let pools = pools.as_ref();
+            OfficerPoolSizes {
+                captains: pools.map_or(0, |p| p.captains.len()),
+                bridge: pools.map_or(0, |p| p.bridge.len()),
+                below_decks: pools.map_or(0, |p| p.below_decks.len()),
+                only_below_decks_with_ability,
+            }
+        });
+
+    let mut meta = OptimizeGatherMeta {
         strategy,
+        scenario_type,
         is_seeded_genetic,
         heuristics_only,
         heuristics_seeds_nonempty,
         using_placeholder_combatants,
+        points_per_kill,
+        officer_pools,
+        fitness_cache: None,
+        auto_strategy: auto_candidates.map(|estimated_candidates| AutoStrategyReport {
+            chosen: if strategy == OptimizerStrategy::Genetic {
+                "genetic"
+            } else {
+                "exhaustive"
+            },
+            estimated_candidates,
+            exhaustive_max_candidates: AUTO_EXHAUSTIVE_MAX_CANDIDATES,
+            agreement: None,
+        }),
+        memory: MemoryUsage::default(),
     };
 
     let mut all_results: Vec<SimulationResult> =
         if heuristics_seeds_nonempty && !is_seeded_genetic {
             let h_total = h_candidates.len() as u32;
             sink.on_heuristics_start(h_total);
-            let (results, _) = run_monte_carlo_parallel_with_registry(
-                registry,
-                &request.ship,
-                &request.hostile,
-                request.ship_tier,
-                request.ship_level,
-                &h_candidates,
-                sims as usize,
-                seed,
-                profile_id,
-            );
+            memory.hold_candidates(h_candidates.len());
+            let results =
+                run_monte_carlo_with_shared(shared, &h_candidates, sims as usize, seed, true);
+            memory.hold_results(results.len());
             sink.on_heuristics_complete(heuristics_only, h_total);
             results
         } else {
@@ -265,24 +678,63 @@ fn gather_optimize_simulation_results(
             seed,
             max_candidates: request.max_candidates.map(|n| n as usize),
             strategy,
-            only_below_decks_with_ability: request.prioritize_below_decks_ability.unwrap_or(false),
+            only_below_decks_with_ability,
             seed_population: if is_seeded_genetic {
                 h_candidates.clone()
             } else {
                 Vec::new()
             },
             profile_id,
-            tiered_scout_sims: None,
-            tiered_top_k: None,
+            tiered_scout_sims: request.tiered_scout_sims.map(|n| n as usize),
+            tiered_top_k: request.tiered_top_k.map(|n| n as usize),
+            scenario_type,
+            mining_rounds,
+            waves,
+            wave_repair,
+            attacker_condition: attacker_condition(request),
+            max_results: request.max_results.map(|n| n as usize),
+            genetic: request.ga.clone().unwrap_or_default(),
+            excluded_officers: &excluded_officers,
+            unknown_officers: request.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+            memory: memory.clone(),
+            pool: sink.pool(),
         };
-        let normal_results = optimize_scenario_with_progress_with_registry(
+        sink.start_optimizer_phase(sims_per_progress_unit(
+            strategy,
+            sims,
+            is_seeded_genetic,
+            &scenario.genetic,
+        ));
+        let (normal_results, fitness_cache) = optimize_scenario_with_progress_with_registry(
             registry,
             &scenario,
-            |crews_done, total_crews| sink.on_optimize_progress(crews_done, total_crews),
+            |phase, crews_done, total_crews, stage, leaders| {
+                sink.on_optimize_progress(phase, crews_done, total_crews, stage, leaders)
+            },
         );
         if sink.job_cancelled() {
             return Err(());
         }
+        meta.fitness_cache = fitness_cache;
+        if let Some(report) = meta.auto_strategy.as_mut() {
+            if strategy == OptimizerStrategy::Exhaustive
+                && agreement_check_is_cheap(report.estimated_candidates, sims, &scenario.genetic)
+            {
+                let genetic_scenario = OptimizationScenario {
+                    strategy: OptimizerStrategy::Genetic,
+                    ..scenario.clone()
+                };
+                let (genetic_results, _) = optimize_scenario_with_progress_with_registry(
+                    registry,
+                    &genetic_scenario,
+                    |_, _, _, _, _| true,
+                );
+                if sink.job_cancelled() {
+                    return Err(());
+                }
+                report.agreement = strategy_agreement(&normal_results, &genetic_results);
+            }
+        }
         all_results.extend(
             normal_results
                 .into_iter()
@@ -290,18 +742,49 @@ fn gather_optimize_simulation_results(
         );
     }
 
+    memory.hold_results(all_results.len());
+    meta.memory = memory.usage();
     Ok((all_results, meta))
 }
 
+/// Response row for a ranked crew; `points_per_kill` is set for points_per_hour scenarios.
+fn crew_recommendation(
+    result: RankedCrewResult,
+    points_per_kill: Option<f64>,
+) -> CrewRecommendation {
+    CrewRecommendation {
+        crew_id: result.stable_id(),
+        points_per_hour: points_per_kill.map(|points| f64::from(result.score.value) * points),
+        captain: result.captain,
+        bridge: result.bridge,
+        below_decks: result.below_decks,
+        win_rate: result.win_rate,
+        stall_rate: result.stall_rate,
+        loss_rate: result.loss_rate,
+        avg_hull_remaining: result.avg_hull_remaining,
+        avg_survival_share: result.avg_survival_share,
+        avg_damage_dealt: result.avg_damage_dealt,
+        survival_rate: result.survival_rate,
+        avg_waves_cleared: result.avg_waves_cleared,
+        median_rounds_to_kill: result.median_rounds_to_kill,
+        p95_rounds_to_kill: result.p95_rounds_to_kill,
+        avg_damage_per_round: result.avg_damage_per_round,
+        avg_hull_lost: result.avg_hull_lost,
+        expected_repair: None,
+        explanation: None,
+        power: None,
+    }
+}
+
 fn build_optimize_response(
     request: &OptimizeRequest,
     all_results: Vec<SimulationResult>,
-    duration_ms: u64,
     meta: &OptimizeGatherMeta,
 ) -> OptimizeResponse {
-    let sims = request.sims.unwrap_or(DEFAULT_SIMS);
+    let sims = request.sims.unwrap_or_else(default_sims);
     let seed = request.seed.unwrap_or(0);
-    let ranked_results = rank_results(all_results);
+    let ranked_results = rank_results_for(all_results, meta.scenario_type);
+    let (waves, _) = wave_settings(request, meta.scenario_type);
 
     let engine = if meta.heuristics_only {
         "heuristics"
@@ -312,14 +795,46 @@ fn build_optimize_response(
             OptimizerStrategy::Exhaustive => "optimizer_v1",
             OptimizerStrategy::Genetic => "genetic",
             OptimizerStrategy::Tiered => "tiered",
+            OptimizerStrategy::TwoPhase => "two_phase",
         }
     };
-    let mut notes =
-        vec!["Results are deterministic for the same ship, hostile, simulation count, and seed."];
+    let mut notes = vec![
+        "Results are deterministic for the same ship, hostile, simulation count, and seed."
+            .to_string(),
+    ];
     if meta.is_seeded_genetic {
-        notes.insert(0, "GA population seeded with heuristics crews.");
+        notes.insert(0, "GA population seeded with heuristics crews.".to_string());
     } else if meta.heuristics_seeds_nonempty {
-        notes.insert(0, "Heuristics crews were evaluated first.");
+        notes.insert(0, "Heuristics crews were evaluated first.".to_string());
+    }
+    if let Some(report) = &meta.auto_strategy {
+        notes.push(format!(
+            "Strategy \"auto\" chose {} for {} candidates (exhaustive limit {}).",
+            report.chosen, report.estimated_candidates, report.exhaustive_max_candidates
+        ));
+        if let Some(agreement) = &report.agreement {
+            notes.push(if agreement.agrees {
+                "Agreement check: the genetic search found the same top crew.".to_string()
+            } else {
+                format!(
+                    "Agreement check: the genetic search's top crew differs \
+                     (score {:.4} vs {:.4}).",
+                    agreement.genetic_score, agreement.exhaustive_score
+                )
+            });
+        }
+    }
+    if let Some(cache) = meta.fitness_cache {
+        notes.push(format!(
+            "GA fitness cache: {} of {} crew evaluations reused ({:.1}% hit rate).",
+            cache.hits,
+            cache.lookups,
+            cache.hit_rate() * 100.0
+        ));
+    }
+
+    if !meta.memory.is_empty() {
+        notes.push(meta.memory.note());
     }
 
     let mut warnings = Vec::new();
@@ -338,20 +853,21 @@ fn build_optimize_response(
             hostile: request.hostile.clone(),
             sims,
             seed,
+            scenario_type: meta.scenario_type,
+            mining_rounds: request.mining_rounds,
+            waves: (waves > 0).then_some(waves),
+            wave_repair: request.wave_repair,
+            attacker_hull_pct: request.attacker_hull_pct,
+            attacker_shield_pct: request.attacker_shield_pct,
+            points_per_kill: meta.points_per_kill,
+            officer_pools: meta.officer_pools,
         },
         recommendations: ranked_results
             .into_iter()
-            .map(|result| CrewRecommendation {
-                captain: result.captain,
-                bridge: result.bridge,
-                below_decks: result.below_decks,
-                win_rate: result.win_rate,
-                stall_rate: result.stall_rate,
-                loss_rate: result.loss_rate,
-                avg_hull_remaining: result.avg_hull_remaining,
-            })
+            .map(|result| crew_recommendation(result, meta.points_per_kill))
             .collect(),
-        duration_ms: Some(duration_ms),
+        duration_ms: None,
+        auto_strategy: meta.auto_strategy.clone(),
         notes,
         warnings,
     }
@@ -363,13 +879,24 @@ pub fn run_optimize(
     request: &OptimizeRequest,
     profile_id: Option<&str>,
 ) -> Result<OptimizeResponse, OptimizePayloadError> {
+    let _entered = tracing::info_span!(
+        "optimize",
+        ship = %request.ship,
+        hostile = %request.hostile,
+    )
+    .entered();
     let start = Instant::now();
     let mut sink = OptimizeProgressSink::None;
     let (all_results, meta) =
         gather_optimize_simulation_results(registry, request, profile_id, &mut sink)
             .expect("sync optimize does not cancel");
+    let mut response = build_optimize_response(request, all_results, &meta);
+    annotate_recommendations(registry, request, profile_id, &mut response);
     let duration_ms = start.elapsed().as_millis() as u64;
-    Ok(build_optimize_response(request, all_results, duration_ms, &meta))
+    response.duration_ms = Some(duration_ms);
+    tracing::info!(duration_ms, "optimize done");
+    record_optimize(request, profile_id, &response);
+    Ok(response)
 }
 
 // --- Optimize job store (for progress polling) ---
@@ -387,16 +914,31 @@ pub struct OptimizeJobState {
     pub progress: u8,
     pub crews_done: u32,
     pub total_crews: u32,
+    /// Measured simulation throughput; `None` until the first progress batch completes.
+    pub sims_per_second: Option<f64>,
+    /// Projected seconds remaining at the current throughput.
+    pub eta_seconds: Option<f64>,
+    /// What the job is doing; `None` until the first progress report.
+    pub phase: Option<OptimizePhase>,
+    /// Current pipeline stage (tiered strategy only).
+    pub stage: Option<StageProgress>,
+    /// Peak buffers so far; `None` until the first progress report.
+    pub memory: Option<MemoryUsage>,
+    /// Exhaustive only: best crews from completed batches while the job runs; cleared when it
+    /// finishes, kept when it is cancelled.
+    pub provisional: Vec<CrewRecommendation>,
     pub result: Option<OptimizeResponse>,
     pub error: Option<String>,
+    /// Replayable fight attached to a failed job when `KOBAYASHI_JOB_SNAPSHOTS=1`.
+    pub snapshot: Option<SimulationSnapshot>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct OptimizeStartResponse {
     pub job_id: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct OptimizeStatusResponse {
     pub status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -406,9 +948,29 @@ pub struct OptimizeStatusResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub total_crews: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub sims_per_second: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<f64>,
+    /// Current phase: generating, simulating, ga_generation or final_ranking.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<OptimizePhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<StageProgress>,
+    /// Approximate peak memory of the run's buffered candidates and results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryUsage>,
+    /// Exhaustive jobs only: provisional top crews (up to 5) from the batches simulated so far,
+    /// best first. Present while running and after a cancel; the final ranking may differ.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub provisional: Vec<CrewRecommendation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<OptimizeResponse>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Failed jobs only (opt-in): save as a file and replay with `kobayashi snapshot run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<serde_json::Value>")]
+    pub snapshot: Option<SimulationSnapshot>,
 }
 
 /// Cap on stored job records (running + finished). Oldest **completed** jobs are dropped first
@@ -417,13 +979,13 @@ const MAX_OPTIMIZE_JOBS_RETAINED: usize = 128;
 
 static OPTIMIZE_JOB_COUNTER: OnceLock<AtomicU64> = OnceLock::new();
 static OPTIMIZE_JOBS: OnceLock<Mutex<HashMap<String, OptimizeJobState>>> = OnceLock::new();
-static OPTIMIZE_CANCEL_FLAGS: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+static OPTIMIZE_CANCEL_FLAGS: OnceLock<Mutex<HashMap<String, CancelToken>>> = OnceLock::new();
 
 fn optimize_jobs() -> &'static Mutex<HashMap<String, OptimizeJobState>> {
     OPTIMIZE_JOBS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn optimize_cancel_flags() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+fn optimize_cancel_flags() -> &'static Mutex<HashMap<String, CancelToken>> {
     OPTIMIZE_CANCEL_FLAGS.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
@@ -449,7 +1011,7 @@ fn parse_optimize_job_timestamp_ms(job_id: &str) -> u128 {
 /// Drop oldest finished jobs until `map.len() <= max_entries`. Running jobs are never removed.
 fn prune_completed_optimize_jobs_over_cap(
     map: &mut HashMap<String, OptimizeJobState>,
-    cancel_flags: &mut HashMap<String, Arc<AtomicBool>>,
+    cancel_flags: &mut HashMap<String, CancelToken>,
     max_entries: usize,
 ) {
     while map.len() > max_entries {
@@ -497,9 +1059,10 @@ pub fn start_optimize_job(
     request: OptimizeRequest,
     profile_id: Option<&str>,
     cpu_permit: OwnedSemaphorePermit,
+    webhooks: Arc<WebhookPolicy>,
 ) -> Result<OptimizeStartResponse, OptimizePayloadError> {
     let job_id = next_job_id();
-    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let cancel_flag = CancelToken::new();
     let heuristics_seeds_nonempty = request
         .heuristics_seeds
         .as_ref()
@@ -514,8 +1077,15 @@ pub fn start_optimize_job(
                 progress: 0,
                 crews_done: 0,
                 total_crews: 0,
+                sims_per_second: None,
+                eta_seconds: None,
+                phase: None,
+                stage: None,
+                memory: None,
+                provisional: Vec::new(),
                 result: None,
                 error: None,
+                snapshot: None,
             },
         );
         let mut cancel_flags = optimize_cancel_flags().lock().unwrap();
@@ -530,47 +1100,123 @@ pub fn start_optimize_job(
     let job_id_thread = job_id.clone();
     let profile_owned = profile_id.map(String::from);
 
+    let span = tracing::info_span!(
+        "optimize_job",
+        job_id = %job_id,
+        ship = %request.ship,
+        hostile = %request.hostile,
+    );
     std::thread::spawn(move || {
-        let _cpu_permit = cpu_permit;
+        let _entered = span.enter();
         let start = Instant::now();
+        tracing::info!(sims = request.sims.unwrap_or_else(default_sims), "optimize job started");
         let mut sink = OptimizeProgressSink::Job {
             job_id: job_id_thread.clone(),
             cancel: cancel_flag.clone(),
             heuristics_seeds_nonempty,
             is_seeded_genetic: false,
+            points_per_kill: None,
+            throughput: ThroughputTracker::new(
+                request.sims.unwrap_or_else(default_sims) as u64,
+            ),
+            memory: None,
         };
-        let gather = gather_optimize_simulation_results(
-            registry.as_ref(),
-            &request,
-            profile_owned.as_deref(),
-            &mut sink,
-        );
+        // A panic in the optimizer must still settle the job, or pollers see it running forever.
+        let gather = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            OPTIMIZE_JOB_POOL.install(|| {
+                crate::server::settings::current().install(|| {
+                    gather_optimize_simulation_results(
+                        registry.as_ref(),
+                        &request,
+                        profile_owned.as_deref(),
+                        &mut sink,
+                    )
+                })
+            })
+        }));
 
-        match gather {
-            Ok((all_results, meta)) => {
+        let summary = match gather {
+            Ok(Ok((all_results, meta))) => {
+                let mut response = build_optimize_response(&request, all_results, &meta);
+                annotate_recommendations(
+                    registry.as_ref(),
+                    &request,
+                    profile_owned.as_deref(),
+                    &mut response,
+                );
                 let duration_ms = start.elapsed().as_millis() as u64;
-                let response = build_optimize_response(&request, all_results, duration_ms, &meta);
+                response.duration_ms = Some(duration_ms);
+                tracing::info!(
+                    duration_ms,
+                    engine = response.engine,
+                    recommendations = response.recommendations.len(),
+                    "optimize job done"
+                );
+                let summary = OptimizeWebhookSummary::done(&job_id_thread, &request, &response);
+                record_optimize(&request, profile_owned.as_deref(), &response);
                 if let Ok(mut map) = optimize_jobs().lock() {
                     if let Some(state) = map.get_mut(&job_id_thread) {
                         state.status = OptimizeJobStatus::Done;
                         state.progress = 100;
+                        if !meta.memory.is_empty() {
+                            state.memory = Some(meta.memory);
+                        }
+                        state.provisional.clear();
+                        state.eta_seconds = Some(0.0);
+                        if let Some(rate) = state.sims_per_second {
+                            record_observed_sims_per_second(rate);
+                        }
                         state.result = Some(response);
                     }
                 }
+                summary
             }
-            Err(()) => {
+            Ok(Err(())) => {
+                tracing::info!("optimize job cancelled");
                 if let Ok(mut map) = optimize_jobs().lock() {
                     if let Some(state) = map.get_mut(&job_id_thread) {
                         state.status = OptimizeJobStatus::Error;
                         state.error = Some("Cancelled".to_string());
                     }
                 }
+                OptimizeWebhookSummary::failed(&job_id_thread, &request, "Cancelled")
             }
-        }
+            Err(panic) => {
+                let error = format!("Internal error: {}", panic_message(panic.as_ref()));
+                tracing::error!(%error, "optimize job failed");
+                let snapshot = job_snapshots_enabled()
+                    .then(|| {
+                        failed_job_snapshot(
+                            registry.as_ref(),
+                            &request,
+                            profile_owned.as_deref(),
+                            &job_id_thread,
+                            &error,
+                        )
+                    })
+                    .flatten();
+                if let Ok(mut map) = optimize_jobs().lock() {
+                    if let Some(state) = map.get_mut(&job_id_thread) {
+                        state.status = OptimizeJobStatus::Error;
+                        state.error = Some(error.clone());
+                        state.snapshot = snapshot;
+                    }
+                }
+                OptimizeWebhookSummary::failed(&job_id_thread, &request, &error)
+            }
+        };
         optimize_cancel_flags()
             .lock()
             .unwrap()
             .remove(&job_id_thread);
+        if let Some(url) = &request.notify_webhook {
+            // The CPU work is over; a slow receiver must not hold the permit.
+            drop(cpu_permit);
+            match post_webhook(url, &summary, &webhooks) {
+                Ok(()) => tracing::info!(status = summary.status, "optimize job webhook sent"),
+                Err(error) => tracing::warn!(%error, "optimize job webhook failed"),
+            }
+        }
     });
 
     Ok(OptimizeStartResponse { job_id })
@@ -589,17 +1235,92 @@ pub fn get_job_status(job_id: &str) -> Result<OptimizeStatusResponse, OptimizeSt
         progress: Some(state.progress),
         crews_done: Some(state.crews_done),
         total_crews: Some(state.total_crews),
+        sims_per_second: state.sims_per_second.map(|r| r.round()),
+        eta_seconds: state.eta_seconds.map(|s| (s * 10.0).round() / 10.0),
+        phase: state.phase,
+        stage: state.stage,
+        memory: state.memory,
+        provisional: state.provisional.clone(),
         result: state.result.clone(),
         error: state.error.clone(),
+        snapshot: state.snapshot.clone(),
     })
 }
 
+/// `KOBAYASHI_JOB_SNAPSHOTS=1`: failed optimize jobs carry a [SimulationSnapshot] of their
+/// scenario so the user can attach it to a bug report. Off by default (snapshots are large).
+fn job_snapshots_enabled() -> bool {
+    std::env::var("KOBAYASHI_JOB_SNAPSHOTS")
+        .map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("optimizer panicked")
+}
+
+/// Snapshot of the failed job's scenario, crewed by its first heuristics crew (or no officers).
+/// The failing crew itself is unknown once the optimizer has unwound. `None` when resolving the
+/// scenario panics too.
+fn failed_job_snapshot(
+    registry: &DataRegistry,
+    request: &OptimizeRequest,
+    profile_id: Option<&str>,
+    job_id: &str,
+    error: &str,
+) -> Option<SimulationSnapshot> {
+    std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let shared = request_shared_scenario(registry, request, profile_id);
+        let bd_strategy = parse_below_decks_strategy(request.below_decks_strategy.as_ref());
+        let candidate = request
+            .heuristics_seeds
+            .as_deref()
+            .and_then(|seeds| {
+                load_heuristics_candidates(registry, seeds, bd_strategy, shared.crew_slots)
+                    .into_iter()
+                    .next()
+            })
+            .unwrap_or_else(|| CrewCandidate::new(String::new(), Vec::new(), Vec::new()));
+        snapshot_candidate_fight(&shared, &candidate, request.seed.unwrap_or(0))
+            .with_data_version(
+                "ships",
+                registry.ship_index().and_then(|i| i.data_version.as_deref()),
+            )
+            .with_data_version(
+                "hostiles",
+                registry.hostile_index().and_then(|i| i.data_version.as_deref()),
+            )
+            .with_context(serde_json::json!({
+                "job_id": job_id,
+                "error": error,
+                "request": request,
+                "profile_id": profile_id,
+            }))
+    }))
+    .ok()
+}
+
+/// Background optimize jobs still running.
+pub fn running_job_count() -> usize {
+    optimize_jobs()
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|job| matches!(job.status, OptimizeJobStatus::Running))
+        .count()
+}
+
 pub fn cancel_job(job_id: &str) -> Result<(), OptimizeStatusError> {
     let flag = {
         let flags = optimize_cancel_flags().lock().unwrap();
         flags.get(job_id).cloned().ok_or(OptimizeStatusError::NotFound)?
     };
-    flag.store(true, Ordering::Relaxed);
+    flag.cancel();
+    tracing::info!(job_id, "optimize job cancel requested");
     Ok(())
 }
 
@@ -613,11 +1334,36 @@ mod optimize_job_store_tests {
             progress: 100,
             crews_done: 1,
             total_crews: 1,
+            sims_per_second: None,
+            eta_seconds: None,
+            phase: None,
+            stage: None,
+            memory: None,
+            provisional: Vec::new(),
             result: None,
             error: None,
+            snapshot: None,
         }
     }
 
+    #[test]
+    fn seed_crews_with_unknown_officers_are_rejected_once_each() {
+        let registry = DataRegistry::load().expect("DataRegistry::load");
+        let crew = |captain: &str| {
+            CrewCandidate::new(captain.to_string(), vec!["kirkk".to_string()], Vec::new())
+        };
+        let crews = [crew("Spock"), crew("Kirk")];
+        let unknown = check_seed_crews(&registry, None, &crews).unwrap_err();
+        assert_eq!(unknown.errors.len(), 1);
+        assert_eq!(unknown.errors[0].field, "heuristics_seeds");
+        assert_eq!(unknown.errors[0].value, "kirkk");
+
+        let neutral = Some(UnknownOfficerMode::Neutral);
+        assert!(check_seed_crews(&registry, neutral, &crews).is_ok());
+        let known = CrewCandidate::new("Kirk".to_string(), vec!["Spock".to_string()], Vec::new());
+        assert!(check_seed_crews(&registry, None, &[known]).is_ok());
+    }
+
     #[test]
     fn parse_job_timestamp_reads_opt_prefix() {
         assert_eq!(parse_optimize_job_timestamp_ms("opt_1700000000123_0"), 1700000000123);
@@ -639,14 +1385,21 @@ mod optimize_job_store_tests {
                 progress: 0,
                 crews_done: 0,
                 total_crews: 0,
+                sims_per_second: None,
+                eta_seconds: None,
+                phase: None,
+                stage: None,
+                memory: None,
+                provisional: Vec::new(),
                 result: None,
                 error: None,
+                snapshot: None,
             },
         );
-        flags.insert("opt_100_0".to_string(), Arc::new(AtomicBool::new(false)));
-        flags.insert("opt_200_1".to_string(), Arc::new(AtomicBool::new(false)));
-        flags.insert("opt_300_2".to_string(), Arc::new(AtomicBool::new(false)));
-        flags.insert("opt_400_run".to_string(), Arc::new(AtomicBool::new(false)));
+        flags.insert("opt_100_0".to_string(), CancelToken::new());
+        flags.insert("opt_200_1".to_string(), CancelToken::new());
+        flags.insert("opt_300_2".to_string(), CancelToken::new());
+        flags.insert("opt_400_run".to_string(), CancelToken::new());
 
         prune_completed_optimize_jobs_over_cap(&mut map, &mut flags, 2);
         assert_eq!(map.len(), 2);
@@ -655,4 +1408,113 @@ mod optimize_job_store_tests {
         assert!(map.contains_key("opt_300_2"));
         assert!(map.contains_key("opt_400_run"));
     }
+
+    #[test]
+    fn status_reports_rounded_throughput_and_eta() {
+        let job_id = "opt_0_throughput_test".to_string();
+        optimize_jobs().lock().unwrap().insert(
+            job_id.clone(),
+            OptimizeJobState {
+                status: OptimizeJobStatus::Running,
+                progress: 40,
+                crews_done: 4,
+                total_crews: 10,
+                sims_per_second: Some(1234.56),
+                eta_seconds: Some(3.16),
+                phase: None,
+                stage: None,
+                memory: None,
+                provisional: Vec::new(),
+                result: None,
+                error: None,
+                snapshot: None,
+            },
+        );
+        let status = get_job_status(&job_id).expect("job present");
+        optimize_jobs().lock().unwrap().remove(&job_id);
+        assert_eq!(status.sims_per_second, Some(1235.0));
+        assert_eq!(status.eta_seconds, Some(3.2));
+    }
+
+    #[test]
+    fn progress_with_leaders_reports_provisional_crews() {
+        let job_id = "opt_0_provisional_test".to_string();
+        let mut running = done_state();
+        running.status = OptimizeJobStatus::Running;
+        optimize_jobs().lock().unwrap().insert(job_id.clone(), running);
+        let mut sink = OptimizeProgressSink::Job {
+            job_id: job_id.clone(),
+            cancel: CancelToken::new(),
+            heuristics_seeds_nonempty: false,
+            is_seeded_genetic: false,
+            points_per_kill: Some(2.0),
+            throughput: ThroughputTracker::new(100),
+            memory: None,
+        };
+        let leader = ranked_crew_to_simulation_result(RankedCrewResult {
+            crew_id: String::new(),
+            captain: "Kirk".to_string(),
+            bridge: vec!["Spock".to_string()],
+            below_decks: Vec::new(),
+            win_rate: 0.9,
+            stall_rate: 0.0,
+            loss_rate: 0.1,
+            avg_hull_remaining: 0.5,
+            avg_survival_share: 1.0,
+            avg_damage_dealt: 1.0,
+            survival_rate: 0.9,
+            avg_waves_cleared: 0.0,
+            median_rounds_to_kill: Some(3),
+            p95_rounds_to_kill: Some(5),
+            avg_damage_per_round: 10.0,
+            avg_hull_lost: 0.2,
+            score: Default::default(),
+        });
+        let leaders = rank_results_for(vec![leader], ScenarioType::Offense);
+
+        assert!(sink.on_optimize_progress(OptimizePhase::Simulating, 1, 4, None, &leaders));
+        let status = get_job_status(&job_id).expect("job present");
+        // Progress without leaders (other strategies) keeps the last provisional crews.
+        assert!(sink.on_optimize_progress(OptimizePhase::FinalRanking, 2, 4, None, &[]));
+        let later = get_job_status(&job_id).expect("job present");
+        optimize_jobs().lock().unwrap().remove(&job_id);
+
+        assert_eq!(status.provisional.len(), 1);
+        assert_eq!(status.provisional[0].captain, "Kirk");
+        let expected_points = f64::from(leaders[0].score.value) * 2.0;
+        assert_eq!(status.provisional[0].points_per_hour, Some(expected_points));
+        assert_eq!(later.provisional.len(), 1);
+        assert_eq!(status.phase, Some(OptimizePhase::Simulating));
+        assert_eq!(later.phase, Some(OptimizePhase::FinalRanking));
+    }
+
+    #[test]
+    fn genetic_progress_unit_covers_whole_generation() {
+        let default = GeneticConfig::default();
+        let no_overrides = GeneticOverrides::default();
+        assert_eq!(
+            sims_per_progress_unit(OptimizerStrategy::Genetic, 5000, false, &no_overrides),
+            (default.population_size * default.sims_per_eval) as u64
+        );
+        let bigger = GeneticOverrides {
+            population_size: Some(200),
+            ..GeneticOverrides::default()
+        };
+        assert_eq!(
+            sims_per_progress_unit(OptimizerStrategy::Genetic, 5000, false, &bigger),
+            (200 * default.sims_per_eval) as u64
+        );
+        let islands = GeneticOverrides {
+            islands: Some(4),
+            ..bigger
+        };
+        assert_eq!(
+            sims_per_progress_unit(OptimizerStrategy::Genetic, 5000, false, &islands),
+            (4 * 200 * default.sims_per_eval) as u64
+        );
+        assert_eq!(
+            sims_per_progress_unit(OptimizerStrategy::Exhaustive, 5000, false, &no_overrides),
+            5000
+        );
+    }
 }
@@ -1,50 +1,179 @@
 //! Request DTOs and validation for the API.
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use std::fmt;
 
 use crate::data::heuristics::BelowDecksStrategy;
+use crate::data::officer::UnknownOfficerMode;
+use crate::optimizer::genetic::{
+    GeneticOverrides, MAX_GA_GENERATIONS, MAX_GA_ISLANDS, MAX_GA_POPULATION,
+};
+use crate::optimizer::ranking::ScenarioType;
 use crate::optimizer::OptimizerStrategy;
+use crate::server::loadouts::unknown_loadout_ids;
+use crate::server::notify::WebhookPolicy;
 
+/// Initial `default_sims`; see [default_sims].
 pub const DEFAULT_SIMS: u32 = 5000;
 pub const MAX_SIMS: u32 = 100_000;
+/// Hard cap on `max_candidates`; the runtime limit ([max_candidates_limit]) may be lower.
 pub const MAX_CANDIDATES: u32 = 2_000_000;
+/// Simulations per crew when a request omits `sims`/`num_sims` (runtime setting, see
+/// [crate::server::settings]).
+pub fn default_sims() -> u32 {
+    crate::server::settings::current().default_sims()
+}
+
+/// Largest `max_candidates` a request may ask for (runtime setting, at most [MAX_CANDIDATES]).
+pub fn max_candidates_limit() -> u32 {
+    crate::server::settings::current().max_candidates()
+}
 
-#[derive(Debug, Clone, Deserialize)]
+/// Upper bound for `tiered_top_k` (crews confirmed with full sims).
+pub const MAX_TIERED_TOP_K: u32 = 1_000;
+/// Upper bound for `max_results` (ranked crews an exhaustive run keeps).
+pub const MAX_RESULTS: u32 = 100_000;
+/// Upper bound for `mining_rounds` (the combat round cap).
+pub const MAX_MINING_ROUNDS: u32 = crate::combat::types::MAX_COMBAT_ROUNDS;
+/// Waves fought when `scenario_type` is "waves" and `waves` is unset.
+pub const DEFAULT_WAVES: u32 = 3;
+/// Upper bound for `waves`.
+pub const MAX_WAVES: u32 = 20;
+/// Upper bound for `explain_top`.
+pub const MAX_EXPLAIN_TOP: u32 = 10;
+
+#[derive(Debug, Clone, serde::Serialize, Deserialize, JsonSchema)]
 pub struct OptimizeRequest {
+    #[schemars(length(min = 1))]
     pub ship: String,
+    #[schemars(length(min = 1))]
     pub hostile: String,
     /// Ship tier (1-based). When set, uses data/ships_extended for accurate stats.
     pub ship_tier: Option<u32>,
     /// Ship level (1-based). When set with tier, applies level bonuses from extended data.
     pub ship_level: Option<u32>,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
     pub sims: Option<u32>,
     pub seed: Option<u64>,
+    #[schemars(range(max = "MAX_CANDIDATES"))]
     pub max_candidates: Option<u32>,
+    /// "exhaustive" (default), "genetic", "tiered", "two_phase" (bridge by simulation, below
+    /// decks by stat value) or "auto" (exhaustive for small search spaces, genetic otherwise).
     pub strategy: Option<String>,
+    /// Tiered and two-phase: sims per crew in the scouting stage (two-phase: per bridge prefix).
+    /// None = default (500).
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub tiered_scout_sims: Option<u32>,
+    /// Tiered and two-phase: crews confirmed with full sims (two-phase: bridge prefixes filled and
+    /// confirmed). None = default (50).
+    #[schemars(range(min = 1, max = "MAX_TIERED_TOP_K"))]
+    pub tiered_top_k: Option<u32>,
+    /// Genetic only: population size, generations, mutation rate, elitism and early stopping.
+    /// Unset fields keep the defaults (or the seeded defaults when heuristics seed the GA).
+    pub ga: Option<GeneticOverrides>,
+    /// Exhaustive: best crews kept (and returned) while results stream in, so memory stays flat
+    /// however many candidates are simulated. None = 1000.
+    #[schemars(range(min = 1, max = "MAX_RESULTS"))]
+    pub max_results: Option<u32>,
+    /// "offense" (default), "defense", "mining", "waves" or "points_per_hour": what the player's
+    /// ship is trying to do. "points_per_hour" ranks by kills per hour and reports the hostile's
+    /// event points (or loot value) per hour.
+    #[schemars(with = "Option<ScenarioType>")]
+    pub scenario_type: Option<String>,
+    /// Mining only: opening rounds the ship keeps mining before returning fire. None = 0.
+    #[schemars(range(max = "MAX_MINING_ROUNDS"))]
+    pub mining_rounds: Option<u32>,
+    /// Waves only: consecutive hostiles fought without a reset. None = 3.
+    #[schemars(range(min = 1, max = "MAX_WAVES"))]
+    pub waves: Option<u32>,
+    /// Waves only: share (0–1) of missing hull and shields repaired between waves. None = 0.
+    #[schemars(range(min = 0, max = 1))]
+    pub wave_repair: Option<f64>,
+    /// Share (0–1, above 0) of hull the player's ship starts with, to optimize for a ship that
+    /// is already damaged. None = 1 (full hull).
+    #[schemars(range(min = 0, max = 1))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attacker_hull_pct: Option<f64>,
+    /// Share (0–1) of shields up when the fight starts; 0 = shields down. None = 1.
+    #[schemars(range(min = 0, max = 1))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attacker_shield_pct: Option<f64>,
+    /// Restrict the below-decks pool to officers with a below-decks ability. Shrinks the search
+    /// space (see `scenario.officer_pools`) at the risk of missing stat-only picks. None = false.
+    #[serde(alias = "only_below_decks_with_ability")]
     pub prioritize_below_decks_ability: Option<bool>,
     pub heuristics_seeds: Option<Vec<String>>,
     pub heuristics_only: Option<bool>,
     pub below_decks_strategy: Option<String>,
+    /// Reject a ship or hostile that does not resolve from data (404 with near-miss ids). None =
+    /// true; false simulates deterministic placeholder stats instead.
+    pub strict: Option<bool>,
+    /// Officers in `heuristics_seeds` crews that match no officer data: "strict" rejects the
+    /// request (see [super::check_known_officers]), "neutral" seats them with no effect, "legacy"
+    /// with a made-up bonus from the name's hash. None = strict.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unknown_officers: Option<UnknownOfficerMode>,
+    /// Top recommendations given an `explanation` (officer contributions and status uptimes).
+    /// None = 3; 0 turns explanations off.
+    #[schemars(range(max = "MAX_EXPLAIN_TOP"))]
+    pub explain_top: Option<u32>,
+    /// `/api/optimize/start` only: http(s) URL POSTed a summary (top 3 crews, win rates,
+    /// duration) when the job settles. Discord webhook URLs post the summary as a message.
+    pub notify_webhook: Option<String>,
+    /// Saved loadout ids (`/api/loadouts`) whose officers are committed elsewhere and never
+    /// recommended.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_loadouts: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
 pub struct ValidationIssue {
     pub field: &'static str,
     pub messages: Vec<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
 pub struct ValidationErrorResponse {
     pub status: &'static str,
     pub message: &'static str,
     pub errors: Vec<ValidationIssue>,
 }
 
+/// A ship or hostile that did not resolve from loaded data, with the closest known ids.
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
+pub struct UnknownIdIssue {
+    pub field: &'static str,
+    pub value: String,
+    pub suggestions: Vec<String>,
+}
+
+/// Strict-mode rejection (HTTP 404): the request named a ship or hostile the data does not have.
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
+pub struct UnknownIdsResponse {
+    pub status: &'static str,
+    pub message: &'static str,
+    pub errors: Vec<UnknownIdIssue>,
+}
+
+impl fmt::Display for UnknownIdsResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        for issue in &self.errors {
+            write!(f, "; {} '{}' not found", issue.field, issue.value)?;
+            if !issue.suggestions.is_empty() {
+                write!(f, " (did you mean: {})", issue.suggestions.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub enum OptimizePayloadError {
     Parse(serde_json::Error),
     Validation(ValidationErrorResponse),
+    UnknownIds(UnknownIdsResponse),
 }
 
 impl fmt::Display for OptimizePayloadError {
@@ -52,6 +181,7 @@ impl fmt::Display for OptimizePayloadError {
         match self {
             Self::Parse(err) => write!(f, "{err}"),
             Self::Validation(_) => write!(f, "invalid optimize request"),
+            Self::UnknownIds(unknown) => write!(f, "{unknown}"),
         }
     }
 }
@@ -61,6 +191,7 @@ impl std::error::Error for OptimizePayloadError {}
 pub fn validate_request(
     request: &OptimizeRequest,
     sims: u32,
+    webhooks: &WebhookPolicy,
 ) -> Result<(), OptimizePayloadError> {
     let mut errors: Vec<ValidationIssue> = Vec::new();
 
@@ -86,10 +217,153 @@ pub fn validate_request(
     }
 
     if let Some(cap) = request.max_candidates {
-        if cap > MAX_CANDIDATES {
+        let limit = max_candidates_limit();
+        if cap > limit {
             errors.push(ValidationIssue {
                 field: "max_candidates",
-                messages: vec![format!("must be at most {MAX_CANDIDATES}")],
+                messages: vec![format!("must be at most {limit}")],
+            });
+        }
+    }
+
+    if let Some(ids) = request.exclude_loadouts.as_deref() {
+        let unknown = unknown_loadout_ids(ids);
+        if !unknown.is_empty() {
+            errors.push(ValidationIssue {
+                field: "exclude_loadouts",
+                messages: vec![format!("unknown loadouts: {}", unknown.join(", "))],
+            });
+        }
+    }
+
+    if let Some(scout_sims) = request.tiered_scout_sims {
+        if !(1..=MAX_SIMS).contains(&scout_sims) {
+            errors.push(ValidationIssue {
+                field: "tiered_scout_sims",
+                messages: vec![format!("must be between 1 and {MAX_SIMS}")],
+            });
+        }
+    }
+
+    if let Some(top_k) = request.tiered_top_k {
+        if !(1..=MAX_TIERED_TOP_K).contains(&top_k) {
+            errors.push(ValidationIssue {
+                field: "tiered_top_k",
+                messages: vec![format!("must be between 1 and {MAX_TIERED_TOP_K}")],
+            });
+        }
+    }
+
+    if let Some(max_results) = request.max_results {
+        if !(1..=MAX_RESULTS).contains(&max_results) {
+            errors.push(ValidationIssue {
+                field: "max_results",
+                messages: vec![format!("must be between 1 and {MAX_RESULTS}")],
+            });
+        }
+    }
+
+    if let Some(ga) = &request.ga {
+        validate_genetic_overrides(ga, &mut errors);
+        if parse_strategy(request.strategy.as_ref()) != OptimizerStrategy::Genetic
+            && !is_auto_strategy(request.strategy.as_ref())
+        {
+            errors.push(ValidationIssue {
+                field: "ga",
+                messages: vec!["requires strategy \"genetic\" or \"auto\"".to_string()],
+            });
+        }
+    }
+
+    let scenario_type = parse_scenario_type(request.scenario_type.as_ref());
+    if scenario_type.is_none() {
+        errors.push(ValidationIssue {
+            field: "scenario_type",
+            messages: vec!["must be one of: offense, defense, mining, waves, points_per_hour".to_string()],
+        });
+    }
+
+    if let Some(mining_rounds) = request.mining_rounds {
+        let mut messages = Vec::new();
+        if mining_rounds > MAX_MINING_ROUNDS {
+            messages.push(format!("must be at most {MAX_MINING_ROUNDS}"));
+        }
+        if scenario_type != Some(ScenarioType::Mining) {
+            messages.push("requires scenario_type \"mining\"".to_string());
+        }
+        if !messages.is_empty() {
+            errors.push(ValidationIssue {
+                field: "mining_rounds",
+                messages,
+            });
+        }
+    }
+
+    let is_waves = scenario_type == Some(ScenarioType::Waves);
+    if let Some(waves) = request.waves {
+        let mut messages = Vec::new();
+        if !(1..=MAX_WAVES).contains(&waves) {
+            messages.push(format!("must be between 1 and {MAX_WAVES}"));
+        }
+        if !is_waves {
+            messages.push("requires scenario_type \"waves\"".to_string());
+        }
+        if !messages.is_empty() {
+            errors.push(ValidationIssue {
+                field: "waves",
+                messages,
+            });
+        }
+    }
+
+    if let Some(repair) = request.wave_repair {
+        let mut messages = Vec::new();
+        if !(0.0..=1.0).contains(&repair) {
+            messages.push("must be between 0 and 1".to_string());
+        }
+        if !is_waves {
+            messages.push("requires scenario_type \"waves\"".to_string());
+        }
+        if !messages.is_empty() {
+            errors.push(ValidationIssue {
+                field: "wave_repair",
+                messages,
+            });
+        }
+    }
+
+    if let Some(hull) = request.attacker_hull_pct {
+        if !(hull > 0.0 && hull <= 1.0) {
+            errors.push(ValidationIssue {
+                field: "attacker_hull_pct",
+                messages: vec!["must be above 0 and at most 1".to_string()],
+            });
+        }
+    }
+
+    if let Some(shield) = request.attacker_shield_pct {
+        if !(0.0..=1.0).contains(&shield) {
+            errors.push(ValidationIssue {
+                field: "attacker_shield_pct",
+                messages: vec!["must be between 0 and 1".to_string()],
+            });
+        }
+    }
+
+    if let Some(top) = request.explain_top {
+        if top > MAX_EXPLAIN_TOP {
+            errors.push(ValidationIssue {
+                field: "explain_top",
+                messages: vec![format!("must be at most {MAX_EXPLAIN_TOP}")],
+            });
+        }
+    }
+
+    if let Some(url) = &request.notify_webhook {
+        if let Err(message) = webhooks.check_url(url) {
+            errors.push(ValidationIssue {
+                field: "notify_webhook",
+                messages: vec![message],
             });
         }
     }
@@ -105,6 +379,83 @@ pub fn validate_request(
     }))
 }
 
+fn validate_genetic_overrides(ga: &GeneticOverrides, errors: &mut Vec<ValidationIssue>) {
+    if let Some(size) = ga.population_size {
+        if !(2..=MAX_GA_POPULATION).contains(&size) {
+            errors.push(ValidationIssue {
+                field: "ga.population_size",
+                messages: vec![format!("must be between 2 and {MAX_GA_POPULATION}")],
+            });
+        }
+    }
+    if let Some(generations) = ga.generations {
+        if !(1..=MAX_GA_GENERATIONS).contains(&generations) {
+            errors.push(ValidationIssue {
+                field: "ga.generations",
+                messages: vec![format!("must be between 1 and {MAX_GA_GENERATIONS}")],
+            });
+        }
+    }
+    if let Some(rate) = ga.mutation_rate {
+        if !(0.0..=1.0).contains(&rate) {
+            errors.push(ValidationIssue {
+                field: "ga.mutation_rate",
+                messages: vec!["must be between 0 and 1".to_string()],
+            });
+        }
+    }
+    if let Some(count) = ga.elitism_count {
+        let population = ga.population_size.unwrap_or(MAX_GA_POPULATION);
+        if count >= population {
+            errors.push(ValidationIssue {
+                field: "ga.elitism_count",
+                messages: vec!["must be less than population_size".to_string()],
+            });
+        }
+    }
+    if let Some(limit) = ga.stagnation_limit {
+        if limit > MAX_GA_GENERATIONS {
+            errors.push(ValidationIssue {
+                field: "ga.stagnation_limit",
+                messages: vec![format!("must be at most {MAX_GA_GENERATIONS}")],
+            });
+        }
+    }
+    if let Some(target) = ga.target_fitness {
+        if !target.is_finite() || target < 0.0 {
+            errors.push(ValidationIssue {
+                field: "ga.target_fitness",
+                messages: vec!["must be a non-negative number".to_string()],
+            });
+        }
+    }
+    if let Some(islands) = ga.islands {
+        if !(1..=MAX_GA_ISLANDS).contains(&islands) {
+            errors.push(ValidationIssue {
+                field: "ga.islands",
+                messages: vec![format!("must be between 1 and {MAX_GA_ISLANDS}")],
+            });
+        }
+    }
+    if let Some(interval) = ga.migration_interval {
+        if !(1..=MAX_GA_GENERATIONS).contains(&interval) {
+            errors.push(ValidationIssue {
+                field: "ga.migration_interval",
+                messages: vec![format!("must be between 1 and {MAX_GA_GENERATIONS}")],
+            });
+        }
+    }
+    if let Some(count) = ga.migration_count {
+        let population = ga.population_size.unwrap_or(MAX_GA_POPULATION);
+        if count >= population {
+            errors.push(ValidationIssue {
+                field: "ga.migration_count",
+                messages: vec!["must be less than population_size".to_string()],
+            });
+        }
+    }
+}
+
 pub fn parse_below_decks_strategy(s: Option<&String>) -> BelowDecksStrategy {
     match s.as_deref() {
         Some(v) if v.trim().eq_ignore_ascii_case("exploration") => BelowDecksStrategy::Exploration,
@@ -112,22 +463,40 @@ pub fn parse_below_decks_strategy(s: Option<&String>) -> BelowDecksStrategy {
     }
 }
 
+/// Whether `strategy` is "auto"; [parse_strategy] treats it as exhaustive.
+pub fn is_auto_strategy(s: Option<&String>) -> bool {
+    s.is_some_and(|v| v.trim().eq_ignore_ascii_case("auto"))
+}
+
 pub fn parse_strategy(s: Option<&String>) -> OptimizerStrategy {
     match s.as_deref() {
         Some(v) if v.trim().eq_ignore_ascii_case("genetic") => OptimizerStrategy::Genetic,
         Some(v) if v.trim().eq_ignore_ascii_case("tiered") => OptimizerStrategy::Tiered,
+        Some(v) if v.trim().eq_ignore_ascii_case("two_phase") => OptimizerStrategy::TwoPhase,
         _ => OptimizerStrategy::Exhaustive,
     }
 }
 
+/// `None` when set to an unknown value; unset means [ScenarioType::Offense].
+pub fn parse_scenario_type(s: Option<&String>) -> Option<ScenarioType> {
+    match s.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("offense") => Some(ScenarioType::Offense),
+        Some("defense") => Some(ScenarioType::Defense),
+        Some("mining") => Some(ScenarioType::Mining),
+        Some("waves") => Some(ScenarioType::Waves),
+        Some("points_per_hour") => Some(ScenarioType::PointsPerHour),
+        _ => None,
+    }
+}
+
 /// Parses query string for optimize estimate: ship, hostile, sims, optional max_candidates,
-/// optional prioritize_below_decks_ability.
+/// optional prioritize_below_decks_ability (or its alias only_below_decks_with_ability).
 pub fn parse_optimize_estimate_query(
     query: &str,
 ) -> (String, String, u32, Option<u32>, bool) {
     let mut ship = String::new();
     let mut hostile = String::new();
-    let mut sims = DEFAULT_SIMS;
+    let mut sims = default_sims();
     let mut max_candidates: Option<u32> = None;
     let mut prioritize_below_decks_ability = false;
     for pair in query.split('&') {
@@ -137,9 +506,9 @@ pub fn parse_optimize_estimate_query(
             match key {
                 "ship" => ship = value.to_string(),
                 "hostile" => hostile = value.to_string(),
-                "sims" => sims = value.parse().unwrap_or(DEFAULT_SIMS),
+                "sims" => sims = value.parse().unwrap_or(sims),
                 "max_candidates" => max_candidates = value.parse().ok(),
-                "prioritize_below_decks_ability" => {
+                "prioritize_below_decks_ability" | "only_below_decks_with_ability" => {
                     prioritize_below_decks_ability =
                         value.eq_ignore_ascii_case("true") || value == "1"
                 }
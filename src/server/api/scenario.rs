@@ -0,0 +1,612 @@
+//! Scenario files: one YAML or JSON document naming the ship, crew, hostile, profile, profile
+//! bonus overrides, fight rules and objective of a simulation, so an experiment is reproduced by
+//! handing the same file to `kobayashi simulate --scenario` or `POST /api/simulate/scenario`.
+//!
+//! A `matrix` section turns the file into a batch: each axis lists values for one field, and
+//! `kobayashi matrix` or `POST /api/simulate/matrix` runs every combination in parallel.
+
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use crate::combat::types::MAX_COMBAT_ROUNDS;
+use crate::data::data_registry::DataRegistry;
+use crate::data::officer::UnknownOfficerMode;
+use crate::data::profile::PlayerBonuses;
+use crate::data::profile_index::load_profile_index;
+use crate::optimizer::monte_carlo::run_monte_carlo_with_shared;
+use crate::optimizer::ranking::{objective_score, ScenarioType};
+use crate::optimizer::{scenario_shared_data_from_registry, OptimizationScenario};
+
+use super::requests::{default_sims, DEFAULT_WAVES, MAX_MINING_ROUNDS, MAX_SIMS, MAX_WAVES};
+use super::{
+    binomial_95_ci, check_known_officers, crew_candidate_from_request, resolve_known_ids,
+    resolve_profile_id, SimulateCrew, SimulateError, SimulateStats,
+    PLACEHOLDER_COMBATANTS_WARNING,
+};
+
+/// Upper bound for the runs a matrix expands to.
+pub const MAX_MATRIX_RUNS: usize = 256;
+/// Crew seats a matrix axis may name without the `crew.` prefix.
+const CREW_AXES: [&str; 3] = ["captain", "bridge", "below_deck"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioFile {
+    /// Label echoed in the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[schemars(length(min = 1))]
+    pub ship: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ship_tier: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ship_level: Option<u32>,
+    #[schemars(length(min = 1))]
+    pub hostile: String,
+    pub crew: SimulateCrew,
+    /// Profile whose roster, tech and bonuses apply. None = the request's profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profile_overrides: ScenarioProfileOverrides,
+    #[serde(default)]
+    pub rules: ScenarioRules,
+    /// What the crew is scored on; the same objectives as the optimizer's `scenario_type`.
+    #[serde(default)]
+    pub objective: ScenarioType,
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_sims: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Reject a ship or hostile that does not resolve. None = true.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+    /// What to do with a crew officer that matches no officer data. None = strict.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unknown_officers: Option<UnknownOfficerMode>,
+}
+
+/// Changes to the profile for this scenario only; the saved profile is not touched.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioProfileOverrides {
+    /// Each stat set here replaces the profile's total for that stat (after synced tech,
+    /// buildings and research are merged in).
+    #[serde(default)]
+    pub bonuses: PlayerBonuses,
+    /// Overrides [crate::data::profile::PlayerProfile::officer_traits].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub officer_traits: Option<bool>,
+    /// Overrides [crate::data::profile::PlayerProfile::officer_stats].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub officer_stats: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioRules {
+    /// Round limit. None = the hostile's limit.
+    #[schemars(range(min = 1, max = "MAX_COMBAT_ROUNDS"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounds: Option<u32>,
+    /// Mining objective only: opening rounds the player's ship holds fire.
+    #[schemars(range(max = "MAX_MINING_ROUNDS"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mining_rounds: Option<u32>,
+    /// Waves objective only: hostiles fought back to back. None = 3.
+    #[schemars(range(min = 1, max = "MAX_WAVES"))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub waves: Option<u32>,
+    /// Waves objective only: share (0–1) of missing hull and shields repaired between waves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wave_repair: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ScenarioResponse {
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub ship: String,
+    pub hostile: String,
+    /// Profile the scenario ran with.
+    pub profile: String,
+    pub objective: ScenarioType,
+    /// The crew's optimizer score for `objective` (see [objective_score]).
+    pub score: f32,
+    pub stats: SimulateStats,
+    pub seed: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Schema of a `/api/simulate/matrix` body: a scenario plus its matrix axes.
+#[derive(Debug, Clone, JsonSchema)]
+pub struct MatrixFile {
+    #[serde(flatten)]
+    pub scenario: ScenarioFile,
+    /// Values per field, keyed by field path: `hostile`, `rules.rounds`,
+    /// `profile_overrides.bonuses.weapon_damage`; `captain`, `bridge` and `below_deck` stand for
+    /// `crew.*`. Every combination is run.
+    pub matrix: BTreeMap<String, Vec<Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MatrixRun {
+    /// This run's value for each axis.
+    pub axes: BTreeMap<String, Value>,
+    pub result: ScenarioResponse,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct MatrixResponse {
+    pub status: &'static str,
+    /// Axis names, sorted; runs vary the last axis fastest.
+    pub axes: Vec<String>,
+    pub runs: Vec<MatrixRun>,
+}
+
+/// Every rule, override and profile problem in `file`, as `field: message` lines.
+fn scenario_issues(file: &ScenarioFile) -> Vec<String> {
+    let mut issues = Vec::new();
+    if file.ship.trim().is_empty() {
+        issues.push("ship: is required".to_string());
+    }
+    if file.hostile.trim().is_empty() {
+        issues.push("hostile: is required".to_string());
+    }
+    if let Some(n) = file.num_sims {
+        if n == 0 || n > MAX_SIMS {
+            issues.push(format!("num_sims: must be between 1 and {MAX_SIMS}"));
+        }
+    }
+    if let Some(id) = file.profile.as_deref() {
+        if !load_profile_index().profiles.iter().any(|p| p.id == id) {
+            issues.push(format!("profile: unknown profile '{id}'"));
+        }
+    }
+    if let Err(bonus_issues) = PlayerBonuses::from_map(&file.profile_overrides.bonuses.to_map()) {
+        issues.extend(
+            bonus_issues
+                .into_iter()
+                .map(|issue| format!("profile_overrides.{}: {}", issue.field, issue.message)),
+        );
+    }
+
+    let rules = &file.rules;
+    if let Some(rounds) = rules.rounds {
+        if !(1..=MAX_COMBAT_ROUNDS).contains(&rounds) {
+            issues.push(format!("rules.rounds: must be between 1 and {MAX_COMBAT_ROUNDS}"));
+        }
+    }
+    if let Some(mining_rounds) = rules.mining_rounds {
+        if mining_rounds > MAX_MINING_ROUNDS {
+            issues.push(format!("rules.mining_rounds: must be at most {MAX_MINING_ROUNDS}"));
+        }
+        if file.objective != ScenarioType::Mining {
+            issues.push("rules.mining_rounds: requires objective \"mining\"".to_string());
+        }
+    }
+    let is_waves = file.objective == ScenarioType::Waves;
+    if let Some(waves) = rules.waves {
+        if !(1..=MAX_WAVES).contains(&waves) {
+            issues.push(format!("rules.waves: must be between 1 and {MAX_WAVES}"));
+        }
+        if !is_waves {
+            issues.push("rules.waves: requires objective \"waves\"".to_string());
+        }
+    }
+    if let Some(repair) = rules.wave_repair {
+        if !(0.0..=1.0).contains(&repair) {
+            issues.push("rules.wave_repair: must be between 0 and 1".to_string());
+        }
+        if !is_waves {
+            issues.push("rules.wave_repair: requires objective \"waves\"".to_string());
+        }
+    }
+    issues
+}
+
+/// A scenario document (YAML, or JSON, which YAML reads as well) as a JSON value.
+fn scenario_document(body: &str) -> Result<Value, SimulateError> {
+    serde_yaml::from_str(body)
+        .map_err(|e| SimulateError::Validation(format!("Invalid scenario: {e}")))
+}
+
+/// The [ScenarioFile] in `document`, checked with [scenario_issues].
+fn scenario_from_document(document: Value) -> Result<ScenarioFile, SimulateError> {
+    let file: ScenarioFile = serde_json::from_value(document)
+        .map_err(|e| SimulateError::Validation(format!("Invalid scenario: {e}")))?;
+    let issues = scenario_issues(&file);
+    if !issues.is_empty() {
+        return Err(SimulateError::Validation(issues.join("; ")));
+    }
+    Ok(file)
+}
+
+/// POST /api/simulate/scenario and `kobayashi simulate --scenario`: runs the scenario document in
+/// `body`. The file's `profile` wins over `profile_id`.
+pub fn simulate_scenario_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, SimulateError> {
+    let document = scenario_document(body)?;
+    if document.get("matrix").is_some() {
+        return Err(SimulateError::Validation(
+            "scenario has a matrix; run it with /api/simulate/matrix or `kobayashi matrix`"
+                .to_string(),
+        ));
+    }
+    let response = run_scenario(registry, scenario_from_document(document)?, profile_id)?;
+    serde_json::to_string_pretty(&response).map_err(SimulateError::Parse)
+}
+
+/// One document per combination of the `matrix` axes in `document` (the last axis varying
+/// fastest), each with the axis values it was given.
+#[allow(clippy::type_complexity)]
+fn expand_matrix(
+    mut document: Value,
+) -> Result<(Vec<String>, Vec<(BTreeMap<String, Value>, Value)>), SimulateError> {
+    let invalid = |msg: String| SimulateError::Validation(msg);
+    let matrix = match document.as_object_mut().and_then(|root| root.remove("matrix")) {
+        Some(Value::Object(matrix)) if !matrix.is_empty() => matrix,
+        _ => return Err(invalid("matrix: must map field paths to lists of values".to_string())),
+    };
+    let mut axes = Vec::with_capacity(matrix.len());
+    let mut values = Vec::with_capacity(matrix.len());
+    for (axis, list) in matrix {
+        match list {
+            Value::Array(list) if !list.is_empty() => values.push(list),
+            _ => return Err(invalid(format!("matrix.{axis}: must be a non-empty list"))),
+        }
+        axes.push(axis);
+    }
+    let runs = values
+        .iter()
+        .try_fold(1usize, |n, list| n.checked_mul(list.len()))
+        .filter(|&n| n <= MAX_MATRIX_RUNS)
+        .ok_or_else(|| invalid(format!("matrix: expands to more than {MAX_MATRIX_RUNS} runs")))?;
+
+    let mut out = Vec::with_capacity(runs);
+    for index in 0..runs {
+        let mut run = document.clone();
+        let mut labels = BTreeMap::new();
+        let mut rest = index;
+        for (axis, list) in axes.iter().zip(&values).rev() {
+            let value = list[rest % list.len()].clone();
+            rest /= list.len();
+            set_path(&mut run, axis, value.clone()).map_err(|section| {
+                invalid(format!("matrix.{axis}: `{section}` is not a section"))
+            })?;
+            labels.insert(axis.clone(), value);
+        }
+        out.push((labels, run));
+    }
+    Ok((axes, out))
+}
+
+/// Sets the field at matrix axis `axis` in `document`, creating missing sections. Errors with
+/// the path segment that holds a value instead of a section.
+fn set_path(document: &mut Value, axis: &str, value: Value) -> Result<(), String> {
+    let path: Vec<&str> = if CREW_AXES.contains(&axis) {
+        vec!["crew", axis]
+    } else {
+        axis.split('.').collect()
+    };
+    let (last, sections) = path.split_last().expect("split always yields a segment");
+    let mut current = document;
+    for section in sections {
+        current = current
+            .as_object_mut()
+            .ok_or_else(|| section.to_string())?
+            .entry(*section)
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+    current
+        .as_object_mut()
+        .ok_or_else(|| sections.last().unwrap_or(last).to_string())?
+        .insert(last.to_string(), value);
+    Ok(())
+}
+
+/// `hostile=swarm_33, captain=kirk` for error messages.
+fn axes_label(axes: &BTreeMap<String, Value>) -> String {
+    axes.iter()
+        .map(|(axis, value)| match value {
+            Value::String(s) => format!("{axis}={s}"),
+            other => format!("{axis}={other}"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Expands the matrix scenario in `body` and runs every combination in parallel. Every run is
+/// validated before any is simulated; the first failing run fails the batch.
+pub fn run_matrix(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<MatrixResponse, SimulateError> {
+    let document = scenario_document(body)?;
+    if document.get("matrix").is_none() {
+        return Err(SimulateError::Validation(
+            "matrix: is required; run a single scenario with /api/simulate/scenario".to_string(),
+        ));
+    }
+    let (axes, expanded) = expand_matrix(document)?;
+    let files = expanded
+        .into_iter()
+        .map(|(labels, run)| match scenario_from_document(run) {
+            Ok(file) => Ok((labels, file)),
+            Err(SimulateError::Validation(msg)) => Err(SimulateError::Validation(format!(
+                "matrix run {}: {msg}",
+                axes_label(&labels)
+            ))),
+            Err(e) => Err(e),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let runs = files
+        .into_par_iter()
+        .map(|(labels, file)| {
+            run_scenario(registry, file, profile_id).map(|result| MatrixRun {
+                axes: labels,
+                result,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(MatrixResponse {
+        status: "ok",
+        axes,
+        runs,
+    })
+}
+
+/// POST /api/simulate/matrix: [run_matrix] as JSON.
+pub fn simulate_matrix_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, SimulateError> {
+    let response = run_matrix(registry, body, profile_id)?;
+    serde_json::to_string_pretty(&response).map_err(SimulateError::Parse)
+}
+
+/// The matrix as CSV: one row per run, axis columns first, then the run's result.
+pub fn matrix_csv(response: &MatrixResponse) -> Result<String, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let columns = [
+        "ship",
+        "hostile",
+        "objective",
+        "score",
+        "win_rate",
+        "stall_rate",
+        "loss_rate",
+        "avg_hull_remaining",
+        "n",
+        "seed",
+    ];
+    writer.write_record(response.axes.iter().map(String::as_str).chain(columns))?;
+    for run in &response.runs {
+        let result = &run.result;
+        let axes = response.axes.iter().map(|axis| match run.axes.get(axis) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        });
+        let values = [
+            result.ship.clone(),
+            result.hostile.clone(),
+            result.objective.as_str().to_string(),
+            result.score.to_string(),
+            result.stats.win_rate.to_string(),
+            result.stats.stall_rate.to_string(),
+            result.stats.loss_rate.to_string(),
+            result.stats.avg_hull_remaining.to_string(),
+            result.stats.n.to_string(),
+            result.seed.to_string(),
+        ];
+        writer.write_record(axes.chain(values))?;
+    }
+    let bytes = writer.into_inner().map_err(|e| csv::Error::from(e.into_error()))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Simulates one validated scenario.
+fn run_scenario(
+    registry: &DataRegistry,
+    mut file: ScenarioFile,
+    profile_id: Option<&str>,
+) -> Result<ScenarioResponse, SimulateError> {
+    let candidate =
+        crew_candidate_from_request(registry, &file.crew).map_err(SimulateError::Validation)?;
+    resolve_known_ids(
+        registry,
+        file.strict,
+        &mut file.ship,
+        file.ship_tier,
+        file.ship_level,
+        Some(&mut file.hostile),
+    )
+    .map_err(SimulateError::UnknownIds)?;
+    check_known_officers(registry, file.unknown_officers, &candidate)
+        .map_err(SimulateError::UnknownIds)?;
+
+    let profile = resolve_profile_id(file.profile.as_deref().or(profile_id));
+    let num_sims = file.num_sims.unwrap_or_else(default_sims);
+    let seed = file.seed.unwrap_or(0);
+    let (waves, wave_repair) = if file.objective == ScenarioType::Waves {
+        (file.rules.waves.unwrap_or(DEFAULT_WAVES), file.rules.wave_repair.unwrap_or(0.0))
+    } else {
+        (0, 0.0)
+    };
+    let scenario = OptimizationScenario {
+        ship: &file.ship,
+        hostile: &file.hostile,
+        ship_tier: file.ship_tier,
+        ship_level: file.ship_level,
+        simulation_count: num_sims as usize,
+        seed,
+        profile_id: Some(&profile),
+        scenario_type: file.objective,
+        mining_rounds: file.rules.mining_rounds.unwrap_or(0),
+        waves,
+        wave_repair,
+        unknown_officers: file.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+        ..OptimizationScenario::default()
+    };
+
+    let mut shared = scenario_shared_data_from_registry(registry, &scenario);
+    if let Some(rounds) = file.rules.rounds {
+        shared = shared.with_rounds(rounds);
+    }
+    let overrides = &file.profile_overrides;
+    shared.profile.bonuses.extend(overrides.bonuses.to_map());
+    if let Some(officer_traits) = overrides.officer_traits {
+        shared.profile.officer_traits = officer_traits;
+    }
+    if let Some(officer_stats) = overrides.officer_stats {
+        shared.profile.officer_stats = officer_stats;
+    }
+    let placeholder = shared.using_placeholder_combatants;
+    let result = run_monte_carlo_with_shared(
+        shared,
+        std::slice::from_ref(&candidate),
+        num_sims as usize,
+        seed,
+        false,
+    )
+    .into_iter()
+    .next()
+    .ok_or_else(|| SimulateError::Validation("scenario produced no result".to_string()))?;
+
+    let wins = (result.win_rate * num_sims as f64).round() as u32;
+    let mut warnings = Vec::new();
+    if placeholder {
+        warnings.push(PLACEHOLDER_COMBATANTS_WARNING.to_string());
+    }
+    Ok(ScenarioResponse {
+        status: "ok",
+        name: file.name,
+        ship: file.ship,
+        hostile: file.hostile,
+        profile,
+        objective: file.objective,
+        score: objective_score(&result, file.objective),
+        stats: SimulateStats {
+            win_rate: result.win_rate,
+            stall_rate: result.stall_rate,
+            loss_rate: result.loss_rate,
+            avg_hull_remaining: result.avg_hull_remaining,
+            n: num_sims,
+            win_rate_95_ci: Some(binomial_95_ci(wins, num_sims)),
+        },
+        seed,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO_YAML: &str = "
+name: swarm check
+ship: saladin
+hostile: '2918121098'
+crew:
+  captain: kirk
+  bridge: [spock, null]
+profile_overrides:
+  bonuses:
+    weapon_damage: 0.25
+rules:
+  rounds: 12
+  waves: 2
+  wave_repair: 0.5
+objective: waves
+num_sims: 50
+seed: 7
+";
+
+    #[test]
+    fn yaml_and_json_scenarios_parse_to_the_same_file() {
+        let yaml: ScenarioFile = serde_yaml::from_str(SCENARIO_YAML).unwrap();
+        let json: ScenarioFile = serde_yaml::from_str(
+            r#"{"name":"swarm check","ship":"saladin","hostile":"2918121098",
+                "crew":{"captain":"kirk","bridge":["spock",null]},
+                "profile_overrides":{"bonuses":{"weapon_damage":0.25}},
+                "rules":{"rounds":12,"waves":2,"wave_repair":0.5},
+                "objective":"waves","num_sims":50,"seed":7}"#,
+        )
+        .unwrap();
+        for file in [&yaml, &json] {
+            assert_eq!(file.objective, ScenarioType::Waves);
+            assert_eq!(file.rules.rounds, Some(12));
+            assert_eq!(file.profile_overrides.bonuses.weapon_damage, Some(0.25));
+            assert_eq!(file.crew.bridge, Some(vec![Some("spock".to_string()), None]));
+            assert!(scenario_issues(file).is_empty());
+        }
+    }
+
+    #[test]
+    fn matrix_expands_every_combination_last_axis_fastest() {
+        let document: Value = serde_yaml::from_str(
+            "ship: saladin\nhostile: a\ncrew: {captain: kirk}\n\
+             matrix:\n  captain: [kirk, picard]\n  hostile: [a, b, c]\n  rules.rounds: [5]\n",
+        )
+        .unwrap();
+        let (axes, runs) = expand_matrix(document).unwrap();
+        assert_eq!(axes, ["captain", "hostile", "rules.rounds"]);
+        assert_eq!(runs.len(), 6);
+        let picked: Vec<(&str, &str)> = runs
+            .iter()
+            .map(|(_, doc)| {
+                (doc["crew"]["captain"].as_str().unwrap(), doc["hostile"].as_str().unwrap())
+            })
+            .collect();
+        assert_eq!(picked[..4], [("kirk", "a"), ("kirk", "b"), ("kirk", "c"), ("picard", "a")]);
+        let (labels, doc) = &runs[5];
+        assert_eq!(axes_label(labels), "captain=picard, hostile=c, rules.rounds=5");
+        assert_eq!(doc["rules"]["rounds"], 5);
+        assert!(doc.get("matrix").is_none());
+        assert!(scenario_from_document(doc.clone()).is_ok());
+    }
+
+    #[test]
+    fn matrix_rejects_empty_oversized_and_misplaced_axes() {
+        let expand = |yaml: &str| expand_matrix(serde_yaml::from_str(yaml).unwrap()).unwrap_err();
+        assert!(expand("ship: a\nmatrix: {hostile: []}").to_string().contains("non-empty list"));
+        let big = format!("matrix: {{seed: [{}]}}", vec!["1"; MAX_MATRIX_RUNS + 1].join(","));
+        assert!(expand(&big).to_string().contains("more than"));
+        assert!(expand("ship: a\nmatrix: {ship.tier: [1]}").to_string().contains("`ship`"));
+
+        let response = MatrixResponse {
+            status: "ok",
+            axes: vec!["captain".to_string()],
+            runs: Vec::new(),
+        };
+        assert!(matrix_csv(&response).unwrap().starts_with("captain,ship,hostile,objective,"));
+    }
+
+    #[test]
+    fn scenario_issues_name_each_bad_rule_and_override() {
+        let mut file: ScenarioFile = serde_yaml::from_str(SCENARIO_YAML).unwrap();
+        file.objective = ScenarioType::Offense;
+        file.rules.rounds = Some(0);
+        file.profile_overrides.bonuses.crit_chance = Some(3.0);
+        let issues = scenario_issues(&file);
+        assert!(issues.iter().any(|i| i.starts_with("rules.rounds:")));
+        assert!(issues.iter().any(|i| i == "rules.waves: requires objective \"waves\""));
+        assert!(issues.iter().any(|i| i.starts_with("profile_overrides.bonuses.crit_chance:")));
+
+        let typo =
+            serde_yaml::from_str::<ScenarioFile>("ship: a\nhostile: b\ncrew: {}\nrule: {}\n");
+        assert!(typo.unwrap_err().to_string().contains("unknown field `rule`"));
+    }
+}
@@ -0,0 +1,130 @@
+//! In-memory LRU of `/api/simulate` payloads in front of the on-disk result cache, for the UI
+//! pattern of re-submitting the same crew while tweaking one slot. Keyed like the disk cache
+//! (request hash); each entry keeps the data fingerprint it was computed against and is dropped
+//! on a mismatch, so data updates and profile edits invalidate it.
+//!
+//! Follows the result cache switch ([result_cache::is_enabled]). `KOBAYASHI_SIMULATE_LRU_ENTRIES`
+//! sets the capacity (default [DEFAULT_LRU_ENTRIES]; `0` turns the memory tier off).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::Serialize;
+
+use crate::optimizer::result_cache::{self, SIMULATE_NAMESPACE};
+
+pub const LRU_ENTRIES_ENV: &str = "KOBAYASHI_SIMULATE_LRU_ENTRIES";
+pub const DEFAULT_LRU_ENTRIES: usize = 128;
+
+static CACHE: OnceLock<Mutex<SimulateLru>> = OnceLock::new();
+
+fn cache() -> &'static Mutex<SimulateLru> {
+    CACHE.get_or_init(|| {
+        let capacity = std::env::var(LRU_ENTRIES_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(DEFAULT_LRU_ENTRIES);
+        Mutex::new(SimulateLru::new(capacity))
+    })
+}
+
+struct LruEntry {
+    fingerprint: String,
+    last_used: u64,
+    payload: Arc<str>,
+}
+
+/// Capacity-bounded map; evicts the least recently used entry. Small enough that a linear scan
+/// on eviction beats maintaining a linked list.
+pub(super) struct SimulateLru {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, LruEntry>,
+}
+
+impl SimulateLru {
+    pub(super) fn new(capacity: usize) -> Self {
+        Self { capacity, tick: 0, entries: HashMap::new() }
+    }
+
+    pub(super) fn get(&mut self, hash: &str, fingerprint: &str) -> Option<Arc<str>> {
+        self.tick += 1;
+        let entry = self.entries.get_mut(hash)?;
+        if entry.fingerprint != fingerprint {
+            self.entries.remove(hash);
+            return None;
+        }
+        entry.last_used = self.tick;
+        Some(entry.payload.clone())
+    }
+
+    pub(super) fn put(&mut self, hash: String, fingerprint: String, payload: Arc<str>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if !self.entries.contains_key(&hash) && self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        let entry = LruEntry { fingerprint, last_used: self.tick, payload };
+        self.entries.insert(hash, entry);
+    }
+}
+
+/// Request hash and data fingerprint of one simulate request.
+pub(super) struct MemoKey {
+    hash: String,
+    fingerprint: String,
+}
+
+impl MemoKey {
+    /// `None` when the result cache is switched off.
+    pub(super) fn new<K: Serialize>(key: &K, profile_id: Option<&str>) -> Option<Self> {
+        result_cache::is_enabled().then(|| Self {
+            hash: result_cache::scenario_hash(SIMULATE_NAMESPACE, key),
+            fingerprint: result_cache::data_fingerprint(profile_id),
+        })
+    }
+
+    pub(super) fn lookup(&self) -> Option<String> {
+        let payload = cache().lock().unwrap().get(&self.hash, &self.fingerprint)?;
+        tracing::debug!(hash = %self.hash, "simulate served from memory cache");
+        Some(payload.to_string())
+    }
+
+    pub(super) fn store(&self, payload: &str) {
+        cache().lock().unwrap().put(self.hash.clone(), self.fingerprint.clone(), payload.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_and_drops_stale_fingerprints() {
+        let mut lru = SimulateLru::new(2);
+        lru.put("a".into(), "v1".into(), "A".into());
+        lru.put("b".into(), "v1".into(), "B".into());
+        assert_eq!(lru.get("a", "v1").as_deref(), Some("A"));
+        // "b" is now the least recently used.
+        lru.put("c".into(), "v1".into(), "C".into());
+        assert!(lru.get("b", "v1").is_none());
+        assert_eq!(lru.get("c", "v1").as_deref(), Some("C"));
+
+        // New data version: the old entry is a miss and is removed.
+        assert!(lru.get("a", "v2").is_none());
+        assert!(lru.get("a", "v1").is_none());
+
+        let mut off = SimulateLru::new(0);
+        off.put("a".into(), "v1".into(), "A".into());
+        assert!(off.get("a", "v1").is_none());
+    }
+}
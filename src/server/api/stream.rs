@@ -0,0 +1,294 @@
+//! One traced fight: the `/ws/simulate` channel streams it a round at a time so the UI can
+//! animate combat without buffering a multi-MB event log, and `POST /api/simulate/trace` returns
+//! it a page of events at a time. Both take an optional `filter` so long fights only carry the
+//! events the caller looks at.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::combat::{
+    CombatEvent, DamageBreakdown, SimulationResult, TraceFilter, TraceFilterError,
+};
+use crate::data::data_registry::DataRegistry;
+use crate::data::officer::UnknownOfficerMode;
+use crate::optimizer::crew_generator::CrewCandidate;
+use crate::optimizer::monte_carlo::stream_fight_with_registry;
+
+use super::{
+    check_known_officers, crew_candidate_from_request, resolve_known_ids, SimulateError,
+    SimulateRequest, PLACEHOLDER_COMBATANTS_WARNING,
+};
+
+/// Events per `/api/simulate/trace` page when `limit` is unset.
+pub const DEFAULT_TRACE_PAGE_SIZE: u32 = 500;
+/// Upper bound for `limit`.
+pub const MAX_TRACE_PAGE_SIZE: u32 = 5000;
+
+/// Events a traced fight keeps; see [TraceFilter]. Omitted fields keep everything.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct TraceFilterRequest {
+    /// Event types to keep (`combat::TRACE_EVENT_TYPES`, e.g. "attack_roll").
+    pub event_types: Option<Vec<String>>,
+    /// Phases to keep (`combat::TRACE_PHASES`, e.g. "round_start").
+    pub phases: Option<Vec<String>>,
+    /// First round kept; round 0 holds combat-begin events.
+    pub first_round: Option<u32>,
+    /// Last round kept. None = through the end of the fight.
+    pub last_round: Option<u32>,
+}
+
+impl TraceFilterRequest {
+    fn to_filter(&self) -> Result<TraceFilter, TraceFilterError> {
+        TraceFilter::default()
+            .event_types(self.event_types.as_deref().unwrap_or_default())?
+            .phases(self.phases.as_deref().unwrap_or_default())?
+            .rounds(self.first_round.unwrap_or(0), self.last_round)
+    }
+}
+
+/// `/api/simulate` body plus trace filter and paging. `/ws/simulate` reads the same shape and
+/// ignores `offset` and `limit`.
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+pub struct TraceRequest {
+    #[serde(flatten)]
+    pub simulate: SimulateRequest,
+    pub filter: Option<TraceFilterRequest>,
+    /// Kept events skipped before the page. None = 0.
+    pub offset: Option<u32>,
+    #[schemars(range(min = 1, max = "MAX_TRACE_PAGE_SIZE"))]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TraceResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    pub seed: u64,
+    pub attacker_won: bool,
+    pub rounds_simulated: u32,
+    /// Events the filter kept over the whole fight.
+    pub total_events: u32,
+    pub offset: u32,
+    pub limit: u32,
+    /// `offset` of the next page; None on the last page.
+    pub next_offset: Option<u32>,
+    #[schemars(with = "Vec<serde_json::Value>")]
+    pub events: Vec<CombatEvent>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// One WebSocket text message. A run sends `start`, then one `round` per round that recorded
+/// events (round 0 holds combat-begin events), then `result`; or a single `error`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimulateStreamFrame {
+    Start {
+        ship: String,
+        hostile: String,
+        seed: u64,
+        captain: String,
+        bridge: Vec<String>,
+        below_decks: Vec<String>,
+    },
+    Round {
+        round: u32,
+        events: Vec<CombatEvent>,
+    },
+    Result {
+        attacker_won: bool,
+        winner_by_round_limit: bool,
+        rounds_simulated: u32,
+        total_damage: f64,
+        attacker_hull_remaining: f64,
+        attacker_shield_remaining: f64,
+        defender_hull_remaining: f64,
+        defender_shield_remaining: f64,
+        attacker_damage: DamageBreakdown,
+        defender_damage: DamageBreakdown,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        warnings: Vec<String>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl SimulateStreamFrame {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| {
+            r#"{"type":"error","message":"failed to serialize frame"}"#.to_string()
+        })
+    }
+}
+
+/// Parsed and resolved trace request: the request, its crew, filter and seed.
+fn prepare_trace(
+    registry: &DataRegistry,
+    body: &str,
+) -> Result<(TraceRequest, CrewCandidate, TraceFilter, u64), SimulateError> {
+    let mut req: TraceRequest = serde_json::from_str(body).map_err(SimulateError::Parse)?;
+    let sim = &mut req.simulate;
+    if sim.ship.trim().is_empty() || sim.hostile.trim().is_empty() {
+        return Err(SimulateError::Validation(
+            "ship and hostile are required".to_string(),
+        ));
+    }
+    let filter = req
+        .filter
+        .as_ref()
+        .map_or(Ok(TraceFilter::default()), TraceFilterRequest::to_filter)
+        .map_err(|e| SimulateError::Validation(e.to_string()))?;
+    let candidate: CrewCandidate =
+        crew_candidate_from_request(registry, &sim.crew).map_err(SimulateError::Validation)?;
+    resolve_known_ids(
+        registry,
+        sim.strict,
+        &mut sim.ship,
+        sim.ship_tier,
+        sim.ship_level,
+        Some(&mut sim.hostile),
+    )
+    .map_err(SimulateError::UnknownIds)?;
+    check_known_officers(registry, sim.unknown_officers, &candidate)
+        .map_err(SimulateError::UnknownIds)?;
+    let seed = sim.seed.unwrap_or(0);
+    Ok((req, candidate, filter, seed))
+}
+
+fn warnings(using_placeholder_combatants: bool) -> Vec<String> {
+    if using_placeholder_combatants {
+        vec![PLACEHOLDER_COMBATANTS_WARNING.to_string()]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Run one traced fight for a `/api/simulate`-shaped request and pass each frame to `send` as
+/// soon as its round finishes. The fight is the first Monte Carlo iteration `/api/simulate`
+/// would run with the same seed; `num_sims` is ignored.
+pub fn simulate_stream(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+    send: &mut dyn FnMut(SimulateStreamFrame),
+) -> Result<(), SimulateError> {
+    let (req, candidate, filter, seed) = prepare_trace(registry, body)?;
+    let req = req.simulate;
+    let attacker_condition = req.attacker_condition()?;
+
+    send(SimulateStreamFrame::Start {
+        ship: req.ship.clone(),
+        hostile: req.hostile.clone(),
+        seed,
+        captain: candidate.captain.clone(),
+        bridge: candidate.bridge_names(),
+        below_decks: candidate.below_deck_names(),
+    });
+
+    let mut round: Option<u32> = None;
+    let mut events: Vec<CombatEvent> = Vec::new();
+    let mut on_event = |event: CombatEvent| {
+        if round.is_some_and(|r| r != event.round_index) {
+            send(SimulateStreamFrame::Round {
+                round: round.unwrap_or_default(),
+                events: std::mem::take(&mut events),
+            });
+        }
+        round = Some(event.round_index);
+        events.push(event);
+    };
+    let (result, using_placeholder_combatants) = stream_fight_with_registry(
+        registry,
+        &req.ship,
+        &req.hostile,
+        req.ship_tier,
+        req.ship_level,
+        &candidate,
+        seed,
+        profile_id,
+        req.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+        attacker_condition,
+        filter,
+        &mut on_event,
+    );
+    if let Some(round) = round {
+        send(SimulateStreamFrame::Round { round, events });
+    }
+
+    send(SimulateStreamFrame::Result {
+        attacker_won: result.attacker_won,
+        winner_by_round_limit: result.winner_by_round_limit,
+        rounds_simulated: result.rounds_simulated,
+        total_damage: result.total_damage,
+        attacker_hull_remaining: result.attacker_hull_remaining,
+        attacker_shield_remaining: result.attacker_shield_remaining,
+        defender_hull_remaining: result.defender_hull_remaining,
+        defender_shield_remaining: result.defender_shield_remaining,
+        attacker_damage: result.attacker_damage,
+        defender_damage: result.defender_damage,
+        warnings: warnings(using_placeholder_combatants),
+    });
+    Ok(())
+}
+
+/// POST /api/simulate/trace: the `/ws/simulate` fight as one page of its (filtered) events.
+/// Events outside the page are counted but not kept.
+pub fn simulate_trace_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+) -> Result<String, SimulateError> {
+    let (req, candidate, filter, seed) = prepare_trace(registry, body)?;
+    let offset = req.offset.unwrap_or(0);
+    let limit = req.limit.unwrap_or(DEFAULT_TRACE_PAGE_SIZE);
+    if limit == 0 || limit > MAX_TRACE_PAGE_SIZE {
+        return Err(SimulateError::Validation(format!(
+            "limit must be between 1 and {MAX_TRACE_PAGE_SIZE}"
+        )));
+    }
+    let sim = req.simulate;
+    let attacker_condition = sim.attacker_condition()?;
+
+    let mut total_events = 0u32;
+    let mut events = Vec::new();
+    let page = offset..offset.saturating_add(limit);
+    let mut on_event = |event: CombatEvent| {
+        if page.contains(&total_events) {
+            events.push(event);
+        }
+        total_events += 1;
+    };
+    let (result, using_placeholder_combatants): (SimulationResult, bool) =
+        stream_fight_with_registry(
+            registry,
+            &sim.ship,
+            &sim.hostile,
+            sim.ship_tier,
+            sim.ship_level,
+            &candidate,
+            seed,
+            profile_id,
+            sim.unknown_officers.unwrap_or(UnknownOfficerMode::Strict),
+            attacker_condition,
+            filter,
+            &mut on_event,
+        );
+
+    let response = TraceResponse {
+        status: "ok",
+        ship: sim.ship,
+        hostile: sim.hostile,
+        seed,
+        attacker_won: result.attacker_won,
+        rounds_simulated: result.rounds_simulated,
+        total_events,
+        offset,
+        limit,
+        next_offset: (page.end < total_events).then_some(page.end),
+        events,
+        warnings: warnings(using_placeholder_combatants),
+    };
+    serde_json::to_string_pretty(&response).map_err(SimulateError::Parse)
+}
@@ -0,0 +1,180 @@
+//! Officer and ship portraits for the UI, proxied from a CDN and cached on disk, so the bundled
+//! UI needs no separate asset pipeline and keeps its images offline.
+//!
+//! `GET /api/assets/<path>` (e.g. `/api/assets/officers/kirk.png`) answers from
+//! `KOBAYASHI_ASSET_CACHE_DIR` (default `cache/assets/`) when the file is there. On a miss it
+//! fetches `<KOBAYASHI_ASSET_CDN_URL>/<path>`, stores it and serves it. Without a CDN URL only
+//! cached files are served. Paths are limited to plain segments and image extensions, so the
+//! route can neither escape the cache directory nor proxy arbitrary content.
+
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+pub const ASSET_CDN_URL_ENV: &str = "KOBAYASHI_ASSET_CDN_URL";
+pub const ASSET_CACHE_DIR_ENV: &str = "KOBAYASHI_ASSET_CACHE_DIR";
+pub const DEFAULT_ASSET_CACHE_DIR: &str = "cache/assets";
+/// Largest asset fetched from the CDN, in bytes.
+pub const MAX_ASSET_BYTES: u64 = 8 * 1024 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_PATH_LEN: usize = 256;
+
+/// Temp file suffixes, so concurrent misses for one asset don't write the same file.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+pub struct AssetProxy {
+    /// CDN base URL without a trailing slash; `None` serves the cache only.
+    cdn_base: Option<String>,
+    cache_dir: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct Asset {
+    pub bytes: Vec<u8>,
+    pub content_type: &'static str,
+    /// True when served from the disk cache rather than fetched.
+    pub cached: bool,
+}
+
+#[derive(Debug)]
+pub enum AssetError {
+    /// Not a plain relative path with an image extension.
+    InvalidPath,
+    /// Not cached, and the CDN is unset or does not have it.
+    NotFound,
+    Upstream(String),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPath => write!(f, "invalid asset path"),
+            Self::NotFound => write!(f, "asset not found"),
+            Self::Upstream(e) => write!(f, "asset CDN request failed: {e}"),
+        }
+    }
+}
+
+/// Content type for an allowed image extension.
+fn content_type_for(path: &str) -> Option<&'static str> {
+    let (_, ext) = path.rsplit_once('.')?;
+    match ext.to_ascii_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        "avif" => Some("image/avif"),
+        _ => None,
+    }
+}
+
+/// Segments of ASCII letters, digits, `-`, `_` and `.`; no empty, `.` or `..` segments.
+fn is_safe_path(path: &str) -> bool {
+    path.len() <= MAX_PATH_LEN
+        && path.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+        })
+}
+
+impl AssetProxy {
+    pub fn new(cdn_base: Option<String>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            cdn_base: cdn_base
+                .map(|url| url.trim().trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty()),
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let cache_dir = std::env::var(ASSET_CACHE_DIR_ENV)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_ASSET_CACHE_DIR.to_string());
+        Self::new(std::env::var(ASSET_CDN_URL_ENV).ok(), cache_dir)
+    }
+
+    pub fn cdn_base(&self) -> Option<&str> {
+        self.cdn_base.as_deref()
+    }
+
+    /// The asset at `path` (relative, e.g. `officers/kirk.png`): from the cache, else fetched
+    /// from the CDN and cached. Blocking; call from `spawn_blocking`.
+    pub fn get(&self, path: &str) -> Result<Asset, AssetError> {
+        let content_type = content_type_for(path).ok_or(AssetError::InvalidPath)?;
+        if !is_safe_path(path) {
+            return Err(AssetError::InvalidPath);
+        }
+        let cached_path = self.cache_dir.join(path);
+        if let Ok(bytes) = fs::read(&cached_path) {
+            return Ok(Asset { bytes, content_type, cached: true });
+        }
+        let base = self.cdn_base.as_deref().ok_or(AssetError::NotFound)?;
+        let bytes = fetch(&format!("{base}/{path}"))?;
+        store(&cached_path, &bytes);
+        Ok(Asset { bytes, content_type, cached: false })
+    }
+}
+
+fn fetch(url: &str) -> Result<Vec<u8>, AssetError> {
+    let response = ureq::get(url).timeout(FETCH_TIMEOUT).call().map_err(|e| match e {
+        ureq::Error::Status(404, _) | ureq::Error::Status(410, _) => AssetError::NotFound,
+        e => AssetError::Upstream(e.to_string()),
+    })?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_ASSET_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| AssetError::Upstream(e.to_string()))?;
+    if bytes.len() as u64 > MAX_ASSET_BYTES {
+        return Err(AssetError::Upstream(format!("{url} is larger than {MAX_ASSET_BYTES} bytes")));
+    }
+    Ok(bytes)
+}
+
+/// Atomic write (temp file + rename). Failures are ignored: the asset is still served.
+fn store(path: &std::path::Path, bytes: &[u8]) {
+    let Some(dir) = path.parent() else {
+        return;
+    };
+    if fs::create_dir_all(dir).is_err() {
+        return;
+    }
+    let n = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp = path.with_extension(format!("{}.{n}.tmp", std::process::id()));
+    if fs::write(&tmp, bytes).is_err() || fs::rename(&tmp, path).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_plain_image_paths_are_accepted() {
+        assert!(is_safe_path("officers/kirk.png"));
+        assert!(is_safe_path("ships/uss_enterprise-ncc1701.webp"));
+        assert!(!is_safe_path("../secrets.png"));
+        assert!(!is_safe_path("officers//kirk.png"));
+        assert!(!is_safe_path("/etc/kirk.png"));
+        assert!(!is_safe_path("officers/kirk%2e.png"));
+        assert_eq!(content_type_for("a/B.JPG"), Some("image/jpeg"));
+        assert_eq!(content_type_for("index.html"), None);
+        assert_eq!(content_type_for("logo.svg"), None);
+
+        let proxy = AssetProxy::new(None, std::env::temp_dir().join("kobayashi-assets-none"));
+        assert!(matches!(proxy.get("officers/../../x.png"), Err(AssetError::InvalidPath)));
+        assert!(matches!(proxy.get("officers/absent.png"), Err(AssetError::NotFound)));
+    }
+}
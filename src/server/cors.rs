@@ -0,0 +1,94 @@
+//! Cross-origin access, so a browser UI hosted elsewhere (another port, a static site) can call
+//! the server without a proxy. Off unless `KOBAYASHI_CORS_ORIGINS` is set: `*` allows any
+//! origin, otherwise it is a comma-separated list of exact origins, e.g.
+//! `https://ui.example.com,http://localhost:5173`.
+//!
+//! The layer answers preflight `OPTIONS` requests itself and adds `Access-Control-Allow-Origin`
+//! to every response for an allowed origin, errors included. Other origins get no CORS headers,
+//! so the browser blocks them as before.
+
+use std::time::Duration;
+
+use axum::http::{header, HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+pub const CORS_ORIGINS_ENV: &str = "KOBAYASHI_CORS_ORIGINS";
+/// How long browsers may cache a preflight answer.
+pub const CORS_MAX_AGE: Duration = Duration::from_secs(600);
+
+/// Request headers the API reads.
+const ALLOWED_HEADERS: [HeaderName; 5] = [
+    header::CONTENT_TYPE,
+    header::ACCEPT,
+    header::AUTHORIZATION,
+    HeaderName::from_static("x-profile-id"),
+    HeaderName::from_static("stfc-sync-token"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CorsOrigins {
+    Any,
+    /// Exact `scheme://host[:port]` origins, without a trailing slash.
+    List(Vec<String>),
+}
+
+/// `None` for an empty value or a list with no usable origin.
+pub fn parse_origins(value: &str) -> Option<CorsOrigins> {
+    let value = value.trim();
+    if value == "*" {
+        return Some(CorsOrigins::Any);
+    }
+    let origins: Vec<String> = value
+        .split(',')
+        .map(|origin| origin.trim().trim_end_matches('/'))
+        .filter(|origin| !origin.is_empty())
+        .filter(|origin| {
+            let valid = (origin.starts_with("http://") || origin.starts_with("https://"))
+                && HeaderValue::from_str(origin).is_ok();
+            if !valid {
+                tracing::warn!(origin, "ignoring invalid {CORS_ORIGINS_ENV} entry");
+            }
+            valid
+        })
+        .map(str::to_string)
+        .collect();
+    (!origins.is_empty()).then_some(CorsOrigins::List(origins))
+}
+
+pub fn origins_from_env() -> Option<CorsOrigins> {
+    parse_origins(&std::env::var(CORS_ORIGINS_ENV).ok()?)
+}
+
+pub fn cors_layer(origins: &CorsOrigins) -> CorsLayer {
+    let allow_origin = match origins {
+        CorsOrigins::Any => AllowOrigin::any(),
+        CorsOrigins::List(list) => AllowOrigin::list(
+            list.iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        ),
+    };
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+        .allow_headers(ALLOWED_HEADERS)
+        .max_age(CORS_MAX_AGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_origins, CorsOrigins};
+
+    #[test]
+    fn parses_wildcard_and_origin_lists() {
+        assert_eq!(parse_origins(" * "), Some(CorsOrigins::Any));
+        assert_eq!(
+            parse_origins("https://ui.example.com/, http://localhost:5173,ftp://x,,"),
+            Some(CorsOrigins::List(vec![
+                "https://ui.example.com".to_string(),
+                "http://localhost:5173".to_string(),
+            ]))
+        );
+        assert_eq!(parse_origins(""), None);
+        assert_eq!(parse_origins("localhost"), None);
+    }
+}
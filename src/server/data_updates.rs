@@ -0,0 +1,180 @@
+//! Remote data updates for the running server: `GET /api/data/updates` compares installed and
+//! mirrored hostile/officer versions, `POST /api/data/updates/apply` (admin token only) downloads,
+//! validates and swaps them in (see [crate::data::remote]) and then reloads the shared registry so
+//! new requests use the new data. In-flight requests keep the registry they started with.
+//!
+//! When `KOBAYASHI_DATA_REMOTE_REFRESH_MINUTES` is set (and the mirror is configured), `serve`
+//! checks for and applies updates on that interval.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::data::data_registry::SharedRegistry;
+use crate::data::remote::{self, DatasetUpdate, RemoteError};
+
+pub const REFRESH_MINUTES_ENV: &str = "KOBAYASHI_DATA_REMOTE_REFRESH_MINUTES";
+/// Shortest auto-refresh interval accepted, so a typo can't hammer the mirror.
+pub const MIN_REFRESH_MINUTES: u64 = 15;
+
+/// Serializes applies (manual and auto-refresh) so two swaps never interleave.
+static APPLY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Dataset directories are relative to the working directory, like [crate::data::data_registry].
+fn data_root() -> &'static Path {
+    Path::new(".")
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DataUpdatesResponse {
+    /// False when `KOBAYASHI_DATA_REMOTE_URL` is unset; `datasets` then lists installed
+    /// versions only.
+    pub enabled: bool,
+    /// Mirror base URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub datasets: Vec<DatasetUpdate>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+pub struct ApplyUpdatesRequest {
+    /// Datasets to install ("hostiles", "officers"), even if the version matches. Default: every
+    /// dataset with a newer mirrored version.
+    #[serde(default)]
+    pub datasets: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct AppliedDataset {
+    pub dataset: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ApplyUpdatesResponse {
+    /// Datasets swapped in and loaded; empty when everything was current.
+    pub applied: Vec<AppliedDataset>,
+}
+
+#[derive(Debug)]
+pub enum DataUpdateError {
+    Parse(String),
+    Remote(RemoteError),
+    /// The swapped data did not load; the previous data was restored.
+    Reload(String),
+}
+
+impl std::fmt::Display for DataUpdateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "Invalid request body: {e}"),
+            Self::Remote(e) => write!(f, "{e}"),
+            Self::Reload(e) => write!(f, "updated data failed to load (rolled back): {e}"),
+        }
+    }
+}
+
+/// GET /api/data/updates.
+pub fn data_updates_payload() -> Result<String, DataUpdateError> {
+    let root = data_root();
+    let response = match remote::remote_base_url() {
+        Some(base) => {
+            let manifest = remote::fetch_manifest(&base).map_err(DataUpdateError::Remote)?;
+            DataUpdatesResponse {
+                enabled: true,
+                datasets: remote::compare_versions(root, &manifest),
+                source: Some(base),
+            }
+        }
+        None => DataUpdatesResponse {
+            enabled: false,
+            source: None,
+            datasets: remote::REMOTE_DATASETS
+                .iter()
+                .map(|dataset| DatasetUpdate {
+                    dataset: dataset.name,
+                    current_version: remote::current_version(root, dataset),
+                    available_version: None,
+                    update_available: false,
+                })
+                .collect(),
+        },
+    };
+    serde_json::to_string(&response).map_err(|e| DataUpdateError::Parse(e.to_string()))
+}
+
+/// Apply updates and reload `data`. If the reload fails every swapped dataset is rolled back
+/// and reloaded again.
+pub fn apply_updates(
+    data: &SharedRegistry,
+    names: Option<&[String]>,
+) -> Result<Vec<AppliedDataset>, DataUpdateError> {
+    let _guard = APPLY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(unknown) = names
+        .unwrap_or_default()
+        .iter()
+        .find(|name| remote::remote_dataset(name).is_none())
+    {
+        return Err(DataUpdateError::Parse(format!("unknown dataset '{unknown}'")));
+    }
+    let root = data_root();
+    let applied = remote::apply_updates(root, names).map_err(DataUpdateError::Remote)?;
+    if applied.is_empty() {
+        return Ok(Vec::new());
+    }
+    if let Err(e) = data.reload() {
+        for (name, _) in &applied {
+            let dataset = remote::remote_dataset(name).expect("applied datasets are known");
+            if let Err(rollback) = remote::roll_back(root, dataset) {
+                tracing::error!(dataset = name, %rollback, "could not restore previous data");
+            }
+        }
+        if let Err(again) = data.reload() {
+            tracing::error!(%again, "reload after rollback failed; keeping the loaded registry");
+        }
+        return Err(DataUpdateError::Reload(e.to_string()));
+    }
+    Ok(applied
+        .into_iter()
+        .map(|(dataset, version)| AppliedDataset { dataset: dataset.to_string(), version })
+        .collect())
+}
+
+/// POST /api/data/updates/apply.
+pub fn apply_updates_payload(data: &SharedRegistry, body: &str) -> Result<String, DataUpdateError> {
+    let request: ApplyUpdatesRequest = if body.trim().is_empty() {
+        ApplyUpdatesRequest::default()
+    } else {
+        serde_json::from_str(body).map_err(|e| DataUpdateError::Parse(e.to_string()))?
+    };
+    let applied = apply_updates(data, request.datasets.as_deref())?;
+    serde_json::to_string(&ApplyUpdatesResponse { applied })
+        .map_err(|e| DataUpdateError::Parse(e.to_string()))
+}
+
+/// Auto-refresh interval from `KOBAYASHI_DATA_REMOTE_REFRESH_MINUTES`, when the mirror is set.
+pub fn refresh_interval() -> Option<Duration> {
+    remote::remote_base_url()?;
+    let minutes: u64 = std::env::var(REFRESH_MINUTES_ENV).ok()?.trim().parse().ok()?;
+    (minutes > 0).then(|| Duration::from_secs(minutes.max(MIN_REFRESH_MINUTES) * 60))
+}
+
+/// Background loop: apply mirrored updates every `interval`.
+pub async fn run_auto_refresh(data: Arc<SharedRegistry>, interval: Duration) {
+    let mut tick = tokio::time::interval(interval);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        let data = Arc::clone(&data);
+        match tokio::task::spawn_blocking(move || apply_updates(&data, None)).await {
+            Ok(Ok(applied)) if !applied.is_empty() => {
+                tracing::info!(count = applied.len(), "remote data updates loaded");
+            }
+            Ok(Err(error)) => tracing::warn!(%error, "remote data refresh failed"),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! Optimize result history: every finished optimize (sync, background job or schedule) appends
+//! its top crews to `cache/history/<scenario hash>.json` together with the data version it ran
+//! against, and `GET /api/optimize/history?ship=…&hostile=…` turns those runs into win rate over
+//! time per crew ("did the latest officer data update change my best crew?").
+//!
+//! The scenario hash covers every request field that changes the ranking plus the profile, so
+//! runs with different sims or seeds never share a series. One run is kept per data version: a
+//! re-run against unchanged data replaces the previous point instead of adding a flat one.
+//!
+//! Like the result cache, recording is off by default for library and CLI use and `serve` turns
+//! it on. `KOBAYASHI_RESULT_HISTORY=0|1` overrides either default and
+//! `KOBAYASHI_RESULT_HISTORY_DIR` relocates the files.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::api::{CrewRecommendation, OptimizeRequest, OptimizeResponse};
+use crate::data::data_registry::DataRegistry;
+use crate::data::profile_index::resolve_profile_id_for_api;
+use crate::optimizer::result_cache::{data_fingerprint, scenario_hash};
+
+pub const DEFAULT_RESULT_HISTORY_DIR: &str = "cache/history";
+/// Namespace of the history scenario hash (distinct from the result cache's).
+pub const HISTORY_NAMESPACE: &str = "optimize-history";
+/// Crews stored per run, so a crew that drops out of the top 3 still has a point.
+pub const HISTORY_STORED_CREWS: usize = 10;
+/// Runs kept per scenario; the oldest are dropped first.
+pub const MAX_HISTORY_RUNS: usize = 100;
+/// Crews given a series when `top` is unset.
+pub const DEFAULT_HISTORY_TOP: usize = 3;
+/// Upper bound for `top`.
+pub const MAX_HISTORY_TOP: usize = HISTORY_STORED_CREWS;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Serializes read-modify-write of history files between concurrent optimize runs.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Turn recording on or off (ignored when `KOBAYASHI_RESULT_HISTORY` is set).
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    match std::env::var("KOBAYASHI_RESULT_HISTORY") {
+        Ok(v) => matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+        Err(_) => ENABLED.load(Ordering::Relaxed),
+    }
+}
+
+fn history_dir() -> PathBuf {
+    std::env::var("KOBAYASHI_RESULT_HISTORY_DIR")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_RESULT_HISTORY_DIR))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CrewWinRate {
+    pub crew_id: String,
+    pub captain: String,
+    pub bridge: Vec<String>,
+    pub below_decks: Vec<String>,
+    pub win_rate: f64,
+}
+
+impl From<&CrewRecommendation> for CrewWinRate {
+    fn from(rec: &CrewRecommendation) -> Self {
+        Self {
+            crew_id: rec.crew_id.clone(),
+            captain: rec.captain.clone(),
+            bridge: rec.bridge.clone(),
+            below_decks: rec.below_decks.clone(),
+            win_rate: rec.win_rate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRun {
+    /// Unix seconds when the run finished.
+    pub at: u64,
+    /// [data_fingerprint] of the static data and the profile.
+    pub data_version: String,
+    pub engine: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Best first, at most [HISTORY_STORED_CREWS].
+    pub crews: Vec<CrewWinRate>,
+}
+
+/// One scenario's history file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioHistory {
+    pub scenario_hash: String,
+    pub ship: String,
+    pub hostile: String,
+    pub profile_id: String,
+    /// The request that produced the runs (without per-call fields such as webhooks).
+    pub request: OptimizeRequest,
+    /// Oldest first, at most [MAX_HISTORY_RUNS].
+    pub runs: Vec<HistoryRun>,
+}
+
+impl ScenarioHistory {
+    /// Append `run`, replacing the last run when it used the same data.
+    fn push_run(&mut self, run: HistoryRun) {
+        if self
+            .runs
+            .last()
+            .is_some_and(|last| last.data_version == run.data_version)
+        {
+            self.runs.pop();
+        }
+        self.runs.push(run);
+        let excess = self.runs.len().saturating_sub(MAX_HISTORY_RUNS);
+        self.runs.drain(..excess);
+    }
+}
+
+/// The request as it affects results: fields that only change delivery are cleared.
+fn history_request(request: &OptimizeRequest) -> OptimizeRequest {
+    OptimizeRequest {
+        explain_top: None,
+        notify_webhook: None,
+        ..request.clone()
+    }
+}
+
+/// Scenario hash under which `request` run for `profile_id` is recorded.
+pub fn history_key(request: &OptimizeRequest, profile_id: Option<&str>) -> String {
+    scenario_hash(
+        HISTORY_NAMESPACE,
+        &(history_request(request), resolve_profile_id_for_api(profile_id)),
+    )
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Record a finished optimize (no-op when disabled). Failures are logged and ignored: history
+/// is advisory and must never fail the optimize itself.
+pub fn record_optimize(
+    request: &OptimizeRequest,
+    profile_id: Option<&str>,
+    response: &OptimizeResponse,
+) {
+    if !is_enabled() {
+        return;
+    }
+    let run = HistoryRun {
+        at: unix_now(),
+        data_version: data_fingerprint(profile_id),
+        engine: response.engine.to_string(),
+        duration_ms: response.duration_ms,
+        crews: response
+            .recommendations
+            .iter()
+            .take(HISTORY_STORED_CREWS)
+            .map(CrewWinRate::from)
+            .collect(),
+    };
+    if let Err(error) = append_run(request, profile_id, run) {
+        tracing::warn!(%error, "could not record optimize history");
+    }
+}
+
+fn append_run(
+    request: &OptimizeRequest,
+    profile_id: Option<&str>,
+    run: HistoryRun,
+) -> std::io::Result<()> {
+    let key = history_key(request, profile_id);
+    let dir = history_dir();
+    let path = dir.join(format!("{key}.json"));
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut history = fs::read_to_string(&path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<ScenarioHistory>(&raw).ok())
+        .filter(|h| h.scenario_hash == key)
+        .unwrap_or_else(|| ScenarioHistory {
+            scenario_hash: key.clone(),
+            ship: request.ship.clone(),
+            hostile: request.hostile.clone(),
+            profile_id: resolve_profile_id_for_api(profile_id),
+            request: history_request(request),
+            runs: Vec::new(),
+        });
+    history.push_run(run);
+    fs::create_dir_all(&dir)?;
+    let body = serde_json::to_string(&history).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&tmp, body)?;
+    fs::rename(&tmp, &path)
+}
+
+/// Every stored scenario for `ship` vs `hostile` under `profile_id`, most recently run first.
+pub fn load_histories(ship: &str, hostile: &str, profile_id: Option<&str>) -> Vec<ScenarioHistory> {
+    let profile = resolve_profile_id_for_api(profile_id);
+    let Ok(entries) = fs::read_dir(history_dir()) else {
+        return Vec::new();
+    };
+    let mut histories: Vec<ScenarioHistory> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|raw| serde_json::from_str::<ScenarioHistory>(&raw).ok())
+        .filter(|h| h.ship == ship && h.hostile == hostile && h.profile_id == profile)
+        .collect();
+    let last_at = |h: &ScenarioHistory| h.runs.last().map_or(0, |r| r.at);
+    histories.sort_by(|a, b| {
+        last_at(b)
+            .cmp(&last_at(a))
+            .then_with(|| a.scenario_hash.cmp(&b.scenario_hash))
+    });
+    histories
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TrendPoint {
+    pub at: u64,
+    pub data_version: String,
+    /// None when the crew was not among the run's stored crews.
+    pub win_rate: Option<f64>,
+    /// 1-based rank in that run.
+    pub rank: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CrewTrend {
+    pub crew_id: String,
+    pub captain: String,
+    pub bridge: Vec<String>,
+    pub below_decks: Vec<String>,
+    /// One point per run, oldest first.
+    pub points: Vec<TrendPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ScenarioTrend {
+    pub scenario_hash: String,
+    pub sims: Option<u32>,
+    pub seed: Option<u64>,
+    pub strategy: Option<String>,
+    pub scenario_type: Option<String>,
+    pub runs: usize,
+    /// Top crews of the latest run, best first.
+    pub crews: Vec<CrewTrend>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OptimizeHistoryResponse {
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    /// Most recently run first.
+    pub scenarios: Vec<ScenarioTrend>,
+}
+
+/// Series for the `top` best crews of the latest run, over every run of `history`.
+pub fn scenario_trend(history: &ScenarioHistory, top: usize) -> ScenarioTrend {
+    let ranks: Vec<HashMap<&str, (u32, f64)>> = history
+        .runs
+        .iter()
+        .map(|run| {
+            run.crews
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (c.crew_id.as_str(), (i as u32 + 1, c.win_rate)))
+                .collect()
+        })
+        .collect();
+    let latest = history.runs.last().map(|r| r.crews.as_slice()).unwrap_or_default();
+    let crews = latest
+        .iter()
+        .take(top)
+        .map(|crew| CrewTrend {
+            crew_id: crew.crew_id.clone(),
+            captain: crew.captain.clone(),
+            bridge: crew.bridge.clone(),
+            below_decks: crew.below_decks.clone(),
+            points: history
+                .runs
+                .iter()
+                .zip(&ranks)
+                .map(|(run, ranks)| {
+                    let hit = ranks.get(crew.crew_id.as_str());
+                    TrendPoint {
+                        at: run.at,
+                        data_version: run.data_version.clone(),
+                        win_rate: hit.map(|&(_, w)| w),
+                        rank: hit.map(|&(r, _)| r),
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+    ScenarioTrend {
+        scenario_hash: history.scenario_hash.clone(),
+        sims: history.request.sims,
+        seed: history.request.seed,
+        strategy: history.request.strategy.clone(),
+        scenario_type: history.request.scenario_type.clone(),
+        runs: history.runs.len(),
+        crews,
+    }
+}
+
+/// GET /api/optimize/history?ship=…&hostile=…[&top=n]. Ship and hostile resolve like optimize
+/// requests (names and near-miss ids map to the stored canonical ids).
+pub fn optimize_history_payload(
+    registry: &DataRegistry,
+    params: &HashMap<String, String>,
+    profile_id: Option<&str>,
+) -> Result<String, String> {
+    let param = |name: &str| params.get(name).map(|v| v.trim()).filter(|v| !v.is_empty());
+    let (Some(ship), Some(hostile)) = (param("ship"), param("hostile")) else {
+        return Err("ship and hostile are required".to_string());
+    };
+    let top = match param("top") {
+        Some(raw) => match raw.parse::<usize>() {
+            Ok(n) if (1..=MAX_HISTORY_TOP).contains(&n) => n,
+            _ => return Err(format!("top: must be between 1 and {MAX_HISTORY_TOP}")),
+        },
+        None => DEFAULT_HISTORY_TOP,
+    };
+    let ship = registry.ship_id_for(ship).unwrap_or_else(|_| ship.to_string());
+    let hostile = registry
+        .hostile_id_for(hostile)
+        .unwrap_or_else(|_| hostile.to_string());
+    let scenarios = load_histories(&ship, &hostile, profile_id)
+        .iter()
+        .map(|h| scenario_trend(h, top))
+        .collect();
+    let response = OptimizeHistoryResponse {
+        status: "ok",
+        ship,
+        hostile,
+        scenarios,
+    };
+    serde_json::to_string_pretty(&response).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crew(id: &str, win_rate: f64) -> CrewWinRate {
+        CrewWinRate {
+            crew_id: id.to_string(),
+            captain: id.to_string(),
+            bridge: Vec::new(),
+            below_decks: Vec::new(),
+            win_rate,
+        }
+    }
+
+    fn run(at: u64, data_version: &str, crews: Vec<CrewWinRate>) -> HistoryRun {
+        HistoryRun {
+            at,
+            data_version: data_version.to_string(),
+            engine: "optimizer_v1".to_string(),
+            duration_ms: None,
+            crews,
+        }
+    }
+
+    fn history() -> ScenarioHistory {
+        ScenarioHistory {
+            scenario_hash: "h".to_string(),
+            ship: "saladin".to_string(),
+            hostile: "swarm".to_string(),
+            profile_id: "default".to_string(),
+            request: serde_json::from_str(r#"{"ship":"saladin","hostile":"swarm","sims":500}"#)
+                .unwrap(),
+            runs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn same_data_version_replaces_the_last_run() {
+        let mut h = history();
+        h.push_run(run(1, "v1", vec![crew("a", 0.5)]));
+        h.push_run(run(2, "v1", vec![crew("a", 0.6)]));
+        h.push_run(run(3, "v2", vec![crew("a", 0.7)]));
+        let ats: Vec<u64> = h.runs.iter().map(|r| r.at).collect();
+        assert_eq!(ats, vec![2, 3]);
+    }
+
+    #[test]
+    fn trend_follows_latest_top_crews_across_runs() {
+        let mut h = history();
+        h.push_run(run(1, "v1", vec![crew("a", 0.9), crew("b", 0.8)]));
+        h.push_run(run(2, "v2", vec![crew("b", 0.85), crew("c", 0.8), crew("a", 0.7)]));
+        let trend = scenario_trend(&h, 2);
+        assert_eq!(trend.runs, 2);
+        assert_eq!(trend.sims, Some(500));
+        let ids: Vec<&str> = trend.crews.iter().map(|c| c.crew_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c"]);
+        let b: Vec<Option<f64>> = trend.crews[0].points.iter().map(|p| p.win_rate).collect();
+        assert_eq!(b, vec![Some(0.8), Some(0.85)]);
+        assert_eq!(trend.crews[0].points[0].rank, Some(2));
+        assert_eq!(trend.crews[1].points[0].win_rate, None);
+    }
+
+    #[test]
+    fn history_key_ignores_delivery_fields() {
+        let base: OptimizeRequest =
+            serde_json::from_str(r#"{"ship":"saladin","hostile":"swarm"}"#).unwrap();
+        let with_webhook = OptimizeRequest {
+            notify_webhook: Some("https://example.com/hook".to_string()),
+            explain_top: Some(0),
+            ..base.clone()
+        };
+        let other_seed = OptimizeRequest {
+            seed: Some(9),
+            ..base.clone()
+        };
+        assert_eq!(history_key(&base, None), history_key(&with_webhook, None));
+        assert_ne!(history_key(&base, None), history_key(&other_seed, None));
+    }
+}
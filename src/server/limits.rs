@@ -0,0 +1,183 @@
+//! Request deadlines and body size for `serve`. Each is read from the environment at startup
+//! (durations in seconds; `0` turns that deadline off):
+//!
+//! - `KOBAYASHI_HEADER_READ_TIMEOUT_SECS` (default 30): a connection must send its request line
+//!   and headers within this long, or it is closed.
+//! - `KOBAYASHI_BODY_READ_TIMEOUT_SECS` (default 30): the whole request body must arrive within
+//!   this long, or the request gets 408.
+//! - `KOBAYASHI_REQUEST_TIMEOUT_SECS` (default 60) and `KOBAYASHI_CPU_REQUEST_TIMEOUT_SECS`
+//!   (default 1800): handler deadline for ordinary and CPU-heavy routes (simulate, analyze,
+//!   synchronous optimize); 408 on expiry.
+//! - `KOBAYASHI_ROUTE_TIMEOUTS`: per-route overrides as comma-separated `path=secs` pairs, e.g.
+//!   `/api/optimize=3600,/api/sync/ingress=10`. The longest matching path prefix wins.
+//! - `KOBAYASHI_MAX_BODY_BYTES` (default 2 MiB): larger request bodies get 413.
+//!
+//! Streaming routes (`/ws/simulate` and the optimize job SSE stream) never get a handler
+//! deadline. A timed-out CPU request stops waiting for its blocking task; the task itself runs
+//! to completion.
+
+use std::time::Duration;
+
+pub const HEADER_READ_TIMEOUT_ENV: &str = "KOBAYASHI_HEADER_READ_TIMEOUT_SECS";
+pub const BODY_READ_TIMEOUT_ENV: &str = "KOBAYASHI_BODY_READ_TIMEOUT_SECS";
+pub const REQUEST_TIMEOUT_ENV: &str = "KOBAYASHI_REQUEST_TIMEOUT_SECS";
+pub const CPU_REQUEST_TIMEOUT_ENV: &str = "KOBAYASHI_CPU_REQUEST_TIMEOUT_SECS";
+pub const ROUTE_TIMEOUTS_ENV: &str = "KOBAYASHI_ROUTE_TIMEOUTS";
+pub const MAX_BODY_BYTES_ENV: &str = "KOBAYASHI_MAX_BODY_BYTES";
+
+pub const DEFAULT_HEADER_READ_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_BODY_READ_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+pub const DEFAULT_CPU_REQUEST_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Same as axum's default body limit, which this replaces.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Routes that run on the CPU job pool; matched by prefix except `/api/optimize` (exact).
+const CPU_ROUTE_PREFIXES: &[&str] = &["/api/simulate", "/api/analyze/"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLimits {
+    pub header_read: Option<Duration>,
+    pub body_read: Option<Duration>,
+    pub request: Option<Duration>,
+    pub cpu_request: Option<Duration>,
+    /// `(path prefix, timeout)` overrides, longest prefix first.
+    pub routes: Vec<(String, Option<Duration>)>,
+    pub max_body_bytes: usize,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            header_read: Some(DEFAULT_HEADER_READ_TIMEOUT),
+            body_read: Some(DEFAULT_BODY_READ_TIMEOUT),
+            request: Some(DEFAULT_REQUEST_TIMEOUT),
+            cpu_request: Some(DEFAULT_CPU_REQUEST_TIMEOUT),
+            routes: Vec::new(),
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+        }
+    }
+}
+
+/// `0` means no deadline; unparsable values keep the default.
+fn parse_secs(value: Option<String>, default: Option<Duration>) -> Option<Duration> {
+    match value.and_then(|v| v.trim().parse::<u64>().ok()) {
+        Some(0) => None,
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None => default,
+    }
+}
+
+/// `path=secs` pairs; malformed entries are skipped with a warning.
+fn parse_route_timeouts(value: &str) -> Vec<(String, Option<Duration>)> {
+    let mut routes: Vec<(String, Option<Duration>)> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let parsed = entry.split_once('=').and_then(|(path, secs)| {
+                let secs = secs.trim().parse::<u64>().ok()?;
+                let path = path.trim();
+                path.starts_with('/')
+                    .then(|| (path.to_string(), (secs > 0).then(|| Duration::from_secs(secs))))
+            });
+            if parsed.is_none() {
+                tracing::warn!(entry, "ignoring malformed {ROUTE_TIMEOUTS_ENV} entry");
+            }
+            parsed
+        })
+        .collect();
+    routes.sort_by_key(|(path, _)| std::cmp::Reverse(path.len()));
+    routes
+}
+
+fn is_streaming_route(path: &str) -> bool {
+    path.starts_with("/ws/")
+        || (path.starts_with("/api/optimize/jobs/") && path.ends_with("/stream"))
+}
+
+fn is_cpu_route(path: &str) -> bool {
+    path == "/api/optimize" || CPU_ROUTE_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+impl RequestLimits {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// [RequestLimits::from_env] over any key lookup.
+    pub fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        Self {
+            header_read: parse_secs(get(HEADER_READ_TIMEOUT_ENV), defaults.header_read),
+            body_read: parse_secs(get(BODY_READ_TIMEOUT_ENV), defaults.body_read),
+            request: parse_secs(get(REQUEST_TIMEOUT_ENV), defaults.request),
+            cpu_request: parse_secs(get(CPU_REQUEST_TIMEOUT_ENV), defaults.cpu_request),
+            routes: get(ROUTE_TIMEOUTS_ENV)
+                .map(|value| parse_route_timeouts(&value))
+                .unwrap_or_default(),
+            max_body_bytes: get(MAX_BODY_BYTES_ENV)
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_body_bytes),
+        }
+    }
+
+    /// Handler deadline for a request to `path`; `None` means it may run indefinitely.
+    pub fn timeout_for(&self, path: &str) -> Option<Duration> {
+        if is_streaming_route(path) {
+            return None;
+        }
+        if let Some((_, timeout)) = self.routes.iter().find(|(prefix, _)| path.starts_with(prefix))
+        {
+            return *timeout;
+        }
+        if is_cpu_route(path) {
+            self.cpu_request
+        } else {
+            self.request
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_pick_cpu_default_and_streaming_deadlines() {
+        let limits = RequestLimits::default();
+        assert_eq!(limits.timeout_for("/api/officers"), Some(DEFAULT_REQUEST_TIMEOUT));
+        assert_eq!(limits.timeout_for("/api/optimize/estimate"), Some(DEFAULT_REQUEST_TIMEOUT));
+        assert_eq!(limits.timeout_for("/api/optimize"), Some(DEFAULT_CPU_REQUEST_TIMEOUT));
+        assert_eq!(limits.timeout_for("/api/simulate/trace"), Some(DEFAULT_CPU_REQUEST_TIMEOUT));
+        assert_eq!(limits.timeout_for("/ws/simulate"), None);
+        assert_eq!(limits.timeout_for("/api/optimize/jobs/opt_1_0/stream"), None);
+    }
+
+    #[test]
+    fn env_overrides_parse_and_longest_route_prefix_wins() {
+        let limits = RequestLimits::from_lookup(|key| match key {
+            REQUEST_TIMEOUT_ENV => Some("5".to_string()),
+            CPU_REQUEST_TIMEOUT_ENV => Some("0".to_string()),
+            BODY_READ_TIMEOUT_ENV => Some("soon".to_string()),
+            ROUTE_TIMEOUTS_ENV => {
+                Some("/api=7, /api/sync/ingress=2,bogus,/api/presets=0".to_string())
+            }
+            MAX_BODY_BYTES_ENV => Some("1024".to_string()),
+            _ => None,
+        });
+        assert_eq!(limits.body_read, Some(DEFAULT_BODY_READ_TIMEOUT));
+        assert_eq!(limits.max_body_bytes, 1024);
+        assert_eq!(limits.routes.len(), 3);
+        assert_eq!(limits.timeout_for("/api/sync/ingress"), Some(Duration::from_secs(2)));
+        assert_eq!(limits.timeout_for("/api/presets/abc"), None);
+        assert_eq!(limits.timeout_for("/api/optimize"), Some(Duration::from_secs(7)));
+        assert_eq!(limits.timeout_for("/"), Some(Duration::from_secs(5)));
+
+        let cpu_off = RequestLimits::from_lookup(|key| {
+            (key == CPU_REQUEST_TIMEOUT_ENV).then(|| "0".to_string())
+        });
+        assert_eq!(cpu_off.timeout_for("/api/analyze/stats"), None);
+    }
+}
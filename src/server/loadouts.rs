@@ -0,0 +1,322 @@
+//! Saved loadouts: named groups of officers committed to a ship ("crew A is on my miner"),
+//! stored in `data/loadouts.json` and managed through `/api/loadouts`. An optimize request lists
+//! loadout ids in `exclude_loadouts` to keep those officers out of every recommended crew.
+//!
+//! Officers are stored by canonical name (requests may use ids or names), which is how the
+//! optimizer's seat pools identify them. `KOBAYASHI_LOADOUTS` relocates the file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::api::{ValidationErrorResponse, ValidationIssue};
+use crate::data::data_registry::DataRegistry;
+
+pub const DEFAULT_LOADOUTS_PATH: &str = "data/loadouts.json";
+/// Loadouts kept in the file.
+pub const MAX_LOADOUTS: usize = 64;
+/// Officers per loadout.
+pub const MAX_LOADOUT_OFFICERS: usize = 16;
+/// Longest accepted loadout name, in characters.
+pub const MAX_LOADOUT_NAME_LEN: usize = 80;
+
+/// Serializes read-modify-write of the loadouts file between handlers.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LoadoutRequest {
+    #[schemars(length(min = 1, max = "MAX_LOADOUT_NAME_LEN"))]
+    pub name: String,
+    /// Ship the officers are on (free text, for display).
+    pub ship: Option<String>,
+    /// Officer ids or names.
+    #[schemars(length(min = 1, max = "MAX_LOADOUT_OFFICERS"))]
+    pub officers: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Loadout {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ship: Option<String>,
+    /// Canonical officer names.
+    pub officers: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LoadoutFile {
+    #[serde(default)]
+    loadouts: Vec<Loadout>,
+}
+
+#[derive(Debug)]
+pub enum LoadoutError {
+    Parse(serde_json::Error),
+    Validation(ValidationErrorResponse),
+    NotFound,
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for LoadoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Validation(_) => write!(f, "invalid loadout"),
+            Self::NotFound => write!(f, "Loadout not found"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadoutError {}
+
+pub fn loadouts_path() -> PathBuf {
+    std::env::var_os("KOBAYASHI_LOADOUTS")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_LOADOUTS_PATH))
+}
+
+/// Loadouts in `path`; a missing file means none.
+pub fn load_loadouts(path: &Path) -> Result<Vec<Loadout>, LoadoutError> {
+    match fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str::<LoadoutFile>(&raw)
+            .map(|file| file.loadouts)
+            .map_err(LoadoutError::Parse),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(LoadoutError::Io(e)),
+    }
+}
+
+/// Writes through a temp file so a crash never leaves a truncated loadouts file.
+fn save_loadouts(path: &Path, loadouts: Vec<Loadout>) -> Result<(), LoadoutError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(LoadoutError::Io)?;
+    }
+    let raw = serde_json::to_string_pretty(&LoadoutFile { loadouts })
+        .map_err(LoadoutError::Parse)?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(LoadoutError::Io)?;
+    fs::rename(&tmp, path).map_err(LoadoutError::Io)
+}
+
+/// Load, change and save the loadouts file under [STORE_LOCK].
+fn update_loadouts<T>(
+    change: impl FnOnce(&mut Vec<Loadout>) -> Result<T, LoadoutError>,
+) -> Result<T, LoadoutError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = loadouts_path();
+    let mut loadouts = load_loadouts(&path)?;
+    let out = change(&mut loadouts)?;
+    save_loadouts(&path, loadouts)?;
+    Ok(out)
+}
+
+fn read_loadouts() -> Result<Vec<Loadout>, LoadoutError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    load_loadouts(&loadouts_path())
+}
+
+fn validation_error(errors: Vec<ValidationIssue>) -> LoadoutError {
+    LoadoutError::Validation(ValidationErrorResponse {
+        status: "error",
+        message: "Validation failed",
+        errors,
+    })
+}
+
+/// Id derived from the name (`"Miner A"` → `miner_a`), suffixed until it is unused.
+fn loadout_id(name: &str, taken: &[Loadout]) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    let slug = slug.trim_matches('_');
+    let base = if slug.is_empty() { "loadout" } else { slug };
+    let is_taken = |id: &str| taken.iter().any(|l| l.id == id);
+    if !is_taken(base) {
+        return base.to_string();
+    }
+    (2..)
+        .map(|n| format!("{base}_{n}"))
+        .find(|id| !is_taken(id))
+        .expect("unbounded suffixes")
+}
+
+/// Validates `body` and resolves its officers to canonical names.
+fn parse_request(registry: &DataRegistry, body: &str) -> Result<Loadout, LoadoutError> {
+    let req: LoadoutRequest = serde_json::from_str(body).map_err(LoadoutError::Parse)?;
+    let mut errors = Vec::new();
+    let name = req.name.trim().to_string();
+    if name.is_empty() || name.chars().count() > MAX_LOADOUT_NAME_LEN {
+        errors.push(ValidationIssue {
+            field: "name",
+            messages: vec![format!("must be 1 to {MAX_LOADOUT_NAME_LEN} characters")],
+        });
+    }
+    let mut officers: Vec<String> = Vec::new();
+    let mut unknown = Vec::new();
+    for query in req.officers.iter().map(|o| o.trim()) {
+        let officer = registry
+            .officers()
+            .iter()
+            .find(|o| o.id == query || o.name.eq_ignore_ascii_case(query));
+        match officer {
+            Some(officer) if !officers.contains(&officer.name) => {
+                officers.push(officer.name.clone())
+            }
+            Some(_) => {}
+            None => unknown.push(query.to_string()),
+        }
+    }
+    if !unknown.is_empty() {
+        errors.push(ValidationIssue {
+            field: "officers",
+            messages: vec![format!("unknown officers: {}", unknown.join(", "))],
+        });
+    } else if officers.is_empty() || officers.len() > MAX_LOADOUT_OFFICERS {
+        errors.push(ValidationIssue {
+            field: "officers",
+            messages: vec![format!("must list 1 to {MAX_LOADOUT_OFFICERS} officers")],
+        });
+    }
+    if !errors.is_empty() {
+        return Err(validation_error(errors));
+    }
+    Ok(Loadout {
+        id: String::new(),
+        name,
+        ship: req.ship.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+        officers,
+    })
+}
+
+/// GET /api/loadouts
+pub fn loadouts_list_payload() -> Result<String, LoadoutError> {
+    let loadouts = read_loadouts()?;
+    serde_json::to_string_pretty(&serde_json::json!({ "loadouts": loadouts }))
+        .map_err(LoadoutError::Parse)
+}
+
+/// GET /api/loadouts/{id}
+pub fn loadout_get_payload(id: &str) -> Result<String, LoadoutError> {
+    let loadout = read_loadouts()?
+        .into_iter()
+        .find(|l| l.id == id)
+        .ok_or(LoadoutError::NotFound)?;
+    serde_json::to_string_pretty(&loadout).map_err(LoadoutError::Parse)
+}
+
+/// POST /api/loadouts: store a new loadout; its id is derived from the name.
+pub fn loadout_create_payload(registry: &DataRegistry, body: &str) -> Result<String, LoadoutError> {
+    let mut loadout = parse_request(registry, body)?;
+    let created = update_loadouts(|loadouts| {
+        if loadouts.len() >= MAX_LOADOUTS {
+            return Err(validation_error(vec![ValidationIssue {
+                field: "loadouts",
+                messages: vec![format!("at most {MAX_LOADOUTS} loadouts; delete one first")],
+            }]));
+        }
+        loadout.id = loadout_id(&loadout.name, loadouts);
+        loadouts.push(loadout.clone());
+        Ok(loadout)
+    })?;
+    serde_json::to_string_pretty(&created).map_err(LoadoutError::Parse)
+}
+
+/// PUT /api/loadouts/{id}: replace a loadout's name, ship and officers (the id stays).
+pub fn loadout_update_payload(
+    registry: &DataRegistry,
+    id: &str,
+    body: &str,
+) -> Result<String, LoadoutError> {
+    let mut loadout = parse_request(registry, body)?;
+    let updated = update_loadouts(|loadouts| {
+        let slot = loadouts
+            .iter_mut()
+            .find(|l| l.id == id)
+            .ok_or(LoadoutError::NotFound)?;
+        loadout.id = slot.id.clone();
+        *slot = loadout.clone();
+        Ok(loadout)
+    })?;
+    serde_json::to_string_pretty(&updated).map_err(LoadoutError::Parse)
+}
+
+/// DELETE /api/loadouts/{id}
+pub fn loadout_delete_payload(id: &str) -> Result<String, LoadoutError> {
+    update_loadouts(|loadouts| {
+        let before = loadouts.len();
+        loadouts.retain(|l| l.id != id);
+        if loadouts.len() == before {
+            return Err(LoadoutError::NotFound);
+        }
+        Ok(())
+    })?;
+    serde_json::to_string_pretty(&serde_json::json!({ "status": "ok" }))
+        .map_err(LoadoutError::Parse)
+}
+
+/// Ids in `ids` that name no saved loadout (all of them when the file is unreadable).
+pub fn unknown_loadout_ids(ids: &[String]) -> Vec<String> {
+    if ids.is_empty() {
+        return Vec::new();
+    }
+    match read_loadouts() {
+        Ok(loadouts) => ids
+            .iter()
+            .filter(|id| !loadouts.iter().any(|l| &l.id == *id))
+            .cloned()
+            .collect(),
+        Err(_) => ids.to_vec(),
+    }
+}
+
+/// Officers committed to the loadouts `ids`, for an optimize request's `exclude_loadouts`.
+/// Ids deleted since the request was validated (e.g. a stored schedule) are skipped.
+pub fn excluded_officers(ids: &[String]) -> Vec<String> {
+    if ids.is_empty() {
+        return Vec::new();
+    }
+    let loadouts = match read_loadouts() {
+        Ok(loadouts) => loadouts,
+        Err(e) => {
+            tracing::warn!(error = %e, "loadouts unreadable; nothing excluded");
+            return Vec::new();
+        }
+    };
+    let mut officers: Vec<String> = loadouts
+        .into_iter()
+        .filter(|l| ids.contains(&l.id))
+        .flat_map(|l| l.officers)
+        .collect();
+    officers.sort();
+    officers.dedup();
+    officers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_come_from_names_and_never_collide() {
+        let taken = |ids: &[&str]| -> Vec<Loadout> {
+            ids.iter()
+                .map(|id| Loadout {
+                    id: id.to_string(),
+                    name: String::new(),
+                    ship: None,
+                    officers: Vec::new(),
+                })
+                .collect()
+        };
+        assert_eq!(loadout_id("Miner A", &[]), "miner_a");
+        assert_eq!(loadout_id("  ", &[]), "loadout");
+        assert_eq!(loadout_id("Miner A", &taken(&["miner_a", "miner_a_2"])), "miner_a_3");
+    }
+}
@@ -1,26 +1,95 @@
 pub mod api;
+pub mod assets;
+pub mod cors;
+pub mod data_updates;
+pub mod history;
+pub mod limits;
+pub mod loadouts;
+pub mod notify;
+pub mod openapi;
 pub mod routes;
+pub mod schedule;
+pub mod settings;
 pub mod static_files;
 pub mod sync;
+pub mod tls;
 
 use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::Router;
+use hyper::server::conn::http1;
+use hyper_util::rt::{TokioIo, TokioTimer};
+use hyper_util::service::TowerToHyperService;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::task::JoinSet;
+use tokio_rustls::TlsAcceptor;
+
+/// Parses a `KOBAYASHI_BIND` value: one or more comma-separated socket addresses, e.g.
+/// `127.0.0.1:3000,[::1]:3000` (IPv6 addresses in brackets). Duplicates are dropped.
+pub fn parse_bind_addrs(bind_addr: &str) -> std::io::Result<Vec<SocketAddr>> {
+    let mut addrs: Vec<SocketAddr> = Vec::new();
+    for part in bind_addr.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        let addr: SocketAddr = part.parse().map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid bind address {part:?}: {e}"),
+            )
+        })?;
+        if !addrs.contains(&addr) {
+            addrs.push(addr);
+        }
+    }
+    if addrs.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "no bind address given",
+        ));
+    }
+    Ok(addrs)
+}
+
+/// IPv6 listeners are v6-only, so `0.0.0.0:3000,[::]:3000` binds both stacks instead of the
+/// second failing with "address in use" on dual-stack hosts.
+fn bind_listener(addr: SocketAddr) -> std::io::Result<TcpListener> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))?;
+    if addr.is_ipv6() {
+        socket.set_only_v6(true)?;
+    }
+    // Same as std/tokio on Unix: allow rebinding while old connections sit in TIME_WAIT.
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    socket.set_nonblocking(true)?;
+    socket.bind(&addr.into()).map_err(|e| {
+        std::io::Error::new(e.kind(), format!("failed to bind {addr}: {e}"))
+    })?;
+    socket.listen(1024)?;
+    TcpListener::from_std(socket.into())
+}
 
 /// Start the Axum HTTP server and block until it shuts down.
 ///
+/// `bind_addr` is one or more comma-separated addresses (see [parse_bind_addrs]); every one is
+/// bound before any connection is accepted, and each gets its own accept loop on the runtime.
+///
 /// This function is `async` and must be called from a tokio runtime.
 /// `main.rs` builds the runtime explicitly for the `serve` command so that
 /// all other CLI sub-commands remain synchronous.
 pub async fn run_server_async(bind_addr: &str) -> std::io::Result<()> {
     crate::parallel::init_from_env();
 
-    let addr: SocketAddr = bind_addr
-        .parse()
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let addrs = parse_bind_addrs(bind_addr)?;
+
+    // Bad or half-configured TLS fails here, before anything slow, rather than serving HTTP.
+    let tls = tls::TlsPaths::from_env()?.map(|paths| tls::load_acceptor(&paths)).transpose()?;
 
     // Validate all data files before accepting any connections.
     // This catches corrupt or missing records immediately rather than surfacing
     // mid-simulation after the user has already waited minutes.
-    println!("kobayashi: validating data files…");
+    tracing::info!("validating data files");
     crate::data::validate::validate_all_startup_data().map_err(|e| {
         std::io::Error::new(std::io::ErrorKind::InvalidData, e)
     })?;
@@ -36,23 +105,114 @@ pub async fn run_server_async(bind_addr: &str) -> std::io::Result<()> {
         )
     })?;
 
-    let app = routes::build_router(registry);
+    // Identical optimize/simulate requests are served from cache/results (see result_cache).
+    crate::optimizer::result_cache::set_enabled(true);
+    // Finished optimizes feed the win-rate trends of /api/optimize/history (see history).
+    history::set_enabled(true);
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    println!("kobayashi server listening on http://{bind_addr}");
-    println!("  Sync: token-based routing (each profile has its own sync token).");
+    let state = routes::AppState::new(registry);
+    let header_read_timeout = state.limits.header_read;
+    if let Some(origins) = &state.cors {
+        tracing::info!(?origins, "CORS: cross-origin browser requests allowed");
+    }
+    // Re-runs stored optimize schedules (data/schedules.json) in the background.
+    tokio::spawn(schedule::run_scheduler(
+        state.data.clone(),
+        state.cpu_jobs.clone(),
+        state.webhooks.clone(),
+    ));
+    // Opt-in: KOBAYASHI_DATA_REMOTE_URL + KOBAYASHI_DATA_REMOTE_REFRESH_MINUTES.
+    if let Some(interval) = data_updates::refresh_interval() {
+        tracing::info!(minutes = interval.as_secs() / 60, "remote data auto-refresh on");
+        tokio::spawn(data_updates::run_auto_refresh(state.data.clone(), interval));
+    }
+    let app = routes::router_with_state(state);
+
+    let listeners = addrs.iter().map(|&addr| bind_listener(addr)).collect::<Result<Vec<_>, _>>()?;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    for addr in &addrs {
+        tracing::info!("kobayashi server listening on {scheme}://{addr}");
+    }
+    if tls.is_none() && addrs.iter().any(|addr| !addr.ip().is_loopback()) {
+        tracing::warn!(
+            "serving plain HTTP on a non-loopback address; roster data and sync tokens are sent \
+             in cleartext. Set {} and {} to enable TLS.",
+            tls::TLS_CERT_ENV,
+            tls::TLS_KEY_ENV
+        );
+    }
+    tracing::info!("sync: token-based routing (each profile has its own sync token)");
     if static_files::static_files_available() {
-        println!("  SPA: serving frontend from frontend/dist");
+        tracing::info!("SPA: serving frontend from frontend/dist");
     } else {
-        println!(
-            "  SPA: not found (API-only mode). \
+        tracing::warn!(
+            "SPA: not found (API-only mode). \
              To use the MVP UI: cd frontend, run 'npm install' then 'npm run build', \
              then restart the server from the project root."
         );
     }
 
-    axum::serve(listener, app).await?;
-    Ok(())
+    let mut servers = JoinSet::new();
+    for listener in listeners {
+        servers.spawn(serve_connections(listener, app.clone(), header_read_timeout, tls.clone()));
+    }
+    // Accept loops only return on a fatal error; the first one ends the server.
+    match servers.join_next().await {
+        Some(Ok(result)) => result,
+        Some(Err(e)) => Err(std::io::Error::other(e)),
+        None => Ok(()),
+    }
+}
+
+/// Accept loop standing in for `axum::serve` so each connection gets a header read deadline:
+/// hyper closes a connection that has not sent a complete request head within
+/// `header_read_timeout`. Body and handler deadlines are enforced per request by the router.
+/// With `tls`, each connection is served over HTTPS after a handshake bounded by the same
+/// deadline.
+pub async fn serve_connections(
+    listener: TcpListener,
+    app: Router,
+    header_read_timeout: Option<Duration>,
+    tls: Option<TlsAcceptor>,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                // Usually fd exhaustion; back off instead of spinning.
+                tracing::warn!(error = %e, "accept failed");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let _ = stream.set_nodelay(true);
+        let service = TowerToHyperService::new(app.clone());
+        let tls = tls.clone();
+        tokio::spawn(async move {
+            let Some(acceptor) = tls else {
+                return serve_connection(stream, service, header_read_timeout).await;
+            };
+            match tls::accept(&acceptor, stream, header_read_timeout).await {
+                Ok(stream) => serve_connection(stream, service, header_read_timeout).await,
+                Err(e) => tracing::debug!(error = %e, "TLS handshake failed"),
+            }
+        });
+    }
+}
+
+async fn serve_connection<I>(
+    io: I,
+    service: TowerToHyperService<Router>,
+    header_read_timeout: Option<Duration>,
+) where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut builder = http1::Builder::new();
+    builder.timer(TokioTimer::new()).header_read_timeout(header_read_timeout);
+    let connection = builder.serve_connection(TokioIo::new(io), service);
+    if let Err(e) = connection.with_upgrades().await {
+        tracing::debug!(error = %e, "connection closed with error");
+    }
 }
 
 /// Synchronous entry point: creates a tokio runtime and drives the async server.
@@ -65,3 +225,20 @@ pub fn run_server(bind_addr: &str) -> std::io::Result<()> {
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
         .block_on(run_server_async(bind_addr))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bind_list_accepts_ipv4_and_bracketed_ipv6() {
+        let addrs = parse_bind_addrs("127.0.0.1:3000, [::1]:3000,127.0.0.1:3000,").unwrap();
+        assert_eq!(addrs.len(), 2);
+        assert!(addrs[0].is_ipv4());
+        assert_eq!(addrs[1], "[::1]:3000".parse::<SocketAddr>().unwrap());
+
+        assert!(parse_bind_addrs(" , ").is_err());
+        let err = parse_bind_addrs("127.0.0.1:3000,::1:3000").unwrap_err();
+        assert!(err.to_string().contains("::1:3000"), "{err}");
+    }
+}
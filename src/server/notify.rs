@@ -0,0 +1,361 @@
+//! Optimize job webhooks: a job started with `notify_webhook` POSTs a compact summary (top crews,
+//! win rates, duration) to that URL once it settles, so a long exhaustive run can finish while the
+//! user is away from the UI.
+//!
+//! The body is a Discord webhook message (`content` holds the ready-to-read text) with the
+//! structured [OptimizeWebhookSummary] alongside under `summary` for other receivers.
+//!
+//! Any API caller picks the URL, so the server's [WebhookPolicy] only posts to hosts in
+//! `KOBAYASHI_WEBHOOK_HOSTS` (Discord unless set; see [webhook_hosts]), refuses hosts that resolve
+//! to loopback, private or otherwise non-public addresses (checked when connecting, see
+//! [post_webhook]), and does not follow redirects.
+
+use std::io;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::api::{CrewRecommendation, OptimizeRequest, OptimizeResponse};
+
+/// Crews listed in a webhook summary.
+pub const WEBHOOK_TOP_CREWS: usize = 3;
+/// Longest accepted `notify_webhook`, in characters.
+pub const MAX_WEBHOOK_URL_LEN: usize = 2048;
+/// Connect + send + response budget; a slow receiver must not hold the job thread for long.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Comma-separated hosts webhooks may be posted to; each also allows its subdomains.
+pub const WEBHOOK_HOSTS_ENV: &str = "KOBAYASHI_WEBHOOK_HOSTS";
+/// Allowed hosts when `KOBAYASHI_WEBHOOK_HOSTS` is unset.
+pub const DEFAULT_WEBHOOK_HOSTS: [&str; 2] = ["discord.com", "discordapp.com"];
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookCrew {
+    pub captain: String,
+    pub bridge: Vec<String>,
+    pub below_decks: Vec<String>,
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OptimizeWebhookSummary {
+    pub job_id: String,
+    /// "done" or "error" (including cancelled jobs).
+    pub status: &'static str,
+    pub ship: String,
+    pub hostile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    pub top_crews: Vec<WebhookCrew>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OptimizeWebhookSummary {
+    pub fn done(job_id: &str, request: &OptimizeRequest, response: &OptimizeResponse) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            status: "done",
+            ship: request.ship.clone(),
+            hostile: request.hostile.clone(),
+            duration_ms: response.duration_ms,
+            top_crews: response
+                .recommendations
+                .iter()
+                .take(WEBHOOK_TOP_CREWS)
+                .map(WebhookCrew::from)
+                .collect(),
+            error: None,
+        }
+    }
+
+    pub fn failed(job_id: &str, request: &OptimizeRequest, error: &str) -> Self {
+        Self {
+            job_id: job_id.to_string(),
+            status: "error",
+            ship: request.ship.clone(),
+            hostile: request.hostile.clone(),
+            duration_ms: None,
+            top_crews: Vec::new(),
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Discord message text (markdown), well under its 2000-character limit.
+    pub fn message(&self) -> String {
+        let mut text = format!("**Kobayashi** {} vs {}", self.ship, self.hostile);
+        match (&self.error, self.duration_ms) {
+            (Some(error), _) => text.push_str(&format!(": job failed ({error})")),
+            (None, Some(ms)) => text.push_str(&format!(": done in {:.1}s", ms as f64 / 1000.0)),
+            (None, None) => text.push_str(": done"),
+        }
+        for (rank, crew) in self.top_crews.iter().enumerate() {
+            let mut officers = vec![crew.captain.as_str()];
+            officers.extend(crew.bridge.iter().map(String::as_str));
+            text.push_str(&format!(
+                "\n{}. {} ({:.1}% win)",
+                rank + 1,
+                officers.join(", "),
+                crew.win_rate * 100.0
+            ));
+            if !crew.below_decks.is_empty() {
+                text.push_str(&format!(" · BD {}", crew.below_decks.join(", ")));
+            }
+        }
+        if self.error.is_none() && self.top_crews.is_empty() {
+            text.push_str("\nNo crews were ranked.");
+        }
+        text
+    }
+
+    pub fn body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "username": "Kobayashi",
+            "content": self.message(),
+            "summary": self,
+        })
+    }
+}
+
+impl From<&CrewRecommendation> for WebhookCrew {
+    fn from(rec: &CrewRecommendation) -> Self {
+        Self {
+            captain: rec.captain.clone(),
+            bridge: rec.bridge.clone(),
+            below_decks: rec.below_decks.clone(),
+            win_rate: rec.win_rate,
+        }
+    }
+}
+
+/// Hosts webhooks may be posted to: `KOBAYASHI_WEBHOOK_HOSTS` (lowercased; empty turns webhooks
+/// off) or [DEFAULT_WEBHOOK_HOSTS] when unset.
+pub fn webhook_hosts() -> Vec<String> {
+    match std::env::var(WEBHOOK_HOSTS_ENV) {
+        Ok(list) => list
+            .split(',')
+            .map(|host| host.trim().trim_end_matches('.').to_ascii_lowercase())
+            .filter(|host| !host.is_empty())
+            .collect(),
+        Err(_) => DEFAULT_WEBHOOK_HOSTS.iter().map(|host| host.to_string()).collect(),
+    }
+}
+
+/// Where webhooks may be posted. The server runs with [WebhookPolicy::from_env]; tests swap in
+/// their own through `AppState::with_webhooks` to reach a loopback receiver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookPolicy {
+    /// Lowercase hosts webhooks may be posted to, each also allowing its subdomains; empty turns
+    /// webhooks off.
+    pub hosts: Vec<String>,
+    /// Also connect to loopback, private and other non-public addresses. Never set in production.
+    pub allow_non_public: bool,
+}
+
+impl WebhookPolicy {
+    /// [webhook_hosts], public addresses only.
+    pub fn from_env() -> Self {
+        Self { hosts: webhook_hosts(), allow_non_public: false }
+    }
+
+    /// Accepts absolute `http://` or `https://` URLs whose host is in [WebhookPolicy::hosts].
+    pub fn check_url(&self, url: &str) -> Result<(), String> {
+        check_webhook_url(url, &self.hosts)
+    }
+}
+
+/// [WebhookPolicy::check_url] against an explicit allowlist.
+fn check_webhook_url(url: &str, allowed_hosts: &[String]) -> Result<(), String> {
+    if url.len() > MAX_WEBHOOK_URL_LEN {
+        return Err(format!("must be at most {MAX_WEBHOOK_URL_LEN} characters"));
+    }
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| "must be an http:// or https:// URL".to_string())?;
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if authority.contains('@') {
+        return Err("must not contain credentials".to_string());
+    }
+    let host = match authority.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => authority.split(':').next().unwrap_or_default(),
+    };
+    if host.is_empty() || host.chars().any(char::is_whitespace) {
+        return Err("must name a host".to_string());
+    }
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let allowed = allowed_hosts.iter().any(|allowed| {
+        host == *allowed || host.strip_suffix(allowed.as_str()).is_some_and(|s| s.ends_with('.'))
+    });
+    if !allowed {
+        return Err(if allowed_hosts.is_empty() {
+            format!("webhooks are disabled ({WEBHOOK_HOSTS_ENV} is empty)")
+        } else {
+            format!("host must be one of {} (see {WEBHOOK_HOSTS_ENV})", allowed_hosts.join(", "))
+        });
+    }
+    Ok(())
+}
+
+/// Whether `ip` is routable on the public internet: not loopback, private, link-local, shared
+/// (carrier-grade NAT), unique-local, multicast, documentation or unspecified.
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_public_ip(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                !(v6.is_loopback()
+                    || v6.is_unspecified()
+                    || v6.is_multicast()
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+/// Resolves `netloc` (host:port) for a webhook connection, refusing hosts with any non-public
+/// address so an allowed name cannot point the server at itself or its network.
+fn resolve_public(netloc: &str) -> io::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = netloc.to_socket_addrs()?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("webhook host resolves to non-public address {}", addr.ip()),
+        ));
+    }
+    Ok(addrs)
+}
+
+/// POSTs `summary` to `url` after re-checking it against `policy`. Connects only to public
+/// addresses unless [WebhookPolicy::allow_non_public] and does not follow redirects. Blocking;
+/// call from the job thread, never from an async handler.
+pub fn post_webhook(
+    url: &str,
+    summary: &OptimizeWebhookSummary,
+    policy: &WebhookPolicy,
+) -> Result<(), String> {
+    policy.check_url(url)?;
+    let agent = ureq::AgentBuilder::new().timeout(WEBHOOK_TIMEOUT).redirects(0);
+    let agent = if policy.allow_non_public { agent } else { agent.resolver(resolve_public) };
+    agent
+        .build()
+        .post(url)
+        .set("Content-Type", "application/json")
+        .send_string(&summary.body().to_string())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hosts(list: &[&str]) -> Vec<String> {
+        list.iter().map(|host| host.to_string()).collect()
+    }
+
+    #[test]
+    fn webhook_urls_need_an_http_scheme_and_host() {
+        let discord = hosts(&DEFAULT_WEBHOOK_HOSTS);
+        assert!(check_webhook_url("https://discord.com/api/webhooks/1/abc", &discord).is_ok());
+        assert!(check_webhook_url("ftp://discord.com", &discord).is_err());
+        assert!(check_webhook_url("https:///path", &discord).is_err());
+        assert!(check_webhook_url("discord.com/api/webhooks/1", &discord).is_err());
+        let long = format!("https://discord.com/{}", "a".repeat(MAX_WEBHOOK_URL_LEN));
+        assert!(check_webhook_url(&long, &discord).is_err());
+    }
+
+    #[test]
+    fn webhook_hosts_must_be_allowed() {
+        let discord = hosts(&DEFAULT_WEBHOOK_HOSTS);
+        let canary = "https://canary.discord.com:443/api/webhooks/1";
+        assert!(check_webhook_url(canary, &discord).is_ok());
+        assert!(check_webhook_url("https://DISCORD.com./api/webhooks/1", &discord).is_ok());
+        for url in [
+            "http://127.0.0.1:9000/hook",
+            "http://localhost/hook",
+            "http://[::1]/hook",
+            "https://evildiscord.com/hook",
+            "https://discord.com.evil.example/hook",
+            "https://discord.com@127.0.0.1/hook",
+        ] {
+            assert!(check_webhook_url(url, &discord).is_err(), "{url}");
+        }
+        let own = hosts(&["hooks.example.com"]);
+        assert!(check_webhook_url("https://hooks.example.com/x", &own).is_ok());
+        assert!(check_webhook_url("https://discord.com/api/webhooks/1", &own).is_err());
+        assert!(check_webhook_url("https://discord.com/api/webhooks/1", &[]).is_err());
+    }
+
+    #[test]
+    fn webhooks_never_connect_to_non_public_addresses() {
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "0.0.0.0",
+            "100.64.0.1", "::1", "fe80::1", "fd00::1", "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["162.159.135.232", "8.8.8.8", "2606:4700::6810:1"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        assert!(resolve_public("127.0.0.1:80").is_err());
+        assert!(resolve_public("localhost:80").is_err());
+    }
+
+    #[test]
+    fn allowed_loopback_hosts_still_need_allow_non_public() {
+        let policy = WebhookPolicy { hosts: hosts(&["127.0.0.1"]), allow_non_public: false };
+        let summary = OptimizeWebhookSummary {
+            job_id: "opt_1_0".to_string(),
+            status: "error",
+            ship: "saladin".to_string(),
+            hostile: "swarm_33".to_string(),
+            duration_ms: None,
+            top_crews: Vec::new(),
+            error: Some("boom".to_string()),
+        };
+        let error = post_webhook("http://127.0.0.1:9/hook", &summary, &policy).unwrap_err();
+        assert!(error.contains("non-public"), "{error}");
+    }
+
+    #[test]
+    fn message_lists_crews_with_win_rates() {
+        let summary = OptimizeWebhookSummary {
+            job_id: "opt_1_0".to_string(),
+            status: "done",
+            ship: "saladin".to_string(),
+            hostile: "swarm_33".to_string(),
+            duration_ms: Some(12_340),
+            top_crews: vec![WebhookCrew {
+                captain: "kirk".to_string(),
+                bridge: vec!["spock".to_string(), "mccoy".to_string()],
+                below_decks: vec!["uhura".to_string()],
+                win_rate: 0.8766,
+            }],
+            error: None,
+        };
+        assert_eq!(
+            summary.message(),
+            "**Kobayashi** saladin vs swarm_33: done in 12.3s\n\
+             1. kirk, spock, mccoy (87.7% win) · BD uhura"
+        );
+        let body = summary.body();
+        assert_eq!(body["summary"]["top_crews"][0]["win_rate"], 0.8766);
+        assert!(body["content"].as_str().unwrap().contains("kirk"));
+    }
+}
@@ -0,0 +1,748 @@
+//! OpenAPI 3.0 description of the HTTP API, served at `GET /api/openapi.json`.
+//!
+//! Request and response schemas are generated with schemars from the `server::api` structs, so
+//! field names, doc comments and the numeric bounds enforced by validation (`#[schemars(range)]`
+//! on the request fields, pointing at the same `MAX_*` constants) follow the code. The operation
+//! list is kept by hand: when adding a route in [crate::server::routes::build_router], add it to
+//! [openapi_spec] too.
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+use crate::server::api::{
+    BracketRequest, BracketResponse, CrewCodeDecodeResponse, CrewCodeRequest, CrewCodeResponse,
+    DataVersionResponse, HealthResponse, HostileListItem, MatrixFile, MatrixResponse,
+    OfficerBoostsRequest, OfficerBoostsResponse, OfficerLeaderboardRequest,
+    OfficerLeaderboardResponse, OfficerListItem, OfficerUpgradesRequest, OfficerUpgradesResponse,
+    OptimizeRequest, OptimizeResponse, OptimizeStartResponse, OptimizeStatusResponse, PlayerProfile,
+    Preset, PresetCrew, PresetSummary, ScenarioFile, ScenarioResponse, ShipDetailResponse,
+    ShipListItem, SimulateRequest, SimulateResponse, StatSensitivityRequest,
+    StatSensitivityResponse, SweepRequest, SweepResponse, TraceRequest, TraceResponse,
+    UnknownIdsResponse, ValidationErrorResponse,
+};
+use crate::server::data_updates::{ApplyUpdatesRequest, ApplyUpdatesResponse, DataUpdatesResponse};
+use crate::server::history::{OptimizeHistoryResponse, MAX_HISTORY_TOP};
+use crate::server::loadouts::{Loadout, LoadoutRequest};
+use crate::server::schedule::{Schedule, ScheduleRequest};
+use crate::server::settings::{SettingsPatch, SettingsResponse};
+
+/// One operation under construction; see [Spec::add].
+struct Operation {
+    summary: &'static str,
+    parameters: Vec<Value>,
+    body: Option<Value>,
+    response: Value,
+    response_type: &'static str,
+    validation_error: Option<Value>,
+    unknown_ids: Option<Value>,
+}
+
+impl Operation {
+    fn new(summary: &'static str, response: Value) -> Self {
+        Self {
+            summary,
+            parameters: Vec::new(),
+            body: None,
+            response,
+            response_type: "application/json",
+            validation_error: None,
+            unknown_ids: None,
+        }
+    }
+
+    /// Honours the `X-Profile-Id` header (or `?profile=`).
+    fn profile(mut self) -> Self {
+        self.parameters
+            .push(json!({ "$ref": "#/components/parameters/ProfileId" }));
+        self
+    }
+
+    fn query(mut self, name: &str, schema: Value, description: &str) -> Self {
+        self.parameters.push(json!({
+            "name": name,
+            "in": "query",
+            "required": false,
+            "description": description,
+            "schema": schema,
+        }));
+        self
+    }
+
+    fn body(mut self, schema: Value) -> Self {
+        self.body = Some(schema);
+        self
+    }
+
+    /// 400 body when validation fails; defaults to the generic error shape.
+    fn validation_error(mut self, schema: Value) -> Self {
+        self.validation_error = Some(schema);
+        self
+    }
+
+    /// 404 body when strict mode rejects an unknown ship or hostile.
+    fn unknown_ids(mut self, schema: Value) -> Self {
+        self.unknown_ids = Some(schema);
+        self
+    }
+
+    fn image(mut self) -> Self {
+        self.response_type = "image/*";
+        self
+    }
+
+    fn event_stream(mut self) -> Self {
+        self.response_type = "text/event-stream";
+        self
+    }
+
+    fn into_json(self, path: &str) -> Value {
+        let mut parameters: Vec<Value> = path
+            .split('/')
+            .filter_map(|seg| seg.strip_prefix('{')?.strip_suffix('}'))
+            .map(|name| {
+                json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } })
+            })
+            .collect();
+        parameters.extend(self.parameters);
+
+        let mut responses = Map::new();
+        responses.insert(
+            "200".to_string(),
+            json!({
+                "description": "OK",
+                "content": { self.response_type: { "schema": self.response } },
+            }),
+        );
+        if self.body.is_some() || self.validation_error.is_some() {
+            let schema = self
+                .validation_error
+                .unwrap_or_else(|| json!({ "$ref": "#/components/schemas/ApiError" }));
+            responses.insert(
+                "400".to_string(),
+                json!({
+                    "description": "Malformed or invalid request",
+                    "content": { "application/json": { "schema": schema } },
+                }),
+            );
+        }
+
+        if let Some(schema) = self.unknown_ids {
+            responses.insert(
+                "404".to_string(),
+                json!({
+                    "description": "Unknown ship or hostile (strict mode), with near-miss ids",
+                    "content": { "application/json": { "schema": schema } },
+                }),
+            );
+        }
+
+        let mut op = Map::new();
+        op.insert("summary".to_string(), json!(self.summary));
+        if !parameters.is_empty() {
+            op.insert("parameters".to_string(), Value::Array(parameters));
+        }
+        if let Some(body) = self.body {
+            op.insert(
+                "requestBody".to_string(),
+                json!({ "required": true, "content": { "application/json": { "schema": body } } }),
+            );
+        }
+        op.insert("responses".to_string(), Value::Object(responses));
+        Value::Object(op)
+    }
+}
+
+struct Spec {
+    gen: SchemaGenerator,
+    paths: Map<String, Value>,
+}
+
+impl Spec {
+    /// `$ref` to `T`'s schema, registering it under `components.schemas`.
+    fn schema<T: JsonSchema>(&mut self) -> Value {
+        serde_json::to_value(self.gen.subschema_for::<T>()).unwrap_or_default()
+    }
+
+    /// `{ "<key>": [T] }`, the shape of the list endpoints.
+    fn list_of<T: JsonSchema>(&mut self, key: &str) -> Value {
+        let items = self.schema::<T>();
+        json!({
+            "type": "object",
+            "properties": { key: { "type": "array", "items": items } },
+        })
+    }
+
+    fn add(&mut self, method: &str, path: &str, op: Operation) {
+        let entry = self
+            .paths
+            .entry(path.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        entry[method] = op.into_json(path);
+    }
+}
+
+/// Response whose shape is not modelled by a `server::api` struct.
+fn free_form(description: &str) -> Value {
+    json!({ "type": "object", "description": description })
+}
+
+/// Build the OpenAPI document for every `/api` route.
+pub fn openapi_spec() -> Value {
+    let mut spec = Spec {
+        gen: SchemaSettings::openapi3().into_generator(),
+        paths: Map::new(),
+    };
+    let owned_only = json!({ "type": "boolean" });
+    let status_ok = json!({ "$ref": "#/components/schemas/ApiStatus" });
+
+    let health = spec.schema::<HealthResponse>();
+    spec.add(
+        "get",
+        "/api/health",
+        Operation::new("Liveness: version, dataset load state, workers and jobs", health.clone()),
+    );
+    spec.add(
+        "get",
+        "/api/health/ready",
+        Operation::new("Readiness: the health report, 503 while a dataset is missing", health),
+    );
+    spec.add(
+        "get",
+        "/api/openapi.json",
+        Operation::new("This document", free_form("OpenAPI 3.0 document")),
+    );
+
+    let officers = spec.list_of::<OfficerListItem>("officers");
+    spec.add(
+        "get",
+        "/api/officers",
+        Operation::new("List officers", officers)
+            .profile()
+            .query("owned_only", owned_only.clone(), "Only officers unlocked in the profile roster"),
+    );
+    spec.add(
+        "post",
+        "/api/officers/import",
+        Operation::new("Import a roster (CSV text or Spocks JSON export)", free_form("Import report"))
+            .profile()
+            .body(free_form(
+                "Roster file contents, or {\"url\": \"...\"} naming a Google Sheets CSV \
+                 (needs KOBAYASHI_SHEETS_IMPORT=1)",
+            )),
+    );
+    spec.add(
+        "get",
+        "/api/officers/{id}/resolved",
+        Operation::new("Officer with abilities resolved to engine effects", free_form("Resolved officer")),
+    );
+
+    let ships = spec.list_of::<ShipListItem>("ships");
+    spec.add(
+        "get",
+        "/api/ships",
+        Operation::new("List ships", ships)
+            .profile()
+            .query("owned_only", owned_only, "Only ships in the profile roster")
+            .query(
+                "q",
+                json!({ "type": "string" }),
+                "Only ships matching this id or name (typos allowed), best match first",
+            ),
+    );
+    let ship_detail = spec.schema::<ShipDetailResponse>();
+    spec.add(
+        "get",
+        "/api/ships/{id}",
+        Operation::new(
+            "Ship with its crew seats per tier and its power (404 when unknown)",
+            ship_detail,
+        )
+        .profile()
+        .query("tier", json!({ "type": "integer" }), "Ship tier (default 1)")
+        .query("level", json!({ "type": "integer" }), "Ship level (default 1)"),
+    );
+    spec.add(
+        "get",
+        "/api/ships/{id}/tiers-levels",
+        Operation::new(
+            "Tiers and levels available for a ship",
+            json!({
+                "type": "object",
+                "properties": {
+                    "tiers": { "type": "array", "items": { "type": "integer" } },
+                    "levels": { "type": "array", "items": { "type": "integer" } },
+                },
+            }),
+        ),
+    );
+    let hostiles = spec.list_of::<HostileListItem>("hostiles");
+    spec.add(
+        "get",
+        "/api/hostiles",
+        Operation::new("List hostiles", hostiles).query(
+            "q",
+            json!({ "type": "string" }),
+            "Only hostiles matching this id or name and level (\"swarm 37\"), best match first",
+        ),
+    );
+
+    let data_version = spec.schema::<DataVersionResponse>();
+    spec.add(
+        "get",
+        "/api/data/version",
+        Operation::new("Data versions and mechanic support status", data_version),
+    );
+    let data_updates = spec.schema::<DataUpdatesResponse>();
+    spec.add(
+        "get",
+        "/api/data/updates",
+        Operation::new("Installed vs mirrored hostile/officer data versions", data_updates),
+    );
+    let apply_updates = spec.schema::<ApplyUpdatesResponse>();
+    let apply_request = spec.schema::<ApplyUpdatesRequest>();
+    spec.add(
+        "post",
+        "/api/data/updates/apply",
+        Operation::new(
+            "Download, validate and load mirrored data updates (admin token)",
+            apply_updates,
+        )
+            .body(apply_request),
+    );
+    spec.add(
+        "get",
+        "/api/forbidden-tech",
+        Operation::new("Forbidden and chaos tech catalog", free_form("{ items: [...] }")),
+    );
+    spec.add(
+        "get",
+        "/api/assets/{path}",
+        Operation::new(
+            "Officer/ship image from the asset cache, fetched from the CDN on a miss",
+            json!({ "type": "string", "format": "binary" }),
+        )
+        .image(),
+    );
+
+    let profile = spec.schema::<PlayerProfile>();
+    let validation_error = spec.schema::<ValidationErrorResponse>();
+    spec.add(
+        "get",
+        "/api/profile",
+        Operation::new("Player profile bonuses", profile.clone()).profile(),
+    );
+    spec.add(
+        "put",
+        "/api/profile",
+        Operation::new("Replace player profile bonuses", status_ok.clone())
+            .profile()
+            .body(profile)
+            .validation_error(validation_error.clone()),
+    );
+    spec.add(
+        "get",
+        "/api/profile/buildings-summary",
+        Operation::new("Synced buildings and their combat bonuses", free_form("Building summary"))
+            .profile(),
+    );
+    spec.add(
+        "get",
+        "/api/profile/research-summary",
+        Operation::new("Synced research and its combat bonuses", free_form("Research summary"))
+            .profile(),
+    );
+    spec.add(
+        "get",
+        "/api/profiles",
+        Operation::new("List profiles", free_form("{ profiles: [...], default_id }")),
+    );
+    spec.add(
+        "post",
+        "/api/profiles",
+        Operation::new("Create a profile", free_form("Profile entry")).body(json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": { "id": { "type": "string" }, "name": { "type": "string" } },
+        })),
+    );
+    spec.add(
+        "delete",
+        "/api/profiles/{id}",
+        Operation::new("Delete a profile", status_ok.clone()),
+    );
+
+    let presets = spec.list_of::<PresetSummary>("presets");
+    let preset = spec.schema::<Preset>();
+    let preset_crew = spec.schema::<PresetCrew>();
+    spec.add(
+        "get",
+        "/api/presets",
+        Operation::new("List saved crew presets", presets).profile(),
+    );
+    spec.add(
+        "post",
+        "/api/presets",
+        Operation::new("Save a crew preset", preset.clone())
+            .profile()
+            .body(json!({
+                "type": "object",
+                "required": ["ship", "scenario", "crew"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "ship": { "type": "string" },
+                    "scenario": { "type": "string" },
+                    "crew": preset_crew,
+                },
+            })),
+    );
+    spec.add(
+        "get",
+        "/api/presets/{id}",
+        Operation::new("Get a crew preset", preset).profile(),
+    );
+
+    let crew_code_request = spec.schema::<CrewCodeRequest>();
+    let crew_code_response = spec.schema::<CrewCodeResponse>();
+    let crew_code_decoded = spec.schema::<CrewCodeDecodeResponse>();
+    spec.add(
+        "post",
+        "/api/crewcode",
+        Operation::new("Pack a crew, ship and profile bonuses into a code", crew_code_response)
+            .profile()
+            .body(crew_code_request),
+    );
+    spec.add(
+        "get",
+        "/api/crewcode/{code}",
+        Operation::new("Decode a share code into a scenario body", crew_code_decoded).query(
+            "hostile",
+            json!({ "type": "string" }),
+            "Hostile to fight; required when the code names none",
+        ),
+    );
+
+    let unknown_ids = spec.schema::<UnknownIdsResponse>();
+    let simulate_request = spec.schema::<SimulateRequest>();
+    let simulate_response = spec.schema::<SimulateResponse>();
+    spec.add(
+        "post",
+        "/api/simulate",
+        Operation::new("Monte Carlo win rate of a fixed crew", simulate_response)
+            .profile()
+            .body(simulate_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let trace_request = spec.schema::<TraceRequest>();
+    let trace_response = spec.schema::<TraceResponse>();
+    spec.add(
+        "post",
+        "/api/simulate/trace",
+        Operation::new("One traced fight, a page of its filtered events", trace_response)
+            .profile()
+            .body(trace_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let scenario_request = spec.schema::<ScenarioFile>();
+    let scenario_response = spec.schema::<ScenarioResponse>();
+    spec.add(
+        "post",
+        "/api/simulate/scenario",
+        Operation::new("Run a scenario file (YAML or JSON)", scenario_response)
+            .profile()
+            .body(scenario_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let matrix_request = spec.schema::<MatrixFile>();
+    let matrix_response = spec.schema::<MatrixResponse>();
+    spec.add(
+        "post",
+        "/api/simulate/matrix",
+        Operation::new("Run every combination of a scenario file's matrix", matrix_response)
+            .profile()
+            .body(matrix_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+
+    let upgrades_request = spec.schema::<OfficerUpgradesRequest>();
+    let upgrades_response = spec.schema::<OfficerUpgradesResponse>();
+    spec.add(
+        "post",
+        "/api/analyze/officer-upgrades",
+        Operation::new("Rank roster officers by win-rate gain per shard", upgrades_response)
+            .profile()
+            .body(upgrades_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let boosts_request = spec.schema::<OfficerBoostsRequest>();
+    let boosts_response = spec.schema::<OfficerBoostsResponse>();
+    spec.add(
+        "post",
+        "/api/analyze/officer-boosts",
+        Operation::new("Rank each top crew's officers by win-rate gain when boosted", boosts_response)
+            .profile()
+            .body(boosts_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let leaderboard_request = spec.schema::<OfficerLeaderboardRequest>();
+    let leaderboard_response = spec.schema::<OfficerLeaderboardResponse>();
+    spec.add(
+        "post",
+        "/api/analyze/officer-leaderboard",
+        Operation::new(
+            "Rank officers per seat by average marginal win rate over sampled crews",
+            leaderboard_response,
+        )
+        .profile()
+        .body(leaderboard_request)
+        .unknown_ids(unknown_ids.clone()),
+    );
+    let stats_request = spec.schema::<StatSensitivityRequest>();
+    let stats_response = spec.schema::<StatSensitivityResponse>();
+    spec.add(
+        "post",
+        "/api/analyze/stats",
+        Operation::new("Win-rate sensitivity of a fixed crew to ship stats", stats_response)
+            .profile()
+            .body(stats_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let sweep_request = spec.schema::<SweepRequest>();
+    let sweep_response = spec.schema::<SweepResponse>();
+    spec.add(
+        "post",
+        "/api/analyze/sweep",
+        Operation::new("Win rate of a fixed crew across a hostile family's levels", sweep_response)
+            .profile()
+            .body(sweep_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+    let bracket_request = spec.schema::<BracketRequest>();
+    let bracket_response = spec.schema::<BracketResponse>();
+    spec.add(
+        "post",
+        "/api/analyze/bracket",
+        Operation::new("Round-robin of saved presets against one hostile", bracket_response)
+            .profile()
+            .body(bracket_request)
+            .unknown_ids(unknown_ids.clone()),
+    );
+
+    let optimize_request = spec.schema::<OptimizeRequest>();
+    let optimize_response = spec.schema::<OptimizeResponse>();
+    spec.add(
+        "post",
+        "/api/optimize",
+        Operation::new("Rank crews for a ship and hostile", optimize_response)
+            .profile()
+            .body(optimize_request.clone())
+            .validation_error(validation_error.clone())
+            .unknown_ids(unknown_ids.clone()),
+    );
+    spec.add(
+        "get",
+        "/api/heuristics",
+        Operation::new(
+            "Heuristics seed lists",
+            json!({
+                "type": "object",
+                "properties": { "seeds": { "type": "array", "items": { "type": "string" } } },
+            }),
+        ),
+    );
+    spec.add(
+        "get",
+        "/api/optimize/estimate",
+        Operation::new("Candidate count and run-time estimate", free_form("Estimate"))
+            .profile()
+            .query("ship", json!({ "type": "string" }), "Ship id")
+            .query("hostile", json!({ "type": "string" }), "Hostile id")
+            .query("sims", json!({ "type": "integer" }), "Sims per crew")
+            .query("max_candidates", json!({ "type": "integer" }), "Candidate cap")
+            .query(
+                "prioritize_below_decks_ability",
+                json!({ "type": "boolean" }),
+                "Prefer below-decks officers with an ability",
+            )
+            .validation_error(validation_error.clone()),
+    );
+    let history_response = spec.schema::<OptimizeHistoryResponse>();
+    spec.add(
+        "get",
+        "/api/optimize/history",
+        Operation::new("Win rate over time of a scenario's top crews", history_response)
+            .profile()
+            .query("ship", json!({ "type": "string" }), "Ship id")
+            .query("hostile", json!({ "type": "string" }), "Hostile id")
+            .query(
+                "top",
+                json!({ "type": "integer", "minimum": 1, "maximum": MAX_HISTORY_TOP }),
+                "Crews per scenario (best of the latest run); default 3",
+            ),
+    );
+    let start_response = spec.schema::<OptimizeStartResponse>();
+    let status_response = spec.schema::<OptimizeStatusResponse>();
+    spec.add(
+        "post",
+        "/api/optimize/start",
+        Operation::new("Start a background optimize job", start_response)
+            .profile()
+            .body(optimize_request)
+            .validation_error(validation_error.clone())
+            .unknown_ids(unknown_ids.clone()),
+    );
+    spec.add(
+        "get",
+        "/api/optimize/status/{job_id}",
+        Operation::new("Progress or result of an optimize job", status_response.clone()),
+    );
+    spec.add(
+        "get",
+        "/api/optimize/jobs/{job_id}/stream",
+        Operation::new(
+            "Server-sent progress events; each `data:` is an OptimizeStatusResponse",
+            status_response,
+        )
+        .event_stream(),
+    );
+    spec.add(
+        "post",
+        "/api/optimize/jobs/{job_id}/cancel",
+        Operation::new("Cancel an optimize job", status_ok.clone()),
+    );
+
+    let schedule_request = spec.schema::<ScheduleRequest>();
+    let schedule = spec.schema::<Schedule>();
+    let schedules = spec.list_of::<Schedule>("schedules");
+    spec.add(
+        "get",
+        "/api/schedules",
+        Operation::new("Scheduled optimizations with their run history", schedules),
+    );
+    spec.add(
+        "post",
+        "/api/schedules",
+        Operation::new("Re-run an optimize request on an interval or on data changes", schedule.clone())
+            .profile()
+            .body(schedule_request)
+            .validation_error(validation_error.clone())
+            .unknown_ids(unknown_ids),
+    );
+    spec.add(
+        "get",
+        "/api/schedules/{id}",
+        Operation::new("A scheduled optimization and its run history", schedule),
+    );
+    spec.add(
+        "delete",
+        "/api/schedules/{id}",
+        Operation::new("Delete a scheduled optimization", status_ok.clone()),
+    );
+
+    let loadout_request = spec.schema::<LoadoutRequest>();
+    let loadout = spec.schema::<Loadout>();
+    let loadouts = spec.list_of::<Loadout>("loadouts");
+    spec.add(
+        "get",
+        "/api/loadouts",
+        Operation::new("Saved loadouts (officers committed to a ship)", loadouts),
+    );
+    spec.add(
+        "post",
+        "/api/loadouts",
+        Operation::new("Save a loadout (see optimize `exclude_loadouts`)", loadout.clone())
+            .body(loadout_request.clone())
+            .validation_error(validation_error.clone()),
+    );
+    spec.add("get", "/api/loadouts/{id}", Operation::new("A saved loadout", loadout.clone()));
+    spec.add(
+        "put",
+        "/api/loadouts/{id}",
+        Operation::new("Replace a saved loadout", loadout)
+            .body(loadout_request)
+            .validation_error(validation_error),
+    );
+    spec.add(
+        "delete",
+        "/api/loadouts/{id}",
+        Operation::new("Delete a saved loadout", status_ok),
+    );
+
+    spec.add(
+        "get",
+        "/api/sync/status",
+        Operation::new("Sync ingress status", free_form("Sync status")),
+    );
+    spec.add(
+        "post",
+        "/api/sync/ingress",
+        Operation::new("Game data sync from the community mod", free_form("Ingress result"))
+            .body(free_form("Sync payload (authenticated with the profile's sync token)")),
+    );
+
+    let settings = spec.schema::<SettingsResponse>();
+    let settings_patch = spec.schema::<SettingsPatch>();
+    spec.add(
+        "get",
+        "/api/admin/settings",
+        Operation::new("Runtime settings (Authorization: Bearer admin token)", settings.clone()),
+    );
+    spec.add(
+        "patch",
+        "/api/admin/settings",
+        Operation::new("Change runtime settings without a restart (admin token)", settings)
+            .body(settings_patch),
+    );
+
+    let mut schemas = Map::new();
+    for (name, schema) in spec.gen.take_definitions() {
+        schemas.insert(name, serde_json::to_value(schema).unwrap_or_default());
+    }
+    schemas.insert(
+        "ApiError".to_string(),
+        json!({
+            "type": "object",
+            "required": ["status", "message"],
+            "properties": {
+                "status": { "type": "string", "enum": ["error"] },
+                "message": { "type": "string" },
+            },
+        }),
+    );
+    schemas.insert(
+        "ApiStatus".to_string(),
+        json!({
+            "type": "object",
+            "properties": {
+                "status": { "type": "string" },
+                "message": { "type": "string" },
+            },
+        }),
+    );
+
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Kobayashi API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": spec.paths,
+        "components": {
+            "schemas": schemas,
+            "parameters": {
+                "ProfileId": {
+                    "name": "X-Profile-Id",
+                    "in": "header",
+                    "required": false,
+                    "description": "Profile to use; the default profile when absent. `?profile=<id>` works too.",
+                    "schema": { "type": "string" },
+                },
+            },
+        },
+    })
+}
+
+/// GET /api/openapi.json
+pub fn openapi_payload() -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&openapi_spec())
+}
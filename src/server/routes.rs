@@ -4,36 +4,145 @@
 //! synchronous and may do I/O or CPU work).  Heavy operations (optimize,
 //! simulate) are offloaded to a blocking thread pool via
 //! `tokio::task::spawn_blocking` so that the async runtime stays responsive.
-//! `/api/simulate` and synchronous `/api/optimize` share a semaphore
-//! (`KOBAYASHI_MAX_CONCURRENT_CPU_JOBS`, default 1).
+//! `/api/simulate`, `/api/simulate/trace`, `/api/simulate/scenario`, `/api/simulate/matrix`,
+//! `/ws/simulate`, `/api/analyze/*` and synchronous `/api/optimize` share a semaphore
+//! (`KOBAYASHI_MAX_CONCURRENT_CPU_JOBS`, default 1). Background optimize jobs hold a slot for
+//! their whole run, so `/api/simulate` takes one reserved extra slot when every slot is taken
+//! while such a job runs; the job pauses between batches meanwhile (see [AppState::cpu_permit]).
+//! Every request goes through [enforce_request_limits] (body and handler deadlines, body size;
+//! see [crate::server::limits]) and, when configured, the CORS layer ([crate::server::cors]).
 
 use axum::{
     Router,
-    extract::OriginalUri,
+    body::Body,
+    extract::{DefaultBodyLimit, OriginalUri, Request},
+    middleware::{self, Next},
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
     extract::{Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
     response::sse::{Event, Sse},
     response::{IntoResponse, Response},
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
 };
 use std::collections::HashMap;
 use std::convert::Infallible;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Semaphore;
+use http_body_util::LengthLimitError;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, TryAcquireError};
 use tokio_stream::wrappers::ReceiverStream;
 
-use crate::data::data_registry::DataRegistry;
+use crate::data::data_registry::{DataRegistry, SharedRegistry};
+use crate::data::sheets::SheetsError;
+use crate::parallel::Priority;
 use crate::server::api;
+use crate::server::assets::{AssetError, AssetProxy};
+use crate::server::cors::{self, CorsOrigins};
+use crate::server::data_updates;
+use crate::server::history;
+use crate::server::loadouts;
+use crate::server::notify::WebhookPolicy;
+use crate::server::limits::RequestLimits;
+use crate::server::openapi;
+use crate::server::schedule;
+use crate::server::settings::{self, RuntimeSettings, SettingsPatch};
 use crate::server::sync;
 
 /// Application state shared by all handlers.
 #[derive(Clone)]
 pub struct AppState {
-    pub registry: Arc<DataRegistry>,
-    /// Limits concurrent CPU-heavy `spawn_blocking` tasks (`/api/simulate`, `/api/optimize`).
+    /// Current game data; use [AppState::registry] for a per-request snapshot.
+    pub data: Arc<SharedRegistry>,
+    /// Limits concurrent CPU-heavy `spawn_blocking` tasks (`/api/simulate`, `/api/analyze/*`,
+    /// `/api/optimize`).
     pub cpu_jobs: Arc<Semaphore>,
+    /// Permits `cpu_jobs` started with, for the health report.
+    pub cpu_job_slots: usize,
+    /// One slot for interactive calls when background jobs hold every `cpu_jobs` slot.
+    pub interactive_cpu_job: Arc<Semaphore>,
+    /// Request deadlines and body size cap, from the environment unless set with
+    /// [AppState::with_limits].
+    pub limits: Arc<RequestLimits>,
+    /// Origins allowed to call the API from a browser; `None` sends no CORS headers.
+    pub cors: Option<CorsOrigins>,
+    /// CDN proxy and disk cache behind `/api/assets/*path`.
+    pub assets: Arc<AssetProxy>,
+    /// Runtime knobs (the process-wide [settings::current]); see `/api/admin/settings`.
+    pub settings: Arc<RuntimeSettings>,
+    /// Bearer token for `/api/admin/*`; `None` disables those routes.
+    pub admin_token: Option<String>,
+    /// Where `notify_webhook` summaries may be posted; see [WebhookPolicy::from_env].
+    pub webhooks: Arc<WebhookPolicy>,
+}
+
+impl AppState {
+    pub fn new(registry: Arc<DataRegistry>) -> Self {
+        let cpu_job_slots = max_concurrent_cpu_jobs();
+        Self {
+            data: Arc::new(SharedRegistry::new(registry)),
+            cpu_jobs: Arc::new(Semaphore::new(cpu_job_slots)),
+            cpu_job_slots,
+            interactive_cpu_job: Arc::new(Semaphore::new(1)),
+            limits: Arc::new(RequestLimits::from_env()),
+            cors: cors::origins_from_env(),
+            assets: Arc::new(AssetProxy::from_env()),
+            settings: settings::current(),
+            admin_token: settings::admin_token_from_env(),
+            webhooks: Arc::new(WebhookPolicy::from_env()),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: RequestLimits) -> Self {
+        self.limits = Arc::new(limits);
+        self
+    }
+
+    pub fn with_cors(mut self, cors: Option<CorsOrigins>) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    pub fn with_assets(mut self, assets: AssetProxy) -> Self {
+        self.assets = Arc::new(assets);
+        self
+    }
+
+    pub fn with_admin_token(mut self, token: Option<String>) -> Self {
+        self.admin_token = token;
+        self
+    }
+
+    pub fn with_webhooks(mut self, webhooks: WebhookPolicy) -> Self {
+        self.webhooks = Arc::new(webhooks);
+        self
+    }
+
+    /// The registry requests should use; stays valid across a data reload.
+    pub fn registry(&self) -> Arc<DataRegistry> {
+        self.data.current()
+    }
+
+    /// A CPU job permit for work at `priority`; `None` once the semaphore is closed.
+    ///
+    /// [Priority::Interactive] work that finds every `cpu_jobs` slot taken while background work
+    /// is in flight takes the reserved interactive slot instead of queueing: the background job
+    /// holds its slot for the whole run but yields between batches, so waiting for the slot
+    /// would wait for the job to finish.
+    pub async fn cpu_permit(&self, priority: Priority) -> Option<OwnedSemaphorePermit> {
+        match Arc::clone(&self.cpu_jobs).try_acquire_owned() {
+            Ok(permit) => return Some(permit),
+            Err(TryAcquireError::Closed) => return None,
+            Err(TryAcquireError::NoPermits) => {}
+        }
+        let semaphore = if priority == Priority::Interactive && Priority::Background.is_in_flight()
+        {
+            &self.interactive_cpu_job
+        } else {
+            &self.cpu_jobs
+        };
+        Arc::clone(semaphore).acquire_owned().await.ok()
+    }
 }
 
 fn max_concurrent_cpu_jobs() -> usize {
@@ -68,6 +177,48 @@ fn ok_json(body: String) -> JsonResponse {
     JsonResponse { status: StatusCode::OK, body }
 }
 
+/// Whether the Accept header asks for MessagePack (`application/msgpack` or
+/// `application/x-msgpack`, not at `q=0`). Everything else gets JSON.
+fn accepts_msgpack(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let media = parts.next().unwrap_or_default().to_ascii_lowercase();
+            let refused = parts.any(|p| {
+                p.strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            });
+            (media == "application/msgpack" || media == "application/x-msgpack") && !refused
+        })
+}
+
+/// 200 with `body` as JSON, or re-encoded as MessagePack (same keys and values) when the
+/// client asked for it. Errors stay JSON either way.
+fn ok_negotiated(headers: &HeaderMap, body: String) -> Response {
+    let msgpack = accepts_msgpack(headers)
+        .then(|| serde_json::from_str::<serde_json::Value>(&body).ok())
+        .flatten()
+        .and_then(|value| rmp_serde::to_vec_named(&value).ok());
+    let mut response = match msgpack {
+        Some(bytes) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, HeaderValue::from_static("application/msgpack"))],
+            bytes,
+        )
+            .into_response(),
+        None => ok_json(body).into_response(),
+    };
+    response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
 fn error_json(status: StatusCode, message: &str) -> JsonResponse {
     let body = format!(
         "{{\n  \"status\": \"error\",\n  \"message\": {}\n}}",
@@ -95,30 +246,49 @@ fn validation_json(payload: api::ValidationErrorResponse) -> JsonResponse {
     }
 }
 
+/// 404 for a strict-mode request naming a ship or hostile the data does not have.
+fn unknown_ids_json(payload: api::UnknownIdsResponse) -> JsonResponse {
+    JsonResponse {
+        status: StatusCode::NOT_FOUND,
+        body: serde_json::to_string_pretty(&payload)
+            .unwrap_or_else(|_| error_json(StatusCode::NOT_FOUND, payload.message).body),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Router construction
 // ---------------------------------------------------------------------------
 
 pub fn build_router(registry: Arc<DataRegistry>) -> Router {
-    let state = AppState {
-        registry,
-        cpu_jobs: Arc::new(Semaphore::new(max_concurrent_cpu_jobs())),
-    };
+    router_with_state(AppState::new(registry))
+}
 
+/// [build_router] over an existing state, so `serve` can share its CPU semaphore with the
+/// schedule runner.
+pub fn router_with_state(state: AppState) -> Router {
+    let limits_state = state.clone();
+    let max_body_bytes = state.limits.max_body_bytes;
+    let cors_layer = state.cors.as_ref().map(cors::cors_layer);
     let api_routes = Router::new()
         // Health
         .route("/api/health", get(handle_health))
+        .route("/api/health/ready", get(handle_health_ready))
+        .route("/api/openapi.json", get(handle_openapi))
         // Officers
         .route("/api/officers", get(handle_officers))
         .route("/api/officers/import", post(handle_officers_import))
         .route("/api/officers/:id/resolved", get(handle_officer_resolved))
         // Ships / hostiles
         .route("/api/ships", get(handle_ships))
+        .route("/api/ships/:id", get(handle_ship))
         .route("/api/ships/:id/tiers-levels", get(handle_ship_tiers_levels))
         .route("/api/hostiles", get(handle_hostiles))
         // Data version
         .route("/api/data/version", get(handle_data_version))
+        .route("/api/data/updates", get(handle_data_updates))
+        .route("/api/data/updates/apply", post(handle_data_updates_apply))
         .route("/api/forbidden-tech", get(handle_forbidden_tech))
+        .route("/api/assets/*path", get(handle_asset))
         // Profile
         .route("/api/profile", get(handle_profile_get))
         .route("/api/profile", put(handle_profile_put))
@@ -138,22 +308,50 @@ pub fn build_router(registry: Arc<DataRegistry>) -> Router {
         .route("/api/presets", get(handle_presets_list))
         .route("/api/presets", post(handle_preset_post))
         .route("/api/presets/:id", get(handle_preset_get))
+        .route("/api/crewcode", post(handle_crew_code_encode))
+        .route("/api/crewcode/:code", get(handle_crew_code_decode))
         // Simulate (CPU-bound, blocking pool)
         .route("/api/simulate", post(handle_simulate))
+        .route("/api/simulate/trace", post(handle_simulate_trace))
+        .route("/api/simulate/scenario", post(handle_simulate_scenario))
+        .route("/api/simulate/matrix", post(handle_simulate_matrix))
+        .route("/ws/simulate", get(handle_ws_simulate))
+        // Analysis (CPU-bound, blocking pool)
+        .route("/api/analyze/officer-upgrades", post(handle_analyze_officer_upgrades))
+        .route("/api/analyze/officer-boosts", post(handle_analyze_officer_boosts))
+        .route("/api/analyze/officer-leaderboard", post(handle_analyze_officer_leaderboard))
+        .route("/api/analyze/stats", post(handle_analyze_stats))
+        .route("/api/analyze/sweep", post(handle_analyze_sweep))
+        .route("/api/analyze/bracket", post(handle_analyze_bracket))
         // Optimize synchronous (long-running, blocking pool)
         .route("/api/optimize", post(handle_optimize))
         // Heuristics seed list
         .route("/api/heuristics", get(handle_heuristics))
         // Optimize estimate (lightweight GET with query params)
         .route("/api/optimize/estimate", get(handle_optimize_estimate))
+        // Win rate over time of a scenario's top crews
+        .route("/api/optimize/history", get(handle_optimize_history))
         // Optimize async job
         .route("/api/optimize/start", post(handle_optimize_start))
         .route("/api/optimize/status/:job_id", get(handle_optimize_status))
         .route("/api/optimize/jobs/:job_id/stream", get(handle_optimize_job_stream))
         .route("/api/optimize/jobs/:job_id/cancel", post(handle_optimize_job_cancel))
+        // Scheduled optimizations
+        .route("/api/schedules", get(handle_schedules_list))
+        .route("/api/schedules", post(handle_schedule_create))
+        .route("/api/schedules/:id", get(handle_schedule_get))
+        .route("/api/schedules/:id", delete(handle_schedule_delete))
+        // Saved loadouts (officers committed to a ship; optimize `exclude_loadouts`)
+        .route("/api/loadouts", get(handle_loadouts_list))
+        .route("/api/loadouts", post(handle_loadout_create))
+        .route("/api/loadouts/:id", get(handle_loadout_get))
+        .route("/api/loadouts/:id", put(handle_loadout_update))
+        .route("/api/loadouts/:id", delete(handle_loadout_delete))
         // Sync ingress
         .route("/api/sync/status", get(handle_sync_status))
         .route("/api/sync/ingress", post(handle_sync_ingress))
+        .route("/api/admin/settings", get(handle_admin_settings_get))
+        .route("/api/admin/settings", patch(handle_admin_settings_patch))
         .with_state(state);
 
     // Wire the SPA or legacy console fallback depending on whether the dist
@@ -168,7 +366,7 @@ pub fn build_router(registry: Arc<DataRegistry>) -> Router {
     // When dist does not exist:
     //   - "/" serves the legacy API console HTML.
     //   - All other paths return 404.
-    match locate_dist_dir() {
+    let app = match locate_dist_dir() {
         Some(_dir) => {
             // Fallback handler: serve static files from dist; if the path doesn't
             // exist, serve index.html (200) so React Router deep-links work.
@@ -179,6 +377,66 @@ pub fn build_router(registry: Arc<DataRegistry>) -> Router {
             // everywhere else.
             api_routes.fallback(handle_no_spa_fallback)
         }
+    };
+    let app = app
+        .layer(DefaultBodyLimit::max(max_body_bytes))
+        .layer(middleware::from_fn_with_state(limits_state, enforce_request_limits));
+    // Outermost, so preflights are answered before routing and errors carry the headers too.
+    match cors_layer {
+        Some(layer) => app.layer(layer),
+        None => app,
+    }
+}
+
+/// Reads the whole body under the body read deadline and size cap, then runs the handler under
+/// the route's deadline ([RequestLimits::timeout_for]). Either deadline expiring answers 408.
+async fn enforce_request_limits(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limits = &state.limits;
+    let timeout = limits.timeout_for(request.uri().path());
+    let (parts, body) = request.into_parts();
+    let read = axum::body::to_bytes(body, limits.max_body_bytes);
+    let read = match limits.body_read {
+        Some(deadline) => match tokio::time::timeout(deadline, read).await {
+            Ok(read) => read,
+            Err(_) => {
+                return error_json(
+                    StatusCode::REQUEST_TIMEOUT,
+                    "Timed out reading the request body",
+                )
+                .into_response();
+            }
+        },
+        None => read.await,
+    };
+    let bytes = match read {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let too_large = std::error::Error::source(&e)
+                .is_some_and(|source| source.is::<LengthLimitError>());
+            return if too_large {
+                let message = format!(
+                    "Request body is larger than {} bytes",
+                    limits.max_body_bytes
+                );
+                error_json(StatusCode::PAYLOAD_TOO_LARGE, &message)
+            } else {
+                error_json(StatusCode::BAD_REQUEST, "Could not read the request body")
+            }
+            .into_response();
+        }
+    };
+    let request = Request::from_parts(parts, Body::from(bytes));
+    match timeout {
+        Some(deadline) => tokio::time::timeout(deadline, next.run(request))
+            .await
+            .unwrap_or_else(|_| {
+                error_json(StatusCode::REQUEST_TIMEOUT, "Request timed out").into_response()
+            }),
+        None => next.run(request).await,
     }
 }
 
@@ -278,8 +536,48 @@ async fn handle_no_spa_fallback(OriginalUri(uri): OriginalUri) -> Response {
 // API handler implementations
 // ---------------------------------------------------------------------------
 
-async fn handle_health() -> impl IntoResponse {
-    match api::health_payload() {
+/// CPU job slots and how many are taken, for the health report.
+fn cpu_job_usage(state: &AppState) -> (usize, usize) {
+    let slots = state.cpu_job_slots;
+    (slots, slots.saturating_sub(state.cpu_jobs.available_permits()))
+}
+
+/// GET /api/health — liveness: always 200 while the server answers, `status` "degraded" when a
+/// dataset is missing. Runs off the async runtime: the first call per registry hashes the data.
+async fn handle_health(State(state): State<AppState>) -> Response {
+    let registry = state.registry();
+    let (slots, running) = cpu_job_usage(&state);
+    match tokio::task::spawn_blocking(move || api::health_payload(&registry, slots, running)).await
+    {
+        Ok(Ok(body)) => ok_json(body).into_response(),
+        Ok(Err(e)) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
+/// GET /api/health/ready — readiness: the same report, 503 while degraded.
+async fn handle_health_ready(State(state): State<AppState>) -> Response {
+    let registry = state.registry();
+    let (slots, running) = cpu_job_usage(&state);
+    let report =
+        match tokio::task::spawn_blocking(move || api::health_report(&registry, slots, running))
+            .await
+        {
+            Ok(report) => report,
+            Err(e) => {
+                return error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+                    .into_response()
+            }
+        };
+    let status = if report.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    match serde_json::to_string_pretty(&report) {
+        Ok(body) => JsonResponse { status, body }.into_response(),
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
+async fn handle_openapi() -> impl IntoResponse {
+    match openapi::openapi_payload() {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
@@ -297,7 +595,7 @@ async fn handle_officers(
         "/api/officers".to_string()
     };
     let profile_id = profile_id_from_request(&headers, &params);
-    match api::officers_payload(state.registry.as_ref(), &path, profile_id.as_deref()) {
+    match api::officers_payload(state.registry().as_ref(), &path, profile_id.as_deref()) {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
@@ -315,15 +613,39 @@ async fn handle_ships(
         .unwrap_or(false);
     let profile_id = profile_id_from_request(&headers, &params);
     match api::ships_payload(
-        state.registry.as_ref(),
+        state.registry().as_ref(),
         owned_only,
         profile_id.as_deref(),
+        params.get("q").map(String::as_str).filter(|q| !q.trim().is_empty()),
     ) {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
 }
 
+async fn handle_ship(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let number = |key: &str| params.get(key).and_then(|v| v.trim().parse::<u32>().ok());
+    let profile_id = profile_id_from_request(&headers, &params);
+    match api::ship_detail_payload(
+        state.registry().as_ref(),
+        &id,
+        number("tier"),
+        number("level"),
+        profile_id.as_deref(),
+    ) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(api::ShipDetailError::NotFound) => {
+            error_json(StatusCode::NOT_FOUND, "Ship not found").into_response()
+        }
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
 async fn handle_ship_tiers_levels(Path(id): Path<String>) -> impl IntoResponse {
     match api::ship_tiers_levels_payload(&id) {
         Ok(body) => ok_json(body).into_response(),
@@ -331,8 +653,11 @@ async fn handle_ship_tiers_levels(Path(id): Path<String>) -> impl IntoResponse {
     }
 }
 
-async fn handle_hostiles(State(state): State<AppState>) -> impl IntoResponse {
-    match api::hostiles_payload(state.registry.as_ref()) {
+async fn handle_hostiles(
+    State(state): State<AppState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    match api::hostiles_payload(state.registry().as_ref(), params.get("q").map(String::as_str).filter(|q| !q.trim().is_empty())) {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
@@ -346,14 +671,85 @@ async fn handle_heuristics() -> impl IntoResponse {
 }
 
 async fn handle_data_version(State(state): State<AppState>) -> impl IntoResponse {
-    match api::data_version_payload(state.registry.as_ref()) {
+    match api::data_version_payload(state.registry().as_ref()) {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
 }
 
+fn data_update_error_response(error: data_updates::DataUpdateError) -> Response {
+    let status = match &error {
+        data_updates::DataUpdateError::Parse(_) => StatusCode::BAD_REQUEST,
+        data_updates::DataUpdateError::Remote(crate::data::remote::RemoteError::Disabled) => {
+            StatusCode::CONFLICT
+        }
+        data_updates::DataUpdateError::Remote(_) => StatusCode::BAD_GATEWAY,
+        data_updates::DataUpdateError::Reload(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    error_json(status, &error.to_string()).into_response()
+}
+
+/// GET /api/data/updates — installed vs mirrored data versions (fetches the mirror manifest).
+async fn handle_data_updates() -> impl IntoResponse {
+    match tokio::task::spawn_blocking(data_updates::data_updates_payload).await {
+        Ok(Ok(body)) => ok_json(body).into_response(),
+        Ok(Err(e)) => data_update_error_response(e),
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
+/// POST /api/data/updates/apply — swap in mirrored data and reload the registry. Needs the admin
+/// token (see [admin_denial]); only the read-only `GET /api/data/updates` is open.
+async fn handle_data_updates_apply(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if let Some(denied) = admin_denial(&state, &headers) {
+        return denied;
+    }
+    let data = state.data.clone();
+    match tokio::task::spawn_blocking(move || data_updates::apply_updates_payload(&data, &body))
+        .await
+    {
+        Ok(Ok(body)) => ok_json(body).into_response(),
+        Ok(Err(e)) => data_update_error_response(e),
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
+/// GET /api/assets/*path — officer/ship image from the asset cache, fetched from the CDN on a
+/// miss (see [crate::server::assets]).
+async fn handle_asset(State(state): State<AppState>, Path(path): Path<String>) -> Response {
+    let assets = state.assets.clone();
+    let asset = match tokio::task::spawn_blocking(move || assets.get(&path)).await {
+        Ok(Ok(asset)) => asset,
+        Ok(Err(e)) => {
+            let status = match e {
+                AssetError::InvalidPath => StatusCode::BAD_REQUEST,
+                AssetError::NotFound => StatusCode::NOT_FOUND,
+                AssetError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            };
+            return error_json(status, &e.to_string()).into_response();
+        }
+        Err(e) => {
+            return error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response()
+        }
+    };
+    let headers = [
+        (header::CONTENT_TYPE, HeaderValue::from_static(asset.content_type)),
+        (header::CACHE_CONTROL, HeaderValue::from_static("public, max-age=604800")),
+        (header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        (
+            header::HeaderName::from_static("x-asset-cache"),
+            HeaderValue::from_static(if asset.cached { "hit" } else { "miss" }),
+        ),
+    ];
+    (headers, asset.bytes).into_response()
+}
+
 async fn handle_forbidden_tech(State(state): State<AppState>) -> impl IntoResponse {
-    match api::forbidden_tech_catalog_payload(state.registry.as_ref()) {
+    match api::forbidden_tech_catalog_payload(state.registry().as_ref()) {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
@@ -378,6 +774,7 @@ async fn handle_profile_put(
     let profile_id = profile_id_from_request(&headers, &params);
     match api::profile_put_payload(&body, profile_id.as_deref()) {
         Ok(response) => ok_json(response).into_response(),
+        Err(api::ProfilePutError::Validation(v)) => validation_json(v).into_response(),
         Err(e) => error_json(StatusCode::BAD_REQUEST, &e.to_string()).into_response(),
     }
 }
@@ -399,7 +796,7 @@ async fn handle_profile_research_summary(
     Query(params): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
     let profile_id = profile_id_from_request(&headers, &params);
-    match api::profile_research_summary_payload(state.registry.as_ref(), profile_id.as_deref()) {
+    match api::profile_research_summary_payload(state.registry().as_ref(), profile_id.as_deref()) {
         Ok(body) => ok_json(body).into_response(),
         Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
     }
@@ -464,23 +861,58 @@ async fn handle_preset_post(
     }
 }
 
-async fn handle_officers_import(
+async fn handle_crew_code_encode(
     headers: HeaderMap,
     Query(params): Query<HashMap<String, String>>,
     body: String,
 ) -> impl IntoResponse {
     let profile_id = profile_id_from_request(&headers, &params);
-    match api::officers_import_payload(&body, profile_id.as_deref()) {
+    match api::crew_code_encode_payload(&body, profile_id.as_deref()) {
         Ok(response) => ok_json(response).into_response(),
         Err(e) => error_json(StatusCode::BAD_REQUEST, &e.to_string()).into_response(),
     }
 }
 
+async fn handle_crew_code_decode(
+    Path(code): Path<String>,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    match api::crew_code_decode_payload(&code, params.get("hostile").map(String::as_str)) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => error_json(StatusCode::BAD_REQUEST, &e.to_string()).into_response(),
+    }
+}
+
+async fn handle_officers_import(
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    let profile_id = profile_id_from_request(&headers, &params);
+    let result = tokio::task::spawn_blocking(move || {
+        api::officers_import_payload(&body, profile_id.as_deref())
+    })
+    .await;
+    match result {
+        Ok(Ok(response)) => ok_json(response).into_response(),
+        Ok(Err(api::ImportError::Sheet(e))) => {
+            let status = match e {
+                SheetsError::Disabled => StatusCode::FORBIDDEN,
+                SheetsError::InvalidUrl(_) => StatusCode::BAD_REQUEST,
+                SheetsError::Http(_) | SheetsError::NotCsv => StatusCode::BAD_GATEWAY,
+            };
+            error_json(status, &e.to_string()).into_response()
+        }
+        Ok(Err(e)) => error_json(StatusCode::BAD_REQUEST, &e.to_string()).into_response(),
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
 async fn handle_officer_resolved(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> impl IntoResponse {
-    match api::officer_resolved_payload(state.registry.as_ref(), &id) {
+    match api::officer_resolved_payload(state.registry().as_ref(), &id) {
         Ok(body) => ok_json(body).into_response(),
         Err(api::OfficerResolveError::NotFound) => {
             error_json(StatusCode::NOT_FOUND, "Officer not found").into_response()
@@ -496,6 +928,220 @@ async fn handle_simulate(
     Query(params): Query<HashMap<String, String>>,
     body: String,
 ) -> impl IntoResponse {
+    run_simulate(state, headers, params, body, Priority::Interactive, api::simulate_payload).await
+}
+
+/// POST /api/simulate/trace — one traced fight, a page of its filtered events; offloaded to
+/// blocking pool.
+async fn handle_simulate_trace(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_simulate(state, headers, params, body, Priority::Normal, api::simulate_trace_payload).await
+}
+
+/// POST /api/simulate/scenario — a YAML or JSON scenario file; offloaded to blocking pool.
+async fn handle_simulate_scenario(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    let payload = api::simulate_scenario_payload;
+    run_simulate(state, headers, params, body, Priority::Normal, payload).await
+}
+
+/// POST /api/simulate/matrix — every combination of a scenario file's matrix axes; offloaded to
+/// blocking pool.
+async fn handle_simulate_matrix(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    let payload = api::simulate_matrix_payload;
+    run_simulate(state, headers, params, body, Priority::Normal, payload).await
+}
+
+/// Shared body of the simulate handlers: CPU permit (see [AppState::cpu_permit]), blocking pool,
+/// error mapping.
+async fn run_simulate(
+    state: AppState,
+    headers: HeaderMap,
+    params: HashMap<String, String>,
+    body: String,
+    priority: Priority,
+    payload: fn(&DataRegistry, &str, Option<&str>) -> Result<String, api::SimulateError>,
+) -> Response {
+    let permit = match state.cpu_permit(priority).await {
+        Some(p) => p,
+        None => {
+            return error_json(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "CPU job semaphore closed",
+            )
+            .into_response();
+        }
+    };
+    let profile_id = profile_id_from_request(&headers, &params);
+    let registry = state.registry();
+    let settings = Arc::clone(&state.settings);
+    let result = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        settings.install(|| payload(registry.as_ref(), &body, profile_id.as_deref()))
+    }).await;
+    match result {
+        Ok(Ok(payload)) => ok_negotiated(&headers, payload),
+        Ok(Err(api::SimulateError::Parse(e))) => {
+            error_json(StatusCode::BAD_REQUEST, &format!("Invalid request body: {e}"))
+                .into_response()
+        }
+        Ok(Err(api::SimulateError::Validation(msg))) => {
+            error_json(StatusCode::BAD_REQUEST, &msg).into_response()
+        }
+        Ok(Err(api::SimulateError::UnknownIds(u))) => unknown_ids_json(u).into_response(),
+        Err(e) => error_json(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            &format!("Task panicked: {e}"),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /ws/simulate — WebSocket upgrade. The client sends one `/api/simulate` request body
+/// (optionally with a trace `filter`, see [api::TraceRequest]) as a text message; the server
+/// streams [api::SimulateStreamFrame]s for one traced fight (start, one frame per round,
+/// result) and closes the socket.
+async fn handle_ws_simulate(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let profile_id = profile_id_from_request(&headers, &params);
+    ws.on_upgrade(move |socket| stream_simulation(socket, state, profile_id))
+}
+
+async fn stream_simulation(mut socket: WebSocket, state: AppState, profile_id: Option<String>) {
+    let body = loop {
+        match socket.recv().await {
+            Some(Ok(Message::Text(text))) => break text,
+            Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+            Some(Ok(_)) => continue,
+        }
+    };
+    let Ok(permit) = Arc::clone(&state.cpu_jobs).acquire_owned().await else {
+        return;
+    };
+
+    // Bounded so a slow client applies back-pressure to the fight instead of buffering it.
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(16);
+    let registry = state.registry();
+    let settings = Arc::clone(&state.settings);
+    let job = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        let mut send = |frame: api::SimulateStreamFrame| {
+            let _ = tx.blocking_send(frame.to_json());
+        };
+        settings.install(|| {
+            api::simulate_stream(registry.as_ref(), &body, profile_id.as_deref(), &mut send)
+        })
+    });
+
+    while let Some(frame) = rx.recv().await {
+        if socket.send(Message::Text(frame)).await.is_err() {
+            break;
+        }
+    }
+    // Closing the receiver lets the fight finish without blocking if the client went away.
+    drop(rx);
+
+    let error = match job.await {
+        Ok(Ok(())) => None,
+        Ok(Err(api::SimulateError::Parse(e))) => Some(format!("Invalid request body: {e}")),
+        Ok(Err(api::SimulateError::Validation(msg))) => Some(msg),
+        Ok(Err(e @ api::SimulateError::UnknownIds(_))) => Some(e.to_string()),
+        Err(e) => Some(format!("Task panicked: {e}")),
+    };
+    if let Some(message) = error {
+        let frame = api::SimulateStreamFrame::Error { message };
+        let _ = socket.send(Message::Text(frame.to_json())).await;
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// POST /api/analyze/officer-upgrades — runs an optimization plus one re-simulation per officer;
+/// offloaded to blocking pool.
+async fn handle_analyze_officer_upgrades(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_analysis(state, headers, params, body, api::officer_upgrades_payload).await
+}
+
+/// POST /api/analyze/officer-boosts — each top crew once per officer; offloaded to blocking pool.
+async fn handle_analyze_officer_boosts(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_analysis(state, headers, params, body, api::officer_boosts_payload).await
+}
+
+/// POST /api/analyze/officer-leaderboard — one run per seating step of each sampled crew;
+/// offloaded to blocking pool.
+async fn handle_analyze_officer_leaderboard(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_analysis(state, headers, params, body, api::officer_leaderboard_payload).await
+}
+
+/// POST /api/analyze/stats — nine Monte Carlo runs of one crew; offloaded to blocking pool.
+async fn handle_analyze_stats(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_analysis(state, headers, params, body, api::stat_sensitivity_payload).await
+}
+
+/// POST /api/analyze/sweep — one Monte Carlo run per hostile level; offloaded to blocking pool.
+async fn handle_analyze_sweep(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_analysis(state, headers, params, body, api::sweep_payload).await
+}
+
+/// POST /api/analyze/bracket — one Monte Carlo run per preset; offloaded to blocking pool.
+async fn handle_analyze_bracket(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    run_analysis(state, headers, params, body, api::bracket_payload).await
+}
+
+/// Shared body of the `/api/analyze/*` handlers: CPU permit, blocking pool, error mapping.
+async fn run_analysis(
+    state: AppState,
+    headers: HeaderMap,
+    params: HashMap<String, String>,
+    body: String,
+    payload: fn(&DataRegistry, &str, Option<&str>) -> Result<String, api::AnalyzeError>,
+) -> Response {
     let permit = match Arc::clone(&state.cpu_jobs).acquire_owned().await {
         Ok(p) => p,
         Err(_) => {
@@ -507,20 +1153,22 @@ async fn handle_simulate(
         }
     };
     let profile_id = profile_id_from_request(&headers, &params);
-    let registry = state.registry.clone();
+    let registry = state.registry();
+    let settings = Arc::clone(&state.settings);
     let result = tokio::task::spawn_blocking(move || {
         let _permit = permit;
-        api::simulate_payload(registry.as_ref(), &body, profile_id.as_deref())
+        settings.install(|| payload(registry.as_ref(), &body, profile_id.as_deref()))
     }).await;
     match result {
         Ok(Ok(payload)) => ok_json(payload).into_response(),
-        Ok(Err(api::SimulateError::Parse(e))) => {
+        Ok(Err(api::AnalyzeError::Parse(e))) => {
             error_json(StatusCode::BAD_REQUEST, &format!("Invalid request body: {e}"))
                 .into_response()
         }
-        Ok(Err(api::SimulateError::Validation(msg))) => {
+        Ok(Err(api::AnalyzeError::Validation(msg))) => {
             error_json(StatusCode::BAD_REQUEST, &msg).into_response()
         }
+        Ok(Err(api::AnalyzeError::UnknownIds(u))) => unknown_ids_json(u).into_response(),
         Err(e) => error_json(
             StatusCode::INTERNAL_SERVER_ERROR,
             &format!("Task panicked: {e}"),
@@ -547,18 +1195,23 @@ async fn handle_optimize(
         }
     };
     let profile_id = profile_id_from_request(&headers, &params);
-    let registry = state.registry.clone();
+    let registry = state.registry();
+    let settings = Arc::clone(&state.settings);
+    let webhooks = Arc::clone(&state.webhooks);
     let result = tokio::task::spawn_blocking(move || {
         let _permit = permit;
-        api::optimize_payload(registry.as_ref(), &body, profile_id.as_deref())
+        settings.install(|| {
+            api::optimize_payload(registry.as_ref(), &body, profile_id.as_deref(), &webhooks)
+        })
     }).await;
     match result {
-        Ok(Ok(payload)) => ok_json(payload).into_response(),
+        Ok(Ok(payload)) => ok_negotiated(&headers, payload),
         Ok(Err(api::OptimizePayloadError::Parse(e))) => {
             error_json(StatusCode::BAD_REQUEST, &format!("Invalid request body: {e}"))
                 .into_response()
         }
         Ok(Err(api::OptimizePayloadError::Validation(v))) => validation_json(v).into_response(),
+        Ok(Err(api::OptimizePayloadError::UnknownIds(u))) => unknown_ids_json(u).into_response(),
         Err(e) => error_json(
             StatusCode::INTERNAL_SERVER_ERROR,
             &format!("Task panicked: {e}"),
@@ -580,12 +1233,26 @@ async fn handle_optimize_estimate(
         .collect::<Vec<_>>()
         .join("&");
     let path = format!("/api/optimize/estimate?{}", query);
-    match api::optimize_estimate_payload(state.registry.as_ref(), &path, profile_id.as_deref()) {
+    match api::optimize_estimate_payload(state.registry().as_ref(), &path, profile_id.as_deref()) {
         Ok(payload) => ok_json(payload).into_response(),
         Err(api::OptimizePayloadError::Parse(e)) => {
             error_json(StatusCode::BAD_REQUEST, &format!("Invalid request: {e}")).into_response()
         }
         Err(api::OptimizePayloadError::Validation(v)) => validation_json(v).into_response(),
+        Err(api::OptimizePayloadError::UnknownIds(u)) => unknown_ids_json(u).into_response(),
+    }
+}
+
+/// GET /api/optimize/history?ship=...&hostile=...&top=...
+async fn handle_optimize_history(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    let profile_id = profile_id_from_request(&headers, &params);
+    match history::optimize_history_payload(state.registry().as_ref(), &params, profile_id.as_deref()) {
+        Ok(payload) => ok_json(payload).into_response(),
+        Err(message) => error_json(StatusCode::BAD_REQUEST, &message).into_response(),
     }
 }
 
@@ -609,9 +1276,10 @@ async fn handle_optimize_start(
     let profile_id = profile_id_from_request(&headers, &params);
     match api::optimize_start_payload(
         permit,
-        state.registry.clone(),
+        state.registry(),
         &body,
         profile_id.as_deref(),
+        Arc::clone(&state.webhooks),
     ) {
         Ok(payload) => ok_json(payload).into_response(),
         Err(api::OptimizePayloadError::Parse(e)) => {
@@ -619,14 +1287,18 @@ async fn handle_optimize_start(
                 .into_response()
         }
         Err(api::OptimizePayloadError::Validation(v)) => validation_json(v).into_response(),
+        Err(api::OptimizePayloadError::UnknownIds(u)) => unknown_ids_json(u).into_response(),
     }
 }
 
 /// GET /api/optimize/status/:job_id
-async fn handle_optimize_status(Path(job_id): Path<String>) -> impl IntoResponse {
+async fn handle_optimize_status(
+    Path(job_id): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
     match api::get_job_status(&job_id) {
         Ok(response) => match serde_json::to_string_pretty(&response) {
-            Ok(payload) => ok_json(payload).into_response(),
+            Ok(payload) => ok_negotiated(&headers, payload),
             Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
         },
         Err(api::OptimizeStatusError::NotFound) => {
@@ -703,6 +1375,121 @@ async fn handle_optimize_job_cancel(Path(job_id): Path<String>) -> impl IntoResp
     }
 }
 
+/// Status and body for a schedule endpoint error.
+fn schedule_error_response(error: schedule::ScheduleError) -> Response {
+    match error {
+        schedule::ScheduleError::Parse(e) => {
+            error_json(StatusCode::BAD_REQUEST, &format!("Invalid request body: {e}"))
+                .into_response()
+        }
+        schedule::ScheduleError::Validation(v) => validation_json(v).into_response(),
+        schedule::ScheduleError::UnknownIds(u) => unknown_ids_json(u).into_response(),
+        schedule::ScheduleError::NotFound => {
+            error_json(StatusCode::NOT_FOUND, "Schedule not found").into_response()
+        }
+        schedule::ScheduleError::Io(e) => {
+            error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response()
+        }
+    }
+}
+
+async fn handle_schedules_list() -> impl IntoResponse {
+    match schedule::schedules_list_payload() {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => schedule_error_response(e),
+    }
+}
+
+/// POST /api/schedules — store an optimize request to re-run on an interval or on data changes.
+async fn handle_schedule_create(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<HashMap<String, String>>,
+    body: String,
+) -> impl IntoResponse {
+    let profile_id = profile_id_from_request(&headers, &params);
+    match schedule::schedule_create_payload(
+        state.registry().as_ref(),
+        &body,
+        profile_id.as_deref(),
+        &state.webhooks,
+    ) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => schedule_error_response(e),
+    }
+}
+
+async fn handle_schedule_get(Path(id): Path<String>) -> impl IntoResponse {
+    match schedule::schedule_get_payload(&id) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => schedule_error_response(e),
+    }
+}
+
+async fn handle_schedule_delete(Path(id): Path<String>) -> impl IntoResponse {
+    match schedule::schedule_delete_payload(&id) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => schedule_error_response(e),
+    }
+}
+
+/// Status and body for a loadout endpoint error.
+fn loadout_error_response(error: loadouts::LoadoutError) -> Response {
+    match error {
+        loadouts::LoadoutError::Parse(e) => {
+            error_json(StatusCode::BAD_REQUEST, &format!("Invalid request body: {e}"))
+                .into_response()
+        }
+        loadouts::LoadoutError::Validation(v) => validation_json(v).into_response(),
+        loadouts::LoadoutError::NotFound => {
+            error_json(StatusCode::NOT_FOUND, "Loadout not found").into_response()
+        }
+        loadouts::LoadoutError::Io(e) => {
+            error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response()
+        }
+    }
+}
+
+async fn handle_loadouts_list() -> impl IntoResponse {
+    match loadouts::loadouts_list_payload() {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => loadout_error_response(e),
+    }
+}
+
+/// POST /api/loadouts — save a named group of officers committed to a ship.
+async fn handle_loadout_create(State(state): State<AppState>, body: String) -> impl IntoResponse {
+    match loadouts::loadout_create_payload(state.registry().as_ref(), &body) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => loadout_error_response(e),
+    }
+}
+
+async fn handle_loadout_get(Path(id): Path<String>) -> impl IntoResponse {
+    match loadouts::loadout_get_payload(&id) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => loadout_error_response(e),
+    }
+}
+
+async fn handle_loadout_update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    body: String,
+) -> impl IntoResponse {
+    match loadouts::loadout_update_payload(state.registry().as_ref(), &id, &body) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => loadout_error_response(e),
+    }
+}
+
+async fn handle_loadout_delete(Path(id): Path<String>) -> impl IntoResponse {
+    match loadouts::loadout_delete_payload(&id) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => loadout_error_response(e),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Sync handlers
 // ---------------------------------------------------------------------------
@@ -721,6 +1508,69 @@ async fn handle_sync_ingress(headers: HeaderMap, body: String) -> impl IntoRespo
     JsonResponse { status, body: response_body }.into_response()
 }
 
+/// The response to send instead, unless the request carries the admin bearer token.
+fn admin_denial(state: &AppState, headers: &HeaderMap) -> Option<Response> {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Some(
+            error_json(
+                StatusCode::FORBIDDEN,
+                &format!("admin API disabled; set {}", settings::ADMIN_TOKEN_ENV),
+            )
+            .into_response(),
+        );
+    };
+    let given = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    match given {
+        Some(token) if settings::token_matches(expected, token) => None,
+        _ => {
+            tracing::warn!(status = 401, "admin request without a valid token");
+            Some(error_json(StatusCode::UNAUTHORIZED, "Invalid or missing admin token")
+                .into_response())
+        }
+    }
+}
+
+fn settings_json(settings: &settings::SettingsResponse) -> Response {
+    match serde_json::to_string_pretty(settings) {
+        Ok(body) => ok_json(body).into_response(),
+        Err(e) => error_json(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()).into_response(),
+    }
+}
+
+/// GET /api/admin/settings — current runtime settings (see [crate::server::settings]).
+async fn handle_admin_settings_get(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(denied) = admin_denial(&state, &headers) {
+        return denied;
+    }
+    settings_json(&state.settings.snapshot())
+}
+
+/// PATCH /api/admin/settings — change some settings; applies to requests started afterwards.
+async fn handle_admin_settings_patch(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    if let Some(denied) = admin_denial(&state, &headers) {
+        return denied;
+    }
+    let patch: SettingsPatch = match serde_json::from_str(&body) {
+        Ok(patch) => patch,
+        Err(e) => {
+            return error_json(StatusCode::BAD_REQUEST, &format!("Invalid request body: {e}"))
+                .into_response()
+        }
+    };
+    match state.settings.apply(&patch) {
+        Ok(settings) => settings_json(&settings),
+        Err(msg) => error_json(StatusCode::BAD_REQUEST, &msg).into_response(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Legacy API console HTML (served when no SPA build is present)
 // ---------------------------------------------------------------------------
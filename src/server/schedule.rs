@@ -0,0 +1,524 @@
+//! Scheduled optimizations: `POST /api/schedules` stores an optimize request in
+//! `data/schedules.json` and the server re-runs it on an interval and/or whenever the loaded game
+//! data changes. Each run appends the top crews to the schedule's history, so users can see how
+//! recommendations shift after a game update.
+//!
+//! [run_scheduler] wakes every [SCHEDULER_TICK] and runs due schedules one at a time, each holding
+//! a CPU permit like `/api/optimize`. "Data changed" compares the content hash of the static data
+//! files ([crate::optimizer::result_cache::static_data_fingerprint]) with the one recorded by the
+//! last run. `KOBAYASHI_SCHEDULES` relocates the file.
+
+use std::fs;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use super::api::{
+    check_seed_officers, resolve_known_ids, run_optimize, validate_request, OptimizePayloadError,
+    OptimizeRequest, UnknownIdsResponse, ValidationErrorResponse, ValidationIssue, DEFAULT_SIMS,
+};
+use super::history::CrewWinRate;
+use super::notify::{post_webhook, OptimizeWebhookSummary, WebhookPolicy};
+use crate::data::data_registry::{DataRegistry, SharedRegistry};
+use crate::optimizer::result_cache::static_data_fingerprint;
+
+pub const DEFAULT_SCHEDULES_PATH: &str = "data/schedules.json";
+/// Schedules kept in the file.
+pub const MAX_SCHEDULES: usize = 32;
+/// Runs kept per schedule; the oldest are dropped first.
+pub const MAX_SCHEDULE_HISTORY: usize = 50;
+/// Crews recorded per run.
+pub const SCHEDULE_TOP_CREWS: usize = 3;
+pub const MIN_SCHEDULE_INTERVAL_MINUTES: u32 = 15;
+/// Four weeks.
+pub const MAX_SCHEDULE_INTERVAL_MINUTES: u32 = 4 * 7 * 24 * 60;
+/// Longest accepted schedule name, in characters.
+pub const MAX_SCHEDULE_NAME_LEN: usize = 80;
+/// How often [run_scheduler] looks for due schedules.
+pub const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+/// Serializes read-modify-write of the schedules file between handlers and the scheduler.
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleRequest {
+    /// Label shown in lists. None = "<ship> vs <hostile>".
+    #[schemars(length(max = "MAX_SCHEDULE_NAME_LEN"))]
+    pub name: Option<String>,
+    /// Same body as `POST /api/optimize`; ship and hostile are stored as resolved ids.
+    pub optimize: OptimizeRequest,
+    /// Minutes between runs. None = only when the game data changes.
+    #[schemars(range(min = "MIN_SCHEDULE_INTERVAL_MINUTES", max = "MAX_SCHEDULE_INTERVAL_MINUTES"))]
+    pub interval_minutes: Option<u32>,
+    /// Re-run when the loaded game data changes. None = true.
+    pub on_data_change: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduleRun {
+    /// Unix seconds when the run finished.
+    pub at: u64,
+    /// Static data content hash the run was computed against.
+    pub data_version: String,
+    /// "done" or "error".
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub top_crews: Vec<CrewWinRate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub optimize: OptimizeRequest,
+    /// Profile whose roster and bonuses the runs use. None = the default profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_minutes: Option<u32>,
+    pub on_data_change: bool,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Oldest first, at most [MAX_SCHEDULE_HISTORY].
+    #[serde(default)]
+    pub history: Vec<ScheduleRun>,
+}
+
+impl Schedule {
+    pub fn last_run(&self) -> Option<&ScheduleRun> {
+        self.history.last()
+    }
+
+    /// Never run, the interval elapsed since the last run, or (with `on_data_change`) the last
+    /// run used different data.
+    pub fn is_due(&self, now: u64, data_version: &str) -> bool {
+        let Some(last) = self.last_run() else {
+            return true;
+        };
+        if self.on_data_change && last.data_version != data_version {
+            return true;
+        }
+        self.interval_minutes
+            .is_some_and(|minutes| now >= last.at.saturating_add(u64::from(minutes) * 60))
+    }
+
+    fn push_run(&mut self, run: ScheduleRun) {
+        self.history.push(run);
+        let excess = self.history.len().saturating_sub(MAX_SCHEDULE_HISTORY);
+        self.history.drain(..excess);
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ScheduleFile {
+    #[serde(default)]
+    schedules: Vec<Schedule>,
+}
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    Parse(serde_json::Error),
+    Validation(ValidationErrorResponse),
+    UnknownIds(UnknownIdsResponse),
+    NotFound,
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::Validation(_) => write!(f, "invalid schedule request"),
+            Self::UnknownIds(unknown) => write!(f, "{unknown}"),
+            Self::NotFound => write!(f, "Schedule not found"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+impl From<OptimizePayloadError> for ScheduleError {
+    fn from(e: OptimizePayloadError) -> Self {
+        match e {
+            OptimizePayloadError::Parse(e) => Self::Parse(e),
+            OptimizePayloadError::Validation(v) => Self::Validation(v),
+            OptimizePayloadError::UnknownIds(u) => Self::UnknownIds(u),
+        }
+    }
+}
+
+pub fn schedules_path() -> PathBuf {
+    std::env::var_os("KOBAYASHI_SCHEDULES")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SCHEDULES_PATH))
+}
+
+/// Schedules in `path`; a missing file means none.
+pub fn load_schedules(path: &Path) -> Result<Vec<Schedule>, ScheduleError> {
+    match fs::read_to_string(path) {
+        Ok(raw) => serde_json::from_str::<ScheduleFile>(&raw)
+            .map(|file| file.schedules)
+            .map_err(ScheduleError::Parse),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(ScheduleError::Io(e)),
+    }
+}
+
+/// Writes through a temp file so a crash never leaves a truncated schedules file.
+fn save_schedules(path: &Path, schedules: Vec<Schedule>) -> Result<(), ScheduleError> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(ScheduleError::Io)?;
+    }
+    let raw = serde_json::to_string_pretty(&ScheduleFile { schedules })
+        .map_err(ScheduleError::Parse)?;
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, raw).map_err(ScheduleError::Io)?;
+    fs::rename(&tmp, path).map_err(ScheduleError::Io)
+}
+
+/// Load, change and save the schedules file under [STORE_LOCK].
+fn update_schedules<T>(
+    change: impl FnOnce(&mut Vec<Schedule>) -> Result<T, ScheduleError>,
+) -> Result<T, ScheduleError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = schedules_path();
+    let mut schedules = load_schedules(&path)?;
+    let out = change(&mut schedules)?;
+    save_schedules(&path, schedules)?;
+    Ok(out)
+}
+
+fn read_schedules() -> Result<Vec<Schedule>, ScheduleError> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    load_schedules(&schedules_path())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Version tag recorded with each run: the static data content hash.
+pub fn current_data_version() -> String {
+    format!("{:016x}", static_data_fingerprint())
+}
+
+fn validation_error(errors: Vec<ValidationIssue>) -> ScheduleError {
+    ScheduleError::Validation(ValidationErrorResponse {
+        status: "error",
+        message: "Validation failed",
+        errors,
+    })
+}
+
+/// POST /api/schedules: validate the embedded optimize request like `/api/optimize`, store the
+/// schedule and return it. Its first run happens on the next scheduler tick.
+pub fn schedule_create_payload(
+    registry: &DataRegistry,
+    body: &str,
+    profile_id: Option<&str>,
+    webhooks: &WebhookPolicy,
+) -> Result<String, ScheduleError> {
+    let req: ScheduleRequest = serde_json::from_str(body).map_err(ScheduleError::Parse)?;
+    let mut optimize = req.optimize;
+    validate_request(&optimize, optimize.sims.unwrap_or(DEFAULT_SIMS), webhooks)?;
+
+    let mut errors = Vec::new();
+    let name = req.name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    if name
+        .as_ref()
+        .is_some_and(|n| n.chars().count() > MAX_SCHEDULE_NAME_LEN)
+    {
+        errors.push(ValidationIssue {
+            field: "name",
+            messages: vec![format!("must be at most {MAX_SCHEDULE_NAME_LEN} characters")],
+        });
+    }
+    if let Some(minutes) = req.interval_minutes {
+        if !(MIN_SCHEDULE_INTERVAL_MINUTES..=MAX_SCHEDULE_INTERVAL_MINUTES).contains(&minutes) {
+            errors.push(ValidationIssue {
+                field: "interval_minutes",
+                messages: vec![format!(
+                    "must be between {MIN_SCHEDULE_INTERVAL_MINUTES} and {MAX_SCHEDULE_INTERVAL_MINUTES}"
+                )],
+            });
+        }
+    }
+    let on_data_change = req.on_data_change.unwrap_or(true);
+    if req.interval_minutes.is_none() && !on_data_change {
+        errors.push(ValidationIssue {
+            field: "interval_minutes",
+            messages: vec!["required when on_data_change is false".to_string()],
+        });
+    }
+    if !errors.is_empty() {
+        return Err(validation_error(errors));
+    }
+
+    resolve_known_ids(
+        registry,
+        optimize.strict,
+        &mut optimize.ship,
+        optimize.ship_tier,
+        optimize.ship_level,
+        Some(&mut optimize.hostile),
+    )
+    .map_err(ScheduleError::UnknownIds)?;
+    check_seed_officers(registry, &optimize).map_err(ScheduleError::UnknownIds)?;
+
+    let schedule = Schedule {
+        id: format!("sched_{}", uuid::Uuid::new_v4().as_simple()),
+        name: name.unwrap_or_else(|| format!("{} vs {}", optimize.ship, optimize.hostile)),
+        optimize,
+        profile_id: profile_id.filter(|p| !p.is_empty()).map(str::to_string),
+        interval_minutes: req.interval_minutes,
+        on_data_change,
+        created_at: unix_now(),
+        history: Vec::new(),
+    };
+    let created = schedule.clone();
+    update_schedules(|schedules| {
+        if schedules.len() >= MAX_SCHEDULES {
+            return Err(validation_error(vec![ValidationIssue {
+                field: "schedules",
+                messages: vec![format!("at most {MAX_SCHEDULES} schedules; delete one first")],
+            }]));
+        }
+        schedules.push(schedule);
+        Ok(())
+    })?;
+    tracing::info!(schedule = %created.id, "schedule created");
+    serde_json::to_string_pretty(&created).map_err(ScheduleError::Parse)
+}
+
+/// GET /api/schedules
+pub fn schedules_list_payload() -> Result<String, ScheduleError> {
+    let schedules = read_schedules()?;
+    serde_json::to_string_pretty(&serde_json::json!({ "schedules": schedules }))
+        .map_err(ScheduleError::Parse)
+}
+
+/// GET /api/schedules/{id}
+pub fn schedule_get_payload(id: &str) -> Result<String, ScheduleError> {
+    let schedule = read_schedules()?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or(ScheduleError::NotFound)?;
+    serde_json::to_string_pretty(&schedule).map_err(ScheduleError::Parse)
+}
+
+/// DELETE /api/schedules/{id}
+pub fn schedule_delete_payload(id: &str) -> Result<String, ScheduleError> {
+    update_schedules(|schedules| {
+        let before = schedules.len();
+        schedules.retain(|s| s.id != id);
+        if schedules.len() == before {
+            return Err(ScheduleError::NotFound);
+        }
+        Ok(())
+    })?;
+    serde_json::to_string_pretty(&serde_json::json!({ "status": "ok" }))
+        .map_err(ScheduleError::Parse)
+}
+
+/// Ids of the schedules due at `now` against `data_version`.
+pub fn due_schedule_ids(now: u64, data_version: &str) -> Result<Vec<String>, ScheduleError> {
+    Ok(read_schedules()?
+        .into_iter()
+        .filter(|s| s.is_due(now, data_version))
+        .map(|s| s.id)
+        .collect())
+}
+
+/// Run schedule `id` once, append the outcome to its history and post its webhook under
+/// `webhooks`. Blocking and CPU-heavy; the caller holds a CPU permit. An optimizer panic is
+/// recorded as an error run.
+pub fn run_schedule(
+    registry: &DataRegistry,
+    id: &str,
+    webhooks: &WebhookPolicy,
+) -> Result<ScheduleRun, ScheduleError> {
+    let schedule = read_schedules()?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or(ScheduleError::NotFound)?;
+    let data_version = current_data_version();
+    tracing::info!(schedule = %schedule.id, name = %schedule.name, "scheduled optimize started");
+    let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        crate::server::settings::current().install(|| {
+            run_optimize(registry, &schedule.optimize, schedule.profile_id.as_deref())
+        })
+    }));
+    let (run, summary) = match outcome {
+        Ok(Ok(response)) => {
+            let run = ScheduleRun {
+                at: unix_now(),
+                data_version,
+                status: "done".to_string(),
+                duration_ms: response.duration_ms,
+                top_crews: response
+                    .recommendations
+                    .iter()
+                    .take(SCHEDULE_TOP_CREWS)
+                    .map(CrewWinRate::from)
+                    .collect(),
+                error: None,
+            };
+            let summary = OptimizeWebhookSummary::done(&schedule.id, &schedule.optimize, &response);
+            (run, summary)
+        }
+        Ok(Err(e)) => failed_run(&schedule, data_version, e.to_string()),
+        Err(_) => {
+            let error = "Internal error: optimizer panicked".to_string();
+            failed_run(&schedule, data_version, error)
+        }
+    };
+    tracing::info!(schedule = %schedule.id, status = %run.status, "scheduled optimize finished");
+
+    // The schedule may have been deleted while it ran; then the run is dropped.
+    let recorded = run.clone();
+    update_schedules(|schedules| {
+        let stored = schedules
+            .iter_mut()
+            .find(|s| s.id == id)
+            .ok_or(ScheduleError::NotFound)?;
+        stored.push_run(recorded);
+        Ok(())
+    })?;
+
+    if let Some(url) = &schedule.optimize.notify_webhook {
+        if let Err(error) = post_webhook(url, &summary, webhooks) {
+            tracing::warn!(%error, schedule = %schedule.id, "schedule webhook failed");
+        }
+    }
+    Ok(run)
+}
+
+fn failed_run(
+    schedule: &Schedule,
+    data_version: String,
+    error: String,
+) -> (ScheduleRun, OptimizeWebhookSummary) {
+    tracing::error!(schedule = %schedule.id, %error, "scheduled optimize failed");
+    let summary = OptimizeWebhookSummary::failed(&schedule.id, &schedule.optimize, &error);
+    let run = ScheduleRun {
+        at: unix_now(),
+        data_version,
+        status: "error".to_string(),
+        duration_ms: None,
+        top_crews: Vec::new(),
+        error: Some(error),
+    };
+    (run, summary)
+}
+
+/// Background loop started by `serve`: every [SCHEDULER_TICK], run each due schedule on the
+/// blocking pool while holding a permit from `cpu_jobs`.
+pub async fn run_scheduler(
+    data: Arc<SharedRegistry>,
+    cpu_jobs: Arc<Semaphore>,
+    webhooks: Arc<WebhookPolicy>,
+) {
+    let mut tick = tokio::time::interval(SCHEDULER_TICK);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tick.tick().await;
+        let due = tokio::task::spawn_blocking(|| {
+            due_schedule_ids(unix_now(), &current_data_version())
+        })
+        .await;
+        let ids = match due {
+            Ok(Ok(ids)) => ids,
+            Ok(Err(error)) => {
+                tracing::warn!(%error, "could not read schedules");
+                continue;
+            }
+            Err(_) => continue,
+        };
+        for id in ids {
+            let Ok(permit) = Arc::clone(&cpu_jobs).acquire_owned().await else {
+                return;
+            };
+            let registry = data.current();
+            let webhooks = Arc::clone(&webhooks);
+            let result = tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                run_schedule(registry.as_ref(), &id, &webhooks)
+            })
+            .await;
+            if let Ok(Err(error)) = result {
+                tracing::warn!(%error, "scheduled optimize not recorded");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(interval_minutes: Option<u32>, on_data_change: bool) -> Schedule {
+        let optimize: OptimizeRequest =
+            serde_json::from_str(r#"{"ship":"saladin","hostile":"swarm_33"}"#).unwrap();
+        Schedule {
+            id: "sched_test".to_string(),
+            name: "test".to_string(),
+            optimize,
+            profile_id: None,
+            interval_minutes,
+            on_data_change,
+            created_at: 0,
+            history: Vec::new(),
+        }
+    }
+
+    fn run_at(at: u64, data_version: &str) -> ScheduleRun {
+        ScheduleRun {
+            at,
+            data_version: data_version.to_string(),
+            status: "done".to_string(),
+            duration_ms: Some(1),
+            top_crews: Vec::new(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn due_when_never_run_interval_elapsed_or_data_changed() {
+        let mut interval = schedule(Some(60), false);
+        assert!(interval.is_due(0, "a"));
+        interval.push_run(run_at(1_000, "a"));
+        assert!(!interval.is_due(1_000 + 3_599, "a"));
+        assert!(interval.is_due(1_000 + 3_600, "a"));
+        assert!(!interval.is_due(1_001, "b"), "data changes ignored without on_data_change");
+
+        let mut on_change = schedule(None, true);
+        on_change.push_run(run_at(1_000, "a"));
+        assert!(!on_change.is_due(u64::MAX, "a"));
+        assert!(on_change.is_due(1_001, "b"));
+    }
+
+    #[test]
+    fn history_keeps_the_newest_runs() {
+        let mut s = schedule(Some(60), false);
+        for at in 0..(MAX_SCHEDULE_HISTORY as u64 + 5) {
+            s.push_run(run_at(at, "a"));
+        }
+        assert_eq!(s.history.len(), MAX_SCHEDULE_HISTORY);
+        assert_eq!(s.history[0].at, 5);
+        assert_eq!(s.last_run().map(|r| r.at), Some(MAX_SCHEDULE_HISTORY as u64 + 4));
+    }
+}
@@ -0,0 +1,259 @@
+//! Runtime knobs the server reads per request, tunable without a restart through
+//! `GET/PATCH /api/admin/settings`. That route needs `Authorization: Bearer <token>` matching
+//! `KOBAYASHI_ADMIN_TOKEN`; without the variable it is disabled (403).
+//!
+//! - `default_sims`: simulations per crew when a request omits `sims`/`num_sims`
+//!   (starts at [DEFAULT_SIMS]).
+//! - `max_candidates`: upper bound for `max_candidates` in optimize and analyze requests; may be
+//!   lowered below, never raised above, [MAX_CANDIDATES].
+//! - `simulation_threads`: Rayon threads for simulate, analyze and optimize work started after
+//!   the change; `0` uses the global pool (`KOBAYASHI_RAYON_THREADS`).
+//! - `memory_budget_mb`: budget for the candidates and results an optimize run buffers (see
+//!   [crate::optimizer::memory]); starts at `KOBAYASHI_MEMORY_BUDGET_MB` or its default.
+//! - `result_cache` / `optimize_history`: toggles for [crate::optimizer::result_cache] and
+//!   [crate::server::history]. Their environment variables, when set, take precedence.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::optimizer::memory::MemoryBudget;
+use crate::optimizer::result_cache;
+use crate::server::api::{DEFAULT_SIMS, MAX_CANDIDATES, MAX_SIMS};
+use crate::server::history;
+
+pub const ADMIN_TOKEN_ENV: &str = "KOBAYASHI_ADMIN_TOKEN";
+/// Largest `simulation_threads` accepted.
+pub const MAX_SIMULATION_THREADS: usize = 1024;
+/// Largest `memory_budget_mb` accepted (1 TiB).
+pub const MAX_MEMORY_BUDGET_MB: u64 = 1024 * 1024;
+
+static SETTINGS: OnceLock<Arc<RuntimeSettings>> = OnceLock::new();
+
+/// The process-wide settings; [crate::server::routes::AppState] holds the same `Arc`.
+pub fn current() -> Arc<RuntimeSettings> {
+    SETTINGS.get_or_init(|| Arc::new(RuntimeSettings::default())).clone()
+}
+
+/// `KOBAYASHI_ADMIN_TOKEN`, if set and non-blank.
+pub fn admin_token_from_env() -> Option<String> {
+    std::env::var(ADMIN_TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Byte comparison that does not stop at the first difference, so response timing does not
+/// reveal how much of a guessed token matched.
+pub fn token_matches(expected: &str, given: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), given.as_bytes());
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug)]
+pub struct RuntimeSettings {
+    default_sims: AtomicU32,
+    max_candidates: AtomicU32,
+    simulation_threads: AtomicUsize,
+    memory_budget_mb: AtomicU64,
+    /// Pool for `simulation_threads > 0`, rebuilt when the count changes.
+    pool: Mutex<Option<Arc<ThreadPool>>>,
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self {
+            default_sims: AtomicU32::new(DEFAULT_SIMS),
+            max_candidates: AtomicU32::new(MAX_CANDIDATES),
+            simulation_threads: AtomicUsize::new(0),
+            memory_budget_mb: AtomicU64::new(
+                MemoryBudget::from_env_or_default().bytes / (1024 * 1024),
+            ),
+            pool: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SettingsResponse {
+    pub default_sims: u32,
+    pub max_candidates: u32,
+    /// 0 = the global Rayon pool.
+    pub simulation_threads: usize,
+    /// Threads in the global Rayon pool (fixed at startup).
+    pub global_pool_threads: usize,
+    pub memory_budget_mb: u64,
+    pub result_cache: bool,
+    pub optimize_history: bool,
+}
+
+/// `PATCH /api/admin/settings` body; omitted fields keep their value.
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsPatch {
+    #[schemars(range(min = 1, max = "MAX_SIMS"))]
+    pub default_sims: Option<u32>,
+    #[schemars(range(min = 1, max = "MAX_CANDIDATES"))]
+    pub max_candidates: Option<u32>,
+    #[schemars(range(max = "MAX_SIMULATION_THREADS"))]
+    pub simulation_threads: Option<usize>,
+    #[schemars(range(min = 1, max = "MAX_MEMORY_BUDGET_MB"))]
+    pub memory_budget_mb: Option<u64>,
+    pub result_cache: Option<bool>,
+    pub optimize_history: Option<bool>,
+}
+
+impl RuntimeSettings {
+    pub fn default_sims(&self) -> u32 {
+        self.default_sims.load(Ordering::Relaxed)
+    }
+
+    pub fn max_candidates(&self) -> u32 {
+        self.max_candidates.load(Ordering::Relaxed)
+    }
+
+    pub fn simulation_threads(&self) -> usize {
+        self.simulation_threads.load(Ordering::Relaxed)
+    }
+
+    pub fn memory_budget(&self) -> MemoryBudget {
+        MemoryBudget::from_mb(self.memory_budget_mb.load(Ordering::Relaxed))
+    }
+
+    pub fn snapshot(&self) -> SettingsResponse {
+        SettingsResponse {
+            default_sims: self.default_sims(),
+            max_candidates: self.max_candidates(),
+            simulation_threads: self.simulation_threads(),
+            global_pool_threads: rayon::current_num_threads(),
+            memory_budget_mb: self.memory_budget_mb.load(Ordering::Relaxed),
+            result_cache: result_cache::is_enabled(),
+            optimize_history: history::is_enabled(),
+        }
+    }
+
+    /// Validates every field first, so a rejected patch changes nothing.
+    pub fn apply(&self, patch: &SettingsPatch) -> Result<SettingsResponse, String> {
+        if patch.default_sims.is_some_and(|n| n == 0 || n > MAX_SIMS) {
+            return Err(format!("default_sims must be between 1 and {MAX_SIMS}"));
+        }
+        if patch.max_candidates.is_some_and(|n| n == 0 || n > MAX_CANDIDATES) {
+            return Err(format!("max_candidates must be between 1 and {MAX_CANDIDATES}"));
+        }
+        if patch.simulation_threads.is_some_and(|n| n > MAX_SIMULATION_THREADS) {
+            return Err(format!("simulation_threads must be at most {MAX_SIMULATION_THREADS}"));
+        }
+        if patch.memory_budget_mb.is_some_and(|mb| mb == 0 || mb > MAX_MEMORY_BUDGET_MB) {
+            return Err(format!("memory_budget_mb must be between 1 and {MAX_MEMORY_BUDGET_MB}"));
+        }
+        if let Some(n) = patch.default_sims {
+            self.default_sims.store(n, Ordering::Relaxed);
+        }
+        if let Some(n) = patch.max_candidates {
+            self.max_candidates.store(n, Ordering::Relaxed);
+        }
+        if let Some(n) = patch.simulation_threads {
+            let mut pool = self.pool.lock().unwrap();
+            if self.simulation_threads.swap(n, Ordering::Relaxed) != n {
+                *pool = None;
+            }
+        }
+        if let Some(mb) = patch.memory_budget_mb {
+            self.memory_budget_mb.store(mb, Ordering::Relaxed);
+        }
+        if let Some(on) = patch.result_cache {
+            result_cache::set_enabled(on);
+        }
+        if let Some(on) = patch.optimize_history {
+            history::set_enabled(on);
+        }
+        let settings = self.snapshot();
+        tracing::info!(?settings, "runtime settings updated");
+        Ok(settings)
+    }
+
+    /// Runs `f` with `simulation_threads` Rayon threads (the global pool when 0). Work already
+    /// running keeps the pool it started on.
+    pub fn install<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R + Send,
+        R: Send,
+    {
+        let threads = self.simulation_threads();
+        if threads == 0 {
+            return f();
+        }
+        let pool = {
+            let mut cached = self.pool.lock().unwrap();
+            match cached.as_ref() {
+                Some(pool) if pool.current_num_threads() == threads => pool.clone(),
+                _ => {
+                    let built = ThreadPoolBuilder::new()
+                        .num_threads(threads)
+                        .thread_name(|i| format!("kobayashi-sim-{i}"))
+                        .build();
+                    match built {
+                        Ok(pool) => cached.insert(Arc::new(pool)).clone(),
+                        Err(e) => {
+                            tracing::warn!(threads, error = %e, "simulation pool not built");
+                            return f();
+                        }
+                    }
+                }
+            }
+        };
+        pool.install(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_validates_before_applying_and_sizes_the_pool() {
+        let settings = RuntimeSettings::default();
+        let rejected = SettingsPatch {
+            default_sims: Some(100),
+            max_candidates: Some(0),
+            ..SettingsPatch::default()
+        };
+        assert!(settings.apply(&rejected).unwrap_err().contains("max_candidates"));
+        assert_eq!(settings.default_sims(), DEFAULT_SIMS);
+
+        let patch = SettingsPatch {
+            default_sims: Some(100),
+            max_candidates: Some(500),
+            simulation_threads: Some(2),
+            ..SettingsPatch::default()
+        };
+        let applied = settings.apply(&patch).unwrap();
+        assert_eq!((applied.default_sims, applied.max_candidates), (100, 500));
+        assert_eq!(settings.install(rayon::current_num_threads), 2);
+
+        let back = SettingsPatch { simulation_threads: Some(0), ..SettingsPatch::default() };
+        settings.apply(&back).unwrap();
+        assert_eq!(settings.install(rayon::current_num_threads), rayon::current_num_threads());
+    }
+
+    #[test]
+    fn memory_budget_is_patchable() {
+        let settings = RuntimeSettings::default();
+        let zero = SettingsPatch { memory_budget_mb: Some(0), ..SettingsPatch::default() };
+        assert!(settings.apply(&zero).unwrap_err().contains("memory_budget_mb"));
+
+        let patch = SettingsPatch { memory_budget_mb: Some(64), ..SettingsPatch::default() };
+        assert_eq!(settings.apply(&patch).unwrap().memory_budget_mb, 64);
+        assert_eq!(settings.memory_budget(), MemoryBudget::from_mb(64));
+    }
+
+    #[test]
+    fn token_comparison_needs_an_exact_match() {
+        assert!(token_matches("s3cret", "s3cret"));
+        assert!(!token_matches("s3cret", "s3cre"));
+        assert!(!token_matches("s3cret", "s3creT"));
+    }
+}
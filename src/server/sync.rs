@@ -43,14 +43,17 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
     let body_len = body.len();
     let ts = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ");
     append_sync_log(&format!("{} POST /api/sync/ingress body_len={}", ts, body_len));
-    eprintln!("[sync] POST /api/sync/ingress received, body_len={}", body_len);
+    let span = tracing::info_span!("sync_ingress", body_len, profile = tracing::field::Empty);
+    let _entered = span.enter();
+    tracing::info!("POST /api/sync/ingress received");
 
     let index = load_profile_index();
     let profile_id = profile_id_by_sync_token(&index, sync_token.unwrap_or(""));
     let Some(ref pid) = profile_id else {
-        eprintln!("[sync] 401 Unauthorized (no profile for stfc-sync-token)");
+        tracing::warn!(status = 401, "no profile for stfc-sync-token");
         return json_error_response(StatusCode::UNAUTHORIZED, "Invalid or missing stfc-sync-token");
     };
+    span.record("profile", pid.as_str());
 
     let roster_path = profile_path(pid, ROSTER_IMPORTED).to_string_lossy().to_string();
     let research_path = profile_path(pid, RESEARCH_IMPORTED).to_string_lossy().to_string();
@@ -62,7 +65,7 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
     let payload: Vec<serde_json::Value> = match serde_json::from_str(body) {
         Ok(arr) => arr,
         Err(e) => {
-            eprintln!("[sync] 400 Bad Request: body is not a JSON array: {e}");
+            tracing::warn!(status = 400, error = %e, "body is not a JSON array");
             return json_error_response(
                 StatusCode::BAD_REQUEST,
                 &format!("Request body must be a JSON array: {e}"),
@@ -71,7 +74,7 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
     };
 
     if payload.is_empty() {
-        eprintln!("[sync] 200 OK accepted=[] (empty array)");
+        tracing::info!(status = 200, "accepted empty array");
         return ok_accepted_response(&[]);
     }
 
@@ -81,17 +84,17 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         .and_then(|v| v.as_str())
         .unwrap_or("unknown");
     let type_lower = type_str.to_ascii_lowercase();
-    eprintln!("[sync] type={type_str} count={}", payload.len());
+    tracing::info!(kind = type_str, count = payload.len(), "payload");
 
     let accepted = match type_lower.as_str() {
         "officer" => {
             match apply_officer_sync(&payload, DEFAULT_GAME_ID_MAP_PATH, &roster_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted officer({accepted_count})");
+                    tracing::info!(status = 200, kind = "officer", accepted = accepted_count, "accepted");
                     vec![format!("officer({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (officer): {e}");
+                    tracing::error!(status = 500, kind = "officer", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
@@ -99,11 +102,11 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         "research" => {
             match apply_research_sync(&payload, &research_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted research({accepted_count})");
+                    tracing::info!(status = 200, kind = "research", accepted = accepted_count, "accepted");
                     vec![format!("research({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (research): {e}");
+                    tracing::error!(status = 500, kind = "research", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
@@ -111,11 +114,11 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         "buildings" | "module" => {
             match apply_buildings_sync(&payload, &buildings_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted buildings({accepted_count})");
+                    tracing::info!(status = 200, kind = "buildings", accepted = accepted_count, "accepted");
                     vec![format!("buildings({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (buildings): {e}");
+                    tracing::error!(status = 500, kind = "buildings", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
@@ -123,11 +126,11 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         "ships" | "ship" => {
             match apply_ships_sync(&payload, &ships_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted ships({accepted_count})");
+                    tracing::info!(status = 200, kind = "ships", accepted = accepted_count, "accepted");
                     vec![format!("ships({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (ships): {e}");
+                    tracing::error!(status = 500, kind = "ships", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
@@ -135,11 +138,11 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         "ft" => {
             match apply_ft_sync(&payload, &ft_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted ft({accepted_count})");
+                    tracing::info!(status = 200, kind = "ft", accepted = accepted_count, "accepted");
                     vec![format!("ft({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (ft): {e}");
+                    tracing::error!(status = 500, kind = "ft", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
@@ -148,11 +151,11 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         "tech" => {
             match apply_ft_sync(&payload, &ft_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted tech({accepted_count}) -> forbidden_tech.imported.json");
+                    tracing::info!(status = 200, kind = "tech", accepted = accepted_count, "accepted into forbidden_tech.imported.json");
                     vec![format!("tech({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (tech/ft): {e}");
+                    tracing::error!(status = 500, kind = "tech/ft", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
@@ -161,21 +164,21 @@ pub fn ingress_payload(body: &str, sync_token: Option<&str>) -> (StatusCode, Str
         "buffs" | "expired_buffs" => {
             match apply_buffs_sync(&payload, &buffs_path) {
                 Ok(accepted_count) => {
-                    eprintln!("[sync] 200 OK accepted buffs({accepted_count})");
+                    tracing::info!(status = 200, kind = "buffs", accepted = accepted_count, "accepted");
                     vec![format!("buffs({accepted_count})")]
                 }
                 Err(e) => {
-                    eprintln!("[sync] 500 Internal Server Error (buffs): {e}");
+                    tracing::error!(status = 500, kind = "buffs", error = %e, "sync failed");
                     return json_error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string());
                 }
             }
         }
         "resources" | "missions" | "battlelogs" | "traits" | "slots" | "inventory" | "jobs" => {
-            eprintln!("[sync] 200 OK accepted {} (not persisted)", type_str);
+            tracing::info!(status = 200, kind = type_str, "accepted (not persisted)");
             vec![type_str.to_string()]
         }
         _ => {
-            eprintln!("[sync] 200 OK accepted {} (unknown type)", type_str);
+            tracing::warn!(status = 200, kind = type_str, "accepted unknown type (not persisted)");
             vec![type_str.to_string()]
         }
     };
@@ -232,6 +235,10 @@ fn apply_officer_sync(
             .level
             .and_then(|l| u16::try_from(l).ok());
         let tier = rank;
+        // The game payload has no boost state; keep what the player set on the roster.
+        let boosted = roster_map
+            .get(&canonical_id)
+            .is_some_and(|existing| existing.boosted);
 
         let entry = import::RosterEntry {
             canonical_officer_id: canonical_id,
@@ -239,6 +246,7 @@ fn apply_officer_sync(
             rank,
             tier,
             level,
+            boosted,
         };
         roster_map.insert(entry.canonical_officer_id.clone(), entry);
         accepted += 1;
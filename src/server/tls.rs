@@ -0,0 +1,133 @@
+//! Optional HTTPS for `serve`, so an instance shared over a network (e.g. alliance-hosted) does
+//! not send roster data and sync tokens in cleartext. Off unless both `KOBAYASHI_TLS_CERT` and
+//! `KOBAYASHI_TLS_KEY` are set to PEM files: the certificate chain (leaf first) and its private
+//! key (PKCS#8, PKCS#1 or SEC1). Plain HTTP stays the default for localhost use.
+//!
+//! Setting only one of the two, or pointing at unreadable or malformed files, fails startup
+//! rather than silently falling back to HTTP.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use tokio::net::TcpStream;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+pub const TLS_CERT_ENV: &str = "KOBAYASHI_TLS_CERT";
+pub const TLS_KEY_ENV: &str = "KOBAYASHI_TLS_KEY";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsPaths {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl TlsPaths {
+    /// `Ok(None)` when neither variable is set (plain HTTP).
+    pub fn from_env() -> io::Result<Option<Self>> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// [TlsPaths::from_env] over any key lookup. Blank values count as unset.
+    pub fn from_lookup(get: impl Fn(&str) -> Option<String>) -> io::Result<Option<Self>> {
+        let get = |key| get(key).filter(|v| !v.trim().is_empty());
+        match (get(TLS_CERT_ENV), get(TLS_KEY_ENV)) {
+            (None, None) => Ok(None),
+            (Some(cert), Some(key)) => Ok(Some(Self {
+                cert: PathBuf::from(cert.trim()),
+                key: PathBuf::from(key.trim()),
+            })),
+            (Some(_), None) | (None, Some(_)) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("TLS needs both {TLS_CERT_ENV} and {TLS_KEY_ENV}"),
+            )),
+        }
+    }
+}
+
+fn pem_error(path: &Path, what: &str, e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{what} {}: {e}", path.display()))
+}
+
+/// Reads the PEM files and builds the acceptor (ring provider, TLS 1.2 and 1.3, ALPN
+/// `http/1.1`).
+pub fn load_acceptor(paths: &TlsPaths) -> io::Result<TlsAcceptor> {
+    let certs = CertificateDer::pem_file_iter(&paths.cert)
+        .and_then(|iter| iter.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| pem_error(&paths.cert, "TLS certificate", e))?;
+    if certs.is_empty() {
+        return Err(pem_error(&paths.cert, "TLS certificate", "no certificates found"));
+    }
+    let key = PrivateKeyDer::from_pem_file(&paths.key)
+        .map_err(|e| pem_error(&paths.key, "TLS private key", e))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("TLS config: {e}")))?;
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// TLS handshake for one accepted connection. `deadline` (the header read timeout) bounds the
+/// handshake too, so a client cannot hold a connection open by stalling before its request.
+pub async fn accept(
+    acceptor: &TlsAcceptor,
+    stream: TcpStream,
+    deadline: Option<Duration>,
+) -> io::Result<TlsStream<TcpStream>> {
+    let handshake = acceptor.accept(stream);
+    match deadline {
+        Some(limit) => tokio::time::timeout(limit, handshake)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out"))?,
+        None => handshake.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_needs_both_paths_or_neither() {
+        let none = TlsPaths::from_lookup(|_| None).unwrap();
+        assert_eq!(none, None);
+
+        let both = TlsPaths::from_lookup(|key| match key {
+            TLS_CERT_ENV => Some(" /etc/kobayashi/cert.pem ".to_string()),
+            TLS_KEY_ENV => Some("/etc/kobayashi/key.pem".to_string()),
+            _ => None,
+        })
+        .unwrap()
+        .unwrap();
+        assert_eq!(both.cert, PathBuf::from("/etc/kobayashi/cert.pem"));
+
+        let cert_only =
+            TlsPaths::from_lookup(|key| (key == TLS_CERT_ENV).then(|| "cert.pem".to_string()));
+        assert!(cert_only.unwrap_err().to_string().contains(TLS_KEY_ENV));
+    }
+
+    #[test]
+    fn load_acceptor_reports_missing_and_malformed_files() {
+        let missing = TlsPaths {
+            cert: PathBuf::from("tests/fixtures/tls/missing.crt"),
+            key: PathBuf::from("tests/fixtures/tls/localhost.key"),
+        };
+        assert!(load_acceptor(&missing).is_err());
+
+        // A key where the certificate should be: no CERTIFICATE sections.
+        let swapped = TlsPaths {
+            cert: PathBuf::from("tests/fixtures/tls/localhost.key"),
+            key: PathBuf::from("tests/fixtures/tls/localhost.key"),
+        };
+        let err = load_acceptor(&swapped).err().expect("key file is not a certificate");
+        assert!(err.to_string().contains("no certificates"), "{err}");
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "server")]
+
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
@@ -23,6 +25,24 @@ fn unique_temp_txt_path(name: &str) -> PathBuf {
     std::env::temp_dir().join(format!("kobayashi-{name}-{stamp}.txt"))
 }
 
+/// Throwaway profile id for commands that persist into `profiles/<id>/`, so tests never rewrite
+/// the checked-in demo profile. Remove it with [remove_profile].
+fn unique_profile_id(name: &str) -> String {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    format!("test-{name}-{stamp}")
+}
+
+fn profile_dir(profile_id: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("profiles").join(profile_id)
+}
+
+fn remove_profile(profile_id: &str) {
+    let _ = fs::remove_dir_all(profile_dir(profile_id));
+}
+
 #[test]
 fn simulate_command_dispatches_and_emits_json() {
     let output = Command::new(bin())
@@ -34,11 +54,71 @@ fn simulate_command_dispatches_and_emits_json() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let payload: serde_json::Value =
         serde_json::from_str(&stdout).expect("simulate should emit json");
-    // Includes per-shot `stack_resolution` trace events (see combat engine).
-    assert_eq!(payload["events"].as_array().map(Vec::len), Some(18));
+    // Includes per-shot `stack_resolution` and defender `counter_*` trace events (see combat
+    // engine).
+    assert_eq!(payload["events"].as_array().map(Vec::len), Some(22));
     assert!(payload["total_damage"].is_number());
 }
 
+#[test]
+fn simulate_command_runs_a_scenario_file() {
+    let path = unique_temp_txt_path("scenario");
+    fs::write(
+        &path,
+        "ship: saladin\nhostile: '2918121098'\nstrict: false\ncrew:\n  captain: kirk-1323b6\n\
+         num_sims: 10\nseed: 4\n",
+    )
+    .unwrap();
+    let output = Command::new(bin())
+        .args(["simulate", "--scenario"])
+        .arg(&path)
+        .output()
+        .expect("simulate should run");
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    let payload: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["objective"], "offense");
+    assert_eq!(payload["stats"]["n"], 10);
+
+    let missing = Command::new(bin())
+        .args(["simulate", "--scenario", "no-such-scenario.yaml"])
+        .output()
+        .expect("simulate should run");
+    assert_eq!(missing.status.code(), Some(2));
+}
+
+#[test]
+fn matrix_command_writes_a_csv_table() {
+    let scenario = unique_temp_txt_path("matrix-scenario");
+    let out = unique_temp_txt_path("matrix-out").with_extension("csv");
+    fs::write(
+        &scenario,
+        "ship: saladin\nhostile: '2918121098'\nstrict: false\ncrew:\n  captain: kirk-1323b6\n\
+         num_sims: 5\nmatrix:\n  seed: [1, 2, 3]\n",
+    )
+    .unwrap();
+    let output = Command::new(bin())
+        .arg("matrix")
+        .arg(&scenario)
+        .arg("--out")
+        .arg(&out)
+        .output()
+        .expect("matrix should run");
+    let table = fs::read_to_string(&out).unwrap_or_default();
+    let _ = fs::remove_file(&scenario);
+    let _ = fs::remove_file(&out);
+
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 4, "{table}");
+    assert!(lines[0].starts_with("seed,ship,hostile,objective,score,win_rate"));
+    assert!(lines[3].starts_with("3,saladin,2918121098,offense,"));
+
+    let usage = Command::new(bin()).arg("matrix").output().expect("matrix should run");
+    assert_eq!(usage.status.code(), Some(2));
+}
+
 #[test]
 fn optimize_command_dispatches_and_emits_deterministic_json() {
     let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -127,6 +207,30 @@ fn import_command_returns_usage_without_path() {
     assert!(stderr.contains("usage: kobayashi import"));
 }
 
+#[test]
+fn officer_upgrades_command_returns_usage_without_scenario() {
+    let output = Command::new(bin())
+        .arg("officer-upgrades")
+        .output()
+        .expect("officer-upgrades should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("usage: kobayashi officer-upgrades"));
+}
+
+#[test]
+fn bracket_command_needs_two_presets() {
+    let output = Command::new(bin())
+        .args(["bracket", "swarm_32", "only_one"])
+        .output()
+        .expect("bracket should run");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("usage: kobayashi bracket"));
+}
+
 #[test]
 fn import_command_imports_json_file() {
     let path = unique_temp_path("import");
@@ -136,16 +240,21 @@ fn import_command_imports_json_file() {
     )
     .expect("fixture should be written");
 
+    let profile_id = unique_profile_id("import-json");
     let output = Command::new(bin())
-        .args(["import", path.to_string_lossy().as_ref()])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["import", path.to_string_lossy().as_ref(), "--profile", &profile_id])
         .output()
         .expect("import should run");
+    let persisted = profile_dir(&profile_id).join("roster.imported.json").exists();
+    remove_profile(&profile_id);
 
     assert_eq!(output.status.code(), Some(0));
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("import summary:"));
     assert!(stdout.contains("matched=2"));
     assert!(stdout.contains("import complete: persisted 2 canonical roster entries"));
+    assert!(persisted, "roster should be written to the requested profile");
 
     let _ = fs::remove_file(path);
 }
@@ -159,14 +268,17 @@ fn import_command_imports_txt_roster() {
     )
     .expect("roster fixture should be written");
 
-    let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let profile_id = unique_profile_id("import-txt");
     let output = Command::new(bin())
-        .current_dir(&crate_root)
-        .args(["import", path.to_string_lossy().as_ref()])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["import", path.to_string_lossy().as_ref(), "--profile", &profile_id])
         .output()
         .expect("import should run");
+    let persisted = profile_dir(&profile_id).join("roster.imported.json").exists();
+    remove_profile(&profile_id);
 
     assert_eq!(output.status.code(), Some(0));
+    assert!(persisted, "roster should be written to the requested profile");
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains("import summary:"));
     assert!(stdout.contains("import complete:"));
@@ -191,3 +303,237 @@ fn validate_command_returns_non_zero_on_invalid_data() {
 
     let _ = fs::remove_file(path);
 }
+
+#[test]
+fn diff_rankings_command_compares_saved_recommendations() {
+    let crew = |captain: &str, win_rate: f64| {
+        serde_json::json!({
+            "captain": captain, "bridge": ["Spock"], "below_decks": [],
+            "win_rate": win_rate, "stall_rate": 0.0, "loss_rate": 1.0 - win_rate,
+            "avg_hull_remaining": 0.5
+        })
+    };
+    let before = unique_temp_path("diff-before");
+    let after = unique_temp_path("diff-after");
+    fs::write(&before, serde_json::json!([crew("Kirk", 0.9), crew("Pike", 0.8)]).to_string())
+        .unwrap();
+    fs::write(
+        &after,
+        serde_json::json!({ "recommendations": [crew("Pike", 0.95), crew("Kirk", 0.9)] }).to_string(),
+    )
+    .unwrap();
+
+    let output = Command::new(bin())
+        .arg("diff-rankings")
+        .arg(&before)
+        .arg(&after)
+        .output()
+        .expect("diff-rankings should run");
+    let _ = fs::remove_file(&before);
+    let _ = fs::remove_file(&after);
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("top crew changed; 0 unchanged, 2 changed"));
+    assert!(stdout.contains("up\t2\t1\t0.8000\t0.9500"));
+    assert!(stdout.contains("down\t1\t2\t"));
+}
+
+#[test]
+fn snapshot_run_command_replays_a_saved_fight() {
+    use kobayashi::combat::{Combatant, CrewConfiguration, SimulationConfig, SimulationSnapshot};
+
+    let attacker = Combatant::builder("player").attack(90.0).hull_health(500.0).build().unwrap();
+    let defender = Combatant::builder("hostile")
+        .attack(15.0)
+        .mitigation(0.2)
+        .hull_health(400.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder().rounds(4).seed(9).build();
+    let snapshot =
+        SimulationSnapshot::new(attacker, defender, CrewConfiguration::default(), config);
+    let path = unique_temp_path("snapshot");
+    fs::write(&path, snapshot.to_json_pretty().unwrap()).unwrap();
+
+    let output = Command::new(bin())
+        .args(["snapshot", "run"])
+        .arg(&path)
+        .output()
+        .expect("snapshot should run");
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(output.status.code(), Some(0));
+    let payload: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["total_damage"].as_f64(), Some(snapshot.run().total_damage));
+
+    let usage = Command::new(bin()).arg("snapshot").output().expect("snapshot should run");
+    assert_eq!(usage.status.code(), Some(2));
+}
+
+#[test]
+fn golden_command_generates_and_verifies_matrix() {
+    let path = unique_temp_path("golden");
+    let generate = Command::new(bin())
+        .args(["golden", "generate", "--out"])
+        .arg(&path)
+        .output()
+        .expect("golden generate should run");
+    assert_eq!(generate.status.code(), Some(0));
+
+    let verify = Command::new(bin())
+        .args(["golden", "verify"])
+        .arg(&path)
+        .output()
+        .expect("golden verify should run");
+    assert_eq!(verify.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&verify.stdout).contains("0 mismatched"));
+
+    let mut matrix: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    matrix["cases"][0]["outputs"]["mitigation"] = serde_json::json!(0.25);
+    fs::write(&path, matrix.to_string()).unwrap();
+    let drifted = Command::new(bin())
+        .args(["golden", "verify"])
+        .arg(&path)
+        .output()
+        .expect("golden verify should run");
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(drifted.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&drifted.stdout);
+    assert!(stdout.contains("mitigation/survey/reference\tmitigation\t0.25\t"));
+}
+
+#[test]
+fn fuzz_command_checks_invariants_and_rejects_bad_flags() {
+    let out_dir = unique_temp_path("fuzz");
+    let output = Command::new(bin())
+        .args(["fuzz", "--cases", "40", "--seed", "7", "--out-dir"])
+        .arg(&out_dir)
+        .output()
+        .expect("fuzz should run");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("40 cases from seed 7, 0 failed"));
+    assert!(!out_dir.exists(), "no snapshots are written when every case passes");
+
+    let bad = Command::new(bin())
+        .args(["fuzz", "--cases", "0"])
+        .output()
+        .expect("fuzz should run");
+    assert_eq!(bad.status.code(), Some(2));
+}
+
+#[test]
+fn parity_command_scores_a_log_and_names_the_diverging_subsystem() {
+    use kobayashi::combat::{
+        Combatant, CrewConfiguration, SimulationConfig, SimulationSnapshot, TraceMode,
+    };
+
+    let attacker = Combatant::builder("player").attack(90.0).hull_health(500.0).build().unwrap();
+    let defender = Combatant::builder("hostile")
+        .attack(15.0)
+        .mitigation(0.2)
+        .hull_health(400.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder().rounds(4).seed(9).build();
+    let mut snapshot =
+        SimulationSnapshot::new(attacker, defender, CrewConfiguration::default(), config);
+    let scenario = unique_temp_path("parity-scenario");
+    fs::write(&scenario, snapshot.to_json_pretty().unwrap()).unwrap();
+
+    // The traced replay is itself a log in the ingested format (extra keys are ignored).
+    snapshot.config.trace_mode = TraceMode::Events;
+    let mut log = serde_json::to_value(snapshot.run()).unwrap();
+    let log_path = unique_temp_path("parity-log");
+    fs::write(&log_path, log.to_string()).unwrap();
+    let run = |log_path: &PathBuf| {
+        Command::new(bin())
+            .arg("parity")
+            .arg(log_path)
+            .arg("--scenario")
+            .arg(&scenario)
+            .args(["--sims", "1"])
+            .output()
+            .expect("parity should run")
+    };
+    let agree = run(&log_path);
+    assert_eq!(agree.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&agree.stdout);
+    assert!(stdout.contains("mitigation\tagree\t0.2000\t0.2000\t1.000\t"));
+    assert!(stdout.contains("0 mechanics diverge"));
+
+    for event in log["events"].as_array_mut().unwrap() {
+        if event["event_type"] == "mitigation_calc" {
+            event["values"]["mitigation"] = serde_json::json!(0.5);
+        }
+    }
+    fs::write(&log_path, log.to_string()).unwrap();
+    let diverged = run(&log_path);
+    let _ = fs::remove_file(&log_path);
+    let _ = fs::remove_file(&scenario);
+
+    assert_eq!(diverged.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&diverged.stdout).contains("mitigation\tDIVERGES\t0.5000"));
+    assert!(String::from_utf8_lossy(&diverged.stderr).contains("check src/combat/mitigation.rs"));
+}
+
+#[test]
+fn import_fight_command_adds_the_enemy_to_a_hostile_index() {
+    let sample = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("fight samples")
+        .join("realta vs takret militia 10.csv");
+    let data_dir = unique_temp_path("import-fight");
+    let output = Command::new(bin())
+        .arg("import-fight")
+        .arg(&sample)
+        .args(["--id", "takret_recorded", "--data-dir"])
+        .arg(&data_dir)
+        .output()
+        .expect("import-fight should run");
+    let index = fs::read_to_string(data_dir.join("index.json"));
+    let record = fs::read_to_string(data_dir.join("takret_recorded.json"));
+    let _ = fs::remove_dir_all(&data_dir);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("imported hostile 'takret_recorded'"));
+    assert!(index.expect("index written").contains("\"takret_recorded\""));
+    let record: serde_json::Value = serde_json::from_str(&record.expect("record written")).unwrap();
+    assert_eq!(record["hull_health"].as_f64(), Some(470.0));
+
+    let usage = Command::new(bin()).arg("import-fight").output().expect("import-fight should run");
+    assert_eq!(usage.status.code(), Some(2));
+}
+
+#[test]
+fn validate_mechanics_checks_officer_buffs_for_monotonicity() {
+    let crate_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let canonical: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(crate_root.join("data/officers/officers.canonical.json")).unwrap(),
+    )
+    .unwrap();
+    let mut subset = canonical.clone();
+    subset["officers"] = serde_json::Value::Array(
+        canonical["officers"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|o| o["id"].as_str().is_some_and(|id| id.starts_with("kirk-")))
+            .cloned()
+            .collect(),
+    );
+    let path = unique_temp_path("mechanics-officers");
+    fs::write(&path, subset.to_string()).expect("fixture should be written");
+
+    let output = Command::new(bin())
+        .args(["validate", path.to_string_lossy().as_ref(), "--mechanics"])
+        .output()
+        .expect("validate should run");
+    let _ = fs::remove_file(&path);
+
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stdout));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("errors=0"));
+    assert!(stdout.contains("- mechanics: 1 officer ability rows checked for buff monotonicity"));
+}
@@ -2,7 +2,7 @@
 
 use kobayashi::combat::{
     simulate_combat, Ability, AbilityClass, AbilityEffect, Combatant, CrewConfiguration, CrewSeat,
-    CrewSeatContext, SimulationConfig, TimingWindow, TraceMode, WeaponStats,
+    CrewSeatContext, RngMode, SimulationConfig, TimingWindow,
     NO_EXPLICIT_CONTRIBUTION_BATCH,
 };
 use kobayashi::combat::{
@@ -18,49 +18,20 @@ fn approx_eq(a: f64, b: f64, tol: f64) {
 /// CombatBegin shred does (see `combat_tests::officer_apex_shred_bonus_at_combat_begin_increases_damage_through_barrier`).
 #[test]
 fn round_end_apex_shred_does_not_affect_same_round_weapon_damage() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 10_000.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(200.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .apex_barrier(10_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let baseline = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
 
@@ -74,6 +45,8 @@ fn round_end_apex_shred_does_not_affect_same_round_weapon_damage() {
                 boostable: false,
                 effect: AbilityEffect::ApexShredBonus(0.15),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -91,6 +64,8 @@ fn round_end_apex_shred_does_not_affect_same_round_weapon_damage() {
                 boostable: false,
                 effect: AbilityEffect::ApexShredBonus(0.15),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -113,55 +88,28 @@ fn round_end_apex_shred_does_not_affect_same_round_weapon_damage() {
 /// Hostile return fire uses the same damage-through, isolytic, apex, and shield-split helpers as outbound shots.
 #[test]
 fn defender_counter_attack_matches_helper_pipeline() {
-    let attacker = Combatant {
-        id: "player".to_string(),
-        attack: 1.0,
-        mitigation: 0.1,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 10_000.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.5,
-        weapons: vec![WeaponStats {
-            attack: 1.0,
-            shots: Some(1),
-        }],
-    };
-    let defender = Combatant {
-        id: "hostile".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.05,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.2,
-        isolytic_damage: 0.1,
-        isolytic_defense: 0.0,
-        weapons: vec![WeaponStats {
-            attack: 200.0,
-            shots: Some(1),
-        }],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 1,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("player")
+        .attack(1.0)
+        .mitigation(0.1)
+        .hull_health(10_000.0)
+        .apex_barrier(10_000.0)
+        .isolytic_defense(0.5)
+        .weapon(1.0, Some(1))
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("hostile")
+        .pierce(0.05)
+        .hull_health(10_000.0)
+        .apex_shred(0.2)
+        .isolytic_damage(0.1)
+        .weapon(200.0, Some(1))
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(1)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
 
     let w = 200.0;
@@ -1,11 +1,13 @@
 use kobayashi::combat::{
-    aggregate_contributions, apply_morale_primary_piercing, component_mitigation, isolytic_damage,
+    aggregate_contributions, apply_morale_primary_piercing, component_mitigation,
     mitigation, mitigation_with_morale, pierce_damage_through_bonus, round_half_even,
-    serialize_events_json, simulate_combat, Ability, AbilityClass, AbilityEffect, AttackerStats,
-    CombatEvent, Combatant, CrewConfiguration, CrewSeat, CrewSeatContext, DefenderStats, EventSource,
-    ShipType, SimulationConfig, StackContribution, StatStacking, TimingWindow, TraceCollector,
-    TraceMode, WeaponStats, EPSILON, PIERCE_CAP, NO_EXPLICIT_CONTRIBUTION_BATCH,
+    serialize_events_json, serialize_events_msgpack, simulate_combat, simulate_combat_streaming, simulate_combat_with_effects, simulate_combat_with_scratch, simulate_waves, Ability, AbilityClass, AbilityEffect, AttackerStartState, AttackerStats,
+    CombatEvent, CombatScratch, Combatant, EventPayload, CrewConfiguration, CrewEffects, GoldenMatrix, InitiativeMode, CrewSeat, CrewSeatContext, DefenderStats, DotChannel, EventSource,
+    RngMode, ShipType, SimulationConfig, StackContribution, StatStacking, TimingWindow,
+    TraceMode, WeaponStats, EPSILON, PIERCE_CAP, NO_EXPLICIT_CONTRIBUTION_BATCH, TRACE_SCHEMA_VERSION,
+    verify_golden_matrix,
 };
+use kobayashi::optimizer::ranking::ScenarioType;
 use serde_json::{Map, Value};
 
 fn approx_eq(a: f64, b: f64, tol: f64) {
@@ -82,44 +84,32 @@ fn mitigation_output_is_bounded_for_extreme_inputs() {
     );
 }
 
+/// tests/fixtures/golden/golden_values.json (regenerate with `kobayashi golden generate --out`)
+/// holds the Python reference mitigation values, the isolytic reference formula and the
+/// community apex formula alongside engine fights.
 #[test]
-fn golden_values_match_python_reference_for_each_ship_type() {
-    let defender = DefenderStats {
-        armor: 100.0,
-        shield_deflection: 80.0,
-        dodge: 60.0,
-    };
-    let attacker = AttackerStats {
-        armor_piercing: 50.0,
-        shield_piercing: 40.0,
-        accuracy: 30.0,
-    };
-
-    approx_eq(
-        mitigation(defender, attacker, ShipType::Survey),
-        0.5489034243492552,
-        1e-12,
-    );
-    approx_eq(
-        mitigation(defender, attacker, ShipType::Armada),
-        0.5489034243492552,
-        1e-12,
-    );
-    approx_eq(
-        mitigation(defender, attacker, ShipType::Battleship),
-        0.5914393181871193,
-        1e-12,
-    );
-    approx_eq(
-        mitigation(defender, attacker, ShipType::Explorer),
-        0.5914393181871193,
-        1e-12,
-    );
-    approx_eq(
-        mitigation(defender, attacker, ShipType::Interceptor),
-        0.5914393181871193,
-        1e-12,
-    );
+fn golden_values_fixture_matches_this_build() {
+    let raw = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/golden/golden_values.json"
+    ))
+    .expect("golden fixture should exist");
+    let matrix: GoldenMatrix = serde_json::from_str(&raw).expect("golden fixture should parse");
+    let report = verify_golden_matrix(&matrix, 1e-12);
+    assert!(report.passed(), "golden mismatches: {:?}", report);
+
+    let output = |id: &str, key: &str| {
+        let case = matrix.cases.iter().find(|c| c.id == id).expect(id);
+        case.outputs[key]
+    };
+    approx_eq(output("mitigation/survey/reference", "mitigation"), 0.5489034243492552, 1e-12);
+    approx_eq(output("mitigation/armada/reference", "mitigation"), 0.5489034243492552, 1e-12);
+    for ship in ["battleship", "explorer", "interceptor"] {
+        let id = format!("mitigation/{ship}/reference");
+        approx_eq(output(&id, "mitigation"), 0.5914393181871193, 1e-12);
+    }
+    approx_eq(output("isolytic/reference", "isolytic_damage"), 8_200.0, 1e-12);
+    approx_eq(output("apex/shred_100_barrier_10k", "apex_damage_factor"), 2.0 / 3.0, 1e-12);
 }
 
 #[test]
@@ -229,29 +219,6 @@ fn mitigation_with_morale_applies_primary_piercing_bonus_when_active() {
     approx_eq(morale, 0.5869213146636679, 1e-12);
 }
 
-#[test]
-fn trace_collector_records_only_when_enabled() {
-    let event = CombatEvent {
-        event_type: "round_start".to_string(),
-        round_index: 1,
-        phase: "round".to_string(),
-        source: EventSource {
-            ship_ability_id: Some("baseline_round".to_string()),
-            ..EventSource::default()
-        },
-        values: Map::new(),
-        weapon_index: None,
-    };
-
-    let mut trace_on = TraceCollector::new(true);
-    trace_on.record(event.clone());
-    assert_eq!(trace_on.events().len(), 1);
-
-    let mut trace_off = TraceCollector::new(false);
-    trace_off.record(event);
-    assert!(trace_off.events().is_empty());
-}
-
 #[test]
 fn serialize_events_json_matches_python_shape() {
     let json = serialize_events_json(&[CombatEvent {
@@ -264,6 +231,7 @@ fn serialize_events_json_matches_python_shape() {
         },
         values: Map::from_iter([("roll".to_string(), Value::from(0.617753))]),
         weapon_index: None,
+        schema_version: TRACE_SCHEMA_VERSION,
     }])
     .expect("serialization should succeed");
 
@@ -276,73 +244,119 @@ fn serialize_events_json_matches_python_shape() {
         serde_json::json!({"officer_id": "nero"})
     );
     assert_eq!(parsed[0]["values"], serde_json::json!({"roll": 0.617753}));
+    assert_eq!(parsed[0]["schema_version"], TRACE_SCHEMA_VERSION);
+}
+
+#[test]
+fn traced_events_carry_the_schema_version_and_parse_into_typed_payloads() {
+    let attacker = Combatant::builder("enterprise")
+        .attack(150.0)
+        .pierce(0.2)
+        .crit_chance(0.5)
+        .crit_multiplier(1.5)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .mitigation(0.3)
+        .hull_health(2000.0)
+        .shield_health(300.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder().rounds(3).seed(5).trace(true).build();
+    let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
+
+    assert!(result.events.iter().all(|e| e.schema_version == TRACE_SCHEMA_VERSION));
+    let mut typed = 0;
+    for event in &result.events {
+        match (event.event_type.as_str(), event.payload()) {
+            ("attack_roll", EventPayload::AttackRoll(roll)) => {
+                assert_eq!(roll.base_attack, 150.0);
+                typed += 1;
+            }
+            ("mitigation_calc", EventPayload::MitigationCalc(calc)) => {
+                approx_eq(calc.multiplier, 0.7, 1e-9);
+                typed += 1;
+            }
+            ("pierce_calc", EventPayload::PierceCalc(_))
+            | ("crit_resolution", EventPayload::CritResolution(_))
+            | ("damage_application", EventPayload::DamageApplication(_))
+            | ("counter_attack_roll", EventPayload::CounterAttackRoll(_))
+            | ("counter_damage_application", EventPayload::CounterDamageApplication(_)) => {
+                typed += 1
+            }
+            (_, EventPayload::Other(values)) => assert_eq!(values, event.values),
+            (event_type, payload) => panic!("{event_type} parsed as {payload:?}"),
+        }
+    }
+    assert!(typed >= 5);
+
+    // The generic map stays available when a payload does not fit its typed shape.
+    let mut broken = result
+        .events
+        .iter()
+        .find(|e| e.event_type == "damage_application")
+        .expect("damage is traced")
+        .clone();
+    broken.values.remove("hull_damage");
+    assert_eq!(broken.payload(), EventPayload::Other(broken.values.clone()));
+}
+
+#[test]
+fn serialize_events_msgpack_decodes_to_the_json_document() {
+    let events = [
+        CombatEvent {
+            event_type: "attack_roll".to_string(),
+            round_index: 1,
+            phase: "attack".to_string(),
+            source: EventSource {
+                officer_id: Some("nero".to_string()),
+                ..EventSource::default()
+            },
+            values: Map::from_iter([
+                ("roll".to_string(), Value::from(0.617753)),
+                ("hit".to_string(), Value::Bool(true)),
+            ]),
+            weapon_index: Some(2),
+            schema_version: TRACE_SCHEMA_VERSION,
+        },
+        CombatEvent {
+            event_type: "round_end".to_string(),
+            round_index: 1,
+            phase: "end".to_string(),
+            source: EventSource::default(),
+            values: Map::from_iter([("defender_hull".to_string(), Value::from(-12))]),
+            weapon_index: None,
+            schema_version: TRACE_SCHEMA_VERSION,
+        },
+    ];
+    let json: Value =
+        serde_json::from_str(&serialize_events_json(&events).expect("json")).expect("valid json");
+    let bytes = serialize_events_msgpack(&events).expect("msgpack");
+    let decoded: Value = rmp_serde::from_slice(&bytes).expect("valid msgpack");
+    assert_eq!(decoded, json);
 }
 
 #[test]
 fn apex_barrier_reduces_damage_and_apex_shred_weakens_barrier() {
     // One round, no mitigation/pierce/crit/proc: damage = attack. Apex factor = 10000/(10000+effective_barrier).
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender_no_barrier = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender_10k_barrier = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 10_000.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(200.0)
+        .build()
+        .unwrap();
+    let defender_no_barrier = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .build()
+        .unwrap();
+    let defender_10k_barrier = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .apex_barrier(10_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew = CrewConfiguration::default();
 
     let no_barrier = simulate_combat(&attacker, &defender_no_barrier, config, &crew);
@@ -351,25 +365,11 @@ fn apex_barrier_reduces_damage_and_apex_shred_weakens_barrier() {
     approx_eq(no_barrier.total_damage, 200.0, 1e-12);
     approx_eq(with_10k_barrier.total_damage, 100.0, 1e-12);
 
-    let attacker_100_pct_shred = Combatant {
-        id: "attacker".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 1.0, // 100% shred
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker_100_pct_shred = Combatant::builder("attacker")
+        .attack(200.0)
+        .apex_shred(1.0) // 100% shred
+        .build()
+        .unwrap();
     let with_shred = simulate_combat(&attacker_100_pct_shred, &defender_10k_barrier, config, &crew);
     // Effective barrier = 10000/(1+1) = 5000, factor = 10000/(10000+5000) = 2/3. Engine rounds total_damage.
     approx_eq(with_shred.total_damage, 200.0 * (10000.0 / 15000.0), 0.01);
@@ -379,50 +379,20 @@ fn apex_barrier_reduces_damage_and_apex_shred_weakens_barrier() {
 /// When shields are depleted, all damage goes to hull.
 #[test]
 fn shield_mitigation_splits_damage_between_shield_and_hull() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(200.0)
+        .build()
+        .unwrap();
     // Defender with 500 SHP, 80% shield mitigation â†’ 80% of damage to shield, 20% to hull.
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 500.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let defender = Combatant::builder("defender")
+        .shield_health(500.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     // 200 damage: 80% = 160 to shield, 20% = 40 to hull.
     approx_eq(result.total_damage, 200.0, 1e-12);
@@ -430,52 +400,288 @@ fn shield_mitigation_splits_damage_between_shield_and_hull() {
     approx_eq(result.defender_hull_remaining, 1000.0 - 40.0, 1e-12);
 }
 
+#[test]
+fn defender_return_fire_does_not_shift_attacker_rolls() {
+    // Three attacker weapons: the defender's return fire lands between the attacker's shots.
+    let attacker = Combatant::builder("attacker")
+        .weapon(100.0, Some(2))
+        .weapon(100.0, Some(2))
+        .weapon(100.0, Some(2))
+        .crit_chance(0.5)
+        .crit_multiplier(2.0)
+        .proc_chance(0.3)
+        .proc_multiplier(1.5)
+        .hull_health(1.0e9)
+        .build()
+        .unwrap();
+    let defender = |weapons: usize| {
+        (0..weapons)
+            .fold(Combatant::builder("defender"), |builder, _| builder.weapon(10.0, None))
+            .crit_chance(0.5)
+            .crit_multiplier(3.0)
+            .proc_chance(0.5)
+            .proc_multiplier(2.0)
+            .hull_health(1.0e9)
+            .build()
+            .unwrap()
+    };
+    let config = SimulationConfig::builder().rounds(15).seed(11).build();
+    let fight = |defender: &Combatant| {
+        simulate_combat(&attacker, defender, config.clone(), &CrewConfiguration::default())
+    };
+    let one_gun = fight(&defender(1));
+    let three_guns = fight(&defender(3));
+    approx_eq(three_guns.total_damage, one_gun.total_damage, 1e-9);
+    assert!(three_guns.attacker_hull_remaining < one_gun.attacker_hull_remaining);
+}
+
+#[test]
+fn damage_breakdown_splits_each_sides_damage_by_source() {
+    // One round, no mitigation: 100 attack x2 crit x1.5 proc = 300, plus 10% isolytic.
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .crit_chance(1.0)
+        .crit_multiplier(2.0)
+        .proc_chance(1.0)
+        .proc_multiplier(1.5)
+        .isolytic_damage(0.1)
+        .hull_health(1000.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .attack(10.0)
+        .hull_health(10_000.0)
+        .shield_health(10_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder().rounds(1).seed(7).build();
+    let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
+
+    let dealt = result.attacker_damage;
+    approx_eq(dealt.crit_damage, 150.0, 1e-6);
+    approx_eq(dealt.proc_damage, 50.0, 1e-6);
+    approx_eq(dealt.isolytic_damage, 30.0, 1e-6);
+    approx_eq(dealt.burning_damage, 0.0, 1e-12);
+    approx_eq(dealt.shield_damage, 10_000.0 - result.defender_shield_remaining, 1e-6);
+    approx_eq(result.total_damage, 330.0, 1e-6);
+
+    let taken = result.defender_damage;
+    approx_eq(taken.crit_damage, 0.0, 1e-12);
+    approx_eq(taken.shield_damage, 0.0, 1e-12);
+    approx_eq(result.attacker_hull_remaining, 990.0, 1e-6);
+}
+
+#[test]
+fn initiative_decides_a_pvp_sub_round_where_both_ships_one_shot_each_other() {
+    let ship = |id: &str| {
+        Combatant::builder(id.to_string())
+            .attack(1000.0)
+            .hull_health(500.0)
+            .build()
+            .unwrap()
+    };
+    let (attacker, defender) = (ship("player_a"), ship("player_b"));
+    let fight = |initiative: InitiativeMode| {
+        let config = SimulationConfig::builder()
+            .rounds(3)
+            .seed(9)
+            .trace(true)
+            .initiative(initiative)
+            .build();
+        simulate_combat(&attacker, &defender, config, &CrewConfiguration::default())
+    };
+    let destroyed = |events: &[CombatEvent]| -> Vec<String> {
+        events
+            .iter()
+            .filter(|e| e.event_type == "ship_destroyed")
+            .map(|e| e.values["side"].as_str().unwrap_or_default().to_string())
+            .collect()
+    };
+
+    let both = fight(InitiativeMode::Simultaneous);
+    assert!(!both.attacker_won);
+    approx_eq(both.attacker_hull_remaining, 0.0, 1e-12);
+    approx_eq(both.defender_hull_remaining, 0.0, 1e-12);
+    assert!(destroyed(&both.events).is_empty());
+
+    let first = fight(InitiativeMode::AttackerFirst);
+    assert!(first.attacker_won);
+    assert_eq!(first.rounds_simulated, 1);
+    approx_eq(first.attacker_hull_remaining, 500.0, 1e-12);
+    assert_eq!(destroyed(&first.events), ["defender"]);
+
+    let second = fight(InitiativeMode::DefenderFirst);
+    assert!(!second.attacker_won);
+    approx_eq(second.attacker_hull_remaining, 0.0, 1e-12);
+    approx_eq(second.defender_hull_remaining, 500.0, 1e-12);
+    assert_eq!(destroyed(&second.events), ["attacker"]);
+
+    let default_config = serde_json::to_value(SimulationConfig::default()).unwrap();
+    assert!(default_config.get("initiative").is_none());
+    let parsed: SimulationConfig = serde_json::from_value(serde_json::json!({
+        "rounds": 3, "seed": 1, "trace_mode": "off", "initiative": "defender_first"
+    }))
+    .unwrap();
+    assert_eq!(parsed.initiative, InitiativeMode::DefenderFirst);
+}
+
+#[test]
+fn defense_scenario_fights_differ_from_offense_for_the_same_seed() {
+    let ship = |id: &str| {
+        Combatant::builder(id.to_string())
+            .attack(1000.0)
+            .hull_health(500.0)
+            .build()
+            .unwrap()
+    };
+    let (attacker, defender) = (ship("player_a"), ship("player_b"));
+    let fight = |scenario: ScenarioType| {
+        let config = SimulationConfig::builder()
+            .rounds(3)
+            .seed(9)
+            .initiative(scenario.initiative())
+            .build();
+        simulate_combat(&attacker, &defender, config, &CrewConfiguration::default())
+    };
+
+    let offense = fight(ScenarioType::Offense);
+    let defense = fight(ScenarioType::Defense);
+    assert_eq!(ScenarioType::Defense.initiative(), InitiativeMode::DefenderFirst);
+    approx_eq(offense.defender_hull_remaining, 0.0, 1e-12);
+    approx_eq(defense.defender_hull_remaining, 500.0, 1e-12);
+    approx_eq(defense.attacker_hull_remaining, 0.0, 1e-12);
+}
+
+#[test]
+fn attacker_delay_rounds_hold_fire_while_defender_shoots() {
+    let ship = |id: &str, attack: f64| Combatant::builder(id.to_string())
+        .attack(attack)
+        .build()
+        .unwrap();
+    let attacker = ship("miner", 200.0);
+    let defender = ship("raider", 50.0);
+    let config = SimulationConfig::builder()
+        .rounds(4)
+        .seed(7)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .attacker_delay_rounds(2)
+        .build();
+    let delayed = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
+    let mut no_delay = config;
+    no_delay.attacker_delay_rounds = 0;
+    let immediate =
+        simulate_combat(&attacker, &defender, no_delay, &CrewConfiguration::default());
+    approx_eq(immediate.total_damage, 800.0, 1e-12);
+    approx_eq(delayed.total_damage, 400.0, 1e-12);
+    approx_eq(delayed.attacker_hull_remaining, immediate.attacker_hull_remaining, 1e-12);
+    let hold_rounds: Vec<u32> = delayed
+        .events
+        .iter()
+        .filter(|e| e.event_type == "attacker_hold_fire")
+        .map(|e| e.round_index)
+        .collect();
+    assert_eq!(hold_rounds, vec![1, 2]);
+}
+
+#[test]
+fn streaming_delivers_the_same_events_as_a_traced_fight() {
+    let ship = |id: &str, attack: f64| Combatant::builder(id.to_string())
+        .attack(attack)
+        .mitigation(0.2)
+        .pierce(0.1)
+        .crit_chance(0.3)
+        .crit_multiplier(1.5)
+        .hull_health(2000.0)
+        .shield_health(500.0)
+        .build()
+        .unwrap();
+    let attacker = ship("player", 180.0);
+    let defender = ship("hostile", 90.0);
+    let config = SimulationConfig::builder()
+        .rounds(6)
+        .seed(21)
+        .build();
+    let crew = CrewConfiguration::default();
+
+    let mut streamed: Vec<CombatEvent> = Vec::new();
+    let result = simulate_combat_streaming(&attacker, &defender, config, &crew, &mut |event| {
+        streamed.push(event)
+    });
+    let mut traced_config = config;
+    traced_config.trace_mode = TraceMode::Events;
+    let traced = simulate_combat(&attacker, &defender, traced_config, &crew);
+
+    assert!(result.events.is_empty());
+    assert!(!streamed.is_empty());
+    assert_eq!(
+        serde_json::to_value(&streamed).unwrap(),
+        serde_json::to_value(&traced.events).unwrap()
+    );
+    approx_eq(result.total_damage, traced.total_damage, 0.0);
+    assert_eq!(result.rounds_simulated, traced.rounds_simulated);
+}
+
+#[test]
+fn simulate_waves_carries_hull_between_waves_and_applies_repair() {
+    let ship = |id: &str, attack: f64, hull: f64| Combatant::builder(id.to_string())
+        .attack(attack)
+        .hull_health(hull)
+        .build()
+        .unwrap();
+    let attacker = ship("tc_guard", 500.0, 1000.0);
+    let hostiles = vec![ship("wave", 100.0, 1000.0); 3];
+    let config = SimulationConfig::builder()
+        .rounds(10)
+        .seed(7)
+        .build();
+    let crew = CrewConfiguration::default();
+
+    let single = simulate_waves(&attacker, &hostiles[..1], config, &crew, 0.0);
+    assert_eq!(single.waves[0], simulate_combat(&attacker, &hostiles[0], config, &crew));
+
+    let no_repair = simulate_waves(&attacker, &hostiles, config, &crew, 0.0);
+    assert_eq!(no_repair.waves_cleared, 3);
+    let lost_first = attacker.hull_health - no_repair.waves[0].attacker_hull_remaining;
+    assert!(lost_first > 0.0);
+    approx_eq(
+        no_repair.attacker_hull_remaining,
+        attacker.hull_health - 3.0 * lost_first,
+        1e-9,
+    );
+
+    let full_repair = simulate_waves(&attacker, &hostiles, config, &crew, 1.0);
+    assert_eq!(full_repair.waves_cleared, 3);
+    approx_eq(
+        full_repair.attacker_hull_remaining,
+        no_repair.waves[0].attacker_hull_remaining,
+        1e-9,
+    );
+
+    let fragile = ship("fragile", 500.0, lost_first * 1.5);
+    let run = simulate_waves(&fragile, &hostiles, config, &crew, 0.0);
+    assert_eq!(run.waves_cleared, 1);
+    assert_eq!(run.waves.len(), 2);
+    assert_eq!(run.attacker_hull_remaining, 0.0);
+}
+
 #[test]
 fn shield_overflow_goes_to_hull_when_shields_depleted_mid_round() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 1000.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(1000.0)
+        .build()
+        .unwrap();
     // Defender has only 100 SHP; 80% of 1000 = 800 to shield â†’ 100 absorbed, 700 overflow to hull. 20% = 200 to hull. Total hull = 900.
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 2000.0,
-        shield_health: 100.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let defender = Combatant::builder("defender")
+        .hull_health(2000.0)
+        .shield_health(100.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     approx_eq(result.total_damage, 1000.0, 1e-12);
     approx_eq(result.defender_shield_remaining, 0.0, 1e-12);
@@ -484,49 +690,20 @@ fn shield_overflow_goes_to_hull_when_shields_depleted_mid_round() {
 
 #[test]
 fn when_shields_depleted_all_damage_goes_to_hull_next_rounds() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 500.0,
-        shield_health: 50.0, // Round 1: 80% of 100 = 80 to shield â†’ 50 absorbed, 30 overflow; 20% = 20 to hull. Shield gone. Hull takes 20+30 = 50.
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 3,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(500.0)
+        .shield_health(50.0) // Round 1: 80% of 100 = 80 to shield â†’ 50 absorbed, 30 overflow; 20% = 20 to hull. Shield gone. Hull takes 20+30 = 50.
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(3)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     approx_eq(result.defender_shield_remaining, 0.0, 1e-12);
     // Round 1: 50 hull damage. Round 2 and 3: 100% to hull = 100 each. Total hull damage = 50 + 100 + 100 = 250.
@@ -536,49 +713,20 @@ fn when_shields_depleted_all_damage_goes_to_hull_next_rounds() {
 
 #[test]
 fn officer_apex_shred_bonus_at_combat_begin_increases_damage_through_barrier() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 10_000.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(200.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .apex_barrier(10_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew_no_apex = CrewConfiguration::default();
     let crew_with_apex_shred = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -590,6 +738,8 @@ fn officer_apex_shred_bonus_at_combat_begin_increases_damage_through_barrier() {
                 boostable: false,
                 effect: AbilityEffect::ApexShredBonus(0.15),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -611,49 +761,20 @@ fn officer_apex_shred_bonus_at_combat_begin_increases_damage_through_barrier() {
 
 #[test]
 fn officer_apex_barrier_bonus_at_combat_begin_reduces_damage_taken() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender_no_bonus = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 5_000.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(200.0)
+        .build()
+        .unwrap();
+    let defender_no_bonus = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .apex_barrier(5_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew_no_apex = CrewConfiguration::default();
     let crew_with_apex_barrier = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -665,6 +786,8 @@ fn officer_apex_barrier_bonus_at_combat_begin_reduces_damage_taken() {
                 boostable: false,
                 effect: AbilityEffect::ApexBarrierBonus(5000.0),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -686,49 +809,21 @@ fn officer_apex_barrier_bonus_at_combat_begin_reduces_damage_taken() {
 #[test]
 fn ship_ability_pierce_bonus_at_round_start_increases_damage() {
     // Ship hull ability (CrewSeat::Ship, AbilityClass::ShipAbility) is evaluated like officer abilities.
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.05,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.15,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 5000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .pierce(0.05)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .mitigation(0.15)
+        .hull_health(5000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew_no_ship_ability = CrewConfiguration::default();
     let crew_with_ship_ability = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -740,6 +835,8 @@ fn ship_ability_pierce_bonus_at_round_start_increases_damage() {
                 boostable: false,
                 effect: AbilityEffect::PierceBonus(0.10),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -757,50 +854,17 @@ fn ship_ability_pierce_bonus_at_round_start_increases_damage() {
 
 #[test]
 fn ship_ability_receive_damage_timing_emits_trace() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 15.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 50_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![WeaponStats {
-            attack: 15.0,
-            shots: Some(1),
-        }],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 50_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![WeaponStats {
-            attack: 40.0,
-            shots: Some(1),
-        }],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(15.0)
+        .hull_health(50_000.0)
+        .weapon(15.0, Some(1))
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(50_000.0)
+        .weapon(40.0, Some(1))
+        .build()
+        .unwrap();
     let crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
             seat: CrewSeat::Ship,
@@ -811,6 +875,8 @@ fn ship_ability_receive_damage_timing_emits_trace() {
                 boostable: false,
                 effect: AbilityEffect::ApexShredBonus(0.04),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -820,11 +886,12 @@ fn ship_ability_receive_damage_timing_emits_trace() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 2,
-            seed: 11,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(2)
+            .seed(11)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
     let hits: Vec<_> = result
@@ -844,44 +911,18 @@ fn ship_ability_receive_damage_timing_emits_trace() {
 
 #[test]
 fn below_deck_morale_effect_triggers_morale_and_increases_damage() {
-    let attacker = Combatant {
-        id: "enterprise".to_string(),
-        attack: 120.0,
-        mitigation: 0.1,
-        pierce: 0.15,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 10.0,
-        mitigation: 0.35,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("enterprise")
+        .attack(120.0)
+        .mitigation(0.1)
+        .pierce(0.15)
+        .hull_health(10000.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .attack(10.0)
+        .mitigation(0.35)
+        .build()
+        .unwrap();
 
     let no_morale = CrewConfiguration::default();
     let morale_below_decks = CrewConfiguration {
@@ -894,6 +935,8 @@ fn below_deck_morale_effect_triggers_morale_and_increases_damage() {
                 boostable: true,
                 effect: AbilityEffect::Morale(1.0),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -901,11 +944,12 @@ fn below_deck_morale_effect_triggers_morale_and_increases_damage() {
         }],
     };
 
-    let config = SimulationConfig {
-        rounds: 2,
-        seed: 7,
-        trace_mode: TraceMode::Events,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(2)
+        .seed(7)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let baseline = simulate_combat(&attacker, &defender, config, &no_morale);
     let with_morale = simulate_combat(&attacker, &defender, config, &morale_below_decks);
@@ -921,45 +965,124 @@ fn below_deck_morale_effect_triggers_morale_and_increases_damage() {
 }
 
 #[test]
-fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
-    let attacker = Combatant {
-        id: "enterprise".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
+fn precomputed_crew_effects_match_per_fight_grouping_and_drop_duplicate_officers() {
+    let attacker = Combatant::builder("enterprise")
+        .attack(120.0)
+        .mitigation(0.1)
+        .pierce(0.15)
+        .hull_health(10000.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .attack(10.0)
+        .mitigation(0.35)
+        .build()
+        .unwrap();
+    let seat = |batch: u32, timing: TimingWindow, effect: AbilityEffect| CrewSeatContext {
+        seat: CrewSeat::BelowDeck,
+        ability: Ability {
+            name: format!("ability_{batch}"),
+            class: AbilityClass::BelowDeck,
+            timing,
+            boostable: true,
+            effect,
+            condition: None,
+            max_activations: None,
+            cooldown_rounds: 0,
+        },
+        boosted: false,
+        officer_id: Some("scotty".to_string()),
+        contribution_batch: batch,
     };
+    // The second row repeats the officer in another slot and must be dropped.
+    let crew = CrewConfiguration {
+        seats: vec![
+            seat(1, TimingWindow::RoundStart, AbilityEffect::Morale(1.0)),
+            seat(2, TimingWindow::AttackPhase, AbilityEffect::AttackMultiplier(0.5)),
+        ],
+    };
+
+    let effects = CrewEffects::new(&crew);
+    assert_eq!(effects.for_timing(TimingWindow::RoundStart).len(), 1);
+    assert!(effects.for_timing(TimingWindow::AttackPhase).is_empty());
+
+    let config = SimulationConfig::builder().rounds(4).seed(11).trace(true).build();
+    let start = AttackerStartState::fresh(&attacker);
+    assert_eq!(
+        simulate_combat_with_effects(&attacker, &defender, config, &effects, start),
+        simulate_combat(&attacker, &defender, config, &crew)
+    );
+}
+
+#[test]
+fn reused_combat_scratch_matches_fresh_fights() {
+    let attacker = Combatant::builder("enterprise")
+        .attack(120.0)
+        .pierce(0.2)
+        .hull_health(5000.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .attack(40.0)
+        .mitigation(0.3)
+        .hull_health(4000.0)
+        .build()
+        .unwrap();
+    let seat = |batch: u32, timing: TimingWindow, effect: AbilityEffect, max: Option<u32>| {
+        CrewSeatContext {
+            seat: CrewSeat::BelowDeck,
+            ability: Ability {
+                name: format!("ability_{batch}"),
+                class: AbilityClass::BelowDeck,
+                timing,
+                boostable: true,
+                effect,
+                condition: None,
+                max_activations: max,
+                cooldown_rounds: 0,
+            },
+            boosted: false,
+            officer_id: Some(format!("officer_{batch}")),
+            contribution_batch: batch,
+        }
+    };
+    // Activation limits, shots bonuses and damage over time all carry state across rounds; a
+    // reused scratch must start every fight without the previous fight's leftovers.
+    let crew = CrewConfiguration {
+        seats: vec![
+            seat(1, TimingWindow::AttackPhase, AbilityEffect::AttackMultiplier(0.5), Some(2)),
+            seat(
+                2,
+                TimingWindow::RoundStart,
+                AbilityEffect::ShotsBonus { chance: 1.0, bonus_pct: 0.5, duration_rounds: 3 },
+                Some(1),
+            ),
+            seat(
+                3,
+                TimingWindow::AttackPhase,
+                AbilityEffect::Burning { chance: 0.5, duration_rounds: 2 },
+                None,
+            ),
+        ],
+    };
+    let effects = CrewEffects::new(&crew);
+    let mut scratch = CombatScratch::default();
+    for seed in 1..=5 {
+        let config = SimulationConfig::builder().rounds(6).seed(seed).trace(true).build();
+        let start = AttackerStartState::fresh(&attacker);
+        let reused =
+            simulate_combat_with_scratch(&attacker, &defender, config, &effects, start, &mut scratch);
+        assert_eq!(reused, simulate_combat(&attacker, &defender, config, &crew));
+    }
+}
+
+#[test]
+fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
+    let attacker = Combatant::builder("enterprise")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm").build().unwrap();
 
     let baseline_crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -971,6 +1094,8 @@ fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
                 boostable: true,
                 effect: AbilityEffect::AttackMultiplier(1.0),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -992,6 +1117,8 @@ fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
                         duration_rounds: 2,
                     },
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -1006,6 +1133,8 @@ fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(1.0),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -1014,11 +1143,12 @@ fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
         ],
     };
 
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Events,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let baseline = simulate_combat(&attacker, &defender, config, &baseline_crew);
     let with_assimilated = simulate_combat(&attacker, &defender, config, &assimilated_crew);
@@ -1047,44 +1177,11 @@ fn assimilated_reduces_officer_effectiveness_by_twenty_five_percent() {
 
 #[test]
 fn dezoc_style_assimilated_can_trigger_from_below_decks() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender").build().unwrap();
 
     let crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -1099,6 +1196,8 @@ fn dezoc_style_assimilated_can_trigger_from_below_decks() {
                     duration_rounds: 4,
                 },
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1109,11 +1208,12 @@ fn dezoc_style_assimilated_can_trigger_from_below_decks() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 7,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(7)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
 
@@ -1132,44 +1232,13 @@ fn dezoc_style_assimilated_can_trigger_from_below_decks() {
 
 #[test]
 fn hull_breach_boosts_critical_damage_after_crit_multiplier() {
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 1.0,
-        crit_multiplier: 2.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("nero")
+        .attack(100.0)
+        .crit_chance(1.0)
+        .crit_multiplier(2.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm").build().unwrap();
 
     let crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -1185,6 +1254,8 @@ fn hull_breach_boosts_critical_damage_after_crit_multiplier() {
                     requires_critical: false,
                 },
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1195,11 +1266,12 @@ fn hull_breach_boosts_critical_damage_after_crit_multiplier() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 7,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(7)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
 
@@ -1223,44 +1295,13 @@ fn hull_breach_boosts_critical_damage_after_crit_multiplier() {
 
 #[test]
 fn hull_breach_can_trigger_from_critical_hit_officer_ability() {
-    let attacker = Combatant {
-        id: "gorkon_ship".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 1.0,
-        crit_multiplier: 1.5,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "target".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("gorkon_ship")
+        .attack(100.0)
+        .crit_chance(1.0)
+        .crit_multiplier(1.5)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("target").build().unwrap();
 
     let crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -1276,6 +1317,8 @@ fn hull_breach_can_trigger_from_critical_hit_officer_ability() {
                     requires_critical: true,
                 },
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1286,11 +1329,12 @@ fn hull_breach_can_trigger_from_critical_hit_officer_ability() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 7,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(7)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
 
@@ -1307,50 +1351,71 @@ fn hull_breach_can_trigger_from_critical_hit_officer_ability() {
     );
 }
 #[test]
-fn simulate_combat_uses_seed_and_emits_canonical_events() {
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 120.0,
-        mitigation: 0.1,
-        pierce: 0.15,
-        crit_chance: 0.5,
-        crit_multiplier: 1.8,
-        proc_chance: 0.4,
-        proc_multiplier: 1.25,
-        end_of_round_damage: 3.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 10.0,
-        mitigation: 0.35,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 2,
-        seed: 7,
-        trace_mode: TraceMode::Events,
+fn counter_rng_is_default_and_deterministic_per_seed() {
+    let config: SimulationConfig =
+        serde_json::from_str(r#"{"rounds":3,"seed":11,"trace_mode":"off"}"#).expect("config");
+    assert_eq!(config.rng_mode, RngMode::Counter);
+
+    let attacker = Combatant::builder("nero")
+        .attack(120.0)
+        .mitigation(0.1)
+        .pierce(0.15)
+        .crit_chance(0.5)
+        .crit_multiplier(1.8)
+        .proc_chance(0.4)
+        .proc_multiplier(1.25)
+        .build()
+        .unwrap();
+    let mut defender = attacker.clone();
+    defender.id = "swarm".to_string();
+    defender.mitigation = 0.35;
+    defender.crit_chance = 0.0;
+    defender.proc_chance = 0.0;
+    let crew = CrewConfiguration::default();
+    let mut counter = config;
+    counter.trace_mode = TraceMode::Events;
+    let first = simulate_combat(&attacker, &defender, counter, &crew);
+    let second = simulate_combat(&attacker, &defender, counter, &crew);
+    assert_eq!(first.events, second.events);
+
+    // Rounds 1..=2 draw from per-round streams, so they match between a 2- and 3-round fight.
+    let mut two_rounds = counter;
+    two_rounds.rounds = 2;
+    let shorter = simulate_combat(&attacker, &defender, two_rounds, &crew);
+    let rolls = |events: &[CombatEvent]| -> Vec<f64> {
+        events
+            .iter()
+            .filter(|e| e.round_index <= 2 && e.event_type == "crit_resolution")
+            .filter_map(|e| e.values["roll"].as_f64())
+            .collect()
     };
+    assert_eq!(rolls(&first.events), rolls(&shorter.events));
+}
+
+#[test]
+fn simulate_combat_uses_seed_and_emits_canonical_events() {
+    let attacker = Combatant::builder("nero")
+        .attack(120.0)
+        .mitigation(0.1)
+        .pierce(0.15)
+        .crit_chance(0.5)
+        .crit_multiplier(1.8)
+        .proc_chance(0.4)
+        .proc_multiplier(1.25)
+        .end_of_round_damage(3.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .attack(10.0)
+        .mitigation(0.35)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(2)
+        .seed(7)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let crew = CrewConfiguration::default();
     let first = simulate_combat(&attacker, &defender, config, &crew);
@@ -1359,7 +1424,7 @@ fn simulate_combat_uses_seed_and_emits_canonical_events() {
     assert_eq!(first.events, second.events);
     assert_eq!(first.total_damage, second.total_damage);
 
-    assert_eq!(first.events.len(), 18);
+    assert_eq!(first.events.len(), 22);
     let expected_event_types = vec![
         "round_start",
         "attack_roll",
@@ -1369,11 +1434,13 @@ fn simulate_combat_uses_seed_and_emits_canonical_events() {
         "proc_triggers",
         "stack_resolution",
         "damage_application",
+        "counter_attack_roll",
+        "counter_damage_application",
         "end_of_round_effects",
     ];
     for (index, expected) in expected_event_types.iter().enumerate() {
         assert_eq!(first.events[index].event_type, *expected);
-        assert_eq!(first.events[index + 9].event_type, *expected);
+        assert_eq!(first.events[index + 11].event_type, *expected);
     }
 
     // Seed 7 (SplitMix64) produces deterministic rolls; exact values depend on RNG implementation.
@@ -1392,8 +1459,8 @@ fn simulate_combat_uses_seed_and_emits_canonical_events() {
         Value::Bool(round_one_proc_roll < 0.4)
     );
 
-    let round_two_crit = &first.events[13];
-    let round_two_proc = &first.events[14];
+    let round_two_crit = &first.events[15];
+    let round_two_proc = &first.events[16];
     let round_two_crit_roll = round_two_crit.values["roll"].as_f64().expect("crit roll as f64");
     let round_two_proc_roll = round_two_proc.values["roll"].as_f64().expect("proc roll as f64");
     assert!(round_two_crit_roll >= 0.0 && round_two_crit_roll <= 1.0);
@@ -1511,6 +1578,8 @@ fn crew_slot_gating_matrix_controls_activation() {
         boostable: true,
         effect: AbilityEffect::AttackMultiplier(0.2),
         condition: None,
+        max_activations: None,
+        cooldown_rounds: 0,
     };
     let bridge_ability = Ability {
         name: "bridge_targeting".to_string(),
@@ -1519,51 +1588,25 @@ fn crew_slot_gating_matrix_controls_activation() {
         boostable: true,
         effect: AbilityEffect::PierceBonus(0.1),
         condition: None,
-    };
-
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.15,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 0.0,
-        mitigation: 0.5,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 9,
-        trace_mode: TraceMode::Events,
-    };
+        max_activations: None,
+        cooldown_rounds: 0,
+    };
+
+    let attacker = Combatant::builder("nero")
+        .attack(100.0)
+        .pierce(0.15)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .mitigation(0.5)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(9)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let valid_crew = CrewConfiguration {
         seats: vec![
@@ -1620,51 +1663,25 @@ fn boosted_non_boostable_abilities_are_filtered_out() {
         boostable: false,
         effect: AbilityEffect::AttackMultiplier(0.5),
         condition: None,
-    };
-
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.1,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 0.0,
-        mitigation: 0.2,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 11,
-        trace_mode: TraceMode::Events,
-    };
+        max_activations: None,
+        cooldown_rounds: 0,
+    };
+
+    let attacker = Combatant::builder("nero")
+        .attack(100.0)
+        .pierce(0.1)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .mitigation(0.2)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(11)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let boosted = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -1703,51 +1720,63 @@ fn boosted_non_boostable_abilities_are_filtered_out() {
     );
 }
 
+#[test]
+fn boosted_abilities_apply_the_boost_multiplier() {
+    let ability = Ability {
+        name: "overclock".to_string(),
+        class: AbilityClass::BridgeAbility,
+        timing: TimingWindow::AttackPhase,
+        boostable: true,
+        effect: AbilityEffect::AttackMultiplier(0.5),
+        condition: None,
+        max_activations: None,
+        cooldown_rounds: 0,
+    };
+    let attacker = Combatant::builder("nero").attack(100.0).build().unwrap();
+    let defender = Combatant::builder("swarm").hull_health(1_000_000.0).build().unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(11)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
+    let crew = |boosted| CrewConfiguration {
+        seats: vec![CrewSeatContext::legacy(CrewSeat::Bridge, ability.clone(), boosted)],
+    };
+
+    let boosted = simulate_combat(&attacker, &defender, config, &crew(true));
+    let unboosted = simulate_combat(&attacker, &defender, config, &crew(false));
+
+    assert!(boosted.total_damage > unboosted.total_damage);
+    let activation = boosted
+        .events
+        .iter()
+        .find(|event| event.event_type == "ability_activation")
+        .expect("boosted ability should activate");
+    assert_eq!(activation.values["boosted"], Value::Bool(true));
+    approx_eq(
+        activation.values["effectiveness_multiplier"].as_f64().unwrap(),
+        1.10,
+        1e-12,
+    );
+}
+
 #[test]
 fn timing_windows_materially_change_damage_outcomes() {
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 0.0,
-        mitigation: 0.5,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 17,
-        trace_mode: TraceMode::Events,
-    };
+    let attacker = Combatant::builder("nero")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .mitigation(0.5)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(17)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let attack_phase_crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -1759,6 +1788,8 @@ fn timing_windows_materially_change_damage_outcomes() {
                 boostable: true,
                 effect: AbilityEffect::PierceBonus(0.2),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1775,6 +1806,8 @@ fn timing_windows_materially_change_damage_outcomes() {
                 boostable: true,
                 effect: AbilityEffect::PierceBonus(0.2),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1791,6 +1824,8 @@ fn timing_windows_materially_change_damage_outcomes() {
                 boostable: true,
                 effect: AbilityEffect::PierceBonus(0.2),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1811,44 +1846,11 @@ fn timing_windows_materially_change_damage_outcomes() {
 
 #[test]
 fn burning_deals_one_percent_hull_per_round() {
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "target".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 500.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("nero").build().unwrap();
+    let defender = Combatant::builder("target")
+        .hull_health(500.0)
+        .build()
+        .unwrap();
 
     let burning_crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -1863,6 +1865,8 @@ fn burning_deals_one_percent_hull_per_round() {
                     duration_rounds: 2,
                 },
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -1873,11 +1877,12 @@ fn burning_deals_one_percent_hull_per_round() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 3,
-            seed: 1,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(3)
+            .seed(1)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &burning_crew,
     );
 
@@ -1892,46 +1897,164 @@ fn burning_deals_one_percent_hull_per_round() {
 }
 
 #[test]
-fn emits_ability_activation_for_each_timing_window() {
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 120.0,
-        mitigation: 0.0,
-        pierce: 0.1,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 1.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "swarm".to_string(),
-        attack: 0.0,
-        mitigation: 0.4,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
+fn plasma_and_isolytic_cascade_tick_on_separate_channels() {
+    let attacker = Combatant::builder("nero").build().unwrap();
+    let defender = Combatant::builder("target")
+        .hull_health(1000.0)
+        .shield_health(15.0)
+        .isolytic_defense(1.0)
+        .build()
+        .unwrap();
+    let dot_seat = |name: &str, channel, hull_pct, duration_rounds| CrewSeatContext {
+        seat: CrewSeat::Bridge,
+        ability: Ability {
+            name: name.to_string(),
+            class: AbilityClass::BridgeAbility,
+            timing: TimingWindow::RoundStart,
+            boostable: true,
+            effect: AbilityEffect::DamageOverTime {
+                channel,
+                chance: 1.0,
+                hull_pct,
+                duration_rounds,
+            },
+            condition: None,
+            max_activations: None,
+            cooldown_rounds: 0,
+        },
+        boosted: false,
+        officer_id: None,
+        contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
+    };
+    let crew = CrewConfiguration {
+        seats: vec![
+            dot_seat("plasma", DotChannel::Plasma, 0.01, 2),
+            dot_seat("cascade", DotChannel::IsolyticCascade, 0.02, 1),
+        ],
     };
 
+    let result = simulate_combat(
+        &attacker,
+        &defender,
+        SimulationConfig::builder()
+            .rounds(3)
+            .seed(1)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
+        &crew,
+    );
+
+    // Plasma stacks (1, 2, 2 stacks of 10) and drains the 15 shield before hull; the cascade
+    // refreshes and is halved by isolytic defense 1.0.
+    approx_eq(result.total_damage, 80.0, 1e-9);
+    let ticks = |channel: &str, key: &str| -> Vec<Value> {
+        result
+            .events
+            .iter()
+            .filter(|e| e.event_type == "dot_damage" && e.values["channel"] == channel)
+            .map(|e| e.values[key].clone())
+            .collect()
+    };
+    assert_eq!(ticks("plasma", "stacks"), vec![Value::from(1), Value::from(2), Value::from(2)]);
+    assert_eq!(
+        ticks("plasma", "shield_damage"),
+        vec![Value::from(10.0), Value::from(5.0), Value::from(0.0)]
+    );
+    assert_eq!(
+        ticks("plasma", "hull_damage"),
+        vec![Value::from(0.0), Value::from(15.0), Value::from(20.0)]
+    );
+    assert_eq!(ticks("isolytic_cascade", "hull_damage"), vec![Value::from(10.0); 3]);
+    assert!(ticks("burning", "hull_damage").is_empty());
+
+    let round_end: Vec<_> = result
+        .events
+        .iter()
+        .filter(|e| e.event_type == "end_of_round_effects")
+        .map(|e| (e.values["plasma_damage"].clone(), e.values["isolytic_cascade_damage"].clone()))
+        .collect();
+    assert_eq!(
+        round_end,
+        vec![
+            (Value::from(10.0), Value::from(20.0)),
+            (Value::from(20.0), Value::from(20.0)),
+            (Value::from(20.0), Value::from(20.0)),
+        ]
+    );
+}
+
+#[test]
+fn activation_limits_suppress_and_trace_extra_triggers() {
+    let attacker = Combatant::builder("nero").attack(100.0).build().unwrap();
+    let defender = Combatant::builder("target").hull_health(1_000_000.0).build().unwrap();
+    let limited_seat = |name: &str, max_activations, cooldown_rounds| CrewSeatContext {
+        seat: CrewSeat::Bridge,
+        ability: Ability {
+            name: name.to_string(),
+            class: AbilityClass::BridgeAbility,
+            timing: TimingWindow::RoundStart,
+            boostable: true,
+            effect: AbilityEffect::AttackMultiplier(0.1),
+            condition: None,
+            max_activations,
+            cooldown_rounds,
+        },
+        boosted: false,
+        officer_id: None,
+        contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
+    };
+    let crew = CrewConfiguration {
+        seats: vec![
+            limited_seat("twice_per_combat", Some(2), 0),
+            limited_seat("every_other_round", None, 2),
+        ],
+    };
+
+    let result = simulate_combat(
+        &attacker,
+        &defender,
+        SimulationConfig::builder().rounds(5).seed(1).trace(true).build(),
+        &crew,
+    );
+
+    let rounds_for = |event_type: &str, name: &str| -> Vec<u32> {
+        result
+            .events
+            .iter()
+            .filter(|e| e.event_type == event_type)
+            .filter(|e| e.source.ship_ability_id.as_deref() == Some(name))
+            .map(|e| e.round_index)
+            .collect()
+    };
+    assert_eq!(rounds_for("ability_activation", "twice_per_combat"), vec![1, 2]);
+    assert_eq!(rounds_for("ability_suppressed", "twice_per_combat"), vec![3, 4, 5]);
+    assert_eq!(rounds_for("ability_activation", "every_other_round"), vec![1, 3, 5]);
+    assert_eq!(rounds_for("ability_suppressed", "every_other_round"), vec![2, 4]);
+
+    let reasons: Vec<_> = result
+        .events
+        .iter()
+        .filter(|e| e.event_type == "ability_suppressed")
+        .map(|e| e.values["reason"].clone())
+        .collect();
+    assert!(reasons.contains(&Value::from("max_activations")));
+    assert!(reasons.contains(&Value::from("cooldown")));
+}
+
+#[test]
+fn emits_ability_activation_for_each_timing_window() {
+    let attacker = Combatant::builder("nero")
+        .attack(120.0)
+        .pierce(0.1)
+        .end_of_round_damage(1.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("swarm")
+        .mitigation(0.4)
+        .build()
+        .unwrap();
+
     let crew = CrewConfiguration {
         seats: vec![
             CrewSeatContext {
@@ -1943,6 +2066,8 @@ fn emits_ability_activation_for_each_timing_window() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -1957,6 +2082,8 @@ fn emits_ability_activation_for_each_timing_window() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -1971,6 +2098,8 @@ fn emits_ability_activation_for_each_timing_window() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -1985,6 +2114,8 @@ fn emits_ability_activation_for_each_timing_window() {
                     boostable: true,
                     effect: AbilityEffect::PierceBonus(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -1999,6 +2130,8 @@ fn emits_ability_activation_for_each_timing_window() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.2),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2010,11 +2143,12 @@ fn emits_ability_activation_for_each_timing_window() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 19,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(19)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
 
@@ -2034,44 +2168,11 @@ fn emits_ability_activation_for_each_timing_window() {
 
 #[test]
 fn additive_attack_modifiers_match_canonical_summed_behavior() {
-    let attacker = Combatant {
-        id: "nero".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "target".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("nero")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("target").build().unwrap();
 
     let two_ten_percent = CrewConfiguration {
         seats: vec![
@@ -2084,6 +2185,8 @@ fn additive_attack_modifiers_match_canonical_summed_behavior() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2098,6 +2201,8 @@ fn additive_attack_modifiers_match_canonical_summed_behavior() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2115,6 +2220,8 @@ fn additive_attack_modifiers_match_canonical_summed_behavior() {
                 boostable: true,
                 effect: AbilityEffect::AttackMultiplier(0.2),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -2122,11 +2229,11 @@ fn additive_attack_modifiers_match_canonical_summed_behavior() {
         }],
     };
 
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 11,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(11)
+        .rng_mode(RngMode::Legacy)
+        .build();
 
     let summed = simulate_combat(&attacker, &defender, config, &two_ten_percent);
     let canonical = simulate_combat(&attacker, &defender, config, &single_twenty_percent);
@@ -2137,44 +2244,16 @@ fn additive_attack_modifiers_match_canonical_summed_behavior() {
 
 #[test]
 fn decaying_attack_multiplier_reduces_damage_over_rounds() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.0,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.0,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .shield_mitigation(0.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .shield_mitigation(0.0)
+        .build()
+        .unwrap();
     let decay_crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
             seat: CrewSeat::Bridge,
@@ -2189,17 +2268,19 @@ fn decaying_attack_multiplier_reduces_damage_over_rounds() {
                     floor: 1.0,
                 },
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
             contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
         }],
     };
-    let config = SimulationConfig {
-        rounds: 5,
-        seed: 42,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(5)
+        .seed(42)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &decay_crew);
     assert!(result.total_damage > 0.0);
     assert!(result.rounds_simulated >= 2);
@@ -2207,44 +2288,16 @@ fn decaying_attack_multiplier_reduces_damage_over_rounds() {
 
 #[test]
 fn accumulating_attack_multiplier_increases_damage_over_rounds() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.0,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.0,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .shield_mitigation(0.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10000.0)
+        .shield_mitigation(0.0)
+        .build()
+        .unwrap();
     let accumulate_crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
             seat: CrewSeat::Bridge,
@@ -2259,17 +2312,19 @@ fn accumulating_attack_multiplier_increases_damage_over_rounds() {
                     ceiling: 1.2,
                 },
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
             contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
         }],
     };
-    let config = SimulationConfig {
-        rounds: 5,
-        seed: 42,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(5)
+        .seed(42)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &accumulate_crew);
     assert!(result.total_damage > 0.0);
     assert!(result.rounds_simulated >= 2);
@@ -2277,53 +2332,20 @@ fn accumulating_attack_multiplier_increases_damage_over_rounds() {
 
 #[test]
 fn combat_rounds_are_capped_at_100() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 1.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(1.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender").build().unwrap();
 
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 150,
-            seed: 9,
-            trace_mode: TraceMode::Off,
-        },
+        SimulationConfig::builder()
+            .rounds(150)
+            .seed(9)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &CrewConfiguration::default(),
     );
 
@@ -2333,44 +2355,16 @@ fn combat_rounds_are_capped_at_100() {
 #[test]
 fn round_end_regen_restores_shield_and_reduces_hull_damage() {
     use kobayashi::combat::CrewSeatContext;
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 150.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.3,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 600.0,
-        shield_health: 200.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(150.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .mitigation(0.3)
+        .hull_health(600.0)
+        .shield_health(200.0)
+        .build()
+        .unwrap();
     let crew_no_regen = CrewConfiguration::default();
     let crew_with_regen = CrewConfiguration {
         seats: vec![
@@ -2383,6 +2377,8 @@ fn round_end_regen_restores_shield_and_reduces_hull_damage() {
                     boostable: false,
                     effect: AbilityEffect::ShieldRegen(60.0),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2397,6 +2393,8 @@ fn round_end_regen_restores_shield_and_reduces_hull_damage() {
                     boostable: false,
                     effect: AbilityEffect::HullRegen(40.0),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2407,21 +2405,21 @@ fn round_end_regen_restores_shield_and_reduces_hull_damage() {
     let result_no_regen = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 2,
-            seed: 99,
-            trace_mode: TraceMode::Off,
-        },
+        SimulationConfig::builder()
+            .rounds(2)
+            .seed(99)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew_no_regen,
     );
     let result_with_regen = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 2,
-            seed: 99,
-            trace_mode: TraceMode::Off,
-        },
+        SimulationConfig::builder()
+            .rounds(2)
+            .seed(99)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew_with_regen,
     );
     assert!(
@@ -2436,53 +2434,24 @@ fn round_end_regen_restores_shield_and_reduces_hull_damage() {
 
 #[test]
 fn round_limit_declares_winner_by_hull_without_destruction() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 1.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 5000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(1.0)
+        .hull_health(10000.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(5000.0)
+        .build()
+        .unwrap();
 
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 100,
-            seed: 3,
-            trace_mode: TraceMode::Off,
-        },
+        SimulationConfig::builder()
+            .rounds(100)
+            .seed(3)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &CrewConfiguration::default(),
     );
 
@@ -2492,59 +2461,23 @@ fn round_limit_declares_winner_by_hull_without_destruction() {
     assert!(result.defender_hull_remaining > 0.0);
 }
 
-#[test]
-fn isolytic_damage_matches_reference_formula() {
-    let damage = isolytic_damage(10_000.0, 0.3, 0.4);
-    approx_eq(damage, 8_200.0, 1e-12);
-}
-
 #[test]
 fn isolytic_on_combatant_increases_damage_defense_reduces_it() {
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let attacker_no_iso = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let defender = Combatant::builder("defender")
+        .hull_health(10_000.0)
+        .build()
+        .unwrap();
+    let attacker_no_iso = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
     let mut attacker_with_iso = attacker_no_iso.clone();
     attacker_with_iso.isolytic_damage = 0.2;
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 5,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(5)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew = CrewConfiguration::default();
     let result_no_iso = simulate_combat(&attacker_no_iso, &defender, config, &crew);
     let result_with_iso = simulate_combat(&attacker_with_iso, &defender, config, &crew);
@@ -2563,49 +2496,19 @@ fn isolytic_on_combatant_increases_damage_defense_reduces_it() {
 
 #[test]
 fn crew_isolytic_damage_bonus_increases_damage() {
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 5,
-        trace_mode: TraceMode::Off,
-    };
+    let defender = Combatant::builder("defender")
+        .hull_health(10_000.0)
+        .build()
+        .unwrap();
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(5)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew_empty = CrewConfiguration::default();
     let crew_with_iso = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -2617,6 +2520,8 @@ fn crew_isolytic_damage_bonus_increases_damage() {
                 boostable: true,
                 effect: AbilityEffect::IsolyticDamageBonus(0.2),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -2633,49 +2538,19 @@ fn crew_isolytic_damage_bonus_increases_damage() {
 
 #[test]
 fn crew_isolytic_cascade_damage_bonus_increases_damage() {
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 5,
-        trace_mode: TraceMode::Off,
-    };
+    let defender = Combatant::builder("defender")
+        .hull_health(10_000.0)
+        .build()
+        .unwrap();
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(5)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let crew_base_iso = CrewConfiguration {
         seats: vec![CrewSeatContext {
             seat: CrewSeat::Bridge,
@@ -2686,6 +2561,8 @@ fn crew_isolytic_cascade_damage_bonus_increases_damage() {
                 boostable: true,
                 effect: AbilityEffect::IsolyticDamageBonus(0.1),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -2703,6 +2580,8 @@ fn crew_isolytic_cascade_damage_bonus_increases_damage() {
                     boostable: true,
                     effect: AbilityEffect::IsolyticDamageBonus(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2717,6 +2596,8 @@ fn crew_isolytic_cascade_damage_bonus_increases_damage() {
                     boostable: true,
                     effect: AbilityEffect::IsolyticCascadeDamageBonus(0.2),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2733,53 +2614,147 @@ fn crew_isolytic_cascade_damage_bonus_increases_damage() {
 }
 
 #[test]
-fn two_weapon_combatant_produces_two_damage_events_per_round() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 150.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![
-            WeaponStats { attack: 50.0, shots: None },
-            WeaponStats { attack: 100.0, shots: None },
+fn per_shot_events_carry_the_firing_weapon_index() {
+    let attacker = Combatant::builder("attacker")
+        .attack(150.0)
+        .weapon(50.0, None)
+        .weapon(100.0, None)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10_000.0)
+        .shield_health(60.0)
+        .build()
+        .unwrap();
+    let seat = |name: &str, timing: TimingWindow, effect: AbilityEffect| CrewSeatContext {
+        seat: CrewSeat::Captain,
+        ability: Ability {
+            name: name.to_string(),
+            class: AbilityClass::CaptainManeuver,
+            timing,
+            boostable: false,
+            effect,
+            condition: None,
+            max_activations: None,
+            cooldown_rounds: 0,
+        },
+        boosted: false,
+        officer_id: None,
+        contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
+    };
+    let crew = CrewConfiguration {
+        seats: vec![
+            seat(
+                "burn",
+                TimingWindow::AttackPhase,
+                AbilityEffect::Burning { chance: 1.0, duration_rounds: 2 },
+            ),
+            seat("breaker", TimingWindow::ShieldBreak, AbilityEffect::AttackMultiplier(0.1)),
         ],
     };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 7,
-        trace_mode: TraceMode::Events,
+    let config = SimulationConfig::builder().rounds(1).seed(3).trace(true).build();
+    let result = simulate_combat(&attacker, &defender, config, &crew);
+
+    for event_type in [
+        "attack_roll",
+        "mitigation_calc",
+        "pierce_calc",
+        "crit_resolution",
+        "burning_trigger",
+        "proc_triggers",
+        "stack_resolution",
+        "damage_application",
+    ] {
+        let weapons: Vec<Option<u32>> = result
+            .events
+            .iter()
+            .filter(|e| e.event_type == event_type)
+            .map(|e| e.weapon_index)
+            .collect();
+        assert_eq!(weapons, vec![Some(0), Some(1)], "{event_type}");
+    }
+    // Abilities triggered mid-round are attributed to the weapon whose shot triggered them.
+    let shield_break: Vec<_> = result
+        .events
+        .iter()
+        .filter(|e| e.phase == "shield_break")
+        .map(|e| e.weapon_index)
+        .collect();
+    assert_eq!(shield_break, vec![Some(1)]);
+    for event in result.events.iter().filter(|e| e.event_type == "round_start") {
+        assert_eq!(event.weapon_index, None);
+    }
+}
+
+#[test]
+fn defender_counter_attacks_are_traced_and_explain_attacker_hull_loss() {
+    let attacker = Combatant::builder("attacker")
+        .attack(50.0)
+        .mitigation(0.2)
+        .hull_health(5_000.0)
+        .shield_health(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("hostile")
+        .weapon(80.0, None)
+        .weapon(120.0, None)
+        .crit_chance(0.5)
+        .crit_multiplier(2.0)
+        .hull_health(100_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder().rounds(3).seed(9).trace(true).build();
+    let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
+
+    let rolls: Vec<_> =
+        result.events.iter().filter(|e| e.event_type == "counter_attack_roll").collect();
+    let hits: Vec<_> = result
+        .events
+        .iter()
+        .filter(|e| e.event_type == "counter_damage_application")
+        .collect();
+    assert_eq!(rolls.len(), 6, "two defender weapons over three rounds");
+    assert_eq!(hits.len(), 6);
+    for (i, event) in rolls.iter().chain(&hits).enumerate() {
+        assert_eq!(event.phase, "counter_attack");
+        assert_eq!(event.weapon_index, Some((i % 2) as u32));
+        assert_eq!(event.source.hostile_ability_id.as_deref(), Some("hostile_weapon"));
+    }
+    let EventPayload::CounterAttackRoll(first) = rolls[0].payload() else {
+        panic!("counter_attack_roll has a typed payload");
     };
+    assert_eq!(first.base_attack, 80.0);
+    approx_eq(first.mitigation, 0.2, 1e-9);
+
+    let hull_lost: f64 = hits
+        .iter()
+        .map(|e| match e.payload() {
+            EventPayload::CounterDamageApplication(hit) => hit.hull_damage,
+            other => panic!("unexpected payload {other:?}"),
+        })
+        .sum();
+    approx_eq(hull_lost, 5_000.0 - result.attacker_hull_remaining, 0.01);
+    assert!(hits.iter().any(|e| e.values["shield_broke"] == Value::Bool(true)));
+}
+
+#[test]
+fn two_weapon_combatant_produces_two_damage_events_per_round() {
+    let attacker = Combatant::builder("attacker")
+        .attack(150.0)
+        .weapon(50.0, None)
+        .weapon(100.0, None)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(7)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     let damage_events: Vec<_> = result
         .events
@@ -2798,52 +2773,23 @@ fn two_weapon_combatant_produces_two_damage_events_per_round() {
 
 #[test]
 fn sub_round_ordering_weapon_one_damage_after_shield_break() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![
-            WeaponStats { attack: 500.0, shots: None },
-            WeaponStats { attack: 200.0, shots: None },
-        ],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 10_000.0,
-        shield_health: 300.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 1,
-        seed: 3,
-        trace_mode: TraceMode::Events,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .weapon(500.0, None)
+        .weapon(200.0, None)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(10_000.0)
+        .shield_health(300.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(3)
+        .trace(true)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     let damage_events: Vec<_> = result
         .events
@@ -2869,49 +2815,21 @@ fn sub_round_ordering_weapon_one_damage_after_shield_break() {
 
 #[test]
 fn shots_bonus_increases_damage() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![WeaponStats { attack: 80.0, shots: None }],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.3,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 50_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 3,
-        seed: 42,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .weapons(vec![WeaponStats { attack: 80.0, shots: None }])
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .mitigation(0.3)
+        .hull_health(50_000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(3)
+        .seed(42)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let no_bonus = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
 
     let crew_with_shots_bonus = CrewConfiguration {
@@ -2929,6 +2847,8 @@ fn shots_bonus_increases_damage() {
                         duration_rounds: 3,
                     },
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -2948,44 +2868,17 @@ fn shots_bonus_increases_damage() {
 
 #[test]
 fn shield_break_and_receive_damage_windows_emit_activations() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 400.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 200.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 50.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 2000.0,
-        shield_health: 100.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(400.0)
+        .shield_health(200.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .attack(50.0)
+        .hull_health(2000.0)
+        .shield_health(100.0)
+        .build()
+        .unwrap();
     let crew = CrewConfiguration {
         seats: vec![
             CrewSeatContext {
@@ -2997,6 +2890,8 @@ fn shield_break_and_receive_damage_windows_emit_activations() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -3011,6 +2906,8 @@ fn shield_break_and_receive_damage_windows_emit_activations() {
                     boostable: true,
                     effect: AbilityEffect::PierceBonus(0.1),
                     condition: None,
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -3022,11 +2919,12 @@ fn shield_break_and_receive_damage_windows_emit_activations() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 13,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(13)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
 
@@ -3044,44 +2942,15 @@ fn shield_break_and_receive_damage_windows_emit_activations() {
 
 #[test]
 fn kill_window_emits_activation_and_applies_hull_regen() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 1000.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 120.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 200.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(1000.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .attack(120.0)
+        .hull_health(200.0)
+        .build()
+        .unwrap();
     let crew_with_regen = CrewConfiguration {
         seats: vec![CrewSeatContext {
             seat: CrewSeat::Captain,
@@ -3092,6 +2961,8 @@ fn kill_window_emits_activation_and_applies_hull_regen() {
                 boostable: true,
                 effect: AbilityEffect::OnKillHullRegen(0.25),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -3102,21 +2973,23 @@ fn kill_window_emits_activation_and_applies_hull_regen() {
     let with_regen = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 7,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(7)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew_with_regen,
     );
     let without_regen = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 7,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(7)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &CrewConfiguration::default(),
     );
 
@@ -3133,44 +3006,11 @@ fn kill_window_emits_activation_and_applies_hull_regen() {
 
 #[test]
 fn combat_end_window_respects_condition_filtering() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender").build().unwrap();
     let crew = CrewConfiguration {
         seats: vec![
             CrewSeatContext {
@@ -3182,6 +3022,8 @@ fn combat_end_window_respects_condition_filtering() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: Some(kobayashi::combat::AbilityCondition::RoundRange { min: 1, max: 10 }),
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -3196,6 +3038,8 @@ fn combat_end_window_respects_condition_filtering() {
                     boostable: true,
                     effect: AbilityEffect::AttackMultiplier(0.1),
                     condition: Some(kobayashi::combat::AbilityCondition::RoundRange { min: 999, max: 1000 }),
+                    max_activations: None,
+                    cooldown_rounds: 0,
                 },
                 boosted: false,
                 officer_id: None,
@@ -3207,11 +3051,12 @@ fn combat_end_window_respects_condition_filtering() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 17,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(17)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
     let combat_end_activations: Vec<_> = result
@@ -3227,48 +3072,116 @@ fn combat_end_window_respects_condition_filtering() {
 }
 
 #[test]
-fn stack_resolution_trace_emits_effect_stack_breakdown() {
-    let attacker = Combatant {
-        id: "attacker".to_string(),
-        attack: 100.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![WeaponStats {
-            attack: 80.0,
-            shots: Some(1),
+fn target_tag_condition_applies_only_against_tagged_defenders() {
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .build()
+        .unwrap();
+    let crew = CrewConfiguration {
+        seats: vec![CrewSeatContext {
+            seat: CrewSeat::Captain,
+            ability: Ability {
+                name: "vs_swarm".to_string(),
+                class: AbilityClass::CaptainManeuver,
+                timing: TimingWindow::RoundStart,
+                boostable: true,
+                effect: AbilityEffect::AttackMultiplier(0.5),
+                condition: Some(kobayashi::combat::AbilityCondition::TargetTag(
+                    "swarm".to_string(),
+                )),
+                max_activations: None,
+                cooldown_rounds: 0,
+            },
+            boosted: false,
+            officer_id: None,
+            contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
         }],
     };
-    let defender = Combatant {
-        id: "defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1_000_000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let config = SimulationConfig::builder()
+        .rounds(1)
+        .seed(5)
+        .trace(true)
+        .build();
+    let fight = |defender: Combatant| simulate_combat(&attacker, &defender, config, &crew);
+
+    let swarm = fight(
+        Combatant::builder("swarm")
+            .hull_health(1_000_000.0)
+            .tags(vec!["interceptor".to_string(), "Swarm".to_string()])
+            .build()
+            .unwrap(),
+    );
+    let other = fight(
+        Combatant::builder("other")
+            .hull_health(1_000_000.0)
+            .tags(vec!["interceptor".to_string()])
+            .build()
+            .unwrap(),
+    );
+
+    let activations = |events: &[CombatEvent]| {
+        events
+            .iter()
+            .filter(|e| {
+                e.event_type == "ability_activation"
+                    && e.source.ship_ability_id.as_deref() == Some("vs_swarm")
+            })
+            .count()
+    };
+    assert_eq!(activations(&swarm.events), 1);
+    assert_eq!(activations(&other.events), 0);
+    assert!(swarm.total_damage > other.total_damage);
+}
+
+#[test]
+fn armada_charged_attack_fires_each_full_bar_through_mitigation_and_shields() {
+    let attacker = Combatant::builder("attacker")
+        .attack(10.0)
+        .mitigation(0.5)
+        .hull_health(10_000.0)
+        .shield_health(400.0)
+        .shield_mitigation(0.8)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("armada")
+        .hull_health(1_000_000.0)
+        .charged_attack(3, 1000.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder().rounds(7).seed(3).trace(true).build();
+    let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
+
+    let charges: Vec<&CombatEvent> = result
+        .events
+        .iter()
+        .filter(|e| e.event_type == "charged_attack")
+        .collect();
+    assert_eq!(
+        charges.iter().map(|e| e.round_index).collect::<Vec<_>>(),
+        [3, 6]
+    );
+    // 1000 × (1 − 0.5) = 500: 400 to shields and 100 to hull, then all 500 to hull.
+    assert_eq!(charges[0].values["shield_damage"], 400.0);
+    assert_eq!(charges[0].values["hull_damage"], 100.0);
+    assert_eq!(charges[1].values["hull_damage"], 500.0);
+    approx_eq(result.attacker_hull_remaining, 9_400.0, 1e-9);
+
+    let plain = Combatant::builder("hostile").hull_health(1_000_000.0).build().unwrap();
+    let calm = simulate_combat(&attacker, &plain, config, &CrewConfiguration::default());
+    approx_eq(calm.attacker_hull_remaining, 10_000.0, 1e-9);
+}
+
+#[test]
+fn stack_resolution_trace_emits_effect_stack_breakdown() {
+    let attacker = Combatant::builder("attacker")
+        .attack(100.0)
+        .weapon(80.0, Some(1))
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("defender")
+        .hull_health(1_000_000.0)
+        .build()
+        .unwrap();
 
     let crew = CrewConfiguration {
         seats: vec![CrewSeatContext {
@@ -3280,6 +3193,8 @@ fn stack_resolution_trace_emits_effect_stack_breakdown() {
                 boostable: false,
                 effect: AbilityEffect::AttackMultiplier(0.25),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
@@ -3290,11 +3205,12 @@ fn stack_resolution_trace_emits_effect_stack_breakdown() {
     let result = simulate_combat(
         &attacker,
         &defender,
-        SimulationConfig {
-            rounds: 1,
-            seed: 3,
-            trace_mode: TraceMode::Events,
-        },
+        SimulationConfig::builder()
+            .rounds(1)
+            .seed(3)
+            .trace(true)
+            .rng_mode(RngMode::Legacy)
+            .build(),
         &crew,
     );
 
@@ -4,7 +4,8 @@ use std::path::Path;
 
 use kobayashi::combat::{
     parse_combat_log_json, parity_within_tolerance, ingested_to_comparable,
-    ingested_events_to_combat_events, IngestedCombatLog,
+    ingested_events_to_combat_events, parse_combat_log_json_with_mapping, IngestedCombatLog,
+    LogColumnMapping,
 };
 
 fn fixture_path(name: &str) -> std::path::PathBuf {
@@ -89,6 +90,9 @@ fn parity_within_tolerance_matches_when_close() {
         attacker_hull_remaining: 1000.0,
         defender_hull_remaining: 0.0,
         defender_shield_remaining: 0.0,
+        attacker_shield_remaining: 0.0,
+        attacker_damage: Default::default(),
+        defender_damage: Default::default(),
         events: vec![],
     };
     assert!(parity_within_tolerance(&sim, &log, 1.0, 1.0));
@@ -129,3 +133,48 @@ fn parse_multi_weapon_round_fixture() {
         .collect();
     assert_eq!(w_idx, vec![Some(0), Some(1)]);
 }
+
+#[test]
+fn community_log_variants_are_mapped_automatically() {
+    let camel = parse_combat_log_json(
+        &std::fs::read_to_string(fixture_path("community_camel_case_log.json")).expect("read"),
+    )
+    .expect("camelCase export parses");
+    let round_log = parse_combat_log_json(
+        &std::fs::read_to_string(fixture_path("community_round_log.json")).expect("read"),
+    )
+    .expect("round log export parses");
+
+    // Both describe the same fight; mitigation percentages are scaled to fractions.
+    assert_eq!(camel, round_log);
+    assert_eq!(camel.rounds_simulated, 1);
+    assert!((camel.defender_hull_remaining - 85.0).abs() < 1e-9);
+    let types: Vec<&str> = camel.events.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(types, ["round_start", "mitigation_calc", "damage_application"]);
+    assert_eq!(camel.events[1].values["mitigation"], 0.5);
+    assert_eq!(camel.events[2].weapon_index, Some(0));
+    assert_eq!(camel.events[2].values["isolytic_damage"], 2.5);
+    assert_eq!(camel.events[2].values["hull_damage"], 15.0);
+}
+
+#[test]
+fn custom_mapping_file_renames_log_columns() {
+    let mapping: LogColumnMapping = serde_json::from_str(
+        r#"{
+            "name": "custom",
+            "fields": {"r": "rounds_simulated", "dmg": "total_damage", "won": "attacker_won",
+                       "hull": "defender_hull_remaining", "evts": "events"},
+            "event_fields": {"t": "event_type", "n": "round_index", "p": "phase"},
+            "values": {"mit": "mitigation"}
+        }"#,
+    )
+    .expect("mapping");
+    let json = r#"{"r":1,"dmg":10.0,"won":true,"hull":0.0,
+        "evts":[{"t":"mitigation_calc","n":1,"p":"defense","values":{"mit":0.4}}]}"#;
+
+    assert!(parse_combat_log_json(json).is_err(), "no built-in mapping knows these keys");
+    let log = parse_combat_log_json_with_mapping(json, &mapping).expect("mapped");
+    assert!(log.attacker_won);
+    assert_eq!(log.events[0].event_type, "mitigation_calc");
+    assert_eq!(log.events[0].values["mitigation"], 0.4);
+}
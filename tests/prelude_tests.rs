@@ -0,0 +1,52 @@
+//! The prelude alone is enough to run a fight and rank crews.
+
+use kobayashi::prelude::*;
+
+fn crew_stats(captain: &str, win_rate: f64) -> CrewSimulationResult {
+    CrewSimulationResult {
+        candidate: CrewCandidate::new(captain.to_string(), vec!["spock".to_string()], vec![]),
+        win_rate,
+        stall_rate: 0.0,
+        loss_rate: 1.0 - win_rate,
+        avg_hull_remaining: win_rate,
+        avg_survival_share: 1.0,
+        avg_damage_dealt: win_rate,
+        survival_rate: win_rate,
+        avg_waves_cleared: win_rate,
+        median_rounds_to_kill: None,
+        p95_rounds_to_kill: None,
+        avg_damage_per_round: 0.0,
+        avg_hull_lost: 1.0 - win_rate,
+    }
+}
+
+#[test]
+fn prelude_runs_a_fight_and_ranks_crews() {
+    let attacker = Combatant::builder("player")
+        .attack(200.0)
+        .pierce(0.2)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("hostile")
+        .mitigation(0.3)
+        .hull_health(300.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(5)
+        .seed(11)
+        .trace(true)
+        .build();
+    let result: SimulationResult =
+        simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
+    assert!(result.total_damage > 0.0);
+    assert!(!result.events.is_empty());
+
+    let ranked = rank_results(vec![crew_stats("kirk", 0.4), crew_stats("pike", 0.9)]);
+    assert_eq!(ranked[0].captain, "pike");
+    assert!(!ranked[0].crew_id.is_empty());
+    assert_eq!(
+        OptimizationScenario::default().scenario_type,
+        ScenarioType::Offense
+    );
+}
@@ -5,9 +5,9 @@
 use std::path::Path;
 
 use kobayashi::combat::{
-    export_to_combat_input, parse_fight_export, simulate_combat, Ability, AbilityClass,
-    AbilityEffect, Combatant, CrewConfiguration, CrewSeat, CrewSeatContext, ShipType,
-    SimulationConfig, TimingWindow, TraceMode, NO_EXPLICIT_CONTRIBUTION_BATCH,
+    export_to_combat_input, export_to_hostile_record, parse_fight_export, simulate_combat, Ability, AbilityClass,
+    AbilityEffect, Combatant, CrewConfiguration, CrewSeat, CrewSeatContext, RngMode, ShipType,
+    SimulationConfig, TimingWindow, NO_EXPLICIT_CONTRIBUTION_BATCH,
 };
 
 fn fixture_path(name: &str) -> std::path::PathBuf {
@@ -22,49 +22,22 @@ fn fixture_path(name: &str) -> std::path::PathBuf {
 /// Asserts simulator output is within expected bounds for regression and formula tuning.
 #[test]
 fn calibration_scenario_outcome_within_tolerance() {
-    let attacker = Combatant {
-        id: "cal_attacker".to_string(),
-        attack: 250.0,
-        mitigation: 0.0,
-        pierce: 0.12,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1000.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "cal_defender".to_string(),
-        attack: 0.0,
-        mitigation: 0.2,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 400.0,
-        shield_health: 200.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let config = SimulationConfig {
-        rounds: 10,
-        seed: 42,
-        trace_mode: TraceMode::Off,
-    };
+    let attacker = Combatant::builder("cal_attacker")
+        .attack(250.0)
+        .pierce(0.12)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("cal_defender")
+        .mitigation(0.2)
+        .hull_health(400.0)
+        .shield_health(200.0)
+        .build()
+        .unwrap();
+    let config = SimulationConfig::builder()
+        .rounds(10)
+        .seed(42)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
 
     assert!(
@@ -119,11 +92,11 @@ fn fight_export_realta_vs_takret_militia_10_matches_simulation() {
     );
 
     let (attacker, defender, crew) = export_to_combat_input(&export);
-    let config = SimulationConfig {
-        rounds: 10,
-        seed: 42,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(10)
+        .seed(42)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let result = simulate_combat(&attacker, &defender, config, &crew);
 
     assert_eq!(
@@ -155,46 +128,53 @@ fn fight_export_realta_vs_takret_militia_10_matches_simulation() {
     );
 }
 
+/// The enemy of a recorded fight becomes a hostile record the loader resolves by id or name.
+#[test]
+fn fight_export_enemy_becomes_a_resolvable_hostile_record() {
+    use kobayashi::data::hostile::{load_hostile_index, save_hostile_record};
+    use kobayashi::data::loader::resolve_hostile_with_index;
+
+    let path = fight_sample_path("realta vs takret militia 10.csv");
+    let export = parse_fight_export(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(export.enemy_name.as_deref(), Some("Takret Militia"));
+    assert_eq!(export.defender_ship_type, ShipType::Battleship);
+
+    let record = export_to_hostile_record(&export);
+    assert_eq!(record.id, "fight_takret_militia_10");
+    assert_eq!((record.level, record.ship_class.as_str()), (10, "battleship"));
+    assert_eq!((record.hull_health, record.shield_health), (470.0, 360.0));
+    assert_eq!((record.armor, record.shield_deflection, record.dodge), (105.0, 26.0, 26.0));
+    assert_eq!((record.dpr, record.crit_chance), (460.0, 0.1));
+    // Realta's only shot into the shield split 1387 / 369.
+    let shield_mitigation = record.shield_mitigation.expect("a shot hit the enemy shield");
+    assert!((shield_mitigation - 1387.0 / 1756.0).abs() < 1e-9);
+    assert_eq!(record.shots_per_round, Some(2.0));
+
+    let data_dir =
+        std::env::temp_dir().join(format!("kobayashi-fight-hostile-{}", std::process::id()));
+    save_hostile_record(&data_dir, &record).expect("record should be written");
+    save_hostile_record(&data_dir, &record).expect("saving again replaces the entry");
+    let index = load_hostile_index(data_dir.join("index.json").to_str().unwrap()).unwrap();
+    assert_eq!(index.hostiles.len(), 1);
+    let resolved = resolve_hostile_with_index(&index, &data_dir, "takret militia 10")
+        .expect("resolved by name and level");
+    let _ = std::fs::remove_dir_all(&data_dir);
+    assert_eq!(resolved.id, record.id);
+    assert_eq!(resolved.shots_per_round, Some(2.0));
+}
+
 #[test]
 fn calibration_on_kill_hull_regen_improves_survivability_within_bounds() {
-    let attacker = Combatant {
-        id: "cal_attacker".to_string(),
-        attack: 650.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 1200.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
-    let defender = Combatant {
-        id: "cal_defender".to_string(),
-        attack: 200.0,
-        mitigation: 0.0,
-        pierce: 0.0,
-        crit_chance: 0.0,
-        crit_multiplier: 1.0,
-        proc_chance: 0.0,
-        proc_multiplier: 1.0,
-        end_of_round_damage: 0.0,
-        hull_health: 400.0,
-        shield_health: 0.0,
-        shield_mitigation: 0.8,
-        apex_barrier: 0.0,
-        apex_shred: 0.0,
-        isolytic_damage: 0.0,
-        isolytic_defense: 0.0,
-        weapons: vec![],
-    };
+    let attacker = Combatant::builder("cal_attacker")
+        .attack(650.0)
+        .hull_health(1200.0)
+        .build()
+        .unwrap();
+    let defender = Combatant::builder("cal_defender")
+        .attack(200.0)
+        .hull_health(400.0)
+        .build()
+        .unwrap();
     let with_kill_regen = CrewConfiguration {
         seats: vec![CrewSeatContext {
             seat: CrewSeat::Captain,
@@ -205,17 +185,19 @@ fn calibration_on_kill_hull_regen_improves_survivability_within_bounds() {
                 boostable: true,
                 effect: AbilityEffect::OnKillHullRegen(0.2),
                 condition: None,
+                max_activations: None,
+                cooldown_rounds: 0,
             },
             boosted: false,
             officer_id: None,
             contribution_batch: NO_EXPLICIT_CONTRIBUTION_BATCH,
         }],
     };
-    let config = SimulationConfig {
-        rounds: 2,
-        seed: 21,
-        trace_mode: TraceMode::Off,
-    };
+    let config = SimulationConfig::builder()
+        .rounds(2)
+        .seed(21)
+        .rng_mode(RngMode::Legacy)
+        .build();
     let baseline = simulate_combat(&attacker, &defender, config, &CrewConfiguration::default());
     let with_regen = simulate_combat(&attacker, &defender, config, &with_kill_regen);
 
@@ -1,7 +1,15 @@
+#![cfg(feature = "server")]
+
 use axum::body::Body;
 use axum::http::{Method, Request};
 use kobayashi::data::data_registry::DataRegistry;
-use kobayashi::server::routes::build_router;
+use kobayashi::parallel::Priority;
+use kobayashi::server::assets::AssetProxy;
+use kobayashi::server::cors::CorsOrigins;
+use kobayashi::server::limits::RequestLimits;
+use kobayashi::server::notify::WebhookPolicy;
+use kobayashi::server::routes::{build_router, router_with_state, AppState};
+use std::time::Duration;
 use tower::ServiceExt;
 
 struct TestResponse {
@@ -16,6 +24,7 @@ async fn route_request(method: &str, path: &str, body: &str, _headers: Option<()
     let m = match method {
         "POST" => Method::POST,
         "PUT" => Method::PUT,
+        "DELETE" => Method::DELETE,
         _ => Method::GET,
     };
     let req = Request::builder()
@@ -45,6 +54,23 @@ async fn health_endpoint_returns_ok_json() {
     assert_eq!(response.status_code, 200);
     assert_eq!(response.content_type, "application/json");
     assert!(response.body.contains("\"status\": \"ok\""));
+    let health: serde_json::Value = serde_json::from_str(&response.body).expect("health json");
+    assert_eq!(health["live"], true);
+    assert_eq!(health["ready"], true);
+    for dataset in ["officers", "ships", "hostiles"] {
+        let entry = &health["datasets"][dataset];
+        assert_eq!(entry["loaded"], true, "{dataset}");
+        assert!(entry["records"].as_u64().unwrap() > 0, "{dataset}");
+        assert_eq!(entry["content_hash"].as_str().unwrap().len(), 16, "{dataset}");
+    }
+    let workers = &health["workers"];
+    assert!(workers["simulation_threads"].as_u64().unwrap() >= 1);
+    assert!(workers["cpu_job_slots"].as_u64().unwrap() >= 1);
+    assert_eq!(workers["cpu_jobs_running"], 0);
+    assert!(workers["optimize_jobs_running"].is_u64());
+
+    let ready = route_request("GET", "/api/health/ready", "", None).await;
+    assert_eq!(ready.status_code, 200, "{}", ready.body);
 }
 
 #[tokio::test]
@@ -69,9 +95,29 @@ async fn profile_research_summary_returns_json() {
     assert!(payload["research"].is_array());
 }
 
+#[tokio::test]
+async fn profile_put_rejects_unknown_and_out_of_range_bonuses_per_field() {
+    let body = r#"{"bonuses":{"weapon_damage":-2,"crit_chance":0.1,"warp_speed":1}}"#;
+    let response = route_request("PUT", "/api/profile", body, None).await;
+    assert_eq!(response.status_code, 400);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("validation json");
+    let errors = payload["errors"].as_array().expect("errors array");
+    let fields: Vec<&str> = errors.iter().filter_map(|e| e["field"].as_str()).collect();
+    assert_eq!(fields, vec!["bonuses", "bonuses.weapon_damage"]);
+    assert!(errors[0]["messages"][0].as_str().unwrap().contains("warp_speed"));
+    assert!(errors[1]["messages"][0].as_str().unwrap().contains("0.25 = +25%"));
+
+    let body = r#"{"bonuses":{},"target_bonuses":{"armada":{"crit_chance":2}}}"#;
+    let response = route_request("PUT", "/api/profile", body, None).await;
+    assert_eq!(response.status_code, 400);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("validation json");
+    assert_eq!(payload["errors"][0]["field"], "target_bonuses.armada");
+    assert!(payload["errors"][0]["messages"][0].as_str().unwrap().starts_with("crit_chance:"));
+}
+
 #[tokio::test]
 async fn optimize_endpoint_returns_ranked_recommendations() {
-    let body = r#"{"ship":"saladin","hostile":"2918121098","sims":2000,"seed":7,"max_candidates":64}"#;
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":2000,"seed":7,"max_candidates":64}"#;
     let response = route_request("POST", "/api/optimize", body, None).await;
 
     assert_eq!(response.status_code, 200);
@@ -99,6 +145,15 @@ async fn optimize_endpoint_returns_ranked_recommendations() {
     assert!(first["below_decks"].as_array().is_some(), "below_decks should be an array");
     assert!(first["win_rate"].as_f64().is_some());
     assert!(first["avg_hull_remaining"].as_f64().is_some());
+    assert!(first["avg_damage_per_round"].as_f64().is_some_and(|d| d >= 0.0));
+    if let Some(median) = first["median_rounds_to_kill"].as_u64() {
+        let p95 = first["p95_rounds_to_kill"]
+            .as_u64()
+            .expect("p95 is set whenever the median is");
+        assert!(median >= 1 && median <= p95, "median {median}, p95 {p95}");
+    } else {
+        assert!(first["median_rounds_to_kill"].is_null());
+    }
 
     let mut prior_score: Option<f64> = None;
     let mut saw_non_trivial_metric = false;
@@ -126,19 +181,41 @@ async fn optimize_endpoint_returns_ranked_recommendations() {
     );
 }
 
+#[tokio::test]
+async fn optimize_endpoint_reports_officer_pools_for_below_decks_filter() {
+    let pools = |filter: bool| async move {
+        let body = format!(
+            r#"{{"strict":false,"ship":"saladin","hostile":"2918121098","sims":50,"seed":7,"max_candidates":8,"explain_top":0,"only_below_decks_with_ability":{filter}}}"#
+        );
+        let response = route_request("POST", "/api/optimize", &body, None).await;
+        assert_eq!(response.status_code, 200, "{}", response.body);
+        let payload: serde_json::Value =
+            serde_json::from_str(&response.body).expect("response should be valid json");
+        payload["scenario"]["officer_pools"].clone()
+    };
+    let all = pools(false).await;
+    let filtered = pools(true).await;
+    assert_eq!(all["only_below_decks_with_ability"], false);
+    assert_eq!(filtered["only_below_decks_with_ability"], true);
+    assert_eq!(all["captains"], filtered["captains"]);
+    assert_eq!(all["bridge"], filtered["bridge"]);
+    let below = |p: &serde_json::Value| p["below_decks"].as_u64().expect("below decks");
+    assert!(below(&filtered) > 0 && below(&filtered) < below(&all), "{all} vs {filtered}");
+}
+
 #[tokio::test]
 async fn optimize_endpoint_changes_with_seed() {
     let response_a = route_request(
         "POST",
         "/api/optimize",
-        r#"{"ship":"saladin","hostile":"2918121098","sims":1000,"seed":7,"max_candidates":32}"#,
+        r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":1000,"seed":7,"max_candidates":32}"#,
         None,
     )
     .await;
     let response_b = route_request(
         "POST",
         "/api/optimize",
-        r#"{"ship":"saladin","hostile":"2918121098","sims":1000,"seed":8,"max_candidates":32}"#,
+        r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":1000,"seed":8,"max_candidates":32}"#,
         None,
     )
     .await;
@@ -150,7 +227,7 @@ async fn optimize_endpoint_changes_with_seed() {
 
 #[tokio::test]
 async fn optimize_endpoint_is_deterministic_for_fixed_seed() {
-    let body = r#"{"ship":"saladin","hostile":"2918121098","sims":2000,"seed":77,"max_candidates":64}"#;
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":2000,"seed":77,"max_candidates":64}"#;
 
     let response_a = route_request("POST", "/api/optimize", body, None).await;
     let response_b = route_request("POST", "/api/optimize", body, None).await;
@@ -234,6 +311,83 @@ async fn optimize_endpoint_rejects_zero_sims() {
     assert!(errors.iter().any(|error| error["field"] == "sims"));
 }
 
+#[tokio::test]
+async fn optimize_endpoint_applies_genetic_overrides() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":50,"seed":5,"strategy":"genetic","explain_top":0,"ga":{"population_size":6,"generations":2,"mutation_rate":0.5,"elitism_count":1,"stagnation_limit":0}}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["engine"], "genetic");
+    let recommendations = payload["recommendations"].as_array().expect("recommendations");
+    // The GA keeps its best generation; it cannot hold more crews than the population.
+    assert!((1..=6).contains(&recommendations.len()), "{}", recommendations.len());
+    let notes = payload["notes"].as_array().expect("notes");
+    assert!(
+        notes.iter().any(|n| n.as_str().is_some_and(|n| n.starts_with("GA fitness cache: "))),
+        "{notes:?}"
+    );
+    assert!(
+        notes.iter().any(|n| n.as_str().is_some_and(|n| n.starts_with("Memory: peak "))),
+        "{notes:?}"
+    );
+}
+
+#[tokio::test]
+async fn optimize_endpoint_auto_strategy_picks_exhaustive_and_cross_checks_with_ga() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":50,"seed":5,"max_candidates":24,"strategy":"auto","explain_top":0,"ga":{"population_size":6,"generations":2}}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["engine"], "optimizer_v1");
+    let auto = &payload["auto_strategy"];
+    assert_eq!(auto["chosen"], "exhaustive");
+    assert!((1..=24).contains(&auto["estimated_candidates"].as_u64().expect("count")));
+    let agreement = &auto["agreement"];
+    assert_eq!(
+        agreement["agrees"].as_bool().expect("agrees"),
+        agreement["exhaustive_crew_id"] == agreement["genetic_crew_id"]
+    );
+    assert_eq!(
+        agreement["exhaustive_crew_id"],
+        payload["recommendations"][0]["crew_id"]
+    );
+}
+
+#[tokio::test]
+async fn optimize_endpoint_rejects_out_of_range_genetic_overrides() {
+    let response = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","ga":{"population_size":1,"mutation_rate":1.5,"elitism_count":4}}"#,
+        None,
+    )
+    .await;
+    assert_eq!(response.status_code, 400);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    let fields: Vec<&str> = payload["errors"]
+        .as_array()
+        .expect("errors should be array")
+        .iter()
+        .map(|error| error["field"].as_str().expect("field"))
+        .collect();
+    assert_eq!(
+        fields,
+        ["ga.population_size", "ga.mutation_rate", "ga.elitism_count", "ga"]
+    );
+
+    let unknown = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","strategy":"genetic","ga":{"pop":10}}"#,
+        None,
+    )
+    .await;
+    assert_eq!(unknown.status_code, 400);
+}
+
 #[tokio::test]
 async fn optimize_endpoint_rejects_very_large_sims() {
     let response = route_request(
@@ -287,6 +441,474 @@ async fn optimize_endpoint_rejects_excessive_max_candidates() {
     );
 }
 
+#[tokio::test]
+async fn optimize_endpoint_runs_tiered_strategy_and_validates_top_k() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":200,"seed":3,"max_candidates":24,"strategy":"tiered","tiered_scout_sims":50,"tiered_top_k":5}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["engine"], "tiered");
+    let recommendations = payload["recommendations"]
+        .as_array()
+        .expect("recommendations should be array");
+    assert!(!recommendations.is_empty() && recommendations.len() <= 5);
+
+    let rejected = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","sims":200,"strategy":"tiered","tiered_top_k":0}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("tiered_top_k"));
+}
+
+#[tokio::test]
+async fn optimize_endpoint_runs_two_phase_strategy_and_fills_below_decks() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":100,"seed":3,"max_candidates":12,"strategy":"two_phase","tiered_scout_sims":20,"tiered_top_k":3}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["engine"], "two_phase");
+    let recommendations = payload["recommendations"]
+        .as_array()
+        .expect("recommendations should be array");
+    assert!(!recommendations.is_empty() && recommendations.len() <= 3);
+    for rec in recommendations {
+        assert_eq!(rec["below_decks"].as_array().map(Vec::len), Some(3), "{rec}");
+    }
+}
+
+#[tokio::test]
+async fn optimize_endpoint_ranks_defense_scenarios_by_survival() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":200,"seed":5,"max_candidates":16,"scenario_type":"defense"}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["scenario"]["scenario_type"], "defense");
+    let recommendations = payload["recommendations"]
+        .as_array()
+        .expect("recommendations should be array");
+    let scores: Vec<f64> = recommendations
+        .iter()
+        .map(|r| {
+            r["avg_survival_share"].as_f64().expect("survival share") * 0.6
+                + r["avg_damage_dealt"].as_f64().expect("damage dealt") * 0.4
+        })
+        .collect();
+    assert!(scores.windows(2).all(|w| w[0] + 1e-6 >= w[1]), "{scores:?}");
+
+    let rejected = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","sims":200,"scenario_type":"siege"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("scenario_type"));
+}
+
+#[tokio::test]
+async fn optimize_endpoint_ranks_mining_scenarios_by_survival_rate() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":200,"seed":5,"max_candidates":16,"scenario_type":"mining","mining_rounds":3}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["scenario"]["scenario_type"], "mining");
+    assert_eq!(payload["scenario"]["mining_rounds"], 3);
+    let rates: Vec<f64> = payload["recommendations"]
+        .as_array()
+        .expect("recommendations should be array")
+        .iter()
+        .map(|r| r["survival_rate"].as_f64().expect("survival rate"))
+        .collect();
+    assert!(!rates.is_empty());
+    assert!(rates.windows(2).all(|w| w[0] >= w[1]), "{rates:?}");
+
+    let rejected = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","sims":200,"mining_rounds":3}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("mining_rounds"));
+}
+
+#[tokio::test]
+async fn optimize_endpoint_ranks_wave_scenarios_by_waves_cleared() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":100,"seed":5,"max_candidates":8,"scenario_type":"waves","waves":3,"wave_repair":0.25}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["scenario"]["scenario_type"], "waves");
+    assert_eq!(payload["scenario"]["waves"], 3);
+    let cleared: Vec<f64> = payload["recommendations"]
+        .as_array()
+        .expect("recommendations should be array")
+        .iter()
+        .map(|r| r["avg_waves_cleared"].as_f64().expect("waves cleared"))
+        .collect();
+    assert!(!cleared.is_empty());
+    assert!(cleared.iter().all(|c| (0.0..=3.0).contains(c)), "{cleared:?}");
+    assert!(cleared.windows(2).all(|w| w[0] >= w[1]), "{cleared:?}");
+
+    let rejected = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","sims":100,"scenario_type":"waves","wave_repair":1.5}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("wave_repair"));
+}
+
+#[tokio::test]
+async fn optimize_endpoint_explains_top_recommendations() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":100,"seed":5,"max_candidates":8,"explain_top":2}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    let recommendations = payload["recommendations"].as_array().expect("recommendations");
+    assert!(recommendations.len() > 2);
+    for rec in &recommendations[..2] {
+        let explanation = &rec["explanation"];
+        assert_eq!(explanation["sims"], 100);
+        let seated = 1
+            + rec["bridge"].as_array().map_or(0, Vec::len)
+            + rec["below_decks"].as_array().map_or(0, Vec::len);
+        let rows = explanation["contributions"].as_array().expect("contributions");
+        assert_eq!(rows.len(), seated, "{explanation}");
+        let shares: Vec<f64> = rows.iter().map(|r| r["damage_share"].as_f64().unwrap()).collect();
+        assert!(shares.windows(2).all(|w| w[0] >= w[1]), "{shares:?}");
+        assert!(explanation["status_uptimes"].is_array());
+    }
+    assert!(recommendations[2].get("explanation").is_none());
+
+    let rejected = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","sims":100,"explain_top":11}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("explain_top"));
+}
+
+#[tokio::test]
+async fn optimize_endpoint_estimates_repair_cost_from_hull_lost() {
+    let body = r#"{"strict":false,"ship":"uss_saladin","ship_tier":2,"hostile":"2918121098","sims":50,"seed":5,"max_candidates":4}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    let recommendations = payload["recommendations"].as_array().expect("recommendations");
+    assert!(!recommendations.is_empty());
+    for rec in recommendations {
+        let hull_lost = rec["avg_hull_lost"].as_f64().expect("avg_hull_lost");
+        assert!((0.0..=1.0).contains(&hull_lost), "{rec}");
+        let repair = &rec["expected_repair"];
+        assert_eq!(repair["seconds"].as_f64(), Some((3672.0 * hull_lost).round()), "{rec}");
+        assert_eq!(
+            repair["resources"]["tritanium"].as_f64(),
+            Some((11625.0 * hull_lost).round()),
+            "{rec}"
+        );
+    }
+
+    let tier_one = r#"{"strict":false,"ship":"uss_saladin","ship_tier":1,"hostile":"2918121098","sims":50,"seed":5,"max_candidates":4}"#;
+    let response = route_request("POST", "/api/optimize", tier_one, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    assert!(!response.body.contains("expected_repair"));
+}
+
+#[tokio::test]
+async fn optimize_endpoint_ranks_points_per_hour_scenarios() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":100,"seed":5,"max_candidates":8,"scenario_type":"points_per_hour"}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["scenario"]["scenario_type"], "points_per_hour");
+    // No reward metadata on this hostile: one point per kill, so the rate is kills per hour.
+    assert_eq!(payload["scenario"]["points_per_kill"], 1.0);
+    let rates: Vec<f64> = payload["recommendations"]
+        .as_array()
+        .expect("recommendations should be array")
+        .iter()
+        .map(|r| r["points_per_hour"].as_f64().expect("points per hour"))
+        .collect();
+    assert!(!rates.is_empty());
+    assert!(rates.windows(2).all(|w| w[0] + 1e-6 >= w[1]), "{rates:?}");
+}
+
+#[tokio::test]
+async fn officer_boosts_endpoint_ranks_each_crews_officers() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":100,"seed":3,"max_candidates":8,"top_crews":2}"#;
+    let response = route_request("POST", "/api/analyze/officer-boosts", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["status"], "ok");
+    let crews = payload["crews"].as_array().expect("crews should be array");
+    assert_eq!(crews.len(), 2);
+    for crew in crews {
+        let unboosted = crew["unboosted_win_rate"].as_f64().expect("unboosted");
+        let options = crew["options"].as_array().expect("options should be array");
+        assert!(!options.is_empty(), "{crew}");
+        let rates: Vec<f64> = options
+            .iter()
+            .map(|o| o["win_rate"].as_f64().expect("win rate"))
+            .collect();
+        assert!(rates.windows(2).all(|w| w[0] >= w[1]), "{rates:?}");
+        for option in options {
+            let gain = option["win_rate_gain"].as_f64().expect("gain");
+            assert!((option["win_rate"].as_f64().unwrap() - unboosted - gain).abs() < 1e-12);
+        }
+    }
+
+    let rejected = route_request(
+        "POST",
+        "/api/analyze/officer-boosts",
+        r#"{"ship":"saladin","hostile":"2918121098","top_crews":0}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("top_crews"));
+}
+
+#[tokio::test]
+async fn officer_leaderboard_endpoint_ranks_officers_per_seat() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":20,"seed":3,"samples":4}"#;
+    let response = route_request("POST", "/api/analyze/officer-leaderboard", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["status"], "ok");
+    assert_eq!(payload["sampled_crews"], 4);
+    let seats = payload["seats"].as_array().expect("seats should be array");
+    let kinds: Vec<&str> = seats.iter().map(|s| s["seat"].as_str().expect("seat")).collect();
+    assert_eq!(kinds, ["captain", "bridge", "below_decks"]);
+
+    // Marginals telescope: per sample they sum to full crew minus empty crew.
+    let mut total = 0.0;
+    let mut captains = 0;
+    for seat in seats {
+        let officers = seat["officers"].as_array().expect("officers should be array");
+        let rates: Vec<f64> = officers
+            .iter()
+            .map(|o| o["marginal_win_rate"].as_f64().expect("marginal"))
+            .collect();
+        assert!(rates.windows(2).all(|w| w[0] >= w[1]), "{rates:?}");
+        for officer in officers {
+            let samples = officer["samples"].as_u64().expect("samples");
+            total += officer["marginal_win_rate"].as_f64().unwrap() * samples as f64;
+            if seat["seat"] == "captain" {
+                captains += samples;
+            }
+        }
+    }
+    assert_eq!(captains, 4);
+    let empty = payload["empty_crew_win_rate"].as_f64().expect("empty");
+    let mean = payload["mean_crew_win_rate"].as_f64().expect("mean");
+    assert!((total - 4.0 * (mean - empty)).abs() < 1e-9, "{total} vs {mean} - {empty}");
+
+    let rejected = route_request(
+        "POST",
+        "/api/analyze/officer-leaderboard",
+        r#"{"ship":"saladin","hostile":"2918121098","samples":0}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("samples"));
+}
+
+#[tokio::test]
+async fn officer_upgrades_endpoint_ranks_promotable_officers() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":100,"seed":3,"max_candidates":8,"top_crews":2}"#;
+    let response = route_request("POST", "/api/analyze/officer-upgrades", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["status"], "ok");
+    assert_eq!(payload["crews_considered"], 2);
+    let baseline = payload["baseline_win_rate"].as_f64().expect("baseline");
+    let upgrades = payload["upgrades"].as_array().expect("upgrades should be array");
+    assert!(!upgrades.is_empty());
+    for upgrade in upgrades {
+        let current = upgrade["current_tier"].as_u64().expect("current tier");
+        assert_eq!(upgrade["next_tier"].as_u64(), Some(current + 1));
+        let before = upgrade["win_rate_before"].as_f64().expect("before");
+        assert!(before <= baseline + 1e-12, "{upgrade}");
+        let gain = upgrade["win_rate_gain"].as_f64().expect("gain");
+        assert!((-1.0..=1.0).contains(&gain), "{upgrade}");
+    }
+    let values: Vec<f64> = upgrades
+        .iter()
+        .filter_map(|u| u["gain_per_100_shards"].as_f64())
+        .collect();
+    assert!(values.windows(2).all(|w| w[0] >= w[1]), "{values:?}");
+
+    let rejected = route_request(
+        "POST",
+        "/api/analyze/officer-upgrades",
+        r#"{"ship":"saladin","hostile":"2918121098","top_crews":0}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("top_crews"));
+}
+
+#[tokio::test]
+async fn stats_endpoint_reports_sensitivity_for_each_stat() {
+    let body = r#"{"strict":false,"ship":"enterprise","hostile":"swarm_32","num_sims":200,"seed":11,"delta_pct":0.2,
+        "crew":{"captain":"kirk-1323b6","bridge":["spock-c04738"],"below_deck":[]}}"#;
+    let response = route_request("POST", "/api/analyze/stats", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["delta_pct"], 0.2);
+    assert!(payload["baseline_win_rate"].as_f64().is_some());
+    let stats = payload["stats"].as_array().expect("stats should be array");
+    let mut names: Vec<&str> = stats.iter().filter_map(|s| s["stat"].as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, ["attack", "crit_chance", "hull_health", "pierce"]);
+    let slopes: Vec<f64> = stats
+        .iter()
+        .map(|s| s["win_rate_per_pct"].as_f64().expect("slope"))
+        .collect();
+    assert!(slopes.windows(2).all(|w| w[0] >= w[1]), "{slopes:?}");
+    let attack = stats.iter().find(|s| s["stat"] == "attack").expect("attack row");
+    assert!(
+        attack["win_rate_up"].as_f64() >= attack["win_rate_down"].as_f64(),
+        "{attack}"
+    );
+
+    for (bad, field) in [
+        (r#""delta_pct":1.5,"crew":{"captain":"kirk-1323b6"}"#, "delta_pct"),
+        (r#""crew":{"bridge":["spock-c04738"]}"#, "crew.captain"),
+    ] {
+        let rejected = route_request(
+            "POST",
+            "/api/analyze/stats",
+            &format!(r#"{{"ship":"enterprise","hostile":"swarm_32",{bad}}}"#),
+            None,
+        )
+        .await;
+        assert_eq!(rejected.status_code, 400);
+        assert!(rejected.body.contains(field), "{}", rejected.body);
+    }
+}
+
+#[tokio::test]
+async fn sweep_endpoint_walks_hostile_family_levels() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"659469618","num_sims":100,"seed":4,
+        "min_level":20,"max_level":23,"target_win_rate":0.9,
+        "crew":{"captain":"kirk-1323b6","bridge":["spock-c04738"]}}"#;
+    let response = route_request("POST", "/api/analyze/sweep", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["family"], "13003:battleship");
+    assert_eq!(payload["target_win_rate"], 0.9);
+    let levels: Vec<u64> = payload["levels"]
+        .as_array()
+        .expect("levels should be array")
+        .iter()
+        .map(|l| l["level"].as_u64().expect("level"))
+        .collect();
+    assert_eq!(levels, [20, 21, 22, 23]);
+    let highest = payload["highest_level_at_target"].as_u64();
+    let farmable = payload["levels"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|l| l["win_rate"].as_f64().unwrap_or(0.0) >= 0.9)
+        .filter_map(|l| l["level"].as_u64())
+        .max();
+    assert_eq!(highest, farmable);
+
+    let unknown = route_request(
+        "POST",
+        "/api/analyze/sweep",
+        r#"{"strict":false,"ship":"saladin","hostile":"no_such_hostile","crew":{"captain":"kirk-1323b6"}}"#,
+        None,
+    )
+    .await;
+    assert_eq!(unknown.status_code, 400);
+    assert!(unknown.body.contains("hostile index"), "{}", unknown.body);
+}
+
+#[tokio::test]
+async fn bracket_endpoint_ranks_saved_presets() {
+    use kobayashi::data::profile_index::{
+        effective_profile_id, load_profile_index, profile_path, PRESETS_SUBDIR,
+    };
+
+    let mut ids = Vec::new();
+    let entries = [("Bracket test kirk", "kirk-1323b6"), ("Bracket test spock", "spock-c04738")];
+    for (name, captain) in entries {
+        let body = format!(
+            r#"{{"name":"{name}","ship":"saladin","scenario":"hostile",
+                "crew":{{"captain":"{captain}"}}}}"#
+        );
+        let created = route_request("POST", "/api/presets", &body, None).await;
+        assert_eq!(created.status_code, 200, "{}", created.body);
+        let preset: serde_json::Value = serde_json::from_str(&created.body).expect("preset json");
+        ids.push(preset["id"].as_str().expect("preset id").to_string());
+    }
+
+    let body = format!(
+        r#"{{"strict":false,"hostile":"swarm_32","num_sims":50,"seed":9,"presets":["{}","{}"]}}"#,
+        ids[0], ids[1]
+    );
+    let response = route_request("POST", "/api/analyze/bracket", &body, None).await;
+    let missing = route_request(
+        "POST",
+        "/api/analyze/bracket",
+        &format!(
+            r#"{{"strict":false,"hostile":"swarm_32","presets":["{}","no_such_preset"]}}"#,
+            ids[0]
+        ),
+        None,
+    )
+    .await;
+    let presets_dir = profile_path(&effective_profile_id(&load_profile_index()), PRESETS_SUBDIR);
+    for id in &ids {
+        let _ = std::fs::remove_file(presets_dir.join(format!("{id}.json")));
+    }
+
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value =
+        serde_json::from_str(&response.body).expect("response should be valid json");
+    assert_eq!(payload["num_sims"], 50);
+    let standings = payload["standings"].as_array().expect("standings should be array");
+    assert_eq!(standings.len(), 2);
+    assert_eq!(standings[0]["rank"], 1);
+    assert_eq!(standings[0]["ship"], "saladin");
+    let matchups = payload["matchups"].as_array().expect("matchups should be array");
+    assert_eq!(matchups.len(), 1);
+    let p_value = matchups[0]["p_value"].as_f64().expect("p_value");
+    assert!((0.0..=1.0).contains(&p_value), "{p_value}");
+
+    assert_eq!(missing.status_code, 400);
+    assert!(missing.body.contains("no_such_preset"), "{}", missing.body);
+}
+
 #[tokio::test]
 async fn optimize_validation_error_has_expected_schema() {
     let response = route_request(
@@ -324,7 +946,7 @@ async fn optimize_validation_error_has_expected_schema() {
 
 #[tokio::test]
 async fn async_optimize_start_poll_completes_with_recommendations() {
-    let body = r#"{"ship":"saladin","hostile":"2918121098","sims":1000,"seed":42,"max_candidates":16}"#;
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":1000,"seed":42,"max_candidates":16}"#;
     let start = route_request("POST", "/api/optimize/start", body, None).await;
     assert_eq!(start.status_code, 200, "body: {}", start.body);
     let payload: serde_json::Value =
@@ -379,7 +1001,7 @@ async fn async_optimize_cancel_unknown_job_returns_404() {
 
 #[tokio::test]
 async fn async_optimize_cancel_after_done_is_idempotent_ok() {
-    let body = r#"{"ship":"saladin","hostile":"2918121098","sims":500,"seed":1,"max_candidates":8}"#;
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":500,"seed":1,"max_candidates":8}"#;
     let start = route_request("POST", "/api/optimize/start", body, None).await;
     assert_eq!(start.status_code, 200);
     let payload: serde_json::Value =
@@ -425,3 +1047,1166 @@ async fn async_optimize_cancel_after_done_is_idempotent_ok() {
         c["message"]
     );
 }
+
+/// Accepts one HTTP request on `listener`, answers 204 and returns the request body.
+fn receive_one_webhook(listener: std::net::TcpListener) -> String {
+    use std::io::{BufRead, BufReader, Read, Write};
+    let (stream, _) = listener.accept().expect("webhook connection");
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).expect("header line");
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().expect("content length");
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).expect("webhook body");
+    reader
+        .get_mut()
+        .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+        .expect("webhook reply");
+    String::from_utf8(body).expect("utf-8 body")
+}
+
+#[tokio::test]
+async fn async_optimize_posts_a_summary_to_notify_webhook() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind webhook listener");
+    let port = listener.local_addr().expect("listener addr").port();
+    let receiver = std::thread::spawn(move || receive_one_webhook(listener));
+
+    // The production policy refuses loopback receivers; this server allows one.
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let webhooks = WebhookPolicy { hosts: vec!["127.0.0.1".to_string()], allow_non_public: true };
+    let app = router_with_state(AppState::new(registry).with_webhooks(webhooks));
+    let body = format!(
+        r#"{{"strict":false,"ship":"saladin","hostile":"2918121098","sims":200,"seed":3,"max_candidates":8,"notify_webhook":"http://127.0.0.1:{port}/hook"}}"#
+    );
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/optimize/start")
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap();
+    let start = app.oneshot(req).await.unwrap();
+    assert_eq!(start.status().as_u16(), 200);
+
+    let received = tokio::time::timeout(
+        tokio::time::Duration::from_secs(60),
+        tokio::task::spawn_blocking(move || receiver.join().expect("webhook thread")),
+    )
+    .await
+    .expect("webhook should be posted when the job finishes")
+    .expect("join webhook thread");
+    let webhook: serde_json::Value = serde_json::from_str(&received).expect("webhook json");
+    let summary = &webhook["summary"];
+    assert_eq!(summary["status"], "done");
+    assert_eq!(summary["ship"], "saladin");
+    let crews = summary["top_crews"].as_array().expect("top crews");
+    assert!(!crews.is_empty() && crews.len() <= 3, "{crews:?}");
+    assert!(crews[0]["win_rate"].is_f64());
+    assert!(summary["duration_ms"].is_u64());
+    let content = webhook["content"].as_str().expect("discord content");
+    assert!(content.contains("saladin vs 2918121098"), "{content}");
+}
+
+#[tokio::test]
+async fn async_optimize_rejects_webhooks_outside_the_allowlist() {
+    // Loopback receivers are refused up front (the default allowlist is Discord only), so the
+    // job never posts to the server's own network.
+    let loopback = route_request(
+        "POST",
+        "/api/optimize/start",
+        r#"{"ship":"saladin","hostile":"2918121098","notify_webhook":"http://127.0.0.1:9/hook"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(loopback.status_code, 400, "{}", loopback.body);
+    assert!(loopback.body.contains("notify_webhook"), "{}", loopback.body);
+
+    let rejected = route_request(
+        "POST",
+        "/api/optimize/start",
+        r#"{"ship":"saladin","hostile":"2918121098","notify_webhook":"ftp://example.com"}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400, "{}", rejected.body);
+    assert!(rejected.body.contains("notify_webhook"));
+}
+
+#[tokio::test]
+async fn schedules_store_runs_and_delete() {
+    let path = std::env::temp_dir().join(format!("kobayashi_schedules_{}.json", std::process::id()));
+    std::env::set_var("KOBAYASHI_SCHEDULES", &path);
+
+    let created = route_request(
+        "POST",
+        "/api/schedules",
+        r#"{"optimize":{"ship":"saladin","hostile":"2918121098","sims":100,"seed":2,"max_candidates":4},"interval_minutes":60}"#,
+        None,
+    )
+    .await;
+    assert_eq!(created.status_code, 200, "body: {}", created.body);
+    let schedule: serde_json::Value = serde_json::from_str(&created.body).expect("schedule json");
+    let id = schedule["id"].as_str().expect("schedule id").to_string();
+    assert_eq!(schedule["on_data_change"], true);
+    assert_eq!(schedule["history"].as_array().map(Vec::len), Some(0));
+
+    let registry = DataRegistry::load().expect("data registry");
+    let webhooks = WebhookPolicy::from_env();
+    let run = kobayashi::server::schedule::run_schedule(&registry, &id, &webhooks)
+        .expect("scheduled run");
+    assert_eq!(run.status, "done", "{:?}", run.error);
+    assert!(!run.top_crews.is_empty() && run.top_crews.len() <= 3);
+
+    let fetched = route_request("GET", &format!("/api/schedules/{id}"), "", None).await;
+    assert_eq!(fetched.status_code, 200);
+    let fetched: serde_json::Value = serde_json::from_str(&fetched.body).expect("schedule json");
+    let history = fetched["history"].as_array().expect("history");
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["data_version"], run.data_version.as_str());
+
+    let too_often = route_request(
+        "POST",
+        "/api/schedules",
+        r#"{"optimize":{"ship":"saladin","hostile":"2918121098"},"interval_minutes":1}"#,
+        None,
+    )
+    .await;
+    assert_eq!(too_often.status_code, 400, "{}", too_often.body);
+    assert!(too_often.body.contains("interval_minutes"));
+
+    let deleted = route_request("DELETE", &format!("/api/schedules/{id}"), "", None).await;
+    assert_eq!(deleted.status_code, 200, "{}", deleted.body);
+    let missing = route_request("GET", &format!("/api/schedules/{id}"), "", None).await;
+    assert_eq!(missing.status_code, 404);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn loadouts_crud_and_exclude_their_officers_from_optimize() {
+    let path = std::env::temp_dir().join(format!("kobayashi_loadouts_{}.json", std::process::id()));
+    std::env::set_var("KOBAYASHI_LOADOUTS", &path);
+    let optimize = |extra: &str| {
+        format!(
+            r#"{{"ship":"saladin","hostile":"2918121098","sims":200,"seed":3,"max_candidates":32{extra}}}"#
+        )
+    };
+
+    let baseline = route_request("POST", "/api/optimize", &optimize(""), None).await;
+    assert_eq!(baseline.status_code, 200, "{}", baseline.body);
+    let baseline: serde_json::Value = serde_json::from_str(&baseline.body).expect("json");
+    let top = &baseline["recommendations"][0];
+    let committed: Vec<String> = std::iter::once(&top["captain"])
+        .chain(top["bridge"].as_array().expect("bridge"))
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect();
+    assert!(!committed.is_empty());
+
+    let body = serde_json::json!({ "name": "Miner A", "ship": "saladin", "officers": committed });
+    let created = route_request("POST", "/api/loadouts", &body.to_string(), None).await;
+    assert_eq!(created.status_code, 200, "{}", created.body);
+    let loadout: serde_json::Value = serde_json::from_str(&created.body).expect("loadout json");
+    assert_eq!(loadout["id"], "miner_a");
+    assert_eq!(loadout["officers"].as_array().map(Vec::len), Some(committed.len()));
+
+    let unknown = route_request(
+        "POST",
+        "/api/loadouts",
+        r#"{"name":"Nobody","officers":["Not An Officer"]}"#,
+        None,
+    )
+    .await;
+    assert_eq!(unknown.status_code, 400, "{}", unknown.body);
+    assert!(unknown.body.contains("Not An Officer"), "{}", unknown.body);
+
+    let excluded = route_request(
+        "POST",
+        "/api/optimize",
+        &optimize(r#","exclude_loadouts":["miner_a"]"#),
+        None,
+    )
+    .await;
+    assert_eq!(excluded.status_code, 200, "{}", excluded.body);
+    let excluded: serde_json::Value = serde_json::from_str(&excluded.body).expect("json");
+    let recommendations = excluded["recommendations"].as_array().expect("recommendations");
+    assert!(!recommendations.is_empty());
+    for crew in recommendations {
+        let seated = std::iter::once(&crew["captain"])
+            .chain(crew["bridge"].as_array().into_iter().flatten())
+            .chain(crew["below_decks"].as_array().into_iter().flatten());
+        for officer in seated.filter_map(|v| v.as_str()) {
+            assert!(!committed.iter().any(|c| c == officer), "{officer} is in a loadout");
+        }
+    }
+
+    let missing_loadout = route_request(
+        "POST",
+        "/api/optimize",
+        &optimize(r#","exclude_loadouts":["no_such_loadout"]"#),
+        None,
+    )
+    .await;
+    assert_eq!(missing_loadout.status_code, 400, "{}", missing_loadout.body);
+    assert!(missing_loadout.body.contains("exclude_loadouts"));
+
+    let renamed = route_request(
+        "PUT",
+        "/api/loadouts/miner_a",
+        &serde_json::json!({ "name": "Miner B", "officers": [committed[0]] }).to_string(),
+        None,
+    )
+    .await;
+    assert_eq!(renamed.status_code, 200, "{}", renamed.body);
+    let listed = route_request("GET", "/api/loadouts", "", None).await;
+    let listed: serde_json::Value = serde_json::from_str(&listed.body).expect("json");
+    assert_eq!(listed["loadouts"][0]["id"], "miner_a");
+    assert_eq!(listed["loadouts"][0]["name"], "Miner B");
+
+    let deleted = route_request("DELETE", "/api/loadouts/miner_a", "", None).await;
+    assert_eq!(deleted.status_code, 200, "{}", deleted.body);
+    let gone = route_request("GET", "/api/loadouts/miner_a", "", None).await;
+    assert_eq!(gone.status_code, 404);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn optimize_history_reports_win_rate_series_per_scenario() {
+    let dir = std::env::temp_dir().join(format!("kobayashi_history_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::env::set_var("KOBAYASHI_RESULT_HISTORY_DIR", &dir);
+    std::env::set_var("KOBAYASHI_RESULT_HISTORY", "1");
+
+    let body = r#"{"ship":"saladin","hostile":"2918121098","sims":100,"seed":11,"max_candidates":4}"#;
+    let optimized = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(optimized.status_code, 200, "body: {}", optimized.body);
+    let optimized: serde_json::Value = serde_json::from_str(&optimized.body).expect("optimize json");
+    let best = optimized["recommendations"][0]["crew_id"].clone();
+
+    let response = route_request(
+        "GET",
+        "/api/optimize/history?ship=saladin&hostile=2918121098&top=2",
+        "",
+        None,
+    )
+    .await;
+    assert_eq!(response.status_code, 200, "body: {}", response.body);
+    let history: serde_json::Value = serde_json::from_str(&response.body).expect("history json");
+    let scenario = history["scenarios"]
+        .as_array()
+        .and_then(|s| s.iter().find(|s| s["seed"] == 11))
+        .expect("scenario for the optimize run");
+    assert_eq!(scenario["runs"], 1);
+    let crews = scenario["crews"].as_array().expect("crews");
+    assert!(!crews.is_empty() && crews.len() <= 2, "{crews:?}");
+    assert_eq!(crews[0]["crew_id"], best);
+    assert!(crews[0]["points"][0]["win_rate"].is_f64());
+
+    let missing = route_request("GET", "/api/optimize/history?ship=saladin", "", None).await;
+    assert_eq!(missing.status_code, 400);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn data_version_reports_content_hashes_and_counts() {
+    let response = route_request("GET", "/api/data/version", "", None).await;
+    assert_eq!(response.status_code, 200);
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    let officers = &body["files"]["officers"];
+    assert_eq!(body["officer_version"], officers["content_hash"]);
+    assert_eq!(officers["content_hash"].as_str().unwrap().len(), 16);
+    assert!(officers["records"].as_u64().unwrap() > 0);
+    for dataset in ["ships", "hostiles"] {
+        let files = &body["files"][dataset];
+        assert!(files["files"].as_u64().unwrap() >= 1, "{dataset}: {files}");
+        assert!(files["last_modified"].is_u64(), "{dataset}: {files}");
+    }
+
+    let mechanics = body["mechanics"].as_array().unwrap();
+    assert_eq!(mechanics.len(), kobayashi::combat::MECHANICS.len());
+    let isolytic = mechanics.iter().find(|m| m["name"] == "Isolytic").unwrap();
+    assert_eq!(isolytic["status"], "implemented");
+
+    let again = route_request("GET", "/api/data/version", "", None).await;
+    assert_eq!(again.body, response.body);
+}
+
+#[tokio::test]
+async fn data_updates_report_installed_versions_when_no_mirror_is_configured() {
+    std::env::remove_var(kobayashi::data::remote::REMOTE_URL_ENV);
+    let response = route_request("GET", "/api/data/updates", "", None).await;
+    assert_eq!(response.status_code, 200);
+    let body: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+    assert_eq!(body["enabled"], false);
+    let hostiles = body["datasets"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["dataset"] == "hostiles")
+        .expect("hostiles listed");
+    assert!(hostiles["current_version"].is_string());
+    assert_eq!(hostiles["update_available"], false);
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let app = router_with_state(
+        AppState::new(registry).with_admin_token(Some("hunter2".to_string())),
+    );
+    let apply = |token: Option<&str>| {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri("/api/data/updates/apply")
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            req = req.header("authorization", format!("Bearer {token}"));
+        }
+        app.clone().oneshot(req.body(Body::from("{}")).unwrap())
+    };
+    let anonymous = apply(None).await.unwrap();
+    assert_eq!(anonymous.status().as_u16(), 401);
+    let admin = apply(Some("hunter2")).await.unwrap();
+    assert_eq!(admin.status().as_u16(), 409);
+    let body = axum::body::to_bytes(admin.into_body(), usize::MAX).await.unwrap();
+    assert!(String::from_utf8_lossy(&body).contains("KOBAYASHI_DATA_REMOTE_URL"));
+}
+
+#[tokio::test]
+async fn openapi_spec_covers_routes_and_validation_bounds() {
+    let response = route_request("GET", "/api/openapi.json", "", None).await;
+    assert_eq!(response.status_code, 200);
+    assert!(response.content_type.contains("application/json"));
+    let spec: serde_json::Value =
+        serde_json::from_str(&response.body).expect("spec should be valid json");
+    assert_eq!(spec["openapi"], "3.0.3");
+
+    for (path, method) in [
+        ("/api/simulate", "post"),
+        ("/api/optimize", "post"),
+        ("/api/optimize/status/{job_id}", "get"),
+        ("/api/analyze/sweep", "post"),
+        ("/api/analyze/bracket", "post"),
+        ("/api/simulate/trace", "post"),
+        ("/api/simulate/scenario", "post"),
+        ("/api/simulate/matrix", "post"),
+        ("/api/hostiles", "get"),
+        ("/api/ships/{id}", "get"),
+        ("/api/presets/{id}", "get"),
+        ("/api/crewcode", "post"),
+        ("/api/crewcode/{code}", "get"),
+        ("/api/optimize/history", "get"),
+        ("/api/schedules", "post"),
+        ("/api/schedules/{id}", "delete"),
+        ("/api/loadouts", "post"),
+        ("/api/loadouts/{id}", "put"),
+        ("/api/data/updates", "get"),
+        ("/api/data/updates/apply", "post"),
+        ("/api/assets/{path}", "get"),
+        ("/api/health/ready", "get"),
+        ("/api/admin/settings", "patch"),
+    ] {
+        assert!(spec["paths"][path][method].is_object(), "missing {method} {path}");
+    }
+
+    let schemas = &spec["components"]["schemas"];
+    let optimize = &schemas["OptimizeRequest"]["properties"];
+    assert_eq!(optimize["sims"]["maximum"], kobayashi::server::api::MAX_SIMS as f64);
+    assert_eq!(optimize["sims"]["minimum"], 1.0);
+    assert_eq!(optimize["waves"]["maximum"], 20.0);
+    assert!(schemas["CrewRecommendation"]["properties"]["crew_id"].is_object());
+
+    // Every $ref resolves to a component.
+    fn refs<'a>(value: &'a serde_json::Value, out: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(r) = map.get("$ref").and_then(|r| r.as_str()) {
+                    out.push(r);
+                }
+                map.values().for_each(|v| refs(v, out));
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| refs(v, out)),
+            _ => {}
+        }
+    }
+    let mut found = Vec::new();
+    refs(&spec, &mut found);
+    assert!(!found.is_empty());
+    for r in found {
+        let pointer = r.trim_start_matches('#');
+        assert!(spec.pointer(pointer).is_some(), "dangling {r}");
+    }
+}
+
+#[tokio::test]
+async fn ws_simulate_streams_rounds_then_result() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, build_router(registry)).await });
+
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws/simulate"))
+        .await
+        .expect("websocket upgrade");
+    let request = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","seed":9,
+        "crew":{"captain":"kirk-1323b6","bridge":["spock-c04738"]}}"#;
+    socket.send(Message::text(request)).await.unwrap();
+
+    let mut frames: Vec<serde_json::Value> = Vec::new();
+    while let Some(message) = socket.next().await {
+        match message.unwrap() {
+            Message::Text(text) => frames.push(serde_json::from_str(&text).unwrap()),
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    let types: Vec<&str> = frames.iter().map(|f| f["type"].as_str().unwrap()).collect();
+    assert_eq!(types.first(), Some(&"start"), "{types:?}");
+    assert_eq!(types.last(), Some(&"result"), "{types:?}");
+    let rounds: Vec<u64> = frames
+        .iter()
+        .filter(|f| f["type"] == "round")
+        .map(|f| f["round"].as_u64().unwrap())
+        .collect();
+    assert!(!rounds.is_empty());
+    assert!(rounds.windows(2).all(|w| w[0] < w[1]), "{rounds:?}");
+    for frame in frames.iter().filter(|f| f["type"] == "round") {
+        let round = frame["round"].as_u64().unwrap();
+        assert!(frame["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|e| e["round_index"].as_u64() == Some(round)));
+    }
+    let result = frames.last().unwrap();
+    let rounds_simulated = result["rounds_simulated"].as_u64().unwrap();
+    assert!(rounds.last().is_some_and(|&r| r <= rounds_simulated), "{rounds:?}");
+    let shield_damage = result["attacker_damage"]["shield_damage"].as_f64().unwrap();
+    assert!(shield_damage <= result["total_damage"].as_f64().unwrap(), "{result}");
+    assert!(result["defender_damage"]["crit_damage"].is_number(), "{result}");
+
+    let (mut bad, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws/simulate"))
+        .await
+        .unwrap();
+    bad.send(Message::text("{not json")).await.unwrap();
+    let first = bad.next().await.unwrap().unwrap();
+    let frame: serde_json::Value = serde_json::from_str(first.to_text().unwrap()).unwrap();
+    assert_eq!(frame["type"], "error");
+}
+
+#[tokio::test]
+async fn simulate_trace_filters_and_pages_events() {
+    let fight = r#""strict":false,"ship":"saladin","hostile":"2918121098","seed":9,
+        "crew":{"captain":"kirk-1323b6","bridge":["spock-c04738"]}"#;
+    let full = route_request("POST", "/api/simulate/trace", &format!("{{{fight}}}"), None).await;
+    assert_eq!(full.status_code, 200, "{}", full.body);
+    let full: serde_json::Value = serde_json::from_str(&full.body).expect("json");
+    let total = full["total_events"].as_u64().expect("total_events");
+    assert!(total > 2);
+
+    let body = format!(
+        r#"{{{fight},"filter":{{"event_types":["attack_roll"],"first_round":1,"last_round":2}},
+            "offset":1,"limit":1}}"#
+    );
+    let page = route_request("POST", "/api/simulate/trace", &body, None).await;
+    assert_eq!(page.status_code, 200, "{}", page.body);
+    let page: serde_json::Value = serde_json::from_str(&page.body).expect("json");
+    let kept = page["total_events"].as_u64().expect("total_events");
+    assert!(kept < total, "{kept} of {total}");
+    let events = page["events"].as_array().expect("events");
+    assert_eq!(events.len(), usize::from(kept > 1));
+    assert!(events.iter().all(|e| e["event_type"] == "attack_roll"
+        && (1..=2).contains(&e["round_index"].as_u64().unwrap())));
+    assert_eq!(page["next_offset"].as_u64(), (kept > 2).then_some(2));
+
+    let bad = format!(r#"{{{fight},"filter":{{"event_types":["nope"]}}}}"#);
+    let rejected = route_request("POST", "/api/simulate/trace", &bad, None).await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("unknown trace event type"), "{}", rejected.body);
+}
+
+#[tokio::test]
+async fn simulate_scenario_runs_yaml_files_with_rules_and_objective() {
+    let scenario = "
+name: one round
+ship: saladin
+hostile: '2918121098'
+strict: false
+crew:
+  captain: kirk-1323b6
+  bridge: [spock-c04738]
+profile_overrides:
+  bonuses:
+    weapon_damage: 0.5
+rules:
+  rounds: 1
+objective: defense
+num_sims: 20
+seed: 3
+";
+    let response = route_request("POST", "/api/simulate/scenario", scenario, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("json");
+    assert_eq!(payload["name"], "one round");
+    assert_eq!(payload["objective"], "defense");
+    assert_eq!(payload["stats"]["n"], 20);
+    assert!(payload["score"].is_number());
+    // A single round never finishes the hostile.
+    assert_eq!(payload["stats"]["win_rate"], 0.0);
+
+    let typo = scenario.replace("rules:", "rulez:");
+    let rejected = route_request("POST", "/api/simulate/scenario", &typo, None).await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("unknown field `rulez`"), "{}", rejected.body);
+
+    let waves_only = scenario.replace("rounds: 1", "waves: 2");
+    let rejected = route_request("POST", "/api/simulate/scenario", &waves_only, None).await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("rules.waves: requires objective"), "{}", rejected.body);
+}
+
+#[tokio::test]
+async fn simulate_matrix_runs_every_axis_combination() {
+    let scenario = "
+ship: saladin
+hostile: '2918121098'
+strict: false
+crew:
+  captain: kirk-1323b6
+num_sims: 10
+matrix:
+  captain: [kirk-1323b6, spock-c04738]
+  rules.rounds: [1, 3]
+";
+    let response = route_request("POST", "/api/simulate/matrix", scenario, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("json");
+    assert_eq!(payload["axes"], serde_json::json!(["captain", "rules.rounds"]));
+    let runs = payload["runs"].as_array().expect("runs");
+    assert_eq!(runs.len(), 4);
+    assert_eq!(runs[1]["axes"]["rules.rounds"], 3);
+    assert_eq!(runs[2]["axes"]["captain"], "spock-c04738");
+    assert!(runs.iter().all(|run| run["result"]["stats"]["n"] == 10));
+
+    let single = route_request("POST", "/api/simulate/scenario", scenario, None).await;
+    assert_eq!(single.status_code, 400);
+    assert!(single.body.contains("has a matrix"), "{}", single.body);
+    let bad = scenario.replace("rules.rounds: [1, 3]", "rules.rounds: [1, 0]");
+    let rejected = route_request("POST", "/api/simulate/matrix", &bad, None).await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("matrix run captain=kirk-1323b6, rules.rounds=0"));
+}
+
+#[tokio::test]
+async fn crew_codes_decode_into_runnable_scenarios() {
+    let body = r#"{"ship":"saladin","crew":{"captain":"kirk-1323b6","bridge":["spock-c04738",null]},
+        "include_profile":false}"#;
+    let encoded = route_request("POST", "/api/crewcode", body, None).await;
+    assert_eq!(encoded.status_code, 200, "{}", encoded.body);
+    let payload: serde_json::Value = serde_json::from_str(&encoded.body).expect("json");
+    let code = payload["code"].as_str().expect("code").to_string();
+
+    let no_hostile = route_request("GET", &format!("/api/crewcode/{code}"), "", None).await;
+    assert_eq!(no_hostile.status_code, 400);
+    assert!(no_hostile.body.contains("pass ?hostile="), "{}", no_hostile.body);
+
+    let uri = format!("/api/crewcode/{code}?hostile=2918121098");
+    let decoded = route_request("GET", &uri, "", None).await;
+    assert_eq!(decoded.status_code, 200, "{}", decoded.body);
+    let mut scenario = serde_json::from_str::<serde_json::Value>(&decoded.body).expect("json")
+        ["scenario"]
+        .take();
+    assert_eq!(scenario["crew"]["bridge"], serde_json::json!(["spock-c04738", null]));
+    scenario["num_sims"] = 10.into();
+    scenario["strict"] = false.into();
+    let run = route_request("POST", "/api/simulate/scenario", &scenario.to_string(), None).await;
+    assert_eq!(run.status_code, 200, "{}", run.body);
+
+    let garbage = route_request("GET", "/api/crewcode/not-a-code", "", None).await;
+    assert_eq!(garbage.status_code, 400);
+}
+
+#[tokio::test]
+async fn unknown_ship_is_rejected_with_near_misses_unless_strict_is_off() {
+    let response = route_request(
+        "POST",
+        "/api/simulate",
+        r#"{"ship":"enterprise","hostile":"2918121098","crew":{"captain":"kirk-1323b6"},"num_sims":10}"#,
+        None,
+    )
+    .await;
+    assert_eq!(response.status_code, 404, "{}", response.body);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("json");
+    assert_eq!(payload["status"], "error");
+    let errors = payload["errors"].as_array().expect("errors array");
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["field"], "ship");
+    assert_eq!(errors[0]["value"], "enterprise");
+    assert_eq!(errors[0]["suggestions"][0], "uss_enterprise");
+
+    let optimize = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"uss_saladin","hostile":"no_such_hostile","sims":10}"#,
+        None,
+    )
+    .await;
+    assert_eq!(optimize.status_code, 404, "{}", optimize.body);
+    let payload: serde_json::Value = serde_json::from_str(&optimize.body).expect("json");
+    assert_eq!(payload["errors"][0]["field"], "hostile");
+
+    let lenient = route_request(
+        "POST",
+        "/api/simulate",
+        r#"{"ship":"enterprise","hostile":"2918121098","crew":{"captain":"kirk-1323b6"},"num_sims":10,"strict":false}"#,
+        None,
+    )
+    .await;
+    assert_eq!(lenient.status_code, 200, "{}", lenient.body);
+    assert!(lenient.body.contains("placeholder stats"), "{}", lenient.body);
+}
+
+#[tokio::test]
+async fn unknown_officer_is_rejected_unless_a_lenient_mode_is_chosen() {
+    let simulate = |mode: &'static str| async move {
+        let body = format!(
+            r#"{{"ship":"uss_enterprise","hostile":"2918121098","crew":{{"captain":"kirkk"}},"num_sims":10{mode}}}"#
+        );
+        route_request("POST", "/api/simulate", &body, None).await
+    };
+    let strict = simulate("").await;
+    assert_eq!(strict.status_code, 404, "{}", strict.body);
+    let payload: serde_json::Value = serde_json::from_str(&strict.body).expect("json");
+    assert_eq!(payload["message"], "Unknown officer");
+    assert_eq!(payload["errors"][0]["field"], "crew.captain");
+    assert_eq!(payload["errors"][0]["value"], "kirkk");
+    assert!(!payload["errors"][0]["suggestions"].as_array().expect("suggestions").is_empty());
+
+    for mode in [r#","unknown_officers":"neutral""#, r#","unknown_officers":"legacy""#] {
+        let lenient = simulate(mode).await;
+        assert_eq!(lenient.status_code, 200, "{}", lenient.body);
+    }
+}
+
+#[tokio::test]
+async fn optimize_accepts_shipped_heuristics_seeds_in_strict_mode() {
+    for seed in ["heuristics-seed", "heuristics-seed-2"] {
+        let body = format!(
+            r#"{{"ship":"uss_saladin","hostile":"2918121098","sims":5,"heuristics_only":true,
+                "heuristics_seeds":["{seed}"]}}"#
+        );
+        let resp = route_request("POST", "/api/optimize", &body, None).await;
+        assert_eq!(resp.status_code, 200, "{seed}: {}", resp.body);
+    }
+}
+
+#[tokio::test]
+async fn damaged_attacker_starts_with_the_requested_hull_and_shields() {
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let fight = |condition: &str| {
+        let body = format!(
+            r#"{{"ship":"uss_saladin","hostile":"2918121098","seed":3,
+                "crew":{{"captain":"kirk-1323b6","bridge":["spock-c04738"]}}{condition}}}"#
+        );
+        let mut result = None;
+        kobayashi::server::api::simulate_stream(&registry, &body, None, &mut |frame| {
+            if let kobayashi::server::api::SimulateStreamFrame::Result {
+                attacker_hull_remaining,
+                attacker_shield_remaining,
+                ..
+            } = frame
+            {
+                result = Some((attacker_hull_remaining, attacker_shield_remaining));
+            }
+        })
+        .expect("fight");
+        result.expect("result frame")
+    };
+    let fresh = fight("");
+    let damaged = fight(r#","attacker_hull_pct":0.4,"attacker_shield_pct":0"#);
+    // This hostile deals the ship no damage, so it ends the fight as it started.
+    assert!((damaged.0 - 0.4 * fresh.0).abs() < 1e-3, "{fresh:?} vs {damaged:?}");
+    assert_eq!(damaged.1, 0.0, "{damaged:?}");
+
+    let simulate = |condition: &'static str| async move {
+        let body = format!(
+            r#"{{"ship":"uss_saladin","hostile":"2918121098","num_sims":20,"seed":3,
+                "crew":{{"captain":"kirk-1323b6"}}{condition}}}"#
+        );
+        route_request("POST", "/api/simulate", &body, None).await
+    };
+    assert_eq!(simulate(r#","attacker_hull_pct":0.5"#).await.status_code, 200);
+    let rejected = simulate(r#","attacker_hull_pct":0"#).await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("attacker_hull_pct"), "{}", rejected.body);
+
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","sims":50,"seed":5,"max_candidates":8,"attacker_hull_pct":0.4}"#;
+    let optimized = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(optimized.status_code, 200, "{}", optimized.body);
+    let payload: serde_json::Value = serde_json::from_str(&optimized.body).expect("json");
+    assert_eq!(payload["scenario"]["attacker_hull_pct"], 0.4);
+    let rejected = route_request(
+        "POST",
+        "/api/optimize",
+        r#"{"ship":"saladin","hostile":"2918121098","sims":50,"attacker_shield_pct":1.5}"#,
+        None,
+    )
+    .await;
+    assert_eq!(rejected.status_code, 400);
+    assert!(rejected.body.contains("attacker_shield_pct"), "{}", rejected.body);
+}
+
+#[tokio::test]
+async fn null_crew_seats_simulate_as_empty_seats() {
+    let simulate = |bridge: &'static str| async move {
+        let body = format!(
+            r#"{{"ship":"uss_saladin","hostile":"2918121098","num_sims":40,"seed":3,
+                "crew":{{"captain":"kirk-1323b6","bridge":{bridge},"below_deck":[null]}}}}"#
+        );
+        let response = route_request("POST", "/api/simulate", &body, None).await;
+        assert_eq!(response.status_code, 200, "{}", response.body);
+        let payload: serde_json::Value = serde_json::from_str(&response.body).expect("json");
+        payload["stats"].clone()
+    };
+    let with_null = simulate(r#"["spock-c04738",null]"#).await;
+    assert_eq!(with_null, simulate(r#"["spock-c04738"]"#).await);
+}
+
+#[tokio::test]
+async fn typed_names_resolve_to_canonical_ids_and_search_ranks_matches() {
+    let ships = route_request("GET", "/api/ships?q=U.S.S.%20Saladin", "", None).await;
+    assert_eq!(ships.status_code, 200);
+    let payload: serde_json::Value = serde_json::from_str(&ships.body).expect("ships json");
+    assert_eq!(payload["ships"][0]["id"], "uss_saladin");
+
+    let hostiles = route_request("GET", "/api/hostiles?q=swarm%20cluster%2024", "", None).await;
+    assert_eq!(hostiles.status_code, 200);
+    let payload: serde_json::Value = serde_json::from_str(&hostiles.body).expect("hostiles json");
+    let first = &payload["hostiles"][0];
+    assert_eq!(first["display_name"], "SWARM CLUSTER");
+    assert_eq!(first["level"], 24);
+
+    let response = route_request(
+        "POST",
+        "/api/simulate",
+        r#"{"ship":"USS Saladin","hostile":"2918121098","crew":{"captain":"kirk-1323b6"},"num_sims":10}"#,
+        None,
+    )
+    .await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    assert!(!response.body.contains("placeholder stats"), "{}", response.body);
+}
+
+#[tokio::test]
+async fn ship_detail_reports_crew_slots_per_tier() {
+    let response = route_request("GET", "/api/ships/uss_saladin?tier=2", "", None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("ship json");
+    assert_eq!(payload["id"], "uss_saladin");
+    assert_eq!(payload["tier"], 2);
+    assert_eq!(payload["crew_slots"], serde_json::json!({ "bridge": 2, "below_decks": 3 }));
+    let by_tier = payload["slots_by_tier"].as_array().expect("slots_by_tier array");
+    assert!(!by_tier.is_empty());
+    assert_eq!(by_tier[0]["tier"], 1);
+
+    let missing = route_request("GET", "/api/ships/no_such_ship", "", None).await;
+    assert_eq!(missing.status_code, 404, "{}", missing.body);
+}
+
+#[tokio::test]
+async fn ship_detail_and_optimize_recommendations_report_power() {
+    let response = route_request("GET", "/api/ships/uss_saladin", "", None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let ship: serde_json::Value = serde_json::from_str(&response.body).expect("ship json");
+    let power = &ship["power"];
+    let part = |key: &str| power[key].as_f64().expect("power part");
+    assert!(part("defense") > 0.0, "{power}");
+    assert_eq!(part("total"), part("attack") + part("defense") + part("health"));
+
+    let body = r#"{"ship":"uss_saladin","hostile":"2918121098","sims":50,"max_candidates":4}"#;
+    let response = route_request("POST", "/api/optimize", body, None).await;
+    assert_eq!(response.status_code, 200, "{}", response.body);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("optimize json");
+    let crew_power = &payload["recommendations"][0]["power"];
+    assert_eq!(crew_power["ship"], *power);
+    let crew = crew_power["crew"].as_f64().expect("crew power");
+    assert!(crew > 0.0, "{crew_power}");
+    assert_eq!(crew_power["total"].as_f64(), Some(part("total") + crew));
+}
+
+#[tokio::test]
+async fn officers_endpoint_lists_rarity_faction_group_and_synergy() {
+    let response = route_request("GET", "/api/officers", "", None).await;
+    assert_eq!(response.status_code, 200);
+    let payload: serde_json::Value = serde_json::from_str(&response.body).expect("officers json");
+    let officers = payload["officers"].as_array().expect("officers array");
+    let airiam = officers
+        .iter()
+        .find(|o| o["name"] == "Airiam")
+        .expect("Airiam in canonical officers");
+    assert_eq!(airiam["rarity"], "uncommon");
+    assert_eq!(airiam["group"], "DISCOVERY CREW");
+    assert!(airiam["faction"].is_string());
+    assert_eq!(airiam["synergy_group"], 18);
+    let same_group = officers
+        .iter()
+        .filter(|o| o["synergy_group"] == airiam["synergy_group"])
+        .all(|o| o["group"] == airiam["group"]);
+    assert!(same_group, "synergy groups follow the crew group");
+}
+
+#[tokio::test]
+async fn simulate_answers_in_msgpack_when_asked() {
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","crew":{"captain":"kirk-1323b6"},"num_sims":20,"seed":11}"#;
+    let json = route_request("POST", "/api/simulate", body, None).await;
+    assert_eq!(json.status_code, 200, "{}", json.body);
+    assert_eq!(json.content_type, "application/json");
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/simulate")
+        .header("content-type", "application/json")
+        .header("accept", "application/msgpack, application/json;q=0.5")
+        .body(Body::from(body))
+        .unwrap();
+    let resp = build_router(registry).oneshot(req).await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/msgpack");
+    assert_eq!(resp.headers()["vary"], "accept");
+    let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let decoded: serde_json::Value = rmp_serde::from_slice(&bytes).expect("msgpack body");
+    let expected: serde_json::Value = serde_json::from_str(&json.body).expect("json");
+    assert_eq!(decoded, expected);
+}
+
+fn limited_router(limits: RequestLimits) -> axum::Router {
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    router_with_state(AppState::new(registry).with_limits(limits))
+}
+
+#[tokio::test]
+async fn request_body_that_stalls_past_read_deadline_gets_408() {
+    let app = limited_router(RequestLimits {
+        body_read: Some(Duration::from_millis(50)),
+        ..RequestLimits::default()
+    });
+    let stalled = futures_util::stream::pending::<Result<axum::body::Bytes, std::io::Error>>();
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/crewcode")
+        .header("content-type", "application/json")
+        .body(Body::from_stream(stalled))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status().as_u16(), 408);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let payload: serde_json::Value = serde_json::from_slice(&body).expect("json error body");
+    assert_eq!(payload["message"], "Timed out reading the request body");
+}
+
+#[tokio::test]
+async fn request_over_route_deadline_gets_408_and_oversized_body_gets_413() {
+    let app = limited_router(RequestLimits {
+        routes: vec![("/api/simulate".to_string(), Some(Duration::from_millis(1)))],
+        max_body_bytes: 1024,
+        ..RequestLimits::default()
+    });
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","crew":{"captain":"kirk-1323b6"},"num_sims":2000,"seed":11}"#;
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/simulate")
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status().as_u16(), 408);
+
+    let req = Request::builder()
+        .method(Method::POST)
+        .uri("/api/crewcode")
+        .header("content-type", "application/json")
+        .body(Body::from(" ".repeat(2048)))
+        .unwrap();
+    let resp = app.oneshot(req).await.unwrap();
+    assert_eq!(resp.status().as_u16(), 413);
+}
+
+#[tokio::test]
+async fn simulate_takes_reserved_slot_while_background_job_holds_every_cpu_slot() {
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let state = AppState::new(registry);
+    // What a running /api/optimize/start job looks like: every slot held, background in flight.
+    let slots = state.cpu_job_slots as u32;
+    let _held = std::sync::Arc::clone(&state.cpu_jobs).acquire_many_owned(slots).await.unwrap();
+    let _background = Priority::Background.enter();
+    let app = router_with_state(state);
+    let post = |uri: &str, body: &'static str| {
+        Request::builder()
+            .method(Method::POST)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let body = r#"{"strict":false,"ship":"saladin","hostile":"2918121098","crew":{"captain":"kirk-1323b6"},"num_sims":50,"seed":11}"#;
+    let simulate = app.clone().oneshot(post("/api/simulate", body));
+    let resp = tokio::time::timeout(Duration::from_secs(60), simulate)
+        .await
+        .expect("interactive simulate should not queue behind the background job")
+        .unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+
+    // Other CPU routes still wait for a regular slot.
+    let scenario = app.oneshot(post("/api/simulate/scenario", "ship: saladin"));
+    assert!(tokio::time::timeout(Duration::from_millis(200), scenario).await.is_err());
+}
+
+#[tokio::test]
+async fn cors_answers_preflight_and_tags_responses_for_allowed_origins_only() {
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let origins = CorsOrigins::List(vec!["http://ui.example.com".to_string()]);
+    let app = router_with_state(AppState::new(registry).with_cors(Some(origins)));
+
+    let preflight = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/simulate")
+        .header("origin", "http://ui.example.com")
+        .header("access-control-request-method", "POST")
+        .header("access-control-request-headers", "content-type,x-profile-id")
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(preflight).await.unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+    let headers = resp.headers();
+    assert_eq!(headers["access-control-allow-origin"], "http://ui.example.com");
+    assert!(headers["access-control-allow-methods"].to_str().unwrap().contains("POST"));
+    assert!(headers["access-control-allow-headers"].to_str().unwrap().contains("x-profile-id"));
+
+    let get = |origin: &'static str| {
+        Request::builder()
+            .uri("/api/health")
+            .header("origin", origin)
+            .body(Body::empty())
+            .unwrap()
+    };
+    let allowed = app.clone().oneshot(get("http://ui.example.com")).await.unwrap();
+    assert_eq!(allowed.status().as_u16(), 200);
+    assert_eq!(allowed.headers()["access-control-allow-origin"], "http://ui.example.com");
+    let other = app.oneshot(get("http://evil.example.com")).await.unwrap();
+    assert!(other.headers().get("access-control-allow-origin").is_none());
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let default = router_with_state(AppState::new(registry).with_cors(None))
+        .oneshot(get("http://ui.example.com"))
+        .await
+        .unwrap();
+    assert!(default.headers().get("access-control-allow-origin").is_none());
+}
+
+#[tokio::test]
+async fn serve_connections_speaks_https_with_configured_certificate() {
+    use kobayashi::server::tls::{load_acceptor, TlsPaths};
+    use rustls::pki_types::pem::PemObject;
+    use rustls::pki_types::{CertificateDer, ServerName};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let paths = TlsPaths {
+        cert: "tests/fixtures/tls/localhost.crt".into(),
+        key: "tests/fixtures/tls/localhost.key".into(),
+    };
+    let acceptor = load_acceptor(&paths).expect("fixture certificate loads");
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = build_router(registry);
+    tokio::spawn(kobayashi::server::serve_connections(
+        listener,
+        app,
+        Some(Duration::from_secs(5)),
+        Some(acceptor),
+    ));
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(CertificateDer::from_pem_file(&paths.cert).unwrap()).unwrap();
+    let client = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_root_certificates(roots)
+    .with_no_client_auth();
+    let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let mut tls = tokio_rustls::TlsConnector::from(Arc::new(client))
+        .connect(ServerName::try_from("localhost").unwrap(), stream)
+        .await
+        .expect("TLS handshake against the fixture certificate");
+    tls.write_all(b"GET /api/health HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    tls.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    // A plaintext request on the TLS port never gets an HTTP answer.
+    let mut plain = tokio::net::TcpStream::connect(addr).await.unwrap();
+    plain.write_all(b"GET /api/health HTTP/1.1\r\nhost: localhost\r\n\r\n").await.unwrap();
+    let mut buf = Vec::new();
+    let _ = plain.read_to_end(&mut buf).await;
+    assert!(!String::from_utf8_lossy(&buf).contains("200 OK"));
+}
+
+/// Serves one request on `listener` with a fixed PNG body and returns the request line.
+fn serve_one_asset(listener: std::net::TcpListener, body: &'static [u8]) -> String {
+    use std::io::{BufRead, BufReader, Write};
+    let (stream, _) = listener.accept().expect("cdn connection");
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).expect("request line");
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).expect("header line");
+        if line.trim_end().is_empty() {
+            break;
+        }
+    }
+    let head = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\n\r\n",
+        body.len()
+    );
+    let stream = reader.get_mut();
+    stream.write_all(head.as_bytes()).expect("cdn head");
+    stream.write_all(body).expect("cdn body");
+    request_line.trim_end().to_string()
+}
+
+#[tokio::test]
+async fn asset_route_fetches_from_cdn_once_then_serves_from_cache() {
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nportrait";
+    let cache_dir = std::env::temp_dir().join(format!("kobayashi-assets-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind cdn listener");
+    let port = listener.local_addr().expect("listener addr").port();
+    let cdn = std::thread::spawn(move || serve_one_asset(listener, PNG));
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let proxy = AssetProxy::new(Some(format!("http://127.0.0.1:{port}/cdn/")), &cache_dir);
+    let app = router_with_state(AppState::new(registry).with_assets(proxy));
+    let get = |path: &str| Request::builder().uri(path).body(Body::empty()).unwrap();
+
+    let first = app.clone().oneshot(get("/api/assets/officers/kirk.png")).await.unwrap();
+    assert_eq!(first.status().as_u16(), 200);
+    assert_eq!(first.headers()["content-type"], "image/png");
+    assert_eq!(first.headers()["x-asset-cache"], "miss");
+    let body = axum::body::to_bytes(first.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], PNG);
+    let request_line = tokio::task::spawn_blocking(move || cdn.join().expect("cdn thread"))
+        .await
+        .unwrap();
+    assert_eq!(request_line, "GET /cdn/officers/kirk.png HTTP/1.1");
+    assert!(cache_dir.join("officers/kirk.png").is_file());
+
+    // The CDN is gone now; the cached copy is still served.
+    let second = app.clone().oneshot(get("/api/assets/officers/kirk.png")).await.unwrap();
+    assert_eq!(second.status().as_u16(), 200);
+    assert_eq!(second.headers()["x-asset-cache"], "hit");
+    let body = axum::body::to_bytes(second.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&body[..], PNG);
+
+    let traversal = app.clone().oneshot(get("/api/assets/officers/%2e%2e/x.png")).await.unwrap();
+    assert_eq!(traversal.status().as_u16(), 400);
+    let not_image = app.oneshot(get("/api/assets/index.html")).await.unwrap();
+    assert_eq!(not_image.status().as_u16(), 400);
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let offline = router_with_state(
+        AppState::new(registry).with_assets(AssetProxy::new(None, &cache_dir)),
+    );
+    let cached = offline.clone().oneshot(get("/api/assets/officers/kirk.png")).await.unwrap();
+    assert_eq!(cached.status().as_u16(), 200);
+    let missing = offline.oneshot(get("/api/assets/ships/enterprise.png")).await.unwrap();
+    assert_eq!(missing.status().as_u16(), 404);
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}
+
+#[tokio::test]
+async fn officers_import_url_is_refused_unless_sheet_import_is_enabled() {
+    // KOBAYASHI_SHEETS_IMPORT is unset in tests, so no request leaves the machine.
+    let body = r#"{"url": "https://docs.google.com/spreadsheets/d/e/2PACX-1vAbc/pub?output=csv"}"#;
+    let resp = route_request("POST", "/api/officers/import", body, None).await;
+    assert_eq!(resp.status_code, 403, "{}", resp.body);
+    assert!(resp.body.contains("KOBAYASHI_SHEETS_IMPORT"), "{}", resp.body);
+}
+
+#[tokio::test]
+async fn admin_settings_require_the_token_and_validate_patches() {
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let app = router_with_state(
+        AppState::new(registry).with_admin_token(Some("hunter2".to_string())),
+    );
+    let send = |method: Method, token: Option<&str>, body: &str| {
+        let mut req = Request::builder()
+            .method(method)
+            .uri("/api/admin/settings")
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            req = req.header("authorization", format!("Bearer {token}"));
+        }
+        req.body(Body::from(body.to_string())).unwrap()
+    };
+    let json = |bytes: axum::body::Bytes| -> serde_json::Value {
+        serde_json::from_slice(&bytes).expect("settings json")
+    };
+
+    let anonymous = app.clone().oneshot(send(Method::GET, None, "")).await.unwrap();
+    assert_eq!(anonymous.status().as_u16(), 401);
+    let wrong = app.clone().oneshot(send(Method::GET, Some("hunter3"), "")).await.unwrap();
+    assert_eq!(wrong.status().as_u16(), 401);
+
+    let current = app.clone().oneshot(send(Method::GET, Some("hunter2"), "")).await.unwrap();
+    assert_eq!(current.status().as_u16(), 200);
+    let current = json(axum::body::to_bytes(current.into_body(), usize::MAX).await.unwrap());
+    assert!(current["default_sims"].as_u64().unwrap() >= 1);
+    assert!(current["global_pool_threads"].as_u64().unwrap() >= 1);
+    assert!(current["result_cache"].is_boolean());
+
+    let rejected = app
+        .clone()
+        .oneshot(send(Method::PATCH, Some("hunter2"), r#"{"default_sims":0}"#))
+        .await
+        .unwrap();
+    assert_eq!(rejected.status().as_u16(), 400);
+    let unknown = app
+        .clone()
+        .oneshot(send(Method::PATCH, Some("hunter2"), r#"{"sims":10}"#))
+        .await
+        .unwrap();
+    assert_eq!(unknown.status().as_u16(), 400);
+
+    // Re-applies the current values: other tests share the process-wide settings.
+    let echo = format!(
+        r#"{{"default_sims":{},"max_candidates":{}}}"#,
+        current["default_sims"], current["max_candidates"]
+    );
+    let patched = app.clone().oneshot(send(Method::PATCH, Some("hunter2"), &echo)).await.unwrap();
+    assert_eq!(patched.status().as_u16(), 200);
+    let patched = json(axum::body::to_bytes(patched.into_body(), usize::MAX).await.unwrap());
+    assert_eq!(patched["default_sims"], current["default_sims"]);
+
+    let registry = DataRegistry::load().expect("data registry required for server tests");
+    let disabled = router_with_state(AppState::new(registry).with_admin_token(None))
+        .oneshot(send(Method::GET, Some("hunter2"), ""))
+        .await
+        .unwrap();
+    assert_eq!(disabled.status().as_u16(), 403);
+}